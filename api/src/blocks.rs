@@ -5,10 +5,24 @@ use crate::{
     accept_type::AcceptType,
     context::{api_spawn_blocking, Context},
     failpoint::fail_point_poem,
-    response::{BasicResponse, BasicResponseStatus, BasicResultWith404},
+    response::{
+        version_not_found, BasicErrorWith404, BasicResponse, BasicResponseStatus,
+        BasicResultWith404, InternalError,
+    },
     ApiTags,
 };
-use aptos_api_types::{BcsBlock, Block, LedgerInfo};
+use anyhow::anyhow;
+use aptos_api_types::{
+    AptosErrorCode, BcsBlock, BcsBlockRandomness, Block, BlockRandomness, LedgerInfo,
+};
+use aptos_logger::warn;
+use aptos_types::{
+    block_metadata_ext::BlockMetadataExt,
+    dkg::DKGState,
+    on_chain_config::OnChainConfig,
+    randomness::{RandMetadata, Randomness, RandomnessProof},
+    transaction::Transaction,
+};
 use poem_openapi::{
     param::{Path, Query},
     OpenApi,
@@ -102,6 +116,38 @@ impl BlocksApi {
         })
         .await
     }
+
+    /// Get block randomness by height
+    ///
+    /// This endpoint allows you to get the randomness seed of a block, along with the
+    /// proof it was derived from.
+    ///
+    /// The proof is the BCS encoded `RandomnessProof`, which can be verified with
+    /// `RandomnessProof::verify` against the DKG session of the block's epoch, i.e.,
+    /// `0x1::dkg::DKGState.last_completed` at the block's first version. The proof is
+    /// only returned if it verifies against the randomness and the DKG session committed
+    /// on chain. It's only available on validators that retain the proofs (see
+    /// `randomness_proof_retention_epochs`), for the blocks of recent epochs.
+    ///
+    /// If the block is pruned, it will return a 410
+    #[oai(
+        path = "/blocks/by_height/:block_height/randomness",
+        method = "get",
+        operation_id = "get_block_randomness_by_height",
+        tag = "ApiTags::Blocks"
+    )]
+    async fn get_block_randomness_by_height(
+        &self,
+        accept_type: AcceptType,
+        /// Block height to lookup.  Starts at 0
+        block_height: Path<u64>,
+    ) -> BasicResultWith404<BlockRandomness> {
+        fail_point_poem("endpoint_get_block_randomness_by_height")?;
+        self.context
+            .check_api_output_enabled("Get block randomness by height", &accept_type)?;
+        let api = self.clone();
+        api_spawn_blocking(move || api.get_randomness_by_height(accept_type, block_height.0)).await
+    }
 }
 
 impl BlocksApi {
@@ -135,6 +181,151 @@ impl BlocksApi {
         self.render_bcs_block(&accept_type, latest_ledger_info, bcs_block)
     }
 
+    fn get_randomness_by_height(
+        &self,
+        accept_type: AcceptType,
+        block_height: u64,
+    ) -> BasicResultWith404<BlockRandomness> {
+        let latest_ledger_info = self.context.get_latest_ledger_info()?;
+        let bcs_block =
+            self.context
+                .get_block_by_height(block_height, &latest_ledger_info, false)?;
+
+        // The block metadata is always the first transaction of the block
+        let txn = self
+            .context
+            .db
+            .get_transaction_by_version(
+                bcs_block.first_version,
+                latest_ledger_info.version(),
+                false,
+            )
+            .map_err(|_| {
+                version_not_found::<BasicErrorWith404>(bcs_block.first_version, &latest_ledger_info)
+            })?
+            .transaction;
+        let (epoch, round, randomness) = match txn {
+            Transaction::BlockMetadata(block_metadata) => {
+                (block_metadata.epoch(), block_metadata.round(), None)
+            },
+            Transaction::BlockMetadataExt(BlockMetadataExt::V0(block_metadata)) => {
+                (block_metadata.epoch(), block_metadata.round(), None)
+            },
+            Transaction::BlockMetadataExt(BlockMetadataExt::V1(block_metadata)) => (
+                block_metadata.epoch,
+                block_metadata.round,
+                block_metadata.randomness,
+            ),
+            _ => {
+                return Err(BasicErrorWith404::internal_with_code(
+                    format!(
+                        "First transaction of block {} is not a block metadata transaction",
+                        block_height
+                    ),
+                    AptosErrorCode::InternalError,
+                    &latest_ledger_info,
+                ))
+            },
+        };
+
+        let metadata = RandMetadata { epoch, round };
+        let proof = match (&randomness, &self.context.randomness_proof_reader) {
+            (Some(randomness), Some(reader)) => reader
+                .get_randomness_proof(&metadata)
+                .map_err(|err| {
+                    BasicErrorWith404::internal_with_code(
+                        err,
+                        AptosErrorCode::InternalError,
+                        &latest_ledger_info,
+                    )
+                })?
+                .filter(|proof| {
+                    if self.context.is_randomness_proof_verified(&metadata) {
+                        return true;
+                    }
+                    match self.verify_randomness_proof(bcs_block.first_version, proof, randomness) {
+                        Ok(()) => {
+                            self.context
+                                .mark_randomness_proof_verified(metadata.clone());
+                            true
+                        },
+                        Err(err) => {
+                            warn!(
+                                "Randomness proof of block {} does not verify: {:?}",
+                                block_height, err
+                            );
+                            false
+                        },
+                    }
+                }),
+            _ => None,
+        };
+
+        let bcs_block_randomness = BcsBlockRandomness {
+            block_height,
+            block_hash: bcs_block.block_hash,
+            epoch,
+            round,
+            randomness,
+            proof,
+        };
+        match accept_type {
+            AcceptType::Json => {
+                let proof = bcs_block_randomness
+                    .proof
+                    .as_ref()
+                    .map(bcs::to_bytes)
+                    .transpose()
+                    .map_err(|err| {
+                        BasicErrorWith404::internal_with_code(
+                            err,
+                            AptosErrorCode::InternalError,
+                            &latest_ledger_info,
+                        )
+                    })?
+                    .map(Into::into);
+                let block_randomness = BlockRandomness {
+                    block_height: block_height.into(),
+                    block_hash: bcs_block_randomness.block_hash.into(),
+                    epoch: epoch.into(),
+                    round: round.into(),
+                    randomness: bcs_block_randomness
+                        .randomness
+                        .as_ref()
+                        .map(|randomness| randomness.randomness_cloned().into()),
+                    proof,
+                };
+                BasicResponse::try_from_json((
+                    block_randomness,
+                    &latest_ledger_info,
+                    BasicResponseStatus::Ok,
+                ))
+            },
+            AcceptType::Bcs => BasicResponse::try_from_bcs((
+                bcs_block_randomness,
+                &latest_ledger_info,
+                BasicResponseStatus::Ok,
+            )),
+        }
+    }
+
+    /// Verifies the proof against the randomness of the block, and the DKG session of its
+    /// epoch, as committed on chain at the given version of the block
+    fn verify_randomness_proof(
+        &self,
+        version: u64,
+        proof: &RandomnessProof,
+        randomness: &Randomness,
+    ) -> anyhow::Result<()> {
+        let state_view = self.context.state_view_at_version(version)?;
+        let dkg_state = DKGState::fetch_config(&state_view)
+            .ok_or_else(|| anyhow!("No DKG state at version {}", version))?;
+        let dkg_session = dkg_state
+            .maybe_last_complete(proof.metadata.epoch)
+            .ok_or_else(|| anyhow!("No DKG session of epoch {}", proof.metadata.epoch))?;
+        proof.verify(dkg_session, randomness)
+    }
+
     /// Renders a [`BcsBlock`] into a [`Block`] if it's a JSON accept type
     fn render_bcs_block(
         &self,
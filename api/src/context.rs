@@ -36,6 +36,7 @@ use aptos_types::{
     indexer::indexer_db_reader::IndexerReader,
     ledger_info::LedgerInfoWithSignatures,
     on_chain_config::{GasSchedule, GasScheduleV2, OnChainConfig, OnChainExecutionConfig},
+    randomness::{RandMetadata, RandomnessProofReader},
     state_store::{
         state_key::{inner::StateKeyInner, prefix::StateKeyPrefix, StateKey},
         state_value::StateValue,
@@ -64,6 +65,9 @@ use std::{
     time::Instant,
 };
 
+/// The number of blocks for which verified randomness proofs are cached
+const VERIFIED_RANDOMNESS_PROOFS_CACHE_SIZE: u64 = 10_000;
+
 // Context holds application scope context
 #[derive(Clone)]
 pub struct Context {
@@ -77,6 +81,12 @@ pub struct Context {
     view_function_stats: Arc<FunctionStats>,
     simulate_txn_stats: Arc<FunctionStats>,
     pub indexer_reader: Option<Arc<dyn IndexerReader>>,
+    /// The randomness proofs are only available on validators, which persist them when
+    /// aggregating the randomness of a block
+    pub randomness_proof_reader: Option<Arc<dyn RandomnessProofReader>>,
+    /// The blocks whose randomness proofs were recently verified, so that the (expensive)
+    /// verification is done once per block, and not for every request
+    verified_randomness_proofs: Cache<RandMetadata, ()>,
     pub wait_for_hash_active_connections: Arc<AtomicUsize>,
 }
 
@@ -130,10 +140,30 @@ impl Context {
             view_function_stats,
             simulate_txn_stats,
             indexer_reader,
+            randomness_proof_reader: None,
+            verified_randomness_proofs: Cache::new(VERIFIED_RANDOMNESS_PROOFS_CACHE_SIZE),
             wait_for_hash_active_connections: Arc::new(AtomicUsize::new(0)),
         }
     }
 
+    pub fn with_randomness_proof_reader(
+        mut self,
+        randomness_proof_reader: Option<Arc<dyn RandomnessProofReader>>,
+    ) -> Self {
+        self.randomness_proof_reader = randomness_proof_reader;
+        self
+    }
+
+    /// Returns true iff the randomness proof of the given block was verified before (proofs
+    /// are persisted once, so a verified proof stays valid)
+    pub fn is_randomness_proof_verified(&self, metadata: &RandMetadata) -> bool {
+        self.verified_randomness_proofs.contains_key(metadata)
+    }
+
+    pub fn mark_randomness_proof_verified(&self, metadata: RandMetadata) {
+        self.verified_randomness_proofs.insert(metadata, ());
+    }
+
     pub fn max_transactions_page_size(&self) -> u16 {
         self.node_config.api.max_transactions_page_size
     }
@@ -23,7 +23,9 @@ use aptos_config::config::{ApiConfig, NodeConfig};
 use aptos_logger::info;
 use aptos_mempool::MempoolClientSender;
 use aptos_storage_interface::DbReader;
-use aptos_types::{chain_id::ChainId, indexer::indexer_db_reader::IndexerReader};
+use aptos_types::{
+    chain_id::ChainId, indexer::indexer_db_reader::IndexerReader, randomness::RandomnessProofReader,
+};
 use poem::{
     handler,
     http::Method,
@@ -45,11 +47,13 @@ pub fn bootstrap(
     db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
     indexer_reader: Option<Arc<dyn IndexerReader>>,
+    randomness_proof_reader: Option<Arc<dyn RandomnessProofReader>>,
 ) -> anyhow::Result<Runtime> {
     let max_runtime_workers = get_max_runtime_workers(&config.api);
     let runtime = aptos_runtimes::spawn_named_runtime("api".into(), Some(max_runtime_workers));
 
-    let context = Context::new(chain_id, db, mp_sender, config.clone(), indexer_reader);
+    let context = Context::new(chain_id, db, mp_sender, config.clone(), indexer_reader)
+        .with_randomness_proof_reader(randomness_proof_reader);
 
     attach_poem_to_runtime(runtime.handle(), context.clone(), config, false)
         .context("Failed to attach poem to runtime")?;
@@ -351,6 +355,7 @@ mod tests {
             context.db.clone(),
             context.mempool.ac_client.clone(),
             None,
+            None,
         );
         assert!(ret.is_ok());
 
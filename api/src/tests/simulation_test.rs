@@ -1,8 +1,9 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use super::new_test_context;
+use super::{new_test_context, new_test_context_with_config};
 use aptos_api_test_context::{current_function_name, TestContext};
+use aptos_config::config::NodeConfig;
 use aptos_crypto::ed25519::Ed25519Signature;
 use aptos_types::transaction::{
     authenticator::TransactionAuthenticator, EntryFunction, TransactionPayload,
@@ -17,6 +18,7 @@ async fn simulate_aptos_transfer(
     transfer_amount: u64,
     expected_status: u16,
     assert_gas_used: bool,
+    trace: bool,
 ) -> serde_json::Value {
     let alice = &mut context.gen_account();
     let bob = &mut context.gen_account();
@@ -35,7 +37,13 @@ async fn simulate_aptos_transfer(
             .unwrap_or(Ed25519Signature::dummy_signature().to_string());
         let req = warp::test::request()
             .method("POST")
-            .path("/v1/transactions/simulate")
+            .path(
+                if trace {
+                    "/v1/transactions/simulate?trace=true"
+                } else {
+                    "/v1/transactions/simulate"
+                },
+            )
             .json(&json!({
                 "sender": txn.sender().to_string(),
                 "sequence_number": txn.sequence_number().to_string(),
@@ -82,21 +90,55 @@ const LARGE_TRANSFER_AMOUNT: u64 = 1_000_000_000;
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_simulate_transaction_with_valid_signature() {
     let mut context = new_test_context(current_function_name!());
-    let resp = simulate_aptos_transfer(&mut context, true, SMALL_TRANSFER_AMOUNT, 400, false).await;
+    let resp =
+        simulate_aptos_transfer(&mut context, true, SMALL_TRANSFER_AMOUNT, 400, false, false).await;
     context.check_golden_output(resp);
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_simulate_transaction_with_not_valid_signature() {
     let mut context = new_test_context(current_function_name!());
-    let resp = simulate_aptos_transfer(&mut context, false, SMALL_TRANSFER_AMOUNT, 200, true).await;
+    let resp =
+        simulate_aptos_transfer(&mut context, false, SMALL_TRANSFER_AMOUNT, 200, true, false).await;
+    assert!(resp[0]["success"].as_bool().is_some_and(|v| v));
+    assert!(resp[0].get("execution_trace").is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_with_trace() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.transaction_simulation_trace_enabled = true;
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let resp =
+        simulate_aptos_transfer(&mut context, false, SMALL_TRANSFER_AMOUNT, 200, true, true).await;
     assert!(resp[0]["success"].as_bool().is_some_and(|v| v));
+
+    let call_tree = &resp[0]["execution_trace"]["call_tree"];
+    assert_eq!(call_tree["function"], "0x1::aptos_account::transfer");
+    assert_eq!(
+        call_tree["args"][1],
+        json!(SMALL_TRANSFER_AMOUNT.to_string())
+    );
+    assert!(!call_tree["calls"].as_array().unwrap().is_empty());
+    assert!(!resp[0]["execution_trace"]["write_set"]
+        .as_array()
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_with_trace_disabled() {
+    let mut context = new_test_context(current_function_name!());
+    let resp =
+        simulate_aptos_transfer(&mut context, false, SMALL_TRANSFER_AMOUNT, 403, false, true).await;
+    assert_eq!(resp["error_code"], "api_disabled");
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_simulate_transaction_with_insufficient_balance() {
     let mut context = new_test_context(current_function_name!());
-    let resp = simulate_aptos_transfer(&mut context, false, LARGE_TRANSFER_AMOUNT, 200, true).await;
+    let resp =
+        simulate_aptos_transfer(&mut context, false, LARGE_TRANSFER_AMOUNT, 200, true, false).await;
     assert!(!resp[0]["success"].as_bool().is_some_and(|v| v));
 }
 
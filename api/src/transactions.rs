@@ -29,6 +29,8 @@ use aptos_api_types::{
     MAX_RECURSIVE_TYPES_ALLOWED, U64,
 };
 use aptos_crypto::{hash::CryptoHash, signing_message};
+use aptos_gas_profiling::ExecutionTracer;
+use aptos_resource_viewer::module_view::ModuleView;
 use aptos_types::{
     account_address::AccountAddress,
     mempool_status::MempoolStatusCode,
@@ -489,6 +491,11 @@ impl TransactionsApi {
         /// If set to true, the transaction will use a higher price than the original
         /// estimate.
         estimate_prioritized_gas_unit_price: Query<Option<bool>>,
+        /// If set to true, the response includes the execution trace of the transaction,
+        /// i.e., the call tree with the arguments, return values, events and resource
+        /// accesses of every call. Only supported for JSON responses, and only if tracing
+        /// is enabled on the node.
+        trace: Query<Option<bool>>,
         data: SubmitTransactionPost,
     ) -> SimulateTransactionResult<Vec<UserTransaction>> {
         data.verify()
@@ -503,6 +510,16 @@ impl TransactionsApi {
         if !self.context.node_config.api.transaction_simulation_enabled {
             return Err(api_disabled("Simulate transaction"));
         }
+        let trace = trace.0.unwrap_or_default();
+        if trace
+            && !self
+                .context
+                .node_config
+                .api
+                .transaction_simulation_trace_enabled
+        {
+            return Err(api_disabled("Simulate transaction with trace"));
+        }
         self.context
             .check_api_output_enabled("Simulate transaction", &accept_type)?;
 
@@ -617,7 +634,7 @@ impl TransactionsApi {
                 );
             }
 
-            api.simulate(&accept_type, ledger_info, signed_transaction)
+            api.simulate(&accept_type, ledger_info, signed_transaction, trace)
         })
         .await
     }
@@ -1343,7 +1360,7 @@ impl TransactionsApi {
     // TODO: This function leverages a lot of types from aptos_types, use the
     // local API types and just return those directly, instead of converting
     // from these types in render_transactions.
-    /// Simulate a transaction in the VM
+    /// Simulate a transaction in the VM, optionally tracing its execution
     ///
     /// Note: this returns a `Vec<UserTransaction>`, but for backwards compatibility, this can't
     /// be removed even though, there is only one possible transaction
@@ -1352,6 +1369,7 @@ impl TransactionsApi {
         accept_type: &AcceptType,
         ledger_info: LedgerInfo,
         txn: SignedTransaction,
+        trace: bool,
     ) -> SimulateTransactionResult<Vec<UserTransaction>> {
         // The caller must ensure that the signature is not valid, as otherwise
         // a malicious actor could execute the transaction without their knowledge
@@ -1363,10 +1381,57 @@ impl TransactionsApi {
             ));
         }
 
+        if trace {
+            // The trace is only rendered as JSON
+            if accept_type == &AcceptType::Bcs {
+                return Err(SubmitTransactionError::bad_request_with_code(
+                    "BCS is not supported for traced simulations",
+                    AptosErrorCode::BcsNotSupported,
+                    &ledger_info,
+                ));
+            }
+            // The payload of a multisig transaction is executed separately from the
+            // multisig account checks, so there is no single call tree
+            if matches!(
+                txn.payload(),
+                TransactionPayload::Multisig(_) | TransactionPayload::ModuleBundle(_)
+            ) {
+                return Err(SubmitTransactionError::bad_request_with_code(
+                    "Only script and entry function transactions can be traced",
+                    AptosErrorCode::InvalidInput,
+                    &ledger_info,
+                ));
+            }
+        }
+
         // Simulate transaction
         let state_view = self.context.latest_state_view_poem(&ledger_info)?;
-        let (vm_status, output) =
-            AptosSimulationVM::create_vm_and_simulate_signed_transaction(&txn, &state_view);
+        let (vm_status, output, execution_trace) = if trace {
+            let (vm_status, output, tracer) =
+                AptosSimulationVM::create_vm_and_simulate_signed_transaction_with_modified_gas_meter(
+                    &txn,
+                    &state_view,
+                    |gas_meter| match txn.payload() {
+                        TransactionPayload::EntryFunction(entry_func) => {
+                            ExecutionTracer::new_function(
+                                gas_meter,
+                                entry_func.module().clone(),
+                                entry_func.function().to_owned(),
+                                entry_func.ty_args().to_vec(),
+                                entry_func.args().to_vec(),
+                            )
+                        },
+                        _ => ExecutionTracer::new_script(gas_meter),
+                    },
+                );
+            let execution_trace =
+                tracer.map(|tracer| tracer.finish().to_json(&ModuleView::new(&state_view)));
+            (vm_status, output, execution_trace)
+        } else {
+            let (vm_status, output) =
+                AptosSimulationVM::create_vm_and_simulate_signed_transaction(&txn, &state_view);
+            (vm_status, output, None)
+        };
         let version = ledger_info.version();
 
         // Ensure that all known statuses return their values in the output (even if they aren't supposed to)
@@ -1449,6 +1514,7 @@ impl TransactionsApi {
                                 },
                                 _ => (),
                             }
+                            user_txn.execution_trace = execution_trace.clone();
                             user_transactions.push(user_txn);
                         },
                         _ => {
@@ -1,7 +1,8 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{HashValue, Transaction, TransactionOnChainData, U64};
+use crate::{HashValue, HexEncodedBytes, Transaction, TransactionOnChainData, U64};
+use aptos_types::randomness::{Randomness, RandomnessProof};
 use poem_openapi::Object;
 use serde::{Deserialize, Serialize};
 
@@ -39,3 +40,33 @@ pub struct BcsBlock {
     /// The transactions in the block in sequential order
     pub transactions: Option<Vec<TransactionOnChainData>>,
 }
+
+/// The randomness of a block, with the proof it was derived from (if available)
+///
+/// The proof is only available on validators that persisted it while aggregating the
+/// randomness of the block, and only for recent epochs.
+#[derive(Debug, Clone, Serialize, Deserialize, Object)]
+pub struct BlockRandomness {
+    pub block_height: U64,
+    pub block_hash: HashValue,
+    pub epoch: U64,
+    pub round: U64,
+    /// The randomness seed of the block, if randomness was enabled for the block
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub randomness: Option<HexEncodedBytes>,
+    /// The BCS encoded `RandomnessProof`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof: Option<HexEncodedBytes>,
+}
+
+/// The randomness of a block for encoding in BCS
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BcsBlockRandomness {
+    /// The block height (number of the block from 0)
+    pub block_height: u64,
+    pub block_hash: aptos_crypto::HashValue,
+    pub epoch: u64,
+    pub round: u64,
+    pub randomness: Option<Randomness>,
+    pub proof: Option<RandomnessProof>,
+}
@@ -22,6 +22,7 @@ use aptos_types::{
     block_metadata_ext::BlockMetadataExt,
     contract_event::{ContractEvent, EventWithVersion},
     dkg::{DKGTranscript, DKGTranscriptMetadata},
+    equivocation::{EquivocationReport, EquivocationReportMetadata},
     jwks::{jwk::JWK, ProviderJWKs, QuorumCertifiedUpdate},
     keyless,
     transaction::{
@@ -297,6 +298,7 @@ impl
             request: (txn, payload).into(),
             events,
             timestamp: timestamp.into(),
+            execution_trace: None,
         })
     }
 }
@@ -413,6 +415,12 @@ pub struct UserTransaction {
     /// Events generated by the transaction
     pub events: Vec<Event>,
     pub timestamp: U64,
+    /// The call tree of the execution, with the arguments, return values, events and resource
+    /// accesses of every call, and the write set of the transaction
+    ///
+    /// Only present for simulated transactions, if requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution_trace: Option<serde_json::Value>,
 }
 
 /// A state checkpoint transaction
@@ -589,6 +597,7 @@ pub struct BlockMetadataTransaction {
 pub enum ValidatorTransaction {
     ObservedJwkUpdate(JWKUpdateTransaction),
     DkgResult(DKGResultTransaction),
+    EquivocationReport(EquivocationReportTransaction),
 }
 
 impl ValidatorTransaction {
@@ -598,6 +607,9 @@ impl ValidatorTransaction {
                 "validator_transaction__observed_jwk_update"
             },
             ValidatorTransaction::DkgResult(_) => "validator_transaction__dkg_result",
+            ValidatorTransaction::EquivocationReport(_) => {
+                "validator_transaction__equivocation_report"
+            },
         }
     }
 
@@ -605,6 +617,7 @@ impl ValidatorTransaction {
         match self {
             ValidatorTransaction::ObservedJwkUpdate(t) => &t.info,
             ValidatorTransaction::DkgResult(t) => &t.info,
+            ValidatorTransaction::EquivocationReport(t) => &t.info,
         }
     }
 
@@ -612,6 +625,7 @@ impl ValidatorTransaction {
         match self {
             ValidatorTransaction::ObservedJwkUpdate(t) => &mut t.info,
             ValidatorTransaction::DkgResult(t) => &mut t.info,
+            ValidatorTransaction::EquivocationReport(t) => &mut t.info,
         }
     }
 
@@ -619,6 +633,7 @@ impl ValidatorTransaction {
         match self {
             ValidatorTransaction::ObservedJwkUpdate(t) => t.timestamp,
             ValidatorTransaction::DkgResult(t) => t.timestamp,
+            ValidatorTransaction::EquivocationReport(t) => t.timestamp,
         }
     }
 
@@ -626,6 +641,7 @@ impl ValidatorTransaction {
         match self {
             ValidatorTransaction::ObservedJwkUpdate(t) => &t.events,
             ValidatorTransaction::DkgResult(t) => &t.events,
+            ValidatorTransaction::EquivocationReport(t) => &t.events,
         }
     }
 }
@@ -663,6 +679,14 @@ impl
                 timestamp: U64::from(timestamp),
                 quorum_certified_update: quorum_certified_update.into(),
             }),
+            aptos_types::validator_txn::ValidatorTransaction::EquivocationReport(report) => {
+                Self::EquivocationReport(EquivocationReportTransaction {
+                    info,
+                    events,
+                    timestamp: U64::from(timestamp),
+                    equivocation_report: report.into(),
+                })
+            },
         }
     }
 }
@@ -771,6 +795,53 @@ impl From<DKGTranscript> for ExportedDKGTranscript {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct EquivocationReportTransaction {
+    #[serde(flatten)]
+    #[oai(flatten)]
+    pub info: TransactionInfo,
+    pub events: Vec<Event>,
+    pub timestamp: U64,
+    pub equivocation_report: ExportedEquivocationReport,
+}
+
+/// A more API-friendly representation of the on-chain `aptos_types::equivocation::EquivocationReport`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ExportedEquivocationReport {
+    pub epoch: U64,
+    pub round: U64,
+    /// The kind of the conflicting messages (`vote` or `proposal`)
+    pub kind: String,
+    pub offender: Address,
+    pub reporter: Address,
+    /// The BCS encoded consensus evidence (i.e., both conflicting signed messages)
+    pub evidence: HexEncodedBytes,
+}
+
+impl From<EquivocationReport> for ExportedEquivocationReport {
+    fn from(value: EquivocationReport) -> Self {
+        let EquivocationReport {
+            metadata,
+            evidence_bytes,
+        } = value;
+        let EquivocationReportMetadata {
+            epoch,
+            round,
+            kind,
+            offender,
+            reporter,
+        } = metadata;
+        Self {
+            epoch: epoch.into(),
+            round: round.into(),
+            kind: kind.as_str().to_string(),
+            offender: offender.into(),
+            reporter: reporter.into(),
+            evidence: HexEncodedBytes::from(evidence_bytes),
+        }
+    }
+}
+
 /// An event from a transaction
 #[derive(Clone, Debug, Deserialize, Eq, Object, PartialEq, Serialize)]
 pub struct Event {
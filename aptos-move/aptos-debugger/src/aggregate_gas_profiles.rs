@@ -0,0 +1,85 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{aptos_debugger::AptosDebugger, common::Target};
+use anyhow::{bail, Result};
+use aptos_rest_client::Client;
+use aptos_types::move_utils::MemberId;
+use clap::Parser;
+use std::{path::PathBuf, str::FromStr};
+use url::Url;
+
+/// Profiles the gas usage of a range of transactions, e.g., of all calls to an entry function,
+/// and merges the results into a single report
+#[derive(Parser)]
+pub struct Command {
+    #[clap(flatten)]
+    target: Target,
+
+    #[clap(long)]
+    begin_version: u64,
+
+    #[clap(long)]
+    limit: u64,
+
+    /// Only profile the calls to this entry function, e.g., `0x1::coin::transfer`
+    #[clap(long, value_parser = MemberId::from_str)]
+    entry_function: Option<MemberId>,
+
+    /// The directory to write the HTML report to
+    #[clap(long)]
+    report: Option<PathBuf>,
+
+    /// Where to save the merged profile as JSON, e.g., to compare it with `compare-gas-profiles`
+    #[clap(long)]
+    save: Option<PathBuf>,
+}
+
+impl Command {
+    pub async fn run(self) -> Result<()> {
+        let debugger = if let Some(rest_endpoint) = self.target.rest_endpoint {
+            AptosDebugger::rest_client(Client::new(Url::parse(&rest_endpoint)?))?
+        } else if let Some(db_path) = self.target.db_path {
+            AptosDebugger::db(db_path)?
+        } else {
+            unreachable!("Must provide one target.");
+        };
+
+        let profile = debugger
+            .profile_past_transactions(self.begin_version, self.limit, self.entry_function.as_ref())
+            .await?;
+        if profile.num_transactions == 0 {
+            bail!("No matching transactions to profile");
+        }
+        println!(
+            "Profiled {} transaction(s), {} internal gas units of execution & IO and {} octas of storage on average",
+            profile.num_transactions,
+            profile.exec_io_total / profile.num_transactions,
+            profile.storage_total / profile.num_transactions,
+        );
+
+        if let Some(save) = &self.save {
+            std::fs::write(save, serde_json::to_string_pretty(&profile)?)?;
+            println!("Profile saved to {}", save.display());
+        }
+        if let Some(report) = &self.report {
+            let header = match &self.entry_function {
+                Some(entry_function) => format!(
+                    "Gas Report - {}::{}, versions {}..{}",
+                    entry_function.module_id.short_str_lossless(),
+                    entry_function.member_id,
+                    self.begin_version,
+                    self.begin_version + self.limit
+                ),
+                None => format!(
+                    "Gas Report - versions {}..{}",
+                    self.begin_version,
+                    self.begin_version + self.limit
+                ),
+            };
+            profile.generate_html_report(report, header)?;
+            println!("Report written to {}", report.display());
+        }
+        Ok(())
+    }
+}
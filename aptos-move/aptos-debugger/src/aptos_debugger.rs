@@ -1,16 +1,37 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(feature = "debugging")]
+use crate::{
+    move_debugger::{
+        Breakpoint, Breakpoints, DebugFrontend, MoveDebugger, Session, SourceResolver,
+    },
+    move_profiler::{self, ExecutionProfile},
+};
+use crate::{
+    replay_diff::ReplayDiff,
+    state_overrides::{OverriddenStateView, StateOverrides},
+};
 use anyhow::{bail, format_err, Result};
-use aptos_block_executor::txn_commit_hook::NoOpTransactionCommitHook;
-use aptos_gas_profiling::{GasProfiler, TransactionGasLog};
+use aptos_block_executor::{
+    conflict_report::{recent_conflict_reports, BlockConflictReport},
+    txn_commit_hook::NoOpTransactionCommitHook,
+};
+#[cfg(feature = "debugging")]
+use aptos_framework::natives::code::PackageRegistry;
+use aptos_gas_profiling::{ExecutionTracer, GasProfiler, MergedGasProfile, TransactionGasLog};
+use aptos_resource_viewer::module_view::ModuleView;
 use aptos_rest_client::Client;
+#[cfg(feature = "debugging")]
+use aptos_types::state_store::state_key::StateKey;
 use aptos_types::{
     account_address::AccountAddress,
     block_executor::config::{
         BlockExecutorConfig, BlockExecutorConfigFromOnchain, BlockExecutorLocalConfig,
     },
-    state_store::TStateView,
+    move_utils::MemberId,
+    on_chain_config::OnChainConfig,
+    state_store::{StateView, TStateView},
     transaction::{
         signature_verified_transaction::SignatureVerifiedTransaction, BlockOutput,
         SignedTransaction, Transaction, TransactionInfo, TransactionOutput, TransactionPayload,
@@ -24,6 +45,7 @@ use aptos_validator_interface::{
 use aptos_vm::{
     block_executor::{AptosTransactionOutput, BlockAptosVM},
     data_cache::AsMoveResolver,
+    move_vm_ext::flush_warm_vm_cache,
     AptosVM,
 };
 use aptos_vm_logging::log_schema::AdapterLogSchema;
@@ -148,6 +170,49 @@ impl AptosDebugger {
         Ok((status, output, gas_profiler.finish()))
     }
 
+    /// Executes the transaction while recording its execution trace, which is returned as JSON:
+    /// the call tree with decoded arguments and return values, and the events emitted and the
+    /// resources accessed by each call.
+    pub fn execute_transaction_at_version_with_tracer(
+        &self,
+        version: Version,
+        txn: SignedTransaction,
+    ) -> Result<(VMStatus, VMOutput, serde_json::Value)> {
+        let state_view = DebuggerStateView::new(self.debugger.clone(), version);
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+        let txn = txn
+            .check_signature()
+            .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))?;
+
+        match txn.payload() {
+            TransactionPayload::Script(_) | TransactionPayload::EntryFunction(_) => (),
+            TransactionPayload::Multisig(_) => bail!("Multisig transactions cannot be traced yet"),
+            TransactionPayload::ModuleBundle(_) => bail!("Module bundle payload has been removed"),
+        }
+
+        let vm = AptosVM::new(&state_view);
+        let resolver = state_view.as_move_resolver();
+
+        let (status, output, tracer) = vm.execute_user_transaction_with_modified_gas_meter(
+            &resolver,
+            &txn,
+            &log_context,
+            |gas_meter| match txn.payload() {
+                TransactionPayload::EntryFunction(entry_func) => ExecutionTracer::new_function(
+                    gas_meter,
+                    entry_func.module().clone(),
+                    entry_func.function().to_owned(),
+                    entry_func.ty_args().to_vec(),
+                    entry_func.args().to_vec(),
+                ),
+                _ => ExecutionTracer::new_script(gas_meter),
+            },
+        )?;
+
+        let trace = tracer.finish().to_json(&ModuleView::new(&state_view));
+        Ok((status, output, trace))
+    }
+
     pub async fn execute_past_transactions(
         &self,
         begin: Version,
@@ -185,6 +250,66 @@ impl AptosDebugger {
         }
     }
 
+    /// Replays the transactions twice, with the on-chain state and with the overrides applied on
+    /// top of it, and reports the differences between the outputs. Every block is executed
+    /// against the committed state at its first version, so that the differences of a block do
+    /// not carry over to the next ones, and the overrides are resolved against that state, so
+    /// that on-chain changes within the range (e.g., of the gas schedule or of the features in a
+    /// new epoch) are overridden as well.
+    pub async fn diff_past_transactions(
+        &self,
+        begin: Version,
+        limit: u64,
+        overrides: &StateOverrides,
+        concurrency_level: usize,
+        top_n: usize,
+    ) -> Result<ReplayDiff> {
+        let (txns, _) = self
+            .debugger
+            .get_committed_transactions(begin, limit)
+            .await?;
+        let labels = txns.iter().map(transaction_label).collect();
+
+        let mut baseline = vec![];
+        let mut modified = vec![];
+        let mut cur_version = begin;
+        for block in split_into_blocks(txns) {
+            let sig_verified_txns: Vec<SignatureVerifiedTransaction> =
+                block.into_iter().map(|x| x.into()).collect::<Vec<_>>();
+            let state_view = DebuggerStateView::new(self.debugger.clone(), cur_version);
+            let overridden_values = overrides.resolve(&state_view)?;
+            let overridden_state_view = OverriddenStateView::new(&state_view, &overridden_values);
+
+            // The VM caches the modules it loads, which are different for both executions
+            if overrides.overrides_code() {
+                flush_warm_vm_cache();
+            }
+            let baseline_outputs =
+                execute_block_no_limit(&sig_verified_txns, &state_view, concurrency_level)
+                    .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))?;
+            if overrides.overrides_code() {
+                flush_warm_vm_cache();
+            }
+            let modified_outputs = execute_block_no_limit(
+                &sig_verified_txns,
+                &overridden_state_view,
+                concurrency_level,
+            )
+            .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))?;
+
+            println!(
+                "[{} txns from {}] Replayed with and without overrides",
+                sig_verified_txns.len(),
+                cur_version,
+            );
+            cur_version += sig_verified_txns.len() as Version;
+            baseline.extend(baseline_outputs);
+            modified.extend(modified_outputs);
+        }
+
+        Ok(ReplayDiff::new(begin, labels, &baseline, &modified, top_n))
+    }
+
     fn print_mismatches(
         txn_outputs: &[TransactionOutput],
         expected_txn_infos: &[TransactionInfo],
@@ -321,6 +446,91 @@ impl AptosDebugger {
         Ok(ret)
     }
 
+    /// Replays the blocks of a range of versions in parallel and reports the conflicts between
+    /// their transactions. Every block is executed against the committed state at its first
+    /// version. Blocks whose parallel execution failed, and that were executed sequentially
+    /// instead, are left out.
+    pub async fn report_conflicts(
+        &self,
+        begin: Version,
+        limit: u64,
+        concurrency_level: usize,
+    ) -> Result<Vec<(Version, Arc<BlockConflictReport>)>> {
+        if concurrency_level < 2 {
+            bail!("Conflict reports require parallel execution, i.e. a concurrency level of at least 2");
+        }
+        let (txns, _) = self
+            .debugger
+            .get_committed_transactions(begin, limit)
+            .await?;
+
+        let mut reports = vec![];
+        let mut version = begin;
+        for block in split_into_blocks(txns) {
+            let num_txns = block.len() as u64;
+            let sig_verified_txns: Vec<SignatureVerifiedTransaction> =
+                block.into_iter().map(|x| x.into()).collect::<Vec<_>>();
+            let state_view = DebuggerStateView::new(self.debugger.clone(), version);
+
+            execute_block(&sig_verified_txns, &state_view, concurrency_level, true)
+                .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))?;
+            // Every parallel execution publishes a report, the one of the block is the latest
+            let report = recent_conflict_reports().pop().ok_or_else(|| {
+                format_err!("No conflict report for the block at version {}", version)
+            })?;
+            if report.sequential_fallback {
+                println!(
+                    "No conflict report for the block at version {}, it was executed sequentially",
+                    version
+                );
+            } else {
+                reports.push((version, report));
+            }
+            version += num_txns;
+        }
+        Ok(reports)
+    }
+
+    /// Profiles the user transactions of a range of versions, optionally only the calls to an
+    /// entry function, and merges their gas logs. Each transaction is executed on its own
+    /// against the committed state before it.
+    pub async fn profile_past_transactions(
+        &self,
+        begin: Version,
+        limit: u64,
+        entry_function: Option<&MemberId>,
+    ) -> Result<MergedGasProfile> {
+        let (txns, _) = self
+            .debugger
+            .get_committed_transactions(begin, limit)
+            .await?;
+
+        let mut profile = MergedGasProfile::new();
+        for (version, txn) in (begin..).zip(txns) {
+            let Transaction::UserTransaction(txn) = txn else {
+                continue;
+            };
+            match txn.payload() {
+                TransactionPayload::Script(_) if entry_function.is_none() => (),
+                TransactionPayload::EntryFunction(entry_func) => {
+                    if let Some(member_id) = entry_function {
+                        if entry_func.module() != &member_id.module_id
+                            || entry_func.function() != member_id.member_id.as_ident_str()
+                        {
+                            continue;
+                        }
+                    }
+                },
+                _ => continue,
+            }
+
+            let (_, _, log) =
+                self.execute_transaction_at_version_with_gas_profiler(version, txn)?;
+            profile.add(&log)?;
+        }
+        Ok(profile)
+    }
+
     pub async fn get_version_by_account_sequence(
         &self,
         account: AccountAddress,
@@ -350,6 +560,76 @@ impl AptosDebugger {
     }
 }
 
+// The debugger and the profiler hook into the interpreter, which requires the `debugging`
+// feature of the Move VM
+#[cfg(feature = "debugging")]
+impl AptosDebugger {
+    /// Executes the transaction with the Move debugger, which hands over to the frontend on
+    /// breakpoints, before aborts (if `stop_on_abort` is set), and on entry of the entry
+    /// function (if `stop_on_entry` is set).
+    pub fn debug_transaction_at_version(
+        &self,
+        version: Version,
+        txn: SignedTransaction,
+        frontend: Box<dyn DebugFrontend>,
+        mut breakpoints: Vec<Breakpoint>,
+        stop_on_entry: bool,
+        stop_on_abort: bool,
+    ) -> Result<(VMStatus, VMOutput)> {
+        let state_view = DebuggerStateView::new(self.debugger.clone(), version);
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+
+        if stop_on_entry {
+            if let TransactionPayload::EntryFunction(entry_func) = txn.payload() {
+                breakpoints.push(Breakpoint::Function {
+                    module_id: entry_func.module().clone(),
+                    function: entry_func.function().to_string(),
+                });
+            }
+        }
+        let debugger = MoveDebugger::new(frontend, Session {
+            breakpoints: Breakpoints::new(breakpoints),
+            sources: self.source_resolver_at_version(version),
+            stop_on_abort,
+        });
+
+        let vm = AptosVM::new(&state_view);
+        let resolver = state_view.as_move_resolver();
+        debugger.run(
+            || vm.execute_user_transaction(&resolver, &txn, &log_context),
+            |(status, _)| format!("{:?}", status),
+        )
+    }
+
+    /// Executes the transaction with the Move profiler installed, see [move_profiler::profile].
+    pub fn profile_transaction_at_version(
+        &self,
+        version: Version,
+        txn: SignedTransaction,
+    ) -> (VMStatus, VMOutput, ExecutionProfile) {
+        let state_view = DebuggerStateView::new(self.debugger.clone(), version);
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+
+        let vm = AptosVM::new(&state_view);
+        let resolver = state_view.as_move_resolver();
+        let ((status, output), profile) =
+            move_profiler::profile(|| vm.execute_user_transaction(&resolver, &txn, &log_context));
+        (status, output, profile)
+    }
+
+    /// Resolves the sources of modules from the package metadata stored on chain at the version
+    pub fn source_resolver_at_version(&self, version: Version) -> SourceResolver {
+        let registry_view = DebuggerStateView::new(self.debugger.clone(), version);
+        SourceResolver::new(Box::new(move |address| {
+            let state_key = StateKey::resource(&address, &PackageRegistry::struct_tag())?;
+            Ok(registry_view
+                .get_state_value_bytes(&state_key)?
+                .map(|bytes| bcs::from_bytes(&bytes))
+                .transpose()?)
+        }))
+    }
+}
+
 fn print_transaction_stats(sig_verified_txns: &[SignatureVerifiedTransaction], version: u64) {
     let transaction_types = sig_verified_txns
         .iter()
@@ -411,6 +691,36 @@ fn print_transaction_stats(sig_verified_txns: &[SignatureVerifiedTransaction], v
     );
 }
 
+/// Splits the transactions at block boundaries, the first block may be partial
+fn split_into_blocks(txns: Vec<Transaction>) -> Vec<Vec<Transaction>> {
+    let mut blocks = vec![];
+    let mut cur = vec![];
+    for txn in txns {
+        if txn.is_block_start() && !cur.is_empty() {
+            blocks.push(std::mem::take(&mut cur));
+        }
+        cur.push(txn);
+    }
+    if !cur.is_empty() {
+        blocks.push(cur);
+    }
+    blocks
+}
+
+fn transaction_label(txn: &Transaction) -> String {
+    match txn.try_as_signed_user_txn().map(|txn| txn.payload()) {
+        Some(TransactionPayload::EntryFunction(entry_func)) => format!(
+            "{}::{}",
+            entry_func.module().short_str_lossless(),
+            entry_func.function()
+        ),
+        Some(TransactionPayload::Script(_)) => "script".to_string(),
+        Some(TransactionPayload::Multisig(_)) => "multisig".to_string(),
+        Some(TransactionPayload::ModuleBundle(_)) => "module bundle".to_string(),
+        None => txn.type_name().to_string(),
+    }
+}
+
 fn is_reconfiguration(vm_output: &TransactionOutput) -> bool {
     let new_epoch_event_key = aptos_types::on_chain_config::new_epoch_event_key();
     vm_output
@@ -421,8 +731,17 @@ fn is_reconfiguration(vm_output: &TransactionOutput) -> bool {
 
 fn execute_block_no_limit(
     sig_verified_txns: &[SignatureVerifiedTransaction],
-    state_view: &DebuggerStateView,
+    state_view: &(impl StateView + Sync),
+    concurrency_level: usize,
+) -> Result<Vec<TransactionOutput>, VMStatus> {
+    execute_block(sig_verified_txns, state_view, concurrency_level, false)
+}
+
+fn execute_block(
+    sig_verified_txns: &[SignatureVerifiedTransaction],
+    state_view: &(impl StateView + Sync),
     concurrency_level: usize,
+    conflict_report: bool,
 ) -> Result<Vec<TransactionOutput>, VMStatus> {
     BlockAptosVM::execute_block::<_, NoOpTransactionCommitHook<AptosTransactionOutput, VMStatus>>(
         sig_verified_txns,
@@ -432,6 +751,8 @@ fn execute_block_no_limit(
                 concurrency_level,
                 allow_fallback: true,
                 discard_failed_blocks: false,
+                conflict_report,
+                prefetch_read_sets: false,
             },
             onchain: BlockExecutorConfigFromOnchain::new_no_block_limit(),
         },
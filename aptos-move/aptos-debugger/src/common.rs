@@ -1,7 +1,10 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{execute_past_transactions, execute_pending_block};
+use crate::{
+    aggregate_gas_profiles, compare_gas_profiles, diff_past_transactions,
+    execute_past_transactions, execute_pending_block, report_conflicts,
+};
 use anyhow::Result;
 use clap::Parser;
 use std::path::PathBuf;
@@ -35,6 +38,10 @@ pub struct Opts {
 pub enum Command {
     ExecutePastTransactions(execute_past_transactions::Command),
     ExecutePendingBlock(execute_pending_block::Command),
+    DiffPastTransactions(diff_past_transactions::Command),
+    AggregateGasProfiles(aggregate_gas_profiles::Command),
+    CompareGasProfiles(compare_gas_profiles::Command),
+    ReportConflicts(report_conflicts::Command),
 }
 
 impl Command {
@@ -42,6 +49,10 @@ impl Command {
         match self {
             Command::ExecutePastTransactions(cmd) => cmd.run().await,
             Command::ExecutePendingBlock(cmd) => cmd.run().await,
+            Command::DiffPastTransactions(cmd) => cmd.run().await,
+            Command::AggregateGasProfiles(cmd) => cmd.run().await,
+            Command::CompareGasProfiles(cmd) => cmd.run().await,
+            Command::ReportConflicts(cmd) => cmd.run().await,
         }
     }
 }
@@ -0,0 +1,64 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, Result};
+use aptos_gas_profiling::{GasProfileComparison, MergedGasProfile};
+use clap::Parser;
+use std::path::{Path, PathBuf};
+
+/// Compares two gas profiles saved by `aggregate-gas-profiles`, e.g., before and after an
+/// upgrade, and reports the costs that changed
+#[derive(Parser)]
+pub struct Command {
+    /// The baseline profile
+    #[clap(long)]
+    before: PathBuf,
+
+    /// The profile to compare against the baseline
+    #[clap(long)]
+    after: PathBuf,
+
+    /// How much the average cost of an item may grow, in percent, before it is reported as a
+    /// regression
+    #[clap(long, default_value_t = 5.0)]
+    threshold: f64,
+
+    /// The directory to write the HTML report to
+    #[clap(long)]
+    report: Option<PathBuf>,
+
+    /// Exit with an error if any regression is found, e.g., to run the comparison in CI
+    #[clap(long)]
+    fail_on_regression: bool,
+}
+
+fn load_profile(path: &Path) -> Result<MergedGasProfile> {
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+impl Command {
+    pub async fn run(self) -> Result<()> {
+        let before = load_profile(&self.before)?;
+        let after = load_profile(&self.after)?;
+        let comparison = GasProfileComparison::new(&before, &after, self.threshold)?;
+        println!("{}", comparison);
+
+        if let Some(report) = &self.report {
+            comparison.generate_html_report(
+                report,
+                format!(
+                    "Gas Comparison - {} vs {}",
+                    self.before.display(),
+                    self.after.display()
+                ),
+            )?;
+            println!("Report written to {}", report.display());
+        }
+
+        let regressions = comparison.regressions().count();
+        if self.fail_on_regression && regressions > 0 {
+            bail!("Found {} gas regression(s)", regressions);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{aptos_debugger::AptosDebugger, common::Target, state_overrides::StateOverrides};
+use anyhow::{bail, format_err, Result};
+use aptos_framework::ReleaseBundle;
+use aptos_gas_schedule::LATEST_GAS_FEATURE_VERSION;
+use aptos_rest_client::Client;
+use aptos_types::on_chain_config::{FeatureFlag, GasScheduleV2};
+use clap::Parser;
+use std::{path::PathBuf, str::FromStr};
+use url::Url;
+
+/// Replays a range of transactions with and without changes to the gas schedule, the feature
+/// flags or the framework, and reports the differences of their outputs
+#[derive(Parser)]
+pub struct Command {
+    #[clap(flatten)]
+    target: Target,
+
+    #[clap(long)]
+    begin_version: u64,
+
+    #[clap(long)]
+    limit: u64,
+
+    #[clap(long, default_value_t = 1)]
+    concurrency_level: usize,
+
+    /// The gas schedule to use instead of the on-chain one: the path to a JSON file of a
+    /// `GasScheduleV2`, or `current` for the one of this binary
+    #[clap(long)]
+    gas_schedule: Option<String>,
+
+    /// Gas parameters to change, as `<name>=<value>`
+    #[clap(long, value_parser = parse_gas_param)]
+    gas_param: Vec<(String, u64)>,
+
+    /// Feature flags to enable, e.g., `CODE_DEPENDENCY_CHECK`
+    #[clap(long, value_delimiter = ',', value_parser = FeatureFlag::from_str)]
+    enable_features: Vec<FeatureFlag>,
+
+    /// Feature flags to disable
+    #[clap(long, value_delimiter = ',', value_parser = FeatureFlag::from_str)]
+    disable_features: Vec<FeatureFlag>,
+
+    /// A release bundle (`.mrb`) whose packages replace the on-chain ones, e.g., built with
+    /// `aptos-release-builder`
+    #[clap(long)]
+    framework: Option<PathBuf>,
+
+    /// The number of transactions to report for the largest gas increases and decreases
+    #[clap(long, default_value_t = 10)]
+    top_n: usize,
+
+    /// Where to write the full report as JSON
+    #[clap(long)]
+    output: Option<PathBuf>,
+}
+
+fn parse_gas_param(s: &str) -> Result<(String, u64)> {
+    let (name, value) = s
+        .split_once('=')
+        .ok_or_else(|| format_err!("Expected `<name>=<value>`, got `{}`", s))?;
+    Ok((name.to_string(), value.parse()?))
+}
+
+impl Command {
+    fn overrides(&self) -> Result<StateOverrides> {
+        let mut overrides = StateOverrides::new();
+        if let Some(gas_schedule) = &self.gas_schedule {
+            let gas_schedule = if gas_schedule == "current" {
+                aptos_gas_schedule_updator::current_gas_schedule(LATEST_GAS_FEATURE_VERSION)
+            } else {
+                serde_json::from_str::<GasScheduleV2>(&std::fs::read_to_string(gas_schedule)?)?
+            };
+            overrides = overrides.with_gas_schedule(gas_schedule);
+        }
+        for (name, value) in &self.gas_param {
+            overrides = overrides.with_gas_param(name.clone(), *value);
+        }
+        for flag in &self.enable_features {
+            overrides = overrides.enable_feature(*flag);
+        }
+        for flag in &self.disable_features {
+            overrides = overrides.disable_feature(*flag);
+        }
+        if let Some(framework) = &self.framework {
+            overrides = overrides.with_framework(ReleaseBundle::read(framework.clone())?);
+        }
+        Ok(overrides)
+    }
+
+    pub async fn run(self) -> Result<()> {
+        let overrides = self.overrides()?;
+        if overrides.is_empty() {
+            bail!("No overrides provided, the replays would be identical");
+        }
+
+        let debugger = if let Some(rest_endpoint) = self.target.rest_endpoint {
+            AptosDebugger::rest_client(Client::new(Url::parse(&rest_endpoint)?))?
+        } else if let Some(db_path) = self.target.db_path {
+            AptosDebugger::db(db_path)?
+        } else {
+            unreachable!("Must provide one target.");
+        };
+
+        let diff = debugger
+            .diff_past_transactions(
+                self.begin_version,
+                self.limit,
+                &overrides,
+                self.concurrency_level,
+                self.top_n,
+            )
+            .await?;
+        println!("{}", diff);
+
+        if let Some(output) = self.output {
+            std::fs::write(&output, serde_json::to_string_pretty(&diff)?)?;
+            println!("Report written to {}", output.display());
+        }
+        Ok(())
+    }
+}
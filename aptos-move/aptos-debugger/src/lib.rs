@@ -1,8 +1,18 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod aggregate_gas_profiles;
 pub mod aptos_debugger;
 pub mod bcs_txn_decoder;
 pub mod common;
+pub mod compare_gas_profiles;
+pub mod diff_past_transactions;
 pub mod execute_past_transactions;
 pub mod execute_pending_block;
+#[cfg(feature = "debugging")]
+pub mod move_debugger;
+#[cfg(feature = "debugging")]
+pub mod move_profiler;
+pub mod replay_diff;
+pub mod report_conflicts;
+pub mod state_overrides;
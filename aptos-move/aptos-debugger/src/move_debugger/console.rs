@@ -0,0 +1,235 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::move_debugger::{
+    Breakpoint, DebugFrontend, ResumeCommand, Session, SourceResolver, StopReason,
+};
+use anyhow::Result;
+use move_vm_runtime::{DebugFrame, DebugState};
+use std::{
+    io::{self, BufRead, Write},
+    str::FromStr,
+};
+
+// The number of lines listed around the current one
+const LIST_CONTEXT_LINES: u32 = 5;
+
+const HELP: &str = "\
+Commands:
+  c, continue             run until the next breakpoint
+  s, step                 step into the next instruction
+  n, next                 step over calls
+  o, out                  step out of the current function
+  bt, backtrace           print the call stack
+  f, frame <n>            select frame <n> of the backtrace (0 is the current one)
+  l, locals               print the locals of the selected frame
+  st, stack               print the operand stack
+  ls, list                print the source (or the bytecode) around the current instruction
+  b, break <breakpoint>   add a breakpoint: <address>::<module>::<function>[+<offset>] or
+                          <file>.move:<line>
+  d, delete <n>           delete breakpoint <n>
+  info                    print the breakpoints
+  abort on|off            whether to stop before aborts
+  detach                  run until the end without stopping
+  h, help                 print this help";
+
+/// A command line frontend on the standard input and output
+#[derive(Default)]
+pub struct ConsoleFrontend {
+    // The selected frame, from the current one
+    selected_frame: usize,
+}
+
+impl ConsoleFrontend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn selected<'a>(&self, state: &'a DebugState) -> &'a DebugFrame {
+        &state.frames[state.frames.len() - 1 - self.selected_frame]
+    }
+
+    fn location(frame: &DebugFrame, sources: &mut SourceResolver) -> String {
+        let line = frame.module_id.as_ref().and_then(|module_id| {
+            let source = sources.get(module_id);
+            source
+                .line(frame.function_index, frame.pc)
+                .map(|line| format!(" ({}:{})", source.name(), line))
+        });
+        format!(
+            "{} @ {}{}",
+            frame.qualified_name(),
+            frame.pc,
+            line.unwrap_or_default()
+        )
+    }
+
+    fn print_backtrace(&self, state: &DebugState, sources: &mut SourceResolver) {
+        for (idx, frame) in state.frames.iter().rev().enumerate() {
+            let marker = if idx == self.selected_frame { '>' } else { ' ' };
+            println!("{} #{} {}", marker, idx, Self::location(frame, sources));
+        }
+    }
+
+    fn print_locals(&self, state: &DebugState, sources: &mut SourceResolver) {
+        let frame = self.selected(state);
+        if frame.locals.is_empty() {
+            println!("(none)");
+        }
+        let source = frame
+            .module_id
+            .as_ref()
+            .map(|module_id| sources.get(module_id));
+        for (idx, value) in frame.locals.iter().enumerate() {
+            let name = source
+                .as_ref()
+                .and_then(|source| source.local_name(frame.function_index, idx))
+                .unwrap_or_else(|| format!("l{}", idx));
+            println!("  [{}] {} = {}", idx, name, value);
+        }
+    }
+
+    fn print_listing(&self, state: &DebugState, sources: &mut SourceResolver) {
+        let frame = self.selected(state);
+        if let Some(module_id) = &frame.module_id {
+            let source = sources.get(module_id);
+            if let (Some(text), Some(line)) =
+                (source.source(), source.line(frame.function_index, frame.pc))
+            {
+                let first = line.saturating_sub(LIST_CONTEXT_LINES).max(1);
+                for (idx, text) in text
+                    .lines()
+                    .enumerate()
+                    .skip(first as usize - 1)
+                    .take((2 * LIST_CONTEXT_LINES + 1) as usize)
+                {
+                    let current = idx as u32 + 1;
+                    let marker = if current == line { '>' } else { ' ' };
+                    println!("{} {:>5} {}", marker, current, text);
+                }
+                return;
+            }
+        }
+        // Without source, only the instruction of the current frame is known
+        if self.selected_frame == 0 {
+            println!("> [{}] {:?}", frame.pc, state.instruction);
+        } else {
+            println!("No source available for {}", frame.qualified_name());
+        }
+    }
+
+    fn print_stop(&self, reason: StopReason, state: &DebugState, sources: &mut SourceResolver) {
+        let frame = state.current_frame();
+        println!(
+            "Stopped ({}) at {}: {:?}",
+            reason,
+            Self::location(frame, sources),
+            state.instruction
+        );
+    }
+}
+
+impl DebugFrontend for ConsoleFrontend {
+    fn start(&mut self, session: &mut Session) -> Result<()> {
+        println!("Move debugger, type `help` for the list of commands");
+        for breakpoint in session.breakpoints.list() {
+            println!("Breakpoint: {}", breakpoint);
+        }
+        Ok(())
+    }
+
+    fn stopped(
+        &mut self,
+        reason: StopReason,
+        state: &DebugState,
+        session: &mut Session,
+    ) -> ResumeCommand {
+        self.selected_frame = 0;
+        self.print_stop(reason, state, &mut session.sources);
+
+        let stdin = io::stdin();
+        loop {
+            print!("(move) ");
+            let _ = io::stdout().flush();
+            let mut input = String::new();
+            match stdin.lock().read_line(&mut input) {
+                // End of input, e.g., when the input is piped
+                Ok(0) => return ResumeCommand::Detach,
+                Ok(_) => (),
+                Err(err) => {
+                    println!("Error reading input: {}", err);
+                    return ResumeCommand::Detach;
+                },
+            }
+            let mut words = input.split_whitespace();
+            let Some(command) = words.next() else {
+                continue;
+            };
+            let argument = words.collect::<Vec<_>>().join(" ");
+            match command {
+                "c" | "continue" => return ResumeCommand::Continue,
+                "s" | "step" => return ResumeCommand::StepIn,
+                "n" | "next" => return ResumeCommand::StepOver,
+                "o" | "out" => return ResumeCommand::StepOut,
+                "detach" => return ResumeCommand::Detach,
+                "bt" | "backtrace" => self.print_backtrace(state, &mut session.sources),
+                "f" | "frame" => match argument.parse::<usize>() {
+                    Ok(frame) if frame < state.frames.len() => {
+                        self.selected_frame = frame;
+                        println!(
+                            "#{} {}",
+                            frame,
+                            Self::location(self.selected(state), &mut session.sources)
+                        );
+                    },
+                    _ => println!("Invalid frame: {}", argument),
+                },
+                "l" | "locals" => self.print_locals(state, &mut session.sources),
+                "st" | "stack" => {
+                    if state.operand_stack.is_empty() {
+                        println!("(empty)");
+                    }
+                    for (idx, value) in state.operand_stack.iter().enumerate().rev() {
+                        println!("  [{}] {}", idx, value);
+                    }
+                },
+                "ls" | "list" => self.print_listing(state, &mut session.sources),
+                "b" | "break" => match Breakpoint::from_str(&argument) {
+                    Ok(breakpoint) => {
+                        println!("Breakpoint: {}", breakpoint);
+                        session.breakpoints.add(breakpoint);
+                    },
+                    Err(err) => println!("{}", err),
+                },
+                "d" | "delete" => match argument
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|idx| session.breakpoints.remove(idx))
+                {
+                    Some(breakpoint) => println!("Deleted breakpoint: {}", breakpoint),
+                    None => println!("Invalid breakpoint: {}", argument),
+                },
+                "info" => {
+                    for (idx, breakpoint) in session.breakpoints.list().iter().enumerate() {
+                        println!("  [{}] {}", idx, breakpoint);
+                    }
+                    println!("Stop before aborts: {}", session.stop_on_abort);
+                },
+                "abort" => match argument.as_str() {
+                    "on" => session.stop_on_abort = true,
+                    "off" => session.stop_on_abort = false,
+                    _ => println!("Expected `abort on` or `abort off`"),
+                },
+                "h" | "help" => println!("{}", HELP),
+                _ => println!(
+                    "Unknown command: {}, type `help` for the list of commands",
+                    command
+                ),
+            }
+        }
+    }
+
+    fn finished(&mut self, status: &str) {
+        println!("Execution finished: {}", status);
+    }
+}
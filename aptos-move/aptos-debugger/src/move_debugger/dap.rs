@@ -0,0 +1,443 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A minimal Debug Adapter Protocol (https://microsoft.github.io/debug-adapter-protocol/)
+//! server, so that editors can attach to the debugger. There is a single thread, the
+//! transaction, and sources are served by reference from the package metadata on chain.
+
+use crate::move_debugger::{
+    Breakpoint, DebugFrontend, ResumeCommand, Session, SourceResolver, StopReason,
+};
+use anyhow::{bail, format_err, Result};
+use move_vm_runtime::DebugState;
+use serde_json::{json, Value};
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::Path,
+    str::FromStr,
+};
+
+const THREAD_ID: u64 = 1;
+// Variable references of the frames are their ids, the operand stack comes after them
+const OPERAND_STACK_REFERENCE: u64 = 1_000_000;
+const ABORT_FILTER: &str = "abort";
+
+enum Handled {
+    Pending,
+    Resume(ResumeCommand),
+    ConfigurationDone,
+    Disconnect,
+}
+
+/// Serves a single client over TCP
+pub struct DapFrontend {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    seq: u64,
+    disconnected: bool,
+}
+
+impl DapFrontend {
+    /// Waits for a client to connect on the port
+    pub fn listen(port: u16) -> Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        println!(
+            "Waiting for a debug adapter client on {}",
+            listener.local_addr()?
+        );
+        let (stream, address) = listener.accept()?;
+        println!("Debug adapter client connected from {}", address);
+        Ok(Self {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+            seq: 0,
+            disconnected: false,
+        })
+    }
+
+    fn read_message(&mut self) -> Result<Value> {
+        let mut content_length = None;
+        loop {
+            let mut header = String::new();
+            if self.reader.read_line(&mut header)? == 0 {
+                bail!("The client disconnected");
+            }
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some(length) = header.strip_prefix("Content-Length:") {
+                content_length = Some(length.trim().parse::<usize>()?);
+            }
+        }
+        let content_length =
+            content_length.ok_or_else(|| format_err!("Missing Content-Length header"))?;
+        let mut content = vec![0; content_length];
+        self.reader.read_exact(&mut content)?;
+        Ok(serde_json::from_slice(&content)?)
+    }
+
+    fn send(&mut self, mut message: Value) {
+        self.seq += 1;
+        message["seq"] = json!(self.seq);
+        let content = message.to_string();
+        let result = write!(
+            self.writer,
+            "Content-Length: {}\r\n\r\n{}",
+            content.len(),
+            content
+        )
+        .and_then(|_| self.writer.flush());
+        if result.is_err() {
+            self.disconnected = true;
+        }
+    }
+
+    fn respond(&mut self, request: &Value, body: Result<Value>) {
+        let mut response = json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+        });
+        match body {
+            Ok(body) => {
+                response["success"] = json!(true);
+                response["body"] = body;
+            },
+            Err(err) => {
+                response["success"] = json!(false);
+                response["message"] = json!(err.to_string());
+            },
+        }
+        self.send(response);
+    }
+
+    fn event(&mut self, event: &str, body: Value) {
+        self.send(json!({
+            "type": "event",
+            "event": event,
+            "body": body,
+        }));
+    }
+
+    /// Handles a request. The state is only available while stopped.
+    fn handle(
+        &mut self,
+        request: &Value,
+        state: Option<&DebugState>,
+        session: &mut Session,
+    ) -> Handled {
+        let arguments = &request["arguments"];
+        let command = request["command"].as_str().unwrap_or_default();
+        let mut handled = Handled::Pending;
+        let body = match command {
+            "initialize" => Ok(json!({
+                "supportsConfigurationDoneRequest": true,
+                "supportsFunctionBreakpoints": true,
+                "exceptionBreakpointFilters": [{
+                    "filter": ABORT_FILTER,
+                    "label": "Move aborts",
+                    "default": session.stop_on_abort,
+                }],
+            })),
+            "launch" | "attach" => Ok(json!({})),
+            "configurationDone" => {
+                handled = Handled::ConfigurationDone;
+                Ok(json!({}))
+            },
+            "setBreakpoints" => set_breakpoints(arguments, session),
+            "setFunctionBreakpoints" => set_function_breakpoints(arguments, session),
+            "setExceptionBreakpoints" => {
+                session.stop_on_abort = arguments["filters"].as_array().map_or(false, |filters| {
+                    filters
+                        .iter()
+                        .any(|filter| filter.as_str() == Some(ABORT_FILTER))
+                });
+                Ok(json!({}))
+            },
+            "threads" => Ok(json!({
+                "threads": [{ "id": THREAD_ID, "name": "transaction" }],
+            })),
+            "stackTrace" => state
+                .ok_or_else(|| format_err!("Not stopped"))
+                .map(|state| stack_trace(state, &mut session.sources)),
+            "scopes" => state
+                .ok_or_else(|| format_err!("Not stopped"))
+                .and_then(|state| scopes(arguments, state)),
+            "variables" => state
+                .ok_or_else(|| format_err!("Not stopped"))
+                .and_then(|state| variables(arguments, state, &mut session.sources)),
+            "source" => arguments["sourceReference"]
+                .as_u64()
+                .and_then(|reference| session.sources.get_by_reference(reference as usize))
+                .and_then(|source| source.source().map(str::to_string))
+                .map(|content| json!({ "content": content }))
+                .ok_or_else(|| format_err!("Source not available")),
+            "continue" => {
+                handled = Handled::Resume(ResumeCommand::Continue);
+                Ok(json!({ "allThreadsContinued": true }))
+            },
+            "next" => {
+                handled = Handled::Resume(ResumeCommand::StepOver);
+                Ok(json!({}))
+            },
+            "stepIn" => {
+                handled = Handled::Resume(ResumeCommand::StepIn);
+                Ok(json!({}))
+            },
+            "stepOut" => {
+                handled = Handled::Resume(ResumeCommand::StepOut);
+                Ok(json!({}))
+            },
+            "disconnect" | "terminate" => {
+                handled = Handled::Disconnect;
+                Ok(json!({}))
+            },
+            // Execution only runs between stops, so there is nothing to pause
+            "pause" => Ok(json!({})),
+            _ => Err(format_err!("Unsupported request: {}", command)),
+        };
+        self.respond(request, body);
+        if command == "initialize" {
+            self.event("initialized", json!({}));
+        }
+        handled
+    }
+}
+
+fn set_breakpoints(arguments: &Value, session: &mut Session) -> Result<Value> {
+    let source = &arguments["source"];
+    // Sources served by the debugger are referenced, local files are matched by name
+    let source_name = match source["sourceReference"].as_u64().filter(|r| *r > 0) {
+        Some(reference) => session
+            .sources
+            .get_by_reference(reference as usize)
+            .map(|source| source.name()),
+        None => source["path"]
+            .as_str()
+            .and_then(|path| Path::new(path).file_name())
+            .and_then(|name| name.to_str())
+            .or_else(|| source["name"].as_str())
+            .map(str::to_string),
+    }
+    .ok_or_else(|| format_err!("Unknown source"))?;
+
+    let lines: Vec<u32> = arguments["breakpoints"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|breakpoint| breakpoint["line"].as_u64())
+        .map(|line| line as u32)
+        .collect();
+    session.breakpoints.replace(
+        |breakpoint| {
+            matches!(breakpoint, Breakpoint::Line { source_name: name, .. } if *name == source_name)
+        },
+        lines.iter().map(|line| Breakpoint::Line {
+            source_name: source_name.clone(),
+            line: *line,
+        }),
+    );
+    Ok(json!({
+        "breakpoints": lines
+            .iter()
+            .map(|line| json!({ "verified": true, "line": line }))
+            .collect::<Vec<_>>(),
+    }))
+}
+
+fn set_function_breakpoints(arguments: &Value, session: &mut Session) -> Result<Value> {
+    let breakpoints: Vec<_> = arguments["breakpoints"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|breakpoint| {
+            breakpoint["name"]
+                .as_str()
+                .ok_or_else(|| format_err!("Missing function name"))
+                .and_then(Breakpoint::from_str)
+        })
+        .collect();
+    session.breakpoints.replace(
+        |breakpoint| !matches!(breakpoint, Breakpoint::Line { .. }),
+        breakpoints
+            .iter()
+            .filter_map(|breakpoint| breakpoint.as_ref().ok())
+            .cloned(),
+    );
+    Ok(json!({
+        "breakpoints": breakpoints
+            .iter()
+            .map(|breakpoint| match breakpoint {
+                Ok(_) => json!({ "verified": true }),
+                Err(err) => json!({ "verified": false, "message": err.to_string() }),
+            })
+            .collect::<Vec<_>>(),
+    }))
+}
+
+// Frame ids start at 1 for the outermost frame
+fn frame_index(state: &DebugState, frame_id: Option<u64>) -> Result<usize> {
+    frame_id
+        .and_then(|id| (id as usize).checked_sub(1))
+        .filter(|idx| *idx < state.frames.len())
+        .ok_or_else(|| format_err!("Invalid frame"))
+}
+
+fn stack_trace(state: &DebugState, sources: &mut SourceResolver) -> Value {
+    let frames: Vec<_> = state
+        .frames
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(idx, frame)| {
+            let mut stack_frame = json!({
+                "id": idx + 1,
+                "name": format!("{} @ {}", frame.qualified_name(), frame.pc),
+                "line": 0,
+                "column": 0,
+                "instructionPointerReference": frame.pc.to_string(),
+            });
+            if let Some(module_id) = &frame.module_id {
+                let reference = sources.source_reference(module_id);
+                let source = sources.get(module_id);
+                if let Some(line) = source.line(frame.function_index, frame.pc) {
+                    stack_frame["source"] = json!({
+                        "name": source.name(),
+                        "sourceReference": reference,
+                    });
+                    stack_frame["line"] = json!(line);
+                    stack_frame["column"] = json!(1);
+                }
+            }
+            stack_frame
+        })
+        .collect();
+    json!({ "stackFrames": frames, "totalFrames": frames.len() })
+}
+
+fn scopes(arguments: &Value, state: &DebugState) -> Result<Value> {
+    let idx = frame_index(state, arguments["frameId"].as_u64())?;
+    let mut scopes = vec![json!({
+        "name": "Locals",
+        "variablesReference": idx + 1,
+        "expensive": false,
+    })];
+    // The operand stack belongs to the current frame
+    if idx == state.frames.len() - 1 {
+        scopes.push(json!({
+            "name": "Operand Stack",
+            "variablesReference": OPERAND_STACK_REFERENCE,
+            "expensive": false,
+        }));
+    }
+    Ok(json!({ "scopes": scopes }))
+}
+
+fn variables(arguments: &Value, state: &DebugState, sources: &mut SourceResolver) -> Result<Value> {
+    let reference = arguments["variablesReference"].as_u64();
+    let variables: Vec<_> = if reference == Some(OPERAND_STACK_REFERENCE) {
+        state
+            .operand_stack
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(idx, value)| {
+                json!({ "name": format!("[{}]", idx), "value": value, "variablesReference": 0 })
+            })
+            .collect()
+    } else {
+        let frame = &state.frames[frame_index(state, reference)?];
+        let source = frame
+            .module_id
+            .as_ref()
+            .map(|module_id| sources.get(module_id));
+        frame
+            .locals
+            .iter()
+            .enumerate()
+            .map(|(idx, value)| {
+                let name = source
+                    .as_ref()
+                    .and_then(|source| source.local_name(frame.function_index, idx))
+                    .unwrap_or_else(|| format!("l{}", idx));
+                json!({ "name": name, "value": value, "variablesReference": 0 })
+            })
+            .collect()
+    };
+    Ok(json!({ "variables": variables }))
+}
+
+impl DebugFrontend for DapFrontend {
+    fn start(&mut self, session: &mut Session) -> Result<()> {
+        loop {
+            let request = self.read_message()?;
+            match self.handle(&request, None, session) {
+                Handled::ConfigurationDone => return Ok(()),
+                Handled::Disconnect => bail!("The client disconnected"),
+                Handled::Pending | Handled::Resume(_) => (),
+            }
+        }
+    }
+
+    fn stopped(
+        &mut self,
+        reason: StopReason,
+        state: &DebugState,
+        session: &mut Session,
+    ) -> ResumeCommand {
+        let (reason, text) = match reason {
+            StopReason::Breakpoint => ("breakpoint", None),
+            StopReason::Step => ("step", None),
+            StopReason::Abort => ("exception", Some(format!("{:?}", state.instruction))),
+        };
+        self.event(
+            "stopped",
+            json!({
+                "reason": reason,
+                "text": text,
+                "threadId": THREAD_ID,
+                "allThreadsStopped": true,
+            }),
+        );
+
+        while !self.disconnected {
+            let Ok(request) = self.read_message() else {
+                break;
+            };
+            match self.handle(&request, Some(state), session) {
+                Handled::Resume(command) => return command,
+                Handled::Disconnect => break,
+                Handled::Pending | Handled::ConfigurationDone => (),
+            }
+        }
+        self.disconnected = true;
+        ResumeCommand::Detach
+    }
+
+    fn finished(&mut self, status: &str) {
+        println!("Execution finished: {}", status);
+        if self.disconnected {
+            return;
+        }
+        self.event(
+            "output",
+            json!({
+                "category": "console",
+                "output": format!("Execution finished: {}\n", status),
+            }),
+        );
+        self.event("terminated", json!({}));
+        self.event("exited", json!({ "exitCode": 0 }));
+        // Serve the remaining requests until the client disconnects
+        while let Ok(request) = self.read_message() {
+            let command = request["command"].as_str().unwrap_or_default();
+            if command == "disconnect" || command == "terminate" {
+                self.respond(&request, Ok(json!({})));
+                break;
+            }
+            self.respond(&request, Err(format_err!("The transaction was executed")));
+        }
+    }
+}
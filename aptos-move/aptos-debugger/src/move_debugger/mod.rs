@@ -0,0 +1,490 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Interactive debugging of the Move code executed by a replayed transaction: breakpoints on
+//! function entry, bytecode offsets and source lines, stepping in, over and out of calls, and
+//! inspection of the locals and the operand stack. The sources are loaded from the package
+//! metadata stored on chain, if the packages were published with them.
+
+mod console;
+mod dap;
+mod sources;
+
+use anyhow::{format_err, Result};
+use aptos_infallible::Mutex;
+use aptos_types::move_utils::MemberId;
+pub use console::ConsoleFrontend;
+pub use dap::DapFrontend;
+use move_binary_format::file_format::{Bytecode, FunctionDefinitionIndex};
+use move_core_types::language_storage::ModuleId;
+use move_vm_runtime::{tracing::set_interpreter_debugger, DebugLocation, DebugState};
+pub use sources::{ModuleSource, PackageRegistryFetcher, SourceResolver};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    str::FromStr,
+    sync::Arc,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Stops on entry of the function
+    Function {
+        module_id: ModuleId,
+        function: String,
+    },
+    /// Stops before the execution of the instruction at the offset of the function
+    Offset {
+        module_id: ModuleId,
+        function: String,
+        offset: u16,
+    },
+    /// Stops on the first instruction of the line, in the modules whose source has the name
+    /// (e.g., `coin.move`)
+    Line { source_name: String, line: u32 },
+}
+
+impl FromStr for Breakpoint {
+    type Err = anyhow::Error;
+
+    /// Parses `<address>::<module>::<function>[+<offset>]` or `<source name>:<line>`
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if let Some((source_name, line)) =
+            s.rsplit_once(':').filter(|(name, _)| !name.ends_with(':'))
+        {
+            return Ok(Breakpoint::Line {
+                source_name: source_name.to_string(),
+                line: line
+                    .parse()
+                    .map_err(|_| format_err!("Invalid line in breakpoint: {}", s))?,
+            });
+        }
+
+        let (function, offset) = match s.split_once('+') {
+            Some((function, offset)) => (
+                function,
+                Some(
+                    offset
+                        .parse()
+                        .map_err(|_| format_err!("Invalid offset in breakpoint: {}", s))?,
+                ),
+            ),
+            None => (s, None),
+        };
+        let MemberId {
+            module_id,
+            member_id,
+        } = MemberId::from_str(function).map_err(|_| {
+            format_err!(
+                "Invalid breakpoint: {}, expected <address>::<module>::<function>[+<offset>] or \
+                 <file>.move:<line>",
+                s
+            )
+        })?;
+        let function = member_id.to_string();
+        Ok(match offset {
+            Some(offset) => Breakpoint::Offset {
+                module_id,
+                function,
+                offset,
+            },
+            None => Breakpoint::Function {
+                module_id,
+                function,
+            },
+        })
+    }
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Breakpoint::Function {
+                module_id,
+                function,
+            } => write!(f, "{}::{}", module_id.short_str_lossless(), function),
+            Breakpoint::Offset {
+                module_id,
+                function,
+                offset,
+            } => write!(
+                f,
+                "{}::{}+{}",
+                module_id.short_str_lossless(),
+                function,
+                offset
+            ),
+            Breakpoint::Line { source_name, line } => write!(f, "{}:{}", source_name, line),
+        }
+    }
+}
+
+/// The breakpoints of a debugging session
+#[derive(Default)]
+pub struct Breakpoints {
+    breakpoints: Vec<Breakpoint>,
+    // The instructions of the line breakpoints, resolved when the modules are first executed
+    line_offsets: HashMap<ModuleId, HashSet<(FunctionDefinitionIndex, u16)>>,
+}
+
+impl Breakpoints {
+    pub fn new(breakpoints: Vec<Breakpoint>) -> Self {
+        Self {
+            breakpoints,
+            line_offsets: HashMap::new(),
+        }
+    }
+
+    pub fn list(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+
+    pub fn add(&mut self, breakpoint: Breakpoint) {
+        if !self.breakpoints.contains(&breakpoint) {
+            self.breakpoints.push(breakpoint);
+            self.line_offsets.clear();
+        }
+    }
+
+    pub fn remove(&mut self, idx: usize) -> Option<Breakpoint> {
+        (idx < self.breakpoints.len()).then(|| {
+            self.line_offsets.clear();
+            self.breakpoints.remove(idx)
+        })
+    }
+
+    /// Replaces the breakpoints matching the predicate
+    pub fn replace(
+        &mut self,
+        predicate: impl Fn(&Breakpoint) -> bool,
+        breakpoints: impl IntoIterator<Item = Breakpoint>,
+    ) {
+        self.breakpoints.retain(|breakpoint| !predicate(breakpoint));
+        for breakpoint in breakpoints {
+            self.add(breakpoint);
+        }
+        self.line_offsets.clear();
+    }
+
+    fn hit(&mut self, location: &DebugLocation, sources: &mut SourceResolver) -> bool {
+        let Some(module_id) = location.module_id else {
+            return false;
+        };
+        let mut has_line_breakpoints = false;
+        for breakpoint in &self.breakpoints {
+            match breakpoint {
+                Breakpoint::Function {
+                    module_id: bp_module_id,
+                    function,
+                } => {
+                    if location.is_entry
+                        && function == location.function_name
+                        && bp_module_id == module_id
+                    {
+                        return true;
+                    }
+                },
+                Breakpoint::Offset {
+                    module_id: bp_module_id,
+                    function,
+                    offset,
+                } => {
+                    if location.pc == *offset
+                        && function == location.function_name
+                        && bp_module_id == module_id
+                    {
+                        return true;
+                    }
+                },
+                Breakpoint::Line { .. } => has_line_breakpoints = true,
+            }
+        }
+        if !has_line_breakpoints {
+            return false;
+        }
+
+        if !self.line_offsets.contains_key(module_id) {
+            let source = sources.get(module_id);
+            let source_name = source.name();
+            let offsets = self
+                .breakpoints
+                .iter()
+                .filter_map(|breakpoint| match breakpoint {
+                    Breakpoint::Line {
+                        source_name: bp_source_name,
+                        line,
+                    } if *bp_source_name == source_name => Some(*line),
+                    _ => None,
+                })
+                .flat_map(|line| source.line_offsets(line))
+                .collect();
+            self.line_offsets.insert(module_id.clone(), offsets);
+        }
+        self.line_offsets[module_id].contains(&(location.function_index, location.pc))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StopReason {
+    Breakpoint,
+    Step,
+    /// Before the execution of an `abort` instruction
+    Abort,
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StopReason::Breakpoint => write!(f, "breakpoint"),
+            StopReason::Step => write!(f, "step"),
+            StopReason::Abort => write!(f, "abort"),
+        }
+    }
+}
+
+/// How to resume execution after a stop
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResumeCommand {
+    /// Runs until the next breakpoint
+    Continue,
+    /// Stops at the next instruction, including in called functions
+    StepIn,
+    /// Stops at the next instruction of the current function (or of its caller, once it
+    /// returns)
+    StepOver,
+    /// Stops once the current function returns
+    StepOut,
+    /// Runs until the end without stopping
+    Detach,
+}
+
+/// The state of a debugging session that frontends can inspect and change
+pub struct Session {
+    pub breakpoints: Breakpoints,
+    pub sources: SourceResolver,
+    pub stop_on_abort: bool,
+}
+
+/// The user interface of the debugger
+pub trait DebugFrontend: Send {
+    /// Called before the execution of the transaction, e.g., to set up breakpoints
+    fn start(&mut self, session: &mut Session) -> Result<()>;
+
+    /// Called when execution stops, blocks until execution should resume
+    fn stopped(
+        &mut self,
+        reason: StopReason,
+        state: &DebugState,
+        session: &mut Session,
+    ) -> ResumeCommand;
+
+    /// Called once the transaction is executed, with its status
+    fn finished(&mut self, status: &str);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum StepMode {
+    Run,
+    StepIn,
+    /// Stops at the next instruction at the depth or above
+    StepOver(usize),
+    /// Stops at the next instruction above the depth
+    StepOut(usize),
+}
+
+impl StepMode {
+    fn should_stop(&self, depth: usize) -> bool {
+        match self {
+            StepMode::Run => false,
+            StepMode::StepIn => true,
+            StepMode::StepOver(step_depth) => depth <= *step_depth,
+            StepMode::StepOut(step_depth) => depth < *step_depth,
+        }
+    }
+}
+
+struct DebuggerState {
+    session: Session,
+    frontend: Box<dyn DebugFrontend>,
+    step_mode: StepMode,
+    detached: bool,
+    stop_reason: Option<StopReason>,
+}
+
+impl DebuggerState {
+    fn should_stop(&mut self, location: &DebugLocation) -> bool {
+        if self.detached {
+            return false;
+        }
+        self.stop_reason =
+            if self.session.stop_on_abort && matches!(location.instruction, Bytecode::Abort) {
+                Some(StopReason::Abort)
+            } else if self
+                .session
+                .breakpoints
+                .hit(location, &mut self.session.sources)
+            {
+                Some(StopReason::Breakpoint)
+            } else if self.step_mode.should_stop(location.depth) {
+                Some(StopReason::Step)
+            } else {
+                None
+            };
+        self.stop_reason.is_some()
+    }
+
+    fn on_stop(&mut self, state: DebugState) {
+        let reason = self.stop_reason.take().unwrap_or(StopReason::Step);
+        let depth = state.frames.len();
+        self.step_mode = match self.frontend.stopped(reason, &state, &mut self.session) {
+            ResumeCommand::Continue => StepMode::Run,
+            ResumeCommand::StepIn => StepMode::StepIn,
+            ResumeCommand::StepOver => StepMode::StepOver(depth),
+            ResumeCommand::StepOut => StepMode::StepOut(depth),
+            ResumeCommand::Detach => {
+                self.detached = true;
+                StepMode::Run
+            },
+        };
+    }
+}
+
+// The handle installed in the VM, the state is shared with the `MoveDebugger`
+struct InterpreterDebuggerHandle(Arc<Mutex<DebuggerState>>);
+
+impl move_vm_runtime::InterpreterDebugger for InterpreterDebuggerHandle {
+    fn should_stop(&mut self, location: &DebugLocation) -> bool {
+        self.0.lock().should_stop(location)
+    }
+
+    fn on_stop(&mut self, state: DebugState) {
+        self.0.lock().on_stop(state)
+    }
+}
+
+/// Drives the frontend while the Move VM executes a transaction
+pub struct MoveDebugger {
+    state: Arc<Mutex<DebuggerState>>,
+}
+
+impl MoveDebugger {
+    pub fn new(frontend: Box<dyn DebugFrontend>, session: Session) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(DebuggerState {
+                session,
+                frontend,
+                step_mode: StepMode::Run,
+                detached: false,
+                stop_reason: None,
+            })),
+        }
+    }
+
+    /// Runs `execute` with the debugger installed in the Move VM, and reports the status it
+    /// returns to the frontend. The VM must run on a single thread, as the debugger is global.
+    pub fn run<T>(&self, execute: impl FnOnce() -> T, status: impl Fn(&T) -> String) -> Result<T> {
+        {
+            let mut state = self.state.lock();
+            let DebuggerState {
+                session, frontend, ..
+            } = &mut *state;
+            frontend.start(session)?;
+        }
+
+        set_interpreter_debugger(Some(Box::new(InterpreterDebuggerHandle(
+            self.state.clone(),
+        ))));
+        let result = execute();
+        set_interpreter_debugger(None);
+
+        self.state.lock().frontend.finished(&status(&result));
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::{account_address::AccountAddress, identifier::Identifier};
+
+    fn coin_module_id() -> ModuleId {
+        ModuleId::new(AccountAddress::ONE, Identifier::new("coin").unwrap())
+    }
+
+    #[test]
+    fn test_parse_breakpoints() {
+        assert_eq!(
+            Breakpoint::from_str("0x1::coin::transfer").unwrap(),
+            Breakpoint::Function {
+                module_id: coin_module_id(),
+                function: "transfer".to_string(),
+            }
+        );
+        assert_eq!(
+            Breakpoint::from_str("0x1::coin::transfer+12").unwrap(),
+            Breakpoint::Offset {
+                module_id: coin_module_id(),
+                function: "transfer".to_string(),
+                offset: 12,
+            }
+        );
+        assert_eq!(
+            Breakpoint::from_str("coin.move:42").unwrap(),
+            Breakpoint::Line {
+                source_name: "coin.move".to_string(),
+                line: 42,
+            }
+        );
+        assert!(Breakpoint::from_str("transfer").is_err());
+        assert!(Breakpoint::from_str("coin.move:x").is_err());
+        assert!(Breakpoint::from_str("0x1::coin::transfer+x").is_err());
+
+        for breakpoint in [
+            "0x1::coin::transfer",
+            "0x1::coin::transfer+12",
+            "coin.move:42",
+        ] {
+            assert_eq!(
+                Breakpoint::from_str(breakpoint).unwrap().to_string(),
+                breakpoint
+            );
+        }
+    }
+
+    #[test]
+    fn test_function_breakpoints_hit_on_entry() {
+        let module_id = coin_module_id();
+        let mut breakpoints =
+            Breakpoints::new(vec![Breakpoint::from_str("0x1::coin::transfer").unwrap()]);
+        let mut sources = SourceResolver::new(Box::new(|_| Ok(None)));
+        let location = |function_name, pc, is_entry| DebugLocation {
+            module_id: Some(&module_id),
+            function_name,
+            function_index: FunctionDefinitionIndex(0),
+            pc,
+            instruction: &Bytecode::Nop,
+            depth: 1,
+            is_entry,
+        };
+
+        assert!(breakpoints.hit(&location("transfer", 0, true), &mut sources));
+        assert!(!breakpoints.hit(&location("withdraw", 0, true), &mut sources));
+        // Branching back to the first instruction (e.g., in a loop) is not an entry
+        assert!(!breakpoints.hit(&location("transfer", 0, false), &mut sources));
+        assert!(!breakpoints.hit(&location("transfer", 3, false), &mut sources));
+    }
+
+    #[test]
+    fn test_step_modes() {
+        assert!(!StepMode::Run.should_stop(1));
+        assert!(StepMode::StepIn.should_stop(3));
+        // Stepping over a call does not stop in the callee, but stops in the caller once the
+        // current function returns
+        assert!(!StepMode::StepOver(2).should_stop(3));
+        assert!(StepMode::StepOver(2).should_stop(2));
+        assert!(StepMode::StepOver(2).should_stop(1));
+        assert!(!StepMode::StepOut(2).should_stop(2));
+        assert!(StepMode::StepOut(2).should_stop(1));
+    }
+}
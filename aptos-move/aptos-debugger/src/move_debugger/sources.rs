@@ -0,0 +1,227 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use aptos_framework::{natives::code::PackageRegistry, unzip_metadata, unzip_metadata_str};
+use move_binary_format::file_format::FunctionDefinitionIndex;
+use move_bytecode_source_map::source_map::SourceMap;
+use move_core_types::{account_address::AccountAddress, language_storage::ModuleId};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+/// Fetches the package registry of an account, if it has one
+pub type PackageRegistryFetcher =
+    Box<dyn Fn(AccountAddress) -> Result<Option<PackageRegistry>> + Send>;
+
+/// The source of a module, from the package metadata stored on chain. Packages can be
+/// published without sources or source maps, in which case only the bytecode is available.
+pub struct ModuleSource {
+    module_id: ModuleId,
+    source: Option<String>,
+    source_map: Option<SourceMap>,
+    // Byte offsets of the start of each line of the source
+    line_starts: Vec<u32>,
+}
+
+impl ModuleSource {
+    pub fn new(module_id: ModuleId, source: Option<String>, source_map: Option<SourceMap>) -> Self {
+        let line_starts = source
+            .as_ref()
+            .map(|source| {
+                std::iter::once(0)
+                    .chain(
+                        source
+                            .match_indices('\n')
+                            .map(|(offset, _)| offset as u32 + 1),
+                    )
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            module_id,
+            source,
+            source_map,
+            line_starts,
+        }
+    }
+
+    pub fn module_id(&self) -> &ModuleId {
+        &self.module_id
+    }
+
+    /// The name of the source file, by which line breakpoints refer to the module
+    pub fn name(&self) -> String {
+        format!("{}.move", self.module_id.name())
+    }
+
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// The line (starting at 1) of the instruction at the offset of the function, if both the
+    /// source and the source map are available
+    pub fn line(&self, function_index: FunctionDefinitionIndex, offset: u16) -> Option<u32> {
+        let location = self
+            .source_map
+            .as_ref()?
+            .get_code_location(function_index, offset)
+            .ok()?;
+        self.line_of(location.start())
+    }
+
+    /// The name of the parameter or local of the function, if the source map is available
+    pub fn local_name(
+        &self,
+        function_index: FunctionDefinitionIndex,
+        idx: usize,
+    ) -> Option<String> {
+        self.source_map
+            .as_ref()?
+            .get_parameter_or_local_name(function_index, idx as u64)
+            .ok()
+            .map(|(name, _)| name)
+    }
+
+    /// The instructions on which execution stops for a breakpoint on the line, i.e., the first
+    /// instruction of every run of instructions of the line
+    pub fn line_offsets(&self, line: u32) -> Vec<(FunctionDefinitionIndex, u16)> {
+        let Some(source_map) = &self.source_map else {
+            return vec![];
+        };
+        if self.source.is_none() {
+            return vec![];
+        }
+
+        let mut offsets = vec![];
+        // Function definitions are indexed densely
+        for idx in 0.. {
+            let function_index = FunctionDefinitionIndex(idx);
+            let Ok(function_map) = source_map.get_function_source_map(function_index) else {
+                break;
+            };
+            let mut previous_line = None;
+            for (offset, location) in &function_map.code_map {
+                let current_line = self.line_of(location.start());
+                if current_line == Some(line) && previous_line != current_line {
+                    offsets.push((function_index, *offset));
+                }
+                previous_line = current_line;
+            }
+        }
+        offsets
+    }
+
+    fn line_of(&self, byte_offset: u32) -> Option<u32> {
+        if self.line_starts.is_empty() {
+            return None;
+        }
+        let line = match self.line_starts.binary_search(&byte_offset) {
+            Ok(idx) => idx,
+            Err(idx) => idx - 1,
+        };
+        Some(line as u32 + 1)
+    }
+}
+
+/// Loads the sources of modules from the package registries of their accounts, on demand
+pub struct SourceResolver {
+    fetch_registry: PackageRegistryFetcher,
+    registries: HashMap<AccountAddress, Option<PackageRegistry>>,
+    // Indexed by source reference - 1
+    sources: Vec<Arc<ModuleSource>>,
+    source_references: BTreeMap<ModuleId, usize>,
+}
+
+impl SourceResolver {
+    pub fn new(fetch_registry: PackageRegistryFetcher) -> Self {
+        Self {
+            fetch_registry,
+            registries: HashMap::new(),
+            sources: vec![],
+            source_references: BTreeMap::new(),
+        }
+    }
+
+    /// Adds the source of a module, e.g., of a local package, which takes precedence over the
+    /// source in the package registry of its account.
+    pub fn insert(&mut self, source: ModuleSource) {
+        let module_id = source.module_id().clone();
+        match self.source_references.get(&module_id) {
+            Some(reference) => self.sources[reference - 1] = Arc::new(source),
+            None => {
+                self.sources.push(Arc::new(source));
+                self.source_references.insert(module_id, self.sources.len());
+            },
+        }
+    }
+
+    /// Returns the source of the module, loading it if needed. Modules of accounts without a
+    /// package registry get an empty source.
+    pub fn get(&mut self, module_id: &ModuleId) -> Arc<ModuleSource> {
+        let reference = self.source_reference(module_id);
+        self.sources[reference - 1].clone()
+    }
+
+    /// Returns a non-zero identifier of the source of the module, loading it if needed
+    pub fn source_reference(&mut self, module_id: &ModuleId) -> usize {
+        if let Some(reference) = self.source_references.get(module_id) {
+            return *reference;
+        }
+        let source = Arc::new(self.load(module_id));
+        self.sources.push(source);
+        let reference = self.sources.len();
+        self.source_references.insert(module_id.clone(), reference);
+        reference
+    }
+
+    pub fn get_by_reference(&self, reference: usize) -> Option<Arc<ModuleSource>> {
+        reference
+            .checked_sub(1)
+            .and_then(|idx| self.sources.get(idx))
+            .cloned()
+    }
+
+    /// The sources loaded so far
+    pub fn loaded(&self) -> &[Arc<ModuleSource>] {
+        &self.sources
+    }
+
+    fn load(&mut self, module_id: &ModuleId) -> ModuleSource {
+        let address = *module_id.address();
+        let registry = self.registries.entry(address).or_insert_with(|| {
+            (self.fetch_registry)(address).unwrap_or_else(|err| {
+                eprintln!(
+                    "Failed to fetch the package registry of {}: {}",
+                    address, err
+                );
+                None
+            })
+        });
+        let Some(module_metadata) = registry
+            .iter()
+            .flat_map(|registry| &registry.packages)
+            .find_map(|package| {
+                package
+                    .modules
+                    .iter()
+                    .find(|module| module.name == module_id.name().as_str())
+            })
+        else {
+            return ModuleSource::new(module_id.clone(), None, None);
+        };
+
+        let source = (!module_metadata.source.is_empty())
+            .then(|| unzip_metadata_str(&module_metadata.source).ok())
+            .flatten();
+        let source_map = (!module_metadata.source_map.is_empty())
+            .then(|| {
+                unzip_metadata(&module_metadata.source_map)
+                    .ok()
+                    .and_then(|bytes| bcs::from_bytes(&bytes).ok())
+            })
+            .flatten();
+        ModuleSource::new(module_id.clone(), source, source_map)
+    }
+}
@@ -0,0 +1,407 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Profiling of the Move code executed by the VM. The wall time spent and the number of
+//! instructions executed are attributed to the call stacks of Move functions they were executed
+//! with, and to the source lines of the instructions if the sources are available. Unlike gas,
+//! the wall time reflects the actual cost of the natives, which is attributed to the lines
+//! calling them. Profiles are written as collapsed stacks, which flamegraph tools render.
+
+use crate::move_debugger::SourceResolver;
+use anyhow::Result;
+use aptos_infallible::Mutex;
+use move_binary_format::file_format::FunctionDefinitionIndex;
+use move_core_types::language_storage::ModuleId;
+use move_vm_runtime::{tracing::set_interpreter_profiler, DebugLocation, InterpreterProfiler};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Function {
+    /// `None` for scripts
+    module_id: Option<ModuleId>,
+    name: String,
+    index: FunctionDefinitionIndex,
+}
+
+impl Function {
+    fn qualified_name(&self) -> String {
+        match &self.module_id {
+            Some(module_id) => format!(
+                "0x{}::{}::{}",
+                module_id.address().short_str_lossless(),
+                module_id.name(),
+                self.name
+            ),
+            None => format!("script::{}", self.name),
+        }
+    }
+}
+
+// A frame of a call stack: the index of the function in the profile, and the offset of the
+// instruction it executes. For the callers, this is the call instruction.
+type Frame = (usize, u16);
+
+/// The cost attributed to a call stack or to a location
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Cost {
+    pub nanos: u64,
+    pub instructions: u64,
+}
+
+impl Cost {
+    fn add(&mut self, other: Cost) {
+        self.nanos += other.nanos;
+        self.instructions += other.instructions;
+    }
+}
+
+// The profile of the code a thread executed since it entered the interpreter, with its call
+// stack. It is merged into the shared profile when the thread exits the interpreter.
+#[derive(Default)]
+struct ThreadProfile {
+    functions: Vec<Function>,
+    function_indices: HashMap<(Option<ModuleId>, FunctionDefinitionIndex), usize>,
+    costs: HashMap<Vec<Frame>, Cost>,
+    stack: Vec<Frame>,
+    last_instruction: Option<Instant>,
+}
+
+impl ThreadProfile {
+    // Attributes the time since the last instruction to the stack it was executed with
+    fn record(&mut self, now: Instant) {
+        let (Some(last_instruction), false) = (self.last_instruction, self.stack.is_empty()) else {
+            return;
+        };
+        let cost = Cost {
+            nanos: now.saturating_duration_since(last_instruction).as_nanos() as u64,
+            instructions: 1,
+        };
+        match self.costs.get_mut(self.stack.as_slice()) {
+            Some(total) => total.add(cost),
+            None => {
+                self.costs.insert(self.stack.clone(), cost);
+            },
+        }
+    }
+
+    fn on_instruction(&mut self, location: &DebugLocation, now: Instant) {
+        self.record(now);
+
+        let Self {
+            functions,
+            function_indices,
+            stack,
+            ..
+        } = self;
+        if location.depth <= stack.len() {
+            // Same function, or returned to a caller
+            stack.truncate(location.depth);
+            if let Some(frame) = stack.last_mut() {
+                frame.1 = location.pc;
+            }
+        } else {
+            let key = (location.module_id.cloned(), location.function_index);
+            let function = *function_indices.entry(key).or_insert_with(|| {
+                functions.push(Function {
+                    module_id: location.module_id.cloned(),
+                    name: location.function_name.to_string(),
+                    index: location.function_index,
+                });
+                functions.len() - 1
+            });
+            // The calls entered before profiling started, if any, are attributed to the
+            // function too
+            stack.resize(location.depth, (function, location.pc));
+        }
+        // Excludes the overhead of profiling from the time of the instruction
+        self.last_instruction = Some(Instant::now());
+    }
+
+    fn on_exit(&mut self, now: Instant) {
+        self.record(now);
+    }
+}
+
+/// The profile of the Move code executed while profiling, see [profile].
+#[derive(Default)]
+pub struct ExecutionProfile {
+    functions: Vec<Function>,
+    function_indices: HashMap<(Option<ModuleId>, FunctionDefinitionIndex), usize>,
+    costs: HashMap<Vec<Frame>, Cost>,
+}
+
+impl ExecutionProfile {
+    fn merge(&mut self, thread_profile: ThreadProfile) {
+        let Self {
+            functions,
+            function_indices,
+            costs,
+        } = self;
+        let indices = thread_profile
+            .functions
+            .into_iter()
+            .map(|function| {
+                let key = (function.module_id.clone(), function.index);
+                *function_indices.entry(key).or_insert_with(|| {
+                    functions.push(function);
+                    functions.len() - 1
+                })
+            })
+            .collect::<Vec<_>>();
+        for (stack, cost) in thread_profile.costs {
+            let stack = stack
+                .into_iter()
+                .map(|(function, pc)| (indices[function], pc))
+                .collect();
+            costs.entry(stack).or_default().add(cost);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.costs.is_empty()
+    }
+
+    /// The total cost of the code executed
+    pub fn total(&self) -> Cost {
+        let mut total = Cost::default();
+        self.costs.values().for_each(|cost| total.add(*cost));
+        total
+    }
+
+    fn frame_name(&self, (function, pc): Frame, sources: &mut SourceResolver) -> String {
+        let function = &self.functions[function];
+        let line = function.module_id.as_ref().and_then(|module_id| {
+            let source = sources.get(module_id);
+            source
+                .line(function.index, pc)
+                .map(|line| format!("{}:{}", source.name(), line))
+        });
+        match line {
+            Some(line) => format!("{} ({})", function.qualified_name(), line),
+            None => format!("{}+{}", function.qualified_name(), pc),
+        }
+    }
+
+    /// The costs by call stack, with frames named after their functions and the source lines of
+    /// their instructions, or their bytecode offsets if the sources are not available. The
+    /// stacks with the same names are merged.
+    pub fn named_stacks(&self, sources: &mut SourceResolver) -> BTreeMap<Vec<String>, Cost> {
+        let mut stacks = BTreeMap::<_, Cost>::new();
+        for (stack, cost) in &self.costs {
+            let names = stack
+                .iter()
+                .map(|frame| self.frame_name(*frame, sources))
+                .collect();
+            stacks.entry(names).or_default().add(*cost);
+        }
+        stacks
+    }
+
+    /// Writes the wall time in nanoseconds and the number of instructions by call stack as
+    /// collapsed stacks, to `<name>.time.folded` and `<name>.instructions.folded` in the
+    /// directory. Returns the paths of the files.
+    pub fn write_collapsed_stacks(
+        &self,
+        dir: &Path,
+        name: &str,
+        sources: &mut SourceResolver,
+    ) -> Result<Vec<PathBuf>> {
+        let stacks = self.named_stacks(sources);
+        fs::create_dir_all(dir)?;
+        let mut paths = vec![];
+        let kinds: [(&str, fn(&Cost) -> u64); 2] = [
+            ("time", |cost| cost.nanos),
+            ("instructions", |cost| cost.instructions),
+        ];
+        for (kind, value) in kinds {
+            let path = dir.join(format!("{}.{}.folded", name, kind));
+            fs::write(&path, collapsed_stacks(&stacks, value))?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    /// A table of the locations with the most wall time spent executing their own
+    /// instructions, including the natives they call
+    pub fn hottest_locations(&self, sources: &mut SourceResolver, limit: usize) -> String {
+        let total = self.total();
+        let mut locations = HashMap::<_, Cost>::new();
+        for (stack, cost) in self.named_stacks(sources) {
+            if let Some(location) = stack.into_iter().last() {
+                locations.entry(location).or_default().add(cost);
+            }
+        }
+        let mut locations = locations.into_iter().collect::<Vec<_>>();
+        locations.sort_by(|(name1, cost1), (name2, cost2)| {
+            cost2.nanos.cmp(&cost1.nanos).then_with(|| name1.cmp(name2))
+        });
+
+        let mut table = format!(
+            "{:>10} {:>12} {:>14}  Location\n",
+            "Time", "Time (us)", "Instructions"
+        );
+        for (location, cost) in locations.into_iter().take(limit) {
+            table.push_str(&format!(
+                "{:>9.2}% {:>12} {:>14}  {}\n",
+                cost.nanos as f64 * 100.0 / total.nanos.max(1) as f64,
+                cost.nanos / 1000,
+                cost.instructions,
+                location
+            ));
+        }
+        table
+    }
+}
+
+// One line per stack, with the frames from the outermost one separated by `;`, followed by the
+// value of the stack
+fn collapsed_stacks(stacks: &BTreeMap<Vec<String>, Cost>, value: fn(&Cost) -> u64) -> String {
+    stacks
+        .iter()
+        .filter(|(_, cost)| value(cost) > 0)
+        .map(|(stack, cost)| format!("{} {}\n", stack.join(";"), value(cost)))
+        .collect()
+}
+
+static NEXT_PROFILER_ID: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    // The profile of the current thread, with the id of the profiler it belongs to
+    static THREAD_PROFILE: RefCell<Option<(u64, ThreadProfile)>> = const { RefCell::new(None) };
+}
+
+// The handle installed in the VM, the profile is shared with `profile`. The instructions are
+// recorded in the profile of the thread, which is only merged into the shared profile when the
+// thread exits the interpreter, so that no lock is taken for every instruction.
+struct InterpreterProfilerHandle {
+    id: u64,
+    profile: Arc<Mutex<ExecutionProfile>>,
+}
+
+impl InterpreterProfiler for InterpreterProfilerHandle {
+    fn on_instruction(&self, location: &DebugLocation) {
+        let now = Instant::now();
+        THREAD_PROFILE.with(|thread_profile| {
+            let mut thread_profile = thread_profile.borrow_mut();
+            match &mut *thread_profile {
+                Some((id, thread_profile)) if *id == self.id => {
+                    thread_profile.on_instruction(location, now)
+                },
+                // The thread entered the interpreter, or left a profile of a previous profiler
+                _ => {
+                    let mut profile = ThreadProfile::default();
+                    profile.on_instruction(location, now);
+                    *thread_profile = Some((self.id, profile));
+                },
+            }
+        })
+    }
+
+    fn on_exit(&self) {
+        let now = Instant::now();
+        let thread_profile =
+            THREAD_PROFILE.with(|thread_profile| thread_profile.borrow_mut().take());
+        if let Some((id, mut thread_profile)) = thread_profile {
+            if id == self.id {
+                thread_profile.on_exit(now);
+                self.profile.lock().merge(thread_profile);
+            }
+        }
+    }
+}
+
+/// Runs `execute` with a profiler installed in the Move VM, and returns the profile of the Move
+/// code it executed. The profiler is global, so the code executed concurrently by other threads
+/// is profiled too.
+pub fn profile<T>(execute: impl FnOnce() -> T) -> (T, ExecutionProfile) {
+    let profile = Arc::new(Mutex::new(ExecutionProfile::default()));
+    set_interpreter_profiler(Some(Arc::new(InterpreterProfilerHandle {
+        id: NEXT_PROFILER_ID.fetch_add(1, Ordering::Relaxed),
+        profile: profile.clone(),
+    })));
+    let result = execute();
+    set_interpreter_profiler(None);
+
+    let profile = std::mem::take(&mut *profile.lock());
+    (result, profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_binary_format::file_format::Bytecode;
+    use move_core_types::{account_address::AccountAddress, identifier::Identifier};
+    use std::time::Duration;
+
+    fn location<'a>(
+        module_id: &'a ModuleId,
+        function_name: &'a str,
+        function_index: u16,
+        pc: u16,
+        depth: usize,
+    ) -> DebugLocation<'a> {
+        DebugLocation {
+            module_id: Some(module_id),
+            function_name,
+            function_index: FunctionDefinitionIndex(function_index),
+            pc,
+            instruction: &Bytecode::Nop,
+            depth,
+            is_entry: pc == 0,
+        }
+    }
+
+    #[test]
+    fn test_attributes_instructions_to_call_stacks() {
+        let module_id = ModuleId::new(AccountAddress::ONE, Identifier::new("coin").unwrap());
+        let start = Instant::now();
+        let mut thread_profile = ThreadProfile::default();
+        // `transfer` calls `withdraw` at offset 1, which returns to offset 2
+        for (name, index, pc, depth) in [
+            ("transfer", 0, 0, 1),
+            ("transfer", 0, 1, 1),
+            ("withdraw", 1, 0, 2),
+            ("withdraw", 1, 1, 2),
+            ("transfer", 0, 2, 1),
+        ] {
+            thread_profile.on_instruction(&location(&module_id, name, index, pc, depth), start);
+        }
+        thread_profile.on_exit(start + Duration::from_secs(1));
+        let mut profile = ExecutionProfile::default();
+        profile.merge(thread_profile);
+
+        let mut sources = SourceResolver::new(Box::new(|_| Ok(None)));
+        let stacks = profile.named_stacks(&mut sources);
+        let instructions = stacks
+            .iter()
+            .map(|(stack, cost)| (stack.join(";"), cost.instructions))
+            .collect::<Vec<_>>();
+        assert_eq!(instructions, vec![
+            ("0x1::coin::transfer+0".to_string(), 1),
+            ("0x1::coin::transfer+1".to_string(), 1),
+            ("0x1::coin::transfer+1;0x1::coin::withdraw+0".to_string(), 1),
+            ("0x1::coin::transfer+1;0x1::coin::withdraw+1".to_string(), 1),
+            ("0x1::coin::transfer+2".to_string(), 1),
+        ]);
+        assert_eq!(profile.total().instructions, 5);
+        // The time until the exit is attributed to the last instruction
+        assert!(stacks[&vec!["0x1::coin::transfer+2".to_string()]].nanos > 0);
+
+        assert_eq!(
+            collapsed_stacks(&stacks, |cost| cost.instructions)
+                .lines()
+                .last(),
+            Some("0x1::coin::transfer+2 1")
+        );
+    }
+}
@@ -0,0 +1,369 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::transaction::{TransactionOutput, Version};
+use serde::Serialize;
+use std::fmt;
+
+// The bounds (in percent) of the buckets of the relative gas changes, which are the same for
+// increases and decreases
+const GAS_DELTA_BUCKET_BOUNDS: [u64; 3] = [1, 10, 50];
+
+/// How the output of a transaction changed when replayed with the overrides
+#[derive(Clone, Debug, Serialize)]
+pub struct TransactionDiff {
+    pub version: Version,
+    /// The entry function (or the kind) of the transaction
+    pub label: String,
+    pub baseline_status: String,
+    pub modified_status: String,
+    pub baseline_gas_used: u64,
+    pub modified_gas_used: u64,
+    /// The state keys written differently, or written by only one of the executions
+    pub changed_writes: Vec<String>,
+}
+
+impl TransactionDiff {
+    pub fn new(
+        version: Version,
+        label: String,
+        baseline: &TransactionOutput,
+        modified: &TransactionOutput,
+    ) -> Self {
+        let changed_writes = baseline
+            .write_set()
+            .iter()
+            .filter(|(key, op)| modified.write_set().get(key) != Some(*op))
+            .map(|(key, _)| key)
+            .chain(
+                modified
+                    .write_set()
+                    .iter()
+                    .filter(|(key, _)| baseline.write_set().get(key).is_none())
+                    .map(|(key, _)| key),
+            )
+            .map(|key| format!("{:?}", key))
+            .collect();
+        Self {
+            version,
+            label,
+            baseline_status: format!("{:?}", baseline.status()),
+            modified_status: format!("{:?}", modified.status()),
+            baseline_gas_used: baseline.gas_used(),
+            modified_gas_used: modified.gas_used(),
+            changed_writes,
+        }
+    }
+
+    pub fn status_changed(&self) -> bool {
+        self.baseline_status != self.modified_status
+    }
+
+    pub fn gas_delta(&self) -> i128 {
+        self.modified_gas_used as i128 - self.baseline_gas_used as i128
+    }
+
+    pub fn is_unchanged(&self) -> bool {
+        !self.status_changed() && self.gas_delta() == 0 && self.changed_writes.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HistogramBucket {
+    pub range: String,
+    pub count: usize,
+}
+
+/// The differences between the outputs of a range of transactions replayed with the on-chain
+/// state (the baseline) and with overrides of the state (the modified execution)
+#[derive(Clone, Debug, Serialize)]
+pub struct ReplayDiff {
+    pub begin: Version,
+    pub num_transactions: usize,
+    pub num_status_changes: usize,
+    pub num_gas_changes: usize,
+    pub num_write_set_changes: usize,
+    pub baseline_gas_used: u64,
+    pub modified_gas_used: u64,
+    /// The number of transactions per relative change of their gas usage
+    pub gas_delta_histogram: Vec<HistogramBucket>,
+    pub top_gas_increases: Vec<TransactionDiff>,
+    pub top_gas_decreases: Vec<TransactionDiff>,
+    /// The transactions whose outputs differ, in order
+    pub changed_transactions: Vec<TransactionDiff>,
+}
+
+impl ReplayDiff {
+    /// Compares the outputs of the transactions starting at version `begin`, `labels` describing
+    /// the transactions. Up to `top_n` transactions are reported for the largest gas increases
+    /// and decreases.
+    pub fn new(
+        begin: Version,
+        labels: Vec<String>,
+        baseline: &[TransactionOutput],
+        modified: &[TransactionOutput],
+        top_n: usize,
+    ) -> Self {
+        assert_eq!(baseline.len(), modified.len());
+        assert_eq!(baseline.len(), labels.len());
+
+        let mut histogram = vec![0; 2 * (GAS_DELTA_BUCKET_BOUNDS.len() + 1) + 1];
+        let mut changed_transactions = vec![];
+        for (idx, (label, (baseline, modified))) in labels
+            .into_iter()
+            .zip(baseline.iter().zip(modified.iter()))
+            .enumerate()
+        {
+            histogram[gas_delta_bucket(baseline.gas_used(), modified.gas_used())] += 1;
+            let diff = TransactionDiff::new(begin + idx as Version, label, baseline, modified);
+            if !diff.is_unchanged() {
+                changed_transactions.push(diff);
+            }
+        }
+
+        let mut by_gas_delta = changed_transactions
+            .iter()
+            .filter(|diff| diff.gas_delta() != 0)
+            .collect::<Vec<_>>();
+        by_gas_delta.sort_by_key(|diff| diff.gas_delta());
+        let top_gas_decreases = by_gas_delta
+            .iter()
+            .take_while(|diff| diff.gas_delta() < 0)
+            .take(top_n)
+            .map(|diff| (*diff).clone())
+            .collect();
+        let top_gas_increases = by_gas_delta
+            .iter()
+            .rev()
+            .take_while(|diff| diff.gas_delta() > 0)
+            .take(top_n)
+            .map(|diff| (*diff).clone())
+            .collect();
+
+        Self {
+            begin,
+            num_transactions: baseline.len(),
+            num_status_changes: changed_transactions
+                .iter()
+                .filter(|diff| diff.status_changed())
+                .count(),
+            num_gas_changes: by_gas_delta.len(),
+            num_write_set_changes: changed_transactions
+                .iter()
+                .filter(|diff| !diff.changed_writes.is_empty())
+                .count(),
+            baseline_gas_used: baseline.iter().map(|output| output.gas_used()).sum(),
+            modified_gas_used: modified.iter().map(|output| output.gas_used()).sum(),
+            gas_delta_histogram: histogram
+                .into_iter()
+                .enumerate()
+                .map(|(bucket, count)| HistogramBucket {
+                    range: gas_delta_bucket_range(bucket),
+                    count,
+                })
+                .collect(),
+            top_gas_increases,
+            top_gas_decreases,
+            changed_transactions,
+        }
+    }
+}
+
+// Buckets are ordered from the largest decrease to the largest increase, with the unchanged
+// transactions in the middle
+fn gas_delta_bucket(baseline: u64, modified: u64) -> usize {
+    let unchanged = GAS_DELTA_BUCKET_BOUNDS.len() + 1;
+    if baseline == modified {
+        return unchanged;
+    }
+    let delta = baseline.abs_diff(modified) as u128;
+    let magnitude = GAS_DELTA_BUCKET_BOUNDS
+        .iter()
+        .filter(|bound| delta * 100 >= baseline as u128 * **bound as u128)
+        .count();
+    if modified > baseline {
+        unchanged + 1 + magnitude
+    } else {
+        unchanged - 1 - magnitude
+    }
+}
+
+fn gas_delta_bucket_range(bucket: usize) -> String {
+    let unchanged = GAS_DELTA_BUCKET_BOUNDS.len() + 1;
+    if bucket == unchanged {
+        return "unchanged".to_string();
+    }
+    let (sign, magnitude) = if bucket > unchanged {
+        ('+', bucket - unchanged - 1)
+    } else {
+        ('-', unchanged - 1 - bucket)
+    };
+    let lower = magnitude
+        .checked_sub(1)
+        .map_or(0, |idx| GAS_DELTA_BUCKET_BOUNDS[idx]);
+    match GAS_DELTA_BUCKET_BOUNDS.get(magnitude) {
+        Some(upper) => format!("{}[{}%, {}%)", sign, lower, upper),
+        None => format!("{}[{}%, inf)", sign, lower),
+    }
+}
+
+impl fmt::Display for ReplayDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let last = self.begin + self.num_transactions as Version;
+        writeln!(
+            f,
+            "Replayed {} transactions [{}, {})",
+            self.num_transactions, self.begin, last
+        )?;
+        writeln!(f, "  Status changes:    {}", self.num_status_changes)?;
+        writeln!(f, "  Gas changes:       {}", self.num_gas_changes)?;
+        writeln!(f, "  Write set changes: {}", self.num_write_set_changes)?;
+        writeln!(
+            f,
+            "  Total gas used:    {} -> {} ({:+})",
+            self.baseline_gas_used,
+            self.modified_gas_used,
+            self.modified_gas_used as i128 - self.baseline_gas_used as i128
+        )?;
+
+        writeln!(f, "Gas delta histogram:")?;
+        for bucket in &self.gas_delta_histogram {
+            writeln!(f, "  {:>14}  {}", bucket.range, bucket.count)?;
+        }
+
+        for (title, diffs) in [
+            ("Top gas increases", &self.top_gas_increases),
+            ("Top gas decreases", &self.top_gas_decreases),
+        ] {
+            if diffs.is_empty() {
+                continue;
+            }
+            writeln!(f, "{}:", title)?;
+            for diff in diffs {
+                writeln!(
+                    f,
+                    "  {:>12}  {} -> {} ({:+})  {}",
+                    diff.version,
+                    diff.baseline_gas_used,
+                    diff.modified_gas_used,
+                    diff.gas_delta(),
+                    diff.label
+                )?;
+            }
+        }
+
+        let status_changes = self
+            .changed_transactions
+            .iter()
+            .filter(|diff| diff.status_changed())
+            .collect::<Vec<_>>();
+        if !status_changes.is_empty() {
+            writeln!(f, "Status changes:")?;
+            for diff in status_changes {
+                writeln!(
+                    f,
+                    "  {:>12}  {} -> {}  {}",
+                    diff.version, diff.baseline_status, diff.modified_status, diff.label
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::{
+        state_store::state_key::StateKey,
+        transaction::{ExecutionStatus, TransactionAuxiliaryData, TransactionStatus},
+        write_set::{WriteOp, WriteSetMut},
+    };
+
+    fn output(
+        gas_used: u64,
+        status: ExecutionStatus,
+        writes: &[(&[u8], &[u8])],
+    ) -> TransactionOutput {
+        let write_set = WriteSetMut::new(writes.iter().map(|(key, value)| {
+            (
+                StateKey::raw(key),
+                WriteOp::legacy_modification(value.to_vec().into()),
+            )
+        }))
+        .freeze()
+        .unwrap();
+        TransactionOutput::new(
+            write_set,
+            vec![],
+            gas_used,
+            TransactionStatus::Keep(status),
+            TransactionAuxiliaryData::default(),
+        )
+    }
+
+    #[test]
+    fn test_gas_delta_buckets() {
+        let ranges = (0..9).map(gas_delta_bucket_range).collect::<Vec<_>>();
+        assert_eq!(ranges, vec![
+            "-[50%, inf)",
+            "-[10%, 50%)",
+            "-[1%, 10%)",
+            "-[0%, 1%)",
+            "unchanged",
+            "+[0%, 1%)",
+            "+[1%, 10%)",
+            "+[10%, 50%)",
+            "+[50%, inf)",
+        ]);
+
+        assert_eq!(gas_delta_bucket(100, 100), 4);
+        assert_eq!(gas_delta_bucket(1000, 1005), 5);
+        assert_eq!(gas_delta_bucket(100, 101), 6);
+        assert_eq!(gas_delta_bucket(100, 149), 7);
+        assert_eq!(gas_delta_bucket(100, 150), 8);
+        assert_eq!(gas_delta_bucket(0, 1), 8);
+        assert_eq!(gas_delta_bucket(100, 95), 2);
+        assert_eq!(gas_delta_bucket(100, 10), 0);
+    }
+
+    #[test]
+    fn test_replay_diff() {
+        let success = || ExecutionStatus::Success;
+        let baseline = vec![
+            output(10, success(), &[(b"a", b"1")]),
+            output(10, success(), &[(b"a", b"1")]),
+            output(10, success(), &[(b"a", b"1"), (b"b", b"1")]),
+            output(10, success(), &[]),
+        ];
+        let modified = vec![
+            output(10, success(), &[(b"a", b"1")]),
+            output(20, success(), &[(b"a", b"1")]),
+            output(5, success(), &[(b"a", b"2"), (b"c", b"1")]),
+            output(30, ExecutionStatus::OutOfGas, &[]),
+        ];
+        let labels = (0..4).map(|idx| format!("txn {}", idx)).collect();
+
+        let diff = ReplayDiff::new(100, labels, &baseline, &modified, 1);
+        assert_eq!(diff.num_transactions, 4);
+        assert_eq!(diff.num_status_changes, 1);
+        assert_eq!(diff.num_gas_changes, 3);
+        assert_eq!(diff.num_write_set_changes, 1);
+        assert_eq!(diff.baseline_gas_used, 40);
+        assert_eq!(diff.modified_gas_used, 65);
+
+        let versions =
+            |diffs: &[TransactionDiff]| diffs.iter().map(|diff| diff.version).collect::<Vec<_>>();
+        assert_eq!(versions(&diff.changed_transactions), vec![101, 102, 103]);
+        assert_eq!(versions(&diff.top_gas_increases), vec![103]);
+        assert_eq!(versions(&diff.top_gas_decreases), vec![102]);
+        assert_eq!(diff.changed_transactions[1].changed_writes.len(), 3);
+
+        let counts = diff
+            .gas_delta_histogram
+            .iter()
+            .map(|bucket| bucket.count)
+            .collect::<Vec<_>>();
+        assert_eq!(counts, vec![1, 0, 0, 0, 1, 0, 0, 0, 2]);
+    }
+}
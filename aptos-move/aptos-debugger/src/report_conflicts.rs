@@ -0,0 +1,80 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{aptos_debugger::AptosDebugger, common::Target};
+use anyhow::Result;
+use aptos_rest_client::Client;
+use clap::Parser;
+use std::{collections::HashMap, path::PathBuf};
+use url::Url;
+
+/// Number of keys listed in the summary over all blocks.
+const MAX_HOT_KEYS: usize = 20;
+
+/// Replays a range of blocks in parallel and reports the conflicts between their transactions:
+/// hot keys, dependencies, re-executions and the effective parallelism
+#[derive(Parser)]
+pub struct Command {
+    #[clap(flatten)]
+    target: Target,
+
+    #[clap(long)]
+    begin_version: u64,
+
+    #[clap(long)]
+    limit: u64,
+
+    #[clap(long, default_value_t = 8)]
+    concurrency_level: usize,
+
+    /// The directory to write the reports to, as `<first version of the block>.json`
+    #[clap(long)]
+    output: Option<PathBuf>,
+}
+
+impl Command {
+    pub async fn run(self) -> Result<()> {
+        let debugger = if let Some(rest_endpoint) = self.target.rest_endpoint {
+            AptosDebugger::rest_client(Client::new(Url::parse(&rest_endpoint)?))?
+        } else if let Some(db_path) = self.target.db_path {
+            AptosDebugger::db(db_path)?
+        } else {
+            unreachable!("Must provide one target.");
+        };
+
+        let reports = debugger
+            .report_conflicts(self.begin_version, self.limit, self.concurrency_level)
+            .await?;
+
+        // Keys contended in many blocks are the ones worth redesigning.
+        let mut hot_keys: HashMap<&str, (u64, usize)> = HashMap::new();
+        for (version, report) in &reports {
+            println!("Block at version {}:\n{}", version, report);
+            for hot_key in &report.hot_keys {
+                let (conflicts, blocks) = hot_keys.entry(&hot_key.key).or_default();
+                *conflicts += (hot_key.validation_failures + hot_key.dependency_waits) as u64;
+                *blocks += 1;
+            }
+        }
+        let mut hot_keys = hot_keys.into_iter().collect::<Vec<_>>();
+        hot_keys.sort_by(|(lhs_key, lhs), (rhs_key, rhs)| rhs.cmp(lhs).then(lhs_key.cmp(rhs_key)));
+        if !hot_keys.is_empty() {
+            println!("Hot keys over {} block(s):", reports.len());
+            for (key, (conflicts, blocks)) in hot_keys.into_iter().take(MAX_HOT_KEYS) {
+                println!("  {} conflicts in {} block(s): {}", conflicts, blocks, key);
+            }
+        }
+
+        if let Some(output) = &self.output {
+            std::fs::create_dir_all(output)?;
+            for (version, report) in &reports {
+                std::fs::write(
+                    output.join(format!("{}.json", version)),
+                    serde_json::to_string_pretty(report.as_ref())?,
+                )?;
+            }
+            println!("Reports written to {}", output.display());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,277 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, format_err, Result};
+use aptos_framework::{natives::code::PackageRegistry, ReleaseBundle};
+use aptos_types::{
+    on_chain_config::{FeatureFlag, Features, GasScheduleV2, OnChainConfig},
+    state_store::{
+        state_key::StateKey, state_storage_usage::StateStorageUsage, state_value::StateValue,
+        Result as StateViewResult, StateView, StateViewId, TStateView,
+    },
+};
+use move_binary_format::{access::ModuleAccess, CompiledModule};
+use std::collections::HashMap;
+
+/// Changes to the on-chain configs and the framework, e.g., the ones of a governance proposal,
+/// that are applied on top of the state transactions are replayed against.
+#[derive(Default)]
+pub struct StateOverrides {
+    gas_schedule: Option<GasScheduleV2>,
+    gas_params: Vec<(String, u64)>,
+    enabled_features: Vec<FeatureFlag>,
+    disabled_features: Vec<FeatureFlag>,
+    framework: Option<ReleaseBundle>,
+}
+
+impl StateOverrides {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the gas schedule
+    pub fn with_gas_schedule(mut self, gas_schedule: GasScheduleV2) -> Self {
+        self.gas_schedule = Some(gas_schedule);
+        self
+    }
+
+    /// Sets a single entry of the gas schedule, on top of the on-chain one (or the one set with
+    /// `with_gas_schedule`)
+    pub fn with_gas_param(mut self, name: String, value: u64) -> Self {
+        self.gas_params.push((name, value));
+        self
+    }
+
+    pub fn enable_feature(mut self, flag: FeatureFlag) -> Self {
+        self.enabled_features.push(flag);
+        self
+    }
+
+    pub fn disable_feature(mut self, flag: FeatureFlag) -> Self {
+        self.disabled_features.push(flag);
+        self
+    }
+
+    /// Replaces the packages of the bundle, e.g., a framework release built with
+    /// `aptos-release-builder`
+    pub fn with_framework(mut self, framework: ReleaseBundle) -> Self {
+        self.framework = Some(framework);
+        self
+    }
+
+    /// Whether code is replaced, in which case modules cached by the VM may be stale
+    pub fn overrides_code(&self) -> bool {
+        self.framework.is_some()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.gas_schedule.is_none()
+            && self.gas_params.is_empty()
+            && self.enabled_features.is_empty()
+            && self.disabled_features.is_empty()
+            && self.framework.is_none()
+    }
+
+    /// Computes the state values that replace the ones of the state view
+    pub fn resolve(&self, state_view: &impl StateView) -> Result<HashMap<StateKey, StateValue>> {
+        let mut values = HashMap::new();
+
+        if self.gas_schedule.is_some() || !self.gas_params.is_empty() {
+            let mut gas_schedule = match &self.gas_schedule {
+                Some(gas_schedule) => gas_schedule.clone(),
+                None => GasScheduleV2::fetch_config(state_view)
+                    .ok_or_else(|| format_err!("Failed to fetch the gas schedule"))?,
+            };
+            for (name, value) in &self.gas_params {
+                match gas_schedule
+                    .entries
+                    .iter_mut()
+                    .find(|(entry_name, _)| entry_name == name)
+                {
+                    Some((_, entry_value)) => *entry_value = *value,
+                    None => bail!("Unknown gas parameter: {}", name),
+                }
+            }
+            insert_config(&mut values, &gas_schedule)?;
+        }
+
+        if !self.enabled_features.is_empty() || !self.disabled_features.is_empty() {
+            let mut features = Features::fetch_config(state_view).unwrap_or_default();
+            for flag in &self.enabled_features {
+                features.enable(*flag);
+            }
+            for flag in &self.disabled_features {
+                features.disable(*flag);
+            }
+            insert_config(&mut values, &features)?;
+        }
+
+        if let Some(framework) = &self.framework {
+            // Packages are published like upgrades: the code of their modules is replaced and
+            // their metadata in the registry of their account is replaced or added
+            let mut registries = HashMap::new();
+            for package in &framework.packages {
+                let mut address = None;
+                for code in package.code() {
+                    let module = CompiledModule::deserialize(code)?;
+                    let module_id = module.self_id();
+                    address = Some(*module_id.address());
+                    values.insert(
+                        StateKey::module_id(&module_id),
+                        StateValue::new_legacy(code.to_vec().into()),
+                    );
+                }
+                let Some(address) = address else {
+                    continue;
+                };
+
+                let registry_key = StateKey::resource(&address, &PackageRegistry::struct_tag())?;
+                if !registries.contains_key(&registry_key) {
+                    let registry = state_view
+                        .get_state_value_bytes(&registry_key)?
+                        .map(|bytes| bcs::from_bytes::<PackageRegistry>(&bytes))
+                        .transpose()?
+                        .unwrap_or(PackageRegistry { packages: vec![] });
+                    registries.insert(registry_key.clone(), registry);
+                }
+                let registry = registries
+                    .get_mut(&registry_key)
+                    .expect("Registry must have been fetched");
+
+                let mut metadata = package.package_metadata().clone();
+                match registry
+                    .packages
+                    .iter_mut()
+                    .find(|existing| existing.name == metadata.name)
+                {
+                    Some(existing) => {
+                        metadata.upgrade_number = existing.upgrade_number + 1;
+                        *existing = metadata;
+                    },
+                    None => registry.packages.push(metadata),
+                }
+            }
+            for (registry_key, registry) in registries {
+                values.insert(
+                    registry_key,
+                    StateValue::new_legacy(bcs::to_bytes(&registry)?.into()),
+                );
+            }
+        }
+
+        Ok(values)
+    }
+}
+
+fn insert_config<T: OnChainConfig + serde::Serialize>(
+    values: &mut HashMap<StateKey, StateValue>,
+    config: &T,
+) -> Result<()> {
+    values.insert(
+        StateKey::on_chain_config::<T>()?,
+        StateValue::new_legacy(bcs::to_bytes(config)?.into()),
+    );
+    Ok(())
+}
+
+/// A state view which reads the overridden values instead of the ones of the base state view
+pub struct OverriddenStateView<'a, S> {
+    base: &'a S,
+    overrides: &'a HashMap<StateKey, StateValue>,
+}
+
+impl<'a, S: StateView> OverriddenStateView<'a, S> {
+    pub fn new(base: &'a S, overrides: &'a HashMap<StateKey, StateValue>) -> Self {
+        Self { base, overrides }
+    }
+}
+
+impl<'a, S: StateView> TStateView for OverriddenStateView<'a, S> {
+    type Key = StateKey;
+
+    fn id(&self) -> StateViewId {
+        self.base.id()
+    }
+
+    fn get_state_value(&self, state_key: &StateKey) -> StateViewResult<Option<StateValue>> {
+        if let Some(value) = self.overrides.get(state_key) {
+            return Ok(Some(value.clone()));
+        }
+        self.base.get_state_value(state_key)
+    }
+
+    fn get_usage(&self) -> StateViewResult<StateStorageUsage> {
+        self.base.get_usage()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::state_store::in_memory_state_view::InMemoryStateView;
+
+    fn state_view(gas_schedule: &GasScheduleV2, features: &Features) -> InMemoryStateView {
+        let mut values = HashMap::new();
+        insert_config(&mut values, gas_schedule).unwrap();
+        insert_config(&mut values, features).unwrap();
+        InMemoryStateView::new(values)
+    }
+
+    fn gas_schedule(entries: &[(&str, u64)]) -> GasScheduleV2 {
+        GasScheduleV2 {
+            feature_version: 1,
+            entries: entries
+                .iter()
+                .map(|(name, value)| (name.to_string(), *value))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_on_top_of_state() {
+        let overrides = StateOverrides::new()
+            .with_gas_param("b".to_string(), 5)
+            .enable_feature(FeatureFlag::CODE_DEPENDENCY_CHECK)
+            .disable_feature(FeatureFlag::VM_BINARY_FORMAT_V6);
+
+        let mut features = Features::default();
+        features.disable(FeatureFlag::CODE_DEPENDENCY_CHECK);
+        features.enable(FeatureFlag::VM_BINARY_FORMAT_V6);
+        features.enable(FeatureFlag::BLS12_381_STRUCTURES);
+        let old_state = state_view(&gas_schedule(&[("a", 1), ("b", 2)]), &features);
+        features.disable(FeatureFlag::BLS12_381_STRUCTURES);
+        let new_state = state_view(&gas_schedule(&[("a", 3), ("b", 4)]), &features);
+
+        // The overrides are applied on top of the configs of the state they are resolved against
+        for (state, a, bls_enabled) in [(old_state, 1, true), (new_state, 3, false)] {
+            let values = overrides.resolve(&state).unwrap();
+            let overridden = OverriddenStateView::new(&state, &values);
+            assert_eq!(
+                GasScheduleV2::fetch_config(&overridden).unwrap(),
+                gas_schedule(&[("a", a), ("b", 5)])
+            );
+            let features = Features::fetch_config(&overridden).unwrap();
+            assert!(features.is_enabled(FeatureFlag::CODE_DEPENDENCY_CHECK));
+            assert!(!features.is_enabled(FeatureFlag::VM_BINARY_FORMAT_V6));
+            assert_eq!(
+                features.is_enabled(FeatureFlag::BLS12_381_STRUCTURES),
+                bls_enabled
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_unknown_gas_param() {
+        let state = state_view(&gas_schedule(&[("a", 1)]), &Features::default());
+        let overrides = StateOverrides::new().with_gas_param("b".to_string(), 5);
+        assert!(overrides.resolve(&state).is_err());
+    }
+
+    #[test]
+    fn test_resolve_without_overrides() {
+        let state = state_view(&gas_schedule(&[("a", 1)]), &Features::default());
+        let overrides = StateOverrides::new();
+        assert!(overrides.is_empty());
+        assert!(overrides.resolve(&state).unwrap().is_empty());
+    }
+}
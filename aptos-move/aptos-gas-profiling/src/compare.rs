@@ -0,0 +1,376 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    flamegraph::merged_flamegraph,
+    merged::{CostEntry, MergedGasProfile},
+    misc::strip_trailing_zeros_and_decimal_point,
+    report::{ensure_dirs_exist, render_table},
+};
+use anyhow::{bail, Result};
+use handlebars::Handlebars;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs,
+    path::Path,
+};
+
+const TEMPLATE: &str = include_str!("../templates/comparison.html");
+
+/// The average cost per transaction of an item, before and after a change.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CostComparison {
+    pub category: String,
+    pub name: String,
+    /// `Gas Units` or `APT`.
+    pub unit: String,
+    pub before: f64,
+    pub after: f64,
+}
+
+impl CostComparison {
+    /// The relative change in percent, or `None` if the item is new.
+    pub fn change_percent(&self) -> Option<f64> {
+        if self.before == 0.0 {
+            None
+        } else {
+            Some((self.after - self.before) / self.before * 100.0)
+        }
+    }
+
+    fn fmt_change(&self) -> String {
+        match self.change_percent() {
+            Some(percent) => format!("{:+.2}%", percent),
+            None => "new".to_string(),
+        }
+    }
+}
+
+/// A comparison of two merged gas profiles, e.g., of the calls to an entry function before and
+/// after an upgrade of its module or of the gas schedule.
+///
+/// Since the profiles may cover different numbers of transactions, costs are compared per
+/// transaction. Items whose average cost grew by more than the threshold are regressions.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GasProfileComparison {
+    pub before_transactions: u64,
+    pub after_transactions: u64,
+    pub threshold_percent: f64,
+    /// Items whose cost changed, the largest changes first.
+    pub entries: Vec<CostComparison>,
+    /// Differential folded stacks of the execution & IO costs, as
+    /// `<stack> <average before> <average after>`.
+    pub exec_io_stacks: Vec<String>,
+}
+
+fn average(total: u64, num_txns: u64) -> f64 {
+    total as f64 / num_txns.max(1) as f64
+}
+
+impl GasProfileComparison {
+    pub fn new(
+        before: &MergedGasProfile,
+        after: &MergedGasProfile,
+        threshold_percent: f64,
+    ) -> Result<Self> {
+        if before.num_transactions == 0 || after.num_transactions == 0 {
+            bail!("Cannot compare empty profiles");
+        }
+        if before.gas_scaling_factor != after.gas_scaling_factor {
+            bail!(
+                "Cannot compare profiles with different gas scaling factors: {} and {}",
+                before.gas_scaling_factor,
+                after.gas_scaling_factor
+            );
+        }
+        let gas_scaling_factor = before.gas_scaling_factor.max(1) as f64;
+
+        let mut entries = vec![];
+        let mut push = |category: &str, name: &str, is_storage: bool, lhs: u64, rhs: u64| {
+            let (unit, scale) = if is_storage {
+                ("APT", 1_0000_0000f64)
+            } else {
+                ("Gas Units", gas_scaling_factor)
+            };
+            let before_avg = average(lhs, before.num_transactions) / scale;
+            let after_avg = average(rhs, after.num_transactions) / scale;
+            if before_avg != after_avg {
+                entries.push(CostComparison {
+                    category: category.to_string(),
+                    name: name.to_string(),
+                    unit: unit.to_string(),
+                    before: before_avg,
+                    after: after_avg,
+                });
+            }
+        };
+
+        push(
+            "Total",
+            "execution & io",
+            false,
+            before.exec_io_total,
+            after.exec_io_total,
+        );
+        push(
+            "Total",
+            "storage",
+            true,
+            before.storage_total,
+            after.storage_total,
+        );
+        push(
+            "Total",
+            "intrinsic",
+            false,
+            before.intrinsic,
+            after.intrinsic,
+        );
+        push("Total", "keyless", false, before.keyless, after.keyless);
+
+        for ((category, is_storage, lhs), (_, _, rhs)) in
+            before.tables().into_iter().zip(after.tables())
+        {
+            let names = lhs.keys().chain(rhs.keys()).collect::<BTreeSet<_>>();
+            for name in names {
+                let cost = |table: &BTreeMap<String, CostEntry>| {
+                    table.get(name).map(|entry| entry.cost).unwrap_or(0)
+                };
+                push(category, name, is_storage, cost(lhs), cost(rhs));
+            }
+        }
+
+        // Totals first, then the largest absolute changes, relative to the unit of the item
+        entries.sort_by(|lhs, rhs| {
+            let key = |entry: &CostComparison| {
+                (
+                    entry.category != "Total",
+                    -(entry.after - entry.before).abs() / entry.before.max(entry.after),
+                )
+            };
+            key(lhs)
+                .partial_cmp(&key(rhs))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let stacks = before
+            .exec_io_stacks
+            .keys()
+            .chain(after.exec_io_stacks.keys())
+            .collect::<BTreeSet<_>>();
+        let exec_io_stacks = stacks
+            .into_iter()
+            .map(|stack| {
+                let cost = |profile: &MergedGasProfile| {
+                    average(
+                        profile.exec_io_stacks.get(stack).copied().unwrap_or(0),
+                        profile.num_transactions,
+                    )
+                    .round() as u64
+                };
+                format!("{} {} {}", stack, cost(before), cost(after))
+            })
+            .collect();
+
+        Ok(Self {
+            before_transactions: before.num_transactions,
+            after_transactions: after.num_transactions,
+            threshold_percent,
+            entries,
+            exec_io_stacks,
+        })
+    }
+
+    /// Whether the item got more expensive by more than the threshold. New items always count
+    /// as regressions.
+    pub fn is_regression(&self, entry: &CostComparison) -> bool {
+        match entry.change_percent() {
+            Some(percent) => percent > self.threshold_percent,
+            None => entry.after > 0.0,
+        }
+    }
+
+    fn is_improvement(&self, entry: &CostComparison) -> bool {
+        matches!(entry.change_percent(), Some(percent) if percent < -self.threshold_percent)
+    }
+
+    pub fn regressions(&self) -> impl Iterator<Item = &CostComparison> {
+        self.entries
+            .iter()
+            .filter(|entry| self.is_regression(entry))
+    }
+
+    pub fn generate_html_report(&self, path: impl AsRef<Path>, header: String) -> Result<()> {
+        let mut data = Map::new();
+        data.insert("title".to_string(), Value::String(header));
+        data.insert(
+            "before-transactions".to_string(),
+            json!(self.before_transactions),
+        );
+        data.insert(
+            "after-transactions".to_string(),
+            json!(self.after_transactions),
+        );
+        data.insert("threshold".to_string(), json!(self.threshold_percent));
+        data.insert(
+            "num-regressions".to_string(),
+            json!(self.regressions().count()),
+        );
+
+        let graph_exec_io = merged_flamegraph(
+            &self.exec_io_stacks,
+            "Execution & IO (average per transaction)".to_string(),
+            |count| format!("{} internal gas units", count),
+        )?;
+        data.insert(
+            "graph-exec-io".to_string(),
+            Value::Bool(graph_exec_io.is_some()),
+        );
+
+        let rows = self
+            .entries
+            .iter()
+            .map(|entry| {
+                json!({
+                    "category": entry.category,
+                    "name": entry.name,
+                    "unit": entry.unit,
+                    "before": fmt_cost(entry.before),
+                    "after": fmt_cost(entry.after),
+                    "change": entry.fmt_change(),
+                    "regression": self.is_regression(entry),
+                    "improvement": self.is_improvement(entry),
+                })
+            })
+            .collect::<Vec<_>>();
+        data.insert("rows".to_string(), Value::Array(rows));
+
+        let mut handlebars = Handlebars::new();
+        handlebars.register_template_string("comparison", TEMPLATE)?;
+        let html = handlebars.render("comparison", &data)?;
+
+        let path_root = path.as_ref();
+
+        ensure_dirs_exist(path_root)?;
+        let path_assets = path_root.join("assets");
+        ensure_dirs_exist(&path_assets)?;
+
+        if let Some(graph_bytes) = graph_exec_io {
+            fs::write(path_assets.join("exec_io_diff.svg"), graph_bytes)?;
+        }
+        fs::write(path_root.join("index.html"), html)?;
+
+        Ok(())
+    }
+}
+
+fn fmt_cost(cost: f64) -> String {
+    strip_trailing_zeros_and_decimal_point(&format!("{:.8}", cost)).to_string()
+}
+
+impl fmt::Display for GasProfileComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Compared {} transaction(s) before and {} after, per transaction:",
+            self.before_transactions, self.after_transactions
+        )?;
+
+        let mut table = vec![[
+            "".to_string(),
+            "category".to_string(),
+            "name".to_string(),
+            "before".to_string(),
+            "after".to_string(),
+            "change".to_string(),
+        ]];
+        for entry in &self.entries {
+            table.push([
+                if self.is_regression(entry) {
+                    "!".to_string()
+                } else {
+                    "".to_string()
+                },
+                entry.category.clone(),
+                entry.name.clone(),
+                format!("{} {}", fmt_cost(entry.before), entry.unit),
+                format!("{} {}", fmt_cost(entry.after), entry.unit),
+                entry.fmt_change(),
+            ]);
+        }
+        if table.len() > 1 {
+            render_table(f, &table, 2)?;
+        } else {
+            writeln!(f, "  (no changes)")?;
+        }
+
+        let regressions = self.regressions().count();
+        if regressions > 0 {
+            writeln!(
+                f,
+                "{} regression(s) above {}%, marked with `!`",
+                regressions, self.threshold_percent
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(num_transactions: u64, instructions: &[(&str, u64)]) -> MergedGasProfile {
+        let mut profile = MergedGasProfile {
+            num_transactions,
+            gas_scaling_factor: 100,
+            ..Default::default()
+        };
+        for (name, cost) in instructions {
+            profile.instructions.insert(name.to_string(), CostEntry {
+                hits: num_transactions,
+                cost: *cost,
+            });
+            profile
+                .exec_io_stacks
+                .insert(format!("0x1::m::f;{}", name), *cost);
+            profile.exec_io_total += cost;
+        }
+        profile
+    }
+
+    #[test]
+    fn compares_averages_per_transaction() {
+        // Same average cost per transaction, different numbers of transactions
+        let before = profile(2, &[("add", 200), ("ld_u64", 400)]);
+        let after = profile(4, &[("add", 400), ("ld_u64", 1000), ("mul", 40)]);
+        let comparison = GasProfileComparison::new(&before, &after, 5.0).unwrap();
+
+        let names = comparison
+            .entries
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["execution & io", "mul", "ld_u64"]);
+
+        let regressions = comparison
+            .regressions()
+            .map(|entry| entry.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(regressions, vec!["execution & io", "mul", "ld_u64"]);
+        assert_eq!(comparison.entries[2].change_percent(), Some(25.0));
+        assert!(comparison
+            .exec_io_stacks
+            .contains(&"0x1::m::f;mul 0 10".to_string()));
+    }
+
+    #[test]
+    fn rejects_mismatched_scaling_factors() {
+        let before = profile(1, &[("add", 1)]);
+        let mut after = profile(1, &[("add", 1)]);
+        after.gas_scaling_factor = 1;
+        assert!(GasProfileComparison::new(&before, &after, 5.0).is_err());
+    }
+}
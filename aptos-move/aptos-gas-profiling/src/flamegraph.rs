@@ -239,3 +239,44 @@ impl ExecutionAndIOCosts {
         Ok(Some(graph_content.as_bytes().to_vec()))
     }
 }
+
+/// Generates a flamegraph from folded stack lines merged over many transactions, in which
+/// identical stacks are merged instead of being laid out in execution order. Lines with two
+/// counts (before and after) produce a differential flamegraph. `format_count` renders the
+/// counts in the unit of the costs.
+/// None will be returned if there are no lines.
+pub(crate) fn merged_flamegraph(
+    lines: &[String],
+    title: String,
+    format_count: impl Fn(u64) -> String,
+) -> anyhow::Result<Option<Vec<u8>>> {
+    if lines.is_empty() {
+        return Ok(None);
+    }
+
+    let mut options = inferno::flamegraph::Options::default();
+    options.text_truncate_direction = TextTruncateDirection::Right;
+    options.color_diffusion = true;
+    options.title = title;
+
+    let mut graph_content = vec![];
+    inferno::flamegraph::from_lines(
+        &mut options,
+        lines.iter().map(|s| s.as_str()),
+        &mut graph_content,
+    )?;
+    let graph_content = String::from_utf8_lossy(&graph_content);
+
+    let re = regex::Regex::new("([1-9][0-9]*(,[0-9]+)*) samples")
+        .expect("should be able to build regex successfully");
+    let graph_content = re.replace_all(&graph_content, |caps: &Captures| {
+        let count: u64 = caps[1]
+            .replace(',', "")
+            .parse()
+            .expect("should be able parse count as u64");
+
+        format_count(count)
+    });
+
+    Ok(Some(graph_content.as_bytes().to_vec()))
+}
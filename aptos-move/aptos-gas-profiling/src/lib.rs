@@ -1,14 +1,26 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+#[macro_use]
+mod macros;
+
 mod aggregate;
+mod compare;
 mod erased;
 mod flamegraph;
 mod log;
+mod merged;
 mod misc;
 mod profiler;
 mod render;
 mod report;
+mod trace;
 
+pub use compare::{CostComparison, GasProfileComparison};
 pub use log::{FrameName, TransactionGasLog};
+pub use merged::{CostEntry, MergedGasProfile};
 pub use profiler::GasProfiler;
+pub use trace::{
+    ExecutionTrace, ExecutionTracer, FrameArgs, ResourceRead, ResourceWrite, ResourceWriteKind,
+    TraceFrame, TracedEvent, TracedValue,
+};
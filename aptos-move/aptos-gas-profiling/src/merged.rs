@@ -0,0 +1,646 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    flamegraph::merged_flamegraph,
+    log::{CallFrame, ExecutionGasEvent, TransactionGasLog},
+    render::{Render, StateKeyKind},
+    report::ensure_dirs_exist,
+};
+use anyhow::{bail, Result};
+use handlebars::Handlebars;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::{collections::BTreeMap, fs, path::Path};
+
+const TEMPLATE: &str = include_str!("../templates/merged.html");
+
+/// The number of occurrences and the total cost of an item, over all merged transactions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CostEntry {
+    pub hits: u64,
+    pub cost: u64,
+}
+
+/// The gas costs of many transactions merged together, e.g., of all calls to an entry function
+/// over a range of versions.
+///
+/// Costs are keyed by name, with the accounts of state items left out, so that the costs of
+/// different transactions add up. Profiles can be saved as JSON and compared later on, e.g.,
+/// before and after a contract upgrade.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MergedGasProfile {
+    pub num_transactions: u64,
+    /// Converts the internal gas units of the execution & IO costs into gas units.
+    pub gas_scaling_factor: u64,
+
+    /// Execution & IO costs, in internal gas units.
+    pub exec_io_total: u64,
+    pub intrinsic: u64,
+    pub keyless: u64,
+    pub dependencies: BTreeMap<String, CostEntry>,
+    pub instructions: BTreeMap<String, CostEntry>,
+    pub natives: BTreeMap<String, CostEntry>,
+    pub storage_reads: BTreeMap<String, CostEntry>,
+    pub ledger_writes: BTreeMap<String, CostEntry>,
+
+    /// Storage fees, in octas.
+    pub storage_total: u64,
+    pub storage_refund: u64,
+    pub storage_fees: BTreeMap<String, CostEntry>,
+
+    /// Folded stacks of the costs, from which the flamegraphs are generated.
+    pub exec_io_stacks: BTreeMap<String, u64>,
+    pub storage_stacks: BTreeMap<String, u64>,
+}
+
+fn add_cost(map: &mut BTreeMap<String, CostEntry>, key: String, cost: impl Into<u64>) {
+    let entry = map.entry(key).or_default();
+    entry.hits += 1;
+    entry.cost += cost.into();
+}
+
+fn add_stack(map: &mut BTreeMap<String, u64>, stack: String, cost: impl Into<u64>) {
+    let cost = cost.into();
+    if cost > 0 {
+        *map.entry(stack).or_default() += cost;
+    }
+}
+
+fn merge_costs(into: &mut BTreeMap<String, CostEntry>, from: &BTreeMap<String, CostEntry>) {
+    for (key, entry) in from {
+        let merged = into.entry(key.clone()).or_default();
+        merged.hits += entry.hits;
+        merged.cost += entry.cost;
+    }
+}
+
+fn merge_stacks(into: &mut BTreeMap<String, u64>, from: &BTreeMap<String, u64>) {
+    for (stack, cost) in from {
+        *into.entry(stack.clone()).or_default() += cost;
+    }
+}
+
+impl MergedGasProfile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn check_gas_scaling_factor(&mut self, gas_scaling_factor: u64) -> Result<()> {
+        if self.num_transactions == 0 {
+            self.gas_scaling_factor = gas_scaling_factor;
+        } else if self.gas_scaling_factor != gas_scaling_factor {
+            bail!(
+                "Cannot merge profiles with different gas scaling factors: {} and {}",
+                self.gas_scaling_factor,
+                gas_scaling_factor
+            );
+        }
+        Ok(())
+    }
+
+    /// Adds the costs of a transaction to the profile.
+    pub fn add(&mut self, log: &TransactionGasLog) -> Result<()> {
+        let exec_io = &log.exec_io;
+        let storage = &log.storage;
+        self.check_gas_scaling_factor(u64::from(exec_io.gas_scaling_factor))?;
+        self.num_transactions += 1;
+
+        // Execution & IO
+        self.exec_io_total += u64::from(exec_io.total);
+        self.intrinsic += u64::from(exec_io.intrinsic_cost);
+        self.keyless += u64::from(exec_io.keyless_cost);
+        add_stack(
+            &mut self.exec_io_stacks,
+            "intrinsic".to_string(),
+            exec_io.intrinsic_cost,
+        );
+        add_stack(
+            &mut self.exec_io_stacks,
+            "keyless".to_string(),
+            exec_io.keyless_cost,
+        );
+
+        for dep in &exec_io.dependencies {
+            let name = format!("{}", Render(&dep.id));
+            add_stack(
+                &mut self.exec_io_stacks,
+                format!("dependencies;{}", name),
+                dep.cost,
+            );
+            add_cost(&mut self.dependencies, name, dep.cost);
+        }
+
+        let mut path = vec![];
+        self.add_frame(&exec_io.call_graph, &mut path);
+
+        if let Some(cost) = exec_io.transaction_transient {
+            add_stack(
+                &mut self.exec_io_stacks,
+                "ledger_writes;transaction".to_string(),
+                cost,
+            );
+            add_cost(&mut self.ledger_writes, "transaction".to_string(), cost);
+        }
+        for event in &exec_io.events_transient {
+            let name = format!("{}", Render(&event.ty));
+            add_stack(
+                &mut self.exec_io_stacks,
+                format!("ledger_writes;events;{}", name),
+                event.cost,
+            );
+            add_cost(
+                &mut self.ledger_writes,
+                format!("event<{}>", name),
+                event.cost,
+            );
+        }
+        for write in &exec_io.write_set_transient {
+            let name = format!("{}<{}>", Render(&write.op_type), StateKeyKind(&write.key));
+            add_stack(
+                &mut self.exec_io_stacks,
+                format!("ledger_writes;state_write_ops;{}", name),
+                write.cost,
+            );
+            add_cost(&mut self.ledger_writes, name, write.cost);
+        }
+
+        // Storage
+        self.storage_total += u64::from(storage.total);
+        self.storage_refund += u64::from(storage.total_refund);
+        add_stack(
+            &mut self.storage_stacks,
+            "transaction".to_string(),
+            storage.txn_storage,
+        );
+        add_cost(
+            &mut self.storage_fees,
+            "transaction".to_string(),
+            storage.txn_storage,
+        );
+        for write in &storage.write_set_storage {
+            let name = format!("{}<{}>", Render(&write.op_type), StateKeyKind(&write.key));
+            add_stack(
+                &mut self.storage_stacks,
+                format!("write_set;{}", name),
+                write.cost,
+            );
+            add_cost(&mut self.storage_fees, name, write.cost);
+        }
+        for event in &storage.events {
+            add_stack(
+                &mut self.storage_stacks,
+                format!("events;{}", event.ty),
+                event.cost,
+            );
+            add_cost(
+                &mut self.storage_fees,
+                format!("event<{}>", event.ty),
+                event.cost,
+            );
+        }
+
+        Ok(())
+    }
+
+    fn add_frame(&mut self, frame: &CallFrame, path: &mut Vec<String>) {
+        use ExecutionGasEvent::*;
+
+        path.push(format!("{}", frame.name));
+        let mut frame_cost = 0;
+        for event in &frame.events {
+            match event {
+                Loc(_) => (),
+                Bytecode { op, cost } => {
+                    frame_cost += u64::from(*cost);
+                    add_cost(
+                        &mut self.instructions,
+                        format!("{:?}", op).to_ascii_lowercase(),
+                        *cost,
+                    );
+                },
+                CreateTy { cost } => {
+                    frame_cost += u64::from(*cost);
+                    add_cost(&mut self.instructions, "create_ty".to_string(), *cost);
+                },
+                Call(inner_frame) => self.add_frame(inner_frame, path),
+                CallNative {
+                    module_id,
+                    fn_name,
+                    ty_args,
+                    cost,
+                } => {
+                    let name = format!(
+                        "{}",
+                        Render(&(module_id, fn_name.as_ident_str(), ty_args.as_slice()))
+                    );
+                    add_stack(
+                        &mut self.exec_io_stacks,
+                        format!("{};{}", path.join(";"), name),
+                        *cost,
+                    );
+                    add_cost(&mut self.natives, name, *cost);
+                },
+                LoadResource { ty, cost, .. } => {
+                    add_stack(
+                        &mut self.exec_io_stacks,
+                        format!("{};load<{}>", path.join(";"), ty),
+                        *cost,
+                    );
+                    add_cost(&mut self.storage_reads, format!("{}", ty), *cost);
+                },
+            }
+        }
+        add_stack(&mut self.exec_io_stacks, path.join(";"), frame_cost);
+        path.pop();
+    }
+
+    /// Merges another profile into this one.
+    pub fn merge(&mut self, other: &MergedGasProfile) -> Result<()> {
+        if other.num_transactions == 0 {
+            return Ok(());
+        }
+        self.check_gas_scaling_factor(other.gas_scaling_factor)?;
+        self.num_transactions += other.num_transactions;
+
+        self.exec_io_total += other.exec_io_total;
+        self.intrinsic += other.intrinsic;
+        self.keyless += other.keyless;
+        merge_costs(&mut self.dependencies, &other.dependencies);
+        merge_costs(&mut self.instructions, &other.instructions);
+        merge_costs(&mut self.natives, &other.natives);
+        merge_costs(&mut self.storage_reads, &other.storage_reads);
+        merge_costs(&mut self.ledger_writes, &other.ledger_writes);
+
+        self.storage_total += other.storage_total;
+        self.storage_refund += other.storage_refund;
+        merge_costs(&mut self.storage_fees, &other.storage_fees);
+
+        merge_stacks(&mut self.exec_io_stacks, &other.exec_io_stacks);
+        merge_stacks(&mut self.storage_stacks, &other.storage_stacks);
+        Ok(())
+    }
+
+    /// The average of a total over all merged transactions.
+    pub(crate) fn average(&self, total: u64) -> f64 {
+        total as f64 / self.num_transactions.max(1) as f64
+    }
+
+    /// Formats internal gas units as gas units.
+    pub(crate) fn fmt_gas(&self, internal_gas: f64) -> String {
+        let scaled = format!(
+            "{:.8}",
+            internal_gas / self.gas_scaling_factor.max(1) as f64
+        );
+        crate::misc::strip_trailing_zeros_and_decimal_point(&scaled).to_string()
+    }
+
+    /// The tables of costs, with their titles and whether they are storage fees (in octas)
+    /// rather than execution & IO costs (in internal gas units).
+    pub(crate) fn tables(&self) -> [(&'static str, bool, &BTreeMap<String, CostEntry>); 6] {
+        [
+            ("Dependencies", false, &self.dependencies),
+            ("Instructions", false, &self.instructions),
+            ("Natives", false, &self.natives),
+            ("State Reads", false, &self.storage_reads),
+            ("Ledger Writes", false, &self.ledger_writes),
+            ("Storage Fees", true, &self.storage_fees),
+        ]
+    }
+
+    pub fn generate_html_report(&self, path: impl AsRef<Path>, header: String) -> Result<()> {
+        let mut data = Map::new();
+        data.insert("title".to_string(), Value::String(header));
+
+        let fmt_apt = |octas: f64| -> String {
+            let scaled = format!("{:.8}", octas / 1_0000_0000f64);
+            crate::misc::strip_trailing_zeros_and_decimal_point(&scaled).to_string()
+        };
+
+        // Flamegraphs
+        let exec_io_lines = self
+            .exec_io_stacks
+            .iter()
+            .map(|(stack, cost)| format!("{} {}", stack, cost))
+            .collect::<Vec<_>>();
+        let graph_exec_io = merged_flamegraph(
+            &exec_io_lines,
+            "Execution & IO (all transactions)".to_string(),
+            |count| format!("{} gas units", self.fmt_gas(count as f64)),
+        )?;
+        let storage_lines = self
+            .storage_stacks
+            .iter()
+            .map(|(stack, cost)| format!("{} {}", stack, cost))
+            .collect::<Vec<_>>();
+        let graph_storage = merged_flamegraph(
+            &storage_lines,
+            "Storage (all transactions)".to_string(),
+            |count| format!("{} Octa", count),
+        )?;
+        data.insert(
+            "graph-exec-io".to_string(),
+            Value::Bool(graph_exec_io.is_some()),
+        );
+        data.insert(
+            "graph-storage".to_string(),
+            Value::Bool(graph_storage.is_some()),
+        );
+
+        // Summary
+        data.insert("num-transactions".to_string(), json!(self.num_transactions));
+        data.insert(
+            "exec-io-total".to_string(),
+            json!(self.fmt_gas(self.exec_io_total as f64)),
+        );
+        data.insert(
+            "exec-io-average".to_string(),
+            json!(self.fmt_gas(self.average(self.exec_io_total))),
+        );
+        data.insert(
+            "intrinsic-average".to_string(),
+            json!(self.fmt_gas(self.average(self.intrinsic))),
+        );
+        data.insert(
+            "keyless-average".to_string(),
+            json!(self.fmt_gas(self.average(self.keyless))),
+        );
+        data.insert(
+            "storage-total".to_string(),
+            json!(fmt_apt(self.storage_total as f64)),
+        );
+        data.insert(
+            "storage-average".to_string(),
+            json!(fmt_apt(self.average(self.storage_total))),
+        );
+        data.insert(
+            "storage-refund-average".to_string(),
+            json!(fmt_apt(self.average(self.storage_refund))),
+        );
+
+        // Cost tables, sorted by cost
+        let tables = self
+            .tables()
+            .into_iter()
+            .map(|(title, is_storage, entries)| {
+                let (unit, total) = if is_storage {
+                    ("APT", self.storage_total)
+                } else {
+                    ("Gas Units", self.exec_io_total)
+                };
+                let fmt_cost = |cost: f64| {
+                    if is_storage {
+                        fmt_apt(cost)
+                    } else {
+                        self.fmt_gas(cost)
+                    }
+                };
+
+                let mut entries = entries.iter().collect::<Vec<_>>();
+                entries.sort_by(|(_, lhs), (_, rhs)| rhs.cost.cmp(&lhs.cost));
+                let rows = entries
+                    .into_iter()
+                    .map(|(name, entry)| {
+                        json!({
+                            "name": name,
+                            "hits": entry.hits,
+                            "hits-average": format!("{:.2}", self.average(entry.hits)),
+                            "cost": fmt_cost(entry.cost as f64),
+                            "cost-average": fmt_cost(self.average(entry.cost)),
+                            "percentage": if total == 0 {
+                                "/".to_string()
+                            } else {
+                                format!("{:.2}%", entry.cost as f64 / total as f64 * 100.0)
+                            },
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                json!({
+                    "title": title,
+                    "unit": unit,
+                    "rows": rows,
+                })
+            })
+            .collect::<Vec<_>>();
+        data.insert("tables".to_string(), Value::Array(tables));
+
+        // Rendering the html doc
+        let mut handlebars = Handlebars::new();
+        handlebars.register_template_string("merged", TEMPLATE)?;
+        let html = handlebars.render("merged", &data)?;
+
+        // Writing to disk
+        let path_root = path.as_ref();
+
+        ensure_dirs_exist(path_root)?;
+        let path_assets = path_root.join("assets");
+        ensure_dirs_exist(&path_assets)?;
+
+        if let Some(graph_bytes) = graph_exec_io {
+            fs::write(path_assets.join("exec_io.svg"), graph_bytes)?;
+        }
+        if let Some(graph_bytes) = graph_storage {
+            fs::write(path_assets.join("storage.svg"), graph_bytes)?;
+        }
+        fs::write(path_root.join("index.html"), html)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log::{ExecutionAndIOCosts, StorageFees};
+    use aptos_gas_algebra::{Fee, GasScalingFactor, InternalGas};
+    use move_binary_format::file_format_common::Opcodes;
+    use move_core_types::{
+        account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+    };
+
+    fn frame(name: &str, events: Vec<ExecutionGasEvent>) -> CallFrame {
+        let mut frame = CallFrame::new_function(
+            ModuleId::new(AccountAddress::ONE, Identifier::new("m").unwrap()),
+            Identifier::new(name).unwrap(),
+            vec![],
+        );
+        frame.events = events;
+        frame
+    }
+
+    fn bytecode(op: Opcodes, cost: u64) -> ExecutionGasEvent {
+        ExecutionGasEvent::Bytecode {
+            op,
+            cost: InternalGas::new(cost),
+        }
+    }
+
+    fn log(gas_scaling_factor: u64, call_graph: CallFrame, txn_storage: u64) -> TransactionGasLog {
+        let mut exec_io = ExecutionAndIOCosts {
+            gas_scaling_factor: GasScalingFactor::new(gas_scaling_factor),
+            total: InternalGas::new(10),
+            intrinsic_cost: InternalGas::new(10),
+            keyless_cost: InternalGas::new(0),
+            dependencies: vec![],
+            call_graph,
+            transaction_transient: None,
+            events_transient: vec![],
+            write_set_transient: vec![],
+        };
+        let execution_cost = exec_io
+            .gas_events()
+            .map(|event| match event {
+                ExecutionGasEvent::Bytecode { cost, .. } => u64::from(*cost),
+                _ => 0,
+            })
+            .sum::<u64>();
+        exec_io.total += InternalGas::new(execution_cost);
+        TransactionGasLog {
+            exec_io,
+            storage: StorageFees {
+                total: Fee::new(txn_storage),
+                total_refund: Fee::new(0),
+                write_set_storage: vec![],
+                events: vec![],
+                event_discount: Fee::new(0),
+                txn_storage: Fee::new(txn_storage),
+            },
+        }
+    }
+
+    #[test]
+    fn adds_frames_of_multiple_transactions() {
+        let mut profile = MergedGasProfile::new();
+        profile
+            .add(&log(
+                100,
+                frame("f", vec![
+                    bytecode(Opcodes::LD_U64, 2),
+                    ExecutionGasEvent::Call(frame("g", vec![bytecode(Opcodes::ADD, 3)])),
+                    bytecode(Opcodes::RET, 1),
+                ]),
+                50,
+            ))
+            .unwrap();
+        profile
+            .add(&log(
+                100,
+                frame("f", vec![
+                    bytecode(Opcodes::ADD, 4),
+                    bytecode(Opcodes::RET, 1),
+                ]),
+                70,
+            ))
+            .unwrap();
+
+        assert_eq!(profile.num_transactions, 2);
+        assert_eq!(profile.exec_io_total, 31);
+        assert_eq!(profile.intrinsic, 20);
+        assert_eq!(profile.storage_total, 120);
+
+        // Costs of the same frame are folded into a single stack, excluding those of its callees
+        let stacks = profile
+            .exec_io_stacks
+            .iter()
+            .map(|(stack, cost)| (stack.as_str(), *cost))
+            .collect::<Vec<_>>();
+        assert_eq!(stacks, vec![
+            ("0x1::m::f", 8),
+            ("0x1::m::f;0x1::m::g", 3),
+            ("intrinsic", 20),
+        ]);
+        assert_eq!(profile.storage_stacks.get("transaction"), Some(&120));
+
+        assert_eq!(
+            profile.instructions.get("add"),
+            Some(&CostEntry { hits: 2, cost: 7 })
+        );
+        assert_eq!(
+            profile.instructions.get("ret"),
+            Some(&CostEntry { hits: 2, cost: 2 })
+        );
+        assert_eq!(
+            profile.instructions.get("ld_u64"),
+            Some(&CostEntry { hits: 1, cost: 2 })
+        );
+        assert_eq!(
+            profile.storage_fees.get("transaction"),
+            Some(&CostEntry { hits: 2, cost: 120 })
+        );
+    }
+
+    #[test]
+    fn merges_profiles() {
+        let mut lhs = MergedGasProfile::new();
+        lhs.add(&log(100, frame("f", vec![bytecode(Opcodes::ADD, 4)]), 50))
+            .unwrap();
+        let mut rhs = MergedGasProfile::new();
+        rhs.add(&log(100, frame("f", vec![bytecode(Opcodes::ADD, 6)]), 30))
+            .unwrap();
+        rhs.add(&log(100, frame("g", vec![bytecode(Opcodes::MUL, 5)]), 40))
+            .unwrap();
+
+        lhs.merge(&rhs).unwrap();
+        assert_eq!(lhs.num_transactions, 3);
+        assert_eq!(lhs.gas_scaling_factor, 100);
+        assert_eq!(lhs.exec_io_total, 45);
+        assert_eq!(lhs.storage_total, 120);
+        assert_eq!(lhs.exec_io_stacks.get("0x1::m::f"), Some(&10));
+        assert_eq!(lhs.exec_io_stacks.get("0x1::m::g"), Some(&5));
+        assert_eq!(lhs.exec_io_stacks.get("intrinsic"), Some(&30));
+        assert_eq!(
+            lhs.instructions.get("add"),
+            Some(&CostEntry { hits: 2, cost: 10 })
+        );
+        assert_eq!(
+            lhs.instructions.get("mul"),
+            Some(&CostEntry { hits: 1, cost: 5 })
+        );
+
+        // Merging into an empty profile takes over the gas scaling factor
+        let mut empty = MergedGasProfile::new();
+        empty.merge(&lhs).unwrap();
+        assert_eq!(empty.gas_scaling_factor, 100);
+        assert_eq!(empty.exec_io_stacks, lhs.exec_io_stacks);
+    }
+
+    #[test]
+    fn rejects_different_gas_scaling_factors() {
+        let mut profile = MergedGasProfile::new();
+        profile
+            .add(&log(100, frame("f", vec![bytecode(Opcodes::ADD, 4)]), 50))
+            .unwrap();
+        assert!(profile
+            .add(&log(1000, frame("f", vec![bytecode(Opcodes::ADD, 4)]), 50))
+            .is_err());
+
+        let mut other = MergedGasProfile::new();
+        other
+            .add(&log(1000, frame("f", vec![bytecode(Opcodes::ADD, 4)]), 50))
+            .unwrap();
+        assert!(profile.merge(&other).is_err());
+        assert_eq!(profile.num_transactions, 1);
+    }
+
+    #[test]
+    fn computes_averages_per_transaction() {
+        let mut profile = MergedGasProfile::new();
+        assert_eq!(profile.average(10), 10.0);
+
+        for cost in [3, 6] {
+            profile
+                .add(&log(
+                    100,
+                    frame("f", vec![bytecode(Opcodes::ADD, cost)]),
+                    50,
+                ))
+                .unwrap();
+        }
+        assert_eq!(profile.average(profile.exec_io_total), 14.5);
+        assert_eq!(profile.average(profile.storage_total), 50.0);
+        assert_eq!(
+            profile.fmt_gas(profile.average(profile.exec_io_total)),
+            "0.145"
+        );
+    }
+}
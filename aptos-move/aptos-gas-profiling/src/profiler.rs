@@ -43,27 +43,6 @@ pub struct GasProfiler<G> {
     storage_fees: Option<StorageFees>,
 }
 
-// TODO: consider switching to a library like https://docs.rs/delegate/latest/delegate/.
-macro_rules! delegate {
-    ($(
-        fn $fn: ident $(<$($lt: lifetime),*>)? (&self $(, $arg: ident : $ty: ty)* $(,)?) -> $ret_ty: ty;
-    )*) => {
-        $(fn $fn $(<$($lt)*>)? (&self, $($arg: $ty),*) -> $ret_ty {
-            self.base.$fn($($arg),*)
-        })*
-    };
-}
-
-macro_rules! delegate_mut {
-    ($(
-        fn $fn: ident $(<$($lt: lifetime),*>)? (&mut self $(, $arg: ident : $ty: ty)* $(,)?) -> $ret_ty: ty;
-    )*) => {
-        $(fn $fn $(<$($lt)*>)? (&mut self, $($arg: $ty),*) -> $ret_ty {
-            self.base.$fn($($arg),*)
-        })*
-    };
-}
-
 macro_rules! record_bytecode {
     ($(
         $([$op: expr])?
@@ -177,6 +156,16 @@ where
             &mut self,
             locals: impl Iterator<Item = impl ValueView> + Clone,
         ) -> PartialVMResult<()>;
+
+        // Note: we don't use this to charge gas so no need to record anything.
+        fn observe_ret_vals(
+            &mut self,
+            ret_vals: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        ) -> PartialVMResult<()>;
+    }
+
+    delegate! {
+        fn observes_ret_vals(&self) -> bool;
     }
 
     record_bytecode! {
@@ -504,7 +493,7 @@ where
     }
 }
 
-fn write_op_type(op: &WriteOpSize) -> WriteOpType {
+pub(crate) fn write_op_type(op: &WriteOpSize) -> WriteOpType {
     use WriteOpSize as O;
     use WriteOpType as T;
 
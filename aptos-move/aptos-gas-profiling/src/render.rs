@@ -116,6 +116,22 @@ impl<'a> Display for Render<'a, StateKey> {
     }
 }
 
+/// Renders a state key without the account it is stored under, so that the same resource
+/// renders the same across accounts. Used when merging the costs of many transactions.
+pub(crate) struct StateKeyKind<'a>(pub &'a StateKey);
+
+impl<'a> Display for StateKeyKind<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use aptos_types::state_store::state_key::inner::StateKeyInner::*;
+
+        match self.0.inner() {
+            AccessPath(ap) => write!(f, "{}", Render(&ap.get_path())),
+            TableItem { handle, .. } => write!(f, "table_item<{}>", Render(handle)),
+            Raw(..) => write!(f, "raw"),
+        }
+    }
+}
+
 impl<'a> Display for Render<'a, WriteOpType> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use WriteOpType::*;
@@ -0,0 +1,1274 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    log::{FrameName, WriteOpType},
+    profiler::write_op_type,
+    render::Render,
+};
+use aptos_gas_algebra::{Fee, FeePerGasUnit, InternalGas, NumArgs, NumBytes, NumTypeNodes};
+use aptos_gas_meter::AptosGasMeter;
+use aptos_types::{
+    access_path::Path,
+    contract_event::ContractEvent,
+    state_store::state_key::{inner::StateKeyInner, StateKey},
+    write_set::WriteOpSize,
+};
+use move_binary_format::{
+    access::ModuleAccess,
+    errors::{PartialVMResult, VMResult},
+    file_format::{CodeOffset, SignatureToken, StructHandleIndex},
+    CompiledModule,
+};
+use move_bytecode_utils::{compiled_module_viewer::CompiledModuleView, layout::TypeLayoutBuilder};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::{IdentStr, Identifier},
+    language_storage::{ModuleId, StructTag, TypeTag, CORE_CODE_ADDRESS},
+    u256::U256,
+    value::{MoveStructLayout, MoveTypeLayout, MoveValue},
+};
+use move_vm_types::{
+    delayed_values::delayed_field_id::DelayedFieldID,
+    gas::{GasMeter, SimpleInstruction},
+    views::{TypeView, ValueView, ValueVisitor},
+};
+use serde_json::{json, Value as JsonValue};
+use std::{borrow::Borrow, cell::RefCell, collections::HashMap};
+
+/// The maximum number of frames recorded in a trace. Calls beyond are executed, but not traced.
+const MAX_TRACED_FRAMES: usize = 10_000;
+
+/// The maximum number of elements (of vectors and structs, nested ones included) recorded for a
+/// single value. Containers beyond are recorded as truncated.
+const MAX_TRACED_VALUE_SIZE: usize = 1_000;
+
+/// A Move value as seen by the gas meter. Values do not carry their types, so structs are plain
+/// sequences of fields until they get decoded with their type layouts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TracedValue {
+    Bool(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    U256(U256),
+    Address(AccountAddress),
+    Vector(Vec<TracedValue>),
+    Struct(Vec<TracedValue>),
+    /// A delayed field (e.g., the value of an aggregator), only materialized on commit.
+    Delayed,
+    /// A container with more elements than can be traced (see `MAX_TRACED_VALUE_SIZE`).
+    Truncated,
+}
+
+impl TracedValue {
+    pub fn from_view(val: impl ValueView) -> Self {
+        let mut collector = ValueCollector::default();
+        val.visit(&mut collector);
+        collector
+            .result
+            .expect("visiting a value must produce a value")
+    }
+
+    fn from_move_value(val: MoveValue) -> Self {
+        match val {
+            MoveValue::Bool(v) => Self::Bool(v),
+            MoveValue::U8(v) => Self::U8(v),
+            MoveValue::U16(v) => Self::U16(v),
+            MoveValue::U32(v) => Self::U32(v),
+            MoveValue::U64(v) => Self::U64(v),
+            MoveValue::U128(v) => Self::U128(v),
+            MoveValue::U256(v) => Self::U256(v),
+            MoveValue::Address(v) | MoveValue::Signer(v) => Self::Address(v),
+            MoveValue::Vector(elems) => {
+                Self::Vector(elems.into_iter().map(Self::from_move_value).collect())
+            },
+            MoveValue::Struct(s) => {
+                let (variant, fields) = s.optional_variant_and_fields();
+                Self::Struct(
+                    variant
+                        .map(Self::U16)
+                        .into_iter()
+                        .chain(fields.iter().cloned().map(Self::from_move_value))
+                        .collect(),
+                )
+            },
+        }
+    }
+}
+
+/// Rebuilds a value from the visitor callbacks, which traverse it in pre-order. References are
+/// transparent: the visitor descends into the referenced value, which takes the place of the
+/// reference.
+struct ValueCollector {
+    // Containers being built, with their expected number of elements
+    containers: Vec<(bool, usize, Vec<TracedValue>)>,
+    result: Option<TracedValue>,
+    // The number of container elements that can still be recorded
+    remaining: usize,
+    // The depth of the truncated container being visited, whose elements are skipped
+    truncated_depth: Option<usize>,
+}
+
+impl Default for ValueCollector {
+    fn default() -> Self {
+        Self {
+            containers: vec![],
+            result: None,
+            remaining: MAX_TRACED_VALUE_SIZE,
+            truncated_depth: None,
+        }
+    }
+}
+
+impl ValueCollector {
+    /// Returns whether the value at the depth is an element of a truncated container. Visitors
+    /// still visit the elements of some containers (e.g., of byte vectors) they were told to
+    /// skip.
+    fn skip(&mut self, depth: usize) -> bool {
+        match self.truncated_depth {
+            Some(truncated_depth) if depth > truncated_depth => true,
+            _ => {
+                self.truncated_depth = None;
+                false
+            },
+        }
+    }
+
+    fn visit(&mut self, depth: usize, val: TracedValue) {
+        if !self.skip(depth) {
+            self.push(val);
+        }
+    }
+
+    fn push(&mut self, mut val: TracedValue) {
+        loop {
+            let Some((is_struct, len, elems)) = self.containers.last_mut() else {
+                self.result = Some(val);
+                return;
+            };
+            elems.push(val);
+            if elems.len() < *len {
+                return;
+            }
+            let is_struct = *is_struct;
+            let (_, _, elems) = self.containers.pop().expect("container must exist");
+            val = if is_struct {
+                TracedValue::Struct(elems)
+            } else {
+                TracedValue::Vector(elems)
+            };
+        }
+    }
+
+    fn open(&mut self, depth: usize, is_struct: bool, len: usize) -> bool {
+        if self.skip(depth) {
+            return false;
+        }
+        if len > self.remaining {
+            self.push(TracedValue::Truncated);
+            self.truncated_depth = Some(depth);
+            return false;
+        }
+        self.remaining -= len;
+
+        if len == 0 {
+            self.push(
+                if is_struct {
+                    TracedValue::Struct(vec![])
+                } else {
+                    TracedValue::Vector(vec![])
+                },
+            );
+        } else {
+            self.containers
+                .push((is_struct, len, Vec::with_capacity(len)));
+        }
+        true
+    }
+}
+
+impl ValueVisitor for ValueCollector {
+    fn visit_delayed(&mut self, depth: usize, _id: DelayedFieldID) {
+        self.visit(depth, TracedValue::Delayed)
+    }
+
+    fn visit_u8(&mut self, depth: usize, val: u8) {
+        self.visit(depth, TracedValue::U8(val))
+    }
+
+    fn visit_u16(&mut self, depth: usize, val: u16) {
+        self.visit(depth, TracedValue::U16(val))
+    }
+
+    fn visit_u32(&mut self, depth: usize, val: u32) {
+        self.visit(depth, TracedValue::U32(val))
+    }
+
+    fn visit_u64(&mut self, depth: usize, val: u64) {
+        self.visit(depth, TracedValue::U64(val))
+    }
+
+    fn visit_u128(&mut self, depth: usize, val: u128) {
+        self.visit(depth, TracedValue::U128(val))
+    }
+
+    fn visit_u256(&mut self, depth: usize, val: U256) {
+        self.visit(depth, TracedValue::U256(val))
+    }
+
+    fn visit_bool(&mut self, depth: usize, val: bool) {
+        self.visit(depth, TracedValue::Bool(val))
+    }
+
+    fn visit_address(&mut self, depth: usize, val: AccountAddress) {
+        self.visit(depth, TracedValue::Address(val))
+    }
+
+    fn visit_struct(&mut self, depth: usize, len: usize) -> bool {
+        self.open(depth, true, len)
+    }
+
+    fn visit_vec(&mut self, depth: usize, len: usize) -> bool {
+        self.open(depth, false, len)
+    }
+
+    fn visit_ref(&mut self, depth: usize, _is_global: bool) -> bool {
+        !self.skip(depth)
+    }
+}
+
+/// The arguments a frame was called with.
+#[derive(Debug, Clone)]
+pub enum FrameArgs {
+    Values(Vec<TracedValue>),
+    /// The BCS-encoded arguments of the entry function, as found in the transaction payload.
+    /// Signer arguments are not included.
+    Serialized(Vec<Vec<u8>>),
+    Unknown,
+}
+
+#[derive(Debug, Clone)]
+pub struct TracedEvent {
+    pub ty: TypeTag,
+    pub data: TracedValue,
+}
+
+/// A resource loaded from storage. Later accesses to the same resource within the transaction
+/// are served from the cache and are not recorded again.
+#[derive(Debug, Clone)]
+pub struct ResourceRead {
+    pub address: AccountAddress,
+    pub ty: TypeTag,
+    /// `None` if the resource does not exist.
+    pub value: Option<TracedValue>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ResourceWriteKind {
+    MoveTo,
+    MoveFrom,
+    BorrowGlobalMut,
+}
+
+/// A global storage operation that (potentially) modifies a resource.
+///
+/// The VM does not expose the address of global storage operations to the gas meter, so writes
+/// are identified by their type. The written locations are in the write set of the trace.
+#[derive(Debug, Clone)]
+pub struct ResourceWrite {
+    pub kind: ResourceWriteKind,
+    pub ty: TypeTag,
+    /// The published value for `move_to`, the removed value for `move_from`.
+    pub value: Option<TracedValue>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TraceFrame {
+    pub name: FrameName,
+    pub is_native: bool,
+    pub args: FrameArgs,
+    /// `None` if the function did not return, i.e., execution failed in it or in one of its
+    /// callees.
+    pub return_values: Option<Vec<TracedValue>>,
+    pub events: Vec<TracedEvent>,
+    pub reads: Vec<ResourceRead>,
+    pub writes: Vec<ResourceWrite>,
+    pub calls: Vec<TraceFrame>,
+}
+
+impl TraceFrame {
+    fn new(name: FrameName, args: FrameArgs) -> Self {
+        Self {
+            name,
+            is_native: false,
+            args,
+            return_values: None,
+            events: vec![],
+            reads: vec![],
+            writes: vec![],
+            calls: vec![],
+        }
+    }
+
+    fn is_event_native(&self) -> bool {
+        match &self.name {
+            FrameName::Function {
+                module_id, name, ..
+            } => {
+                module_id.address() == &CORE_CODE_ADDRESS
+                    && module_id.name().as_str() == "event"
+                    && (name.as_str() == "write_to_event_store"
+                        || name.as_str() == "write_module_event_to_store")
+            },
+            FrameName::Script => false,
+        }
+    }
+}
+
+/// The structured trace of a transaction: the call tree, with the arguments and return values of
+/// every call, along with the events emitted and the resources accessed by each frame.
+#[derive(Debug, Clone)]
+pub struct ExecutionTrace {
+    pub call_tree: TraceFrame,
+    pub write_set: Vec<(StateKey, WriteOpType)>,
+    /// Whether calls were left out of the call tree (see `MAX_TRACED_FRAMES`).
+    pub truncated: bool,
+}
+
+/// A gas meter adapter that records a structured execution trace, without altering the costs
+/// assessed by the underlying gas meter.
+pub struct ExecutionTracer<G> {
+    base: G,
+
+    frames: Vec<TraceFrame>,
+    write_set: Vec<(StateKey, WriteOpType)>,
+    // The number of frames recorded so far
+    num_frames: usize,
+    // The number of nested calls being executed past the frame limit, which are not traced
+    untraced_depth: usize,
+    truncated: bool,
+}
+
+impl<G> ExecutionTracer<G> {
+    pub fn new_script(base: G) -> Self {
+        Self::new(base, TraceFrame::new(FrameName::Script, FrameArgs::Unknown))
+    }
+
+    pub fn new_function(
+        base: G,
+        module_id: ModuleId,
+        func_name: Identifier,
+        ty_args: Vec<TypeTag>,
+        args: Vec<Vec<u8>>,
+    ) -> Self {
+        let name = FrameName::Function {
+            module_id,
+            name: func_name,
+            ty_args,
+        };
+        Self::new(base, TraceFrame::new(name, FrameArgs::Serialized(args)))
+    }
+
+    fn new(base: G, root: TraceFrame) -> Self {
+        Self {
+            base,
+            frames: vec![root],
+            write_set: vec![],
+            num_frames: 1,
+            untraced_depth: 0,
+            truncated: false,
+        }
+    }
+
+    /// Whether the current call is traced, i.e., it is not past the frame limit
+    fn is_tracing(&self) -> bool {
+        self.untraced_depth == 0
+    }
+
+    fn current_frame(&mut self) -> &mut TraceFrame {
+        self.frames.last_mut().expect("frame must exist")
+    }
+
+    /// Enters a call. The arguments are only recorded if the call is traced.
+    fn enter(
+        &mut self,
+        module_id: &ModuleId,
+        func_name: &str,
+        ty_args: impl FnOnce() -> Vec<TypeTag>,
+        args: impl FnOnce() -> Vec<TracedValue>,
+    ) {
+        if !self.is_tracing() || self.num_frames >= MAX_TRACED_FRAMES {
+            self.untraced_depth += 1;
+            self.truncated = true;
+            return;
+        }
+        self.num_frames += 1;
+
+        let name = FrameName::Function {
+            module_id: module_id.clone(),
+            name: Identifier::new(func_name).unwrap(),
+            ty_args: ty_args(),
+        };
+        self.frames
+            .push(TraceFrame::new(name, FrameArgs::Values(args())));
+    }
+
+    fn exit(&mut self) {
+        if !self.is_tracing() {
+            self.untraced_depth -= 1;
+            return;
+        }
+        if self.frames.len() > 1 {
+            let cur = self.frames.pop().expect("frame must exist");
+            self.current_frame().calls.push(cur);
+        }
+    }
+
+    pub fn finish(mut self) -> ExecutionTrace {
+        while self.frames.len() > 1 {
+            self.exit();
+        }
+        ExecutionTrace {
+            call_tree: self.frames.pop().expect("frame must exist"),
+            write_set: self.write_set,
+            truncated: self.truncated,
+        }
+    }
+}
+
+impl<G> GasMeter for ExecutionTracer<G>
+where
+    G: AptosGasMeter,
+{
+    delegate_mut! {
+        fn charge_br_true(&mut self, target_offset: Option<CodeOffset>) -> PartialVMResult<()>;
+
+        fn charge_br_false(&mut self, target_offset: Option<CodeOffset>) -> PartialVMResult<()>;
+
+        fn charge_branch(&mut self, target_offset: CodeOffset) -> PartialVMResult<()>;
+
+        fn charge_simple_instr(&mut self, instr: SimpleInstruction) -> PartialVMResult<()>;
+
+        fn charge_pop(&mut self, popped_val: impl ValueView) -> PartialVMResult<()>;
+
+        fn charge_ld_const(&mut self, size: NumBytes) -> PartialVMResult<()>;
+
+        fn charge_ld_const_after_deserialization(&mut self, val: impl ValueView)
+            -> PartialVMResult<()>;
+
+        fn charge_copy_loc(&mut self, val: impl ValueView) -> PartialVMResult<()>;
+
+        fn charge_move_loc(&mut self, val: impl ValueView) -> PartialVMResult<()>;
+
+        fn charge_store_loc(&mut self, val: impl ValueView) -> PartialVMResult<()>;
+
+        fn charge_pack(
+            &mut self,
+            is_generic: bool,
+            args: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        ) -> PartialVMResult<()>;
+
+        fn charge_unpack(
+            &mut self,
+            is_generic: bool,
+            args: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        ) -> PartialVMResult<()>;
+
+        fn charge_read_ref(&mut self, val: impl ValueView) -> PartialVMResult<()>;
+
+        fn charge_write_ref(
+            &mut self,
+            new_val: impl ValueView,
+            old_val: impl ValueView,
+        ) -> PartialVMResult<()>;
+
+        fn charge_eq(&mut self, lhs: impl ValueView, rhs: impl ValueView) -> PartialVMResult<()>;
+
+        fn charge_neq(&mut self, lhs: impl ValueView, rhs: impl ValueView) -> PartialVMResult<()>;
+
+        fn charge_exists(
+            &mut self,
+            is_generic: bool,
+            ty: impl TypeView,
+            exists: bool,
+        ) -> PartialVMResult<()>;
+
+        fn charge_vec_pack<'a>(
+            &mut self,
+            ty: impl TypeView + 'a,
+            args: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        ) -> PartialVMResult<()>;
+
+        fn charge_vec_len(&mut self, ty: impl TypeView) -> PartialVMResult<()>;
+
+        fn charge_vec_borrow(
+            &mut self,
+            is_mut: bool,
+            ty: impl TypeView,
+            is_success: bool,
+        ) -> PartialVMResult<()>;
+
+        fn charge_vec_push_back(
+            &mut self,
+            ty: impl TypeView,
+            val: impl ValueView,
+        ) -> PartialVMResult<()>;
+
+        fn charge_vec_pop_back(
+            &mut self,
+            ty: impl TypeView,
+            val: Option<impl ValueView>,
+        ) -> PartialVMResult<()>;
+
+        fn charge_vec_unpack(
+            &mut self,
+            ty: impl TypeView,
+            expect_num_elements: NumArgs,
+            elems: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        ) -> PartialVMResult<()>;
+
+        fn charge_vec_swap(&mut self, ty: impl TypeView) -> PartialVMResult<()>;
+
+        fn charge_native_function_before_execution(
+            &mut self,
+            ty_args: impl ExactSizeIterator<Item = impl TypeView> + Clone,
+            args: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        ) -> PartialVMResult<()>;
+
+        fn charge_drop_frame(
+            &mut self,
+            locals: impl Iterator<Item = impl ValueView> + Clone,
+        ) -> PartialVMResult<()>;
+
+        fn charge_create_ty(&mut self, num_nodes: NumTypeNodes) -> PartialVMResult<()>;
+
+        fn charge_dependency(
+            &mut self,
+            is_new: bool,
+            addr: &AccountAddress,
+            name: &IdentStr,
+            size: NumBytes,
+        ) -> PartialVMResult<()>;
+    }
+
+    fn balance_internal(&self) -> InternalGas {
+        self.base.balance_internal()
+    }
+
+    fn observes_ret_vals(&self) -> bool {
+        true
+    }
+
+    fn charge_call(
+        &mut self,
+        module_id: &ModuleId,
+        func_name: &str,
+        args: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        num_locals: NumArgs,
+    ) -> PartialVMResult<()> {
+        self.enter(module_id, func_name, Vec::new, || {
+            args.clone().map(TracedValue::from_view).collect()
+        });
+
+        self.base
+            .charge_call(module_id, func_name, args, num_locals)
+    }
+
+    fn charge_call_generic(
+        &mut self,
+        module_id: &ModuleId,
+        func_name: &str,
+        ty_args: impl ExactSizeIterator<Item = impl TypeView> + Clone,
+        args: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        num_locals: NumArgs,
+    ) -> PartialVMResult<()> {
+        self.enter(
+            module_id,
+            func_name,
+            || ty_args.clone().map(|ty| ty.to_type_tag()).collect(),
+            || args.clone().map(TracedValue::from_view).collect(),
+        );
+
+        self.base
+            .charge_call_generic(module_id, func_name, ty_args, args, num_locals)
+    }
+
+    fn observe_ret_vals(
+        &mut self,
+        ret_vals: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+    ) -> PartialVMResult<()> {
+        if self.is_tracing() {
+            self.current_frame().return_values =
+                Some(ret_vals.clone().map(TracedValue::from_view).collect());
+        }
+        self.exit();
+
+        self.base.observe_ret_vals(ret_vals)
+    }
+
+    fn charge_native_function(
+        &mut self,
+        amount: InternalGas,
+        ret_vals: Option<impl ExactSizeIterator<Item = impl ValueView> + Clone>,
+    ) -> PartialVMResult<()> {
+        if !self.is_tracing() {
+            if ret_vals.is_some() {
+                self.exit();
+            }
+            return self.base.charge_native_function(amount, ret_vals);
+        }
+
+        // The frame was created by `charge_call/charge_call_generic`, before the VM knew that the
+        // function is native.
+        let cur = self.current_frame();
+        cur.is_native = true;
+        match &ret_vals {
+            Some(vals) => {
+                cur.return_values = Some(vals.clone().map(TracedValue::from_view).collect());
+                if cur.is_event_native() {
+                    self.record_event();
+                }
+                self.exit();
+            },
+            // Either the native failed, which ends execution, or it dispatches to a Move function.
+            // In the latter case, the frame stays on the stack and is popped when the dispatched
+            // function returns.
+            None => (),
+        }
+
+        self.base.charge_native_function(amount, ret_vals)
+    }
+
+    fn charge_load_resource(
+        &mut self,
+        addr: AccountAddress,
+        ty: impl TypeView,
+        val: Option<impl ValueView>,
+        bytes_loaded: NumBytes,
+    ) -> PartialVMResult<()> {
+        if self.is_tracing() {
+            let read = ResourceRead {
+                address: addr,
+                ty: ty.to_type_tag(),
+                value: val.as_ref().map(TracedValue::from_view),
+            };
+            self.current_frame().reads.push(read);
+        }
+
+        self.base.charge_load_resource(addr, ty, val, bytes_loaded)
+    }
+
+    fn charge_borrow_global(
+        &mut self,
+        is_mut: bool,
+        is_generic: bool,
+        ty: impl TypeView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        if is_mut && is_success && self.is_tracing() {
+            self.record_write(ResourceWriteKind::BorrowGlobalMut, &ty, None);
+        }
+
+        self.base
+            .charge_borrow_global(is_mut, is_generic, ty, is_success)
+    }
+
+    fn charge_move_from(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        val: Option<impl ValueView>,
+    ) -> PartialVMResult<()> {
+        match &val {
+            Some(val) if self.is_tracing() => {
+                let value = TracedValue::from_view(val);
+                self.record_write(ResourceWriteKind::MoveFrom, &ty, Some(value));
+            },
+            _ => (),
+        }
+
+        self.base.charge_move_from(is_generic, ty, val)
+    }
+
+    fn charge_move_to(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        val: impl ValueView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        if is_success && self.is_tracing() {
+            let value = TracedValue::from_view(&val);
+            self.record_write(ResourceWriteKind::MoveTo, &ty, Some(value));
+        }
+
+        self.base.charge_move_to(is_generic, ty, val, is_success)
+    }
+}
+
+impl<G> ExecutionTracer<G> {
+    fn record_write(
+        &mut self,
+        kind: ResourceWriteKind,
+        ty: &impl TypeView,
+        value: Option<TracedValue>,
+    ) {
+        let write = ResourceWrite {
+            kind,
+            ty: ty.to_type_tag(),
+            value,
+        };
+        self.current_frame().writes.push(write);
+    }
+
+    /// Records the event emitted by the current (event native) frame on the closest frame outside
+    /// of the event module, i.e., on the function that emitted it.
+    fn record_event(&mut self) {
+        let native = self.frames.last().expect("frame must exist");
+        let (FrameName::Function { ty_args, .. }, FrameArgs::Values(args)) =
+            (&native.name, &native.args)
+        else {
+            return;
+        };
+        let (Some(ty), Some(data)) = (ty_args.first(), args.last()) else {
+            return;
+        };
+        let event = TracedEvent {
+            ty: ty.clone(),
+            data: data.clone(),
+        };
+
+        let event_module = native.name.clone();
+        if let Some(emitter) = self
+            .frames
+            .iter_mut()
+            .rev()
+            .find(|frame| !same_module(&frame.name, &event_module))
+        {
+            emitter.events.push(event);
+        }
+    }
+}
+
+fn same_module(lhs: &FrameName, rhs: &FrameName) -> bool {
+    match (lhs, rhs) {
+        (
+            FrameName::Function {
+                module_id: lhs_module,
+                ..
+            },
+            FrameName::Function {
+                module_id: rhs_module,
+                ..
+            },
+        ) => lhs_module == rhs_module,
+        _ => false,
+    }
+}
+
+impl<G> AptosGasMeter for ExecutionTracer<G>
+where
+    G: AptosGasMeter,
+{
+    type Algebra = G::Algebra;
+
+    delegate! {
+        fn algebra(&self) -> &Self::Algebra;
+    }
+
+    delegate_mut! {
+        fn algebra_mut(&mut self) -> &mut Self::Algebra;
+
+        fn charge_storage_fee(
+            &mut self,
+            amount: Fee,
+            gas_unit_price: FeePerGasUnit,
+        ) -> PartialVMResult<()>;
+
+        fn charge_intrinsic_gas_for_transaction(&mut self, txn_size: NumBytes) -> VMResult<()>;
+
+        fn charge_keyless(&mut self) -> VMResult<()>;
+
+        fn charge_io_gas_for_transaction(&mut self, txn_size: NumBytes) -> VMResult<()>;
+
+        fn charge_io_gas_for_event(&mut self, event: &ContractEvent) -> VMResult<()>;
+    }
+
+    fn charge_io_gas_for_write(&mut self, key: &StateKey, op: &WriteOpSize) -> VMResult<()> {
+        self.write_set.push((key.clone(), write_op_type(op)));
+
+        self.base.charge_io_gas_for_write(key, op)
+    }
+}
+
+impl ExecutionTrace {
+    /// Renders the trace as JSON. Values are decoded with the type layouts resolved from the
+    /// given modules; values whose layout cannot be resolved are rendered without field names.
+    pub fn to_json(&self, modules: &impl CompiledModuleView) -> JsonValue {
+        let decoder = Decoder {
+            modules,
+            layouts: RefCell::new(HashMap::new()),
+        };
+        json!({
+            "call_tree": decoder.frame(&self.call_tree),
+            "truncated": self.truncated,
+            "write_set": self
+                .write_set
+                .iter()
+                .map(|(key, op)| json!({
+                    "state_key": state_key_to_json(key),
+                    "op": format!("{}", Render(op)),
+                }))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+fn state_key_to_json(key: &StateKey) -> JsonValue {
+    match key.inner() {
+        StateKeyInner::AccessPath(ap) => match ap.get_path() {
+            Path::Code(module_id) => json!({
+                "address": ap.address.to_hex_literal(),
+                "module": module_id.name().as_str(),
+            }),
+            Path::Resource(struct_tag) => json!({
+                "address": ap.address.to_hex_literal(),
+                "resource": struct_tag.to_string(),
+            }),
+            Path::ResourceGroup(struct_tag) => json!({
+                "address": ap.address.to_hex_literal(),
+                "resource_group": struct_tag.to_string(),
+            }),
+        },
+        StateKeyInner::TableItem { handle, key } => json!({
+            "table_handle": handle.0.to_hex_literal(),
+            "key": format!("0x{}", hex::encode(key)),
+        }),
+        StateKeyInner::Raw(bytes) => json!({
+            "raw": format!("0x{}", hex::encode(bytes)),
+        }),
+    }
+}
+
+/// The parameter and return types of a function, `None` where they cannot be expressed as type
+/// tags.
+type FunctionSignature = (Vec<Option<TypeTag>>, Vec<Option<TypeTag>>);
+
+struct Decoder<'a, V> {
+    modules: &'a V,
+    // Type layouts, `None` if they cannot be built
+    layouts: RefCell<HashMap<TypeTag, Option<MoveTypeLayout>>>,
+}
+
+impl<'a, V: CompiledModuleView> Decoder<'a, V> {
+    fn frame(&self, frame: &TraceFrame) -> JsonValue {
+        let (function, ty_args) = match &frame.name {
+            FrameName::Script => ("<script>".to_string(), vec![]),
+            FrameName::Function {
+                module_id,
+                name,
+                ty_args,
+            } => (
+                format!("{}::{}", module_id.short_str_lossless(), name),
+                ty_args.clone(),
+            ),
+        };
+        let (param_tys, return_tys) = match &frame.name {
+            FrameName::Script => None,
+            FrameName::Function {
+                module_id,
+                name,
+                ty_args,
+            } => self.function_signature(module_id, name, ty_args),
+        }
+        .unwrap_or_default();
+
+        let args = match &frame.args {
+            FrameArgs::Values(args) => JsonValue::Array(self.values(args, &param_tys)),
+            FrameArgs::Serialized(args) => {
+                // Signers are not part of the transaction payload
+                let param_tys = param_tys
+                    .into_iter()
+                    .skip_while(|ty| matches!(ty, Some(TypeTag::Signer)))
+                    .collect::<Vec<_>>();
+                JsonValue::Array(
+                    args.iter()
+                        .enumerate()
+                        .map(|(idx, arg)| {
+                            self.serialized_value(arg, param_tys.get(idx).cloned().flatten())
+                        })
+                        .collect(),
+                )
+            },
+            FrameArgs::Unknown => JsonValue::Null,
+        };
+        let return_values = frame
+            .return_values
+            .as_ref()
+            .map(|vals| JsonValue::Array(self.values(vals, &return_tys)))
+            .unwrap_or(JsonValue::Null);
+
+        json!({
+            "function": function,
+            "type_args": ty_args.iter().map(|ty| ty.to_string()).collect::<Vec<_>>(),
+            "native": frame.is_native,
+            "args": args,
+            "return_values": return_values,
+            "events": frame
+                .events
+                .iter()
+                .map(|event| json!({
+                    "type": event.ty.to_string(),
+                    "data": self.value(&event.data, Some(&event.ty)),
+                }))
+                .collect::<Vec<_>>(),
+            "resource_reads": frame
+                .reads
+                .iter()
+                .map(|read| json!({
+                    "address": read.address.to_hex_literal(),
+                    "type": read.ty.to_string(),
+                    "value": read
+                        .value
+                        .as_ref()
+                        .map(|val| self.value(val, Some(&read.ty)))
+                        .unwrap_or(JsonValue::Null),
+                }))
+                .collect::<Vec<_>>(),
+            "resource_writes": frame
+                .writes
+                .iter()
+                .map(|write| json!({
+                    "kind": match write.kind {
+                        ResourceWriteKind::MoveTo => "move_to",
+                        ResourceWriteKind::MoveFrom => "move_from",
+                        ResourceWriteKind::BorrowGlobalMut => "borrow_global_mut",
+                    },
+                    "type": write.ty.to_string(),
+                    "value": write
+                        .value
+                        .as_ref()
+                        .map(|val| self.value(val, Some(&write.ty)))
+                        .unwrap_or(JsonValue::Null),
+                }))
+                .collect::<Vec<_>>(),
+            "calls": frame.calls.iter().map(|call| self.frame(call)).collect::<Vec<_>>(),
+        })
+    }
+
+    fn values(&self, vals: &[TracedValue], tys: &[Option<TypeTag>]) -> Vec<JsonValue> {
+        vals.iter()
+            .enumerate()
+            .map(|(idx, val)| self.value(val, tys.get(idx).and_then(|ty| ty.as_ref())))
+            .collect()
+    }
+
+    fn value(&self, val: &TracedValue, ty: Option<&TypeTag>) -> JsonValue {
+        let layout = ty.and_then(|ty| self.layout(ty));
+        value_to_json(val, layout.as_ref())
+    }
+
+    fn serialized_value(&self, bytes: &[u8], ty: Option<TypeTag>) -> JsonValue {
+        let decoded = ty.as_ref().and_then(|ty| {
+            let layout = TypeLayoutBuilder::build_runtime(ty, self.modules).ok()?;
+            MoveValue::simple_deserialize(bytes, &layout).ok()
+        });
+        match decoded {
+            Some(val) => self.value(&TracedValue::from_move_value(val), ty.as_ref()),
+            None => json!(format!("0x{}", hex::encode(bytes))),
+        }
+    }
+
+    fn layout(&self, ty: &TypeTag) -> Option<MoveTypeLayout> {
+        if let TypeTag::Signer = ty {
+            return Some(MoveTypeLayout::Signer);
+        }
+        self.layouts
+            .borrow_mut()
+            .entry(ty.clone())
+            .or_insert_with(|| TypeLayoutBuilder::build_with_types(ty, self.modules).ok())
+            .clone()
+    }
+
+    fn function_signature(
+        &self,
+        module_id: &ModuleId,
+        name: &IdentStr,
+        ty_args: &[TypeTag],
+    ) -> Option<FunctionSignature> {
+        let module = self.modules.view_compiled_module(module_id).ok()??;
+        let module: &CompiledModule = module.borrow();
+        let handle = module
+            .function_defs()
+            .iter()
+            .map(|def| module.function_handle_at(def.function))
+            .find(|handle| module.identifier_at(handle.name) == name)?;
+        let to_tags = |tokens: &[SignatureToken]| {
+            tokens
+                .iter()
+                .map(|token| signature_to_type_tag(module, token, ty_args))
+                .collect::<Vec<_>>()
+        };
+        Some((
+            to_tags(&module.signature_at(handle.parameters).0),
+            to_tags(&module.signature_at(handle.return_).0),
+        ))
+    }
+}
+
+/// Converts a signature token to the type tag of the value it describes; references are
+/// replaced with the types they refer to.
+fn signature_to_type_tag(
+    module: &CompiledModule,
+    token: &SignatureToken,
+    ty_args: &[TypeTag],
+) -> Option<TypeTag> {
+    use SignatureToken as S;
+
+    Some(match token {
+        S::Bool => TypeTag::Bool,
+        S::U8 => TypeTag::U8,
+        S::U16 => TypeTag::U16,
+        S::U32 => TypeTag::U32,
+        S::U64 => TypeTag::U64,
+        S::U128 => TypeTag::U128,
+        S::U256 => TypeTag::U256,
+        S::Address => TypeTag::Address,
+        S::Signer => TypeTag::Signer,
+        S::Vector(elem) => TypeTag::Vector(Box::new(signature_to_type_tag(module, elem, ty_args)?)),
+        S::Struct(idx) => TypeTag::Struct(Box::new(struct_tag(module, *idx, vec![]))),
+        S::StructInstantiation(idx, tokens) => {
+            let type_args = tokens
+                .iter()
+                .map(|token| signature_to_type_tag(module, token, ty_args))
+                .collect::<Option<Vec<_>>>()?;
+            TypeTag::Struct(Box::new(struct_tag(module, *idx, type_args)))
+        },
+        S::Reference(inner) | S::MutableReference(inner) => {
+            signature_to_type_tag(module, inner, ty_args)?
+        },
+        S::TypeParameter(idx) => ty_args.get(*idx as usize)?.clone(),
+    })
+}
+
+fn struct_tag(
+    module: &CompiledModule,
+    idx: StructHandleIndex,
+    type_args: Vec<TypeTag>,
+) -> StructTag {
+    let handle = module.struct_handle_at(idx);
+    let module_handle = module.module_handle_at(handle.module);
+    StructTag {
+        address: *module.address_identifier_at(module_handle.address),
+        module: module.identifier_at(module_handle.name).to_owned(),
+        name: module.identifier_at(handle.name).to_owned(),
+        type_args,
+    }
+}
+
+/// Renders a value as JSON, in the conventions of the REST API: 64 bits and larger integers are
+/// strings, byte vectors are hex strings, and structs are objects keyed by field names when the
+/// layout is known.
+fn value_to_json(val: &TracedValue, layout: Option<&MoveTypeLayout>) -> JsonValue {
+    use TracedValue as V;
+
+    match (val, layout) {
+        (V::Bool(v), _) => json!(v),
+        (V::U8(v), _) => json!(v),
+        (V::U16(v), _) => json!(v),
+        (V::U32(v), _) => json!(v),
+        (V::U64(v), _) => json!(v.to_string()),
+        (V::U128(v), _) => json!(v.to_string()),
+        (V::U256(v), _) => json!(v.to_string()),
+        (V::Address(v), _) => json!(v.to_hex_literal()),
+        (V::Delayed, _) => json!("<delayed>"),
+        (V::Truncated, _) => json!("<truncated>"),
+        (V::Vector(elems), Some(MoveTypeLayout::Vector(elem_layout))) => {
+            match (elem_layout.as_ref(), as_bytes(elems)) {
+                (MoveTypeLayout::U8, Some(bytes)) => json!(format!("0x{}", hex::encode(bytes))),
+                _ => JsonValue::Array(
+                    elems
+                        .iter()
+                        .map(|elem| value_to_json(elem, Some(elem_layout)))
+                        .collect(),
+                ),
+            }
+        },
+        (V::Vector(elems), _) => {
+            JsonValue::Array(elems.iter().map(|elem| value_to_json(elem, None)).collect())
+        },
+        // A signer holds the address it stands for
+        (V::Struct(fields), Some(MoveTypeLayout::Signer)) => fields
+            .iter()
+            .find(|field| matches!(field, V::Address(_)))
+            .map(|addr| value_to_json(addr, None))
+            .unwrap_or(JsonValue::Null),
+        (V::Struct(fields), Some(MoveTypeLayout::Struct(struct_layout))) => {
+            struct_to_json(fields, struct_layout)
+        },
+        (V::Struct(fields), _) => JsonValue::Array(
+            fields
+                .iter()
+                .map(|field| value_to_json(field, None))
+                .collect(),
+        ),
+    }
+}
+
+fn struct_to_json(fields: &[TracedValue], layout: &MoveStructLayout) -> JsonValue {
+    let field_layouts = match layout {
+        MoveStructLayout::WithTypes {
+            type_,
+            fields: field_layouts,
+        } => {
+            // Strings are rendered as such, as in the REST API
+            if type_.is_std_string(&CORE_CODE_ADDRESS) {
+                if let Some(bytes) = fields.first().and_then(|field| match field {
+                    TracedValue::Vector(elems) => as_bytes(elems),
+                    _ => None,
+                }) {
+                    return json!(String::from_utf8_lossy(&bytes));
+                }
+            }
+            field_layouts
+        },
+        MoveStructLayout::WithFields(field_layouts) => field_layouts,
+        _ => return value_to_json(&TracedValue::Struct(fields.to_vec()), None),
+    };
+    if field_layouts.len() != fields.len() {
+        return value_to_json(&TracedValue::Struct(fields.to_vec()), None);
+    }
+    JsonValue::Object(
+        field_layouts
+            .iter()
+            .zip(fields)
+            .map(|(field_layout, field)| {
+                (
+                    field_layout.name.to_string(),
+                    value_to_json(field, Some(&field_layout.layout)),
+                )
+            })
+            .collect(),
+    )
+}
+
+fn as_bytes(elems: &[TracedValue]) -> Option<Vec<u8>> {
+    elems
+        .iter()
+        .map(|elem| match elem {
+            TracedValue::U8(byte) => Some(*byte),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::value::MoveFieldLayout;
+
+    fn struct_layout(name: &str, fields: Vec<(&str, MoveTypeLayout)>) -> MoveTypeLayout {
+        MoveTypeLayout::Struct(MoveStructLayout::WithTypes {
+            type_: StructTag {
+                address: AccountAddress::ONE,
+                module: Identifier::new("test").unwrap(),
+                name: Identifier::new(name).unwrap(),
+                type_args: vec![],
+            },
+            fields: fields
+                .into_iter()
+                .map(|(name, layout)| MoveFieldLayout::new(Identifier::new(name).unwrap(), layout))
+                .collect(),
+        })
+    }
+
+    #[test]
+    fn test_collect_nested_values() {
+        let mut collector = ValueCollector::default();
+        collector.visit_struct(0, 3);
+        collector.visit_u64(1, 7);
+        collector.visit_vec_u8(1, &[1, 2]);
+        collector.visit_struct(1, 0);
+
+        assert_eq!(
+            collector.result,
+            Some(TracedValue::Struct(vec![
+                TracedValue::U64(7),
+                TracedValue::Vector(vec![TracedValue::U8(1), TracedValue::U8(2)]),
+                TracedValue::Struct(vec![]),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_value_to_json_with_layout() {
+        let layout = struct_layout("Coin", vec![
+            ("value", MoveTypeLayout::U64),
+            ("memo", MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U8))),
+            ("owner", MoveTypeLayout::Address),
+        ]);
+        let val = TracedValue::Struct(vec![
+            TracedValue::U64(100),
+            TracedValue::Vector(vec![TracedValue::U8(0xAB), TracedValue::U8(0xCD)]),
+            TracedValue::Address(AccountAddress::ONE),
+        ]);
+
+        assert_eq!(
+            value_to_json(&val, Some(&layout)),
+            json!({ "value": "100", "memo": "0xabcd", "owner": "0x1" })
+        );
+        assert_eq!(value_to_json(&val, None), json!(["100", [171, 205], "0x1"]));
+    }
+
+    #[test]
+    fn test_value_to_json_mismatched_layout() {
+        let layout = struct_layout("Pair", vec![("first", MoveTypeLayout::U8)]);
+        let val = TracedValue::Struct(vec![TracedValue::U8(1), TracedValue::U8(2)]);
+
+        assert_eq!(value_to_json(&val, Some(&layout)), json!([1, 2]));
+    }
+
+    #[test]
+    fn test_collect_truncated_values() {
+        let mut collector = ValueCollector::default();
+        collector.visit_struct(0, 3);
+        collector.visit_vec_u8(1, &[0; MAX_TRACED_VALUE_SIZE]);
+        collector.visit_u64(1, 7);
+        collector.visit_vec_u8(1, &[1]);
+
+        assert_eq!(
+            collector.result,
+            Some(TracedValue::Struct(vec![
+                TracedValue::Truncated,
+                TracedValue::U64(7),
+                TracedValue::Vector(vec![TracedValue::U8(1)]),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_trace_frame_limit() {
+        let module_id = ModuleId::new(AccountAddress::ONE, Identifier::new("test").unwrap());
+        let mut tracer = ExecutionTracer::new_script(());
+        for _ in 0..MAX_TRACED_FRAMES + 10 {
+            tracer.enter(&module_id, "f", Vec::new, Vec::new);
+        }
+        for _ in 0..MAX_TRACED_FRAMES + 10 {
+            tracer.exit();
+        }
+        let trace = tracer.finish();
+
+        assert!(trace.truncated);
+        let mut num_frames = 1;
+        let mut frame = &trace.call_tree;
+        while let Some(call) = frame.calls.first() {
+            assert_eq!(frame.calls.len(), 1);
+            num_frames += 1;
+            frame = call;
+        }
+        assert_eq!(num_frames, MAX_TRACED_FRAMES);
+    }
+}
@@ -117,6 +117,11 @@ where
 
         fn charge_ld_const(&mut self, size: NumBytes) -> PartialVMResult<()>;
 
+        fn observe_ret_vals(
+            &mut self,
+            ret_vals: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+        ) -> PartialVMResult<()>;
+
         fn charge_move_loc(&mut self, val: impl ValueView) -> PartialVMResult<()>;
 
         fn charge_store_loc(&mut self, val: impl ValueView) -> PartialVMResult<()>;
@@ -168,6 +173,10 @@ where
         fn charge_dependency(&mut self, is_new: bool, addr: &AccountAddress, name: &IdentStr, size: NumBytes) -> PartialVMResult<()>;
     }
 
+    delegate! {
+        fn observes_ret_vals(&self) -> bool;
+    }
+
     #[inline]
     fn balance_internal(&self) -> InternalGas {
         self.base.balance_internal()
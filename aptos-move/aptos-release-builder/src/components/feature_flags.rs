@@ -127,6 +127,8 @@ pub enum FeatureFlag {
     EnableEnumTypes,
     EnableResourceAccessControl,
     RejectUnstableBytecodeForScript,
+    ResourceAccessMetadata,
+    EquivocationReports,
 }
 
 fn generate_features_blob(writer: &CodeWriter, data: &[u64]) {
@@ -336,6 +338,8 @@ impl From<FeatureFlag> for AptosFeatureFlag {
             FeatureFlag::RejectUnstableBytecodeForScript => {
                 AptosFeatureFlag::REJECT_UNSTABLE_BYTECODE_FOR_SCRIPT
             },
+            FeatureFlag::ResourceAccessMetadata => AptosFeatureFlag::RESOURCE_ACCESS_METADATA,
+            FeatureFlag::EquivocationReports => AptosFeatureFlag::EQUIVOCATION_REPORTS,
         }
     }
 }
@@ -474,6 +478,8 @@ impl From<AptosFeatureFlag> for FeatureFlag {
             AptosFeatureFlag::REJECT_UNSTABLE_BYTECODE_FOR_SCRIPT => {
                 FeatureFlag::RejectUnstableBytecodeForScript
             },
+            AptosFeatureFlag::RESOURCE_ACCESS_METADATA => FeatureFlag::ResourceAccessMetadata,
+            AptosFeatureFlag::EQUIVOCATION_REPORTS => FeatureFlag::EquivocationReports,
         }
     }
 }
@@ -121,6 +121,8 @@ static EXECUTION_CONCURRENCY_LEVEL: OnceCell<usize> = OnceCell::new();
 static NUM_EXECUTION_SHARD: OnceCell<usize> = OnceCell::new();
 static NUM_PROOF_READING_THREADS: OnceCell<usize> = OnceCell::new();
 static DISCARD_FAILED_BLOCKS: OnceCell<bool> = OnceCell::new();
+static CONFLICT_REPORTS: OnceCell<bool> = OnceCell::new();
+static PREFETCH_READ_SETS: OnceCell<bool> = OnceCell::new();
 static PROCESSED_TRANSACTIONS_DETAILED_COUNTERS: OnceCell<bool> = OnceCell::new();
 
 macro_rules! deprecated_module_bundle {
@@ -343,6 +345,36 @@ impl AptosVM {
         }
     }
 
+    /// Sets whether parallel execution publishes a conflict report for every block, when
+    /// invoked the first time.
+    pub fn set_conflict_reports(enable: bool) {
+        // Only the first call succeeds, due to OnceCell semantics.
+        CONFLICT_REPORTS.set(enable).ok();
+    }
+
+    /// Get the conflict reports flag if already set, otherwise return default (false)
+    pub fn get_conflict_reports() -> bool {
+        match CONFLICT_REPORTS.get() {
+            Some(enable) => *enable,
+            None => false,
+        }
+    }
+
+    /// Sets whether the state that transactions are predicted to read is prefetched before
+    /// executing a block, when invoked the first time.
+    pub fn set_prefetch_read_sets(enable: bool) {
+        // Only the first call succeeds, due to OnceCell semantics.
+        PREFETCH_READ_SETS.set(enable).ok();
+    }
+
+    /// Get the read set prefetching flag if already set, otherwise return default (false)
+    pub fn get_prefetch_read_sets() -> bool {
+        match PREFETCH_READ_SETS.get() {
+            Some(enable) => *enable,
+            None => false,
+        }
+    }
+
     /// Sets the # of async proof reading threads.
     pub fn set_num_proof_reading_threads_once(mut num_threads: usize) {
         // TODO(grao): Do more analysis to tune this magic number.
@@ -2568,6 +2600,8 @@ impl VMExecutor for AptosVM {
                     concurrency_level: Self::get_concurrency_level(),
                     allow_fallback: true,
                     discard_failed_blocks: Self::get_discard_failed_blocks(),
+                    conflict_report: Self::get_conflict_reports(),
+                    prefetch_read_sets: Self::get_prefetch_read_sets(),
                 },
                 onchain: onchain_config,
             },
@@ -2745,6 +2779,47 @@ impl AptosSimulationVM {
             .expect("Materializing aggregator V1 deltas should never fail");
         (vm_status, txn_output)
     }
+
+    /// Same as `create_vm_and_simulate_signed_transaction`, but with a gas meter derived from the
+    /// production one, e.g., to observe the execution. The gas meter is not returned if the
+    /// transaction is discarded before being executed.
+    /// *Precondition:* the transaction must **not** have a valid signature.
+    pub fn create_vm_and_simulate_signed_transaction_with_modified_gas_meter<G, F>(
+        transaction: &SignedTransaction,
+        state_view: &impl StateView,
+        modify_gas_meter: F,
+    ) -> (VMStatus, TransactionOutput, Option<G>)
+    where
+        F: FnOnce(ProdGasMeter) -> G,
+        G: AptosGasMeter,
+    {
+        assert_err!(
+            transaction.verify_signature(),
+            "Simulated transaction should not have a valid signature"
+        );
+
+        let vm = Self::new(state_view);
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+
+        let resolver = state_view.as_move_resolver();
+        let (vm_status, vm_output, gas_meter) =
+            match vm.0.execute_user_transaction_with_modified_gas_meter(
+                &resolver,
+                transaction,
+                &log_context,
+                modify_gas_meter,
+            ) {
+                Ok((vm_status, vm_output, gas_meter)) => (vm_status, vm_output, Some(gas_meter)),
+                Err(vm_status) => {
+                    let vm_output = discarded_output(vm_status.status_code());
+                    (vm_status, vm_output, None)
+                },
+            };
+        let txn_output = vm_output
+            .try_materialize_into_transaction_output(&resolver)
+            .expect("Materializing aggregator V1 deltas should never fail");
+        (vm_status, txn_output, gas_meter)
+    }
 }
 
 fn create_account_if_does_not_exist(
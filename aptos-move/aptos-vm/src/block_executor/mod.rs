@@ -12,7 +12,7 @@ use aptos_aggregator::{
     delayed_change::DelayedChange, delta_change_set::DeltaOp, resolver::TAggregatorV1View,
 };
 use aptos_block_executor::{
-    errors::BlockExecutionError, executor::BlockExecutor,
+    errors::BlockExecutionError, executor::BlockExecutor, prefetch::ReadSetPrefetcher,
     task::TransactionOutput as BlockExecutorTransactionOutput,
     txn_commit_hook::TransactionCommitHook, types::InputOutputKey,
 };
@@ -57,6 +57,11 @@ pub static RAYON_EXEC_POOL: Lazy<Arc<rayon::ThreadPool>> = Lazy::new(|| {
     )
 });
 
+/// Shared across blocks, so that the reads of previous blocks are used to predict the reads of
+/// the next ones.
+static READ_SET_PREFETCHER: Lazy<Arc<ReadSetPrefetcher<StateKey>>> =
+    Lazy::new(|| Arc::new(ReadSetPrefetcher::default()));
+
 /// Output type wrapper used by block executor. VM output is stored first, then
 /// transformed into TransactionOutput type that is returned.
 #[derive(Debug)]
@@ -403,13 +408,17 @@ impl BlockAptosVM {
         }
 
         BLOCK_EXECUTOR_CONCURRENCY.set(config.local.concurrency_level as i64);
-        let executor = BlockExecutor::<
+        let prefetch_read_sets = config.local.prefetch_read_sets;
+        let mut executor = BlockExecutor::<
             SignatureVerifiedTransaction,
             AptosExecutorTask,
             S,
             L,
             ExecutableTestType,
         >::new(config, executor_thread_pool, transaction_commit_listener);
+        if prefetch_read_sets {
+            executor = executor.with_read_set_prefetcher(READ_SET_PREFETCHER.clone());
+        }
 
         let environment =
             Arc::new(Environment::new(state_view).try_enable_delayed_field_optimization());
@@ -116,6 +116,7 @@ mod keyless_validation;
 pub mod keyless_validation;
 pub mod move_vm_ext;
 pub mod natives;
+pub mod resource_access_hints;
 pub mod sharded_block_executor;
 pub mod system_module_names;
 pub mod testing;
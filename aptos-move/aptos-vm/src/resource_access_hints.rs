@@ -0,0 +1,139 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Read/write hints for the block partitioner, derived from the resources which entry functions
+//! declare to access in their module metadata (see `KnownAttribute::resource_access`).
+
+use aptos_framework::get_metadata_from_compiled_module;
+pub use aptos_framework::ResourceAccess;
+use aptos_types::{
+    state_store::{state_key::StateKey, StateView},
+    transaction::{
+        analyzed_transaction::{
+            account_resource_location, aptos_coin_info_location, chain_id_location,
+            coin_store_location, current_ts_location, features_location,
+            primary_fungible_store_location, transaction_fee_burn_cap_location,
+            AnalyzedTransaction, StorageLocation,
+        },
+        signature_verified_transaction::SignatureVerifiedTransaction,
+        EntryFunction, SignedTransaction, Transaction, TransactionPayload,
+    },
+};
+use move_binary_format::CompiledModule;
+use std::collections::BTreeSet;
+
+/// Analyzes the transaction, taking its read/write hints from the resource access metadata of
+/// the called entry function if available, and falling back to [AnalyzedTransaction::new]
+/// otherwise. The metadata isn't verified against the bytecode when the module is published, so
+/// the hints taken from it are advisory: the sharded block executor checks that the transaction
+/// respects them, and executes the block unsharded if it doesn't.
+pub fn analyze_transaction(
+    txn: SignatureVerifiedTransaction,
+    state_view: &impl StateView,
+) -> AnalyzedTransaction {
+    let hints = match &txn {
+        SignatureVerifiedTransaction::Valid(Transaction::UserTransaction(signed_txn)) => {
+            resource_access_hints(signed_txn, state_view)
+        },
+        _ => None,
+    };
+    match hints {
+        Some((read_hints, write_hints)) => {
+            AnalyzedTransaction::new_with_advisory_hints(txn, read_hints, write_hints)
+        },
+        None => AnalyzedTransaction::new(txn),
+    }
+}
+
+/// The read/write hints of an entry function call, if its module declares the resources the
+/// function accesses, and their state keys can be determined from the transaction.
+pub fn resource_access_hints(
+    signed_txn: &SignedTransaction,
+    state_view: &impl StateView,
+) -> Option<(Vec<StorageLocation>, Vec<StorageLocation>)> {
+    let TransactionPayload::EntryFunction(entry_fun) = signed_txn.payload() else {
+        return None;
+    };
+    let accesses = declared_resource_accesses(entry_fun, state_view)?;
+    let accessed_keys = accessed_state_keys(signed_txn, &accesses)?;
+
+    let mut write_hints = fee_payment_locations(signed_txn);
+    let mut read_hints = vec![
+        current_ts_location(),
+        features_location(),
+        aptos_coin_info_location(),
+        chain_id_location(),
+        transaction_fee_burn_cap_location(),
+    ];
+    let mut writes = write_hints
+        .iter()
+        .map(|location| location.state_key().clone())
+        .collect::<BTreeSet<_>>();
+
+    let mut reads = BTreeSet::new();
+    for (state_key, write) in accessed_keys {
+        if write {
+            if writes.insert(state_key.clone()) {
+                write_hints.push(StorageLocation::Specific(state_key));
+            }
+        } else {
+            reads.insert(state_key);
+        }
+    }
+    // Locations which are written are not repeated in the read hints.
+    read_hints.retain(|location| !writes.contains(location.state_key()));
+    read_hints.extend(
+        reads
+            .into_iter()
+            .filter(|state_key| !writes.contains(state_key))
+            .map(StorageLocation::Specific),
+    );
+    Some((read_hints, write_hints))
+}
+
+/// The locations which the prologue and epilogue of the transaction write, to bump the sequence
+/// number of the sender and to charge gas. The gas payer pays either from its coin store or, once
+/// migrated to fungible assets, from its primary fungible store, so both are included.
+pub fn fee_payment_locations(signed_txn: &SignedTransaction) -> Vec<StorageLocation> {
+    let gas_payer = signed_txn
+        .authenticator_ref()
+        .fee_payer_address()
+        .unwrap_or(signed_txn.sender());
+    vec![
+        account_resource_location(signed_txn.sender()),
+        coin_store_location(gas_payer),
+        primary_fungible_store_location(gas_payer),
+    ]
+}
+
+/// The resources which the entry function declares to access in the metadata of its module.
+pub fn declared_resource_accesses(
+    entry_fun: &EntryFunction,
+    state_view: &impl StateView,
+) -> Option<Vec<ResourceAccess>> {
+    let module_bytes = state_view
+        .get_state_value_bytes(&StateKey::module_id(entry_fun.module()))
+        .ok()??;
+    let module = CompiledModule::deserialize(&module_bytes).ok()?;
+    get_metadata_from_compiled_module(&module)?.get_resource_access(entry_fun.function().as_str())
+}
+
+/// The state keys of the declared resource accesses of the entry function call of the
+/// transaction, with whether they are written, if they can all be determined from the transaction.
+pub fn accessed_state_keys(
+    signed_txn: &SignedTransaction,
+    accesses: &[ResourceAccess],
+) -> Option<Vec<(StateKey, bool)>> {
+    let TransactionPayload::EntryFunction(entry_fun) = signed_txn.payload() else {
+        return None;
+    };
+    let mut signers = vec![signed_txn.sender()];
+    signers.extend(signed_txn.authenticator_ref().secondary_signer_addresses());
+    accesses
+        .iter()
+        .map(|access| {
+            let state_key = access.state_key(&signers, entry_fun.args(), entry_fun.ty_args())?;
+            Some((state_key, access.write))
+        })
+        .collect()
+}
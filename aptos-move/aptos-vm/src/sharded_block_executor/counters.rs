@@ -3,8 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_metrics_core::{
-    exponential_buckets, register_histogram, register_histogram_vec, register_int_gauge, Histogram,
-    HistogramVec, IntGauge,
+    exponential_buckets, register_histogram, register_histogram_vec, register_int_counter,
+    register_int_gauge, Histogram, HistogramVec, IntCounter, IntGauge,
 };
 use once_cell::sync::Lazy;
 
@@ -72,3 +72,12 @@ pub static SHARDED_EXECUTOR_SERVICE_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static SHARDED_EXECUTION_FALLBACK_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "sharded_execution_fallback_count",
+        "Number of blocks executed unsharded as their transactions didn't respect their advisory \
+         read/write hints"
+    )
+    .unwrap()
+});
@@ -9,7 +9,7 @@ use crate::{
     },
 };
 use aptos_block_executor::txn_commit_hook::TransactionCommitHook;
-use aptos_logger::trace;
+use aptos_logger::{trace, warn};
 use aptos_mvhashmap::types::TxnIndex;
 use aptos_types::{
     block_executor::partitioner::{RoundId, ShardId, SubBlock, GLOBAL_ROUND_ID},
@@ -42,6 +42,11 @@ impl CrossShardCommitReceiver {
                     trace!("Cross shard commit receiver stopped for round {}", round);
                     break;
                 },
+                CrossShardMsg::AbortMsg => {
+                    warn!("Cross shard commit receiver aborted for round {}", round);
+                    cross_shard_state_view.abort();
+                    break;
+                },
             }
         }
     }
@@ -4,22 +4,29 @@ use crate::sharded_block_executor::remote_state_value::RemoteStateValue;
 use anyhow::Result;
 use aptos_logger::trace;
 use aptos_types::{
-    block_executor::partitioner::TransactionWithDependencies,
+    block_executor::partitioner::{HintedWrites, RoundId, ShardId, TransactionWithDependencies},
     state_store::{
         errors::StateviewError, state_key::StateKey, state_storage_usage::StateStorageUsage,
         state_value::StateValue, StateView, TStateView,
     },
     transaction::analyzed_transaction::AnalyzedTransaction,
 };
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 /// A state view for reading cross shard state values. It is backed by a state view
 /// and a hashmap of cross shard state keys. When a cross shard state value is not
 /// available in the hashmap, it will be fetched from the underlying base view.
-#[derive(Clone)]
 pub struct CrossShardStateView<'a, S> {
     cross_shard_data: HashMap<StateKey, RemoteStateValue>,
     base_view: &'a S,
+    // The hinted writes of the block, and the round and shard of the sub block that reads through
+    // this view, to detect reads from the base view which may be stale.
+    hinted_writes: Option<(&'a HintedWrites, RoundId, ShardId)>,
+    stale_reads: AtomicBool,
+    aborted: AtomicBool,
 }
 
 impl<'a, S: StateView + Sync + Send> CrossShardStateView<'a, S> {
@@ -35,9 +42,40 @@ impl<'a, S: StateView + Sync + Send> CrossShardStateView<'a, S> {
         Self {
             cross_shard_data,
             base_view,
+            hinted_writes: None,
+            stale_reads: AtomicBool::new(false),
+            aborted: AtomicBool::new(false),
         }
     }
 
+    pub fn with_hinted_writes(
+        mut self,
+        hinted_writes: Option<&'a HintedWrites>,
+        round: RoundId,
+        shard_id: ShardId,
+    ) -> Self {
+        self.hinted_writes = hinted_writes.map(|hinted_writes| (hinted_writes, round, shard_id));
+        self
+    }
+
+    /// Whether a key that a preceding sub block is hinted to write was read from the base view.
+    pub fn has_stale_reads(&self) -> bool {
+        self.stale_reads.load(Ordering::Relaxed)
+    }
+
+    /// Stops waiting for the cross shard values which did not arrive yet, so that reading them
+    /// fails.
+    pub fn abort(&self) {
+        self.aborted.store(true, Ordering::Relaxed);
+        for value in self.cross_shard_data.values() {
+            value.abort();
+        }
+    }
+
+    pub fn is_aborted(&self) -> bool {
+        self.aborted.load(Ordering::Relaxed)
+    }
+
     #[cfg(test)]
     fn waiting_count(&self) -> usize {
         self.cross_shard_data
@@ -76,7 +114,12 @@ impl<'a, S: StateView + Sync + Send> TStateView for CrossShardStateView<'a, S> {
 
     fn get_state_value(&self, state_key: &StateKey) -> Result<Option<StateValue>, StateviewError> {
         if let Some(value) = self.cross_shard_data.get(state_key) {
-            return Ok(value.get_value());
+            return value.get_value();
+        }
+        if let Some((hinted_writes, round, shard_id)) = self.hinted_writes {
+            if hinted_writes.written_before(state_key, round, shard_id) {
+                self.stale_reads.store(true, Ordering::Relaxed);
+            }
         }
         self.base_view.get_state_value(state_key)
     }
@@ -89,9 +132,20 @@ impl<'a, S: StateView + Sync + Send> TStateView for CrossShardStateView<'a, S> {
 #[cfg(test)]
 mod tests {
     use crate::sharded_block_executor::cross_shard_state_view::CrossShardStateView;
-    use aptos_types::state_store::{
-        in_memory_state_view::InMemoryStateView, state_key::StateKey, state_value::StateValue,
-        TStateView,
+    use aptos_crypto::HashValue;
+    use aptos_types::{
+        block_executor::partitioner::{
+            CrossShardDependencies, HintedWrites, SubBlock, SubBlocksForShard,
+            TransactionWithDependencies,
+        },
+        state_store::{
+            in_memory_state_view::InMemoryStateView, state_key::StateKey, state_value::StateValue,
+            TStateView,
+        },
+        transaction::{
+            analyzed_transaction::{AnalyzedTransaction, StorageLocation},
+            Transaction,
+        },
     };
     use once_cell::sync::Lazy;
     use std::{
@@ -130,4 +184,76 @@ mod tests {
 
         wait_thread.join().unwrap();
     }
+
+    #[test]
+    fn test_cross_shard_state_view_abort() {
+        let ready_key = StateKey::raw(b"key1");
+        let waiting_key = StateKey::raw(b"key2");
+        let state_value = StateValue::from("value1".as_bytes().to_owned());
+        let waiting_key_clone = waiting_key.clone();
+
+        let state_keys = HashSet::from([ready_key.clone(), waiting_key.clone()]);
+        let cross_shard_state_view = Arc::new(CrossShardStateView::new(state_keys, &EMPTY_VIEW));
+        let cross_shard_state_view_clone = cross_shard_state_view.clone();
+        cross_shard_state_view.set_value(&ready_key, Some(state_value.clone()));
+
+        let wait_thread = thread::spawn(move || {
+            assert!(cross_shard_state_view_clone
+                .get_state_value(&waiting_key_clone)
+                .is_err());
+        });
+
+        thread::sleep(Duration::from_millis(100));
+
+        cross_shard_state_view.abort();
+        wait_thread.join().unwrap();
+        assert!(cross_shard_state_view.is_aborted());
+        assert_eq!(
+            cross_shard_state_view.get_state_value(&ready_key).unwrap(),
+            Some(state_value)
+        );
+        assert!(cross_shard_state_view
+            .get_state_value(&waiting_key)
+            .is_err());
+    }
+
+    #[test]
+    fn test_cross_shard_state_view_stale_reads() {
+        let state_key = StateKey::raw(b"key1");
+        let txn = AnalyzedTransaction::new_with_advisory_hints(
+            Transaction::StateCheckpoint(HashValue::zero()).into(),
+            vec![],
+            vec![StorageLocation::Specific(state_key.clone())],
+        );
+        let sub_block = SubBlock::new(0, vec![TransactionWithDependencies::new(
+            txn,
+            CrossShardDependencies::default(),
+        )]);
+        let hinted_writes = HintedWrites::new(&[
+            SubBlocksForShard::empty(0),
+            SubBlocksForShard::new(1, vec![sub_block]),
+        ]);
+
+        // The sub block which writes the key, and the one of the same round on the preceding
+        // shard, read the value before the write.
+        for (round, shard_id) in [(0, 0), (0, 1)] {
+            let view = CrossShardStateView::new(HashSet::new(), &EMPTY_VIEW).with_hinted_writes(
+                Some(&hinted_writes),
+                round,
+                shard_id,
+            );
+            view.get_state_value(&state_key).unwrap();
+            assert!(!view.has_stale_reads());
+        }
+
+        let view = CrossShardStateView::new(HashSet::new(), &EMPTY_VIEW).with_hinted_writes(
+            Some(&hinted_writes),
+            1,
+            0,
+        );
+        view.get_state_value(&StateKey::raw(b"key2")).unwrap();
+        assert!(!view.has_stale_reads());
+        view.get_state_value(&state_key).unwrap();
+        assert!(view.has_stale_reads());
+    }
 }
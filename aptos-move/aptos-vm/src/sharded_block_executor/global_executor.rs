@@ -8,7 +8,7 @@ use aptos_logger::trace;
 use aptos_types::{
     block_executor::{
         config::{BlockExecutorConfig, BlockExecutorConfigFromOnchain, BlockExecutorLocalConfig},
-        partitioner::{TransactionWithDependencies, GLOBAL_ROUND_ID},
+        partitioner::{HintedWrites, TransactionWithDependencies, GLOBAL_ROUND_ID},
     },
     state_store::StateView,
     transaction::{analyzed_transaction::AnalyzedTransaction, TransactionOutput},
@@ -45,6 +45,7 @@ impl<S: StateView + Sync + Send + 'static> GlobalExecutor<S> {
         &self,
         transactions: Vec<TransactionWithDependencies<AnalyzedTransaction>>,
         state_view: &S,
+        hinted_writes: Option<&HintedWrites>,
         onchain_config: BlockExecutorConfigFromOnchain,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
         trace!("executing the last round in global executor",);
@@ -59,11 +60,14 @@ impl<S: StateView + Sync + Send + 'static> GlobalExecutor<S> {
             None,
             GLOBAL_ROUND_ID,
             state_view,
+            hinted_writes,
             BlockExecutorConfig {
                 local: BlockExecutorLocalConfig {
                     concurrency_level: self.concurrency_level,
                     allow_fallback: true,
                     discard_failed_blocks: false,
+                    conflict_report: false,
+                    prefetch_read_sets: false,
                 },
                 onchain: onchain_config,
             },
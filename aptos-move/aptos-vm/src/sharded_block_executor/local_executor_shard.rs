@@ -164,14 +164,18 @@ impl<S: StateView + Sync + Send + 'static> LocalExecutorClient<S> {
     fn get_output_from_shards(&self) -> Result<Vec<Vec<Vec<TransactionOutput>>>, VMStatus> {
         let _timer = WAIT_FOR_SHARDED_OUTPUT_SECONDS.start_timer();
         trace!("LocalExecutorClient Waiting for results");
-        let mut results = vec![];
-        for (i, rx) in self.result_rxs.iter().enumerate() {
-            results.push(
+        // The results of all shards are received before returning an error, so that none of them
+        // is left in the channels for the next block.
+        let results = self
+            .result_rxs
+            .iter()
+            .enumerate()
+            .map(|(i, rx)| {
                 rx.recv()
-                    .unwrap_or_else(|_| panic!("Did not receive output from shard {}", i))?,
-            );
-        }
-        Ok(results)
+                    .unwrap_or_else(|_| panic!("Did not receive output from shard {}", i))
+            })
+            .collect::<Vec<_>>();
+        results.into_iter().collect()
     }
 }
 
@@ -189,6 +193,9 @@ impl<S: StateView + Sync + Send + 'static> ExecutorClient<S> for LocalExecutorCl
     ) -> Result<ShardedExecutionOutput, VMStatus> {
         assert_eq!(transactions.num_shards(), self.num_shards());
         let (sub_blocks, global_txns) = transactions.into();
+        let hinted_writes = sub_blocks
+            .first()
+            .and_then(|sub_blocks| sub_blocks.hinted_writes.clone());
         for (i, sub_blocks_for_shard) in sub_blocks.into_iter().enumerate() {
             self.command_txs[i]
                 .send(ExecutorShardCommand::ExecuteSubBlocks(
@@ -204,13 +211,15 @@ impl<S: StateView + Sync + Send + 'static> ExecutorClient<S> for LocalExecutorCl
         // global transactions will be blocked for cross shard transaction results. This hopefully will help with
         // finishing the global transactions faster but we need to evaluate if this causes thread contention. If it
         // does, then we can simply move this call to the end of the function.
-        let mut global_output = self.global_executor.execute_global_txns(
+        let global_output = self.global_executor.execute_global_txns(
             global_txns,
             state_view.as_ref(),
+            hinted_writes.as_ref(),
             onchain_config,
-        )?;
+        );
 
         let mut sharded_output = self.get_output_from_shards()?;
+        let mut global_output = global_output?;
 
         sharded_aggregator_service::aggregate_and_update_total_supply(
             &mut sharded_output,
@@ -8,6 +8,10 @@ use serde::{Deserialize, Serialize};
 pub enum CrossShardMsg {
     RemoteTxnWriteMsg(RemoteTxnWrite),
     StopMsg,
+    /// Aborts the execution of the round, as the messages of the other shards will not arrive.
+    /// It is not sent by other shards, but returned by the cross shard client which receives the
+    /// messages, e.g. when they did not arrive in time.
+    AbortMsg,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
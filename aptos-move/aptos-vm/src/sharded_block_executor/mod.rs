@@ -2,21 +2,30 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::sharded_block_executor::{
-    counters::{
-        NUM_EXECUTOR_SHARDS, SHARDED_BLOCK_EXECUTION_SECONDS,
-        SHARDED_EXECUTION_RESULT_AGGREGATION_SECONDS,
+use crate::{
+    resource_access_hints::fee_payment_locations,
+    sharded_block_executor::{
+        counters::{
+            NUM_EXECUTOR_SHARDS, SHARDED_BLOCK_EXECUTION_SECONDS, SHARDED_EXECUTION_FALLBACK_COUNT,
+            SHARDED_EXECUTION_RESULT_AGGREGATION_SECONDS,
+        },
+        executor_client::{ExecutorClient, ShardedExecutionOutput},
     },
-    executor_client::ExecutorClient,
+    AptosVM, VMExecutor,
 };
-use aptos_logger::info;
+use aptos_logger::{info, warn};
 use aptos_types::{
     block_executor::{
         config::BlockExecutorConfigFromOnchain,
         partitioner::{PartitionedTransactions, SubBlocksForShard},
     },
     state_store::StateView,
-    transaction::{analyzed_transaction::AnalyzedTransaction, TransactionOutput},
+    transaction::{
+        analyzed_transaction::{AnalyzedTransaction, StorageLocation},
+        signature_verified_transaction::SignatureVerifiedTransaction,
+        BlockOutput, Transaction, TransactionOutput,
+    },
+    write_set::TOTAL_SUPPLY_STATE_KEY,
 };
 use move_core_types::vm_status::VMStatus;
 use std::{marker::PhantomData, sync::Arc};
@@ -68,10 +77,16 @@ impl<S: StateView + Sync + Send + 'static, C: ExecutorClient<S>> ShardedBlockExe
 
     /// Execute a block of transactions in parallel by splitting the block into num_remote_executors partitions and
     /// dispatching each partition to a remote executor shard.
+    ///
+    /// Advisory hints (see [AnalyzedTransaction::has_advisory_hints]) may not cover all the
+    /// locations a transaction accesses. If the block contains transactions with advisory hints,
+    /// the shards check that they don't read locations written by preceding sub-blocks without a
+    /// cross-shard dependency, the writes of those transactions are checked against their hints,
+    /// and the block is executed unsharded if either check fails.
     pub fn execute_block(
         &self,
         state_view: Arc<S>,
-        transactions: PartitionedTransactions,
+        mut transactions: PartitionedTransactions,
         concurrency_level_per_shard: usize,
         onchain_config: BlockExecutorConfigFromOnchain,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
@@ -84,15 +99,49 @@ impl<S: StateView + Sync + Send + 'static, C: ExecutorClient<S>> ShardedBlockExe
             "Block must be partitioned into {} sub-blocks",
             num_executor_shards
         );
-        let (sharded_output, global_output) = self
+        let advisory_txns = if transactions.has_advisory_hints() {
+            transactions.add_hinted_writes();
+            Some(PartitionedTransactions::flatten(transactions.clone()))
+        } else {
+            None
+        };
+        let result = self
             .executor_client
             .execute_block(
-                state_view,
+                state_view.clone(),
                 transactions,
                 concurrency_level_per_shard,
-                onchain_config,
-            )?
-            .into_inner();
+                onchain_config.clone(),
+            )
+            .map(|output| Self::aggregate_output(num_executor_shards, output));
+
+        let Some(txns) = advisory_txns else {
+            return result;
+        };
+        match result {
+            Ok(outputs) if Self::hints_cover_writes(&txns, &outputs) => Ok(outputs),
+            result => {
+                warn!(
+                    "Transactions did not respect their advisory hints ({:?}), executing the \
+                     block unsharded",
+                    result.err()
+                );
+                SHARDED_EXECUTION_FALLBACK_COUNT.inc();
+                let txns = txns
+                    .into_iter()
+                    .map(SignatureVerifiedTransaction::from)
+                    .collect::<Vec<_>>();
+                AptosVM::execute_block(&txns, state_view.as_ref(), onchain_config)
+                    .map(BlockOutput::into_transaction_outputs_forced)
+            },
+        }
+    }
+
+    fn aggregate_output(
+        num_executor_shards: usize,
+        output: ShardedExecutionOutput,
+    ) -> Vec<TransactionOutput> {
+        let (sharded_output, global_output) = output.into_inner();
         // wait for all remote executors to send the result back and append them in order by shard id
         info!("ShardedBlockExecutor Received all results");
         let _aggregation_timer = SHARDED_EXECUTION_RESULT_AGGREGATION_SECONDS.start_timer();
@@ -113,7 +162,36 @@ impl<S: StateView + Sync + Send + 'static, C: ExecutorClient<S>> ShardedBlockExe
         // Lastly append the global output
         aggregated_results.extend(global_output);
 
-        Ok(aggregated_results)
+        aggregated_results
+    }
+
+    // Whether the transactions with advisory hints only wrote the locations they were hinted to,
+    // or the locations written to pay for gas, i.e. the gas payer's coin store or primary
+    // fungible store. The total supply is exempt, as the shards account for it separately.
+    fn hints_cover_writes(txns: &[AnalyzedTransaction], outputs: &[TransactionOutput]) -> bool {
+        txns.len() == outputs.len()
+            && txns
+                .iter()
+                .zip(outputs)
+                .filter(|(txn, _)| txn.has_advisory_hints())
+                .all(|(txn, output)| {
+                    let fee_locations = match txn.transaction() {
+                        SignatureVerifiedTransaction::Valid(Transaction::UserTransaction(
+                            signed_txn,
+                        )) => fee_payment_locations(signed_txn),
+                        _ => vec![],
+                    };
+                    output.write_set().iter().all(|(state_key, _)| {
+                        state_key == &*TOTAL_SUPPLY_STATE_KEY
+                            || txn
+                                .write_hints()
+                                .iter()
+                                .chain(&fee_locations)
+                                .any(|location| {
+                                    matches!(location, StorageLocation::Specific(key) if key == state_key)
+                                })
+                    })
+                })
     }
 
     pub fn shutdown(&mut self) {
@@ -1,8 +1,11 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_types::state_store::state_value::StateValue;
-use std::sync::{Arc, Condvar, Mutex};
+use aptos_types::state_store::{errors::StateviewError, state_value::StateValue};
+use std::{
+    sync::{Arc, Condvar, Mutex},
+    time::Duration,
+};
 
 #[derive(Clone)]
 // This struct is used to store the status of a remote state value. It provides semantics for
@@ -26,18 +29,48 @@ impl RemoteStateValue {
         cvar.notify_all();
     }
 
-    pub fn get_value(&self) -> Option<StateValue> {
+    /// Stops waiting for the value, e.g. because the remote server will not push it. Reading the
+    /// value fails afterwards, unless it was already available.
+    pub fn abort(&self) {
+        let (lock, cvar) = &*self.value_condition;
+        let mut status = lock.lock().unwrap();
+        if let RemoteValueStatus::Waiting = *status {
+            *status = RemoteValueStatus::Aborted;
+            cvar.notify_all();
+        }
+    }
+
+    pub fn get_value(&self) -> Result<Option<StateValue>, StateviewError> {
         let (lock, cvar) = &*self.value_condition;
         let mut status = lock.lock().unwrap();
         while let RemoteValueStatus::Waiting = *status {
             status = cvar.wait(status).unwrap();
         }
         match &*status {
-            RemoteValueStatus::Ready(value) => value.clone(),
+            RemoteValueStatus::Ready(value) => Ok(value.clone()),
+            RemoteValueStatus::Aborted => Err(StateviewError::Other(
+                "Waiting for the remote state value was aborted".to_string(),
+            )),
             RemoteValueStatus::Waiting => unreachable!(),
         }
     }
 
+    /// Like [Self::get_value], but returns `None` if the value is not available within the
+    /// timeout, or waiting for it was aborted.
+    pub fn get_value_timeout(&self, timeout: Duration) -> Option<Option<StateValue>> {
+        let (lock, cvar) = &*self.value_condition;
+        let status = lock.lock().unwrap();
+        let (status, _) = cvar
+            .wait_timeout_while(status, timeout, |status| {
+                matches!(status, RemoteValueStatus::Waiting)
+            })
+            .unwrap();
+        match &*status {
+            RemoteValueStatus::Ready(value) => Some(value.clone()),
+            RemoteValueStatus::Waiting | RemoteValueStatus::Aborted => None,
+        }
+    }
+
     pub fn is_ready(&self) -> bool {
         let (lock, _cvar) = &*self.value_condition;
         let status = lock.lock().unwrap();
@@ -51,4 +84,6 @@ pub enum RemoteValueStatus {
     Ready(Option<StateValue>),
     /// We are still waiting for remote shard to push the state value
     Waiting,
+    /// We stopped waiting for the state value before it was pushed
+    Aborted,
 }
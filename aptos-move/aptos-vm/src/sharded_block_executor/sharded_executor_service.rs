@@ -20,7 +20,10 @@ use aptos_logger::{info, trace};
 use aptos_types::{
     block_executor::{
         config::{BlockExecutorConfig, BlockExecutorLocalConfig},
-        partitioner::{ShardId, SubBlock, SubBlocksForShard, TransactionWithDependencies},
+        partitioner::{
+            HintedWrites, ShardId, SubBlock, SubBlocksForShard, TransactionWithDependencies,
+            GLOBAL_SHARD_ID,
+        },
     },
     state_store::StateView,
     transaction::{
@@ -31,7 +34,7 @@ use aptos_types::{
 };
 use aptos_vm_logging::disable_speculative_logging;
 use futures::{channel::oneshot, executor::block_on};
-use move_core_types::vm_status::VMStatus;
+use move_core_types::vm_status::{StatusCode, VMStatus};
 use std::sync::Arc;
 
 pub struct ShardedExecutorService<S: StateView + Sync + Send + 'static> {
@@ -73,6 +76,7 @@ impl<S: StateView + Sync + Send + 'static> ShardedExecutorService<S> {
         sub_block: SubBlock<AnalyzedTransaction>,
         round: usize,
         state_view: &S,
+        hinted_writes: Option<&HintedWrites>,
         config: BlockExecutorConfig,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
         disable_speculative_logging();
@@ -91,6 +95,7 @@ impl<S: StateView + Sync + Send + 'static> ShardedExecutorService<S> {
             Some(cross_shard_commit_sender),
             round,
             state_view,
+            hinted_writes,
             config,
         )
     }
@@ -103,14 +108,15 @@ impl<S: StateView + Sync + Send + 'static> ShardedExecutorService<S> {
         cross_shard_commit_sender: Option<CrossShardCommitSender>,
         round: usize,
         state_view: &S,
+        hinted_writes: Option<&HintedWrites>,
         config: BlockExecutorConfig,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
         let (callback, callback_receiver) = oneshot::channel();
 
-        let cross_shard_state_view = Arc::new(CrossShardStateView::create_cross_shard_state_view(
-            state_view,
-            &transactions,
-        ));
+        let cross_shard_state_view = Arc::new(
+            CrossShardStateView::create_cross_shard_state_view(state_view, &transactions)
+                .with_hinted_writes(hinted_writes, round, shard_id.unwrap_or(GLOBAL_SHARD_ID)),
+        );
 
         let cross_shard_state_view_clone = cross_shard_state_view.clone();
         let cross_shard_client_clone = cross_shard_client.clone();
@@ -168,16 +174,38 @@ impl<S: StateView + Sync + Send + 'static> ShardedExecutorService<S> {
             });
         });
 
-        block_on(callback_receiver).unwrap()
+        let ret = block_on(callback_receiver).unwrap();
+        if cross_shard_state_view.is_aborted() {
+            return Err(VMStatus::error(
+                StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR,
+                Some(format!(
+                    "Execution of round {} was aborted while waiting for cross shard messages",
+                    round
+                )),
+            ));
+        }
+        if ret.is_ok() && cross_shard_state_view.has_stale_reads() {
+            return Err(VMStatus::error(
+                StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR,
+                Some(format!(
+                    "Transactions of round {} read state which preceding transactions are hinted \
+                     to write, without a cross shard dependency",
+                    round
+                )),
+            ));
+        }
+        ret
     }
 
     fn execute_block(
         &self,
-        transactions: SubBlocksForShard<AnalyzedTransaction>,
+        mut transactions: SubBlocksForShard<AnalyzedTransaction>,
         state_view: &S,
         config: BlockExecutorConfig,
     ) -> Result<Vec<Vec<TransactionOutput>>, VMStatus> {
+        let hinted_writes = transactions.hinted_writes.take();
         let mut result = vec![];
+        let mut first_error = None;
         for (round, sub_block) in transactions.into_sub_blocks().into_iter().enumerate() {
             let _timer = SHARDED_BLOCK_EXECUTION_BY_ROUNDS_SECONDS
                 .with_label_values(&[&self.shard_id.to_string(), &round.to_string()])
@@ -191,14 +219,30 @@ impl<S: StateView + Sync + Send + 'static> ShardedExecutorService<S> {
                 round,
                 sub_block.transactions.len()
             );
-            result.push(self.execute_sub_block(sub_block, round, state_view, config.clone())?);
+            // The following rounds are still executed after an error, as the other shards may
+            // wait for their cross shard messages.
+            match self.execute_sub_block(
+                sub_block,
+                round,
+                state_view,
+                hinted_writes.as_ref(),
+                config.clone(),
+            ) {
+                Ok(output) => result.push(output),
+                Err(err) => {
+                    first_error.get_or_insert(err);
+                },
+            }
             trace!(
                 "Finished executing sub block for shard {} and round {}",
                 self.shard_id,
                 round
             );
         }
-        Ok(result)
+        match first_error {
+            Some(err) => Err(err),
+            None => Ok(result),
+        }
     }
 
     pub fn start(&self) {
@@ -234,6 +278,8 @@ impl<S: StateView + Sync + Send + 'static> ShardedExecutorService<S> {
                                 concurrency_level: concurrency_level_per_shard,
                                 allow_fallback: true,
                                 discard_failed_blocks: false,
+                                conflict_report: false,
+                                prefetch_read_sets: false,
                             },
                             onchain: onchain_config,
                         },
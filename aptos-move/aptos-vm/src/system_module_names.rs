@@ -48,6 +48,15 @@ pub static JWKS_MODULE: Lazy<ModuleId> = Lazy::new(|| {
 
 pub const UPSERT_INTO_OBSERVED_JWKS: &IdentStr = ident_str!("upsert_into_observed_jwks");
 
+pub static EQUIVOCATION_EVIDENCE_MODULE: Lazy<ModuleId> = Lazy::new(|| {
+    ModuleId::new(
+        account_config::CORE_CODE_ADDRESS,
+        ident_str!("equivocation_evidence").to_owned(),
+    )
+});
+
+pub const RECORD_EQUIVOCATION_REPORT: &IdentStr = ident_str!("record_equivocation_report");
+
 pub static MULTISIG_ACCOUNT_MODULE: Lazy<ModuleId> = Lazy::new(|| {
     ModuleId::new(
         account_config::CORE_CODE_ADDRESS,
@@ -0,0 +1,144 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    aptos_vm::{get_or_vm_startup_failure, get_system_transaction_output},
+    errors::expect_only_successful_execution,
+    move_vm_ext::{AptosMoveResolver, SessionId},
+    system_module_names::{EQUIVOCATION_EVIDENCE_MODULE, RECORD_EQUIVOCATION_REPORT},
+    validator_txns::equivocation::{
+        ExecutionFailure::{Expected, Unexpected},
+        ExpectedFailure::*,
+    },
+    AptosVM,
+};
+use aptos_types::{
+    equivocation::EquivocationReport,
+    move_utils::as_move_value::AsMoveValue,
+    on_chain_config::{ConfigurationResource, OnChainConfig, ValidatorSet},
+    transaction::TransactionStatus,
+    validator_verifier::ValidatorVerifier,
+};
+use aptos_vm_logging::log_schema::AdapterLogSchema;
+use aptos_vm_types::output::VMOutput;
+use move_core_types::{
+    account_address::AccountAddress,
+    value::{serialize_values, MoveValue},
+    vm_status::{AbortLocation, StatusCode, VMStatus},
+};
+use move_vm_runtime::module_traversal::{TraversalContext, TraversalStorage};
+use move_vm_types::gas::UnmeteredGasMeter;
+
+#[derive(Debug)]
+enum ExpectedFailure {
+    // Move equivalent: `errors::invalid_argument(*)`
+    EpochNotCurrent = 0x10201,
+    OffenderNotValidator = 0x10202,
+    ReporterNotValidator = 0x10203,
+    EmptyEvidence = 0x10204,
+
+    // Move equivalent: `errors::invalid_state(*)`
+    MissingResourceValidatorSet = 0x30201,
+    MissingResourceConfiguration = 0x30202,
+}
+
+enum ExecutionFailure {
+    Expected(ExpectedFailure),
+    Unexpected(VMStatus),
+}
+
+impl AptosVM {
+    pub(crate) fn process_equivocation_report(
+        &self,
+        resolver: &impl AptosMoveResolver,
+        log_context: &AdapterLogSchema,
+        session_id: SessionId,
+        report: EquivocationReport,
+    ) -> Result<(VMStatus, VMOutput), VMStatus> {
+        match self.process_equivocation_report_inner(resolver, log_context, session_id, report) {
+            Ok((vm_status, vm_output)) => Ok((vm_status, vm_output)),
+            Err(Expected(failure)) => {
+                // Pretend we are inside Move, and expected failures are like Move aborts.
+                Ok((
+                    VMStatus::MoveAbort(AbortLocation::Script, failure as u64),
+                    VMOutput::empty_with_status(TransactionStatus::Discard(StatusCode::ABORTED)),
+                ))
+            },
+            Err(Unexpected(vm_status)) => Err(vm_status),
+        }
+    }
+
+    fn process_equivocation_report_inner(
+        &self,
+        resolver: &impl AptosMoveResolver,
+        log_context: &AdapterLogSchema,
+        session_id: SessionId,
+        report: EquivocationReport,
+    ) -> Result<(VMStatus, VMOutput), ExecutionFailure> {
+        let validator_set = ValidatorSet::fetch_config(resolver)
+            .ok_or_else(|| Expected(MissingResourceValidatorSet))?;
+        let config_resource = ConfigurationResource::fetch_config(resolver)
+            .ok_or_else(|| Expected(MissingResourceConfiguration))?;
+
+        // Check epoch number. The evidence itself (i.e., the conflicting signed consensus
+        // messages) was verified by consensus against the validator set of this epoch.
+        let metadata = &report.metadata;
+        if metadata.epoch != config_resource.epoch() {
+            return Err(Expected(EpochNotCurrent));
+        }
+        if report.evidence_bytes.is_empty() {
+            return Err(Expected(EmptyEvidence));
+        }
+
+        // Check that both the offender and the reporter are validators of this epoch.
+        let verifier = ValidatorVerifier::from(&validator_set);
+        if verifier.get_public_key(&metadata.offender).is_none() {
+            return Err(Expected(OffenderNotValidator));
+        }
+        if verifier.get_public_key(&metadata.reporter).is_none() {
+            return Err(Expected(ReporterNotValidator));
+        }
+
+        // All check passed, invoke VM to record the equivocation on chain.
+        let mut gas_meter = UnmeteredGasMeter;
+        let mut session = self.new_session(resolver, session_id, None);
+        let args = vec![
+            MoveValue::Signer(AccountAddress::ONE),
+            MoveValue::U64(metadata.epoch),
+            MoveValue::U64(metadata.round),
+            MoveValue::U8(metadata.kind.as_u8()),
+            MoveValue::Address(metadata.offender),
+            MoveValue::Address(metadata.reporter),
+            report.evidence_bytes.as_move_value(),
+        ];
+
+        let module_storage = TraversalStorage::new();
+        session
+            .execute_function_bypass_visibility(
+                &EQUIVOCATION_EVIDENCE_MODULE,
+                RECORD_EQUIVOCATION_REPORT,
+                vec![],
+                serialize_values(&args),
+                &mut gas_meter,
+                &mut TraversalContext::new(&module_storage),
+            )
+            .map_err(|e| {
+                expect_only_successful_execution(
+                    e,
+                    RECORD_EQUIVOCATION_REPORT.as_str(),
+                    log_context,
+                )
+            })
+            .map_err(|r| Unexpected(r.unwrap_err()))?;
+
+        let output = get_system_transaction_output(
+            session,
+            &get_or_vm_startup_failure(&self.storage_gas_params, log_context)
+                .map_err(Unexpected)?
+                .change_set_configs,
+        )
+        .map_err(Unexpected)?;
+
+        Ok((VMStatus::Executed, output))
+    }
+}
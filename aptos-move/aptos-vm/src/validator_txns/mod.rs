@@ -25,9 +25,13 @@ impl AptosVM {
             ValidatorTransaction::ObservedJWKUpdate(jwk_update) => {
                 self.process_jwk_update(resolver, log_context, session_id, jwk_update)
             },
+            ValidatorTransaction::EquivocationReport(report) => {
+                self.process_equivocation_report(resolver, log_context, session_id, report)
+            },
         }
     }
 }
 
 mod dkg;
+mod equivocation;
 mod jwk;
@@ -0,0 +1,38 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+// Run this bencher via `cargo bench --features fuzzing --bench prefetch_benches`.
+// Compare the two benchmarks to get the effect of prefetching the read sets.
+use aptos_block_executor::proptest_types::bencher::PrefetchBencher;
+use criterion::{criterion_group, criterion_main, Criterion};
+use proptest::prelude::*;
+use std::time::Duration;
+
+const NUM_TRANSACTIONS: usize = 1000;
+const UNIVERSE_SIZE: usize = 10000;
+const COLD_READ_LATENCY: Duration = Duration::from_micros(100);
+
+fn prefetch_benches(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cold_state_execution");
+    group.sample_size(10);
+    for prefetch in [false, true] {
+        let name = if prefetch {
+            "with_prefetch"
+        } else {
+            "without_prefetch"
+        };
+        group.bench_function(name, |b| {
+            let bencher = PrefetchBencher::<[u8; 32], [u8; 32]>::new(
+                NUM_TRANSACTIONS,
+                UNIVERSE_SIZE,
+                COLD_READ_LATENCY,
+            );
+            bencher.bench(&any::<[u8; 32]>(), prefetch, b)
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, prefetch_benches);
+
+criterion_main!(benches);
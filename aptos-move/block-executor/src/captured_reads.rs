@@ -324,6 +324,14 @@ enum UpdateResult {
 }
 
 impl<T: Transaction> CapturedReads<T> {
+    /// The keys of the captured data, group and module reads.
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &T::Key> {
+        self.data_reads
+            .keys()
+            .chain(self.group_reads.keys())
+            .chain(self.module_reads.iter())
+    }
+
     // Return an iterator over the captured reads.
     pub(crate) fn get_read_values_with_delayed_fields(
         &self,
@@ -549,6 +557,35 @@ impl<T: Transaction> CapturedReads<T> {
         self.incorrect_use
     }
 
+    fn validate_data_read(
+        data_map: &VersionedData<T::Key, T::Value>,
+        idx_to_validate: TxnIndex,
+        k: &T::Key,
+        r: &DataRead<T::Value>,
+    ) -> bool {
+        use MVDataError::*;
+        use MVDataOutput::*;
+        match data_map.fetch_data(k, idx_to_validate) {
+            Ok(Versioned(version, v)) => {
+                matches!(
+                    DataRead::from_value_with_layout(version, v).contains(r),
+                    DataReadComparison::Contains
+                )
+            },
+            Ok(Resolved(value)) => matches!(
+                DataRead::Resolved(value).contains(r),
+                DataReadComparison::Contains
+            ),
+            // Dependency implies a validation failure, and if the original read were to
+            // observe an unresolved delta, it would set the aggregator base value in the
+            // multi-versioned data-structure, resolve, and record the resolved value.
+            Err(Dependency(_))
+            | Err(Unresolved(_))
+            | Err(DeltaApplicationFailure)
+            | Err(Uninitialized) => false,
+        }
+    }
+
     pub(crate) fn validate_data_reads(
         &self,
         data_map: &VersionedData<T::Key, T::Value>,
@@ -558,27 +595,49 @@ impl<T: Transaction> CapturedReads<T> {
             return false;
         }
 
-        use MVDataError::*;
-        use MVDataOutput::*;
-        self.data_reads.iter().all(|(k, r)| {
-            match data_map.fetch_data(k, idx_to_validate) {
-                Ok(Versioned(version, v)) => {
+        self.data_reads
+            .iter()
+            .all(|(k, r)| Self::validate_data_read(data_map, idx_to_validate, k, r))
+    }
+
+    fn validate_group_read(
+        group_map: &VersionedGroupData<T::Key, T::Tag, T::Value>,
+        idx_to_validate: TxnIndex,
+        key: &T::Key,
+        group: &GroupRead<T>,
+    ) -> bool {
+        use MVGroupError::*;
+
+        let mut ret = true;
+        if let Some(size) = group.collected_size {
+            ret &= group_map.validate_group_size(key, idx_to_validate, size);
+        }
+
+        ret && group.inner_reads.iter().all(|(tag, r)| {
+            match group_map.fetch_tagged_data(key, tag, idx_to_validate) {
+                Ok((version, v)) => {
                     matches!(
                         DataRead::from_value_with_layout(version, v).contains(r),
                         DataReadComparison::Contains
                     )
                 },
-                Ok(Resolved(value)) => matches!(
-                    DataRead::Resolved(value).contains(r),
-                    DataReadComparison::Contains
-                ),
-                // Dependency implies a validation failure, and if the original read were to
-                // observe an unresolved delta, it would set the aggregator base value in the
-                // multi-versioned data-structure, resolve, and record the resolved value.
-                Err(Dependency(_))
-                | Err(Unresolved(_))
-                | Err(DeltaApplicationFailure)
-                | Err(Uninitialized) => false,
+                Err(TagNotFound) => {
+                    let sentinel_deletion =
+                        Arc::<T::Value>::new(TransactionWrite::from_state_value(None));
+                    assert!(sentinel_deletion.is_deletion());
+                    matches!(
+                        DataRead::Versioned(Err(StorageVersion), sentinel_deletion, None)
+                            .contains(r),
+                        DataReadComparison::Contains
+                    )
+                },
+                Err(Dependency(_)) => false,
+                Err(Uninitialized) => {
+                    unreachable!("May not be uninitialized if captured for validation");
+                },
+                Err(TagSerializationError(_)) => {
+                    unreachable!("Should not require tag serialization");
+                },
             }
         })
     }
@@ -588,46 +647,37 @@ impl<T: Transaction> CapturedReads<T> {
         group_map: &VersionedGroupData<T::Key, T::Tag, T::Value>,
         idx_to_validate: TxnIndex,
     ) -> bool {
-        use MVGroupError::*;
-
         if self.speculative_failure {
             return false;
         }
 
-        self.group_reads.iter().all(|(key, group)| {
-            let mut ret = true;
-            if let Some(size) = group.collected_size {
-                ret &= group_map.validate_group_size(key, idx_to_validate, size);
-            }
+        self.group_reads
+            .iter()
+            .all(|(key, group)| Self::validate_group_read(group_map, idx_to_validate, key, group))
+    }
 
-            ret && group.inner_reads.iter().all(|(tag, r)| {
-                match group_map.fetch_tagged_data(key, tag, idx_to_validate) {
-                    Ok((version, v)) => {
-                        matches!(
-                            DataRead::from_value_with_layout(version, v).contains(r),
-                            DataReadComparison::Contains
-                        )
-                    },
-                    Err(TagNotFound) => {
-                        let sentinel_deletion =
-                            Arc::<T::Value>::new(TransactionWrite::from_state_value(None));
-                        assert!(sentinel_deletion.is_deletion());
-                        matches!(
-                            DataRead::Versioned(Err(StorageVersion), sentinel_deletion, None)
-                                .contains(r),
-                            DataReadComparison::Contains
-                        )
-                    },
-                    Err(Dependency(_)) => false,
-                    Err(Uninitialized) => {
-                        unreachable!("May not be uninitialized if captured for validation");
-                    },
-                    Err(TagSerializationError(_)) => {
-                        unreachable!("Should not require tag serialization");
-                    },
-                }
-            })
-        })
+    /// Returns the keys of the data and group reads that no longer validate, i.e. the keys
+    /// responsible for a validation failure. Only used for conflict reports, as validation
+    /// itself stops at the first invalid read.
+    pub(crate) fn invalid_keys(
+        &self,
+        data_map: &VersionedData<T::Key, T::Value>,
+        group_map: &VersionedGroupData<T::Key, T::Tag, T::Value>,
+        idx_to_validate: TxnIndex,
+    ) -> Vec<T::Key> {
+        self.data_reads
+            .iter()
+            .filter(|(k, r)| !Self::validate_data_read(data_map, idx_to_validate, k, r))
+            .map(|(k, _)| k.clone())
+            .chain(
+                self.group_reads
+                    .iter()
+                    .filter(|(key, group)| {
+                        !Self::validate_group_read(group_map, idx_to_validate, key, group)
+                    })
+                    .map(|(key, _)| key.clone()),
+            )
+            .collect()
     }
 
     // This validation needs to be called at commit time
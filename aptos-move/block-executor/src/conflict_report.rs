@@ -0,0 +1,440 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Opt-in analytics of the conflicts between the transactions of a block during parallel
+//! execution. While enabled, the executor records which transactions were re-executed, which
+//! keys caused validation failures and which transactions waited on the ESTIMATE markers of
+//! lower transactions. At the end of the block, these are summarized in a [BlockConflictReport],
+//! which is kept in memory (e.g., to be served by the admin service) and optionally written to
+//! a directory by a background thread.
+
+use aptos_infallible::Mutex;
+use aptos_logger::warn;
+use aptos_mvhashmap::types::TxnIndex;
+use crossbeam::{
+    channel::{bounded, Sender, TrySendError},
+    utils::CachePadded,
+};
+use dashmap::DashMap;
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, VecDeque},
+    fmt,
+    fmt::Debug,
+    hash::Hash,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Number of most contended keys included in a report.
+const MAX_HOT_KEYS: usize = 20;
+/// Number of most recent reports kept in memory, and queued for the writer.
+const MAX_RECENT_REPORTS: usize = 64;
+
+static REPORT_WRITER: OnceCell<Sender<Arc<BlockConflictReport>>> = OnceCell::new();
+static RECENT_REPORTS: Lazy<Mutex<VecDeque<Arc<BlockConflictReport>>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(MAX_RECENT_REPORTS)));
+
+/// Sets the directory conflict reports are written to, as `<timestamp_usecs>.json`, when
+/// invoked the first time. Reports are written by a background thread, so that execution does
+/// not wait for the disk.
+pub fn set_conflict_report_dir_once(dir: PathBuf) {
+    // Only the first call succeeds, due to OnceCell semantics.
+    REPORT_WRITER.get_or_init(|| {
+        let (report_tx, report_rx) = bounded::<Arc<BlockConflictReport>>(MAX_RECENT_REPORTS);
+        std::thread::Builder::new()
+            .name("conflict-report-writer".to_string())
+            .spawn(move || {
+                for report in report_rx {
+                    write_report(&dir, &report);
+                }
+            })
+            .expect("Failed to spawn the conflict report writer");
+        report_tx
+    });
+}
+
+fn write_report(dir: &Path, report: &BlockConflictReport) {
+    let path = dir.join(format!("{}.json", report.timestamp_usecs));
+    let result = serde_json::to_vec_pretty(report)
+        .map_err(anyhow::Error::from)
+        .and_then(|bytes| {
+            std::fs::create_dir_all(dir)?;
+            Ok(std::fs::write(&path, bytes)?)
+        });
+    if let Err(err) = result {
+        warn!("Failed to write conflict report to {:?}: {:?}", path, err);
+    }
+}
+
+/// Returns the reports of the most recently executed blocks, the latest last.
+pub fn recent_conflict_reports() -> Vec<Arc<BlockConflictReport>> {
+    RECENT_REPORTS.lock().iter().cloned().collect()
+}
+
+pub(crate) fn publish(report: BlockConflictReport) {
+    let report = Arc::new(report);
+    if let Some(report_tx) = REPORT_WRITER.get() {
+        // Reports are dropped rather than delaying execution if the writer falls behind
+        if let Err(TrySendError::Full(report)) = report_tx.try_send(report.clone()) {
+            warn!(
+                "Conflict report writer is falling behind, dropped the report {}",
+                report.timestamp_usecs
+            );
+        }
+    }
+
+    let mut reports = RECENT_REPORTS.lock();
+    if reports.len() == MAX_RECENT_REPORTS {
+        reports.pop_front();
+    }
+    reports.push_back(report);
+}
+
+#[derive(Default)]
+struct TxnConflicts {
+    incarnations: u32,
+    execution_time: Duration,
+    last_execution_time: Duration,
+    dependency_wait_time: Duration,
+    // Time the current incarnation waited on dependencies, excluded from its execution time
+    incarnation_wait_time: Duration,
+    validation_failures: u32,
+    dependency_waits: u32,
+    dependencies: BTreeSet<TxnIndex>,
+}
+
+#[derive(Default)]
+struct KeyConflicts {
+    validation_failures: u32,
+    dependency_waits: u32,
+    txns: BTreeSet<TxnIndex>,
+}
+
+/// Collects the conflicts of a block while it is executed in parallel.
+pub(crate) struct ConflictRecorder<K> {
+    txns: Vec<CachePadded<Mutex<TxnConflicts>>>,
+    keys: DashMap<K, KeyConflicts>,
+}
+
+impl<K: Hash + Eq + Clone + Debug> ConflictRecorder<K> {
+    pub(crate) fn new(num_txns: TxnIndex) -> Self {
+        Self {
+            txns: (0..num_txns)
+                .map(|_| CachePadded::new(Mutex::new(TxnConflicts::default())))
+                .collect(),
+            keys: DashMap::new(),
+        }
+    }
+
+    /// Records an incarnation of txn_idx, which took execution_time, including the time it
+    /// waited on dependencies.
+    pub(crate) fn record_execution(&self, txn_idx: TxnIndex, execution_time: Duration) {
+        let mut txn = self.txns[txn_idx as usize].lock();
+        let execution_time =
+            execution_time.saturating_sub(std::mem::take(&mut txn.incarnation_wait_time));
+        txn.incarnations += 1;
+        txn.execution_time += execution_time;
+        txn.last_execution_time = execution_time;
+    }
+
+    /// Records that txn_idx read an ESTIMATE marker of dep_idx at key and waits for it.
+    pub(crate) fn record_dependency(&self, txn_idx: TxnIndex, dep_idx: TxnIndex, key: &K) {
+        {
+            let mut txn = self.txns[txn_idx as usize].lock();
+            txn.dependency_waits += 1;
+            txn.dependencies.insert(dep_idx);
+        }
+
+        let mut key_conflicts = self.keys.entry(key.clone()).or_default();
+        key_conflicts.dependency_waits += 1;
+        key_conflicts.txns.insert(txn_idx);
+        key_conflicts.txns.insert(dep_idx);
+    }
+
+    /// Records the time txn_idx waited on a dependency, while executing.
+    pub(crate) fn record_dependency_wait_time(&self, txn_idx: TxnIndex, wait_time: Duration) {
+        let mut txn = self.txns[txn_idx as usize].lock();
+        txn.dependency_wait_time += wait_time;
+        txn.incarnation_wait_time += wait_time;
+    }
+
+    /// Records that an incarnation of txn_idx was aborted, as its reads of keys were invalid.
+    pub(crate) fn record_validation_failure(
+        &self,
+        txn_idx: TxnIndex,
+        keys: impl IntoIterator<Item = K>,
+    ) {
+        self.txns[txn_idx as usize].lock().validation_failures += 1;
+
+        for key in keys {
+            let mut key_conflicts = self.keys.entry(key).or_default();
+            key_conflicts.validation_failures += 1;
+            key_conflicts.txns.insert(txn_idx);
+        }
+    }
+
+    pub(crate) fn into_report(
+        self,
+        state_view_id: String,
+        concurrency_level: usize,
+        wall_time: Duration,
+        sequential_fallback: bool,
+    ) -> BlockConflictReport {
+        let txns = self
+            .txns
+            .into_iter()
+            .map(|txn| CachePadded::into_inner(txn).into_inner())
+            .collect::<Vec<_>>();
+
+        let total_execution_time = txns.iter().map(|txn| txn.execution_time).sum::<Duration>();
+        let final_execution_time = txns
+            .iter()
+            .map(|txn| txn.last_execution_time)
+            .sum::<Duration>();
+        let dependency_wait_time = txns
+            .iter()
+            .map(|txn| txn.dependency_wait_time)
+            .sum::<Duration>();
+
+        let mut hot_keys = self
+            .keys
+            .into_iter()
+            .map(|(key, conflicts)| HotKey {
+                key: format!("{:?}", key),
+                validation_failures: conflicts.validation_failures,
+                dependency_waits: conflicts.dependency_waits,
+                txns: conflicts.txns.into_iter().collect(),
+            })
+            .collect::<Vec<_>>();
+        hot_keys.sort_by(|lhs, rhs| {
+            (rhs.validation_failures + rhs.dependency_waits)
+                .cmp(&(lhs.validation_failures + lhs.dependency_waits))
+                .then_with(|| rhs.txns.len().cmp(&lhs.txns.len()))
+                .then_with(|| lhs.key.cmp(&rhs.key))
+        });
+        hot_keys.truncate(MAX_HOT_KEYS);
+
+        let dependencies = txns
+            .iter()
+            .enumerate()
+            .filter(|(_, txn)| !txn.dependencies.is_empty())
+            .map(|(txn_idx, txn)| TxnDependencies {
+                txn_idx: txn_idx as TxnIndex,
+                depends_on: txn.dependencies.iter().copied().collect(),
+            })
+            .collect::<Vec<_>>();
+
+        BlockConflictReport {
+            state_view_id,
+            timestamp_usecs: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_micros() as u64,
+            num_txns: txns.len() as TxnIndex,
+            concurrency_level,
+            sequential_fallback,
+            wall_time_us: wall_time.as_micros() as u64,
+            total_execution_time_us: total_execution_time.as_micros() as u64,
+            final_execution_time_us: final_execution_time.as_micros() as u64,
+            dependency_wait_time_us: dependency_wait_time.as_micros() as u64,
+            effective_parallelism: if wall_time.is_zero() {
+                0.0
+            } else {
+                final_execution_time.as_secs_f64() / wall_time.as_secs_f64()
+            },
+            num_incarnations: txns.iter().map(|txn| txn.incarnations as u64).sum(),
+            num_reexecuted_txns: txns.iter().filter(|txn| txn.incarnations > 1).count() as TxnIndex,
+            num_validation_failures: txns.iter().map(|txn| txn.validation_failures as u64).sum(),
+            num_dependency_waits: txns.iter().map(|txn| txn.dependency_waits as u64).sum(),
+            incarnations: txns.iter().map(|txn| txn.incarnations).collect(),
+            longest_dependency_chain: longest_dependency_chain(&txns),
+            hot_keys,
+            dependencies,
+        }
+    }
+}
+
+/// Dependencies always point to lower transactions, so the longest chain is computed in a
+/// single pass in the order of the block.
+fn longest_dependency_chain(txns: &[TxnConflicts]) -> Vec<TxnIndex> {
+    // For every transaction, the length of the longest chain ending at it and its predecessor.
+    let mut chains: Vec<(usize, Option<TxnIndex>)> = Vec::with_capacity(txns.len());
+    for txn in txns {
+        let longest = txn
+            .dependencies
+            .iter()
+            .map(|dep_idx| (chains[*dep_idx as usize].0 + 1, Some(*dep_idx)))
+            .max_by_key(|(len, _)| *len)
+            .unwrap_or((1, None));
+        chains.push(longest);
+    }
+
+    let Some((mut txn_idx, _)) = chains
+        .iter()
+        .enumerate()
+        .filter(|(_, (len, _))| *len > 1)
+        .max_by_key(|(_, (len, _))| *len)
+    else {
+        return vec![];
+    };
+    let mut chain = vec![txn_idx as TxnIndex];
+    while let Some(prev_idx) = chains[txn_idx].1 {
+        chain.push(prev_idx);
+        txn_idx = prev_idx as usize;
+    }
+    chain.reverse();
+    chain
+}
+
+/// A key read or written by several transactions of the block.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct HotKey {
+    pub key: String,
+    /// Number of aborted incarnations that read an outdated value of the key.
+    pub validation_failures: u32,
+    /// Number of times a transaction waited on an ESTIMATE marker at the key.
+    pub dependency_waits: u32,
+    /// Transactions involved in the conflicts at the key.
+    pub txns: Vec<TxnIndex>,
+}
+
+/// The lower transactions a transaction waited on.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct TxnDependencies {
+    pub txn_idx: TxnIndex,
+    pub depends_on: Vec<TxnIndex>,
+}
+
+/// Summary of the conflicts that occurred while executing a block in parallel.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BlockConflictReport {
+    /// Identifies the block, e.g. by the id of the state view it was executed against.
+    pub state_view_id: String,
+    pub timestamp_usecs: u64,
+    pub num_txns: TxnIndex,
+    pub concurrency_level: usize,
+    /// Whether parallel execution failed, in which case the block was executed sequentially,
+    /// and the report only covers the parallel execution until it failed.
+    pub sequential_fallback: bool,
+    /// Time spent executing the block in parallel.
+    pub wall_time_us: u64,
+    /// Time spent executing all incarnations, excluding the time they waited on dependencies.
+    pub total_execution_time_us: u64,
+    /// Time spent executing the final incarnations, i.e. what sequential execution would take.
+    pub final_execution_time_us: u64,
+    /// Time spent by incarnations waiting on dependencies.
+    pub dependency_wait_time_us: u64,
+    /// The speed-up over sequential execution, i.e. the final execution time over the wall time.
+    pub effective_parallelism: f64,
+    pub num_incarnations: u64,
+    pub num_reexecuted_txns: TxnIndex,
+    pub num_validation_failures: u64,
+    pub num_dependency_waits: u64,
+    /// Number of incarnations of every transaction of the block.
+    pub incarnations: Vec<u32>,
+    /// The longest sequence of transactions where each waited on the previous one.
+    pub longest_dependency_chain: Vec<TxnIndex>,
+    /// The most contended keys, the most contended first.
+    pub hot_keys: Vec<HotKey>,
+    pub dependencies: Vec<TxnDependencies>,
+}
+
+impl fmt::Display for BlockConflictReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} txns with concurrency level {}: {} incarnations, {} txns re-executed, {} validation failures, {} dependency waits",
+            self.num_txns,
+            self.concurrency_level,
+            self.num_incarnations,
+            self.num_reexecuted_txns,
+            self.num_validation_failures,
+            self.num_dependency_waits,
+        )?;
+        if self.sequential_fallback {
+            writeln!(
+                f,
+                "Parallel execution failed, the block was executed sequentially"
+            )?;
+        }
+        writeln!(
+            f,
+            "Wall time {}us, final incarnations {}us, all incarnations {}us, dependency waits {}us, effective parallelism {:.2}",
+            self.wall_time_us,
+            self.final_execution_time_us,
+            self.total_execution_time_us,
+            self.dependency_wait_time_us,
+            self.effective_parallelism,
+        )?;
+        if !self.longest_dependency_chain.is_empty() {
+            writeln!(
+                f,
+                "Longest dependency chain ({} txns): {:?}",
+                self.longest_dependency_chain.len(),
+                self.longest_dependency_chain
+            )?;
+        }
+        if !self.hot_keys.is_empty() {
+            writeln!(f, "Hot keys:")?;
+            for hot_key in &self.hot_keys {
+                writeln!(
+                    f,
+                    "  {} validation failures, {} dependency waits, {} txns: {}",
+                    hot_key.validation_failures,
+                    hot_key.dependency_waits,
+                    hot_key.txns.len(),
+                    hot_key.key
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conflict_report() {
+        let recorder = ConflictRecorder::<u32>::new(5);
+        for txn_idx in 0..5 {
+            recorder.record_execution(txn_idx, Duration::from_millis(10));
+        }
+        // 2 waits on 1 which waits on 0, 4 waits on 0.
+        recorder.record_dependency(1, 0, &7);
+        recorder.record_dependency(2, 1, &7);
+        recorder.record_dependency(4, 0, &9);
+        recorder.record_validation_failure(3, vec![7, 8]);
+        // The time waited on dependencies is not part of the execution time
+        recorder.record_dependency_wait_time(3, Duration::from_millis(5));
+        recorder.record_execution(3, Duration::from_millis(25));
+
+        let report = recorder.into_report("test".to_string(), 4, Duration::from_millis(35), false);
+        assert_eq!(report.incarnations, vec![1, 1, 1, 2, 1]);
+        assert_eq!(report.num_incarnations, 6);
+        assert_eq!(report.num_reexecuted_txns, 1);
+        assert_eq!(report.num_validation_failures, 1);
+        assert_eq!(report.num_dependency_waits, 3);
+        assert_eq!(report.total_execution_time_us, 70_000);
+        assert_eq!(report.final_execution_time_us, 60_000);
+        assert_eq!(report.dependency_wait_time_us, 5_000);
+        assert!(!report.sequential_fallback);
+        assert_eq!(report.longest_dependency_chain, vec![0, 1, 2]);
+
+        assert_eq!(report.hot_keys[0], HotKey {
+            key: "7".to_string(),
+            validation_failures: 1,
+            dependency_waits: 2,
+            txns: vec![0, 1, 2, 3],
+        });
+        assert_eq!(report.hot_keys.len(), 3);
+        assert_eq!(report.dependencies[2], TxnDependencies {
+            txn_idx: 4,
+            depends_on: vec![0],
+        });
+    }
+}
@@ -162,6 +162,29 @@ pub static DEPENDENCY_WAIT_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static READ_SET_PREFETCH_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_execution_read_set_prefetch_seconds",
+        "The time spent in seconds prefetching predicted read sets (concurrently with the \
+        execution of the block), and recording the reads of executed blocks to predict future \
+        read sets",
+        &["stage"],
+        time_buckets(),
+    )
+    .unwrap()
+});
+
+pub static READ_SET_PREFETCH_KEYS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_execution_read_set_prefetch_keys",
+        "The per-block number of keys predicted to be read, and of those prefetched before \
+        the block was executed",
+        &["kind"],
+        exponential_buckets(/*start=*/ 1.0, /*factor=*/ 2.0, /*count=*/ 30).unwrap(),
+    )
+    .unwrap()
+});
+
 pub static BLOCK_GAS: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
         "aptos_execution_block_gas",
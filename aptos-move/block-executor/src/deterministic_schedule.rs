@@ -0,0 +1,218 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A testing mode for parallel execution, in which the workers run one at a time and take turns
+//! at schedule points, in an order chosen by a seeded random number generator. Since nothing
+//! else runs concurrently, the interleaving of the workers is fully determined by the seed, so
+//! a seed for which a test fails replays the exact same interleaving.
+//!
+//! Workers yield their turn before every task, and may additionally be delayed at validation
+//! and commit points, i.e. not be scheduled for a number of turns, to explore the interleavings
+//! in which these steps happen late. While waiting for a dependency, a worker yields its turn
+//! until the dependency is resolved, instead of blocking.
+
+use aptos_infallible::{Mutex, MutexGuard};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    sync::{Arc, Condvar},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SchedulePoint {
+    /// Before a worker asks the scheduler for its next task.
+    NextTask,
+    /// Before a worker executes a transaction.
+    Execution,
+    /// Before a worker validates a transaction. Workers may be delayed here.
+    Validation,
+    /// Before a worker coordinates and materializes commits. Workers may be delayed here.
+    Commit,
+    /// While a worker waits for a dependency to be resolved.
+    DependencyWait,
+}
+
+impl SchedulePoint {
+    fn may_delay(self) -> bool {
+        matches!(self, SchedulePoint::Validation | SchedulePoint::Commit)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DeterministicScheduleConfig {
+    pub seed: u64,
+    /// Probability, in percent, that a worker is delayed at a validation or commit point.
+    pub delay_percentage: u8,
+    /// Maximum number of turns a delayed worker is not scheduled for.
+    pub max_delay: u32,
+}
+
+impl DeterministicScheduleConfig {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            delay_percentage: 10,
+            max_delay: 8,
+        }
+    }
+}
+
+struct ScheduleState {
+    rng: StdRng,
+    /// The worker whose turn it is.
+    current: Option<usize>,
+    /// The running workers, with the number of turns they are still delayed for.
+    workers: BTreeMap<usize, u32>,
+    /// The id of the next worker to enter the block.
+    next_worker_id: usize,
+    /// The schedule points reached by the workers, in order.
+    trace: Vec<(usize, SchedulePoint)>,
+}
+
+impl ScheduleState {
+    /// Gives the turn to a random worker that is not delayed, or to any worker if all are.
+    fn pick_next(&mut self) {
+        let mut candidates = self
+            .workers
+            .iter()
+            .filter(|(_, delay)| **delay == 0)
+            .map(|(worker_id, _)| *worker_id)
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            candidates = self.workers.keys().copied().collect();
+        }
+
+        self.current = if candidates.is_empty() {
+            None
+        } else {
+            Some(candidates[self.rng.gen_range(0, candidates.len())])
+        };
+        for delay in self.workers.values_mut() {
+            *delay = delay.saturating_sub(1);
+        }
+    }
+}
+
+/// Lets the workers of parallel execution run one at a time, see the module documentation.
+/// The same schedule can be used for several blocks, in which case the random number generator
+/// carries over from one block to the next.
+pub struct DeterministicSchedule {
+    config: DeterministicScheduleConfig,
+    state: Mutex<ScheduleState>,
+    turn_changed: Condvar,
+}
+
+thread_local! {
+    /// The schedule and the id of the worker running on this thread, if any.
+    static CURRENT_WORKER: RefCell<Option<(Arc<DeterministicSchedule>, usize)>> =
+        RefCell::new(None);
+}
+
+impl DeterministicSchedule {
+    pub fn new(config: DeterministicScheduleConfig) -> Self {
+        Self {
+            state: Mutex::new(ScheduleState {
+                rng: StdRng::seed_from_u64(config.seed),
+                current: None,
+                workers: BTreeMap::new(),
+                next_worker_id: 0,
+                trace: vec![],
+            }),
+            config,
+            turn_changed: Condvar::new(),
+        }
+    }
+
+    pub fn config(&self) -> &DeterministicScheduleConfig {
+        &self.config
+    }
+
+    /// The schedule points reached by the workers so far, as (worker id, point), in order.
+    /// Replaying with the same seed (and the same blocks) reproduces the same trace.
+    pub fn trace(&self) -> Vec<(usize, SchedulePoint)> {
+        self.state.lock().trace.clone()
+    }
+
+    /// Must be called before the workers of a block are started, with the number of threads
+    /// of the pool the workers run on. Workers waiting for their turn block their threads, so
+    /// every worker needs its own thread, or the block never finishes.
+    pub(crate) fn start_block(&self, num_workers: usize, num_threads: usize) {
+        assert!(
+            num_threads >= num_workers,
+            "Deterministic schedule needs a thread per worker ({} workers, {} threads)",
+            num_workers,
+            num_threads
+        );
+        let mut state = self.state.lock();
+        state.workers = (0..num_workers).map(|worker_id| (worker_id, 0)).collect();
+        state.next_worker_id = 0;
+        state.pick_next();
+    }
+
+    /// Registers the calling thread as the next worker and waits for its first turn. The ids
+    /// are assigned in the order the workers enter, which does not affect the interleaving, as
+    /// the workers are interchangeable until they first run.
+    pub(crate) fn enter(self: &Arc<Self>) {
+        let mut state = self.state.lock();
+        let worker_id = state.next_worker_id;
+        state.next_worker_id += 1;
+        CURRENT_WORKER.with(|current| *current.borrow_mut() = Some((self.clone(), worker_id)));
+        drop(self.wait_for_turn(state, worker_id));
+    }
+
+    /// Removes the worker running on the calling thread and passes the turn on. Must be called
+    /// when the worker finishes, even if it fails, so that the remaining workers are not blocked.
+    pub(crate) fn exit(&self) {
+        let Some((_, worker_id)) = CURRENT_WORKER.with(|current| current.borrow_mut().take())
+        else {
+            return;
+        };
+        let mut state = self.state.lock();
+        state.workers.remove(&worker_id);
+        if state.current == Some(worker_id) {
+            state.pick_next();
+        }
+        self.turn_changed.notify_all();
+    }
+
+    fn yield_turn(&self, worker_id: usize, point: SchedulePoint) {
+        let mut state = self.state.lock();
+        state.trace.push((worker_id, point));
+        if point.may_delay() && state.rng.gen_range(0, 100) < self.config.delay_percentage {
+            let delay = state.rng.gen_range(1, self.config.max_delay.max(1) + 1);
+            state.workers.insert(worker_id, delay);
+        }
+        state.pick_next();
+        self.turn_changed.notify_all();
+        drop(self.wait_for_turn(state, worker_id));
+    }
+
+    fn wait_for_turn<'a>(
+        &self,
+        mut state: MutexGuard<'a, ScheduleState>,
+        worker_id: usize,
+    ) -> MutexGuard<'a, ScheduleState> {
+        while state.current != Some(worker_id) {
+            state = self
+                .turn_changed
+                .wait(state)
+                .expect("Deterministic schedule lock must not be poisoned");
+        }
+        state
+    }
+}
+
+/// Whether the calling thread runs as a worker of a deterministic schedule.
+pub(crate) fn is_active() -> bool {
+    CURRENT_WORKER.with(|current| current.borrow().is_some())
+}
+
+/// Yields the turn of the calling worker, if it runs under a deterministic schedule, and returns
+/// once it is its turn again. Does nothing otherwise.
+pub(crate) fn yield_point(point: SchedulePoint) {
+    let current = CURRENT_WORKER.with(|current| current.borrow().clone());
+    if let Some((schedule, worker_id)) = current {
+        schedule.yield_turn(worker_id, point);
+    }
+}
@@ -2,7 +2,10 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(any(test, feature = "fuzzing"))]
+use crate::deterministic_schedule::{self, DeterministicSchedule, SchedulePoint};
 use crate::{
+    conflict_report::{self, ConflictRecorder},
     counters,
     counters::{
         PARALLEL_EXECUTION_SECONDS, RAYON_EXECUTION_SECONDS, TASK_EXECUTE_SECONDS,
@@ -12,6 +15,7 @@ use crate::{
     executor_utilities::*,
     explicit_sync_wrapper::ExplicitSyncWrapper,
     limit_processor::BlockGasLimitProcessor,
+    prefetch::ReadSetPrefetcher,
     scheduler::{DependencyStatus, ExecutionTaskType, Scheduler, SchedulerTask, Wave},
     task::{ExecutionStatus, ExecutorTask, TransactionOutput},
     txn_commit_hook::TransactionCommitHook,
@@ -60,14 +64,18 @@ use std::{
         atomic::{AtomicBool, AtomicU32, Ordering},
         Arc,
     },
+    time::Instant,
 };
 
-pub struct BlockExecutor<T, E, S, L, X> {
+pub struct BlockExecutor<T: Transaction, E, S, L, X> {
     // Number of active concurrent tasks, corresponding to the maximum number of rayon
     // threads that may be concurrently participating in parallel execution.
     config: BlockExecutorConfig,
     executor_thread_pool: Arc<rayon::ThreadPool>,
     transaction_commit_hook: Option<L>,
+    #[cfg(any(test, feature = "fuzzing"))]
+    deterministic_schedule: Option<Arc<DeterministicSchedule>>,
+    read_set_prefetcher: Option<Arc<ReadSetPrefetcher<T::Key>>>,
     phantom: PhantomData<(T, E, S, L, X)>,
 }
 
@@ -95,10 +103,40 @@ where
             config,
             executor_thread_pool,
             transaction_commit_hook,
+            #[cfg(any(test, feature = "fuzzing"))]
+            deterministic_schedule: None,
+            read_set_prefetcher: None,
             phantom: PhantomData,
         }
     }
 
+    /// Runs the workers of parallel execution one at a time, interleaved according to the
+    /// given schedule. Only meant for testing. As the workers do not run concurrently, the
+    /// given concurrency level replaces the configured one regardless of the number of CPUs,
+    /// so that the interleavings do not depend on the machine. Since waiting workers block
+    /// their threads, the thread pool must have at least as many threads as the workers.
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub fn with_deterministic_schedule(
+        mut self,
+        schedule: Arc<DeterministicSchedule>,
+        concurrency_level: usize,
+    ) -> Self {
+        assert!(
+            concurrency_level > 1,
+            "Deterministic schedule requires parallel execution"
+        );
+        self.config.local.concurrency_level = concurrency_level;
+        self.deterministic_schedule = Some(schedule);
+        self
+    }
+
+    /// Prefetches the state that the transactions are predicted to read while executing a
+    /// block, and records the reads of the executed block for future predictions.
+    pub fn with_read_set_prefetcher(mut self, prefetcher: Arc<ReadSetPrefetcher<T::Key>>) -> Self {
+        self.read_set_prefetcher = Some(prefetcher);
+        self
+    }
+
     fn execute(
         idx_to_execute: TxnIndex,
         incarnation: Incarnation,
@@ -394,10 +432,24 @@ where
         last_input_output: &TxnLastInputOutput<T, E::Output, E::Error>,
         versioned_cache: &MVHashMap<T::Key, T::Tag, T::Value, X, T::Identifier>,
         scheduler: &Scheduler,
+        conflict_recorder: Option<&ConflictRecorder<T::Key>>,
     ) -> Result<SchedulerTask, PanicError> {
         let aborted = !valid && scheduler.try_abort(txn_idx, incarnation);
 
         if aborted {
+            if let Some(conflict_recorder) = conflict_recorder {
+                let read_set = last_input_output
+                    .read_set(txn_idx)
+                    .expect("[BlockSTM]: Prior read-set must be recorded");
+                conflict_recorder.record_validation_failure(
+                    txn_idx,
+                    read_set.invalid_keys(
+                        versioned_cache.data(),
+                        versioned_cache.group_data(),
+                        txn_idx,
+                    ),
+                );
+            }
             Self::update_transaction_on_abort(txn_idx, last_input_output, versioned_cache);
             scheduler.finish_abort(txn_idx, incarnation)
         } else {
@@ -465,6 +517,7 @@ where
         executor: &E,
         block: &[T],
         num_workers: usize,
+        conflict_recorder: Option<&ConflictRecorder<T::Key>>,
     ) -> Result<(), PanicOr<ParallelBlockExecutionError>> {
         let mut block_limit_processor = shared_commit_state.acquire();
 
@@ -477,6 +530,7 @@ where
                 // are executing immediately, and will reduce it unconditionally
                 // after execution, inside finish_execution_during_commit.
                 // Because of that, we can also ignore _needs_suffix_validation result.
+                let execution_start = Instant::now();
                 let _needs_suffix_validation = Self::execute(
                     txn_idx,
                     incarnation + 1,
@@ -490,8 +544,14 @@ where
                         scheduler,
                         start_shared_counter,
                         shared_counter,
+                        conflict_recorder,
                     ),
                 )?;
+                if let Some(conflict_recorder) = conflict_recorder {
+                    // Delayed field reads failed validation, which are not attributed to keys.
+                    conflict_recorder.record_validation_failure(txn_idx, vec![]);
+                    conflict_recorder.record_execution(txn_idx, execution_start.elapsed());
+                }
 
                 scheduler.finish_execution_during_commit(txn_idx)?;
 
@@ -759,7 +819,19 @@ where
         shared_commit_state: &ExplicitSyncWrapper<BlockGasLimitProcessor<T>>,
         final_results: &ExplicitSyncWrapper<Vec<E::Output>>,
         num_workers: usize,
+        conflict_recorder: Option<&ConflictRecorder<T::Key>>,
     ) -> Result<(), PanicOr<ParallelBlockExecutionError>> {
+        #[cfg(any(test, feature = "fuzzing"))]
+        if let Some(schedule) = &self.deterministic_schedule {
+            schedule.enter();
+        }
+        #[cfg(any(test, feature = "fuzzing"))]
+        defer! {
+            if let Some(schedule) = &self.deterministic_schedule {
+                schedule.exit();
+            }
+        }
+
         // Make executor for each task. TODO: fast concurrent executor.
         let init_timer = VM_INIT_SECONDS.start_timer();
         let executor = E::init(env.clone(), base_view);
@@ -785,6 +857,8 @@ where
         };
 
         loop {
+            #[cfg(any(test, feature = "fuzzing"))]
+            deterministic_schedule::yield_point(SchedulePoint::Commit);
             while scheduler.should_coordinate_commits() {
                 self.prepare_and_queue_commit_ready_txns(
                     &self.config.onchain.block_gas_limit_type,
@@ -799,6 +873,7 @@ where
                     &executor,
                     block,
                     num_workers,
+                    conflict_recorder,
                 )?;
                 scheduler.queueing_commits_mark_done();
             }
@@ -807,6 +882,8 @@ where
 
             scheduler_task = match scheduler_task {
                 SchedulerTask::ValidationTask(txn_idx, incarnation, wave) => {
+                    #[cfg(any(test, feature = "fuzzing"))]
+                    deterministic_schedule::yield_point(SchedulePoint::Validation);
                     let valid = Self::validate(txn_idx, last_input_output, versioned_cache)?;
                     Self::update_on_validation(
                         txn_idx,
@@ -816,6 +893,7 @@ where
                         last_input_output,
                         versioned_cache,
                         scheduler,
+                        conflict_recorder,
                     )?
                 },
                 SchedulerTask::ExecutionTask(
@@ -823,6 +901,9 @@ where
                     incarnation,
                     ExecutionTaskType::Execution,
                 ) => {
+                    #[cfg(any(test, feature = "fuzzing"))]
+                    deterministic_schedule::yield_point(SchedulePoint::Execution);
+                    let execution_start = Instant::now();
                     let needs_suffix_validation = Self::execute(
                         txn_idx,
                         incarnation,
@@ -836,8 +917,12 @@ where
                             scheduler,
                             start_shared_counter,
                             shared_counter,
+                            conflict_recorder,
                         ),
                     )?;
+                    if let Some(conflict_recorder) = conflict_recorder {
+                        conflict_recorder.record_execution(txn_idx, execution_start.elapsed());
+                    }
                     scheduler.finish_execution(txn_idx, incarnation, needs_suffix_validation)?
                 },
                 SchedulerTask::ExecutionTask(_, _, ExecutionTaskType::Wakeup(condvar)) => {
@@ -851,9 +936,15 @@ where
                         cvar.notify_one();
                     }
 
+                    #[cfg(any(test, feature = "fuzzing"))]
+                    deterministic_schedule::yield_point(SchedulePoint::NextTask);
+                    scheduler.next_task()
+                },
+                SchedulerTask::Retry => {
+                    #[cfg(any(test, feature = "fuzzing"))]
+                    deterministic_schedule::yield_point(SchedulePoint::NextTask);
                     scheduler.next_task()
                 },
-                SchedulerTask::Retry => scheduler.next_task(),
                 SchedulerTask::Done => {
                     drain_commit_queue()?;
                     break Ok(());
@@ -907,7 +998,18 @@ where
 
         let last_input_output = TxnLastInputOutput::new(num_txns);
         let scheduler = Scheduler::new(num_txns);
+        let conflict_recorder = self
+            .config
+            .local
+            .conflict_report
+            .then(|| ConflictRecorder::new(num_txns));
+
+        #[cfg(any(test, feature = "fuzzing"))]
+        if let Some(schedule) = &self.deterministic_schedule {
+            schedule.start_block(num_workers, self.executor_thread_pool.current_num_threads());
+        }
 
+        let execution_start = Instant::now();
         let timer = RAYON_EXECUTION_SECONDS.start_timer();
         self.executor_thread_pool.scope(|s| {
             for _ in 0..num_workers {
@@ -924,6 +1026,7 @@ where
                         &shared_commit_state,
                         &final_results,
                         num_workers,
+                        conflict_recorder.as_ref(),
                     ) {
                         // If there are multiple errors, they all get logged:
                         // ModulePathReadWriteError and FatalVMError variant is logged at construction,
@@ -940,9 +1043,28 @@ where
             }
         });
         drop(timer);
+        let execution_time = execution_start.elapsed();
 
         counters::update_state_counters(versioned_cache.stats(), true);
 
+        if let Some(prefetcher) = &self.read_set_prefetcher {
+            if !shared_maybe_error.load(Ordering::SeqCst) {
+                prefetcher.record_reads(signature_verified_block, |txn_idx| {
+                    last_input_output.read_set(txn_idx)
+                });
+            }
+        }
+
+        if let Some(conflict_recorder) = conflict_recorder {
+            // On errors, the block is executed sequentially (or execution panics)
+            conflict_report::publish(conflict_recorder.into_report(
+                format!("{:?}", base_view.id()),
+                num_workers,
+                execution_time,
+                shared_maybe_error.load(Ordering::SeqCst),
+            ));
+        }
+
         // Explicit async drops.
         DEFAULT_DROPPER.schedule_drop((last_input_output, scheduler, versioned_cache));
 
@@ -1352,6 +1474,22 @@ where
         env: E::Environment,
         signature_verified_block: &[T],
         base_view: &S,
+    ) -> BlockExecutionResult<BlockOutput<E::Output>, E::Error> {
+        match &self.read_set_prefetcher {
+            Some(prefetcher) => {
+                prefetcher.prefetch_during(signature_verified_block, base_view, || {
+                    self.execute_block_impl(env, signature_verified_block, base_view)
+                })
+            },
+            None => self.execute_block_impl(env, signature_verified_block, base_view),
+        }
+    }
+
+    fn execute_block_impl(
+        &self,
+        env: E::Environment,
+        signature_verified_block: &[T],
+        base_view: &S,
     ) -> BlockExecutionResult<BlockOutput<E::Output>, E::Error> {
         if self.config.local.concurrency_level > 1 {
             let parallel_result =
@@ -140,12 +140,16 @@ subsequent incarnation to finish.
 extern crate scopeguard;
 
 mod captured_reads;
+pub mod conflict_report;
 pub mod counters;
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod deterministic_schedule;
 pub mod errors;
 pub mod executor;
 mod executor_utilities;
 pub mod explicit_sync_wrapper;
 mod limit_processor;
+pub mod prefetch;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod proptest_types;
 mod scheduler;
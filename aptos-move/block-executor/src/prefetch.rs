@@ -0,0 +1,265 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Opt-in prefetching of the state that the transactions of a block are predicted to read. Lazy
+//! reads of cold state stall the worker threads during execution, so while a block is executed,
+//! the predicted keys are read on a separate thread pool through the base view to warm its caches
+//! (e.g., the cache of a `CachedStateView`). The keys are read in the order of the transactions,
+//! ahead of the workers, and the prefetching stops as soon as the block is executed.
+//!
+//! The predictions combine the hints of the transactions themselves (see
+//! [Transaction::read_hints]), and the keys that were read by all the transactions
+//! with the same access pattern (e.g., calls of the same entry function) in the last block they
+//! occurred in.
+
+use crate::{
+    captured_reads::CapturedReads,
+    counters::{READ_SET_PREFETCH_KEYS, READ_SET_PREFETCH_SECONDS},
+};
+use aptos_infallible::Mutex;
+use aptos_mvhashmap::types::TxnIndex;
+use aptos_types::{
+    state_store::TStateView, transaction::BlockExecutableTransaction as Transaction,
+};
+use rayon::ThreadPool;
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    hash::Hash,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+/// Maximum number of access patterns whose reads are remembered.
+const MAX_ACCESS_PATTERNS: usize = 10_000;
+/// Maximum number of keys remembered per access pattern.
+const MAX_KEYS_PER_PATTERN: usize = 64;
+/// Default number of threads reading the predicted keys.
+const DEFAULT_NUM_PREFETCH_THREADS: usize = 8;
+
+/// Predicts and prefetches the read sets of blocks. Meant to be shared across blocks, so that
+/// the reads of previous blocks can be used for the predictions.
+pub struct ReadSetPrefetcher<K> {
+    /// For every access pattern, the keys read by all its transactions in the last block it
+    /// occurred in.
+    history: Mutex<HashMap<String, Vec<K>>>,
+    /// Reads the predicted keys. Separate from the execution thread pool, whose threads are all
+    /// taken by the workers during parallel execution.
+    thread_pool: ThreadPool,
+}
+
+impl<K: Clone + Eq + Hash + Ord + Send + Sync> Default for ReadSetPrefetcher<K> {
+    fn default() -> Self {
+        Self::new(DEFAULT_NUM_PREFETCH_THREADS)
+    }
+}
+
+impl<K: Clone + Eq + Hash + Ord + Send + Sync> ReadSetPrefetcher<K> {
+    pub fn new(num_threads: usize) -> Self {
+        Self {
+            history: Mutex::new(HashMap::new()),
+            thread_pool: rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .thread_name(|index| format!("read_set_prefetch-{}", index))
+                .build()
+                .unwrap(),
+        }
+    }
+
+    /// The keys the transactions are predicted to read, in the order of the transactions and
+    /// without duplicates.
+    pub fn predicted_reads<T: Transaction<Key = K>>(&self, txns: &[T]) -> Vec<K> {
+        let history = self.history.lock();
+        let mut seen = HashSet::new();
+        let mut keys = vec![];
+        for txn in txns {
+            let hints = txn.read_hints();
+            let keys_read = txn
+                .access_pattern()
+                .and_then(|pattern| history.get(&pattern))
+                .into_iter()
+                .flatten()
+                .cloned();
+            for key in hints.into_iter().chain(keys_read) {
+                if seen.insert(key.clone()) {
+                    keys.push(key);
+                }
+            }
+        }
+        keys
+    }
+
+    /// Runs the execution of the block while reading the predicted keys on the prefetch thread
+    /// pool, and returns the result of the execution. The prefetching stops once the execution
+    /// returns, so that the keys that were not read in time do not delay the block.
+    pub(crate) fn prefetch_during<T, S, R>(
+        &self,
+        txns: &[T],
+        base_view: &S,
+        execute: impl FnOnce() -> R,
+    ) -> R
+    where
+        T: Transaction<Key = K>,
+        S: TStateView<Key = K> + Sync,
+    {
+        let keys = self.predicted_reads(txns);
+        if keys.is_empty() {
+            return execute();
+        }
+
+        let start = Instant::now();
+        let next_key = AtomicUsize::new(0);
+        let num_prefetched = AtomicUsize::new(0);
+        let num_running = AtomicUsize::new(self.thread_pool.current_num_threads());
+        let executed = AtomicBool::new(false);
+        let result = self.thread_pool.in_place_scope(|s| {
+            for _ in 0..self.thread_pool.current_num_threads() {
+                s.spawn(|_| {
+                    while !executed.load(Ordering::Relaxed) {
+                        let Some(key) = keys.get(next_key.fetch_add(1, Ordering::Relaxed)) else {
+                            break;
+                        };
+                        // Errors are ignored, they are encountered again when the key is read
+                        // during execution.
+                        let _ = base_view.get_state_value(key);
+                        num_prefetched.fetch_add(1, Ordering::Relaxed);
+                    }
+                    if num_running.fetch_sub(1, Ordering::AcqRel) == 1 {
+                        READ_SET_PREFETCH_SECONDS
+                            .with_label_values(&["prefetch"])
+                            .observe(start.elapsed().as_secs_f64());
+                    }
+                });
+            }
+
+            let result = execute();
+            executed.store(true, Ordering::Relaxed);
+            result
+        });
+        READ_SET_PREFETCH_KEYS
+            .with_label_values(&["predicted"])
+            .observe(keys.len() as f64);
+        READ_SET_PREFETCH_KEYS
+            .with_label_values(&["prefetched"])
+            .observe(num_prefetched.load(Ordering::Relaxed) as f64);
+        result
+    }
+
+    /// Records the keys read by the transactions of an executed block, to predict the reads of
+    /// the transactions with the same access patterns in future blocks.
+    pub(crate) fn record_reads<T: Transaction<Key = K>>(
+        &self,
+        txns: &[T],
+        read_set: impl Fn(TxnIndex) -> Option<Arc<CapturedReads<T>>>,
+    ) {
+        let _timer = READ_SET_PREFETCH_SECONDS
+            .with_label_values(&["record"])
+            .start_timer();
+        let mut block_reads = HashMap::new();
+        for (txn_idx, txn) in txns.iter().enumerate() {
+            let (Some(pattern), Some(read_set)) =
+                (txn.access_pattern(), read_set(txn_idx as TxnIndex))
+            else {
+                continue;
+            };
+            add_reads(
+                &mut block_reads,
+                pattern,
+                read_set.keys().cloned().collect(),
+            );
+        }
+        self.update_history(block_reads);
+    }
+
+    fn update_history(&self, block_reads: HashMap<String, HashSet<K>>) {
+        let mut history = self.history.lock();
+        for (pattern, keys) in block_reads {
+            if history.len() >= MAX_ACCESS_PATTERNS && !history.contains_key(&pattern) {
+                continue;
+            }
+            let mut keys = keys.into_iter().collect::<Vec<_>>();
+            keys.sort();
+            keys.truncate(MAX_KEYS_PER_PATTERN);
+            history.insert(pattern, keys);
+        }
+    }
+}
+
+/// Keeps the keys read by all the transactions with the same access pattern, which excludes
+/// e.g. the resources of their senders.
+fn add_reads<K: Eq + Hash>(
+    block_reads: &mut HashMap<String, HashSet<K>>,
+    pattern: String,
+    keys: HashSet<K>,
+) {
+    match block_reads.entry(pattern) {
+        Entry::Occupied(mut entry) => entry.get_mut().retain(|key| keys.contains(key)),
+        Entry::Vacant(entry) => {
+            entry.insert(keys);
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proptest_types::types::{
+        ColdDataView, KeyType, MockEvent, MockIncarnation, MockTransaction,
+    };
+    use std::time::Duration;
+
+    fn mock_txn(reads: &[u32]) -> MockTransaction<KeyType<u32>, MockEvent> {
+        MockTransaction::from_behavior(MockIncarnation::new(
+            reads.iter().map(|key| KeyType(*key, false)).collect(),
+            vec![],
+            vec![],
+            vec![],
+            1,
+        ))
+    }
+
+    #[test]
+    fn prefetches_during_execution() {
+        let txns = vec![mock_txn(&[3, 1]), mock_txn(&[1, 2])];
+        let prefetcher = ReadSetPrefetcher::new(2);
+        let keys = vec![KeyType(3, false), KeyType(1, false), KeyType(2, false)];
+        assert_eq!(prefetcher.predicted_reads(&txns), keys);
+
+        // The execution only finishes once the keys are prefetched, so the prefetching must run
+        // at the same time.
+        let data_view = ColdDataView::new(Duration::from_millis(1));
+        let start = Instant::now();
+        let result = prefetcher.prefetch_during(&txns, &data_view, || {
+            while !keys.iter().all(|key| data_view.is_warm(key)) {
+                assert!(start.elapsed() < Duration::from_secs(10));
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            42
+        });
+        assert_eq!(result, 42);
+    }
+
+    #[test]
+    fn remembers_reads_common_to_a_pattern() {
+        let mut block_reads = HashMap::new();
+        add_reads(
+            &mut block_reads,
+            "transfer".to_string(),
+            HashSet::from([1, 2, 3]),
+        );
+        add_reads(
+            &mut block_reads,
+            "transfer".to_string(),
+            HashSet::from([1, 3, 4]),
+        );
+        add_reads(&mut block_reads, "mint".to_string(), HashSet::from([5]));
+
+        let prefetcher = ReadSetPrefetcher::new(1);
+        prefetcher.update_history(block_reads);
+        let history = prefetcher.history.lock();
+        assert_eq!(history.get("transfer"), Some(&vec![1, 3]));
+        assert_eq!(history.get("mint"), Some(&vec![5]));
+    }
+}
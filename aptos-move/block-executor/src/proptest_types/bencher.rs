@@ -3,19 +3,21 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    counters::SPECULATIVE_ABORT_COUNT,
     executor::BlockExecutor,
+    prefetch::ReadSetPrefetcher,
     proptest_types::{
         baseline::BaselineOutput,
         types::{
-            EmptyDataView, KeyType, MockOutput, MockTask, MockTransaction, TransactionGen,
-            TransactionGenParams,
+            ColdDataView, EmptyDataView, KeyType, MockEvent, MockIncarnation, MockOutput, MockTask,
+            MockTransaction, TransactionGen, TransactionGenParams, ValueType,
         },
     },
     txn_commit_hook::NoOpTransactionCommitHook,
 };
 use aptos_types::{
     block_executor::config::BlockExecutorConfig, contract_event::TransactionEvent,
-    executable::ExecutableTestType,
+    executable::ExecutableTestType, transaction::BlockOutput,
 };
 use criterion::{BatchSize, Bencher as CBencher};
 use num_cpus;
@@ -26,7 +28,7 @@ use proptest::{
     strategy::{Strategy, ValueTree},
     test_runner::TestRunner,
 };
-use std::{fmt::Debug, hash::Hash, marker::PhantomData, sync::Arc};
+use std::{fmt::Debug, hash::Hash, marker::PhantomData, sync::Arc, time::Duration};
 
 pub struct Bencher<K, V, E> {
     transaction_size: usize,
@@ -141,3 +143,164 @@ where
         self.baseline_output.assert_parallel_output(&output);
     }
 }
+
+/// Measures the effect of prefetching the read sets on the execution of blocks whose state is
+/// cold, i.e. the first read of every key takes the given latency. The transactions hint their
+/// reads, so the prefetcher predicts their read sets exactly.
+pub struct PrefetchBencher<K, V> {
+    transaction_size: usize,
+    universe_size: usize,
+    read_latency: Duration,
+    phantom: PhantomData<(K, V)>,
+}
+
+impl<K, V> PrefetchBencher<K, V>
+where
+    K: Hash + Clone + Debug + Eq + Send + Sync + PartialOrd + Ord + Arbitrary + 'static,
+    V: Clone + Eq + Send + Sync + Arbitrary + 'static,
+    Vec<u8>: From<V>,
+{
+    pub fn new(transaction_size: usize, universe_size: usize, read_latency: Duration) -> Self {
+        Self {
+            transaction_size,
+            universe_size,
+            read_latency,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Benchmarks the execution of a block, with the prefetching of the read sets overlapping
+    /// with it if `prefetch` is set.
+    pub fn bench(
+        &self,
+        key_strategy: &impl Strategy<Value = K>,
+        prefetch: bool,
+        bencher: &mut CBencher,
+    ) {
+        let executor_thread_pool = Arc::new(
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(num_cpus::get())
+                .build()
+                .unwrap(),
+        );
+        let prefetcher = Arc::new(ReadSetPrefetcher::default());
+
+        bencher.iter_batched(
+            || {
+                (
+                    BencherState::<K, MockEvent>::with_universe::<V>(
+                        vec(key_strategy, self.universe_size),
+                        self.transaction_size,
+                        TransactionGenParams::default(),
+                    ),
+                    ColdDataView::<KeyType<K>>::new(self.read_latency),
+                )
+            },
+            |(state, data_view)| {
+                let mut executor = BlockExecutor::<
+                    MockTransaction<KeyType<K>, MockEvent>,
+                    MockTask<KeyType<K>, MockEvent>,
+                    ColdDataView<KeyType<K>>,
+                    NoOpTransactionCommitHook<MockOutput<KeyType<K>, MockEvent>, usize>,
+                    ExecutableTestType,
+                >::new(
+                    BlockExecutorConfig::new_no_block_limit(num_cpus::get()),
+                    executor_thread_pool.clone(),
+                    None,
+                );
+                if prefetch {
+                    executor = executor.with_read_set_prefetcher(prefetcher.clone());
+                }
+                let output = executor.execute_block((), &state.transactions, &data_view);
+                assert!(output.is_ok(), "Block execution must succeed");
+            },
+            BatchSize::LargeInput,
+        )
+    }
+}
+
+/// A block of mock transactions, each described by the keys it reads and the keys it writes.
+/// Useful for measuring how the order of transactions in a block affects the BlockSTM conflict
+/// rate.
+pub struct ReadWriteSetBlock<K: Hash + Clone + Debug + Eq + PartialOrd + Ord> {
+    transactions: Vec<MockTransaction<KeyType<K>, MockEvent>>,
+    baseline_output: BaselineOutput<KeyType<K>>,
+}
+
+impl<K> ReadWriteSetBlock<K>
+where
+    K: Hash + Clone + Debug + Eq + Send + Sync + PartialOrd + Ord + 'static,
+{
+    pub fn new(read_write_sets: Vec<(Vec<K>, Vec<K>)>) -> Self {
+        let transactions: Vec<_> = read_write_sets
+            .into_iter()
+            .map(|(reads, writes)| {
+                MockTransaction::from_behavior(MockIncarnation::new(
+                    reads.into_iter().map(|key| KeyType(key, false)).collect(),
+                    writes
+                        .into_iter()
+                        .map(|key| (KeyType(key, false), ValueType::from_value(vec![1_u8], true)))
+                        .collect(),
+                    vec![],
+                    vec![],
+                    1,
+                ))
+            })
+            .collect();
+        let baseline_output = BaselineOutput::generate(&transactions, None);
+
+        Self {
+            transactions,
+            baseline_output,
+        }
+    }
+
+    /// Executes the block in parallel on the given thread pool and returns the number of
+    /// speculative aborts (i.e. re-executions caused by read-write conflicts) incurred.
+    /// The output is not checked, see `execute_and_verify`.
+    pub fn execute(
+        &self,
+        executor_thread_pool: &Arc<rayon::ThreadPool>,
+        concurrency_level: usize,
+    ) -> u64 {
+        self.execute_block(executor_thread_pool, concurrency_level)
+            .0
+    }
+
+    /// Executes the block like `execute`, and checks the output against the sequential
+    /// baseline.
+    pub fn execute_and_verify(
+        &self,
+        executor_thread_pool: &Arc<rayon::ThreadPool>,
+        concurrency_level: usize,
+    ) -> u64 {
+        let (aborts, output) = self.execute_block(executor_thread_pool, concurrency_level);
+        self.baseline_output.assert_parallel_output(&output);
+        aborts
+    }
+
+    fn execute_block(
+        &self,
+        executor_thread_pool: &Arc<rayon::ThreadPool>,
+        concurrency_level: usize,
+    ) -> (
+        u64,
+        Result<BlockOutput<MockOutput<KeyType<K>, MockEvent>>, ()>,
+    ) {
+        let data_view = EmptyDataView::<KeyType<K>> {
+            phantom: PhantomData,
+        };
+
+        let aborts_before = SPECULATIVE_ABORT_COUNT.get();
+        let config = BlockExecutorConfig::new_no_block_limit(concurrency_level);
+        let output = BlockExecutor::<
+            MockTransaction<KeyType<K>, MockEvent>,
+            MockTask<KeyType<K>, MockEvent>,
+            EmptyDataView<KeyType<K>>,
+            NoOpTransactionCommitHook<MockOutput<KeyType<K>, MockEvent>, usize>,
+            ExecutableTestType,
+        >::new(config, executor_thread_pool.clone(), None)
+        .execute_transactions_parallel(&(), &self.transactions, &data_view);
+        (SPECULATIVE_ABORT_COUNT.get() - aborts_before, output)
+    }
+}
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    deterministic_schedule::{DeterministicSchedule, DeterministicScheduleConfig, SchedulePoint},
     errors::SequentialBlockExecutionError,
     executor::BlockExecutor,
     proptest_types::{
@@ -722,3 +723,80 @@ fn module_publishing_races_with_block_gas_limit_test() {
         );
     }
 }
+
+const DETERMINISTIC_SCHEDULE_CONCURRENCY_LEVEL: usize = 4;
+
+fn run_with_deterministic_schedule(
+    universe: &[[u8; 32]],
+    transaction_gen: &[TransactionGen<[u8; 32]>],
+    seed: u64,
+) -> Vec<(usize, SchedulePoint)> {
+    // Incarnation counters are shared between clones, so materialize the transactions anew.
+    let transactions: Vec<_> = transaction_gen
+        .iter()
+        .cloned()
+        .map(|txn_gen| txn_gen.materialize(universe, (false, false)))
+        .collect();
+
+    let data_view = EmptyDataView::<KeyType<[u8; 32]>> {
+        phantom: PhantomData,
+    };
+
+    // Fixed, so that the interleavings of a seed are the same on every machine. All workers
+    // must be able to run at the same time, as they wait for their turns.
+    let executor_thread_pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(DETERMINISTIC_SCHEDULE_CONCURRENCY_LEVEL)
+            .build()
+            .unwrap(),
+    );
+
+    let schedule = Arc::new(DeterministicSchedule::new(DeterministicScheduleConfig {
+        delay_percentage: 30,
+        ..DeterministicScheduleConfig::new(seed)
+    }));
+    let output = BlockExecutor::<
+        MockTransaction<KeyType<[u8; 32]>, MockEvent>,
+        MockTask<KeyType<[u8; 32]>, MockEvent>,
+        EmptyDataView<KeyType<[u8; 32]>>,
+        NoOpTransactionCommitHook<MockOutput<KeyType<[u8; 32]>, MockEvent>, usize>,
+        ExecutableTestType,
+    >::new(
+        // Replaced by the fixed concurrency level of the schedule.
+        BlockExecutorConfig::new_no_block_limit(1),
+        executor_thread_pool,
+        None,
+    )
+    .with_deterministic_schedule(schedule.clone(), DETERMINISTIC_SCHEDULE_CONCURRENCY_LEVEL)
+    .execute_transactions_parallel(&(), &transactions, &data_view);
+
+    BaselineOutput::generate(&transactions, None).assert_parallel_output(&output);
+    schedule.trace()
+}
+
+#[test_case(0)]
+#[test_case(1)]
+#[test_case(42)]
+#[test_case(1234567)]
+fn deterministic_schedule_contended(seed: u64) {
+    let mut runner = TestRunner::deterministic();
+    let universe = vec(any::<[u8; 32]>(), 10)
+        .new_tree(&mut runner)
+        .expect("creating a new value should succeed")
+        .current();
+    let transaction_gen = vec(
+        any_with::<TransactionGen<[u8; 32]>>(TransactionGenParams::new_dynamic()),
+        200,
+    )
+    .new_tree(&mut runner)
+    .expect("creating a new value should succeed")
+    .current();
+
+    let trace = run_with_deterministic_schedule(&universe, &transaction_gen, seed);
+    assert!(!trace.is_empty());
+    // Replaying the seed reproduces the exact same interleaving.
+    assert_eq!(
+        run_with_deterministic_schedule(&universe, &transaction_gen, seed),
+        trace
+    );
+}
@@ -8,6 +8,7 @@ use aptos_aggregator::{
     delta_change_set::{delta_add, delta_sub, serialize, DeltaOp},
     resolver::TAggregatorV1View,
 };
+use aptos_infallible::Mutex;
 use aptos_mvhashmap::types::TxnIndex;
 use aptos_types::{
     account_address::AccountAddress,
@@ -42,6 +43,7 @@ use std::{
         atomic::{AtomicUsize, Ordering},
         Arc,
     },
+    time::Duration,
 };
 
 // Should not be possible to overflow or underflow, as each delta is at most 100 in the tests.
@@ -136,6 +138,50 @@ where
     }
 }
 
+/// A data view without values, whose first read of every key takes the given latency, as if
+/// the key was read from cold storage and cached afterwards.
+pub(crate) struct ColdDataView<K> {
+    read_latency: Duration,
+    warm_keys: Mutex<HashSet<K>>,
+}
+
+impl<K: Hash + Eq> ColdDataView<K> {
+    pub(crate) fn new(read_latency: Duration) -> Self {
+        Self {
+            read_latency,
+            warm_keys: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Whether the key was read before.
+    pub(crate) fn is_warm(&self, key: &K) -> bool {
+        self.warm_keys.lock().contains(key)
+    }
+}
+
+impl<K> TStateView for ColdDataView<K>
+where
+    K: PartialOrd + Ord + Send + Sync + Clone + Hash + Eq + ModulePath + 'static,
+{
+    type Key = K;
+
+    fn get_state_value(&self, key: &K) -> Result<Option<StateValue>, StateviewError> {
+        if !self.warm_keys.lock().contains(key) {
+            std::thread::sleep(self.read_latency);
+            self.warm_keys.lock().insert(key.clone());
+        }
+        Ok(None)
+    }
+
+    fn id(&self) -> StateViewId {
+        StateViewId::Miscellaneous
+    }
+
+    fn get_usage(&self) -> Result<StateStorageUsage, StateviewError> {
+        unreachable!("Not used in tests");
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////////
 // Generation of transactions
 ///////////////////////////////////////////////////////////////////////////
@@ -462,6 +508,20 @@ impl<
     fn user_txn_bytes_len(&self) -> usize {
         0
     }
+
+    /// The reads of the first incarnation.
+    fn read_hints(&self) -> Vec<K> {
+        match self {
+            Self::Write {
+                incarnation_behaviors,
+                ..
+            } => incarnation_behaviors
+                .first()
+                .map(|behavior| behavior.reads.clone())
+                .unwrap_or_default(),
+            Self::SkipRest(_) | Self::Abort => vec![],
+        }
+    }
 }
 
 // TODO: try and test different strategies.
@@ -1,6 +1,8 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(any(test, feature = "fuzzing"))]
+use crate::deterministic_schedule::{self, SchedulePoint};
 #[cfg(test)]
 use crate::types::InputOutputKey;
 use crate::{
@@ -8,6 +10,7 @@ use crate::{
         CapturedReads, DataRead, DelayedFieldRead, DelayedFieldReadKind, GroupRead, ReadKind,
         UnsyncReadSet,
     },
+    conflict_report::ConflictRecorder,
     counters,
     scheduler::{DependencyResult, DependencyStatus, Scheduler, TWaitForDependency},
     value_exchange::{
@@ -70,6 +73,7 @@ use std::{
         atomic::{AtomicU32, Ordering},
         Arc,
     },
+    time::Instant,
 };
 
 /// A struct which describes the result of the read from the proxy. The client
@@ -161,6 +165,7 @@ pub(crate) struct ParallelState<'a, T: Transaction, X: Executable> {
     start_counter: u32,
     counter: &'a AtomicU32,
     captured_reads: RefCell<CapturedReads<T>>,
+    conflict_recorder: Option<&'a ConflictRecorder<T::Key>>,
 }
 
 fn get_delayed_field_value_impl<T: Transaction>(
@@ -425,6 +430,20 @@ fn wait_for_dependency(
             // than txn_idx are not blocked, so the execution of dep_idx will
             // eventually finish and lead to unblocking txn_idx, contradiction.
             let (lock, cvar) = &*dep_condition;
+            #[cfg(any(test, feature = "fuzzing"))]
+            if deterministic_schedule::is_active() {
+                // Only one worker runs at a time, so give the turn to the others until one
+                // of them resolves the dependency, instead of blocking.
+                loop {
+                    match &*lock.lock() {
+                        DependencyStatus::Unresolved => (),
+                        dep_resolved => {
+                            return Ok(matches!(dep_resolved, DependencyStatus::Resolved))
+                        },
+                    }
+                    deterministic_schedule::yield_point(SchedulePoint::DependencyWait);
+                }
+            }
             let mut dep_resolved = lock.lock();
             while matches!(*dep_resolved, DependencyStatus::Unresolved) {
                 dep_resolved = cvar.wait(dep_resolved).unwrap();
@@ -443,6 +462,7 @@ impl<'a, T: Transaction, X: Executable> ParallelState<'a, T, X> {
         shared_scheduler: &'a Scheduler,
         start_shared_counter: u32,
         shared_counter: &'a AtomicU32,
+        conflict_recorder: Option<&'a ConflictRecorder<T::Key>>,
     ) -> Self {
         Self {
             versioned_map: shared_map,
@@ -450,9 +470,27 @@ impl<'a, T: Transaction, X: Executable> ParallelState<'a, T, X> {
             start_counter: start_shared_counter,
             counter: shared_counter,
             captured_reads: RefCell::new(CapturedReads::new()),
+            conflict_recorder,
         }
     }
 
+    // Waits for the dependency of txn_idx on dep_idx, observed when reading key.
+    fn wait_for_dependency(
+        &self,
+        key: &T::Key,
+        txn_idx: TxnIndex,
+        dep_idx: TxnIndex,
+    ) -> Result<bool, PanicError> {
+        let Some(conflict_recorder) = self.conflict_recorder else {
+            return wait_for_dependency(self.scheduler, txn_idx, dep_idx);
+        };
+        conflict_recorder.record_dependency(txn_idx, dep_idx, key);
+        let wait_start = Instant::now();
+        let result = wait_for_dependency(self.scheduler, txn_idx, dep_idx);
+        conflict_recorder.record_dependency_wait_time(txn_idx, wait_start.elapsed());
+        result
+    }
+
     pub(crate) fn set_delayed_field_value(&self, id: T::Identifier, base_value: DelayedFieldValue) {
         self.versioned_map
             .delayed_fields()
@@ -508,7 +546,7 @@ impl<'a, T: Transaction, X: Executable> ParallelState<'a, T, X> {
                     unreachable!("Reading group size does not require a specific tag look-up");
                 },
                 Err(Dependency(dep_idx)) => {
-                    if !wait_for_dependency(self.scheduler, txn_idx, dep_idx)? {
+                    if !self.wait_for_dependency(group_key, txn_idx, dep_idx)? {
                         return Err(PartialVMError::new(
                             StatusCode::SPECULATIVE_EXECUTION_ABORT_ERROR,
                         )
@@ -633,7 +671,7 @@ impl<'a, T: Transaction, X: Executable> ResourceState<T> for ParallelState<'a, T
                     return ReadResult::Uninitialized;
                 },
                 Err(Dependency(dep_idx)) => {
-                    match wait_for_dependency(self.scheduler, txn_idx, dep_idx) {
+                    match self.wait_for_dependency(key, txn_idx, dep_idx) {
                         Err(e) => {
                             error!("Error {:?} in wait for dependency", e);
                             self.captured_reads.borrow_mut().mark_incorrect_use();
@@ -751,7 +789,7 @@ impl<'a, T: Transaction, X: Executable> ResourceGroupState<T> for ParallelState<
                     return Ok(GroupReadResult::Value(None, None));
                 },
                 Err(Dependency(dep_idx)) => {
-                    if !wait_for_dependency(self.scheduler, txn_idx, dep_idx)? {
+                    if !self.wait_for_dependency(group_key, txn_idx, dep_idx)? {
                         // TODO[agg_v2](cleanup): consider changing from PartialVMResult<GroupReadResult> to GroupReadResult
                         // like in ReadResult for resources.
                         return Err(PartialVMError::new(
@@ -2770,6 +2808,7 @@ mod test {
                         &self.scheduler,
                         self.start_counter,
                         &self.counter,
+                        None,
                     )),
                     1,
                 );
@@ -38,6 +38,7 @@ mod offer_rotation_capability;
 mod offer_signer_capability;
 mod per_category_gas_limits;
 mod randomness_test_and_abort;
+mod resource_access;
 mod resource_groups;
 mod rotate_auth_key;
 mod scripts;
@@ -0,0 +1,44 @@
+module 0xcafe::counter {
+    use std::vector;
+
+    #[resource_group(scope = global)]
+    struct Group {}
+
+    #[resource_group_member(group = 0xcafe::counter::Group)]
+    struct Profile has key {
+        name: vector<u8>,
+    }
+
+    struct Counter<phantom T> has key {
+        value: u64,
+    }
+
+    struct Config has key {
+        step: u64,
+    }
+
+    fun init_module(account: &signer) {
+        move_to(account, Config { step: 1 })
+    }
+
+    public entry fun create<T>(account: &signer, name: vector<u8>) {
+        move_to(account, Counter<T> { value: 0 });
+        move_to(account, Profile { name });
+    }
+
+    public entry fun increment<T>(_account: &signer, owner: address) acquires Config, Counter {
+        let step = borrow_global<Config>(@0xcafe).step;
+        add<T>(owner, step);
+    }
+
+    /// The addresses are taken from a vector, so the accesses cannot be determined statically.
+    public entry fun increment_all<T>(_account: &signer, owners: vector<address>) acquires Config, Counter {
+        let step = borrow_global<Config>(@0xcafe).step;
+        vector::for_each(owners, |owner| add<T>(owner, step));
+    }
+
+    fun add<T>(owner: address, step: u64) acquires Counter {
+        let counter = borrow_global_mut<Counter<T>>(owner);
+        counter.value = counter.value + step;
+    }
+}
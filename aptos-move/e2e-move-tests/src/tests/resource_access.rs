@@ -0,0 +1,157 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{assert_success, tests::common, MoveHarness};
+use aptos_cached_packages::aptos_stdlib;
+use aptos_framework::{get_metadata_from_compiled_module, BuildOptions};
+use aptos_language_e2e_tests::account::TransactionBuilder;
+use aptos_types::{
+    account_address::AccountAddress,
+    on_chain_config::FeatureFlag,
+    state_store::state_key::StateKey,
+    transaction::{
+        analyzed_transaction::{
+            coin_store_location, primary_fungible_store_location, StorageLocation,
+        },
+        EntryFunction, TransactionPayload,
+    },
+    utility_coin::APTOS_COIN_TYPE,
+};
+use aptos_vm::resource_access_hints::resource_access_hints;
+use move_binary_format::CompiledModule;
+use move_core_types::{
+    language_storage::{ModuleId, TypeTag},
+    parser::parse_struct_tag,
+};
+
+fn publish_counter(h: &mut MoveHarness) {
+    let acc = h.new_account_at(AccountAddress::from_hex_literal("0xcafe").unwrap());
+    assert_success!(h.publish_package_with_options(
+        &acc,
+        &common::test_dir_path("resource_access.data/pack"),
+        BuildOptions {
+            with_resource_access: true,
+            ..BuildOptions::default()
+        }
+    ));
+}
+
+fn resource_access(h: &MoveHarness, fun: &str) -> Option<Vec<String>> {
+    let module_id = ModuleId::new(
+        AccountAddress::from_hex_literal("0xcafe").unwrap(),
+        "counter".parse().unwrap(),
+    );
+    let module_bytes = h
+        .read_state_value_bytes(&StateKey::module_id(&module_id))
+        .unwrap();
+    let module = CompiledModule::deserialize(&module_bytes).unwrap();
+    let accesses = get_metadata_from_compiled_module(&module)
+        .unwrap()
+        .get_resource_access(fun)?;
+    Some(accesses.iter().map(ToString::to_string).collect())
+}
+
+#[test]
+fn test_resource_access_summaries() {
+    let mut h = MoveHarness::new();
+    publish_counter(&mut h);
+    let acc = AccountAddress::from_hex_literal("0xcafe").unwrap();
+    let owner = h.new_account_at(AccountAddress::from_hex_literal("0xf00d").unwrap());
+
+    assert_eq!(
+        resource_access(&h, "create"),
+        Some(vec![
+            "w signer:0 0xcafe::counter::Counter<$0>".to_string(),
+            "wg signer:0 0xcafe::counter::Group".to_string(),
+        ])
+    );
+    assert_eq!(
+        resource_access(&h, "increment"),
+        Some(vec![
+            "r 0xcafe 0xcafe::counter::Config".to_string(),
+            "w arg:0 0xcafe::counter::Counter<$0>".to_string(),
+        ])
+    );
+    assert_eq!(resource_access(&h, "increment_all"), None);
+
+    // The summaries are instantiated with the signers, arguments and type arguments of calls.
+    let txn = h.create_entry_function(
+        &owner,
+        str::parse("0xcafe::counter::increment").unwrap(),
+        vec![TypeTag::U64],
+        vec![bcs::to_bytes(owner.address()).unwrap()],
+    );
+    let (read_hints, write_hints) = resource_access_hints(&txn, h.executor.data_store()).unwrap();
+    let counter = StateKey::resource(
+        owner.address(),
+        &parse_struct_tag("0xcafe::counter::Counter<u64>").unwrap(),
+    )
+    .unwrap();
+    let config =
+        StateKey::resource(&acc, &parse_struct_tag("0xcafe::counter::Config").unwrap()).unwrap();
+    assert!(write_hints.contains(&StorageLocation::Specific(counter)));
+    assert!(read_hints.contains(&StorageLocation::Specific(config)));
+
+    let txn = h.create_entry_function(
+        &owner,
+        str::parse("0xcafe::counter::increment_all").unwrap(),
+        vec![TypeTag::U64],
+        vec![bcs::to_bytes(&vec![*owner.address()]).unwrap()],
+    );
+    assert!(resource_access_hints(&txn, h.executor.data_store()).is_none());
+}
+
+#[test]
+fn test_resource_access_hints_fa_gas_payer() {
+    let mut h = MoveHarness::new();
+    publish_counter(&mut h);
+    let owner = h.new_account_at(AccountAddress::from_hex_literal("0xf00d").unwrap());
+    let payer = h.new_account_at(AccountAddress::from_hex_literal("0xb0b").unwrap());
+    assert_success!(h.run_entry_function(
+        &owner,
+        str::parse("0xcafe::counter::create").unwrap(),
+        vec![TypeTag::U64],
+        vec![bcs::to_bytes(&b"owner".to_vec()).unwrap()],
+    ));
+
+    // Move the balance of the gas payer into its primary fungible store, and charge gas from it.
+    assert_success!(h.run_transaction_payload(
+        &payer,
+        aptos_stdlib::coin_migrate_to_fungible_store(APTOS_COIN_TYPE.clone()),
+    ));
+    h.enable_features(
+        vec![FeatureFlag::OPERATIONS_DEFAULT_TO_FA_APT_STORE],
+        vec![],
+    );
+
+    let payload = TransactionPayload::EntryFunction(EntryFunction::new(
+        ModuleId::new(
+            AccountAddress::from_hex_literal("0xcafe").unwrap(),
+            "counter".parse().unwrap(),
+        ),
+        "increment".parse().unwrap(),
+        vec![TypeTag::U64],
+        vec![bcs::to_bytes(owner.address()).unwrap()],
+    ));
+    let txn = TransactionBuilder::new(owner.clone())
+        .fee_payer(payer.clone())
+        .payload(payload)
+        .sequence_number(h.sequence_number(owner.address()))
+        .max_gas_amount(100_000)
+        .gas_unit_price(100)
+        .sign_fee_payer();
+
+    // The gas payer, rather than the sender, pays from either of its stores.
+    let (_, write_hints) = resource_access_hints(&txn, h.executor.data_store()).unwrap();
+    let payer_store = primary_fungible_store_location(*payer.address());
+    assert!(write_hints.contains(&payer_store));
+    assert!(write_hints.contains(&coin_store_location(*payer.address())));
+    assert!(!write_hints.contains(&primary_fungible_store_location(*owner.address())));
+
+    let output = h.run_raw(txn);
+    assert_success!(*output.status());
+    assert!(output
+        .write_set()
+        .iter()
+        .any(|(state_key, _)| state_key == payer_store.state_key()));
+}
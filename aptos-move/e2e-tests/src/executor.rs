@@ -529,6 +529,8 @@ impl FakeExecutor {
                 },
                 allow_fallback: self.allow_block_executor_fallback,
                 discard_failed_blocks: false,
+                conflict_report: false,
+                prefetch_read_sets: false,
             },
             onchain: onchain_config,
         };
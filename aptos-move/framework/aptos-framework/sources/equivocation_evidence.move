@@ -0,0 +1,87 @@
+/// Provable records of validators equivocating in consensus, i.e., signing two conflicting votes
+/// (or proposals) for the same round. Reports are submitted by validators as validator transactions,
+/// and consensus only certifies blocks whose reports carry valid evidence (both conflicting signed
+/// messages). The evidence is emitted in an event, so that it can be re-verified (e.g., by governance).
+module aptos_framework::equivocation_evidence {
+    use aptos_std::table::{Self, Table};
+    use aptos_framework::event::emit;
+    use aptos_framework::system_addresses;
+
+    /// The offender signed two different votes for the same round.
+    const KIND_VOTE: u8 = 0;
+    /// The offender signed two different proposals for the same round.
+    const KIND_PROPOSAL: u8 = 1;
+
+    /// Identifies an equivocation. At most one report is recorded per equivocation.
+    struct EquivocationKey has copy, drop, store {
+        epoch: u64,
+        round: u64,
+        kind: u8,
+        offender: address,
+    }
+
+    /// The equivocations recorded so far, mapped to the validator that reported them.
+    struct EquivocationRecords has key {
+        reporters: Table<EquivocationKey, address>,
+    }
+
+    #[event]
+    /// Emitted when an equivocation is recorded, carrying the (BCS encoded) consensus evidence.
+    struct EquivocationReported has drop, store {
+        epoch: u64,
+        round: u64,
+        kind: u8,
+        offender: address,
+        reporter: address,
+        evidence: vector<u8>,
+    }
+
+    #[view]
+    /// Returns whether the given equivocation has been recorded.
+    public fun is_recorded(epoch: u64, round: u64, kind: u8, offender: address): bool acquires EquivocationRecords {
+        if (!exists<EquivocationRecords>(@aptos_framework)) {
+            return false
+        };
+        let records = borrow_global<EquivocationRecords>(@aptos_framework);
+        table::contains(&records.reporters, EquivocationKey { epoch, round, kind, offender })
+    }
+
+    /// Records an equivocation report, and emits the evidence. Reports of an equivocation that
+    /// was already recorded are ignored. Called by the VM when executing a validator transaction.
+    fun record_equivocation_report(
+        framework: &signer,
+        epoch: u64,
+        round: u64,
+        kind: u8,
+        offender: address,
+        reporter: address,
+        evidence: vector<u8>,
+    ) acquires EquivocationRecords {
+        system_addresses::assert_aptos_framework(framework);
+        if (!exists<EquivocationRecords>(@aptos_framework)) {
+            move_to(framework, EquivocationRecords { reporters: table::new() });
+        };
+
+        let records = borrow_global_mut<EquivocationRecords>(@aptos_framework);
+        let key = EquivocationKey { epoch, round, kind, offender };
+        if (table::contains(&records.reporters, key)) {
+            return
+        };
+        table::add(&mut records.reporters, key, reporter);
+        emit(EquivocationReported { epoch, round, kind, offender, reporter, evidence });
+    }
+
+    #[test(framework = @aptos_framework)]
+    fun test_record_equivocation_report(framework: &signer) acquires EquivocationRecords {
+        assert!(!is_recorded(1, 2, KIND_VOTE, @0x123), 0);
+        record_equivocation_report(framework, 1, 2, KIND_VOTE, @0x123, @0x456, b"evidence");
+        assert!(is_recorded(1, 2, KIND_VOTE, @0x123), 1);
+        assert!(!is_recorded(1, 2, KIND_PROPOSAL, @0x123), 2);
+
+        // A second report of the same equivocation is ignored
+        record_equivocation_report(framework, 1, 2, KIND_VOTE, @0x123, @0x789, b"evidence");
+        let records = borrow_global<EquivocationRecords>(@aptos_framework);
+        let key = EquivocationKey { epoch: 1, round: 2, kind: KIND_VOTE, offender: @0x123 };
+        assert!(*table::borrow(&records.reporters, key) == @0x456, 3);
+    }
+}
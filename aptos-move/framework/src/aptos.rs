@@ -125,6 +125,7 @@ impl ReleaseTarget {
                 language_version: None,
                 skip_attribute_checks: false,
                 check_test_code: false,
+                with_resource_access: false,
                 known_attributes: extended_checks::get_all_attribute_names().clone(),
                 experiments: vec![],
             },
@@ -93,6 +93,10 @@ pub struct BuildOptions {
     pub skip_attribute_checks: bool,
     #[clap(long)]
     pub check_test_code: bool,
+    /// Records the resources accessed by entry functions in the module metadata, where they can
+    /// be determined statically, to be used as read/write hints.
+    #[clap(long)]
+    pub with_resource_access: bool,
     #[clap(skip)]
     pub known_attributes: BTreeSet<String>,
     #[clap(skip)]
@@ -122,6 +126,7 @@ impl Default for BuildOptions {
             language_version: None,
             skip_attribute_checks: false,
             check_test_code: false,
+            with_resource_access: false,
             known_attributes: extended_checks::get_all_attribute_names().clone(),
             experiments: vec![],
         }
@@ -249,7 +254,11 @@ impl BuiltPackage {
 
         // Run extended checks as well derive runtime metadata
         let model = &model_opt.expect("move model");
-        let runtime_metadata = extended_checks::run_extended_checks(model);
+        let runtime_metadata = if options.with_resource_access {
+            extended_checks::run_extended_checks_with_resource_access(model)
+        } else {
+            extended_checks::run_extended_checks(model)
+        };
         if model.diag_count(Severity::Warning) > 0 {
             let mut error_writer = StandardStream::stderr(ColorChoice::Auto);
             model.report_diag(&mut error_writer, Severity::Warning);
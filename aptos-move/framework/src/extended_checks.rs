@@ -1,7 +1,9 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{KnownAttribute, RandomnessAnnotation, RuntimeModuleMetadataV1};
+use crate::{
+    AccessAddress, KnownAttribute, RandomnessAnnotation, ResourceAccess, RuntimeModuleMetadataV1,
+};
 use move_binary_format::file_format::{Ability, AbilitySet, Visibility};
 use move_cli::base::test_validation;
 use move_compiler::shared::known_attributes;
@@ -12,7 +14,7 @@ use move_core_types::{
     language_storage::ModuleId,
 };
 use move_model::{
-    ast::{Attribute, AttributeValue, Value},
+    ast::{Address, Attribute, AttributeValue, TempIndex, Value},
     model::{
         FunId, FunctionEnv, GlobalEnv, Loc, ModuleEnv, NamedConstantEnv, Parameter, QualifiedId,
         StructEnv, StructId,
@@ -22,13 +24,13 @@ use move_model::{
 };
 use move_stackless_bytecode::{
     function_target::{FunctionData, FunctionTarget},
-    stackless_bytecode::{AttrId, Bytecode, Operation},
+    stackless_bytecode::{AttrId, Bytecode, Constant, Operation},
     stackless_bytecode_generator::StacklessBytecodeGenerator,
 };
 use num_traits::Signed;
 use once_cell::sync::Lazy;
 use std::{
-    collections::{BTreeMap, BTreeSet},
+    collections::{btree_map::Entry, BTreeMap, BTreeSet},
     rc::Rc,
     str::FromStr,
 };
@@ -50,6 +52,33 @@ const VIEW_FUN_ATTRIBUTE: &str = "view";
 
 const RANDOMNESS_MODULE_NAME: &str = "randomness";
 
+/// Framework modules whose native functions do not access global storage.
+const PURE_NATIVE_MODULES: [&str; 23] = [
+    "aggregator_v2",
+    "aptos_hash",
+    "bcs",
+    "bls12381",
+    "create_signer",
+    "crypto_algebra",
+    "debug",
+    "ed25519",
+    "event",
+    "from_bcs",
+    "hash",
+    "multi_ed25519",
+    "object",
+    "randomness",
+    "ristretto255",
+    "ristretto255_bulletproofs",
+    "secp256k1",
+    "signer",
+    "string",
+    "string_utils",
+    "transaction_context",
+    "type_info",
+    "vector",
+];
+
 // top-level attribute names, only.
 pub fn get_all_attribute_names() -> &'static BTreeSet<String> {
     const ALL_ATTRIBUTE_NAMES: [&str; 8] = [
@@ -88,6 +117,17 @@ pub fn run_extended_checks(env: &GlobalEnv) -> BTreeMap<ModuleId, RuntimeModuleM
     checker.output
 }
 
+/// Like [run_extended_checks], but additionally records the resources accessed by the entry
+/// functions whose accesses can be determined statically, to be used as read/write hints.
+pub fn run_extended_checks_with_resource_access(
+    env: &GlobalEnv,
+) -> BTreeMap<ModuleId, RuntimeModuleMetadataV1> {
+    let mut checker = ExtendedChecker::new(env);
+    checker.record_resource_access = true;
+    checker.run();
+    checker.output
+}
+
 /// Configures the move-cli unit test validation hook to run the extended checker.
 pub fn configure_extended_checks_for_unit_test() {
     fn validate(env: &GlobalEnv) {
@@ -105,6 +145,10 @@ struct ExtendedChecker<'a> {
     error_category_module: ModuleId,
     /// A cache for functions which are known to call or not call randomness features
     randomness_caller_cache: BTreeMap<QualifiedId<FunId>, bool>,
+    /// Whether to record the resources accessed by entry functions
+    record_resource_access: bool,
+    /// A cache for the resource accesses of functions, `None` if they cannot be determined
+    access_summary_cache: BTreeMap<QualifiedId<FunId>, Option<Rc<AccessSummary>>>,
 }
 
 impl<'a> ExtendedChecker<'a> {
@@ -117,6 +161,8 @@ impl<'a> ExtendedChecker<'a> {
                 Identifier::new("error").unwrap(),
             ),
             randomness_caller_cache: BTreeMap::new(),
+            record_resource_access: false,
+            access_summary_cache: BTreeMap::new(),
         }
     }
 
@@ -131,6 +177,9 @@ impl<'a> ExtendedChecker<'a> {
                 self.check_unsafe_randomness_usage(module);
                 self.check_and_record_events(module);
                 self.check_init_module(module);
+                if self.record_resource_access {
+                    self.check_and_record_resource_access(module);
+                }
                 self.build_error_map(module)
             }
         }
@@ -830,6 +879,411 @@ impl<'a> ExtendedChecker<'a> {
     }
 }
 
+// ----------------------------------------------------------------------------------
+// Resource Access
+
+/// Where an address value in a function comes from.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum AddressSource {
+    Param(usize),
+    Constant(AccountAddress),
+    /// The address of the module declaring the type.
+    TypeAddress(Type),
+    /// Not an address but the `0x1::type_info::TypeInfo` of the type, which carries its address.
+    TypeInfo(Type),
+}
+
+/// A resource access of a function, relative to its parameters and type parameters.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Access {
+    write: bool,
+    address: AddressSource,
+    resource: Type,
+}
+
+/// The resources a function may access, and where the addresses it returns come from.
+#[derive(Debug, Default)]
+struct AccessSummary {
+    accesses: BTreeSet<Access>,
+    returns: Vec<Option<AddressSource>>,
+}
+
+impl<'a> ExtendedChecker<'a> {
+    /// Records the resources accessed by entry functions, if all of them can be determined from
+    /// the signers, arguments and type arguments of the call.
+    fn check_and_record_resource_access(&mut self, module: &ModuleEnv) {
+        let module_id = self.get_runtime_module_id(module);
+        for ref fun in module.get_functions() {
+            if !fun.is_entry() {
+                continue;
+            }
+            let fun_id = module.get_id().qualified(fun.get_id());
+            let Some(accesses) = self
+                .access_summary(fun_id)
+                .and_then(|summary| self.get_entry_resource_access(fun, &summary))
+            else {
+                continue;
+            };
+            self.output
+                .entry(module_id.clone())
+                .or_default()
+                .fun_attributes
+                .entry(fun.get_name_str())
+                .or_default()
+                .push(KnownAttribute::resource_access(&accesses));
+        }
+    }
+
+    /// Translates the accesses of an entry function to be relative to its transaction, where the
+    /// leading signer parameters are the signers, and the remaining ones the arguments.
+    fn get_entry_resource_access(
+        &self,
+        fun: &FunctionEnv,
+        summary: &AccessSummary,
+    ) -> Option<Vec<ResourceAccess>> {
+        let num_signers = fun
+            .get_parameter_types()
+            .iter()
+            .take_while(|ty| ty.skip_reference().is_signer())
+            .count();
+        let mut accesses = BTreeSet::new();
+        for access in &summary.accesses {
+            let address = match &access.address {
+                AddressSource::Param(idx) if *idx < num_signers => AccessAddress::Signer(*idx),
+                AddressSource::Param(idx) => AccessAddress::Argument(idx - num_signers),
+                AddressSource::Constant(address) => AccessAddress::Constant(*address),
+                AddressSource::TypeAddress(Type::TypeParameter(idx)) => {
+                    AccessAddress::TypeArgument(*idx as usize)
+                },
+                AddressSource::TypeAddress(Type::Struct(mid, _, _)) => AccessAddress::Constant(
+                    self.env
+                        .get_module(*mid)
+                        .get_name()
+                        .addr()
+                        .expect_numerical(),
+                ),
+                _ => return None,
+            };
+            let (resource_group, resource) =
+                match self.get_resource_group_container(&access.resource) {
+                    Some(container) => (true, container),
+                    None => (false, access.resource.clone()),
+                };
+            accesses.insert(ResourceAccess {
+                write: access.write,
+                resource_group,
+                address,
+                resource: self.get_type_tag_string(&resource)?,
+            });
+        }
+        Some(accesses.into_iter().collect())
+    }
+
+    /// Computes the resource accesses of the given function, or `None` if they cannot be
+    /// determined, e.g. because an address is computed, or a native function accesses storage.
+    /// Cycles in the call graph are cut by assuming the accesses cannot be determined.
+    fn access_summary(&mut self, fun: QualifiedId<FunId>) -> Option<Rc<AccessSummary>> {
+        if let Some(summary) = self.access_summary_cache.get(&fun) {
+            return summary.clone();
+        }
+        self.access_summary_cache.insert(fun, None);
+        let summary = self.compute_access_summary(fun).map(Rc::new);
+        self.access_summary_cache.insert(fun, summary.clone());
+        summary
+    }
+
+    fn compute_access_summary(&mut self, fun: QualifiedId<FunId>) -> Option<AccessSummary> {
+        let fun_env = self.env.get_function(fun);
+        if let Some(summary) = self.get_known_access_summary(fun) {
+            return Some(summary);
+        }
+        if fun_env.is_native() {
+            let module_name = fun_env.module_env.get_name();
+            return (self.is_framework_function(fun)
+                && PURE_NATIVE_MODULES.contains(&self.name_string(module_name.name()).as_str()))
+            .then(|| AccessSummary {
+                accesses: BTreeSet::new(),
+                returns: vec![None; fun_env.get_return_count()],
+            });
+        }
+        if fun_env.is_inline() {
+            return None;
+        }
+        let data = self.get_stackless_data(&fun_env);
+        let target = FunctionTarget::new(&fun_env, &data);
+
+        // Compute the sources of the address values in the temporaries, by iterating over the
+        // definitions until a fixpoint is reached. Temporaries with different definitions, or
+        // which may be modified through a mutable reference, have an unknown source (`None`).
+        let mut sources = BTreeMap::new();
+        for idx in 0..target.get_parameter_count() {
+            let source = (!target.get_local_type(idx).is_mutable_reference())
+                .then_some(AddressSource::Param(idx));
+            sources.insert(idx, source);
+        }
+        loop {
+            let mut changed = false;
+            for bc in target.get_bytecode() {
+                for (temp, source) in self.get_address_definitions(&target, bc, &sources)? {
+                    changed |= merge_address_source(&mut sources, temp, source);
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        let source = |temp: &TempIndex| sources.get(temp).cloned().flatten();
+
+        let mut accesses = BTreeSet::new();
+        let mut returns = BTreeMap::new();
+        for bc in target.get_bytecode() {
+            match bc {
+                Bytecode::Call(_, dests, op, srcs, _) => {
+                    let (write, mid, sid, inst, address) = match op {
+                        Operation::MoveTo(mid, sid, inst) => (true, mid, sid, inst, &srcs[1]),
+                        Operation::MoveFrom(mid, sid, inst) => (true, mid, sid, inst, &srcs[0]),
+                        Operation::Exists(mid, sid, inst) => (false, mid, sid, inst, &srcs[0]),
+                        Operation::BorrowGlobal(mid, sid, inst) => {
+                            let write = target.get_local_type(dests[0]).is_mutable_reference();
+                            (write, mid, sid, inst, &srcs[0])
+                        },
+                        Operation::Function(mid, fid, inst) => {
+                            let summary = self.access_summary(mid.qualified(*fid))?;
+                            for access in &summary.accesses {
+                                accesses.insert(Access {
+                                    write: access.write,
+                                    address: map_address_source(
+                                        &access.address,
+                                        srcs,
+                                        inst,
+                                        &sources,
+                                    )
+                                    .flatten()?,
+                                    resource: access.resource.instantiate(inst),
+                                });
+                            }
+                            continue;
+                        },
+                        _ => continue,
+                    };
+                    accesses.insert(Access {
+                        write,
+                        address: source(address)?,
+                        resource: Type::Struct(*mid, *sid, inst.clone()),
+                    });
+                },
+                Bytecode::Ret(_, srcs) => {
+                    for (idx, temp) in srcs.iter().enumerate() {
+                        merge_address_source(&mut returns, idx, source(temp));
+                    }
+                },
+                _ => {},
+            }
+        }
+        Some(AccessSummary {
+            accesses,
+            returns: (0..target.get_return_count())
+                .map(|idx| returns.get(&idx).cloned().flatten())
+                .collect(),
+        })
+    }
+
+    /// The summaries of framework functions which cannot be derived from their code.
+    fn get_known_access_summary(&self, fun: QualifiedId<FunId>) -> Option<AccessSummary> {
+        if self.is_function(fun, "0x1::object::exists_at") {
+            Some(AccessSummary {
+                accesses: BTreeSet::from([Access {
+                    write: false,
+                    address: AddressSource::Param(0),
+                    resource: Type::TypeParameter(0),
+                }]),
+                returns: vec![None],
+            })
+        } else if self.is_function(fun, "0x1::create_signer::create_signer")
+            || self.is_function(fun, "0x1::signer::borrow_address")
+            || self.is_function(fun, "0x1::object::object_address")
+        {
+            Some(AccessSummary {
+                accesses: BTreeSet::new(),
+                returns: vec![Some(AddressSource::Param(0))],
+            })
+        } else if self.is_function(fun, "0x1::type_info::type_of") {
+            Some(AccessSummary {
+                accesses: BTreeSet::new(),
+                returns: vec![Some(AddressSource::TypeInfo(Type::TypeParameter(0)))],
+            })
+        } else {
+            None
+        }
+    }
+
+    /// The sources of the temporaries defined by the instruction, given the sources known so
+    /// far. Returns `None` if a called function has unknown accesses.
+    fn get_address_definitions(
+        &mut self,
+        target: &FunctionTarget,
+        bc: &Bytecode,
+        sources: &BTreeMap<TempIndex, Option<AddressSource>>,
+    ) -> Option<Vec<(TempIndex, Option<AddressSource>)>> {
+        // Definitions from temporaries whose sources are not known yet are deferred to a later
+        // iteration.
+        let copy = |dest: TempIndex, src: &TempIndex| -> Vec<(TempIndex, Option<AddressSource>)> {
+            sources
+                .get(src)
+                .map(|source| (dest, source.clone()))
+                .into_iter()
+                .collect()
+        };
+        Some(match bc {
+            Bytecode::Assign(_, dest, src, _) => copy(*dest, src),
+            Bytecode::Load(_, dest, Constant::Address(Address::Numerical(address))) => {
+                vec![(*dest, Some(AddressSource::Constant(*address)))]
+            },
+            Bytecode::Load(_, dest, _) => vec![(*dest, None)],
+            Bytecode::Call(_, dests, Operation::BorrowLoc, srcs, _) => {
+                if target.get_local_type(dests[0]).is_mutable_reference() {
+                    vec![(dests[0], None), (srcs[0], None)]
+                } else {
+                    copy(dests[0], &srcs[0])
+                }
+            },
+            Bytecode::Call(_, dests, Operation::ReadRef | Operation::FreezeRef(_), srcs, _) => {
+                copy(dests[0], &srcs[0])
+            },
+            Bytecode::Call(_, dests, Operation::Function(mid, fid, inst), srcs, _) => {
+                let callee = mid.qualified(*fid);
+                if self.is_function(callee, "0x1::type_info::account_address") {
+                    return Some(match sources.get(&srcs[0]) {
+                        Some(Some(AddressSource::TypeInfo(ty))) => {
+                            vec![(dests[0], Some(AddressSource::TypeAddress(ty.clone())))]
+                        },
+                        Some(_) => vec![(dests[0], None)],
+                        None => vec![],
+                    });
+                }
+                let summary = self.access_summary(callee)?;
+                dests
+                    .iter()
+                    .zip(&summary.returns)
+                    .filter_map(|(dest, ret)| match ret {
+                        Some(ret) => map_address_source(ret, srcs, inst, sources)
+                            .map(|source| (*dest, source)),
+                        None => Some((*dest, None)),
+                    })
+                    .collect()
+            },
+            Bytecode::Call(_, dests, _, _, _) => dests.iter().map(|dest| (*dest, None)).collect(),
+            _ => vec![],
+        })
+    }
+
+    /// If the type is a member of a resource group, the type of its group container.
+    fn get_resource_group_container(&self, ty: &Type) -> Option<Type> {
+        let Type::Struct(mid, sid, _) = ty else {
+            return None;
+        };
+        let struct_ = self.env.get_struct(mid.qualified(*sid));
+        struct_.get_attributes().iter().find_map(|attr| {
+            let Attribute::Apply(_, name, attributes) = attr else {
+                return None;
+            };
+            if self.name_string(*name).as_str() != RESOURCE_GROUP_MEMBER {
+                return None;
+            }
+            let Some(Attribute::Assign(_, _, AttributeValue::Name(_, Some(module), name))) =
+                attributes.first()
+            else {
+                return None;
+            };
+            let module = self.env.find_module(module)?;
+            let container = module.find_struct(*name)?;
+            Some(Type::Struct(
+                container.module_env.get_id(),
+                container.get_id(),
+                vec![],
+            ))
+        })
+    }
+
+    /// The type as a string which can be parsed as a `TypeTag` once the type parameters, which
+    /// are represented as `$<index>`, are substituted.
+    fn get_type_tag_string(&self, ty: &Type) -> Option<String> {
+        Some(match ty {
+            Type::Primitive(PrimitiveType::Bool) => "bool".to_string(),
+            Type::Primitive(PrimitiveType::U8) => "u8".to_string(),
+            Type::Primitive(PrimitiveType::U16) => "u16".to_string(),
+            Type::Primitive(PrimitiveType::U32) => "u32".to_string(),
+            Type::Primitive(PrimitiveType::U64) => "u64".to_string(),
+            Type::Primitive(PrimitiveType::U128) => "u128".to_string(),
+            Type::Primitive(PrimitiveType::U256) => "u256".to_string(),
+            Type::Primitive(PrimitiveType::Address) => "address".to_string(),
+            Type::Primitive(PrimitiveType::Signer) => "signer".to_string(),
+            Type::Vector(elem) => format!("vector<{}>", self.get_type_tag_string(elem)?),
+            Type::Struct(mid, sid, inst) => {
+                let struct_ = self.env.get_struct(mid.qualified(*sid));
+                let module_name = struct_.module_env.get_name();
+                let name = format!(
+                    "{}::{}::{}",
+                    module_name.addr().expect_numerical().to_hex_literal(),
+                    self.name_string(module_name.name()),
+                    self.name_string(struct_.get_name())
+                );
+                if inst.is_empty() {
+                    name
+                } else {
+                    let inst = inst
+                        .iter()
+                        .map(|ty| self.get_type_tag_string(ty))
+                        .collect::<Option<Vec<_>>>()?;
+                    format!("{}<{}>", name, inst.join(","))
+                }
+            },
+            Type::TypeParameter(idx) => format!("${}", idx),
+            _ => return None,
+        })
+    }
+}
+
+/// Merges a definition into the known sources, returning whether they changed.
+fn merge_address_source<K: Ord>(
+    sources: &mut BTreeMap<K, Option<AddressSource>>,
+    key: K,
+    source: Option<AddressSource>,
+) -> bool {
+    match sources.entry(key) {
+        Entry::Vacant(entry) => {
+            entry.insert(source);
+            true
+        },
+        Entry::Occupied(mut entry) => {
+            if entry.get().is_some() && *entry.get() != source {
+                entry.insert(None);
+                true
+            } else {
+                false
+            }
+        },
+    }
+}
+
+/// Maps a source of a called function to the caller, given the arguments and type arguments of
+/// the call. Returns `None` if the source of the argument is not known yet.
+fn map_address_source(
+    source: &AddressSource,
+    args: &[TempIndex],
+    inst: &[Type],
+    sources: &BTreeMap<TempIndex, Option<AddressSource>>,
+) -> Option<Option<AddressSource>> {
+    match source {
+        AddressSource::Param(idx) => sources.get(&args[*idx]).cloned(),
+        AddressSource::Constant(address) => Some(Some(AddressSource::Constant(*address))),
+        AddressSource::TypeAddress(ty) => {
+            Some(Some(AddressSource::TypeAddress(ty.instantiate(inst))))
+        },
+        AddressSource::TypeInfo(ty) => Some(Some(AddressSource::TypeInfo(ty.instantiate(inst)))),
+    }
+}
+
 // ----------------------------------------------------------------------------------
 // Helpers
 
@@ -4,6 +4,7 @@
 use crate::extended_checks::ResourceGroupScope;
 use aptos_types::{
     on_chain_config::{FeatureFlag, Features, TimedFeatureFlag, TimedFeatures},
+    state_store::state_key::StateKey,
     transaction::AbortInfo,
 };
 use lru::LruCache;
@@ -16,15 +17,16 @@ use move_binary_format::{
     CompiledModule,
 };
 use move_core_types::{
+    account_address::AccountAddress,
     errmap::ErrorDescription,
     identifier::{IdentStr, Identifier},
-    language_storage::{ModuleId, StructTag},
+    language_storage::{ModuleId, StructTag, TypeTag},
     metadata::Metadata,
 };
 use move_model::metadata::{CompilationMetadata, COMPILATION_METADATA_KEY};
 use move_vm_runtime::move_vm::MoveVM;
 use serde::{Deserialize, Serialize};
-use std::{cell::RefCell, collections::BTreeMap, env, sync::Arc};
+use std::{cell::RefCell, collections::BTreeMap, env, fmt, str::FromStr, sync::Arc};
 use thiserror::Error;
 
 /// The minimal file format version from which the V1 metadata is supported
@@ -85,6 +87,7 @@ pub enum KnownAttributeKind {
     ResourceGroupMember = 3,
     Event = 4,
     Randomness = 5,
+    ResourceAccess = 6,
 }
 
 impl KnownAttribute {
@@ -176,6 +179,25 @@ impl KnownAttribute {
             None
         }
     }
+
+    pub fn resource_access(accesses: &[ResourceAccess]) -> Self {
+        Self {
+            kind: KnownAttributeKind::ResourceAccess as u8,
+            args: accesses.iter().map(|access| access.to_string()).collect(),
+        }
+    }
+
+    pub fn is_resource_access(&self) -> bool {
+        self.kind == KnownAttributeKind::ResourceAccess as u8
+    }
+
+    pub fn get_resource_access(&self) -> Option<Vec<ResourceAccess>> {
+        if self.kind == KnownAttributeKind::ResourceAccess as u8 {
+            self.args.iter().map(|arg| arg.parse().ok()).collect()
+        } else {
+            None
+        }
+    }
 }
 
 const METADATA_CACHE_SIZE: usize = 1024;
@@ -415,6 +437,28 @@ pub fn is_valid_unbiasable_function(
     })
 }
 
+/// Only checks that the attribute is well formed and attached to an entry function: the declared
+/// accesses are not verified against the bytecode, so they can only serve as advisory hints, which
+/// their users have to check (see `aptos_vm::resource_access_hints`).
+pub fn is_valid_resource_access(
+    functions: &BTreeMap<&IdentStr, (&FunctionHandle, &FunctionDefinition)>,
+    fun: &str,
+    attr: &KnownAttribute,
+) -> Result<(), AttributeValidationError> {
+    if let Ok(ident_fun) = Identifier::new(fun) {
+        if let Some((_func_handle, func_def)) = functions.get(ident_fun.as_ident_str()) {
+            if func_def.is_entry && attr.get_resource_access().is_some() {
+                return Ok(());
+            }
+        }
+    }
+
+    Err(AttributeValidationError {
+        key: fun.to_string(),
+        attribute: KnownAttributeKind::ResourceAccess as u8,
+    })
+}
+
 pub fn is_valid_view_function(
     module: &CompiledModule,
     functions: &BTreeMap<&IdentStr, (&FunctionHandle, &FunctionDefinition)>,
@@ -514,6 +558,10 @@ pub fn verify_module_metadata(
                 is_valid_view_function(module, &functions, fun)?;
             } else if attr.is_randomness() {
                 is_valid_unbiasable_function(&functions, fun)?;
+            } else if attr.is_resource_access()
+                && features.is_enabled(FeatureFlag::RESOURCE_ACCESS_METADATA)
+            {
+                is_valid_resource_access(&functions, fun, attr)?;
             } else {
                 return Err(AttributeValidationError {
                     key: fun.clone(),
@@ -601,6 +649,14 @@ impl RuntimeModuleMetadataV1 {
                 description: descr.code_description.clone(),
             })
     }
+
+    /// The resources the given entry function may access, if they are known.
+    pub fn get_resource_access(&self, fun: &str) -> Option<Vec<ResourceAccess>> {
+        self.fun_attributes
+            .get(fun)?
+            .iter()
+            .find_map(|attr| attr.get_resource_access())
+    }
 }
 
 /// Checks the complexity of a module.
@@ -718,3 +774,126 @@ impl RandomnessAnnotation {
         Self { max_gas }
     }
 }
+
+/// The address of a resource accessed by an entry function, relative to its call.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AccessAddress {
+    /// The address of the signer at the given index, i.e., the sender followed by the secondary
+    /// signers.
+    Signer(usize),
+    /// The address passed as the (non-signer) argument at the given index.
+    Argument(usize),
+    /// The address of the module declaring the type argument at the given index.
+    TypeArgument(usize),
+    Constant(AccountAddress),
+}
+
+/// A resource an entry function may access, as derived by the extended checks. The resource type
+/// refers to the type arguments of the function as `$<index>`. Members of a resource group are
+/// represented by their group.
+///
+/// Encoded as `<r|w>[g] <address> <resource>` in the metadata, where the address is one of
+/// `signer:<index>`, `arg:<index>`, `type:<index>` or a literal address.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ResourceAccess {
+    pub write: bool,
+    pub resource_group: bool,
+    pub address: AccessAddress,
+    pub resource: String,
+}
+
+impl ResourceAccess {
+    /// The state key accessed by a call with the given signers, arguments and type arguments, or
+    /// `None` if it cannot be determined from them.
+    pub fn state_key(
+        &self,
+        signers: &[AccountAddress],
+        args: &[Vec<u8>],
+        ty_args: &[TypeTag],
+    ) -> Option<StateKey> {
+        let address = match &self.address {
+            AccessAddress::Signer(idx) => *signers.get(*idx)?,
+            AccessAddress::Argument(idx) => bcs::from_bytes(args.get(*idx)?).ok()?,
+            AccessAddress::TypeArgument(idx) => match ty_args.get(*idx)? {
+                TypeTag::Struct(struct_tag) => struct_tag.address,
+                _ => return None,
+            },
+            AccessAddress::Constant(address) => *address,
+        };
+        // Substitute the highest indices first, so that `$1` does not replace a prefix of `$10`.
+        let mut resource = self.resource.clone();
+        for (idx, ty_arg) in ty_args.iter().enumerate().rev() {
+            resource = resource.replace(&format!("${}", idx), &ty_arg.to_canonical_string());
+        }
+        let struct_tag = StructTag::from_str(&resource).ok()?;
+        if self.resource_group {
+            Some(StateKey::resource_group(&address, &struct_tag))
+        } else {
+            StateKey::resource(&address, &struct_tag).ok()
+        }
+    }
+}
+
+impl fmt::Display for AccessAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccessAddress::Signer(idx) => write!(f, "signer:{}", idx),
+            AccessAddress::Argument(idx) => write!(f, "arg:{}", idx),
+            AccessAddress::TypeArgument(idx) => write!(f, "type:{}", idx),
+            AccessAddress::Constant(address) => write!(f, "{}", address.to_hex_literal()),
+        }
+    }
+}
+
+impl FromStr for AccessAddress {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.split_once(':') {
+            Some(("signer", idx)) => AccessAddress::Signer(idx.parse()?),
+            Some(("arg", idx)) => AccessAddress::Argument(idx.parse()?),
+            Some(("type", idx)) => AccessAddress::TypeArgument(idx.parse()?),
+            Some(_) => anyhow::bail!("invalid access address `{}`", s),
+            None => AccessAddress::Constant(AccountAddress::from_hex_literal(s)?),
+        })
+    }
+}
+
+impl fmt::Display for ResourceAccess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{} {} {}",
+            if self.write { "w" } else { "r" },
+            if self.resource_group { "g" } else { "" },
+            self.address,
+            self.resource
+        )
+    }
+}
+
+impl FromStr for ResourceAccess {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, ' ');
+        let (Some(mode), Some(address), Some(resource)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            anyhow::bail!("invalid resource access `{}`", s)
+        };
+        let (write, resource_group) = match mode {
+            "r" => (false, false),
+            "rg" => (false, true),
+            "w" => (true, false),
+            "wg" => (true, true),
+            _ => anyhow::bail!("invalid resource access mode `{}`", mode),
+        };
+        Ok(Self {
+            write,
+            resource_group,
+            address: address.parse()?,
+            resource: resource.to_string(),
+        })
+    }
+}
@@ -10,6 +10,7 @@ use aptos_consensus::{
     },
     consensus_provider::start_consensus_observer,
     network_interface::ConsensusMsg,
+    RandDb,
 };
 use aptos_consensus_notifications::ConsensusNotifier;
 use aptos_dkg_runtime::{start_dkg_runtime, DKGMessage};
@@ -17,7 +18,7 @@ use aptos_event_notifications::{
     DbBackedOnChainConfig, EventNotificationListener, ReconfigNotificationListener,
 };
 use aptos_jwk_consensus::{start_jwk_consensus_runtime, types::JWKConsensusMsg};
-use aptos_logger::debug;
+use aptos_logger::{debug, error};
 use aptos_mempool::QuorumStoreRequest;
 use aptos_safety_rules::safety_rules_manager::load_consensus_key_from_secure_storage;
 use aptos_storage_interface::DbReaderWriter;
@@ -98,6 +99,17 @@ pub fn create_consensus_publisher(
     }
 }
 
+/// Opens the randomness storage of consensus (if consensus is enabled). This is done
+/// before the API is bootstrapped, so that the API can serve the randomness proofs.
+pub fn create_rand_db(
+    node_config: &NodeConfig,
+    consensus_network_interfaces: &Option<ApplicationNetworkInterfaces<ConsensusMsg>>,
+) -> Option<Arc<RandDb>> {
+    consensus_network_interfaces
+        .as_ref()
+        .map(|_| Arc::new(RandDb::new(node_config.storage.dir())))
+}
+
 /// Creates and starts the consensus runtime (if enabled)
 pub fn create_consensus_runtime(
     node_config: &NodeConfig,
@@ -108,23 +120,35 @@ pub fn create_consensus_runtime(
     consensus_to_mempool_sender: Sender<QuorumStoreRequest>,
     vtxn_pool: VTxnPoolState,
     consensus_publisher: Option<Arc<ConsensusPublisher>>,
+    rand_db: Option<Arc<RandDb>>,
     admin_service: &mut AdminService,
 ) -> Option<Runtime> {
-    consensus_network_interfaces.map(|consensus_network_interfaces| {
-        let (consensus_runtime, consensus_db, quorum_store_db) = services::start_consensus_runtime(
-            node_config,
-            db_rw.clone(),
-            consensus_reconfig_subscription,
-            consensus_network_interfaces,
-            consensus_notifier.clone(),
-            consensus_to_mempool_sender.clone(),
-            vtxn_pool,
-            consensus_publisher.clone(),
-        );
-        admin_service.set_consensus_dbs(consensus_db, quorum_store_db);
+    consensus_network_interfaces
+        .zip(rand_db)
+        .map(|(consensus_network_interfaces, rand_db)| {
+            let (
+                consensus_runtime,
+                consensus_db,
+                quorum_store_db,
+                quorum_store_inspector,
+                dag_inspector,
+            ) = services::start_consensus_runtime(
+                node_config,
+                db_rw.clone(),
+                consensus_reconfig_subscription,
+                consensus_network_interfaces,
+                consensus_notifier.clone(),
+                consensus_to_mempool_sender.clone(),
+                vtxn_pool,
+                consensus_publisher.clone(),
+                rand_db,
+            );
+            admin_service.set_consensus_dbs(consensus_db, quorum_store_db);
+            admin_service.set_quorum_store_inspector(quorum_store_inspector);
+            admin_service.set_dag_inspector(dag_inspector);
 
-        consensus_runtime
-    })
+            consensus_runtime
+        })
 }
 
 /// Creates and starts the DKG runtime (if enabled)
@@ -164,7 +188,15 @@ pub fn create_dkg_runtime(
             );
             Some(dkg_runtime)
         },
-        _ => None,
+        (Some(_), Err(error)) => {
+            // E.g., the consensus key is held by a remote signer
+            error!(
+                "The DKG runtime is not started, as the dealer key is unavailable: {}",
+                error
+            );
+            None
+        },
+        (None, _) => None,
     };
 
     (vtxn_pool, dkg_runtime)
@@ -208,7 +240,15 @@ pub fn create_jwk_consensus_runtime(
             );
             Some(jwk_consensus_runtime)
         },
-        _ => None,
+        (Some(_), Err(error)) => {
+            // E.g., the consensus key is held by a remote signer
+            error!(
+                "The JWK consensus runtime is not started, as the consensus key is unavailable: {}",
+                error
+            );
+            None
+        },
+        (None, _) => None,
     };
     jwk_consensus_runtime
 }
@@ -26,7 +26,10 @@ use aptos_config::config::{
 use aptos_framework::ReleaseBundle;
 use aptos_logger::{prelude::*, telemetry_log_writer::TelemetryLog, Level, LoggerFilterUpdater};
 use aptos_state_sync_driver::driver_factory::StateSyncRuntimes;
-use aptos_types::{chain_id::ChainId, on_chain_config::OnChainJWKConsensusConfig};
+use aptos_types::{
+    chain_id::ChainId, on_chain_config::OnChainJWKConsensusConfig,
+    randomness::RandomnessProofReader,
+};
 use clap::Parser;
 use futures::channel::mpsc;
 use hex::{FromHex, FromHexError};
@@ -681,6 +684,9 @@ pub fn setup_environment_and_start_node(
         peers_and_metadata.clone(),
     );
 
+    // Open the randomness storage of consensus (if enabled)
+    let rand_db = consensus::create_rand_db(&node_config, &consensus_network_interfaces);
+
     // Bootstrap the API and indexer
     let (
         mempool_client_receiver,
@@ -689,7 +695,15 @@ pub fn setup_environment_and_start_node(
         indexer_runtime,
         indexer_grpc_runtime,
         internal_indexer_db_runtime,
-    ) = services::bootstrap_api_and_indexer(&node_config, db_rw.clone(), chain_id, indexer_db_opt)?;
+    ) = services::bootstrap_api_and_indexer(
+        &node_config,
+        db_rw.clone(),
+        chain_id,
+        indexer_db_opt,
+        rand_db
+            .clone()
+            .map(|rand_db| rand_db as Arc<dyn RandomnessProofReader>),
+    )?;
 
     // Create mempool and get the consensus to mempool sender
     let (mempool_runtime, consensus_to_mempool_sender) =
@@ -745,6 +759,7 @@ pub fn setup_environment_and_start_node(
         consensus_to_mempool_sender.clone(),
         vtxn_pool,
         consensus_publisher.clone(),
+        rand_db,
         &mut admin_service,
     );
 
@@ -6,8 +6,11 @@ use aptos_admin_service::AdminService;
 use aptos_build_info::build_information;
 use aptos_config::config::NodeConfig;
 use aptos_consensus::{
-    consensus_observer::publisher::ConsensusPublisher, network_interface::ConsensusMsg,
-    persistent_liveness_storage::StorageWriteProxy, quorum_store::quorum_store_db::QuorumStoreDB,
+    consensus_observer::publisher::ConsensusPublisher,
+    network_interface::ConsensusMsg,
+    persistent_liveness_storage::StorageWriteProxy,
+    quorum_store::{inspection::QuorumStoreInspector, quorum_store_db::QuorumStoreDB},
+    DagInspector, RandDb,
 };
 use aptos_consensus_notifications::ConsensusNotifier;
 use aptos_data_client::client::AptosDataClient;
@@ -29,7 +32,9 @@ use aptos_peer_monitoring_service_server::{
 use aptos_peer_monitoring_service_types::PeerMonitoringServiceMessage;
 use aptos_storage_interface::{DbReader, DbReaderWriter};
 use aptos_time_service::TimeService;
-use aptos_types::{chain_id::ChainId, indexer::indexer_db_reader::IndexerReader};
+use aptos_types::{
+    chain_id::ChainId, indexer::indexer_db_reader::IndexerReader, randomness::RandomnessProofReader,
+};
 use aptos_validator_transaction_pool::VTxnPoolState;
 use futures::channel::{mpsc, mpsc::Sender};
 use std::{sync::Arc, time::Instant};
@@ -45,6 +50,7 @@ pub fn bootstrap_api_and_indexer(
     db_rw: DbReaderWriter,
     chain_id: ChainId,
     internal_indexer_db: Option<InternalIndexerDB>,
+    randomness_proof_reader: Option<Arc<dyn RandomnessProofReader>>,
 ) -> anyhow::Result<(
     Receiver<MempoolClientRequest>,
     Option<Runtime>,
@@ -88,6 +94,7 @@ pub fn bootstrap_api_and_indexer(
             db_rw.reader.clone(),
             mempool_client_sender.clone(),
             indexer_reader.clone(),
+            randomness_proof_reader,
         )?)
     } else {
         None
@@ -130,7 +137,14 @@ pub fn start_consensus_runtime(
     consensus_to_mempool_sender: Sender<QuorumStoreRequest>,
     vtxn_pool: VTxnPoolState,
     consensus_publisher: Option<Arc<ConsensusPublisher>>,
-) -> (Runtime, Arc<StorageWriteProxy>, Arc<QuorumStoreDB>) {
+    rand_db: Arc<RandDb>,
+) -> (
+    Runtime,
+    Arc<StorageWriteProxy>,
+    Arc<QuorumStoreDB>,
+    Arc<QuorumStoreInspector>,
+    Arc<DagInspector>,
+) {
     let instant = Instant::now();
 
     let reconfig_subscription = consensus_reconfig_subscription
@@ -146,6 +160,7 @@ pub fn start_consensus_runtime(
         reconfig_subscription,
         vtxn_pool,
         consensus_publisher,
+        rand_db,
     );
     debug!("Consensus started in {} ms", instant.elapsed().as_millis());
 
@@ -4,7 +4,11 @@
 use crate::network::ApplicationNetworkInterfaces;
 use aptos_config::config::{NodeConfig, StateSyncConfig};
 use aptos_consensus_notifications::ConsensusNotifier;
-use aptos_data_client::{client::AptosDataClient, poller};
+use aptos_data_client::{
+    client::AptosDataClient,
+    peer_selection::{DefaultPeerSelectionPolicy, PeerSelectionPolicy, PreferredPeersPolicy},
+    poller,
+};
 use aptos_data_streaming_service::{
     streaming_client::{new_streaming_service_client_listener_pair, StreamingServiceClient},
     streaming_service::DataStreamingService,
@@ -34,7 +38,7 @@ use aptos_storage_service_types::StorageServiceMessage;
 use aptos_time_service::TimeService;
 use aptos_types::waypoint::Waypoint;
 use aptos_vm::AptosVM;
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 use tokio::runtime::Runtime;
 
 /// Creates the event subscription service and two reconfiguration
@@ -147,9 +151,9 @@ pub fn start_state_sync_and_get_notification_handles(
         setup_aptos_data_client(node_config, network_client, db_rw.reader.clone())?;
 
     // Start the data streaming service
-    let state_sync_config = node_config.state_sync;
+    let state_sync_config = node_config.state_sync.clone();
     let (streaming_service_client, streaming_service_runtime) =
-        setup_data_streaming_service(state_sync_config, aptos_data_client.clone())?;
+        setup_data_streaming_service(state_sync_config.clone(), aptos_data_client.clone())?;
 
     // Create the chunk executor and persistent storage
     let chunk_executor = Arc::new(ChunkExecutor::<AptosVM>::new(db_rw.clone()));
@@ -248,14 +252,36 @@ fn setup_aptos_data_client(
     // Create a new runtime for the data client
     let aptos_data_client_runtime = aptos_runtimes::spawn_named_runtime("data-client".into(), None);
 
+    // Create the peer selection policy (favouring any preferred peers)
+    let data_client_config = node_config.state_sync.aptos_data_client;
+    let mut peer_selection_policy: Arc<dyn PeerSelectionPolicy> =
+        Arc::new(DefaultPeerSelectionPolicy::new(
+            Arc::new(node_config.base.clone()),
+            Arc::new(data_client_config),
+            storage_service_client.get_peers_and_metadata(),
+        ));
+    let preferred_peers: HashSet<_> = node_config
+        .state_sync
+        .peer_selection
+        .preferred_peers
+        .iter()
+        .copied()
+        .collect();
+    if !preferred_peers.is_empty() {
+        peer_selection_policy = Arc::new(PreferredPeersPolicy::new(
+            preferred_peers,
+            peer_selection_policy,
+        ));
+    }
+
     // Create the data client and spawn the data poller
-    let (aptos_data_client, data_summary_poller) = AptosDataClient::new(
-        node_config.state_sync.aptos_data_client,
-        node_config.base.clone(),
+    let (aptos_data_client, data_summary_poller) = AptosDataClient::new_with_peer_selection_policy(
+        data_client_config,
         TimeService::real(),
         storage,
         storage_service_client,
         Some(aptos_data_client_runtime.handle().clone()),
+        peer_selection_policy,
     );
     aptos_data_client_runtime.spawn(poller::start_poller(data_summary_poller));
 
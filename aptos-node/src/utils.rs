@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::anyhow;
+use aptos_block_executor::conflict_report::set_conflict_report_dir_once;
 use aptos_config::config::{NodeConfig, DEFAULT_EXECUTION_CONCURRENCY_LEVEL};
 use aptos_storage_interface::{state_view::LatestDbStateCheckpointView, DbReaderWriter};
 use aptos_types::{
@@ -57,6 +58,11 @@ pub fn set_aptos_vm_configurations(node_config: &NodeConfig) {
     };
     AptosVM::set_concurrency_level_once(effective_concurrency_level as usize);
     AptosVM::set_discard_failed_blocks(node_config.execution.discard_failed_blocks);
+    AptosVM::set_conflict_reports(node_config.execution.enable_conflict_reports);
+    if let Some(conflict_report_dir) = &node_config.execution.conflict_report_dir {
+        set_conflict_report_dir_once(conflict_report_dir.clone());
+    }
+    AptosVM::set_prefetch_read_sets(node_config.execution.prefetch_read_sets);
     AptosVM::set_num_proof_reading_threads_once(
         node_config.execution.num_proof_reading_threads as usize,
     );
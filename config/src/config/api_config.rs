@@ -49,6 +49,10 @@ pub struct ApiConfig {
     /// Enables transaction simulation
     #[serde(default = "default_enabled")]
     pub transaction_simulation_enabled: bool,
+    /// Enables execution traces of simulated transactions (`trace=true`). Tracing records
+    /// every call of the transaction, so it is more expensive than a plain simulation.
+    #[serde(default = "default_disabled")]
+    pub transaction_simulation_trace_enabled: bool,
     /// Maximum number of transactions that can be sent with the Batch submit API
     pub max_submit_transaction_batch_size: usize,
     /// Maximum page size for transaction paginated APIs
@@ -124,6 +128,7 @@ impl Default for ApiConfig {
             encode_submission_enabled: default_enabled(),
             transaction_submission_enabled: default_enabled(),
             transaction_simulation_enabled: default_enabled(),
+            transaction_simulation_trace_enabled: default_disabled(),
             max_submit_transaction_batch_size: DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE,
             max_block_transactions_page_size: *MAX_RECEIVING_BLOCK_TXNS as u16,
             max_transactions_page_size: DEFAULT_MAX_PAGE_SIZE,
@@ -87,7 +87,41 @@ pub struct ConsensusConfig {
     pub broadcast_vote: bool,
     pub proof_cache_capacity: u64,
     pub rand_rb_config: ReliableBroadcastConfig,
+    // The number of epochs (including the current one) for which the proofs of the
+    // aggregated randomness are kept (e.g., to serve them through the REST API). If 0
+    // (the default), no proofs are persisted.
+    pub randomness_proof_retention_epochs: u64,
     pub num_bounded_executor_tasks: u64,
+    pub flight_recorder: ConsensusFlightRecorderConfig,
+}
+
+/// The config for the consensus flight recorder, which records the events
+/// processed by the round manager (e.g., proposals, votes and timeouts) to
+/// disk, so that they can be replayed offline (e.g., after liveness incidents).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConsensusFlightRecorderConfig {
+    // Whether or not the flight recorder is enabled
+    pub enabled: bool,
+    // The maximum size of a single recording segment (in bytes). Once a segment
+    // reaches this size, a new segment (starting with a checkpoint) is created.
+    pub max_segment_size_bytes: u64,
+    // The maximum number of segments to retain on disk (older segments are deleted)
+    pub max_num_segments: usize,
+    // The maximum number of records waiting to be written to disk. If the writer
+    // falls behind, new records are dropped (and a new segment is started).
+    pub max_pending_records: usize,
+}
+
+impl Default for ConsensusFlightRecorderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_segment_size_bytes: 16 * 1024 * 1024, // 16 MiB
+            max_num_segments: 8,
+            max_pending_records: 1024,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
@@ -346,7 +380,9 @@ impl Default for ConsensusConfig {
                 backoff_policy_max_delay_ms: 10000,
                 rpc_timeout_ms: 10000,
             },
+            randomness_proof_retention_epochs: 0,
             num_bounded_executor_tasks: 16,
+            flight_recorder: ConsensusFlightRecorderConfig::default(),
         }
     }
 }
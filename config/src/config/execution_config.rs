@@ -46,6 +46,14 @@ pub struct ExecutionConfig {
     pub paranoid_type_verification: bool,
     /// Enabled discarding blocks that fail execution due to BlockSTM/VM issue.
     pub discard_failed_blocks: bool,
+    /// Enables per-block reports of the conflicts between transactions during parallel
+    /// execution (hot keys, dependencies, re-executions), served by the admin service
+    pub enable_conflict_reports: bool,
+    /// If set, conflict reports are also written to this directory, one JSON file per block
+    pub conflict_report_dir: Option<PathBuf>,
+    /// Enables prefetching the state that the transactions of a block are predicted to read,
+    /// while executing the block. Helps nodes whose state caches are cold.
+    pub prefetch_read_sets: bool,
     /// Enables paranoid mode for hot potatoes, which adds extra runtime VM checks
     pub paranoid_hot_potato_verification: bool,
     /// Enables enhanced metrics around processed transactions
@@ -83,6 +91,9 @@ impl Default for ExecutionConfig {
             paranoid_type_verification: true,
             paranoid_hot_potato_verification: true,
             discard_failed_blocks: false,
+            enable_conflict_reports: false,
+            conflict_report_dir: None,
+            prefetch_read_sets: false,
             processed_transactions_detailed_counters: false,
             transaction_filter: Filter::empty(),
             genesis_waypoint: None,
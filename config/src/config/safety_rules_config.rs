@@ -12,7 +12,7 @@ use crate::{
     keys::ConfigKey,
 };
 use anyhow::bail;
-use aptos_crypto::{bls12381, Uniform};
+use aptos_crypto::{bls12381, x25519, Uniform};
 use aptos_types::{chain_id::ChainId, network_address::NetworkAddress, waypoint::Waypoint, PeerId};
 use rand::rngs::StdRng;
 use serde::{Deserialize, Serialize};
@@ -32,6 +32,7 @@ pub struct SafetyRulesConfig {
     pub network_timeout_ms: u64,
     pub enable_cached_safety_data: bool,
     pub initial_safety_rules_config: InitialSafetyRulesConfig,
+    pub signer: ConsensusSignerConfig,
 }
 
 impl Default for SafetyRulesConfig {
@@ -45,6 +46,7 @@ impl Default for SafetyRulesConfig {
             network_timeout_ms: 30_000,
             enable_cached_safety_data: true,
             initial_safety_rules_config: InitialSafetyRulesConfig::None,
+            signer: ConsensusSignerConfig::SecureBackend,
         }
     }
 }
@@ -113,6 +115,35 @@ impl ConfigSanitizer for SafetyRulesConfig {
             }
         }
 
+        // Verify that a remote signer is not used on chains with randomness enabled (DKG
+        // and randomness require direct access to the consensus key).
+        if let Some(chain_id) = chain_id {
+            if (chain_id.is_mainnet() || chain_id.is_testnet())
+                && safety_rules_config.signer.is_remote()
+            {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "A remote signer cannot be used on chains with randomness enabled (DKG and randomness require the consensus key)!"
+                        .to_string(),
+                ));
+            }
+        }
+
+        // Verify that the consensus key is not given to the node when using a remote signer
+        if safety_rules_config.signer.is_remote() {
+            let test_consensus_key = safety_rules_config
+                .test
+                .as_ref()
+                .and_then(|test_config| test_config.consensus_key.as_ref());
+            if test_consensus_key.is_some() {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "The consensus key should not be provided to the node when using a remote signer!"
+                        .to_string(),
+                ));
+            }
+        }
+
         Ok(())
     }
 }
@@ -200,6 +231,49 @@ impl RemoteService {
     }
 }
 
+/// Defines where the consensus key is held (and how consensus messages are signed)
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum ConsensusSignerConfig {
+    /// The consensus key is held in the safety rules secure backend
+    SecureBackend,
+    /// The consensus key is held by an external signing daemon. The node never sees
+    /// the key, and the daemon enforces its own double-sign protection. Note: components
+    /// that require direct access to the consensus key (e.g., DKG, randomness, JWK
+    /// consensus and DAG) are unavailable when using a remote signer.
+    Remote(RemoteSignerConfig),
+}
+
+impl ConsensusSignerConfig {
+    /// Returns true iff consensus messages are signed by a remote signer
+    pub fn is_remote(&self) -> bool {
+        matches!(self, ConsensusSignerConfig::Remote(_))
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteSignerConfig {
+    pub server_address: NetworkAddress,
+    // The static (Noise) public key of the signer. All requests are sent over a mutually
+    // authenticated and encrypted channel, so the node only talks to this signer.
+    pub server_public_key: x25519::PublicKey,
+    // The static (Noise) private key used by the node to authenticate to the signer
+    pub client_private_key: ConfigKey<x25519::PrivateKey>,
+    // Read/Write/Connect networking operation timeout in milliseconds.
+    pub network_timeout_ms: u64,
+}
+
+impl RemoteSignerConfig {
+    /// Resolves the address of the signer (this may require a DNS lookup)
+    pub fn server_address(&self) -> anyhow::Result<SocketAddr> {
+        self.server_address
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Failed to resolve: {}", self.server_address))
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct SafetyRulesTestConfig {
     pub author: PeerId,
@@ -302,6 +376,66 @@ mod tests {
         assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
     }
 
+    #[test]
+    fn test_sanitize_consensus_key_with_remote_signer() {
+        // Create a node config with a remote signer and a test consensus key
+        let mut test_config = SafetyRulesTestConfig::new(PeerId::random());
+        test_config.consensus_key(bls12381::PrivateKey::generate_for_testing());
+        let node_config = NodeConfig {
+            consensus: ConsensusConfig {
+                safety_rules: SafetyRulesConfig {
+                    test: Some(test_config),
+                    signer: create_remote_signer_config(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that the config sanitizer fails
+        let error =
+            SafetyRulesConfig::sanitize(&node_config, NodeType::Validator, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    #[test]
+    fn test_sanitize_remote_signer_with_randomness() {
+        // Create a node config with a remote signer
+        let node_config = NodeConfig {
+            consensus: ConsensusConfig {
+                safety_rules: SafetyRulesConfig {
+                    signer: create_remote_signer_config(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that the config sanitizer fails on chains with randomness enabled
+        for chain_id in [ChainId::mainnet(), ChainId::testnet()] {
+            let error =
+                SafetyRulesConfig::sanitize(&node_config, NodeType::Validator, Some(chain_id))
+                    .unwrap_err();
+            assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+        }
+
+        // Verify that the config sanitizer succeeds on other chains
+        SafetyRulesConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::test()))
+            .unwrap();
+    }
+
+    /// Creates a remote signer config (for a signer on the local host)
+    fn create_remote_signer_config() -> ConsensusSignerConfig {
+        ConsensusSignerConfig::Remote(RemoteSignerConfig {
+            server_address: "/ip4/127.0.0.1/tcp/6200".parse().unwrap(),
+            server_public_key: x25519::PrivateKey::generate_for_testing().public_key(),
+            client_private_key: ConfigKey::new(x25519::PrivateKey::generate_for_testing()),
+            network_timeout_ms: 1_000,
+        })
+    }
+
     #[test]
     fn test_sanitize_test_config_on_mainnet() {
         // Create a node config with a test config
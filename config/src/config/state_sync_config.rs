@@ -6,9 +6,10 @@ use crate::config::{
     config_optimizer::ConfigOptimizer, config_sanitizer::ConfigSanitizer,
     node_config_loader::NodeType, Error, NodeConfig,
 };
-use aptos_types::chain_id::ChainId;
+use aptos_types::{account_address::AccountAddress, chain_id::ChainId, PeerId};
 use serde::{Deserialize, Serialize};
 use serde_yaml::Value;
+use std::collections::HashSet;
 
 // The maximum message size per state sync message
 const MAX_MESSAGE_SIZE: usize = 6 * 1024 * 1024; /* 6 MiB */
@@ -23,11 +24,13 @@ const MAX_TRANSACTION_OUTPUT_CHUNK_SIZE: u64 = 3000;
 const MAX_CONCURRENT_REQUESTS: u64 = 6;
 const MAX_CONCURRENT_STATE_REQUESTS: u64 = 6;
 
-#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct StateSyncConfig {
     pub data_streaming_service: DataStreamingServiceConfig,
     pub aptos_data_client: AptosDataClientConfig,
+    pub light_client: LightClientConfig,
+    pub peer_selection: PeerSelectionConfig,
     pub state_sync_driver: StateSyncDriverConfig,
     pub storage_service: StorageServiceConfig,
 }
@@ -144,6 +147,39 @@ impl Default for StateSyncDriverConfig {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LightClientConfig {
+    /// Whether or not to run the node as a light client. Light clients only
+    /// sync and verify ledger infos (i.e., they don't store transactions,
+    /// outputs or states), and serve state queries by fetching proofs from peers.
+    pub enable_light_client: bool,
+    /// The interval (ms) at which to sync the latest ledger info
+    pub sync_interval_ms: u64,
+    /// The accounts for which to sync (and verify) all state values at the
+    /// latest ledger info (i.e., partial state). If empty, no states are synced.
+    pub tracked_accounts: Vec<AccountAddress>,
+}
+
+impl Default for LightClientConfig {
+    fn default() -> Self {
+        Self {
+            enable_light_client: false,
+            sync_interval_ms: 1000,
+            tracked_accounts: vec![],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PeerSelectionConfig {
+    /// The operator preferred peers (e.g., the other VFNs run by the same
+    /// validator operator). If non-empty, the data client always prioritizes
+    /// these peers over all other peers when selecting peers for requests.
+    pub preferred_peers: Vec<PeerId>,
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct StorageServiceConfig {
@@ -201,6 +237,9 @@ impl Default for StorageServiceConfig {
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct DataStreamingServiceConfig {
+    /// The bandwidth budget config for the data streaming service
+    pub bandwidth_budget: StreamBandwidthBudgetConfig,
+
     /// The dynamic prefetching config for the data streaming service
     pub dynamic_prefetching: DynamicPrefetchingConfig,
 
@@ -246,6 +285,7 @@ pub struct DataStreamingServiceConfig {
 impl Default for DataStreamingServiceConfig {
     fn default() -> Self {
         Self {
+            bandwidth_budget: StreamBandwidthBudgetConfig::default(),
             dynamic_prefetching: DynamicPrefetchingConfig::default(),
             enable_subscription_streaming: false,
             global_summary_refresh_interval_ms: 50,
@@ -305,6 +345,39 @@ impl Default for DynamicPrefetchingConfig {
     }
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StreamBandwidthBudgetConfig {
+    /// Whether or not to enforce the bandwidth budget (across all data streams)
+    pub enable_bandwidth_budget: bool,
+
+    /// The maximum number of response bytes (per second) that data streams can consume
+    pub max_bytes_per_second: u64,
+
+    /// The relative share of the budget for bootstrapping (e.g., fast syncing)
+    pub bootstrapping_weight: u64,
+
+    /// The relative share of the budget for catching up (i.e., continuous
+    /// syncing requests for data that already exists on the network)
+    pub catch_up_weight: u64,
+
+    /// The relative share of the budget for continuous syncing at the head of
+    /// the chain (i.e., optimistic fetch and subscription requests)
+    pub continuous_syncing_weight: u64,
+}
+
+impl Default for StreamBandwidthBudgetConfig {
+    fn default() -> Self {
+        Self {
+            enable_bandwidth_budget: false,
+            max_bytes_per_second: 100 * 1024 * 1024, // 100 MiB/s
+            bootstrapping_weight: 1,
+            catch_up_weight: 2,
+            continuous_syncing_weight: 4,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct AptosDataPollerConfig {
@@ -458,11 +531,68 @@ impl ConfigSanitizer for StateSyncConfig {
         node_type: NodeType,
         chain_id: Option<ChainId>,
     ) -> Result<(), Error> {
+        // Sanitize the light client config
+        LightClientConfig::sanitize(node_config, node_type, chain_id)?;
+
         // Sanitize the state sync driver config
         StateSyncDriverConfig::sanitize(node_config, node_type, chain_id)
     }
 }
 
+impl ConfigSanitizer for LightClientConfig {
+    fn sanitize(
+        node_config: &NodeConfig,
+        node_type: NodeType,
+        _chain_id: Option<ChainId>,
+    ) -> Result<(), Error> {
+        let sanitizer_name = Self::get_sanitizer_name();
+        let light_client_config = &node_config.state_sync.light_client;
+        if !light_client_config.enable_light_client {
+            return Ok(());
+        }
+
+        // Verify that the light client is not enabled for validators or
+        // validator fullnodes (they must execute and store the chain).
+        if node_type.is_validator() || node_type.is_validator_fullnode() {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "The light client should only be enabled for public fullnodes!".to_string(),
+            ));
+        }
+
+        // Verify that consensus observer is not enabled (the light client
+        // does not execute or commit blocks).
+        let consensus_observer_config = &node_config.consensus_observer;
+        if consensus_observer_config.observer_enabled || consensus_observer_config.publisher_enabled
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "Consensus observer should not be enabled for light clients!".to_string(),
+            ));
+        }
+
+        // Verify that the tracked accounts are unique
+        let tracked_accounts = &light_client_config.tracked_accounts;
+        let unique_tracked_accounts: HashSet<_> = tracked_accounts.iter().collect();
+        if unique_tracked_accounts.len() != tracked_accounts.len() {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "The light client tracked accounts must be unique!".to_string(),
+            ));
+        }
+
+        // Verify that the sync interval is non-zero
+        if light_client_config.sync_interval_ms == 0 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "The light client sync interval must be non-zero!".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
 impl ConfigSanitizer for StateSyncDriverConfig {
     fn sanitize(
         node_config: &NodeConfig,
@@ -788,6 +918,58 @@ mod tests {
         assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
     }
 
+    #[test]
+    fn test_sanitize_light_client_validator() {
+        // Create a node config with the light client enabled
+        let node_config = create_light_client_config();
+
+        // Verify that sanitization fails for validators and VFNs
+        for node_type in [NodeType::Validator, NodeType::ValidatorFullnode] {
+            let error =
+                StateSyncConfig::sanitize(&node_config, node_type, Some(ChainId::testnet()))
+                    .unwrap_err();
+            assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+        }
+
+        // Verify that sanitization passes for public fullnodes
+        StateSyncConfig::sanitize(
+            &node_config,
+            NodeType::PublicFullnode,
+            Some(ChainId::testnet()),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_sanitize_light_client_consensus_observer() {
+        // Create a node config with the light client and consensus observer enabled
+        let mut node_config = create_light_client_config();
+        node_config.consensus_observer.observer_enabled = true;
+
+        // Verify that sanitization fails
+        let error = StateSyncConfig::sanitize(
+            &node_config,
+            NodeType::PublicFullnode,
+            Some(ChainId::testnet()),
+        )
+        .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    /// Creates and returns a node config with the light client enabled
+    fn create_light_client_config() -> NodeConfig {
+        NodeConfig {
+            state_sync: StateSyncConfig {
+                light_client: LightClientConfig {
+                    enable_light_client: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
     /// Creates and returns a node config with the syncing modes set to execution
     fn create_execution_mode_config() -> NodeConfig {
         NodeConfig {
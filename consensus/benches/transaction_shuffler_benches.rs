@@ -0,0 +1,200 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+// Measures how transaction shuffling affects BlockSTM conflicts, using the block executor's mock
+// transactions with read and write sets derived from the shuffled block. As the number of
+// speculative aborts depends on thread scheduling, each shuffled block is executed (and checked
+// against the sequential baseline) several times, and the distribution of the abort counts is
+// printed before the timing results.
+//
+// Run this bencher via
+// `cargo bench -p aptos-consensus --features fuzzing --bench transaction_shuffler_benches`.
+use aptos_block_executor::proptest_types::bencher::ReadWriteSetBlock;
+use aptos_consensus::{create_transaction_shuffler, TransactionShuffler};
+use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, SigningKey, Uniform};
+use aptos_types::{
+    chain_id::ChainId,
+    on_chain_config::TransactionShufflerType,
+    transaction::{
+        authenticator::AccountAuthenticator,
+        use_case::{UseCaseAwareTransaction, UseCaseKey},
+        EntryFunction, RawTransaction, SignedTransaction,
+    },
+};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use move_core_types::{
+    account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{collections::HashMap, sync::Arc};
+
+const NUM_TXNS: usize = 2000;
+const NUM_SENDERS: usize = 500;
+const NUM_CONTRACTS: usize = 20;
+const NUM_SPONSORS: usize = 3;
+const CONCURRENCY_LEVEL: usize = 8;
+const NUM_ABORT_SAMPLES: usize = 20;
+
+/// A key touched by a mock transaction: the account (balance, sequence number) of a sender or
+/// fee payer, or the hot state of a contract.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+enum Key {
+    Account(AccountAddress),
+    Contract(AccountAddress),
+}
+
+fn address(kind: u8, idx: usize) -> AccountAddress {
+    let mut addr = [0u8; AccountAddress::LENGTH];
+    addr[0] = kind;
+    addr[24..].copy_from_slice(&(idx as u64).to_be_bytes());
+    AccountAddress::new(addr)
+}
+
+/// Creates a block where half of the transactions call into a single hot contract, a fifth are
+/// platform transactions, and a third are paid for by a few sponsors.
+fn create_block() -> Vec<SignedTransaction> {
+    let mut rng = StdRng::seed_from_u64(0);
+    let private_key = Ed25519PrivateKey::generate_for_testing();
+    let public_key = private_key.public_key();
+    let sign = |raw_txn: &RawTransaction| {
+        private_key
+            .sign(raw_txn)
+            .expect("Signing a raw transaction must succeed")
+    };
+    let mut sequence_numbers = HashMap::new();
+
+    (0..NUM_TXNS)
+        .map(|_| {
+            let sender = address(1, rng.gen_range(0, NUM_SENDERS));
+            let sequence_number = sequence_numbers.entry(sender).or_insert(0);
+            let module_address = match rng.gen_range(0, 10) {
+                0..=4 => address(2, 0),
+                5 | 6 => AccountAddress::ONE,
+                _ => address(2, rng.gen_range(1, NUM_CONTRACTS)),
+            };
+            let raw_txn = RawTransaction::new_entry_function(
+                sender,
+                *sequence_number,
+                EntryFunction::new(
+                    ModuleId::new(
+                        module_address,
+                        Identifier::new("module").expect("Identifier must be valid"),
+                    ),
+                    Identifier::new("function").expect("Identifier must be valid"),
+                    vec![],
+                    vec![],
+                ),
+                1_000_000,
+                rng.gen_range(100, 200),
+                0,
+                ChainId::test(),
+            );
+            *sequence_number += 1;
+
+            if rng.gen_range(0, 3) == 0 {
+                let signature = AccountAuthenticator::ed25519(public_key.clone(), sign(&raw_txn));
+                SignedTransaction::new_fee_payer(
+                    raw_txn,
+                    signature.clone(),
+                    vec![],
+                    vec![],
+                    address(3, rng.gen_range(0, NUM_SPONSORS)),
+                    signature,
+                )
+            } else {
+                let signature = sign(&raw_txn);
+                SignedTransaction::new(raw_txn, public_key.clone(), signature)
+            }
+        })
+        .collect()
+}
+
+/// Every transaction updates the accounts of its sender and fee payer, and the state of the
+/// contract it calls into. Platform transactions only touch accounts.
+fn read_write_sets(txns: &[SignedTransaction]) -> Vec<(Vec<Key>, Vec<Key>)> {
+    txns.iter()
+        .map(|txn| {
+            let mut keys = vec![Key::Account(txn.sender())];
+            if let Some(fee_payer) = txn.authenticator_ref().fee_payer_address() {
+                keys.push(Key::Account(fee_payer));
+            }
+            if let UseCaseKey::ContractAddress(contract) = txn.parse_use_case() {
+                keys.push(Key::Contract(contract));
+            }
+            (keys.clone(), keys)
+        })
+        .collect()
+}
+
+fn shuffler_types() -> Vec<(&'static str, TransactionShufflerType)> {
+    vec![
+        ("no_shuffling", TransactionShufflerType::NoShuffling),
+        ("sender_aware", TransactionShufflerType::SenderAwareV2(32)),
+        (
+            "use_case_aware",
+            TransactionShufflerType::default_for_genesis(),
+        ),
+        ("fee_priority", TransactionShufflerType::FeePriority {
+            conflict_window_size: 32,
+            max_contract_share_percentage: 20,
+        }),
+    ]
+}
+
+/// Executes the block several times and prints the distribution of the speculative aborts
+fn print_speculative_aborts(
+    name: &str,
+    block: &ReadWriteSetBlock<Key>,
+    executor_thread_pool: &Arc<rayon::ThreadPool>,
+) {
+    let mut aborts: Vec<_> = (0..NUM_ABORT_SAMPLES)
+        .map(|_| block.execute_and_verify(executor_thread_pool, CONCURRENCY_LEVEL))
+        .collect();
+    aborts.sort_unstable();
+    let mean = aborts.iter().sum::<u64>() as f64 / NUM_ABORT_SAMPLES as f64;
+    println!(
+        "{}: speculative aborts for {} transactions over {} runs: min {}, median {}, max {}, mean {:.1} ({:.3} per transaction)",
+        name,
+        NUM_TXNS,
+        NUM_ABORT_SAMPLES,
+        aborts[0],
+        aborts[NUM_ABORT_SAMPLES / 2],
+        aborts[NUM_ABORT_SAMPLES - 1],
+        mean,
+        mean / NUM_TXNS as f64
+    );
+}
+
+fn shuffler_benches(c: &mut Criterion) {
+    let txns = create_block();
+    let executor_thread_pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(CONCURRENCY_LEVEL)
+            .build()
+            .expect("Creating the executor thread pool must succeed"),
+    );
+
+    let mut group = c.benchmark_group("transaction_shuffler");
+    for (name, shuffler_type) in shuffler_types() {
+        let shuffler = create_transaction_shuffler(shuffler_type, None);
+        let block = ReadWriteSetBlock::new(read_write_sets(&shuffler.shuffle(txns.clone())));
+        print_speculative_aborts(name, &block, &executor_thread_pool);
+
+        group.bench_function(format!("{}/shuffle", name), |b| {
+            b.iter_batched(
+                || txns.clone(),
+                |txns| shuffler.shuffle(txns),
+                BatchSize::LargeInput,
+            )
+        });
+        // Only the parallel execution is measured (the output is checked above)
+        group.bench_function(format!("{}/execute", name), |b| {
+            b.iter(|| block.execute(&executor_thread_pool, CONCURRENCY_LEVEL))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, shuffler_benches);
+
+criterion_main!(benches);
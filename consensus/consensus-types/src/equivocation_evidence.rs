@@ -0,0 +1,247 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    block::Block,
+    common::{Author, Round},
+    vote::Vote,
+};
+use anyhow::{bail, ensure, Context};
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
+use aptos_short_hex_str::AsShortHexStr;
+use aptos_types::{
+    account_address::AccountAddress,
+    equivocation::{EquivocationKind, EquivocationReport, EquivocationReportMetadata},
+    validator_verifier::ValidatorVerifier,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// Cryptographically verifiable evidence that a validator equivocated, i.e., signed two
+/// conflicting consensus messages for the same round. Each message carries the
+/// signature of the offender, so the evidence can be verified by anyone that knows the
+/// validator set of the epoch.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, CryptoHasher, BCSCryptoHash)]
+pub enum EquivocationEvidence {
+    /// Two votes (with different `LedgerInfo`s) from the same author for the same round.
+    ConflictingVotes { first: Box<Vote>, second: Box<Vote> },
+    /// Two different proposals from the same author for the same round.
+    ConflictingProposals {
+        first: Box<Block>,
+        second: Box<Block>,
+    },
+}
+
+impl Display for EquivocationEvidence {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "EquivocationEvidence: [kind: {}, offender: {}, epoch: {}, round: {}, id: {}]",
+            self.kind().as_str(),
+            self.offender().short_str(),
+            self.epoch(),
+            self.round(),
+            self.id()
+        )
+    }
+}
+
+impl EquivocationEvidence {
+    pub fn conflicting_votes(first: Vote, second: Vote) -> Self {
+        Self::ConflictingVotes {
+            first: Box::new(first),
+            second: Box::new(second),
+        }
+    }
+
+    pub fn conflicting_proposals(first: Block, second: Block) -> Self {
+        Self::ConflictingProposals {
+            first: Box::new(first),
+            second: Box::new(second),
+        }
+    }
+
+    pub fn kind(&self) -> EquivocationKind {
+        match self {
+            EquivocationEvidence::ConflictingVotes { .. } => EquivocationKind::Vote,
+            EquivocationEvidence::ConflictingProposals { .. } => EquivocationKind::Proposal,
+        }
+    }
+
+    /// The validator that signed both conflicting messages (for proposals without an
+    /// author, which are rejected by `verify`, this is the zero address)
+    pub fn offender(&self) -> Author {
+        match self {
+            EquivocationEvidence::ConflictingVotes { first, .. } => first.author(),
+            EquivocationEvidence::ConflictingProposals { first, .. } => {
+                first.author().unwrap_or(AccountAddress::ZERO)
+            },
+        }
+    }
+
+    pub fn epoch(&self) -> u64 {
+        match self {
+            EquivocationEvidence::ConflictingVotes { first, .. } => first.epoch(),
+            EquivocationEvidence::ConflictingProposals { first, .. } => first.epoch(),
+        }
+    }
+
+    pub fn round(&self) -> Round {
+        match self {
+            EquivocationEvidence::ConflictingVotes { first, .. } => {
+                first.vote_data().proposed().round()
+            },
+            EquivocationEvidence::ConflictingProposals { first, .. } => first.round(),
+        }
+    }
+
+    /// The hash of the evidence, which uniquely identifies it
+    pub fn id(&self) -> HashValue {
+        self.hash()
+    }
+
+    /// Verifies that both messages are correctly signed by the same author, are for the
+    /// same epoch and round, and conflict with each other.
+    pub fn verify(&self, validator: &ValidatorVerifier) -> anyhow::Result<()> {
+        match self {
+            EquivocationEvidence::ConflictingVotes { first, second } => {
+                ensure!(
+                    first.author() == second.author(),
+                    "Conflicting votes have different authors"
+                );
+                ensure!(
+                    (first.epoch(), first.vote_data().proposed().round())
+                        == (second.epoch(), second.vote_data().proposed().round()),
+                    "Conflicting votes have different (epoch, round)"
+                );
+                ensure!(
+                    first.ledger_info().hash() != second.ledger_info().hash(),
+                    "Conflicting votes have the same LedgerInfo"
+                );
+                first.verify(validator).context("Invalid first vote")?;
+                second.verify(validator).context("Invalid second vote")?;
+            },
+            EquivocationEvidence::ConflictingProposals { first, second } => {
+                match (first.author(), second.author()) {
+                    (Some(first_author), Some(second_author)) => ensure!(
+                        first_author == second_author,
+                        "Conflicting proposals have different authors"
+                    ),
+                    _ => bail!("Conflicting proposals must have an author"),
+                }
+                ensure!(
+                    (first.epoch(), first.round()) == (second.epoch(), second.round()),
+                    "Conflicting proposals have different (epoch, round)"
+                );
+                ensure!(
+                    first.id() != second.id(),
+                    "Conflicting proposals have the same id"
+                );
+                for proposal in [first, second] {
+                    proposal
+                        .validate_signature(validator)
+                        .context("Invalid proposal signature")?;
+                    proposal.verify_well_formed()?;
+                }
+            },
+        }
+        Ok(())
+    }
+
+    /// Creates a report of the evidence that can be submitted on-chain
+    pub fn to_report(&self, reporter: Author) -> anyhow::Result<EquivocationReport> {
+        let metadata = EquivocationReportMetadata {
+            epoch: self.epoch(),
+            round: self.round(),
+            kind: self.kind(),
+            offender: self.offender(),
+            reporter,
+        };
+        Ok(EquivocationReport::new(metadata, bcs::to_bytes(self)?))
+    }
+
+    /// Decodes the evidence of a report, and verifies that it matches the report metadata.
+    /// Note: the evidence itself still needs to be verified (see `verify`).
+    pub fn from_report(report: &EquivocationReport) -> anyhow::Result<Self> {
+        let evidence: Self = bcs::from_bytes(&report.evidence_bytes)?;
+        let metadata = &report.metadata;
+        ensure!(
+            (
+                evidence.epoch(),
+                evidence.round(),
+                evidence.kind(),
+                evidence.offender()
+            ) == (
+                metadata.epoch,
+                metadata.round,
+                metadata.kind,
+                metadata.offender
+            ),
+            "Equivocation report metadata does not match the evidence"
+        );
+        Ok(evidence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{equivocation_evidence::EquivocationEvidence, vote::Vote, vote_data::VoteData};
+    use aptos_crypto::{hash::CryptoHash, HashValue};
+    use aptos_types::{
+        block_info::BlockInfo, ledger_info::LedgerInfo, validator_signer::ValidatorSigner,
+        validator_verifier::random_validator_verifier,
+    };
+
+    fn create_vote(signer: &ValidatorSigner, round: u64) -> Vote {
+        let proposed = BlockInfo::new(1, round, HashValue::random(), HashValue::zero(), 0, 0, None);
+        let vote_data = VoteData::new(proposed, BlockInfo::random(round - 1));
+        let ledger_info = LedgerInfo::new(BlockInfo::empty(), vote_data.hash());
+        Vote::new(vote_data, signer.author(), ledger_info, signer).unwrap()
+    }
+
+    #[test]
+    fn test_conflicting_votes() {
+        let (signers, validators) = random_validator_verifier(4, None, false);
+
+        let first = create_vote(&signers[0], 2);
+        let second = create_vote(&signers[0], 2);
+        let evidence = EquivocationEvidence::conflicting_votes(first.clone(), second.clone());
+        evidence.verify(&validators).unwrap();
+        assert_eq!(evidence.offender(), signers[0].author());
+        assert_eq!((evidence.epoch(), evidence.round()), (1, 2));
+
+        // The report carries the evidence, which can be decoded and verified again
+        let report = evidence.to_report(signers[1].author()).unwrap();
+        assert_eq!(report.metadata.reporter, signers[1].author());
+        let decoded = EquivocationEvidence::from_report(&report).unwrap();
+        assert_eq!(decoded, evidence);
+        decoded.verify(&validators).unwrap();
+
+        // The report metadata must match the evidence
+        let mut mismatched_report = report;
+        mismatched_report.metadata.offender = signers[2].author();
+        assert!(EquivocationEvidence::from_report(&mismatched_report).is_err());
+
+        // Identical votes, votes from different authors, and votes for different
+        // rounds are not evidence of equivocation
+        let identical = EquivocationEvidence::conflicting_votes(first.clone(), first.clone());
+        assert!(identical.verify(&validators).is_err());
+        let different_authors =
+            EquivocationEvidence::conflicting_votes(first.clone(), create_vote(&signers[1], 2));
+        assert!(different_authors.verify(&validators).is_err());
+        let different_rounds =
+            EquivocationEvidence::conflicting_votes(first, create_vote(&signers[0], 3));
+        assert!(different_rounds.verify(&validators).is_err());
+
+        // The votes must be signed by the offender
+        let forged = Vote::new_with_signature(
+            second.vote_data().clone(),
+            signers[0].author(),
+            second.ledger_info().clone(),
+            signers[1].sign(second.ledger_info()).unwrap(),
+        );
+        let forged = EquivocationEvidence::conflicting_votes(create_vote(&signers[0], 2), forged);
+        assert!(forged.verify(&validators).is_err());
+    }
+}
@@ -0,0 +1,71 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{remote_signer::RemoteSigner, signer_protocol::SignableMessage, Error};
+use aptos_consensus_types::common::Author;
+use aptos_crypto::bls12381;
+use aptos_types::validator_signer::ValidatorSigner;
+use std::sync::Arc;
+
+/// The signer used by safety rules to sign consensus messages. The consensus key is
+/// either held locally (i.e., loaded from secure storage), or by a remote signer.
+pub(crate) enum ConsensusSigner {
+    Local(ValidatorSigner),
+    Remote {
+        author: Author,
+        public_key: bls12381::PublicKey,
+        remote_signer: Arc<RemoteSigner>,
+    },
+    // Signs with a throwaway key on behalf of the expected consensus key. This is
+    // only used to replay recordings offline (where the consensus key is not
+    // available), so the produced signatures never verify.
+    Replay {
+        public_key: bls12381::PublicKey,
+        validator_signer: ValidatorSigner,
+    },
+}
+
+impl ConsensusSigner {
+    pub fn author(&self) -> Author {
+        match self {
+            ConsensusSigner::Local(validator_signer) => validator_signer.author(),
+            ConsensusSigner::Remote { author, .. } => *author,
+            ConsensusSigner::Replay {
+                validator_signer, ..
+            } => validator_signer.author(),
+        }
+    }
+
+    pub fn public_key(&self) -> bls12381::PublicKey {
+        match self {
+            ConsensusSigner::Local(validator_signer) => validator_signer.public_key(),
+            ConsensusSigner::Remote { public_key, .. }
+            | ConsensusSigner::Replay { public_key, .. } => public_key.clone(),
+        }
+    }
+
+    pub fn sign(&self, message: SignableMessage) -> Result<bls12381::Signature, Error> {
+        match self {
+            ConsensusSigner::Local(validator_signer)
+            | ConsensusSigner::Replay {
+                validator_signer, ..
+            } => sign_locally(validator_signer, &message),
+            ConsensusSigner::Remote { remote_signer, .. } => remote_signer.sign(message),
+        }
+    }
+}
+
+/// Signs the message using the given (locally held) validator signer
+fn sign_locally(
+    validator_signer: &ValidatorSigner,
+    message: &SignableMessage,
+) -> Result<bls12381::Signature, Error> {
+    let signature = match message {
+        SignableMessage::Proposal(block_data) => validator_signer.sign(block_data.as_ref()),
+        SignableMessage::Vote { ledger_info, .. }
+        | SignableMessage::OrderVote(ledger_info)
+        | SignableMessage::CommitVote(ledger_info) => validator_signer.sign(ledger_info.as_ref()),
+        SignableMessage::Timeout(timeout) => validator_signer.sign(timeout),
+    };
+    signature.map_err(|error| Error::SerializationError(error.to_string()))
+}
@@ -59,6 +59,12 @@ pub enum Error {
     WaypointOutOfDate(u64, u64, u64, u64),
     #[error("Invalid Timeout: {0}")]
     InvalidTimeout(String),
+    #[error("Invalid signing request: {0}")]
+    InvalidSigningRequest(String),
+    #[error("Unable to communicate with the remote signer: {0}")]
+    RemoteSignerError(String),
+    #[error("The remote signer refused to sign the message: {0}")]
+    SignerRefused(String),
     #[error("Incorrect 1-chain Quorum Certificate provided for signing order votes. Quorum Certificate: {0}, block id: {1}")]
     InvalidOneChainQuorumCertificate(HashValue, HashValue),
 }
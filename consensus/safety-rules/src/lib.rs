@@ -4,6 +4,7 @@
 
 #![forbid(unsafe_code)]
 
+mod consensus_signer;
 mod consensus_state;
 mod counters;
 mod error;
@@ -12,18 +13,22 @@ mod logging;
 mod persistent_safety_storage;
 mod process;
 mod remote_service;
+mod remote_signer;
 mod safety_rules;
 mod safety_rules_2chain;
 pub mod safety_rules_manager;
+mod secure_channel;
 mod serializer;
+pub mod signer_protocol;
+pub mod signer_service;
 mod t_safety_rules;
 mod thread;
 
 pub use crate::{
     consensus_state::ConsensusState, error::Error,
     persistent_safety_storage::PersistentSafetyStorage, process::Process,
-    safety_rules::SafetyRules, safety_rules_manager::SafetyRulesManager,
-    t_safety_rules::TSafetyRules,
+    remote_signer::RemoteSigner, safety_rules::SafetyRules,
+    safety_rules_manager::SafetyRulesManager, t_safety_rules::TSafetyRules,
 };
 
 #[cfg(any(test, feature = "fuzzing"))]
@@ -42,6 +42,29 @@ impl PersistentSafetyStorage {
         Self::initialize_keys_and_accounts(&mut internal_store, author, consensus_private_key)
             .expect("Unable to initialize keys and accounts in storage");
 
+        Self::initialize_safety_data(internal_store, waypoint, enable_cached_safety_data)
+    }
+
+    /// Use this to instantiate a PersistentStorage for a new data store when the consensus key
+    /// is held by a remote signer (i.e., the consensus key is never written to the data store).
+    pub fn initialize_without_consensus_key(
+        mut internal_store: Storage,
+        author: Author,
+        waypoint: Waypoint,
+        enable_cached_safety_data: bool,
+    ) -> Self {
+        internal_store
+            .set(OWNER_ACCOUNT, author)
+            .expect("Unable to initialize the owner account in storage");
+
+        Self::initialize_safety_data(internal_store, waypoint, enable_cached_safety_data)
+    }
+
+    fn initialize_safety_data(
+        internal_store: Storage,
+        waypoint: Waypoint,
+        enable_cached_safety_data: bool,
+    ) -> Self {
         // Create the new persistent safety storage
         let safety_data = SafetyData::new(1, 0, 0, 0, None, 0);
         let mut persisent_safety_storage = Self {
@@ -3,9 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    persistent_safety_storage::PersistentSafetyStorage,
     remote_service::{self, RemoteService},
-    safety_rules_manager,
+    safety_rules_manager, SafetyRules,
 };
 use aptos_config::config::{SafetyRulesConfig, SafetyRulesService};
 use std::net::SocketAddr;
@@ -16,7 +15,7 @@ pub struct Process {
 
 impl Process {
     pub fn new(config: SafetyRulesConfig) -> Self {
-        let storage = safety_rules_manager::storage(&config);
+        let safety_rules = safety_rules_manager::safety_rules(&config);
 
         let service = match &config.service {
             SafetyRulesService::Process(service) => service,
@@ -27,7 +26,7 @@ impl Process {
         Self {
             data: Some(ProcessData {
                 server_addr,
-                storage,
+                safety_rules,
                 network_timeout: config.network_timeout_ms,
             }),
         }
@@ -35,13 +34,13 @@ impl Process {
 
     pub fn start(&mut self) {
         let data = self.data.take().expect("Unable to retrieve ProcessData");
-        remote_service::execute(data.storage, data.server_addr, data.network_timeout);
+        remote_service::execute(data.safety_rules, data.server_addr, data.network_timeout);
     }
 }
 
 struct ProcessData {
     server_addr: SocketAddr,
-    storage: PersistentSafetyStorage,
+    safety_rules: SafetyRules,
     // Timeout in Seconds for network operations
     network_timeout: u64,
 }
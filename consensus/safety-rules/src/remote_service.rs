@@ -3,7 +3,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    persistent_safety_storage::PersistentSafetyStorage,
     serializer::{SafetyRulesInput, SerializerClient, SerializerService, TSerializerClient},
     Error, SafetyRules, TSafetyRules,
 };
@@ -28,8 +27,7 @@ pub trait RemoteService {
     fn network_timeout_ms(&self) -> u64;
 }
 
-pub fn execute(storage: PersistentSafetyStorage, listen_addr: SocketAddr, network_timeout_ms: u64) {
-    let mut safety_rules = SafetyRules::new(storage);
+pub fn execute(mut safety_rules: SafetyRules, listen_addr: SocketAddr, network_timeout_ms: u64) {
     if let Err(e) = safety_rules.consensus_state() {
         warn!("Unable to print consensus state: {}", e);
     }
@@ -0,0 +1,95 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    secure_channel::SecureChannelClient,
+    signer_protocol::{SignableMessage, SignerRequest, SignerResponse},
+    Error,
+};
+use aptos_crypto::{bls12381, x25519};
+use aptos_infallible::Mutex;
+use aptos_logger::warn;
+use aptos_types::network_address::NetworkAddress;
+
+/// The name of the remote signer service (used by the secure-net metrics and logs)
+pub(crate) const REMOTE_SIGNER_SERVICE: &str = "consensus-signer";
+
+/// The number of times a request is sent to the remote signer before giving up. Requests
+/// are retried once to handle stale connections (e.g., after the signer restarts). This is
+/// safe because the signer will re-sign identical messages.
+const MAX_REQUEST_ATTEMPTS: usize = 2;
+
+/// A client for a remote signer that holds the consensus key (e.g., in an HSM).
+/// Unlike the remote safety rules client, failed requests are not retried indefinitely:
+/// the error is returned to safety rules, which will fail the corresponding operation.
+/// All requests are sent over a mutually authenticated (Noise) channel.
+pub struct RemoteSigner {
+    secure_channel: Mutex<SecureChannelClient>,
+}
+
+impl RemoteSigner {
+    pub fn new(
+        server_address: NetworkAddress,
+        server_public_key: x25519::PublicKey,
+        client_private_key: x25519::PrivateKey,
+        network_timeout_ms: u64,
+    ) -> Self {
+        let secure_channel = SecureChannelClient::new(
+            REMOTE_SIGNER_SERVICE.to_string(),
+            server_address,
+            network_timeout_ms,
+            client_private_key,
+            server_public_key,
+        );
+        Self {
+            secure_channel: Mutex::new(secure_channel),
+        }
+    }
+
+    /// Returns the public key of the consensus key held by the remote signer
+    pub fn public_key(&self) -> Result<bls12381::PublicKey, Error> {
+        match self.request(&SignerRequest::PublicKey)? {
+            SignerResponse::PublicKey(public_key) => Ok(public_key),
+            response => Err(Error::RemoteSignerError(format!(
+                "Unexpected response to a public key request: {:?}",
+                response
+            ))),
+        }
+    }
+
+    /// Requests the remote signer to sign the given message
+    pub fn sign(&self, message: SignableMessage) -> Result<bls12381::Signature, Error> {
+        match self.request(&SignerRequest::Sign(message))? {
+            SignerResponse::Signature(signature) => Ok(signature),
+            response => Err(Error::RemoteSignerError(format!(
+                "Unexpected response to a signing request: {:?}",
+                response
+            ))),
+        }
+    }
+
+    fn request(&self, request: &SignerRequest) -> Result<SignerResponse, Error> {
+        let request = serde_json::to_vec(request)?;
+        let mut secure_channel = self.secure_channel.lock();
+
+        let mut last_error = None;
+        for _ in 0..MAX_REQUEST_ATTEMPTS {
+            match secure_channel.request(&request) {
+                Ok(response) => {
+                    let response: Result<SignerResponse, Error> =
+                        serde_json::from_slice(&response)?;
+                    return response;
+                },
+                Err(error) => {
+                    warn!("Failed to communicate with the remote signer: {}", error);
+                    last_error = Some(error);
+                },
+            }
+        }
+
+        Err(Error::RemoteSignerError(format!(
+            "Failed to communicate with the remote signer: {:?}",
+            last_error
+        )))
+    }
+}
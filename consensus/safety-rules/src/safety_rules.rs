@@ -3,11 +3,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    consensus_signer::ConsensusSigner,
     consensus_state::ConsensusState,
     counters,
     error::Error,
     logging::{LogEntry, LogEvent, SafetyLogSchema},
     persistent_safety_storage::PersistentSafetyStorage,
+    remote_signer::RemoteSigner,
+    signer_protocol::SignableMessage,
     t_safety_rules::TSafetyRules,
 };
 use aptos_consensus_types::{
@@ -22,7 +25,7 @@ use aptos_consensus_types::{
     vote_data::VoteData,
     vote_proposal::VoteProposal,
 };
-use aptos_crypto::{bls12381, hash::CryptoHash};
+use aptos_crypto::{bls12381, Uniform};
 use aptos_logger::prelude::*;
 use aptos_types::{
     epoch_change::EpochChangeProof,
@@ -31,8 +34,8 @@ use aptos_types::{
     validator_signer::ValidatorSigner,
     waypoint::Waypoint,
 };
-use serde::Serialize;
-use std::cmp::Ordering;
+use rand::rngs::OsRng;
+use std::{borrow::Cow, cmp::Ordering, sync::Arc};
 
 pub(crate) fn next_round(round: Round) -> Result<Round, Error> {
     u64::checked_add(round, 1).ok_or(Error::IncorrectRound(round))
@@ -41,8 +44,10 @@ pub(crate) fn next_round(round: Round) -> Result<Round, Error> {
 /// @TODO consider a cache of verified QCs to cut down on verification costs
 pub struct SafetyRules {
     pub(crate) persistent_storage: PersistentSafetyStorage,
-    pub(crate) validator_signer: Option<ValidatorSigner>,
+    pub(crate) validator_signer: Option<ConsensusSigner>,
     pub(crate) epoch_state: Option<EpochState>,
+    remote_signer: Option<Arc<RemoteSigner>>,
+    replay: bool,
 }
 
 impl SafetyRules {
@@ -53,6 +58,36 @@ impl SafetyRules {
             persistent_storage,
             validator_signer: None,
             epoch_state: None,
+            remote_signer: None,
+            replay: false,
+        }
+    }
+
+    /// Constructs a new instance of SafetyRules for replaying recordings offline. All
+    /// safety checks are performed as usual, but the consensus key is not required:
+    /// messages are signed with a throwaway key (so the signatures never verify).
+    pub fn new_for_replay(persistent_storage: PersistentSafetyStorage) -> Self {
+        Self {
+            persistent_storage,
+            validator_signer: None,
+            epoch_state: None,
+            remote_signer: None,
+            replay: true,
+        }
+    }
+
+    /// Constructs a new instance of SafetyRules with the given persistent storage, where
+    /// the consensus key is held by the given remote signer (instead of persistent storage)
+    pub fn new_with_remote_signer(
+        persistent_storage: PersistentSafetyStorage,
+        remote_signer: RemoteSigner,
+    ) -> Self {
+        Self {
+            persistent_storage,
+            validator_signer: None,
+            epoch_state: None,
+            remote_signer: Some(Arc::new(remote_signer)),
+            replay: false,
         }
     }
 
@@ -105,17 +140,11 @@ impl SafetyRules {
         Ok(())
     }
 
-    pub(crate) fn sign<T: Serialize + CryptoHash>(
-        &self,
-        message: &T,
-    ) -> Result<bls12381::Signature, Error> {
-        let signer = self.signer()?;
-        signer
-            .sign(message)
-            .map_err(|err| Error::SerializationError(err.to_string()))
+    pub(crate) fn sign(&self, message: SignableMessage) -> Result<bls12381::Signature, Error> {
+        self.signer()?.sign(message)
     }
 
-    pub(crate) fn signer(&self) -> Result<&ValidatorSigner, Error> {
+    pub(crate) fn signer(&self) -> Result<&ConsensusSigner, Error> {
         self.validator_signer
             .as_ref()
             .ok_or_else(|| Error::NotInitialized("validator_signer".into()))
@@ -314,6 +343,32 @@ impl SafetyRules {
                         "in set",
                     );
                     Ok(())
+                } else if let Some(remote_signer) = &self.remote_signer {
+                    // Verify the remote signer holds the expected consensus key
+                    let public_key = remote_signer.public_key()?;
+                    if public_key == expected_key {
+                        self.validator_signer = Some(ConsensusSigner::Remote {
+                            author,
+                            public_key,
+                            remote_signer: remote_signer.clone(),
+                        });
+                        Ok(())
+                    } else {
+                        Err(Error::ValidatorKeyNotFound(format!(
+                            "The remote signer holds the key {}, but {} is expected",
+                            public_key, expected_key
+                        )))
+                    }
+                } else if self.replay {
+                    // The consensus key is not available during replay
+                    self.validator_signer = Some(ConsensusSigner::Replay {
+                        public_key: expected_key,
+                        validator_signer: ValidatorSigner::new(
+                            author,
+                            bls12381::PrivateKey::generate(&mut OsRng),
+                        ),
+                    });
+                    Ok(())
                 } else {
                     // Try to export the consensus key directly from storage.
                     match self
@@ -321,8 +376,9 @@ impl SafetyRules {
                         .consensus_key_for_version(expected_key)
                     {
                         Ok(consensus_key) => {
-                            self.validator_signer =
-                                Some(ValidatorSigner::new(author, consensus_key));
+                            self.validator_signer = Some(ConsensusSigner::Local(
+                                ValidatorSigner::new(author, consensus_key),
+                            ));
                             Ok(())
                         },
                         Err(Error::SecureStorageMissingDataError(error)) => {
@@ -364,7 +420,7 @@ impl SafetyRules {
         self.verify_and_update_preferred_round(block_data.quorum_cert(), &mut safety_data)?;
         // we don't persist the updated preferred round to save latency (it'd be updated upon voting)
 
-        let signature = self.sign(block_data)?;
+        let signature = self.sign(SignableMessage::Proposal(Cow::Borrowed(block_data)))?;
         Ok(signature)
     }
 
@@ -409,7 +465,7 @@ impl SafetyRules {
         // TODO: add guarding rules in unhappy path
         // TODO: add extension check
 
-        let signature = self.sign(&new_ledger_info)?;
+        let signature = self.sign(SignableMessage::CommitVote(Cow::Borrowed(&new_ledger_info)))?;
 
         Ok(signature)
     }
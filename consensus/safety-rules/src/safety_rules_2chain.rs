@@ -2,7 +2,9 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{error::Error, safety_rules::next_round, SafetyRules};
+use crate::{
+    error::Error, safety_rules::next_round, signer_protocol::SignableMessage, SafetyRules,
+};
 use aptos_consensus_types::{
     block::Block,
     order_vote::OrderVote,
@@ -14,6 +16,7 @@ use aptos_consensus_types::{
 };
 use aptos_crypto::{bls12381, hash::CryptoHash, HashValue};
 use aptos_types::{block_info::BlockInfo, ledger_info::LedgerInfo};
+use std::borrow::Cow;
 
 /// 2-chain safety rules implementation
 impl SafetyRules {
@@ -45,7 +48,7 @@ impl SafetyRules {
         self.update_highest_timeout_round(timeout, &mut safety_data);
         self.persistent_storage.set_safety_data(safety_data)?;
 
-        let signature = self.sign(&timeout.signing_format())?;
+        let signature = self.sign(SignableMessage::Timeout(timeout.signing_format()))?;
         Ok(signature)
     }
 
@@ -84,7 +87,10 @@ impl SafetyRules {
         // Construct and sign vote
         let author = self.signer()?.author();
         let ledger_info = self.construct_ledger_info_2chain(proposed_block, vote_data.hash())?;
-        let signature = self.sign(&ledger_info)?;
+        let signature = self.sign(SignableMessage::Vote {
+            vote_data: Cow::Borrowed(&vote_data),
+            ledger_info: Cow::Borrowed(&ledger_info),
+        })?;
         let vote = Vote::new_with_signature(vote_data, author, ledger_info, signature);
 
         safety_data.last_vote = Some(vote.clone());
@@ -111,7 +117,7 @@ impl SafetyRules {
         let author = self.signer()?.author();
         let ledger_info =
             LedgerInfo::new(order_vote_proposal.block_info().clone(), HashValue::zero());
-        let signature = self.sign(&ledger_info)?;
+        let signature = self.sign(SignableMessage::OrderVote(Cow::Borrowed(&ledger_info)))?;
         let order_vote = OrderVote::new_with_signature(author, ledger_info.clone(), signature);
         self.persistent_storage.set_safety_data(safety_data)?;
         Ok(order_vote)
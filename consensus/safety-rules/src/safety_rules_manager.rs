@@ -9,10 +9,12 @@ use crate::{
     remote_service::RemoteService,
     serializer::{SerializerClient, SerializerService},
     thread::ThreadService,
-    SafetyRules, TSafetyRules,
+    RemoteSigner, SafetyRules, TSafetyRules,
+};
+use anyhow::{anyhow, bail};
+use aptos_config::config::{
+    ConsensusSignerConfig, InitialSafetyRulesConfig, SafetyRulesConfig, SafetyRulesService,
 };
-use anyhow::anyhow;
-use aptos_config::config::{InitialSafetyRulesConfig, SafetyRulesConfig, SafetyRulesService};
 use aptos_crypto::bls12381::PrivateKey;
 use aptos_global_constants::CONSENSUS_KEY;
 use aptos_infallible::RwLock;
@@ -28,13 +30,23 @@ pub fn storage(config: &SafetyRulesConfig) -> PersistentSafetyStorage {
 
     if let Some(test_config) = &config.test {
         let author = test_config.author;
+        let waypoint = test_config.waypoint.expect("No waypoint in config");
+
+        // The consensus key is held by the remote signer (if configured)
+        if config.signer.is_remote() {
+            return PersistentSafetyStorage::initialize_without_consensus_key(
+                internal_storage,
+                author,
+                waypoint,
+                config.enable_cached_safety_data,
+            );
+        }
+
         let consensus_private_key = test_config
             .consensus_key
             .as_ref()
             .expect("Missing consensus key in test config")
             .private_key();
-        let waypoint = test_config.waypoint.expect("No waypoint in config");
-
         PersistentSafetyStorage::initialize(
             internal_storage,
             author,
@@ -57,20 +69,31 @@ pub fn storage(config: &SafetyRulesConfig) -> PersistentSafetyStorage {
                 .identity_blob()
                 .expect("No identity blob in initial safety rules config");
             let waypoint = config.initial_safety_rules_config.waypoint();
+            let author = identity_blob
+                .account_address
+                .expect("AccountAddress needed for safety rules");
 
             let backend = &config.backend;
             let internal_storage: Storage = backend.into();
-            PersistentSafetyStorage::initialize(
-                internal_storage,
-                identity_blob
-                    .account_address
-                    .expect("AccountAddress needed for safety rules"),
-                identity_blob
-                    .consensus_private_key
-                    .expect("Consensus key needed for safety rules"),
-                waypoint,
-                config.enable_cached_safety_data,
-            )
+            if config.signer.is_remote() {
+                // The consensus key is held by the remote signer, so it is never persisted
+                PersistentSafetyStorage::initialize_without_consensus_key(
+                    internal_storage,
+                    author,
+                    waypoint,
+                    config.enable_cached_safety_data,
+                )
+            } else {
+                PersistentSafetyStorage::initialize(
+                    internal_storage,
+                    author,
+                    identity_blob
+                        .consensus_private_key
+                        .expect("Consensus key needed for safety rules"),
+                    waypoint,
+                    config.enable_cached_safety_data,
+                )
+            }
         } else {
             panic!(
                 "Safety rules storage is not initialized, provide an initial safety rules config"
@@ -79,9 +102,31 @@ pub fn storage(config: &SafetyRulesConfig) -> PersistentSafetyStorage {
     }
 }
 
+/// Creates the safety rules for the given config (using a remote signer, if configured)
+pub fn safety_rules(config: &SafetyRulesConfig) -> SafetyRules {
+    let storage = storage(config);
+    match &config.signer {
+        ConsensusSignerConfig::SecureBackend => SafetyRules::new(storage),
+        ConsensusSignerConfig::Remote(signer_config) => {
+            let remote_signer = RemoteSigner::new(
+                signer_config.server_address.clone(),
+                signer_config.server_public_key,
+                signer_config.client_private_key.private_key(),
+                signer_config.network_timeout_ms,
+            );
+            SafetyRules::new_with_remote_signer(storage, remote_signer)
+        },
+    }
+}
+
+/// Loads the consensus key from secure storage. Note: this fails if the consensus key is
+/// held by a remote signer (as the key never leaves the signer).
 pub fn load_consensus_key_from_secure_storage(
     config: &SafetyRulesConfig,
 ) -> anyhow::Result<PrivateKey> {
+    if config.signer.is_remote() {
+        bail!("load_consensus_key_from_secure_storage failed: the consensus key is held by a remote signer");
+    }
     let storage: Storage = (&config.backend).into();
     let storage = Box::new(storage);
     let response = storage.get::<PrivateKey>(CONSENSUS_KEY).map_err(|e| {
@@ -107,17 +152,22 @@ impl SafetyRulesManager {
             return Self::new_process(conf.server_address(), config.network_timeout_ms);
         }
 
-        let storage = storage(config);
+        let safety_rules = safety_rules(config);
         match config.service {
-            SafetyRulesService::Local => Self::new_local(storage),
-            SafetyRulesService::Serializer => Self::new_serializer(storage),
-            SafetyRulesService::Thread => Self::new_thread(storage, config.network_timeout_ms),
+            SafetyRulesService::Local => Self::new_local_with_safety_rules(safety_rules),
+            SafetyRulesService::Serializer => Self::new_serializer_with_safety_rules(safety_rules),
+            SafetyRulesService::Thread => {
+                Self::new_thread_with_safety_rules(safety_rules, config.network_timeout_ms)
+            },
             _ => panic!("Unimplemented SafetyRulesService: {:?}", config.service),
         }
     }
 
     pub fn new_local(storage: PersistentSafetyStorage) -> Self {
-        let safety_rules = SafetyRules::new(storage);
+        Self::new_local_with_safety_rules(SafetyRules::new(storage))
+    }
+
+    pub fn new_local_with_safety_rules(safety_rules: SafetyRules) -> Self {
         Self {
             internal_safety_rules: SafetyRulesWrapper::Local(Arc::new(RwLock::new(safety_rules))),
         }
@@ -131,7 +181,10 @@ impl SafetyRulesManager {
     }
 
     pub fn new_serializer(storage: PersistentSafetyStorage) -> Self {
-        let safety_rules = SafetyRules::new(storage);
+        Self::new_serializer_with_safety_rules(SafetyRules::new(storage))
+    }
+
+    pub fn new_serializer_with_safety_rules(safety_rules: SafetyRules) -> Self {
         let serializer_service = SerializerService::new(safety_rules);
         Self {
             internal_safety_rules: SafetyRulesWrapper::Serializer(Arc::new(RwLock::new(
@@ -141,7 +194,11 @@ impl SafetyRulesManager {
     }
 
     pub fn new_thread(storage: PersistentSafetyStorage, timeout_ms: u64) -> Self {
-        let thread = ThreadService::new(storage, timeout_ms);
+        Self::new_thread_with_safety_rules(SafetyRules::new(storage), timeout_ms)
+    }
+
+    pub fn new_thread_with_safety_rules(safety_rules: SafetyRules, timeout_ms: u64) -> Self {
+        let thread = ThreadService::new(safety_rules, timeout_ms);
         Self {
             internal_safety_rules: SafetyRulesWrapper::Thread(thread),
         }
@@ -0,0 +1,316 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A mutually authenticated (and encrypted) channel between the remote signer client
+//! and the signer daemon. The channel runs the Noise IK handshake over the secure-net
+//! framing: the client must know the static public key of the signer, and the signer
+//! only accepts clients with trusted static public keys. A new session is started
+//! whenever the client (re)connects or the signer loses the session (e.g., on restart).
+
+use crate::Error;
+use aptos_crypto::{
+    noise::{self, NoiseConfig, NoiseSession},
+    x25519,
+};
+use aptos_logger::warn;
+use aptos_secure_net::{NetworkClient, NetworkServer};
+use aptos_types::network_address::NetworkAddress;
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use std::net::{SocketAddr, ToSocketAddrs};
+
+/// The prologue of the Noise handshake (binds the handshake to the signer protocol)
+const NOISE_PROLOGUE: &[u8] = b"aptos-consensus-signer";
+
+/// The maximum number of plaintext bytes encrypted as a single Noise message
+const MAX_CHUNK_SIZE_BYTES: usize = noise::MAX_SIZE_NOISE_MSG - noise::AES_GCM_TAGLEN;
+
+/// The frames sent over the channel
+#[derive(Debug, Deserialize, Serialize)]
+enum ChannelFrame {
+    // A Noise handshake message (the client starts every session with a handshake)
+    Handshake(Vec<u8>),
+    // A message encrypted using the current session
+    Message(Vec<u8>),
+    // The signer failed to process the frame (e.g., it has no session for the client)
+    Error(String),
+}
+
+/// The client side of the channel (used by the remote signer client). The server
+/// address is resolved on the first request, so resolution failures are returned
+/// as request errors (and retried by subsequent requests).
+pub(crate) struct SecureChannelClient {
+    service: String,
+    server_address: NetworkAddress,
+    network_timeout_ms: u64,
+    network_client: Option<NetworkClient>,
+    noise_config: NoiseConfig,
+    server_public_key: x25519::PublicKey,
+    session: Option<NoiseSession>,
+}
+
+impl SecureChannelClient {
+    pub fn new(
+        service: String,
+        server_address: NetworkAddress,
+        network_timeout_ms: u64,
+        client_private_key: x25519::PrivateKey,
+        server_public_key: x25519::PublicKey,
+    ) -> Self {
+        Self {
+            service,
+            server_address,
+            network_timeout_ms,
+            network_client: None,
+            noise_config: NoiseConfig::new(client_private_key),
+            server_public_key,
+            session: None,
+        }
+    }
+
+    /// Sends the request to the signer and returns the response. If the request fails,
+    /// the session is dropped, so the next request starts a new session.
+    pub fn request(&mut self, request: &[u8]) -> Result<Vec<u8>, Error> {
+        let result = self.send_request(request);
+        if result.is_err() {
+            self.session = None;
+        }
+        result
+    }
+
+    fn send_request(&mut self, request: &[u8]) -> Result<Vec<u8>, Error> {
+        if self.network_client.is_none() {
+            self.network_client = Some(self.create_network_client()?);
+        }
+        if self.session.is_none() {
+            self.session = Some(self.handshake()?);
+        }
+        let (network_client, session) = match (&mut self.network_client, &mut self.session) {
+            (Some(network_client), Some(session)) => (network_client, session),
+            _ => {
+                return Err(Error::RemoteSignerError(
+                    "No session with the remote signer!".into(),
+                ))
+            },
+        };
+
+        let frame = ChannelFrame::Message(encrypt(session, request)?);
+        network_client.write(&serialize_frame(&frame)?)?;
+        match deserialize_frame(&network_client.read()?)? {
+            ChannelFrame::Message(response) => decrypt(session, &response),
+            frame => Err(unexpected_frame(frame)),
+        }
+    }
+
+    /// Resolves the server address and creates the network client
+    fn create_network_client(&self) -> Result<NetworkClient, Error> {
+        let server_addr = self
+            .server_address
+            .to_socket_addrs()
+            .ok()
+            .and_then(|mut addresses| addresses.next())
+            .ok_or_else(|| {
+                Error::RemoteSignerError(format!(
+                    "Failed to resolve the remote signer address: {}",
+                    self.server_address
+                ))
+            })?;
+        Ok(NetworkClient::new(
+            self.service.clone(),
+            server_addr,
+            self.network_timeout_ms,
+        ))
+    }
+
+    /// Performs the Noise handshake with the signer and returns the new session
+    fn handshake(&mut self) -> Result<NoiseSession, Error> {
+        let network_client = self.network_client.as_mut().ok_or_else(|| {
+            Error::RemoteSignerError("No connection to the remote signer!".into())
+        })?;
+        let mut handshake_message = vec![0u8; noise::handshake_init_msg_len(0)];
+        let handshake_state = self
+            .noise_config
+            .initiate_connection(
+                &mut OsRng,
+                NOISE_PROLOGUE,
+                self.server_public_key,
+                None,
+                &mut handshake_message,
+            )
+            .map_err(noise_error)?;
+
+        let frame = ChannelFrame::Handshake(handshake_message);
+        network_client.write(&serialize_frame(&frame)?)?;
+        match deserialize_frame(&network_client.read()?)? {
+            ChannelFrame::Handshake(response) => {
+                let (_, session) = self
+                    .noise_config
+                    .finalize_connection(handshake_state, &response)
+                    .map_err(noise_error)?;
+                Ok(session)
+            },
+            frame => Err(unexpected_frame(frame)),
+        }
+    }
+}
+
+/// The server side of the channel (used by the signer daemon)
+pub(crate) struct SecureChannelServer {
+    network_server: NetworkServer,
+    noise_config: NoiseConfig,
+    trusted_client_keys: Vec<x25519::PublicKey>,
+    session: Option<NoiseSession>,
+}
+
+impl SecureChannelServer {
+    pub fn new(
+        service: String,
+        listen_addr: SocketAddr,
+        network_timeout_ms: u64,
+        server_private_key: x25519::PrivateKey,
+        trusted_client_keys: Vec<x25519::PublicKey>,
+    ) -> Self {
+        Self {
+            network_server: NetworkServer::new(service, listen_addr, network_timeout_ms),
+            noise_config: NoiseConfig::new(server_private_key),
+            trusted_client_keys,
+            session: None,
+        }
+    }
+
+    /// Returns the next request received from an authenticated client. Handshakes
+    /// are processed internally, and only replace the current session once the client
+    /// is authenticated (so untrusted peers can't drop the session of a trusted client).
+    /// Invalid frames are answered with an error frame.
+    pub fn read(&mut self) -> Result<Vec<u8>, Error> {
+        loop {
+            let frame = self.network_server.read()?;
+            let response = match deserialize_frame(&frame) {
+                Ok(ChannelFrame::Handshake(handshake_message)) => self
+                    .respond_to_handshake(&handshake_message)
+                    .map(|(response, session)| {
+                        self.session = Some(session);
+                        ChannelFrame::Handshake(response)
+                    }),
+                Ok(ChannelFrame::Message(request)) => match self.session.as_mut() {
+                    Some(session) => match decrypt(session, &request) {
+                        Ok(request) => return Ok(request),
+                        Err(error) => {
+                            // The session can't be used after a failed decryption
+                            self.session = None;
+                            Err(error)
+                        },
+                    },
+                    None => Err(Error::RemoteSignerError(
+                        "No session with the client! A handshake is required.".into(),
+                    )),
+                },
+                Ok(frame) => Err(unexpected_frame(frame)),
+                Err(error) => Err(error),
+            };
+
+            let response = response.unwrap_or_else(|error| {
+                warn!("Rejected a frame from the remote signer client: {}", error);
+                ChannelFrame::Error(error.to_string())
+            });
+            self.network_server.write(&serialize_frame(&response)?)?;
+        }
+    }
+
+    /// Encrypts the response and sends it to the client
+    pub fn write(&mut self, response: &[u8]) -> Result<(), Error> {
+        let session = self.session.as_mut().ok_or_else(|| {
+            Error::RemoteSignerError("No session with the client to respond on!".into())
+        })?;
+        let frame = ChannelFrame::Message(encrypt(session, response)?);
+        self.network_server.write(&serialize_frame(&frame)?)?;
+        Ok(())
+    }
+
+    /// Verifies the client's handshake (and that the client is trusted), and returns
+    /// the handshake response together with the new session.
+    fn respond_to_handshake(
+        &self,
+        handshake_message: &[u8],
+    ) -> Result<(Vec<u8>, NoiseSession), Error> {
+        let (client_public_key, handshake_state, _) = self
+            .noise_config
+            .parse_client_init_message(NOISE_PROLOGUE, handshake_message)
+            .map_err(noise_error)?;
+        if !self.trusted_client_keys.contains(&client_public_key) {
+            return Err(Error::RemoteSignerError(format!(
+                "The client key is not trusted: {}",
+                client_public_key
+            )));
+        }
+
+        let mut response = vec![0u8; noise::handshake_resp_msg_len(0)];
+        let session = self
+            .noise_config
+            .respond_to_client(&mut OsRng, handshake_state, None, &mut response)
+            .map_err(noise_error)?;
+        Ok((response, session))
+    }
+}
+
+/// Encrypts the plaintext (in chunks that fit into single Noise messages)
+fn encrypt(session: &mut NoiseSession, plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut ciphertext = vec![];
+    let mut chunks: Vec<&[u8]> = plaintext.chunks(MAX_CHUNK_SIZE_BYTES).collect();
+    if chunks.is_empty() {
+        chunks.push(&[]); // Empty messages are still authenticated
+    }
+    for chunk in chunks {
+        let mut buffer = chunk.to_vec();
+        let auth_tag = session
+            .write_message_in_place(&mut buffer)
+            .map_err(noise_error)?;
+        ciphertext.extend(buffer);
+        ciphertext.extend(auth_tag);
+    }
+    Ok(ciphertext)
+}
+
+/// Decrypts (and authenticates) the ciphertext produced by `encrypt`
+fn decrypt(session: &mut NoiseSession, ciphertext: &[u8]) -> Result<Vec<u8>, Error> {
+    if ciphertext.is_empty() {
+        return Err(Error::RemoteSignerError(
+            "Received an empty (unauthenticated) message!".into(),
+        ));
+    }
+    let mut plaintext = vec![];
+    for chunk in ciphertext.chunks(noise::MAX_SIZE_NOISE_MSG) {
+        let mut buffer = chunk.to_vec();
+        let decrypted_chunk = session
+            .read_message_in_place(&mut buffer)
+            .map_err(noise_error)?;
+        plaintext.extend_from_slice(decrypted_chunk);
+    }
+    Ok(plaintext)
+}
+
+fn serialize_frame(frame: &ChannelFrame) -> Result<Vec<u8>, Error> {
+    bcs::to_bytes(frame).map_err(|error| Error::SerializationError(error.to_string()))
+}
+
+fn deserialize_frame(bytes: &[u8]) -> Result<ChannelFrame, Error> {
+    bcs::from_bytes(bytes).map_err(|error| Error::SerializationError(error.to_string()))
+}
+
+fn noise_error(error: noise::NoiseError) -> Error {
+    Error::RemoteSignerError(format!("Noise error: {}", error))
+}
+
+fn unexpected_frame(frame: ChannelFrame) -> Error {
+    match frame {
+        ChannelFrame::Error(error) => {
+            Error::RemoteSignerError(format!("The remote peer returned an error: {}", error))
+        },
+        ChannelFrame::Handshake(_) => {
+            Error::RemoteSignerError("Received an unexpected handshake frame!".into())
+        },
+        ChannelFrame::Message(_) => {
+            Error::RemoteSignerError("Received an unexpected message frame!".into())
+        },
+    }
+}
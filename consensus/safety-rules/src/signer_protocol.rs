@@ -0,0 +1,140 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! The protocol spoken between safety rules and a remote (e.g., HSM-style) signer.
+//! Each request is a JSON encoded `SignerRequest`, and each response is a JSON
+//! encoded `Result<SignerResponse, Error>`. Messages are framed by the secure-net
+//! transport (a length prefix, followed by the message bytes).
+//!
+//! Signing requests carry the full consensus message (rather than a hash), so that
+//! the signer can derive the epoch and round of each message, and enforce its own
+//! double-sign protection independently of the node.
+
+use crate::Error;
+use aptos_consensus_types::{
+    block_data::BlockData, common::Round, timeout_2chain::TimeoutSigningRepr, vote_data::VoteData,
+};
+use aptos_crypto::{
+    bls12381,
+    hash::{CryptoHash, HashValue},
+    traits::{signing_message, SigningKey},
+};
+use aptos_types::{block_info::BlockInfo, ledger_info::LedgerInfo};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// A request sent to the remote signer
+#[derive(Debug, Deserialize, Serialize)]
+pub enum SignerRequest<'a> {
+    /// Returns the public key of the consensus key held by the signer
+    PublicKey,
+    /// Signs the given consensus message (if permitted by the signer's watermarks)
+    Sign(SignableMessage<'a>),
+}
+
+/// A (successful) response returned by the remote signer
+#[derive(Debug, Deserialize, Serialize)]
+pub enum SignerResponse {
+    PublicKey(bls12381::PublicKey),
+    Signature(bls12381::Signature),
+}
+
+/// The consensus messages that can be signed with the consensus key
+#[derive(Debug, Deserialize, Serialize)]
+pub enum SignableMessage<'a> {
+    Proposal(Cow<'a, BlockData>),
+    Vote {
+        vote_data: Cow<'a, VoteData>,
+        ledger_info: Cow<'a, LedgerInfo>,
+    },
+    Timeout(TimeoutSigningRepr),
+    OrderVote(Cow<'a, LedgerInfo>),
+    CommitVote(Cow<'a, LedgerInfo>),
+}
+
+impl<'a> SignableMessage<'a> {
+    /// Returns a short label for the message (e.g., for logging and errors)
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            SignableMessage::Proposal(_) => "proposal",
+            SignableMessage::Vote { .. } => "vote",
+            SignableMessage::Timeout(_) => "timeout",
+            SignableMessage::OrderVote(_) => "order_vote",
+            SignableMessage::CommitVote(_) => "commit_vote",
+        }
+    }
+
+    /// Returns the epoch and round of the message
+    pub fn get_epoch_and_round(&self) -> (u64, Round) {
+        match self {
+            SignableMessage::Proposal(block_data) => (block_data.epoch(), block_data.round()),
+            SignableMessage::Vote { vote_data, .. } => {
+                (vote_data.proposed().epoch(), vote_data.proposed().round())
+            },
+            SignableMessage::Timeout(timeout) => (timeout.epoch, timeout.round),
+            SignableMessage::OrderVote(ledger_info) | SignableMessage::CommitVote(ledger_info) => {
+                (ledger_info.epoch(), ledger_info.round())
+            },
+        }
+    }
+
+    /// Verifies that the message is internally consistent. For votes, the ledger info
+    /// must commit to the vote data, and may only commit the parent of the proposed
+    /// block (according to the 2-chain commit rule).
+    pub fn verify(&self) -> Result<(), Error> {
+        if let SignableMessage::Vote {
+            vote_data,
+            ledger_info,
+        } = self
+        {
+            if ledger_info.consensus_data_hash() != vote_data.hash() {
+                return Err(Error::InvalidSigningRequest(
+                    "The vote ledger info does not match the vote data".into(),
+                ));
+            }
+
+            let commit_info = ledger_info.commit_info();
+            let parent = vote_data.parent();
+            let is_2chain_commit = commit_info == parent
+                && parent.round().checked_add(1) == Some(vote_data.proposed().round());
+            if commit_info != &BlockInfo::empty() && !is_2chain_commit {
+                return Err(Error::InvalidSigningRequest(format!(
+                    "The vote ledger info commits an unexpected block: {}",
+                    commit_info
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the bytes that are signed with the consensus key (i.e., the domain
+    /// separation seed, followed by the BCS bytes of the message). An HSM holding
+    /// the consensus key only needs to sign these bytes.
+    pub fn signing_message(&self) -> Result<Vec<u8>, Error> {
+        let message = match self {
+            SignableMessage::Proposal(block_data) => signing_message(block_data.as_ref()),
+            SignableMessage::Vote { ledger_info, .. }
+            | SignableMessage::OrderVote(ledger_info)
+            | SignableMessage::CommitVote(ledger_info) => signing_message(ledger_info.as_ref()),
+            SignableMessage::Timeout(timeout) => signing_message(timeout),
+        };
+        message.map_err(|error| Error::SerializationError(error.to_string()))
+    }
+
+    /// Returns the hash of the signing message (e.g., to detect conflicting messages)
+    pub fn signing_message_hash(&self) -> Result<HashValue, Error> {
+        Ok(HashValue::sha3_256_of(&self.signing_message()?))
+    }
+
+    /// Signs the message using the given consensus key
+    pub fn sign(&self, consensus_key: &bls12381::PrivateKey) -> Result<bls12381::Signature, Error> {
+        let signature = match self {
+            SignableMessage::Proposal(block_data) => consensus_key.sign(block_data.as_ref()),
+            SignableMessage::Vote { ledger_info, .. }
+            | SignableMessage::OrderVote(ledger_info)
+            | SignableMessage::CommitVote(ledger_info) => consensus_key.sign(ledger_info.as_ref()),
+            SignableMessage::Timeout(timeout) => consensus_key.sign(timeout),
+        };
+        signature.map_err(|error| Error::SerializationError(error.to_string()))
+    }
+}
@@ -0,0 +1,279 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A reference implementation of the remote signer daemon. The daemon holds the consensus
+//! key and signs consensus messages on behalf of safety rules. To protect against a
+//! compromised (or buggy) node, the daemon maintains its own watermarks (persisted to
+//! secure storage before any signature is returned) and refuses to sign conflicting
+//! messages, e.g., two different votes for the same round.
+
+use crate::{
+    remote_signer::REMOTE_SIGNER_SERVICE,
+    secure_channel::SecureChannelServer,
+    signer_protocol::{SignableMessage, SignerRequest, SignerResponse},
+    Error,
+};
+use aptos_consensus_types::common::Round;
+use aptos_crypto::{bls12381, x25519, HashValue, PrivateKey};
+use aptos_global_constants::{CONSENSUS_KEY, SIGNER_WATERMARKS};
+use aptos_logger::{info, warn};
+use aptos_secure_storage::{KVStorage, Storage};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+/// A consensus key that can sign consensus messages. Keys held by an HSM (e.g., over
+/// PKCS#11) can implement this trait by signing the bytes of `message.signing_message()`.
+pub trait ConsensusKey: Send {
+    /// Returns the public key of the consensus key
+    fn public_key(&self) -> bls12381::PublicKey;
+
+    /// Signs the given message
+    fn sign(&self, message: &SignableMessage) -> Result<bls12381::Signature, Error>;
+}
+
+/// A consensus key held in memory
+pub struct InMemoryConsensusKey {
+    private_key: bls12381::PrivateKey,
+}
+
+impl InMemoryConsensusKey {
+    pub fn new(private_key: bls12381::PrivateKey) -> Self {
+        Self { private_key }
+    }
+
+    /// Loads the consensus key from the given storage
+    pub fn from_storage(storage: &Storage) -> Result<Self, Error> {
+        let private_key = storage.get(CONSENSUS_KEY).map(|v| v.value)?;
+        Ok(Self::new(private_key))
+    }
+}
+
+impl ConsensusKey for InMemoryConsensusKey {
+    fn public_key(&self) -> bls12381::PublicKey {
+        self.private_key.public_key()
+    }
+
+    fn sign(&self, message: &SignableMessage) -> Result<bls12381::Signature, Error> {
+        message.sign(&self.private_key)
+    }
+}
+
+/// The position (and hash) of the highest message of a kind that was signed
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Watermark {
+    pub epoch: u64,
+    pub round: Round,
+    pub message_hash: HashValue,
+}
+
+impl Watermark {
+    fn new(message: &SignableMessage) -> Result<Self, Error> {
+        let (epoch, round) = message.get_epoch_and_round();
+        Ok(Self {
+            epoch,
+            round,
+            message_hash: message.signing_message_hash()?,
+        })
+    }
+
+    fn position(&self) -> (u64, Round) {
+        (self.epoch, self.round)
+    }
+}
+
+/// The watermarks of all messages signed by the signer. The signer enforces the
+/// following rules (where messages are ordered by epoch, and then by round):
+/// 1. Proposals and votes must be higher than the last proposal or vote (respectively),
+///    unless the message is identical to the last one (e.g., a retried request).
+/// 2. Votes must be higher than the last timeout, and timeouts must not be lower than
+///    the last vote or timeout.
+/// 3. Order votes and commit votes must be higher than the last order vote or commit vote
+///    (respectively), unless the message is identical to the last one. Order votes must
+///    also be higher than the last timeout.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SignerWatermarks {
+    pub proposal: Option<Watermark>,
+    pub vote: Option<Watermark>,
+    pub timeout: Option<Watermark>,
+    pub order_vote: Option<Watermark>,
+    pub commit_vote: Option<Watermark>,
+}
+
+impl SignerWatermarks {
+    /// Verifies that the given message can be signed, and returns the updated watermarks
+    pub fn check_and_update(&self, message: &SignableMessage) -> Result<Self, Error> {
+        let label = message.get_label();
+        let watermark = Watermark::new(message)?;
+        let mut watermarks = self.clone();
+        match message {
+            SignableMessage::Proposal(_) => {
+                check_increasing(label, &self.proposal, &watermark)?;
+                watermarks.proposal = Some(watermark);
+            },
+            SignableMessage::Vote { .. } => {
+                check_increasing(label, &self.vote, &watermark)?;
+                check_above_timeout(label, &self.timeout, &watermark)?;
+                watermarks.vote = Some(watermark);
+            },
+            SignableMessage::Timeout(_) => {
+                for (previous_label, previous) in [("timeout", &self.timeout), ("vote", &self.vote)]
+                {
+                    if let Some(previous) = previous {
+                        if watermark.position() < previous.position() {
+                            return Err(refused(label, previous_label, previous));
+                        }
+                    }
+                }
+                watermarks.timeout = Some(watermark);
+            },
+            SignableMessage::OrderVote(_) => {
+                check_increasing(label, &self.order_vote, &watermark)?;
+                check_above_timeout(label, &self.timeout, &watermark)?;
+                watermarks.order_vote = Some(watermark);
+            },
+            SignableMessage::CommitVote(_) => {
+                check_increasing(label, &self.commit_vote, &watermark)?;
+                watermarks.commit_vote = Some(watermark);
+            },
+        }
+        Ok(watermarks)
+    }
+}
+
+/// Verifies that the new watermark is higher than the previous one (or identical)
+fn check_increasing(
+    label: &str,
+    previous: &Option<Watermark>,
+    watermark: &Watermark,
+) -> Result<(), Error> {
+    match previous {
+        Some(previous)
+            if watermark.position() < previous.position()
+                || (watermark.position() == previous.position() && watermark != previous) =>
+        {
+            Err(refused(label, label, previous))
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Verifies that the new watermark is strictly higher than the last timeout
+fn check_above_timeout(
+    label: &str,
+    timeout: &Option<Watermark>,
+    watermark: &Watermark,
+) -> Result<(), Error> {
+    match timeout {
+        Some(timeout) if watermark.position() <= timeout.position() => {
+            Err(refused(label, "timeout", timeout))
+        },
+        _ => Ok(()),
+    }
+}
+
+fn refused(label: &str, previous_label: &str, previous: &Watermark) -> Error {
+    Error::SignerRefused(format!(
+        "The {} conflicts with the last signed {} (epoch {}, round {})",
+        label, previous_label, previous.epoch, previous.round
+    ))
+}
+
+/// The signer daemon: verifies signing requests against the watermarks, and signs
+/// them using the consensus key.
+pub struct SignerService {
+    consensus_key: Box<dyn ConsensusKey>,
+    storage: Storage,
+    watermarks: SignerWatermarks,
+}
+
+impl SignerService {
+    /// Creates a new signer service. The watermarks are loaded from the given storage.
+    pub fn new(consensus_key: Box<dyn ConsensusKey>, storage: Storage) -> Result<Self, Error> {
+        let watermarks = match storage.get(SIGNER_WATERMARKS) {
+            Ok(response) => response.value,
+            Err(aptos_secure_storage::Error::KeyNotSet(_)) => SignerWatermarks::default(),
+            Err(error) => return Err(error.into()),
+        };
+        Ok(Self {
+            consensus_key,
+            storage,
+            watermarks,
+        })
+    }
+
+    pub fn watermarks(&self) -> &SignerWatermarks {
+        &self.watermarks
+    }
+
+    /// Handles a serialized request, and returns the serialized response
+    pub fn handle_message(&mut self, request: Vec<u8>) -> Result<Vec<u8>, Error> {
+        let response = serde_json::from_slice(&request)
+            .map_err(Error::from)
+            .and_then(|request| self.handle_request(request));
+        Ok(serde_json::to_vec(&response)?)
+    }
+
+    /// Handles the given request
+    pub fn handle_request(&mut self, request: SignerRequest) -> Result<SignerResponse, Error> {
+        match request {
+            SignerRequest::PublicKey => {
+                Ok(SignerResponse::PublicKey(self.consensus_key.public_key()))
+            },
+            SignerRequest::Sign(message) => self.sign(&message).map(SignerResponse::Signature),
+        }
+    }
+
+    fn sign(&mut self, message: &SignableMessage) -> Result<bls12381::Signature, Error> {
+        message.verify()?;
+
+        // Persist the updated watermarks before returning the signature
+        let watermarks = self.watermarks.check_and_update(message).map_err(|error| {
+            warn!("Refused to sign the {}: {}", message.get_label(), error);
+            error
+        })?;
+        if watermarks != self.watermarks {
+            self.storage.set(SIGNER_WATERMARKS, watermarks.clone())?;
+            self.watermarks = watermarks;
+        }
+
+        self.consensus_key.sign(message)
+    }
+}
+
+/// Runs the signer service, serving requests on the given address (indefinitely).
+/// Only clients holding one of the trusted (x25519) keys are served.
+pub fn execute(
+    mut signer_service: SignerService,
+    listen_addr: SocketAddr,
+    server_private_key: x25519::PrivateKey,
+    trusted_client_keys: Vec<x25519::PublicKey>,
+    network_timeout_ms: u64,
+) {
+    info!(
+        "Starting the consensus signer (public key: {})",
+        signer_service.consensus_key.public_key()
+    );
+    let mut secure_channel = SecureChannelServer::new(
+        REMOTE_SIGNER_SERVICE.to_string(),
+        listen_addr,
+        network_timeout_ms,
+        server_private_key,
+        trusted_client_keys,
+    );
+
+    loop {
+        if let Err(e) = process_one_message(&mut secure_channel, &mut signer_service) {
+            warn!("Failed to process message: {}", e);
+        }
+    }
+}
+
+fn process_one_message(
+    secure_channel: &mut SecureChannelServer,
+    signer_service: &mut SignerService,
+) -> Result<(), Error> {
+    let request = secure_channel.read()?;
+    let response = signer_service.handle_message(request)?;
+    secure_channel.write(&response)?;
+    Ok(())
+}
@@ -5,6 +5,7 @@
 extern crate claims;
 mod local;
 mod networking;
+mod remote_signer;
 mod safety_rules;
 mod serializer;
 mod suite;
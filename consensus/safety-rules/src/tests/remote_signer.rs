@@ -0,0 +1,351 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    signer_protocol::{SignableMessage, SignerRequest, SignerResponse},
+    signer_service::{self, InMemoryConsensusKey, SignerService},
+    test_utils,
+    tests::suite,
+    Error, PersistentSafetyStorage, RemoteSigner, SafetyRules, SafetyRulesManager,
+};
+use aptos_config::utils;
+use aptos_consensus_types::{timeout_2chain::TimeoutSigningRepr, vote_data::VoteData};
+use aptos_crypto::{hash::CryptoHash, x25519, HashValue, Uniform};
+use aptos_secure_storage::{InMemoryStorage, OnDiskStorage, Storage};
+use aptos_types::{
+    block_info::BlockInfo, ledger_info::LedgerInfo, network_address::NetworkAddress,
+    validator_signer::ValidatorSigner,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{
+    borrow::Cow,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    thread,
+};
+
+// Test value for network_timeout, in milliseconds.
+const NETWORK_TIMEOUT_MS: u64 = 5_000;
+
+#[test]
+fn test() {
+    suite::run_test_suite(&safety_rules());
+}
+
+fn safety_rules() -> suite::Callback {
+    Box::new(move || {
+        let signer = ValidatorSigner::from_int(0);
+        let client_private_key = create_noise_key(1);
+        let (server_address, server_public_key) =
+            start_signer_service(&signer, vec![client_private_key.public_key()]);
+
+        // The consensus key is only held by the signer service
+        let waypoint = test_utils::validator_signers_to_waypoint(&[&signer]);
+        let storage = PersistentSafetyStorage::initialize_without_consensus_key(
+            Storage::from(InMemoryStorage::new()),
+            signer.author(),
+            waypoint,
+            true,
+        );
+        let remote_signer = RemoteSigner::new(
+            server_address,
+            server_public_key,
+            client_private_key,
+            NETWORK_TIMEOUT_MS,
+        );
+        let safety_rules = SafetyRules::new_with_remote_signer(storage, remote_signer);
+        let safety_rules_manager = SafetyRulesManager::new_local_with_safety_rules(safety_rules);
+        (safety_rules_manager.client(), signer)
+    })
+}
+
+/// Starts a signer service that trusts the given client keys, and returns the
+/// address and (noise) public key of the service
+fn start_signer_service(
+    signer: &ValidatorSigner,
+    trusted_client_keys: Vec<x25519::PublicKey>,
+) -> (NetworkAddress, x25519::PublicKey) {
+    let listen_port = utils::get_available_port();
+    let listen_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), listen_port);
+    let server_private_key = create_noise_key(2);
+    let server_public_key = server_private_key.public_key();
+
+    let signer_service = create_signer_service(signer, Storage::from(InMemoryStorage::new()));
+    thread::spawn(move || {
+        signer_service::execute(
+            signer_service,
+            listen_addr,
+            server_private_key,
+            trusted_client_keys,
+            NETWORK_TIMEOUT_MS,
+        )
+    });
+    (NetworkAddress::from(listen_addr), server_public_key)
+}
+
+#[test]
+fn test_public_key() {
+    let signer = ValidatorSigner::from_int(0);
+    let client_private_key = create_noise_key(3);
+    let (server_address, server_public_key) =
+        start_signer_service(&signer, vec![client_private_key.public_key()]);
+    let remote_signer = RemoteSigner::new(
+        server_address,
+        server_public_key,
+        client_private_key,
+        NETWORK_TIMEOUT_MS,
+    );
+    assert_eq!(remote_signer.public_key().unwrap(), signer.public_key());
+}
+
+#[test]
+fn test_reject_untrusted_client() {
+    let signer = ValidatorSigner::from_int(0);
+    let trusted_private_key = create_noise_key(4);
+    let (server_address, server_public_key) =
+        start_signer_service(&signer, vec![trusted_private_key.public_key()]);
+
+    // A client with an untrusted key is refused
+    let untrusted_signer = RemoteSigner::new(
+        server_address.clone(),
+        server_public_key,
+        create_noise_key(5),
+        NETWORK_TIMEOUT_MS,
+    );
+    assert!(matches!(
+        untrusted_signer.public_key(),
+        Err(Error::RemoteSignerError(_))
+    ));
+
+    // A client that expects a different signer key can't complete the handshake
+    let impersonated_signer = RemoteSigner::new(
+        server_address.clone(),
+        create_noise_key(6).public_key(),
+        create_noise_key(4), // The trusted client key
+        NETWORK_TIMEOUT_MS,
+    );
+    assert!(matches!(
+        impersonated_signer.public_key(),
+        Err(Error::RemoteSignerError(_))
+    ));
+
+    // The trusted client is still served
+    let remote_signer = RemoteSigner::new(
+        server_address,
+        server_public_key,
+        trusted_private_key,
+        NETWORK_TIMEOUT_MS,
+    );
+    assert_eq!(remote_signer.public_key().unwrap(), signer.public_key());
+}
+
+#[test]
+fn test_refuse_conflicting_votes() {
+    let signer = ValidatorSigner::from_int(0);
+    let mut signer_service = create_signer_service(&signer, Storage::from(InMemoryStorage::new()));
+
+    // Sign a vote, and verify that the identical vote can be signed again
+    let vote_data = create_vote_data(1, 2, HashValue::random());
+    let signature = sign(&mut signer_service, create_vote(&vote_data)).unwrap();
+    assert_eq!(
+        sign(&mut signer_service, create_vote(&vote_data)).unwrap(),
+        signature
+    );
+
+    // Verify that a conflicting vote (and a vote for a lower round) are refused
+    let conflicting_vote_data = create_vote_data(1, 2, HashValue::random());
+    assert!(matches!(
+        sign(&mut signer_service, create_vote(&conflicting_vote_data)),
+        Err(Error::SignerRefused(_))
+    ));
+    let old_vote_data = create_vote_data(1, 1, HashValue::random());
+    assert!(matches!(
+        sign(&mut signer_service, create_vote(&old_vote_data)),
+        Err(Error::SignerRefused(_))
+    ));
+
+    // Verify that a vote for a higher round (or epoch) can be signed
+    sign(
+        &mut signer_service,
+        create_vote(&create_vote_data(1, 3, HashValue::random())),
+    )
+    .unwrap();
+    sign(
+        &mut signer_service,
+        create_vote(&create_vote_data(2, 1, HashValue::random())),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_refuse_vote_after_timeout() {
+    let signer = ValidatorSigner::from_int(0);
+    let mut signer_service = create_signer_service(&signer, Storage::from(InMemoryStorage::new()));
+
+    // Sign a timeout for round 3, and verify that votes for round 3 are refused
+    sign(&mut signer_service, create_timeout(1, 3)).unwrap();
+    let vote_data = create_vote_data(1, 3, HashValue::random());
+    assert!(matches!(
+        sign(&mut signer_service, create_vote(&vote_data)),
+        Err(Error::SignerRefused(_))
+    ));
+
+    // Verify that timeouts can't go backwards, but votes for higher rounds can be signed
+    assert!(matches!(
+        sign(&mut signer_service, create_timeout(1, 2)),
+        Err(Error::SignerRefused(_))
+    ));
+    let vote_data = create_vote_data(1, 4, HashValue::random());
+    sign(&mut signer_service, create_vote(&vote_data)).unwrap();
+    sign(&mut signer_service, create_timeout(1, 4)).unwrap();
+}
+
+#[test]
+fn test_refuse_conflicting_order_and_commit_votes() {
+    let signer = ValidatorSigner::from_int(0);
+    let mut signer_service = create_signer_service(&signer, Storage::from(InMemoryStorage::new()));
+
+    for create_message in [create_order_vote, create_commit_vote] {
+        // Sign votes for rounds 2 and 4, and verify that the last one can be signed again
+        sign(
+            &mut signer_service,
+            create_message(1, 2, HashValue::random()),
+        )
+        .unwrap();
+        let block_id = HashValue::random();
+        let signature = sign(&mut signer_service, create_message(1, 4, block_id)).unwrap();
+        assert_eq!(
+            sign(&mut signer_service, create_message(1, 4, block_id)).unwrap(),
+            signature
+        );
+
+        // Verify that conflicting votes below (and at) the watermark are refused
+        for round in [2, 3, 4] {
+            assert!(matches!(
+                sign(
+                    &mut signer_service,
+                    create_message(1, round, HashValue::random())
+                ),
+                Err(Error::SignerRefused(_))
+            ));
+        }
+
+        // Verify that a vote for a higher round can be signed
+        sign(
+            &mut signer_service,
+            create_message(1, 5, HashValue::random()),
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn test_refuse_inconsistent_vote() {
+    let signer = ValidatorSigner::from_int(0);
+    let mut signer_service = create_signer_service(&signer, Storage::from(InMemoryStorage::new()));
+
+    // The ledger info does not commit to the vote data
+    let vote_data = create_vote_data(1, 2, HashValue::random());
+    let ledger_info = LedgerInfo::new(BlockInfo::empty(), HashValue::random());
+    let vote = SignableMessage::Vote {
+        vote_data: Cow::Borrowed(&vote_data),
+        ledger_info: Cow::Borrowed(&ledger_info),
+    };
+    assert!(matches!(
+        sign(&mut signer_service, vote),
+        Err(Error::InvalidSigningRequest(_))
+    ));
+}
+
+#[test]
+fn test_watermarks_persist_across_restarts() {
+    let signer = ValidatorSigner::from_int(0);
+    let storage_dir = tempfile::tempdir().unwrap();
+    let storage_path = storage_dir.path().join("signer_storage.json");
+
+    // Sign a vote, and restart the signer service
+    let vote_data = create_vote_data(1, 2, HashValue::random());
+    let mut signer_service = create_signer_service(
+        &signer,
+        Storage::from(OnDiskStorage::new(storage_path.clone())),
+    );
+    sign(&mut signer_service, create_vote(&vote_data)).unwrap();
+    let watermarks = signer_service.watermarks().clone();
+    drop(signer_service);
+
+    // Verify that the watermarks were restored, and a conflicting vote is refused
+    let mut signer_service =
+        create_signer_service(&signer, Storage::from(OnDiskStorage::new(storage_path)));
+    assert_eq!(signer_service.watermarks(), &watermarks);
+    let conflicting_vote_data = create_vote_data(1, 2, HashValue::random());
+    assert!(matches!(
+        sign(&mut signer_service, create_vote(&conflicting_vote_data)),
+        Err(Error::SignerRefused(_))
+    ));
+}
+
+/// Creates a (noise) key from the given seed (distinct seeds create distinct keys)
+fn create_noise_key(seed: u8) -> x25519::PrivateKey {
+    x25519::PrivateKey::generate(&mut StdRng::from_seed([seed; 32]))
+}
+
+fn create_signer_service(signer: &ValidatorSigner, storage: Storage) -> SignerService {
+    let consensus_key = InMemoryConsensusKey::new(signer.private_key().clone());
+    SignerService::new(Box::new(consensus_key), storage).unwrap()
+}
+
+fn create_vote_data(epoch: u64, round: u64, block_id: HashValue) -> VoteData {
+    let proposed = BlockInfo::new(epoch, round, block_id, HashValue::zero(), 0, 0, None);
+    let parent = BlockInfo::new(
+        epoch,
+        round - 1,
+        HashValue::zero(),
+        HashValue::zero(),
+        0,
+        0,
+        None,
+    );
+    VoteData::new(proposed, parent)
+}
+
+fn create_vote(vote_data: &VoteData) -> SignableMessage<'static> {
+    let ledger_info = LedgerInfo::new(BlockInfo::empty(), vote_data.hash());
+    SignableMessage::Vote {
+        vote_data: Cow::Owned(vote_data.clone()),
+        ledger_info: Cow::Owned(ledger_info),
+    }
+}
+
+fn create_timeout(epoch: u64, round: u64) -> SignableMessage<'static> {
+    SignableMessage::Timeout(TimeoutSigningRepr {
+        epoch,
+        round,
+        hqc_round: round - 1,
+    })
+}
+
+fn create_ledger_info(epoch: u64, round: u64, block_id: HashValue) -> LedgerInfo {
+    let commit_info = BlockInfo::new(epoch, round, block_id, HashValue::zero(), 0, 0, None);
+    LedgerInfo::new(commit_info, HashValue::zero())
+}
+
+fn create_order_vote(epoch: u64, round: u64, block_id: HashValue) -> SignableMessage<'static> {
+    SignableMessage::OrderVote(Cow::Owned(create_ledger_info(epoch, round, block_id)))
+}
+
+fn create_commit_vote(epoch: u64, round: u64, block_id: HashValue) -> SignableMessage<'static> {
+    SignableMessage::CommitVote(Cow::Owned(create_ledger_info(epoch, round, block_id)))
+}
+
+/// Sends a signing request to the signer service (serializing the request and response)
+fn sign(
+    signer_service: &mut SignerService,
+    message: SignableMessage,
+) -> Result<aptos_crypto::bls12381::Signature, Error> {
+    let request = serde_json::to_vec(&SignerRequest::Sign(message)).unwrap();
+    let response = signer_service.handle_message(request).unwrap();
+    let response: Result<SignerResponse, Error> = serde_json::from_slice(&response).unwrap();
+    match response? {
+        SignerResponse::Signature(signature) => Ok(signature),
+        response => panic!("Unexpected response: {:?}", response),
+    }
+}
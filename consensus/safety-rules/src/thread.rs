@@ -9,8 +9,8 @@
 //! in testing correctness of the communication layer between Consensus and SafetyRules.
 
 use crate::{
-    persistent_safety_storage::PersistentSafetyStorage,
     remote_service::{self, RemoteService},
+    SafetyRules,
 };
 use aptos_config::utils;
 use std::{
@@ -27,12 +27,13 @@ pub struct ThreadService {
 }
 
 impl ThreadService {
-    pub fn new(storage: PersistentSafetyStorage, timeout: u64) -> Self {
+    pub fn new(safety_rules: SafetyRules, timeout: u64) -> Self {
         let listen_port = utils::get_available_port();
         let listen_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), listen_port);
         let server_addr = listen_addr;
 
-        let child = thread::spawn(move || remote_service::execute(storage, listen_addr, timeout));
+        let child =
+            thread::spawn(move || remote_service::execute(safety_rules, listen_addr, timeout));
 
         Self {
             _child: child,
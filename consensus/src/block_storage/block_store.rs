@@ -482,6 +482,11 @@ impl BlockStore {
     pub fn check_payload(&self, proposal: &Block) -> bool {
         self.payload_manager.check_payload_availability(proposal)
     }
+
+    /// Returns the ids of all the blocks that descend from the commit root, sorted by round
+    pub fn get_block_ids_from_commit_root(&self) -> Vec<HashValue> {
+        self.inner.read().get_block_ids_from_commit_root()
+    }
 }
 
 impl BlockReader for BlockStore {
@@ -188,6 +188,25 @@ impl BlockTree {
             .expect("Commit root must exist")
     }
 
+    /// Returns the ids of all the blocks that descend from the commit root (excluding
+    /// the root itself), sorted by round, so that parents always precede their children.
+    pub(super) fn get_block_ids_from_commit_root(&self) -> Vec<HashValue> {
+        let mut rounds_and_ids = vec![];
+        let mut block_ids: VecDeque<HashValue> =
+            self.linkable_root().children().iter().copied().collect();
+        while let Some(block_id) = block_ids.pop_front() {
+            if let Some(linkable_block) = self.get_linkable_block(&block_id) {
+                block_ids.extend(linkable_block.children().iter().copied());
+                rounds_and_ids.push((linkable_block.executed_block().round(), block_id));
+            }
+        }
+        rounds_and_ids.sort();
+        rounds_and_ids
+            .into_iter()
+            .map(|(_, block_id)| block_id)
+            .collect()
+    }
+
     pub(super) fn highest_certified_block(&self) -> Arc<PipelinedBlock> {
         self.get_block(&self.highest_certified_block_id)
             .expect("Highest cerfified block must exist")
@@ -18,6 +18,9 @@ pub enum Error {
     #[error("Subscription disconnected: {0}")]
     SubscriptionDisconnected(String),
 
+    #[error("Subscription lagging: {0}")]
+    SubscriptionLagging(String),
+
     #[error("Subscription progress stopped: {0}")]
     SubscriptionProgressStopped(String),
 
@@ -39,6 +42,7 @@ impl Error {
             Self::NetworkError(_) => "network_error",
             Self::RpcError(_) => "rpc_error",
             Self::SubscriptionDisconnected(_) => "subscription_disconnected",
+            Self::SubscriptionLagging(_) => "subscription_lagging",
             Self::SubscriptionProgressStopped(_) => "subscription_progress_stopped",
             Self::SubscriptionSuboptimal(_) => "subscription_suboptimal",
             Self::SubscriptionTimeout(_) => "subscription_timeout",
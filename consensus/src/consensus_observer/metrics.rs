@@ -5,8 +5,8 @@
 
 use aptos_config::network_id::{NetworkId, PeerNetworkId};
 use aptos_metrics_core::{
-    register_histogram_vec, register_int_counter_vec, register_int_gauge_vec, HistogramVec,
-    IntCounterVec, IntGaugeVec,
+    register_gauge_vec, register_histogram_vec, register_int_counter_vec, register_int_gauge_vec,
+    GaugeVec, HistogramVec, IntCounterVec, IntGaugeVec,
 };
 use once_cell::sync::Lazy;
 
@@ -14,6 +14,8 @@ use once_cell::sync::Lazy;
 pub const BLOCK_PAYLOAD_LABEL: &str = "block_payload";
 pub const COMMIT_DECISION_LABEL: &str = "commit_decision";
 pub const CREATED_SUBSCRIPTION_LABEL: &str = "created_subscription";
+pub const DUPLICATE_MESSAGE_LABEL: &str = "duplicate_message";
+pub const INVALID_MESSAGE_LABEL: &str = "invalid_message";
 pub const ORDERED_BLOCK_ENTRIES_LABEL: &str = "ordered_block_entries";
 pub const ORDERED_BLOCKS_LABEL: &str = "ordered_blocks";
 pub const PENDING_BLOCK_ENTRIES_LABEL: &str = "pending_block_entries";
@@ -30,6 +32,16 @@ pub static OBSERVER_CREATED_SUBSCRIPTIONS: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter for tracking dropped (e.g., duplicate or invalid) messages by the consensus observer
+pub static OBSERVER_DROPPED_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_dropped_messages",
+        "Counters related to dropped (e.g., duplicate or invalid) messages by the consensus observer",
+        &["drop_reason", "network_id"]
+    )
+    .unwrap()
+});
+
 /// Gauge for tracking the number of active subscriptions for the consensus observer
 pub static OBSERVER_NUM_ACTIVE_SUBSCRIPTIONS: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
@@ -120,6 +132,16 @@ pub static OBSERVER_SENT_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Gauge for tracking the quality scores of the active subscriptions (per publisher)
+pub static OBSERVER_SUBSCRIPTION_QUALITY_SCORES: Lazy<GaugeVec> = Lazy::new(|| {
+    register_gauge_vec!(
+        "consensus_observer_subscription_quality_scores",
+        "Gauge for tracking the quality scores of the active subscriptions (per publisher)",
+        &["peer_id"]
+    )
+    .unwrap()
+});
+
 /// Counter for tracking terminated subscriptions for the consensus observer
 pub static OBSERVER_TERMINATED_SUBSCRIPTIONS: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
@@ -210,6 +232,18 @@ pub fn set_gauge(counter: &Lazy<IntGaugeVec>, network_id: &NetworkId, value: i64
     counter.with_label_values(&[network_id.as_str()]).set(value);
 }
 
+/// Sets the gauge for the specific peer (identified by peer ID) and value
+pub fn set_gauge_for_peer(gauge: &Lazy<GaugeVec>, peer_network_id: &PeerNetworkId, value: f64) {
+    let peer_id = peer_network_id.peer_id().to_string();
+    gauge.with_label_values(&[peer_id.as_str()]).set(value);
+}
+
+/// Removes the gauge for the specific peer (identified by peer ID)
+pub fn remove_gauge_for_peer(gauge: &Lazy<GaugeVec>, peer_network_id: &PeerNetworkId) {
+    let peer_id = peer_network_id.peer_id().to_string();
+    let _ = gauge.remove_label_values(&[peer_id.as_str()]);
+}
+
 /// Sets the gauge with the specific label and value
 pub fn set_gauge_with_label(counter: &Lazy<IntGaugeVec>, label: &str, value: u64) {
     counter.with_label_values(&[label]).set(value as i64);
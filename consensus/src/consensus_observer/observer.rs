@@ -28,7 +28,10 @@ use crate::{
     state_replication::StateComputerCommitCallBackType,
 };
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
-use aptos_config::{config::NodeConfig, network_id::PeerNetworkId};
+use aptos_config::{
+    config::NodeConfig,
+    network_id::{NetworkId, PeerNetworkId},
+};
 use aptos_consensus_types::{pipeline, pipelined_block::PipelinedBlock};
 use aptos_crypto::{bls12381, Genesis};
 use aptos_event_notifications::{DbBackedOnChainConfig, ReconfigNotificationListener};
@@ -57,13 +60,20 @@ use futures::{
 };
 use futures_channel::oneshot;
 use move_core_types::account_address::AccountAddress;
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
 use tokio::{sync::mpsc::UnboundedSender, time::interval};
 use tokio_stream::wrappers::IntervalStream;
 
 // Whether to log messages at the info level (useful for debugging)
 const LOG_MESSAGES_AT_INFO_LEVEL: bool = true;
 
+/// The response to a subscription request sent to a peer
+pub type SubscriptionResponse = (PeerNetworkId, Result<ConsensusObserverResponse, Error>);
+
 /// The consensus observer receives consensus updates and propagates them to the execution pipeline
 pub struct ConsensusObserver {
     // The configuration of the node
@@ -98,8 +108,15 @@ pub struct ConsensusObserver {
 
     // The consensus publisher to forward payload messages
     consensus_publisher: Option<Arc<ConsensusPublisher>>,
-    // The currently active consensus observer subscription
-    active_observer_subscription: Option<ConsensusObserverSubscription>,
+    // The currently active consensus observer subscriptions (indexed by publisher)
+    active_observer_subscriptions: HashMap<PeerNetworkId, ConsensusObserverSubscription>,
+    // The publishers with in-flight subscription requests
+    pending_subscription_requests: HashSet<PeerNetworkId>,
+    // The sender to notify the consensus observer of subscription responses
+    subscription_response_sender: UnboundedSender<SubscriptionResponse>,
+    // The quality scores of terminated subscriptions (indexed by publisher).
+    // These are used to deprioritize poor publishers when subscribing.
+    publisher_quality_scores: HashMap<PeerNetworkId, f64>,
     // A handle to storage (used to read the latest state and check progress)
     db_reader: Arc<dyn DbReader>,
     // The time service (used to check progress)
@@ -115,6 +132,7 @@ impl ConsensusObserver {
         db_reader: Arc<dyn DbReader>,
         execution_client: Arc<dyn TExecutionClient>,
         sync_notification_sender: UnboundedSender<(u64, Round)>,
+        subscription_response_sender: UnboundedSender<SubscriptionResponse>,
         reconfig_events: Option<ReconfigNotificationListener<DbBackedOnChainConfig>>,
         consensus_publisher: Option<Arc<ConsensusPublisher>>,
         time_service: TimeService,
@@ -142,7 +160,10 @@ impl ConsensusObserver {
             sync_notification_sender,
             reconfig_events,
             consensus_publisher,
-            active_observer_subscription: None,
+            active_observer_subscriptions: HashMap::new(),
+            pending_subscription_requests: HashSet::new(),
+            subscription_response_sender,
+            publisher_quality_scores: HashMap::new(),
             db_reader,
             time_service,
         }
@@ -175,88 +196,87 @@ impl ConsensusObserver {
             return;
         }
 
-        // Get the peer ID of the currently active subscription (if any)
-        let active_subscription_peer = self
-            .active_observer_subscription
-            .as_ref()
-            .map(|subscription| subscription.get_peer_network_id());
-
-        // If we have an active subscription, verify that the subscription
-        // is still healthy. If not, the subscription should be terminated.
-        if let Some(active_subscription_peer) = active_subscription_peer {
-            if let Err(error) = self.check_active_subscription() {
-                // Log the subscription termination
-                warn!(
-                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                        "Terminating subscription to peer: {:?}! Error: {:?}",
-                        active_subscription_peer, error
-                    ))
-                );
-
-                // Unsubscribe from the peer
-                self.unsubscribe_from_peer(active_subscription_peer);
-
-                // Update the subscription termination metrics
-                self.update_subscription_termination_metrics(active_subscription_peer, error);
-            }
+        // Verify that the active subscriptions are still healthy.
+        // Any unhealthy subscriptions will be terminated.
+        let terminated_subscription_peers = self.check_active_subscriptions();
+
+        // If we have fewer (active and requested) subscriptions than the maximum, we
+        // should select new peers to subscribe to. Any terminated subscriptions are excluded.
+        let max_concurrent_subscriptions = self
+            .node_config
+            .consensus_observer
+            .max_concurrent_subscriptions as usize;
+        let num_subscriptions =
+            self.active_observer_subscriptions.len() + self.pending_subscription_requests.len();
+        if num_subscriptions < max_concurrent_subscriptions {
+            self.create_new_observer_subscriptions(
+                max_concurrent_subscriptions - num_subscriptions,
+                terminated_subscription_peers,
+            );
         }
 
-        // If we don't have a subscription, we should select a new peer to
-        // subscribe to. If we had a previous subscription, it should be
-        // excluded from the selection process.
-        if self.active_observer_subscription.is_none() {
-            // Create a new observer subscription
-            self.create_new_observer_subscription(active_subscription_peer)
-                .await;
-
-            // If we successfully created a new subscription, clear the state and update the metrics
-            if let Some(active_subscription) = &self.active_observer_subscription {
-                // Clear the block state
-                self.clear_pending_block_state().await;
+        // Update the subscription quality metrics
+        self.update_subscription_quality_metrics();
+    }
 
-                // Update the subscription creation metrics
-                self.update_subscription_creation_metrics(
-                    active_subscription.get_peer_network_id(),
-                );
+    /// Checks if the active subscriptions are still healthy. Any unhealthy
+    /// subscriptions are terminated, and the peers of the terminated
+    /// subscriptions are returned.
+    fn check_active_subscriptions(&mut self) -> HashSet<PeerNetworkId> {
+        // Get the connected peers and metadata
+        let connected_peers_and_metadata = self.get_connected_peers_and_metadata();
+
+        // Get the highest ordered epoch and round received by any subscription
+        let highest_ordered_epoch_and_round = self
+            .active_observer_subscriptions
+            .values()
+            .map(|subscription| subscription.get_highest_ordered_epoch_and_round())
+            .max()
+            .unwrap_or_default();
+
+        // Identify the unhealthy subscriptions
+        let active_subscription_peers: HashSet<_> =
+            self.active_observer_subscriptions.keys().copied().collect();
+        let mut unhealthy_subscriptions = vec![];
+        for (peer_network_id, subscription) in self.active_observer_subscriptions.iter_mut() {
+            if let Err(error) = check_subscription_health(
+                subscription,
+                connected_peers_and_metadata.as_ref(),
+                &active_subscription_peers,
+                highest_ordered_epoch_and_round,
+            ) {
+                unhealthy_subscriptions.push((*peer_network_id, error));
             }
         }
-    }
 
-    /// Checks if the active subscription is still healthy. If not, an error is returned.
-    fn check_active_subscription(&mut self) -> Result<(), Error> {
-        let active_observer_subscription = self.active_observer_subscription.take();
-        if let Some(mut active_subscription) = active_observer_subscription {
-            // Check if the peer for the subscription is still connected
-            let peer_network_id = active_subscription.get_peer_network_id();
-            let peer_still_connected = self
-                .get_connected_peers_and_metadata()
-                .map_or(false, |peers_and_metadata| {
-                    peers_and_metadata.contains_key(&peer_network_id)
-                });
-
-            // Verify the peer is still connected
-            if !peer_still_connected {
-                return Err(Error::SubscriptionDisconnected(
-                    "The peer is no longer connected!".to_string(),
-                ));
+        // Terminate the unhealthy subscriptions
+        let mut terminated_subscription_peers = HashSet::new();
+        for (peer_network_id, error) in unhealthy_subscriptions {
+            // Log the subscription termination (and remember the quality score)
+            let quality_score = self
+                .active_observer_subscriptions
+                .remove(&peer_network_id)
+                .map(|subscription| subscription.get_quality_score());
+            if let Some(quality_score) = quality_score {
+                self.publisher_quality_scores
+                    .insert(peer_network_id, quality_score);
             }
+            warn!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Terminating subscription to peer: {:?} (quality score: {:?})! Error: {:?}",
+                    peer_network_id, quality_score, error
+                ))
+            );
 
-            // Verify the subscription has not timed out
-            active_subscription.check_subscription_timeout()?;
-
-            // Verify that the DB is continuing to sync and commit new data
-            active_subscription.check_syncing_progress()?;
-
-            // Verify that the subscription peer is optimal
-            if let Some(peers_and_metadata) = self.get_connected_peers_and_metadata() {
-                active_subscription.check_subscription_peer_optimality(peers_and_metadata)?;
-            }
+            // Unsubscribe from the peer
+            self.unsubscribe_from_peer(peer_network_id);
 
-            // The subscription seems healthy, we can keep it
-            self.active_observer_subscription = Some(active_subscription);
+            // Update the subscription termination metrics
+            self.update_subscription_termination_metrics(peer_network_id, error);
+            terminated_subscription_peers.insert(peer_network_id);
         }
 
-        Ok(())
+        terminated_subscription_peers
     }
 
     /// Clears the pending block state (this is useful for changing
@@ -318,15 +338,20 @@ impl ConsensusObserver {
         })
     }
 
-    /// Creates a new observer subscription by sending subscription requests to
-    /// appropriate peers and waiting for a successful response. If `previous_subscription_peer`
-    /// is provided, it will be excluded from the selection process.
-    async fn create_new_observer_subscription(
+    /// Creates new observer subscriptions by sending subscription requests to
+    /// appropriate peers. Peers with active subscriptions or in-flight requests,
+    /// and the `excluded_peers` are excluded from the selection process. The
+    /// requests are sent asynchronously, and the responses are processed by
+    /// `process_subscription_response` (so we never block on the requests).
+    fn create_new_observer_subscriptions(
         &mut self,
-        previous_subscription_peer: Option<PeerNetworkId>,
+        num_subscriptions: usize,
+        mut excluded_peers: HashSet<PeerNetworkId>,
     ) {
-        // Get a set of sorted peers to service our subscription request
-        let sorted_peers = match self.sort_peers_for_subscription(previous_subscription_peer) {
+        // Get a set of sorted peers to service our subscription requests
+        excluded_peers.extend(self.active_observer_subscriptions.keys().copied());
+        excluded_peers.extend(self.pending_subscription_requests.iter().copied());
+        let sorted_peers = match self.sort_peers_for_subscription(&excluded_peers) {
             Some(sorted_peers) => sorted_peers,
             None => {
                 error!(LogSchema::new(LogEntry::ConsensusObserver)
@@ -337,82 +362,51 @@ impl ConsensusObserver {
 
         // Verify that we have potential peers
         if sorted_peers.is_empty() {
-            warn!(LogSchema::new(LogEntry::ConsensusObserver)
-                .message("There are no peers to subscribe to!"));
+            if self.active_observer_subscriptions.is_empty()
+                && self.pending_subscription_requests.is_empty()
+            {
+                warn!(LogSchema::new(LogEntry::ConsensusObserver)
+                    .message("There are no peers to subscribe to!"));
+            }
             return;
         }
 
-        // Go through the sorted peers and attempt to subscribe to a single peer.
-        // The first peer that responds successfully will be the selected peer.
-        for selected_peer in &sorted_peers {
+        // Send the subscription requests to the best peers. If any requests
+        // fail, the next progress check will select the next best peers.
+        let request_timeout_ms = self
+            .node_config
+            .consensus_observer
+            .network_request_timeout_ms;
+        for selected_peer in sorted_peers.into_iter().take(num_subscriptions) {
             info!(
                 LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
                     "Attempting to subscribe to peer: {}!",
                     selected_peer
                 ))
             );
-
-            // Send a subscription request to the peer and wait for the response.
-            // Note: it is fine to block here because we assume only a single active subscription.
-            let subscription_request = ConsensusObserverRequest::Subscribe;
-            let request_timeout_ms = self
-                .node_config
-                .consensus_observer
-                .network_request_timeout_ms;
-            let response = self
-                .consensus_observer_client
-                .send_rpc_request_to_peer(selected_peer, subscription_request, request_timeout_ms)
-                .await;
-
-            // Process the response and update the active subscription
-            match response {
-                Ok(ConsensusObserverResponse::SubscribeAck) => {
-                    info!(
-                        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                            "Successfully subscribed to peer: {}!",
-                            selected_peer
-                        ))
-                    );
-
-                    // Update the active subscription
-                    let subscription = ConsensusObserverSubscription::new(
-                        self.node_config.consensus_observer,
-                        self.db_reader.clone(),
-                        *selected_peer,
-                        self.time_service.clone(),
-                    );
-                    self.active_observer_subscription = Some(subscription);
-
-                    return; // Return after successfully subscribing
-                },
-                Ok(response) => {
-                    // We received an invalid response
-                    warn!(
-                        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                            "Got unexpected response type: {:?}",
-                            response.get_label()
-                        ))
-                    );
-                },
-                Err(error) => {
-                    // We encountered an error while sending the request
+            self.pending_subscription_requests.insert(selected_peer);
+
+            // Send the request and notify the observer of the response
+            let consensus_observer_client = self.consensus_observer_client.clone();
+            let subscription_response_sender = self.subscription_response_sender.clone();
+            tokio::spawn(async move {
+                let response = consensus_observer_client
+                    .send_rpc_request_to_peer(
+                        &selected_peer,
+                        ConsensusObserverRequest::Subscribe,
+                        request_timeout_ms,
+                    )
+                    .await;
+                if let Err(error) = subscription_response_sender.send((selected_peer, response)) {
                     error!(
                         LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                            "Failed to send subscription request to peer: {}! Error: {:?}",
+                            "Failed to send the subscription response for peer: {}! Error: {:?}",
                             selected_peer, error
                         ))
                     );
-                },
-            }
+                }
+            });
         }
-
-        // We failed to connect to any peers
-        warn!(
-            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                "Failed to subscribe to any peers! Num peers attempted: {:?}",
-                sorted_peers.len()
-            ))
-        );
     }
 
     /// Finalizes the ordered block by sending it to the execution pipeline
@@ -486,6 +480,12 @@ impl ConsensusObserver {
         }
     }
 
+    /// Returns the epoch and round of the root ledger info
+    fn get_root_epoch_and_round(&self) -> (u64, Round) {
+        let root = self.root.lock();
+        (root.commit_info().epoch(), root.commit_info().round())
+    }
+
     /// Gets the connected peers and metadata. If an error occurred,
     /// it is logged and None is returned.
     fn get_connected_peers_and_metadata(&self) -> Option<HashMap<PeerNetworkId, PeerMetadata>> {
@@ -507,6 +507,58 @@ impl ConsensusObserver {
         }
     }
 
+    /// Updates the subscription state and metrics for an invalid message from the given peer
+    fn handle_invalid_message(&mut self, peer_network_id: &PeerNetworkId) {
+        if let Some(subscription) = self.active_observer_subscriptions.get_mut(peer_network_id) {
+            subscription.update_invalid_message_stats();
+        }
+        metrics::increment_request_counter(
+            &metrics::OBSERVER_DROPPED_MESSAGES,
+            metrics::INVALID_MESSAGE_LABEL,
+            peer_network_id,
+        );
+    }
+
+    /// Returns true iff the commit decision has already been processed (e.g.,
+    /// because it was received from another publisher, or the block was committed)
+    fn is_duplicate_commit_decision(&self, commit_decision: &CommitDecision) -> bool {
+        // Check if the commit decision is for a block that has already been committed
+        let commit_epoch_and_round = (commit_decision.epoch(), commit_decision.round());
+        if commit_epoch_and_round <= self.get_root_epoch_and_round() {
+            return true;
+        }
+
+        // Check if the commit decision has already been added to the ordered block
+        self.ordered_block_store
+            .get_commit_decision(commit_decision.epoch(), commit_decision.round())
+            .is_some()
+    }
+
+    /// Returns true iff the ordered block has already been processed (e.g., because
+    /// it was received from another publisher, or the block was committed). Blocks
+    /// in the ordered and pending block stores are compared by block id, so a
+    /// different block for the same round is never treated as a duplicate.
+    fn is_duplicate_ordered_block(&self, ordered_block: &OrderedBlock) -> bool {
+        // Check if the ordered block is for a block that has already been committed
+        let last_block = ordered_block.last_block();
+        let last_block_epoch_and_round = (last_block.epoch(), last_block.round());
+        if last_block_epoch_and_round <= self.get_root_epoch_and_round() {
+            return true;
+        }
+
+        // Check if the ordered block is already in the ordered or pending block stores
+        let is_existing_ordered_block = self
+            .ordered_block_store
+            .get_ordered_block(last_block.epoch(), last_block.round())
+            .map_or(false, |existing_block| {
+                existing_block.last_block().id() == last_block.id()
+            });
+        is_existing_ordered_block
+            || self
+                .pending_block_store
+                .existing_pending_block(ordered_block)
+    }
+
     /// Returns true iff we are waiting for state sync to complete an epoch change
     fn in_state_sync_epoch_change(&self) -> bool {
         matches!(self.sync_handle, Some((_, true)))
@@ -517,19 +569,27 @@ impl ConsensusObserver {
         self.sync_handle.is_some()
     }
 
-    /// Orders any ready pending blocks for the given epoch and round
+    /// Orders any ready pending blocks for the given epoch and round. If
+    /// several blocks were received for the round, the ready block with an
+    /// ordered proof that verifies against the current epoch state is chosen.
     async fn order_ready_pending_block(&mut self, block_epoch: u64, block_round: Round) {
+        let epoch_state = self.get_epoch_state();
         if let Some(ordered_block) = self.pending_block_store.remove_ready_block(
             block_epoch,
             block_round,
             &self.block_payload_store,
+            &epoch_state,
         ) {
-            self.process_ordered_block(ordered_block).await;
+            self.process_verified_ordered_block(ordered_block).await;
         }
     }
 
     /// Processes the block payload message
-    async fn process_block_payload_message(&mut self, block_payload: BlockPayload) {
+    async fn process_block_payload_message(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        block_payload: BlockPayload,
+    ) {
         // Get the epoch and round for the block
         let block_epoch = block_payload.block.epoch();
         let block_round = block_payload.block.round();
@@ -541,6 +601,19 @@ impl ConsensusObserver {
             block_round,
         );
 
+        // If the payload has already been received and verified (e.g., from another publisher), drop it
+        if self
+            .block_payload_store
+            .existing_verified_payload(&block_payload)
+        {
+            metrics::increment_request_counter(
+                &metrics::OBSERVER_DROPPED_MESSAGES,
+                metrics::DUPLICATE_MESSAGE_LABEL,
+                &peer_network_id,
+            );
+            return;
+        }
+
         // Verify the block payload digests
         if let Err(error) = block_payload.verify_payload_digests() {
             error!(
@@ -549,6 +622,7 @@ impl ConsensusObserver {
                     block_payload.block, error
                 ))
             );
+            self.handle_invalid_message(&peer_network_id);
             return;
         }
 
@@ -563,6 +637,7 @@ impl ConsensusObserver {
                         block_payload.block, error
                     ))
                 );
+                self.handle_invalid_message(&peer_network_id);
                 return;
             }
 
@@ -585,7 +660,11 @@ impl ConsensusObserver {
     }
 
     /// Processes the commit decision message
-    fn process_commit_decision_message(&mut self, commit_decision: CommitDecision) {
+    fn process_commit_decision_message(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        commit_decision: CommitDecision,
+    ) {
         // Update the metrics for the received commit decision
         metrics::set_gauge_with_label(
             &metrics::OBSERVER_RECEIVED_MESSAGE_ROUNDS,
@@ -593,6 +672,16 @@ impl ConsensusObserver {
             commit_decision.round(),
         );
 
+        // If the commit decision has already been received (e.g., from another publisher), drop it
+        if self.is_duplicate_commit_decision(&commit_decision) {
+            metrics::increment_request_counter(
+                &metrics::OBSERVER_DROPPED_MESSAGES,
+                metrics::DUPLICATE_MESSAGE_LABEL,
+                &peer_network_id,
+            );
+            return;
+        }
+
         // If the commit decision is for the current epoch, verify and process it
         let epoch_state = self.get_epoch_state();
         let commit_decision_epoch = commit_decision.epoch();
@@ -606,6 +695,7 @@ impl ConsensusObserver {
                         error
                     ))
                 );
+                self.handle_invalid_message(&peer_network_id);
                 return;
             }
 
@@ -710,8 +800,10 @@ impl ConsensusObserver {
         peer_network_id: PeerNetworkId,
         message: ConsensusObserverDirectSend,
     ) {
-        // Verify the message is from the peer we've subscribed to
-        if let Some(active_subscription) = &mut self.active_observer_subscription {
+        // Verify the message is from a peer we've subscribed to
+        if let Some(active_subscription) =
+            self.active_observer_subscriptions.get_mut(&peer_network_id)
+        {
             if let Err(error) = active_subscription.verify_message_sender(&peer_network_id) {
                 warn!(
                     LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
@@ -727,7 +819,7 @@ impl ConsensusObserver {
         } else {
             warn!(
                 LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                    "Received message from unexpected peer: {}! No active subscription found!",
+                    "Received message from unexpected peer: {}! No active subscription found for the peer!",
                     peer_network_id
                 ))
             );
@@ -756,7 +848,8 @@ impl ConsensusObserver {
                 log_received_message(log_message);
 
                 // Process the ordered block message
-                self.process_ordered_block_message(ordered_block).await;
+                self.process_ordered_block_message(peer_network_id, ordered_block)
+                    .await;
             },
             ConsensusObserverDirectSend::CommitDecision(commit_decision) => {
                 // Log the received commit decision message
@@ -768,7 +861,7 @@ impl ConsensusObserver {
                 log_received_message(log_message);
 
                 // Process the commit decision message
-                self.process_commit_decision_message(commit_decision);
+                self.process_commit_decision_message(peer_network_id, commit_decision);
             },
             ConsensusObserverDirectSend::BlockPayload(block_payload) => {
                 // Log the received block payload message
@@ -779,7 +872,8 @@ impl ConsensusObserver {
                 log_received_message(log_message);
 
                 // Process the block payload message
-                self.process_block_payload_message(block_payload).await;
+                self.process_block_payload_message(peer_network_id, block_payload)
+                    .await;
             },
         }
 
@@ -788,7 +882,11 @@ impl ConsensusObserver {
     }
 
     /// Processes the ordered block
-    async fn process_ordered_block_message(&mut self, ordered_block: OrderedBlock) {
+    async fn process_ordered_block_message(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        ordered_block: OrderedBlock,
+    ) {
         // Verify the ordered blocks before processing
         if let Err(error) = ordered_block.verify_ordered_blocks() {
             error!(
@@ -798,13 +896,60 @@ impl ConsensusObserver {
                     error
                 ))
             );
+            self.handle_invalid_message(&peer_network_id);
             return;
         };
 
+        // If the ordered block is for the current epoch, verify the ordered proof
+        // before anything else. This ensures that invalid blocks from one publisher
+        // can't displace valid blocks from other publishers (e.g., in the pending
+        // block store), and that the subscription state is only updated for valid blocks.
+        let epoch_state = self.get_epoch_state();
+        let block_epoch = ordered_block.proof_block_info().epoch();
+        let block_round = ordered_block.proof_block_info().round();
+        let verified_ordered_proof = block_epoch == epoch_state.epoch;
+        if verified_ordered_proof {
+            if let Err(error) = ordered_block.verify_ordered_proof(&epoch_state) {
+                warn!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Failed to verify ordered proof! Ignoring: {:?}, Error: {:?}",
+                        ordered_block.proof_block_info(),
+                        error
+                    ))
+                );
+                self.handle_invalid_message(&peer_network_id);
+                return;
+            }
+        }
+
+        // If the ordered block has already been received (e.g., from another
+        // publisher), drop it. The peer only gets credit for its progress
+        // (to detect lagging subscriptions), and not for delivering the block.
+        if self.is_duplicate_ordered_block(&ordered_block) {
+            if verified_ordered_proof {
+                self.update_ordered_block_progress(&peer_network_id, block_epoch, block_round);
+            }
+            metrics::increment_request_counter(
+                &metrics::OBSERVER_DROPPED_MESSAGES,
+                metrics::DUPLICATE_MESSAGE_LABEL,
+                &peer_network_id,
+            );
+            return;
+        }
+
+        // Update the subscription state for the new (verified) block
+        if verified_ordered_proof {
+            self.update_ordered_block_stats(&peer_network_id, block_epoch, block_round);
+        }
+
         // If all payloads exist, process the block. Otherwise, store it
         // in the pending block store and wait for the payloads to arrive.
         if self.all_payloads_exist(ordered_block.blocks()) {
-            self.process_ordered_block(ordered_block).await;
+            if verified_ordered_proof {
+                self.process_verified_ordered_block(ordered_block).await;
+            } else {
+                self.process_ordered_block(ordered_block).await;
+            }
         } else {
             self.pending_block_store.insert_pending_block(ordered_block);
         }
@@ -837,6 +982,14 @@ impl ConsensusObserver {
             return;
         };
 
+        // Process the verified ordered block
+        self.process_verified_ordered_block(ordered_block).await;
+    }
+
+    /// Processes the verified ordered block. This assumes the ordered block has
+    /// been sanity checked, that the ordered proof has been verified, and that
+    /// all payloads exist.
+    async fn process_verified_ordered_block(&mut self, ordered_block: OrderedBlock) {
         // Verify the block payloads against the ordered block
         if let Err(error) = self
             .block_payload_store
@@ -873,6 +1026,84 @@ impl ConsensusObserver {
         }
     }
 
+    /// Processes the response to a subscription request sent to the given peer
+    async fn process_subscription_response(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        response: Result<ConsensusObserverResponse, Error>,
+    ) {
+        // Verify that we're still waiting for the response
+        if !self.pending_subscription_requests.remove(&peer_network_id) {
+            warn!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Received an unexpected subscription response from peer: {}!",
+                    peer_network_id
+                ))
+            );
+            return;
+        }
+
+        match response {
+            Ok(ConsensusObserverResponse::SubscribeAck) => {
+                // If we already have enough subscriptions (e.g., if the requests raced
+                // with other subscriptions), unsubscribe from the peer again.
+                let num_active_subscriptions = self.active_observer_subscriptions.len();
+                let max_concurrent_subscriptions =
+                    self.node_config
+                        .consensus_observer
+                        .max_concurrent_subscriptions as usize;
+                if num_active_subscriptions >= max_concurrent_subscriptions {
+                    self.unsubscribe_from_peer(peer_network_id);
+                    return;
+                }
+
+                info!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Successfully subscribed to peer: {}!",
+                        peer_network_id
+                    ))
+                );
+
+                // Add the new subscription
+                let subscription = ConsensusObserverSubscription::new(
+                    self.node_config.consensus_observer,
+                    self.db_reader.clone(),
+                    peer_network_id,
+                    self.time_service.clone(),
+                );
+                self.active_observer_subscriptions
+                    .insert(peer_network_id, subscription);
+
+                // If we previously had no active subscriptions, clear the block state.
+                // Otherwise, the remaining subscriptions let us switch seamlessly.
+                if num_active_subscriptions == 0 {
+                    self.clear_pending_block_state().await;
+                }
+
+                // Update the subscription creation metrics
+                self.update_subscription_creation_metrics(peer_network_id);
+            },
+            Ok(response) => {
+                // We received an invalid response
+                warn!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Got unexpected response type: {:?}",
+                        response.get_label()
+                    ))
+                );
+            },
+            Err(error) => {
+                // We encountered an error while sending the request
+                error!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Failed to send subscription request to peer: {}! Error: {:?}",
+                        peer_network_id, error
+                    ))
+                );
+            },
+        }
+    }
+
     /// Processes a request message
     fn process_request_message(
         &mut self,
@@ -962,20 +1193,23 @@ impl ConsensusObserver {
         }
     }
 
-    /// Produces a list of sorted peers to service our subscription request. Peers
+    /// Produces a list of sorted peers to service our subscription requests. Peers
     /// are prioritized by validator distance and latency.
-    /// Note: if `previous_subscription_peer` is provided, it will be excluded
-    /// from the selection process. Likewise, all peers currently subscribed to us
-    /// will be excluded from the selection process.
+    /// Note: the `excluded_peers` will be excluded from the selection process.
+    /// Likewise, all peers currently subscribed to us will be excluded from
+    /// the selection process.
     fn sort_peers_for_subscription(
         &mut self,
-        previous_subscription_peer: Option<PeerNetworkId>,
+        excluded_peers: &HashSet<PeerNetworkId>,
     ) -> Option<Vec<PeerNetworkId>> {
         if let Some(mut peers_and_metadata) = self.get_connected_peers_and_metadata() {
-            // Remove the previous subscription peer (if provided)
-            if let Some(previous_subscription_peer) = previous_subscription_peer {
-                let _ = peers_and_metadata.remove(&previous_subscription_peer);
-            }
+            // Forget the quality scores of disconnected peers
+            self.publisher_quality_scores
+                .retain(|peer_network_id, _| peers_and_metadata.contains_key(peer_network_id));
+
+            // Remove the excluded peers
+            peers_and_metadata
+                .retain(|peer_network_id, _| !excluded_peers.contains(peer_network_id));
 
             // Remove any peers that are currently subscribed to us
             if let Some(consensus_publisher) = &self.consensus_publisher {
@@ -984,8 +1218,13 @@ impl ConsensusObserver {
                 }
             }
 
-            // Sort the peers by validator distance and latency
+            // Sort the peers by validator distance and latency, and deprioritize
+            // the peers with poor quality scores (from previous subscriptions).
             let sorted_peers = subscription::sort_peers_by_distance_and_latency(peers_and_metadata);
+            let sorted_peers = subscription::deprioritize_poor_quality_peers(
+                sorted_peers,
+                &self.publisher_quality_scores,
+            );
 
             // Return the sorted peers
             Some(sorted_peers)
@@ -1055,14 +1294,54 @@ impl ConsensusObserver {
         self.ordered_block_store.update_ordered_blocks_metrics();
     }
 
-    /// Updates the subscription creation metrics for the given peer
-    fn update_subscription_creation_metrics(&self, peer_network_id: PeerNetworkId) {
-        // Set the number of active subscriptions
+    /// Updates the number of active subscriptions for the given network
+    fn update_num_active_subscriptions_metrics(&self, network_id: NetworkId) {
+        let num_active_subscriptions = self
+            .active_observer_subscriptions
+            .keys()
+            .filter(|peer_network_id| peer_network_id.network_id() == network_id)
+            .count();
         metrics::set_gauge(
             &metrics::OBSERVER_NUM_ACTIVE_SUBSCRIPTIONS,
-            &peer_network_id.network_id(),
-            1,
+            &network_id,
+            num_active_subscriptions as i64,
         );
+    }
+
+    /// Updates the subscription progress for a (verified) duplicate ordered block
+    /// from the given peer
+    fn update_ordered_block_progress(
+        &mut self,
+        peer_network_id: &PeerNetworkId,
+        epoch: u64,
+        round: Round,
+    ) {
+        if let Some(subscription) = self.active_observer_subscriptions.get_mut(peer_network_id) {
+            subscription.update_highest_ordered_epoch_and_round(epoch, round);
+        }
+    }
+
+    /// Updates the subscription state for a new (verified) ordered block from the
+    /// given peer. All other subscriptions missed the block (they didn't deliver it first).
+    fn update_ordered_block_stats(
+        &mut self,
+        peer_network_id: &PeerNetworkId,
+        epoch: u64,
+        round: Round,
+    ) {
+        for (subscription_peer, subscription) in self.active_observer_subscriptions.iter_mut() {
+            if subscription_peer == peer_network_id {
+                subscription.update_ordered_block_stats(epoch, round);
+            } else {
+                subscription.update_missed_ordered_block_stats();
+            }
+        }
+    }
+
+    /// Updates the subscription creation metrics for the given peer
+    fn update_subscription_creation_metrics(&self, peer_network_id: PeerNetworkId) {
+        // Update the number of active subscriptions
+        self.update_num_active_subscriptions_metrics(peer_network_id.network_id());
 
         // Update the number of created subscriptions
         metrics::increment_request_counter(
@@ -1078,11 +1357,13 @@ impl ConsensusObserver {
         peer_network_id: PeerNetworkId,
         error: Error,
     ) {
-        // Reset the number of active subscriptions
-        metrics::set_gauge(
-            &metrics::OBSERVER_NUM_ACTIVE_SUBSCRIPTIONS,
-            &peer_network_id.network_id(),
-            0,
+        // Update the number of active subscriptions
+        self.update_num_active_subscriptions_metrics(peer_network_id.network_id());
+
+        // Remove the quality score of the subscription
+        metrics::remove_gauge_for_peer(
+            &metrics::OBSERVER_SUBSCRIPTION_QUALITY_SCORES,
+            &peer_network_id,
         );
 
         // Update the number of terminated subscriptions
@@ -1093,6 +1374,17 @@ impl ConsensusObserver {
         );
     }
 
+    /// Updates the quality score metrics for the active subscriptions
+    fn update_subscription_quality_metrics(&self) {
+        for (peer_network_id, subscription) in &self.active_observer_subscriptions {
+            metrics::set_gauge_for_peer(
+                &metrics::OBSERVER_SUBSCRIPTION_QUALITY_SCORES,
+                peer_network_id,
+                subscription.get_quality_score(),
+            );
+        }
+    }
+
     /// Waits for a new epoch to start
     async fn wait_for_epoch_start(&mut self) {
         // Extract the epoch state and on-chain configs
@@ -1156,6 +1448,9 @@ impl ConsensusObserver {
         mut self,
         mut network_service_events: ConsensusObserverNetworkEvents,
         mut sync_notification_listener: tokio::sync::mpsc::UnboundedReceiver<(u64, Round)>,
+        mut subscription_response_listener: tokio::sync::mpsc::UnboundedReceiver<
+            SubscriptionResponse,
+        >,
     ) {
         // If the consensus publisher is enabled but the observer is disabled,
         // we should only forward incoming requests to the consensus publisher.
@@ -1209,6 +1504,9 @@ impl ConsensusObserver {
                 Some((epoch, round)) = sync_notification_listener.recv() => {
                     self.process_sync_notification(epoch, round).await;
                 },
+                Some((peer_network_id, response)) = subscription_response_listener.recv() => {
+                    self.process_subscription_response(peer_network_id, response).await;
+                },
                 _ = progress_check_interval.select_next_some() => {
                     self.check_progress().await;
                 }
@@ -1260,6 +1558,47 @@ impl ConsensusObserver {
     }
 }
 
+/// Checks if the given subscription is still healthy. If not, an error is returned.
+fn check_subscription_health(
+    subscription: &mut ConsensusObserverSubscription,
+    connected_peers_and_metadata: Option<&HashMap<PeerNetworkId, PeerMetadata>>,
+    active_subscription_peers: &HashSet<PeerNetworkId>,
+    highest_ordered_epoch_and_round: (u64, Round),
+) -> Result<(), Error> {
+    // Verify the peer is still connected
+    let peer_network_id = subscription.get_peer_network_id();
+    let peer_still_connected = connected_peers_and_metadata.map_or(false, |peers_and_metadata| {
+        peers_and_metadata.contains_key(&peer_network_id)
+    });
+    if !peer_still_connected {
+        return Err(Error::SubscriptionDisconnected(
+            "The peer is no longer connected!".to_string(),
+        ));
+    }
+
+    // Verify the subscription has not timed out
+    subscription.check_subscription_timeout()?;
+
+    // Verify that the DB is continuing to sync and commit new data
+    subscription.check_syncing_progress()?;
+
+    // Verify that the subscription is not lagging behind the other subscriptions
+    subscription.check_subscription_lag(highest_ordered_epoch_and_round)?;
+
+    // Verify that the subscription peer is optimal. Note: the peers of the
+    // other active subscriptions are excluded (they're already subscribed to).
+    if let Some(peers_and_metadata) = connected_peers_and_metadata {
+        let mut peers_and_metadata = peers_and_metadata.clone();
+        peers_and_metadata.retain(|other_peer_network_id, _| {
+            *other_peer_network_id == peer_network_id
+                || !active_subscription_peers.contains(other_peer_network_id)
+        });
+        subscription.check_subscription_peer_optimality(peers_and_metadata)?;
+    }
+
+    Ok(())
+}
+
 /// Checks that the epoch and round match the current root
 fn check_root_epoch_and_round(
     root: Arc<Mutex<LedgerInfoWithSignatures>>,
@@ -52,6 +52,14 @@ impl OrderedBlockStore {
             .map(|(_, (ordered_block, _))| ordered_block.last_block().block_info())
     }
 
+    /// Returns the commit decision for the given epoch and round (if any)
+    pub fn get_commit_decision(&self, epoch: u64, round: Round) -> Option<CommitDecision> {
+        self.ordered_blocks
+            .lock()
+            .get(&(epoch, round))
+            .and_then(|(_, commit_decision)| commit_decision.clone())
+    }
+
     /// Returns the ordered block for the given epoch and round (if any)
     pub fn get_ordered_block(&self, epoch: u64, round: Round) -> Option<OrderedBlock> {
         self.ordered_blocks
@@ -422,8 +430,11 @@ mod test {
         );
 
         // Verify the ordered blocks don't have any commit decisions
-        for (_, (_, commit_decision)) in all_ordered_blocks.iter() {
+        for ((epoch, round), (_, commit_decision)) in all_ordered_blocks.iter() {
             assert!(commit_decision.is_none());
+            assert!(ordered_block_store
+                .get_commit_decision(*epoch, *round)
+                .is_none());
         }
 
         // Create a commit decision for the first ordered block
@@ -568,5 +579,11 @@ mod test {
             commit_decision,
             updated_commit_decision.as_ref().unwrap().clone()
         );
+
+        // Verify the commit decision can also be fetched directly
+        assert_eq!(
+            Some(commit_decision),
+            ordered_block_store.get_commit_decision(block_info.epoch(), block_info.round())
+        );
     }
 }
@@ -54,6 +54,17 @@ impl BlockPayloadStore {
         })
     }
 
+    /// Returns true iff a verified payload already exists for the given block
+    pub fn existing_verified_payload(&self, block_payload: &BlockPayload) -> bool {
+        let epoch_and_round = (block_payload.block.epoch(), block_payload.block.round());
+        match self.block_payloads.lock().get(&epoch_and_round) {
+            Some(BlockPayloadStatus::AvailableAndVerified(existing_payload)) => {
+                existing_payload.block.id() == block_payload.block.id()
+            },
+            _ => false,
+        }
+    }
+
     /// Clears all the payloads from the block payload store
     pub fn clear_all_payloads(&self) {
         self.block_payloads.lock().clear();
@@ -445,13 +456,32 @@ mod test {
         // Verify the number of verified blocks in the block payload store
         check_num_verified_payloads(&block_payload_store, num_blocks_in_store - 1);
 
-        // Insert the same block payload into the block payload store (as verified)
+        // Verify that the unverified payload is not considered an existing verified payload
         let transaction_payload = BlockTransactionPayload::empty();
         let block_payload = BlockPayload::new(verified_blocks[0].block_info(), transaction_payload);
-        block_payload_store.insert_block_payload(block_payload, true);
+        assert!(!block_payload_store.existing_verified_payload(&block_payload));
+
+        // Insert the same block payload into the block payload store (as verified)
+        block_payload_store.insert_block_payload(block_payload.clone(), true);
 
         // Check that the block payload store now contains the requested block payload
         assert!(block_payload_store.all_payloads_exist(&verified_blocks));
+        assert!(block_payload_store.existing_verified_payload(&block_payload));
+
+        // Verify that a payload for a different block (at the same round) is not an existing payload
+        let block_info = verified_blocks[0].block_info();
+        let different_block_info = BlockInfo::new(
+            block_info.epoch(),
+            block_info.round(),
+            HashValue::random(),
+            HashValue::random(),
+            block_info.version(),
+            block_info.timestamp_usecs(),
+            None,
+        );
+        let different_block_payload =
+            BlockPayload::new(different_block_info, BlockTransactionPayload::empty());
+        assert!(!block_payload_store.existing_verified_payload(&different_block_payload));
     }
 
     #[test]
@@ -8,13 +8,14 @@ use crate::consensus_observer::{
     payload_store::BlockPayloadStore,
 };
 use aptos_config::config::ConsensusObserverConfig;
+use aptos_crypto::HashValue;
 use aptos_infallible::Mutex;
 use aptos_logger::{info, warn};
-use aptos_types::block_info::Round;
-use std::{
-    collections::{btree_map::Entry, BTreeMap},
-    sync::Arc,
-};
+use aptos_types::{block_info::Round, epoch_state::EpochState};
+use std::{collections::BTreeMap, sync::Arc};
+
+/// The candidate blocks for a single round, keyed by the id of the last block
+type CandidateBlocks = BTreeMap<HashValue, OrderedBlock>;
 
 /// A simple struct to hold blocks that are waiting for payloads
 #[derive(Clone)]
@@ -23,8 +24,11 @@ pub struct PendingBlockStore {
     consensus_observer_config: ConsensusObserverConfig,
 
     // A map of ordered blocks that are without payloads. The key is the
-    // (epoch, round) of the first block in the ordered block.
-    blocks_without_payloads: Arc<Mutex<BTreeMap<(u64, Round), OrderedBlock>>>,
+    // (epoch, round) of the first block in the ordered block. Each entry holds
+    // the candidate blocks for the round (e.g., as received from different
+    // publishers). Candidates are only chosen between once they are ready,
+    // and their proofs can be verified.
+    blocks_without_payloads: Arc<Mutex<BTreeMap<(u64, Round), CandidateBlocks>>>,
 }
 
 impl PendingBlockStore {
@@ -40,32 +44,59 @@ impl PendingBlockStore {
         self.blocks_without_payloads.lock().clear();
     }
 
-    /// Inserts a block (without payloads) into the store
-    pub fn insert_pending_block(&self, ordered_block: OrderedBlock) {
+    /// Returns true iff the store contains the given ordered block (i.e., a
+    /// candidate for the same epoch and round, with the same last block id)
+    pub fn existing_pending_block(&self, ordered_block: &OrderedBlock) -> bool {
         // Get the epoch and round of the first block
         let first_block = ordered_block.first_block();
         let first_block_epoch_round = (first_block.epoch(), first_block.round());
 
-        // Insert the block into the store using the round of the first block
-        match self
-            .blocks_without_payloads
+        // Check if the block is already in the store
+        self.blocks_without_payloads
             .lock()
+            .get(&first_block_epoch_round)
+            .map_or(false, |candidate_blocks| {
+                candidate_blocks.contains_key(&ordered_block.last_block().id())
+            })
+    }
+
+    /// Inserts a block (without payloads) into the store. Different blocks for
+    /// the same epoch and round are kept as candidates (up to one per concurrent
+    /// subscription), as the block proofs may not be verifiable yet.
+    pub fn insert_pending_block(&self, ordered_block: OrderedBlock) {
+        // Get the epoch and round of the first block
+        let first_block = ordered_block.first_block();
+        let first_block_epoch_round = (first_block.epoch(), first_block.round());
+
+        // Insert the block into the candidates for the round of the first block
+        let mut blocks_without_payloads = self.blocks_without_payloads.lock();
+        let candidate_blocks = blocks_without_payloads
             .entry(first_block_epoch_round)
-        {
-            Entry::Occupied(_) => {
-                // The block is already in the store
-                warn!(
-                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                        "A pending block was already found for the given epoch and round: {:?}",
-                        first_block_epoch_round
-                    ))
-                );
-            },
-            Entry::Vacant(entry) => {
-                // Insert the block into the store
-                entry.insert(ordered_block);
-            },
+            .or_default();
+        let last_block_id = ordered_block.last_block().id();
+        let max_num_candidate_blocks =
+            (self.consensus_observer_config.max_concurrent_subscriptions as usize).max(1);
+        if candidate_blocks.contains_key(&last_block_id) {
+            // The block is already in the store
+            warn!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "The pending block was already found for the given epoch and round: {:?}",
+                    first_block_epoch_round
+                ))
+            );
+        } else if candidate_blocks.len() >= max_num_candidate_blocks {
+            // There are too many candidates for the round
+            warn!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Too many pending blocks were found for the given epoch and round: {:?}. Dropping block: {:?}",
+                    first_block_epoch_round, last_block_id
+                ))
+            );
+        } else {
+            // Insert the block into the store
+            candidate_blocks.insert(last_block_id, ordered_block);
         }
+        drop(blocks_without_payloads);
 
         // Perform garbage collection if the store is too large
         self.garbage_collect_pending_blocks();
@@ -94,12 +125,16 @@ impl PendingBlockStore {
     }
 
     /// Removes and returns the block from the store that is now ready
-    /// to be processed (after the new payload has been received).
+    /// to be processed (after the new payload has been received). If there
+    /// are several candidates for the round, the first ready candidate with
+    /// an ordered proof that verifies against the given epoch state is
+    /// returned, and the other candidates are dropped.
     pub fn remove_ready_block(
         &self,
         received_payload_epoch: u64,
         received_payload_round: Round,
         block_payload_store: &BlockPayloadStore,
+        epoch_state: &EpochState,
     ) -> Option<OrderedBlock> {
         // Calculate the round at which to split the blocks
         let split_round = received_payload_round.saturating_add(1);
@@ -109,19 +144,40 @@ impl PendingBlockStore {
         let mut blocks_at_higher_rounds =
             blocks_without_payloads.split_off(&(received_payload_epoch, split_round));
 
-        // Check if the last block is ready (this should be the only ready block).
-        // Any earlier blocks are considered out-of-date and will be dropped.
+        // Check if a candidate for the last round is ready (this should be the only
+        // ready round). Any earlier blocks are considered out-of-date and will be dropped.
         let mut ready_block = None;
-        if let Some((epoch_and_round, ordered_block)) = blocks_without_payloads.pop_last() {
-            // If all payloads exist for the block, then the block is ready
-            if block_payload_store.all_payloads_exist(ordered_block.blocks()) {
-                ready_block = Some(ordered_block);
-            } else {
-                // Otherwise, check if we're still waiting for higher payloads for the block
-                if ordered_block.last_block().round() > received_payload_round {
-                    blocks_at_higher_rounds.insert(epoch_and_round, ordered_block);
+        if let Some((epoch_and_round, candidate_blocks)) = blocks_without_payloads.pop_last() {
+            let mut waiting_candidate_blocks = BTreeMap::new();
+            for (last_block_id, ordered_block) in candidate_blocks {
+                if block_payload_store.all_payloads_exist(ordered_block.blocks()) {
+                    // If all payloads exist for the block, then the block is ready
+                    // (but only if the ordered proof verifies).
+                    if ready_block.is_some() {
+                        continue;
+                    }
+                    match ordered_block.verify_ordered_proof(epoch_state) {
+                        Ok(()) => ready_block = Some(ordered_block),
+                        Err(error) => {
+                            warn!(
+                                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                                    "Failed to verify ordered proof of pending block! Dropping: {:?}, Error: {:?}",
+                                    ordered_block.proof_block_info(),
+                                    error
+                                ))
+                            );
+                        },
+                    }
+                } else if ordered_block.last_block().round() > received_payload_round {
+                    // Otherwise, check if we're still waiting for higher payloads for the block
+                    waiting_candidate_blocks.insert(last_block_id, ordered_block);
                 }
             }
+
+            // If no candidate was ready, keep the candidates that are still waiting
+            if ready_block.is_none() && !waiting_candidate_blocks.is_empty() {
+                blocks_at_higher_rounds.insert(epoch_and_round, waiting_candidate_blocks);
+            }
         }
 
         // Check if any out-of-date blocks were dropped
@@ -156,6 +212,7 @@ impl PendingBlockStore {
         // Update the total number of pending blocks
         let num_pending_blocks = blocks_without_payloads
             .values()
+            .flat_map(|candidate_blocks| candidate_blocks.values())
             .map(|block| block.blocks().len() as u64)
             .sum();
         metrics::set_gauge_with_label(
@@ -167,7 +224,12 @@ impl PendingBlockStore {
         // Update the highest round for the pending blocks
         let highest_pending_round = blocks_without_payloads
             .last_key_value()
-            .map(|(_, pending_block)| pending_block.last_block().round())
+            .and_then(|(_, candidate_blocks)| {
+                candidate_blocks
+                    .values()
+                    .map(|pending_block| pending_block.last_block().round())
+                    .max()
+            })
             .unwrap_or(0);
         metrics::set_gauge_with_label(
             &metrics::OBSERVER_PROCESSED_BLOCK_ROUNDS,
@@ -189,9 +251,11 @@ mod test {
     };
     use aptos_crypto::HashValue;
     use aptos_types::{
-        aggregate_signature::AggregateSignature,
+        aggregate_signature::{AggregateSignature, PartialSignatures},
         block_info::BlockInfo,
         ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+        validator_signer::ValidatorSigner,
+        validator_verifier::{ValidatorConsensusInfo, ValidatorVerifier},
     };
     use rand::Rng;
 
@@ -231,6 +295,52 @@ mod test {
         assert!(blocks_without_payloads.is_empty());
     }
 
+    #[test]
+    fn test_existing_pending_block() {
+        // Create a new pending block store
+        let pending_block_store = PendingBlockStore::new(ConsensusObserverConfig::default());
+
+        // Insert several blocks into the store
+        let current_epoch = 0;
+        let starting_round = 0;
+        let pending_blocks = create_and_add_pending_blocks(
+            &pending_block_store,
+            10,
+            current_epoch,
+            starting_round,
+            5,
+        );
+
+        // Verify that all blocks exist in the store
+        for pending_block in &pending_blocks {
+            assert!(pending_block_store.existing_pending_block(pending_block));
+        }
+
+        // Verify that blocks for a different epoch don't exist in the store
+        let other_pending_blocks = create_and_add_pending_blocks(
+            &PendingBlockStore::new(ConsensusObserverConfig::default()),
+            10,
+            current_epoch + 1,
+            starting_round,
+            5,
+        );
+        for pending_block in &other_pending_blocks {
+            assert!(!pending_block_store.existing_pending_block(pending_block));
+        }
+
+        // Verify that different blocks for the same epoch and rounds don't exist in the store
+        let conflicting_pending_blocks = create_and_add_pending_blocks(
+            &PendingBlockStore::new(ConsensusObserverConfig::default()),
+            10,
+            current_epoch,
+            starting_round,
+            5,
+        );
+        for pending_block in &conflicting_pending_blocks {
+            assert!(!pending_block_store.existing_pending_block(pending_block));
+        }
+    }
+
     #[test]
     fn test_insert_pending_block() {
         // Create a new pending block store
@@ -378,6 +488,83 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_remove_ready_block_conflicting_blocks() {
+        // Create a new pending block store
+        let consensus_observer_config = ConsensusObserverConfig::default();
+        let pending_block_store = PendingBlockStore::new(consensus_observer_config);
+
+        // Create two different blocks for the same epoch and round (e.g., as sent by two publishers)
+        let current_epoch = 0;
+        let round = 10;
+        let invalid_block = create_and_add_pending_blocks(
+            &PendingBlockStore::new(consensus_observer_config),
+            1,
+            current_epoch,
+            round,
+            1,
+        )[0]
+        .clone();
+        let valid_block = create_and_add_pending_blocks(
+            &PendingBlockStore::new(consensus_observer_config),
+            1,
+            current_epoch,
+            round,
+            1,
+        )[0]
+        .clone();
+
+        // Create an epoch state (with a single validator) and sign the ordered proof of the valid block
+        let validator_signer = ValidatorSigner::random(None);
+        let validator_consensus_info = ValidatorConsensusInfo::new(
+            validator_signer.author(),
+            validator_signer.public_key(),
+            100,
+        );
+        let validator_verifier = ValidatorVerifier::new(vec![validator_consensus_info]);
+        let epoch_state = EpochState::new(current_epoch, validator_verifier.clone());
+        let ledger_info = valid_block.ordered_proof().ledger_info().clone();
+        let mut partial_signatures = PartialSignatures::empty();
+        partial_signatures.add_signature(
+            validator_signer.author(),
+            validator_signer.sign(&ledger_info).unwrap(),
+        );
+        let aggregate_signature = validator_verifier
+            .aggregate_signatures(&partial_signatures)
+            .unwrap();
+        let valid_block = OrderedBlock::new(
+            valid_block.blocks().clone(),
+            LedgerInfoWithSignatures::new(ledger_info, aggregate_signature),
+        );
+
+        // Insert both blocks into the store and verify they are both kept
+        pending_block_store.insert_pending_block(invalid_block.clone());
+        pending_block_store.insert_pending_block(valid_block.clone());
+        assert!(pending_block_store.existing_pending_block(&invalid_block));
+        assert!(pending_block_store.existing_pending_block(&valid_block));
+        verify_pending_blocks(&pending_block_store, 1, &vec![
+            invalid_block.clone(),
+            valid_block.clone(),
+        ]);
+
+        // Insert the payloads for both blocks
+        let mut block_payload_store = BlockPayloadStore::new(consensus_observer_config);
+        insert_payloads_for_ordered_block(&mut block_payload_store, &invalid_block);
+        insert_payloads_for_ordered_block(&mut block_payload_store, &valid_block);
+
+        // Remove the ready block and verify the valid block is chosen
+        let ready_block = pending_block_store.remove_ready_block(
+            current_epoch,
+            round,
+            &block_payload_store,
+            &epoch_state,
+        );
+        assert_eq!(ready_block, Some(valid_block));
+
+        // Verify that the store is now empty
+        verify_pending_blocks(&pending_block_store, 0, &vec![]);
+    }
+
     #[test]
     fn test_remove_ready_block_multiple_blocks() {
         // Create a new pending block store
@@ -410,6 +597,7 @@ mod test {
             current_epoch,
             payload_round,
             &block_payload_store,
+            &create_epoch_state(current_epoch),
         );
         assert_eq!(ready_block, Some(second_block));
 
@@ -430,6 +618,7 @@ mod test {
             current_epoch,
             payload_round,
             &block_payload_store,
+            &create_epoch_state(current_epoch),
         );
 
         // Verify that the last block was removed
@@ -477,6 +666,7 @@ mod test {
                 current_epoch,
                 payload_round,
                 &block_payload_store,
+                &create_epoch_state(current_epoch),
             );
 
             // If the block is ready, verify that it was removed.
@@ -520,6 +710,7 @@ mod test {
                 current_epoch,
                 payload_round,
                 &block_payload_store,
+                &create_epoch_state(current_epoch),
             );
 
             // The block should not be ready
@@ -569,6 +760,7 @@ mod test {
             current_epoch,
             payload_round,
             &block_payload_store,
+            &create_epoch_state(current_epoch),
         );
         assert_eq!(ready_block, Some(first_block));
 
@@ -589,6 +781,7 @@ mod test {
             current_epoch,
             payload_round,
             &block_payload_store,
+            &create_epoch_state(current_epoch),
         );
         assert_eq!(ready_block, Some(second_block));
 
@@ -609,6 +802,7 @@ mod test {
             current_epoch,
             payload_round,
             &block_payload_store,
+            &create_epoch_state(current_epoch),
         );
 
         // Verify that the last block was removed
@@ -649,6 +843,7 @@ mod test {
             current_epoch,
             third_block_round,
             &block_payload_store,
+            &create_epoch_state(current_epoch),
         );
         assert!(ready_block.is_none());
 
@@ -666,6 +861,7 @@ mod test {
             current_epoch,
             last_block_round,
             &block_payload_store,
+            &create_epoch_state(current_epoch),
         );
         assert!(ready_block.is_none());
 
@@ -673,6 +869,11 @@ mod test {
         verify_pending_blocks(&pending_block_store, 0, &vec![]);
     }
 
+    /// Creates an epoch state (with an empty verifier) for the given epoch
+    fn create_epoch_state(epoch: u64) -> EpochState {
+        EpochState::new(epoch, ValidatorVerifier::new(vec![]))
+    }
+
     /// Creates and adds the specified number of blocks to the pending block store
     fn create_and_add_pending_blocks(
         pending_block_store: &PendingBlockStore,
@@ -764,6 +965,8 @@ mod test {
             assert_eq!(
                 blocks_without_payloads
                     .get(&(first_block.epoch(), first_block.round()))
+                    .unwrap()
+                    .get(&pending_block.last_block().id())
                     .unwrap(),
                 pending_block
             );
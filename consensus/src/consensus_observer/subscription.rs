@@ -10,6 +10,7 @@ use aptos_logger::warn;
 use aptos_network::application::metadata::PeerMetadata;
 use aptos_storage_interface::DbReader;
 use aptos_time_service::{TimeService, TimeServiceTrait};
+use aptos_types::block_info::Round;
 use ordered_float::OrderedFloat;
 use std::{
     collections::{BTreeMap, HashMap},
@@ -20,6 +21,9 @@ use std::{
 // A useful constant for representing the maximum ping latency
 const MAX_PING_LATENCY_SECS: f64 = 10_000.0;
 
+// The quality score of a subscription without any history (see `get_quality_score`)
+const NEUTRAL_QUALITY_SCORE: f64 = 0.5;
+
 /// A single consensus observer subscription
 pub struct ConsensusObserverSubscription {
     // The configuration of the consensus observer
@@ -40,6 +44,18 @@ pub struct ConsensusObserverSubscription {
     // The highest synced version we've seen from storage, along with the time at which it was seen
     highest_synced_version_and_time: (u64, Instant),
 
+    // The highest epoch and round of the ordered blocks received from the peer
+    highest_ordered_epoch_and_round: (u64, Round),
+
+    // The number of ordered blocks received from the peer before any other peer
+    num_new_ordered_blocks: u64,
+
+    // The number of ordered blocks first received from another peer
+    num_missed_ordered_blocks: u64,
+
+    // The number of invalid messages received from the peer
+    num_invalid_messages: u64,
+
     // The time service (used to check the last message receive time)
     time_service: TimeService,
 }
@@ -60,6 +76,10 @@ impl ConsensusObserverSubscription {
             last_message_receive_time: time_now,
             last_peer_optimality_check: time_now,
             highest_synced_version_and_time: (0, time_now),
+            highest_ordered_epoch_and_round: (0, 0),
+            num_new_ordered_blocks: 0,
+            num_missed_ordered_blocks: 0,
+            num_invalid_messages: 0,
             time_service,
         }
     }
@@ -99,6 +119,33 @@ impl ConsensusObserverSubscription {
         Ok(())
     }
 
+    /// Verifies that the subscription is not lagging behind the other active
+    /// subscriptions, i.e., that the highest ordered round received from the
+    /// peer is close to the highest ordered round received from any peer.
+    /// Note: rounds are only comparable within the same epoch.
+    pub fn check_subscription_lag(
+        &self,
+        highest_ordered_epoch_and_round: (u64, Round),
+    ) -> Result<(), Error> {
+        // Calculate the number of rounds the subscription is lagging behind
+        let (highest_epoch, highest_round) = highest_ordered_epoch_and_round;
+        let (peer_epoch, peer_round) = self.highest_ordered_epoch_and_round;
+        if peer_epoch != highest_epoch {
+            return Ok(()); // We can't compare rounds across epochs
+        }
+        let lag_rounds = highest_round.saturating_sub(peer_round);
+
+        // Check if the subscription is lagging too far behind
+        if lag_rounds > self.consensus_observer_config.max_subscription_lag_rounds {
+            return Err(Error::SubscriptionLagging(format!(
+                "Subscription to peer: {} is lagging by {} rounds! Highest round: {}, peer round: {}",
+                self.peer_network_id, lag_rounds, highest_round, peer_round
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Verifies that the subscription has not timed out based
     /// on the last received message time.
     pub fn check_subscription_timeout(&self) -> Result<(), Error> {
@@ -158,11 +205,53 @@ impl ConsensusObserverSubscription {
         Ok(())
     }
 
+    /// Returns the highest epoch and round of the ordered blocks received from the peer
+    pub fn get_highest_ordered_epoch_and_round(&self) -> (u64, Round) {
+        self.highest_ordered_epoch_and_round
+    }
+
     /// Returns the peer network id of the subscription
     pub fn get_peer_network_id(&self) -> PeerNetworkId {
         self.peer_network_id
     }
 
+    /// Returns the quality score of the subscription (between 0 and 1). This
+    /// is the (smoothed) fraction of ordered blocks the peer delivered before
+    /// any other peer, where invalid messages count against the peer.
+    pub fn get_quality_score(&self) -> f64 {
+        let num_messages = self.num_new_ordered_blocks
+            + self.num_missed_ordered_blocks
+            + self.num_invalid_messages;
+        (self.num_new_ordered_blocks as f64 + 1.0) / (num_messages as f64 + 2.0)
+    }
+
+    /// Updates the subscription state for an invalid message received from the peer
+    pub fn update_invalid_message_stats(&mut self) {
+        self.num_invalid_messages += 1;
+    }
+
+    /// Updates the highest epoch and round of the (verified) ordered blocks
+    /// received from the peer. This is also done for duplicate blocks, as
+    /// it only tracks the progress of the peer (e.g., to detect lagging).
+    pub fn update_highest_ordered_epoch_and_round(&mut self, epoch: u64, round: Round) {
+        self.highest_ordered_epoch_and_round =
+            self.highest_ordered_epoch_and_round.max((epoch, round));
+    }
+
+    /// Updates the subscription state for a new (verified) ordered block
+    /// received from the peer, i.e., one that no other peer delivered first.
+    /// Duplicate blocks never update the stats.
+    pub fn update_ordered_block_stats(&mut self, epoch: u64, round: Round) {
+        self.update_highest_ordered_epoch_and_round(epoch, round);
+        self.num_new_ordered_blocks += 1;
+    }
+
+    /// Updates the subscription state for a new (verified) ordered block
+    /// that was first received from another peer
+    pub fn update_missed_ordered_block_stats(&mut self) {
+        self.num_missed_ordered_blocks += 1;
+    }
+
     /// Verifies the given message is from the expected peer
     pub fn verify_message_sender(&mut self, peer_network_id: &PeerNetworkId) -> Result<(), Error> {
         // Verify the message is from the expected peer
@@ -227,6 +316,33 @@ fn get_latency_for_peer(
     latency
 }
 
+/// Moves the peers with poor quality scores (i.e., below the score of a new
+/// subscription) from previous subscriptions to the end of the sorted peers
+/// (best score first). The order of all other peers is preserved.
+pub fn deprioritize_poor_quality_peers(
+    sorted_peers: Vec<PeerNetworkId>,
+    quality_scores: &HashMap<PeerNetworkId, f64>,
+) -> Vec<PeerNetworkId> {
+    let get_quality_score = |peer_network_id: &PeerNetworkId| {
+        quality_scores
+            .get(peer_network_id)
+            .copied()
+            .unwrap_or(NEUTRAL_QUALITY_SCORE)
+    };
+
+    // Split the peers into poor and other peers
+    let (mut peers, mut poor_quality_peers): (Vec<_>, Vec<_>) = sorted_peers
+        .into_iter()
+        .partition(|peer_network_id| get_quality_score(peer_network_id) >= NEUTRAL_QUALITY_SCORE);
+
+    // Sort the poor peers by quality score and append them to the other peers
+    poor_quality_peers.sort_by_key(|peer_network_id| {
+        std::cmp::Reverse(OrderedFloat(get_quality_score(peer_network_id)))
+    });
+    peers.extend(poor_quality_peers);
+    peers
+}
+
 /// Sorts the peers by distance from the validator set and latency.
 /// We prioritize distance over latency as we want to avoid close
 /// but not up-to-date peers. If peers don't have sufficient metadata
@@ -363,6 +479,87 @@ mod test {
         assert_eq!(subscription.last_peer_optimality_check, current_time);
     }
 
+    #[test]
+    fn test_check_subscription_lag() {
+        // Create a new observer subscription
+        let consensus_observer_config = ConsensusObserverConfig::default();
+        let mut subscription = ConsensusObserverSubscription::new(
+            consensus_observer_config,
+            Arc::new(MockDatabaseReader::new()),
+            PeerNetworkId::random(),
+            TimeService::mock(),
+        );
+
+        // Update the highest ordered round received from the peer
+        let epoch = 10;
+        let round = 100;
+        subscription.update_ordered_block_stats(epoch, round);
+        assert_eq!(
+            subscription.get_highest_ordered_epoch_and_round(),
+            (epoch, round)
+        );
+
+        // Verify that older blocks don't decrease the highest ordered round
+        subscription.update_highest_ordered_epoch_and_round(epoch, round - 1);
+        assert_eq!(
+            subscription.get_highest_ordered_epoch_and_round(),
+            (epoch, round)
+        );
+
+        // Verify that the subscription is not lagging (the lag is within the limit)
+        let max_lag_rounds = consensus_observer_config.max_subscription_lag_rounds;
+        assert!(subscription
+            .check_subscription_lag((epoch, round + max_lag_rounds))
+            .is_ok());
+
+        // Verify that the subscription is not lagging (rounds in different epochs are not compared)
+        assert!(subscription
+            .check_subscription_lag((epoch + 1, round + max_lag_rounds + 1))
+            .is_ok());
+
+        // Verify that the subscription is lagging
+        assert!(matches!(
+            subscription.check_subscription_lag((epoch, round + max_lag_rounds + 1)),
+            Err(Error::SubscriptionLagging(_))
+        ));
+    }
+
+    #[test]
+    fn test_get_quality_score() {
+        // Create a new observer subscription
+        let mut subscription = ConsensusObserverSubscription::new(
+            ConsensusObserverConfig::default(),
+            Arc::new(MockDatabaseReader::new()),
+            PeerNetworkId::random(),
+            TimeService::mock(),
+        );
+
+        // Verify that a new subscription has a neutral score
+        assert_eq!(subscription.get_quality_score(), 0.5);
+
+        // Deliver several new blocks and verify the score increases
+        for round in 0..8 {
+            subscription.update_ordered_block_stats(1, round);
+        }
+        assert_eq!(subscription.get_quality_score(), 0.9);
+
+        // Verify that progress updates (e.g., for duplicate blocks) don't change the score
+        for round in 8..14 {
+            subscription.update_highest_ordered_epoch_and_round(1, round);
+        }
+        assert_eq!(subscription.get_quality_score(), 0.9);
+        assert_eq!(subscription.get_highest_ordered_epoch_and_round(), (1, 13));
+
+        // Miss several blocks and send invalid messages, and verify the score decreases
+        for _ in 8..14 {
+            subscription.update_missed_ordered_block_stats();
+        }
+        for _ in 0..4 {
+            subscription.update_invalid_message_stats();
+        }
+        assert_eq!(subscription.get_quality_score(), 0.45);
+    }
+
     #[test]
     fn test_check_subscription_timeout() {
         // Create a new observer subscription
@@ -516,6 +713,41 @@ mod test {
         assert_eq!(subscription.last_message_receive_time, current_time);
     }
 
+    #[test]
+    fn test_deprioritize_poor_quality_peers() {
+        // Create a list of sorted peers
+        let sorted_peers: Vec<_> = (0..6).map(|_| PeerNetworkId::random()).collect();
+
+        // Verify that the order is unchanged without quality scores
+        let quality_scores = HashMap::new();
+        assert_eq!(
+            deprioritize_poor_quality_peers(sorted_peers.clone(), &quality_scores),
+            sorted_peers
+        );
+
+        // Add quality scores for some of the peers
+        let quality_scores = HashMap::from([
+            (sorted_peers[0], 0.2),
+            (sorted_peers[1], 0.9),
+            (sorted_peers[3], 0.4),
+            (sorted_peers[4], NEUTRAL_QUALITY_SCORE),
+        ]);
+
+        // Verify that the poor peers are moved to the end (best first), and
+        // that the order of the remaining peers is preserved.
+        assert_eq!(
+            deprioritize_poor_quality_peers(sorted_peers.clone(), &quality_scores),
+            vec![
+                sorted_peers[1],
+                sorted_peers[2],
+                sorted_peers[4],
+                sorted_peers[5],
+                sorted_peers[3],
+                sorted_peers[0],
+            ]
+        );
+    }
+
     #[test]
     fn test_sort_peers_by_distance_and_latency() {
         // Sort an empty list of peers
@@ -9,12 +9,13 @@ use crate::{
         publisher::ConsensusPublisher,
     },
     counters,
+    dag::DagInspector,
     epoch_manager::EpochManager,
     network::NetworkTask,
     network_interface::{ConsensusMsg, ConsensusNetworkClient},
     persistent_liveness_storage::StorageWriteProxy,
     pipeline::execution_client::{DummyExecutionClient, ExecutionProxyClient, TExecutionClient},
-    quorum_store::quorum_store_db::QuorumStoreDB,
+    quorum_store::{inspection::QuorumStoreInspector, quorum_store_db::QuorumStoreDB},
     rand::rand_gen::storage::db::RandDb,
     state_computer::ExecutionProxy,
     transaction_filter::TransactionFilter,
@@ -52,10 +53,19 @@ pub fn start_consensus(
     reconfig_events: ReconfigNotificationListener<DbBackedOnChainConfig>,
     vtxn_pool: VTxnPoolState,
     consensus_publisher: Option<Arc<ConsensusPublisher>>,
-) -> (Runtime, Arc<StorageWriteProxy>, Arc<QuorumStoreDB>) {
+    rand_storage: Arc<RandDb>,
+) -> (
+    Runtime,
+    Arc<StorageWriteProxy>,
+    Arc<QuorumStoreDB>,
+    Arc<QuorumStoreInspector>,
+    Arc<DagInspector>,
+) {
     let runtime = aptos_runtimes::spawn_named_runtime("consensus".into(), None);
     let storage = Arc::new(StorageWriteProxy::new(node_config, aptos_db.reader.clone()));
     let quorum_store_db = Arc::new(QuorumStoreDB::new(node_config.storage.dir()));
+    let quorum_store_inspector = Arc::new(QuorumStoreInspector::new(quorum_store_db.clone()));
+    let dag_inspector = Arc::new(DagInspector::new(TimeService::real()));
 
     let txn_notifier = Arc::new(MempoolNotifier::new(
         consensus_to_mempool_sender.clone(),
@@ -63,7 +73,7 @@ pub fn start_consensus(
     ));
 
     let execution_proxy = ExecutionProxy::new(
-        Arc::new(BlockExecutor::<AptosVM>::new(aptos_db)),
+        Arc::new(BlockExecutor::<AptosVM>::new(aptos_db.clone())),
         txn_notifier,
         state_sync_notifier,
         runtime.handle(),
@@ -81,7 +91,6 @@ pub fn start_consensus(
         node_config.consensus.num_bounded_executor_tasks as usize,
         runtime.handle().clone(),
     );
-    let rand_storage = Arc::new(RandDb::new(node_config.storage.dir()));
 
     let execution_client = Arc::new(ExecutionProxyClient::new(
         node_config.consensus.clone(),
@@ -93,6 +102,7 @@ pub fn start_consensus(
         rand_storage.clone(),
         node_config.consensus_observer,
         consensus_publisher.clone(),
+        aptos_db.reader.clone(),
     ));
 
     let epoch_mgr = EpochManager::new(
@@ -104,7 +114,10 @@ pub fn start_consensus(
         consensus_to_mempool_sender,
         execution_client,
         storage.clone(),
+        storage.clone(),
         quorum_store_db.clone(),
+        quorum_store_inspector.clone(),
+        dag_inspector.clone(),
         reconfig_events,
         bounded_executor,
         aptos_time_service::TimeService::real(),
@@ -119,7 +132,13 @@ pub fn start_consensus(
     runtime.spawn(epoch_mgr.start(timeout_receiver, network_receiver));
 
     debug!("Consensus started.");
-    (runtime, storage, quorum_store_db)
+    (
+        runtime,
+        storage,
+        quorum_store_db,
+        quorum_store_inspector,
+        dag_inspector,
+    )
 }
 
 /// A helper function to start the consensus observer
@@ -190,6 +209,7 @@ pub fn start_consensus_observer(
             rand_storage.clone(),
             node_config.consensus_observer,
             consensus_publisher.clone(),
+            aptos_db.reader.clone(),
         ));
         execution_proxy_client as Arc<dyn TExecutionClient>
     } else {
@@ -198,19 +218,22 @@ pub fn start_consensus_observer(
 
     // Create the consensus observer
     let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    let (subscription_response_tx, subscription_response_rx) =
+        tokio::sync::mpsc::unbounded_channel();
     let consensus_observer = ConsensusObserver::new(
         node_config.clone(),
         consensus_observer_client,
         aptos_db.reader.clone(),
         execution_client,
         tx,
+        subscription_response_tx,
         reconfig_events,
         consensus_publisher,
         TimeService::real(),
     );
 
     // Start the consensus observer
-    runtime.spawn(consensus_observer.start(observer_network_events, rx));
+    runtime.spawn(consensus_observer.start(observer_network_events, rx, subscription_response_rx));
 
     runtime
 }
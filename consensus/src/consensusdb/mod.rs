@@ -8,7 +8,9 @@ mod schema;
 
 use crate::error::DbError;
 use anyhow::Result;
-use aptos_consensus_types::{block::Block, quorum_cert::QuorumCert};
+use aptos_consensus_types::{
+    block::Block, equivocation_evidence::EquivocationEvidence, quorum_cert::QuorumCert,
+};
 use aptos_crypto::HashValue;
 use aptos_logger::prelude::*;
 use aptos_schemadb::{schema::Schema, Options, SchemaBatch, DB, DEFAULT_COLUMN_FAMILY_NAME};
@@ -16,12 +18,13 @@ use aptos_storage_interface::AptosDbError;
 pub use schema::{
     block::BlockSchema,
     dag::{CertifiedNodeSchema, DagVoteSchema, NodeSchema},
+    equivocation_evidence::EquivocationEvidenceSchema,
     quorum_certificate::QCSchema,
 };
 use schema::{
     single_entry::{SingleEntryKey, SingleEntrySchema},
-    BLOCK_CF_NAME, CERTIFIED_NODE_CF_NAME, DAG_VOTE_CF_NAME, NODE_CF_NAME, QC_CF_NAME,
-    SINGLE_ENTRY_CF_NAME,
+    BLOCK_CF_NAME, CERTIFIED_NODE_CF_NAME, DAG_VOTE_CF_NAME, EQUIVOCATION_EVIDENCE_CF_NAME,
+    NODE_CF_NAME, QC_CF_NAME, SINGLE_ENTRY_CF_NAME,
 };
 use std::{iter::Iterator, path::Path, time::Instant};
 
@@ -59,6 +62,7 @@ impl ConsensusDB {
             CERTIFIED_NODE_CF_NAME,
             DAG_VOTE_CF_NAME,
             "ordered_anchor_id", // deprecated CF
+            EQUIVOCATION_EVIDENCE_CF_NAME,
         ];
 
         let path = db_root_path.as_ref().join(CONSENSUS_DB_NAME);
@@ -106,6 +110,17 @@ impl ConsensusDB {
         ))
     }
 
+    /// Returns all the equivocation evidence collected so far, ordered by (epoch, round)
+    pub fn get_equivocation_evidence(&self) -> Result<Vec<EquivocationEvidence>, DbError> {
+        let mut evidence: Vec<_> = self
+            .get_all::<EquivocationEvidenceSchema>()?
+            .into_iter()
+            .map(|(_, evidence)| evidence)
+            .collect();
+        evidence.sort_by_key(|evidence| (evidence.epoch(), evidence.round()));
+        Ok(evidence)
+    }
+
     pub fn save_highest_2chain_timeout_certificate(&self, tc: Vec<u8>) -> Result<(), DbError> {
         let batch = SchemaBatch::new();
         batch.put::<SingleEntrySchema>(&SingleEntryKey::Highest2ChainTimeoutCert, &tc)?;
@@ -0,0 +1,52 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for the equivocation evidence collected
+//! by consensus (i.e., conflicting votes or proposals signed by the same validator).
+//!
+//! Serialized evidence bytes identified by the evidence id.
+//! ```text
+//! |<---key---->|<--------value-------->|
+//! | evidence_id | EquivocationEvidence |
+//! ```
+
+use crate::define_schema;
+use anyhow::Result;
+use aptos_consensus_types::equivocation_evidence::EquivocationEvidence;
+use aptos_crypto::HashValue;
+use aptos_schemadb::{
+    schema::{KeyCodec, ValueCodec},
+    ColumnFamilyName,
+};
+
+pub const EQUIVOCATION_EVIDENCE_CF_NAME: ColumnFamilyName = "equivocation_evidence";
+
+define_schema!(
+    EquivocationEvidenceSchema,
+    HashValue,
+    EquivocationEvidence,
+    EQUIVOCATION_EVIDENCE_CF_NAME
+);
+
+impl KeyCodec<EquivocationEvidenceSchema> for HashValue {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(self.to_vec())
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        Ok(HashValue::from_slice(data)?)
+    }
+}
+
+impl ValueCodec<EquivocationEvidenceSchema> for EquivocationEvidence {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
+
+#[cfg(test)]
+mod test;
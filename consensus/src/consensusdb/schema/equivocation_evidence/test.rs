@@ -0,0 +1,26 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use aptos_consensus_types::{vote::Vote, vote_data::VoteData};
+use aptos_schemadb::{schema::fuzzing::assert_encode_decode, test_no_panic_decoding};
+use aptos_types::{
+    block_info::BlockInfo, ledger_info::LedgerInfo, validator_signer::ValidatorSigner,
+};
+
+#[test]
+fn test_encode_decode() {
+    let signer = ValidatorSigner::random(None);
+    let create_vote = || {
+        let vote_data = VoteData::new(
+            BlockInfo::new(1, 2, HashValue::random(), HashValue::zero(), 0, 0, None),
+            BlockInfo::random(1),
+        );
+        let ledger_info = LedgerInfo::new(BlockInfo::empty(), HashValue::zero());
+        Vote::new(vote_data, signer.author(), ledger_info, &signer).unwrap()
+    };
+    let evidence = EquivocationEvidence::conflicting_votes(create_vote(), create_vote());
+    assert_encode_decode::<EquivocationEvidenceSchema>(&evidence.id(), &evidence);
+}
+
+test_no_panic_decoding!(EquivocationEvidenceSchema);
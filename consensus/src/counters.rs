@@ -1175,6 +1175,16 @@ pub static PROPOSED_VTXN_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Count of the equivocation evidence collected (i.e., conflicting votes or proposals)
+pub static EQUIVOCATION_EVIDENCE_COLLECTED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_equivocation_evidence_collected",
+        "Number of equivocations detected, for which evidence was collected",
+        &["kind"]
+    )
+    .unwrap()
+});
+
 pub static RAND_QUEUE_SIZE: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge!(
         "aptos_consensus_rand_queue_size",
@@ -11,6 +11,7 @@ use super::{
     dag_state_sync::{DagStateSynchronizer, StateSyncTrigger},
     dag_store::DagStore,
     health::{ChainHealthBackoff, HealthBackoff, PipelineLatencyBasedBackpressure, TChainHealth},
+    inspection::DagInspector,
     order_rule::OrderRule,
     rb_handler::NodeBroadcastHandler,
     storage::{CommitEvent, DAGStorage},
@@ -48,7 +49,7 @@ use aptos_reliable_broadcast::{RBNetworkSender, ReliableBroadcast};
 use aptos_types::{
     epoch_state::EpochState,
     on_chain_config::{
-        AnchorElectionMode, DagConsensusConfigV1,
+        AnchorElectionMode, DagConsensusConfigV1, Features,
         LeaderReputationType::{ProposerAndVoter, ProposerAndVoterV2},
         OnChainJWKConsensusConfig, OnChainRandomnessConfig, ProposerAndVoterConfig,
         ValidatorTxnConfig,
@@ -338,8 +339,10 @@ pub struct DagBootstrapper {
     vtxn_config: ValidatorTxnConfig,
     randomness_config: OnChainRandomnessConfig,
     jwk_consensus_config: OnChainJWKConsensusConfig,
+    features: Features,
     executor: BoundedExecutor,
     allow_batches_without_pos_in_proposal: bool,
+    dag_inspector: Arc<DagInspector>,
 }
 
 impl DagBootstrapper {
@@ -363,8 +366,10 @@ impl DagBootstrapper {
         vtxn_config: ValidatorTxnConfig,
         randomness_config: OnChainRandomnessConfig,
         jwk_consensus_config: OnChainJWKConsensusConfig,
+        features: Features,
         executor: BoundedExecutor,
         allow_batches_without_pos_in_proposal: bool,
+        dag_inspector: Arc<DagInspector>,
     ) -> Self {
         Self {
             self_peer,
@@ -385,8 +390,10 @@ impl DagBootstrapper {
             vtxn_config,
             randomness_config,
             jwk_consensus_config,
+            features,
             executor,
             allow_batches_without_pos_in_proposal,
+            dag_inspector,
         }
     }
 
@@ -605,6 +612,8 @@ impl DagBootstrapper {
                 self.config.fetcher_config.clone(),
             );
         let fetch_requester = Arc::new(fetch_requester);
+        self.dag_inspector
+            .set_dag(dag_store, &dag_fetcher.pending_fetches());
         let (new_round_tx, new_round_rx) = tokio::sync::mpsc::unbounded_channel();
         let round_state = RoundState::new(
             new_round_tx.clone(),
@@ -662,6 +671,7 @@ impl DagBootstrapper {
             self.vtxn_config.clone(),
             self.randomness_config.clone(),
             self.jwk_consensus_config.clone(),
+            self.features.clone(),
             health_backoff,
         );
         let fetch_handler = FetchRequestHandler::new(dag_store.clone(), self.epoch_state.clone());
@@ -758,7 +768,7 @@ pub(super) fn bootstrap_dag_for_test(
         rb_network_sender,
         dag_network_sender,
         proof_notifier.clone(),
-        time_service,
+        time_service.clone(),
         payload_manager,
         payload_client,
         ordered_nodes_tx,
@@ -767,8 +777,10 @@ pub(super) fn bootstrap_dag_for_test(
         ValidatorTxnConfig::default_enabled(),
         OnChainRandomnessConfig::default_enabled(),
         OnChainJWKConsensusConfig::default_enabled(),
+        Features::default(),
         BoundedExecutor::new(2, Handle::current()),
         true,
+        Arc::new(DagInspector::new(time_service)),
     );
 
     let (_base_state, handler, fetch_service) = bootstraper.full_bootstrap();
@@ -13,15 +13,19 @@ use anyhow::{bail, ensure};
 use aptos_bitvec::BitVec;
 use aptos_config::config::DagFetcherConfig;
 use aptos_consensus_types::common::{Author, Round};
+use aptos_infallible::Mutex;
 use aptos_logger::{debug, error, info};
-use aptos_time_service::TimeService;
+use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_types::epoch_state::EpochState;
 use async_trait::async_trait;
 use futures::{future::Shared, stream::FuturesUnordered, Future, FutureExt, Stream, StreamExt};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
     task::{Context, Poll},
     time::Duration,
 };
@@ -130,6 +134,39 @@ impl LocalFetchRequest {
     }
 }
 
+/// A fetch of the missing parents of a node that is in progress
+#[derive(Clone, Debug)]
+pub struct PendingFetch {
+    pub target: NodeMetadata,
+    pub parents: Vec<NodeMetadata>,
+    pub responders: Vec<Author>,
+    pub start_time: Duration,
+}
+
+/// Tracks the fetches in progress, so that stalled fetches can be inspected
+#[derive(Default)]
+pub struct PendingFetches {
+    next_id: AtomicU64,
+    fetches: Mutex<BTreeMap<u64, PendingFetch>>,
+}
+
+impl PendingFetches {
+    fn start(&self, fetch: PendingFetch) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.fetches.lock().insert(id, fetch);
+        id
+    }
+
+    fn finish(&self, id: u64) {
+        self.fetches.lock().remove(&id);
+    }
+
+    /// Returns the fetches in progress, in the order they were started
+    pub fn get_all(&self) -> Vec<PendingFetch> {
+        self.fetches.lock().values().cloned().collect()
+    }
+}
+
 pub struct DagFetcherService {
     inner: Arc<DagFetcher>,
     dag: Arc<DagStore>,
@@ -142,6 +179,7 @@ pub struct DagFetcherService {
     futures:
         FuturesUnordered<Pin<Box<dyn Future<Output = anyhow::Result<LocalFetchRequest>> + Send>>>,
     max_concurrent_fetches: usize,
+    pending_fetches: Arc<PendingFetches>,
 }
 
 impl DagFetcherService {
@@ -170,6 +208,7 @@ impl DagFetcherService {
                 ordered_authors,
                 inflight_requests: HashMap::new(),
                 futures: FuturesUnordered::new(),
+                pending_fetches: Arc::new(PendingFetches::default()),
             },
             FetchRequester {
                 request_tx,
@@ -181,6 +220,10 @@ impl DagFetcherService {
         )
     }
 
+    pub fn pending_fetches(&self) -> Arc<PendingFetches> {
+        self.pending_fetches.clone()
+    }
+
     pub async fn start(mut self) {
         loop {
             select! {
@@ -192,10 +235,21 @@ impl DagFetcherService {
                 },
                 // TODO: Configure concurrency
                 Some(local_request) = self.request_rx.recv(), if self.futures.len() < self.max_concurrent_fetches => {
-                    match self.fetch(local_request.node(), local_request.responders(&self.ordered_authors)) {
+                    let responders = local_request.responders(&self.ordered_authors);
+                    match self.fetch(local_request.node(), responders.clone()) {
                         Ok(fut) => {
+                            let node = local_request.node();
+                            let pending_fetches = self.pending_fetches.clone();
+                            let fetch_id = pending_fetches.start(PendingFetch {
+                                target: node.metadata().clone(),
+                                parents: node.parents_metadata().cloned().collect(),
+                                responders,
+                                start_time: self.inner.time_service.now_unix_time(),
+                            });
                             self.futures.push(async move {
-                                fut.await?;
+                                let result = fut.await;
+                                pending_fetches.finish(fetch_id);
+                                result?;
                                 Ok(local_request)
                             }.boxed())
                         },
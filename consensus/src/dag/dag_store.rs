@@ -20,7 +20,7 @@ use aptos_logger::{debug, error, warn};
 use aptos_types::{epoch_state::EpochState, validator_verifier::ValidatorVerifier};
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
-    ops::Deref,
+    ops::{Deref, RangeInclusive},
     sync::Arc,
 };
 
@@ -56,6 +56,9 @@ pub struct InMemDag {
     epoch_state: Arc<EpochState>,
     /// The window we maintain between highest committed round and initial round
     window_size: u64,
+    /// The authors of the ordered anchors still in the DAG, by round
+    ordered_anchors: BTreeMap<Round, Author>,
+    highest_committed_anchor_round: Option<Round>,
 }
 
 impl InMemDag {
@@ -68,6 +71,8 @@ impl InMemDag {
             start_round,
             epoch_state,
             window_size,
+            ordered_anchors: BTreeMap::new(),
+            highest_committed_anchor_round: None,
         }
     }
 
@@ -408,6 +413,7 @@ impl InMemDag {
     pub(super) fn prune(&mut self) -> BTreeMap<u64, Vec<Option<NodeStatus>>> {
         let to_keep = self.nodes_by_round.split_off(&self.start_round);
         let to_prune = std::mem::replace(&mut self.nodes_by_round, to_keep);
+        self.ordered_anchors = self.ordered_anchors.split_off(&self.start_round);
         debug!(
             "pruning dag. start round {}. pruning from {}",
             self.start_round,
@@ -420,6 +426,8 @@ impl InMemDag {
         &mut self,
         commit_round: Round,
     ) -> Option<BTreeMap<u64, Vec<Option<NodeStatus>>>> {
+        self.highest_committed_anchor_round =
+            self.highest_committed_anchor_round.max(Some(commit_round));
         let new_start_round = commit_round.saturating_sub(3 * self.window_size);
         if new_start_round > self.start_round {
             self.start_round = new_start_round;
@@ -439,6 +447,31 @@ impl InMemDag {
         None
     }
 
+    pub(super) fn epoch_state(&self) -> &Arc<EpochState> {
+        &self.epoch_state
+    }
+
+    pub(super) fn record_ordered_anchor(&mut self, anchor: &NodeMetadata) {
+        self.ordered_anchors
+            .insert(anchor.round(), *anchor.author());
+    }
+
+    pub(super) fn ordered_anchors(&self) -> &BTreeMap<Round, Author> {
+        &self.ordered_anchors
+    }
+
+    pub(super) fn highest_committed_anchor_round(&self) -> Option<Round> {
+        self.highest_committed_anchor_round
+    }
+
+    /// Returns the nodes of the rounds in the given range, indexed by validator
+    pub(super) fn rounds(
+        &self,
+        range: RangeInclusive<Round>,
+    ) -> impl Iterator<Item = (&Round, &Vec<Option<NodeStatus>>)> {
+        self.nodes_by_round.range(range)
+    }
+
     pub fn is_empty(&self) -> bool {
         self.nodes_by_round.is_empty() && self.start_round > 1
     }
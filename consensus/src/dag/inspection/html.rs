@@ -0,0 +1,265 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Renders a DAG snapshot as a self-contained HTML page: rounds are columns, validators are
+//! rows and nodes are linked to their parents. Missing parents and the nodes waiting for
+//! a fetch are highlighted in red.
+
+use crate::dag::inspection::{DagFetchSnapshot, DagNodeRef, DagNodeStatus, DagSnapshot};
+use aptos_consensus_types::common::{Author, Round};
+use std::{collections::HashMap, fmt::Write};
+
+const ROUND_WIDTH: u64 = 90;
+const VALIDATOR_HEIGHT: u64 = 40;
+const MARGIN_LEFT: u64 = 120;
+const MARGIN_TOP: u64 = 40;
+const NODE_RADIUS: u64 = 12;
+
+const MISSING_COLOR: &str = "#d0021b";
+
+struct Layout {
+    from_round: Round,
+    to_round: Round,
+    validator_indices: HashMap<Author, u64>,
+}
+
+impl Layout {
+    fn position(&self, round: Round, author: &Author) -> Option<(u64, u64)> {
+        if round < self.from_round || round > self.to_round {
+            return None;
+        }
+        let index = self.validator_indices.get(author)?;
+        Some((
+            MARGIN_LEFT + (round - self.from_round) * ROUND_WIDTH,
+            MARGIN_TOP + index * VALIDATOR_HEIGHT,
+        ))
+    }
+}
+
+fn short(author: &Author) -> String {
+    author.short_str_lossless().chars().take(8).collect()
+}
+
+fn status_color(status: DagNodeStatus) -> &'static str {
+    match status {
+        DagNodeStatus::Unordered => "#bbbbbb",
+        DagNodeStatus::Ordered => "#4a90d9",
+        DagNodeStatus::Committed => "#2e9e44",
+    }
+}
+
+fn write_edge(svg: &mut String, layout: &Layout, from: (u64, u64), to: &DagNodeRef, missing: bool) {
+    let Some((x, y)) = layout.position(to.round, &to.author) else {
+        return;
+    };
+    let (color, dash) = if missing {
+        (MISSING_COLOR, " stroke-dasharray=\"4 3\"")
+    } else {
+        ("#cccccc", "")
+    };
+    let _ = writeln!(
+        svg,
+        "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\"{}/>",
+        from.0, from.1, x, y, color, dash
+    );
+}
+
+fn write_missing_node(svg: &mut String, layout: &Layout, node: &DagNodeRef, label: &str) {
+    if let Some((x, y)) = layout.position(node.round, &node.author) {
+        let _ = writeln!(
+            svg,
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"white\" stroke=\"{}\" stroke-width=\"2\" \
+             stroke-dasharray=\"4 3\"><title>{} {}@{} {}</title></circle>",
+            x,
+            y,
+            NODE_RADIUS,
+            MISSING_COLOR,
+            label,
+            short(&node.author),
+            node.round,
+            node.digest
+        );
+    }
+}
+
+fn render_svg(snapshot: &DagSnapshot, layout: &Layout) -> String {
+    let num_rounds = snapshot.to_round - snapshot.from_round + 1;
+    let width = MARGIN_LEFT + num_rounds * ROUND_WIDTH;
+    let height = MARGIN_TOP + snapshot.validators.len() as u64 * VALIDATOR_HEIGHT;
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+         font-family=\"monospace\" font-size=\"11\">",
+        width, height
+    );
+
+    // Axes
+    for round in snapshot.from_round..=snapshot.to_round {
+        let x = MARGIN_LEFT + (round - snapshot.from_round) * ROUND_WIDTH;
+        let _ = writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">r{}</text>",
+            x,
+            MARGIN_TOP / 2,
+            round
+        );
+    }
+    for (index, author) in snapshot.validators.iter().enumerate() {
+        let _ = writeln!(
+            svg,
+            "<text x=\"10\" y=\"{}\">{}</text>",
+            MARGIN_TOP + index as u64 * VALIDATOR_HEIGHT + 4,
+            short(author)
+        );
+    }
+
+    // Edges first, so that the nodes are drawn on top of them
+    for node in &snapshot.nodes {
+        if let Some(position) = layout.position(node.round, &node.author) {
+            for parent in &node.parents {
+                write_edge(
+                    &mut svg,
+                    layout,
+                    position,
+                    parent,
+                    node.missing_parents.contains(parent),
+                );
+            }
+        }
+    }
+    for fetch in &snapshot.pending_fetches {
+        if let Some(position) = layout.position(fetch.target.round, &fetch.target.author) {
+            for parent in &fetch.missing_parents {
+                write_edge(&mut svg, layout, position, parent, true);
+            }
+        }
+    }
+
+    for (round, author) in &snapshot.absent_nodes {
+        if let Some((x, y)) = layout.position(*round, author) {
+            let _ = writeln!(
+                svg,
+                "<circle cx=\"{}\" cy=\"{}\" r=\"3\" fill=\"#eeeeee\"/>",
+                x, y
+            );
+        }
+    }
+    for node in &snapshot.nodes {
+        let Some((x, y)) = layout.position(node.round, &node.author) else {
+            continue;
+        };
+        let (stroke, stroke_width) = if node.is_anchor {
+            ("black", 3)
+        } else {
+            ("white", 1)
+        };
+        let _ = writeln!(
+            svg,
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\" stroke=\"{}\" stroke-width=\"{}\">\
+             <title>{}@{} {:?}{}{}\n{}</title></circle>",
+            x,
+            y,
+            NODE_RADIUS,
+            status_color(node.status),
+            stroke,
+            stroke_width,
+            short(&node.author),
+            node.round,
+            node.status,
+            if node.is_anchor { " anchor" } else { "" },
+            node.ordered_by_anchor
+                .map(|round| format!(" (ordered by anchor r{})", round))
+                .unwrap_or_default(),
+            node.digest
+        );
+        for parent in &node.missing_parents {
+            write_missing_node(&mut svg, layout, parent, "missing parent");
+        }
+    }
+    for fetch in &snapshot.pending_fetches {
+        write_missing_node(&mut svg, layout, &fetch.target, "fetching parents of");
+        for parent in &fetch.missing_parents {
+            write_missing_node(&mut svg, layout, parent, "fetching");
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn render_fetches(fetches: &[DagFetchSnapshot]) -> String {
+    if fetches.is_empty() {
+        return "<p>No fetches in progress.</p>\n".to_string();
+    }
+    let mut table = String::from(
+        "<table border=\"1\" cellpadding=\"4\">\n<tr><th>Target</th><th>Missing parents</th>\
+         <th>Responders</th><th>Elapsed (ms)</th></tr>\n",
+    );
+    for fetch in fetches {
+        let missing_parents: Vec<_> = fetch
+            .missing_parents
+            .iter()
+            .map(|parent| format!("{}@{}", short(&parent.author), parent.round))
+            .collect();
+        let responders: Vec<_> = fetch.responders.iter().map(short).collect();
+        let _ = writeln!(
+            table,
+            "<tr><td>{}@{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            short(&fetch.target.author),
+            fetch.target.round,
+            missing_parents.join(", "),
+            responders.join(", "),
+            fetch.elapsed_ms
+        );
+    }
+    table.push_str("</table>\n");
+    table
+}
+
+pub(super) fn render(snapshot: &DagSnapshot) -> String {
+    let layout = Layout {
+        from_round: snapshot.from_round,
+        to_round: snapshot.to_round,
+        validator_indices: snapshot
+            .validators
+            .iter()
+            .enumerate()
+            .map(|(index, author)| (*author, index as u64))
+            .collect(),
+    };
+
+    let mut html = String::from("<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\">");
+    let _ = writeln!(
+        html,
+        "<title>DAG epoch {} rounds {}-{}</title></head>\n<body style=\"font-family: monospace\">",
+        snapshot.epoch, snapshot.from_round, snapshot.to_round
+    );
+    let _ = writeln!(
+        html,
+        "<h3>DAG epoch {}, rounds {}-{} (available {}-{}), highest committed anchor round {}</h3>",
+        snapshot.epoch,
+        snapshot.from_round,
+        snapshot.to_round,
+        snapshot.lowest_round,
+        snapshot.highest_round,
+        snapshot
+            .highest_committed_anchor_round
+            .map_or_else(|| "none".to_string(), |round| round.to_string())
+    );
+    let _ = writeln!(
+        html,
+        "<p>Nodes: <span style=\"color: {}\">unordered</span>, <span style=\"color: {}\">ordered</span>, \
+         <span style=\"color: {}\">committed</span>; anchors have a black border. \
+         <span style=\"color: {}\">Dashed red</span>: missing parents and fetches in progress.</p>",
+        status_color(DagNodeStatus::Unordered),
+        status_color(DagNodeStatus::Ordered),
+        status_color(DagNodeStatus::Committed),
+        MISSING_COLOR
+    );
+    html.push_str(&render_svg(snapshot, &layout));
+    html.push_str("<h3>Fetches in progress</h3>\n");
+    html.push_str(&render_fetches(&snapshot.pending_fetches));
+    html.push_str("</body>\n</html>\n");
+    html
+}
@@ -0,0 +1,364 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::dag::{
+    dag_fetcher::PendingFetches,
+    dag_store::{DagStore, InMemDag, NodeStatus},
+    types::NodeMetadata,
+};
+use anyhow::{bail, Result};
+use aptos_consensus_types::common::{Author, Round};
+use aptos_crypto::HashValue;
+use aptos_infallible::RwLock;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{Arc, Weak},
+};
+
+mod html;
+
+/// The number of rounds exported by default, ending at the highest round of the DAG
+pub const DEFAULT_SNAPSHOT_ROUNDS: u64 = 20;
+/// The maximum number of rounds exported by a snapshot
+pub const MAX_SNAPSHOT_ROUNDS: u64 = 200;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DagNodeStatus {
+    Unordered,
+    Ordered,
+    /// Ordered by an anchor that is committed
+    Committed,
+}
+
+/// Identifies a node in the DAG
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DagNodeRef {
+    pub round: Round,
+    pub author: Author,
+    pub digest: HashValue,
+}
+
+impl From<&NodeMetadata> for DagNodeRef {
+    fn from(metadata: &NodeMetadata) -> Self {
+        Self {
+            round: metadata.round(),
+            author: *metadata.author(),
+            digest: *metadata.digest(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DagNodeSnapshot {
+    pub round: Round,
+    pub author: Author,
+    pub digest: HashValue,
+    pub timestamp: u64,
+    pub status: DagNodeStatus,
+    /// Whether the node is an ordered anchor
+    pub is_anchor: bool,
+    /// The round of the anchor that ordered the node (if known)
+    pub ordered_by_anchor: Option<Round>,
+    /// The voting power of the nodes linking to this one (only tracked while unordered)
+    pub strong_voting_power: Option<u128>,
+    pub weak_voting_power: Option<u128>,
+    /// The certified parents (strong links) of the node
+    pub parents: Vec<DagNodeRef>,
+    /// The parents that are not in the DAG. Only expected for the lowest round, whose
+    /// parents were pruned.
+    pub missing_parents: Vec<DagNodeRef>,
+}
+
+/// A fetch of the missing parents of a node that is in progress
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DagFetchSnapshot {
+    /// The node whose parents are fetched (which isn't in the DAG yet)
+    pub target: DagNodeRef,
+    /// The parents of the target that are still missing from the DAG
+    pub missing_parents: Vec<DagNodeRef>,
+    pub responders: Vec<Author>,
+    pub elapsed_ms: u64,
+}
+
+/// A snapshot of the DAG of the current epoch for a window of rounds
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DagSnapshot {
+    pub epoch: u64,
+    /// The validators of the epoch, in index order
+    pub validators: Vec<Author>,
+    /// The rounds available in the DAG
+    pub lowest_round: Round,
+    pub highest_round: Round,
+    /// The rounds of the snapshot
+    pub from_round: Round,
+    pub to_round: Round,
+    pub highest_committed_anchor_round: Option<Round>,
+    pub nodes: Vec<DagNodeSnapshot>,
+    /// The (round, author) pairs of the window for which the DAG has no node
+    pub absent_nodes: Vec<(Round, Author)>,
+    pub pending_fetches: Vec<DagFetchSnapshot>,
+}
+
+impl DagSnapshot {
+    /// Renders the snapshot as a self-contained HTML page
+    pub fn to_html(&self) -> String {
+        html::render(self)
+    }
+}
+
+/// Provides structured access to the live DAG of the current epoch (e.g., for the
+/// admin service).
+pub struct DagInspector {
+    time_service: TimeService,
+    // Weak, so that the DAG of an epoch is dropped once the epoch ends
+    dag_store: RwLock<Weak<DagStore>>,
+    pending_fetches: RwLock<Weak<PendingFetches>>,
+}
+
+impl DagInspector {
+    pub fn new(time_service: TimeService) -> Self {
+        Self {
+            time_service,
+            dag_store: RwLock::new(Weak::new()),
+            pending_fetches: RwLock::new(Weak::new()),
+        }
+    }
+
+    /// Sets the DAG store and fetch service state of the current epoch
+    pub(crate) fn set_dag(&self, dag_store: &Arc<DagStore>, pending_fetches: &Arc<PendingFetches>) {
+        *self.dag_store.write() = Arc::downgrade(dag_store);
+        *self.pending_fetches.write() = Arc::downgrade(pending_fetches);
+    }
+
+    /// Exports the DAG between the given rounds (inclusive). By default, exports the last
+    /// `DEFAULT_SNAPSHOT_ROUNDS` rounds, and at most `MAX_SNAPSHOT_ROUNDS` rounds are
+    /// exported. The DAG is only locked while the window is copied.
+    pub fn snapshot(
+        &self,
+        from_round: Option<Round>,
+        to_round: Option<Round>,
+    ) -> Result<DagSnapshot> {
+        let Some(dag_store) = self.dag_store.read().upgrade() else {
+            bail!("No DAG is running");
+        };
+        let pending_fetches = self
+            .pending_fetches
+            .read()
+            .upgrade()
+            .map(|pending_fetches| pending_fetches.get_all())
+            .unwrap_or_default();
+        let now = self.time_service.now_unix_time();
+
+        // Only copy the window (and check the fetches) while holding the lock
+        let (window, pending_fetches) = {
+            let dag = dag_store.read();
+            let window = DagWindow::copy(&dag, from_round, to_round)?;
+            let pending_fetches: Vec<_> = pending_fetches
+                .into_iter()
+                .map(|fetch| DagFetchSnapshot {
+                    target: DagNodeRef::from(&fetch.target),
+                    missing_parents: dag
+                        .filter_missing(fetch.parents.iter())
+                        .map(DagNodeRef::from)
+                        .collect(),
+                    responders: fetch.responders,
+                    elapsed_ms: now.saturating_sub(fetch.start_time).as_millis() as u64,
+                })
+                .collect();
+            (window, pending_fetches)
+        };
+        Ok(window.into_snapshot(pending_fetches))
+    }
+}
+
+/// A copy of the rounds of the DAG exported by a snapshot
+struct DagWindow {
+    epoch: u64,
+    validators: Vec<Author>,
+    lowest_round: Round,
+    highest_round: Round,
+    from_round: Round,
+    to_round: Round,
+    highest_committed_anchor_round: Option<Round>,
+    ordered_anchors: BTreeMap<Round, Author>,
+    // The rounds of the window, and the round below it (to find missing parents)
+    lowest_copied_round: Round,
+    rounds: BTreeMap<Round, Vec<Option<NodeStatus>>>,
+    author_indices: HashMap<Author, usize>,
+}
+
+impl DagWindow {
+    fn copy(dag: &InMemDag, from_round: Option<Round>, to_round: Option<Round>) -> Result<Self> {
+        let lowest_round = dag.lowest_round();
+        let highest_round = dag.highest_round();
+        let to_round = to_round.unwrap_or(highest_round).min(highest_round);
+        let from_round = from_round
+            .unwrap_or_else(|| to_round.saturating_sub(DEFAULT_SNAPSHOT_ROUNDS - 1))
+            .max(lowest_round);
+        if from_round > to_round {
+            bail!(
+                "Empty round window [{}, {}], the DAG has rounds [{}, {}]",
+                from_round,
+                to_round,
+                lowest_round,
+                highest_round
+            );
+        }
+        if to_round - from_round >= MAX_SNAPSHOT_ROUNDS {
+            bail!(
+                "Round window [{}, {}] exceeds {} rounds",
+                from_round,
+                to_round,
+                MAX_SNAPSHOT_ROUNDS
+            );
+        }
+
+        let validators = dag.epoch_state().verifier.get_ordered_account_addresses();
+        let author_indices = validators
+            .iter()
+            .enumerate()
+            .map(|(index, author)| (*author, index))
+            .collect();
+        let lowest_copied_round = from_round.saturating_sub(1).max(lowest_round);
+        Ok(Self {
+            epoch: dag.epoch_state().epoch,
+            validators,
+            lowest_round,
+            highest_round,
+            from_round,
+            to_round,
+            highest_committed_anchor_round: dag.highest_committed_anchor_round(),
+            ordered_anchors: dag
+                .ordered_anchors()
+                .range(from_round..=to_round)
+                .map(|(round, author)| (*round, *author))
+                .collect(),
+            lowest_copied_round,
+            rounds: dag
+                .rounds(lowest_copied_round..=to_round)
+                .map(|(round, round_nodes)| (*round, round_nodes.clone()))
+                .collect(),
+            author_indices,
+        })
+    }
+
+    fn get(&self, round: Round, author: &Author) -> Option<&NodeStatus> {
+        let index = self.author_indices.get(author)?;
+        self.rounds.get(&round)?.get(*index)?.as_ref()
+    }
+
+    /// Whether the parent of a node of the window is in the DAG (as of the copy). The
+    /// parents below the copied rounds were pruned.
+    fn contains_parent(&self, parent: &NodeMetadata) -> bool {
+        parent.round() >= self.lowest_copied_round
+            && self.get(parent.round(), parent.author()).is_some()
+    }
+
+    /// Attributes the ordered nodes of the window to the lowest anchor of the window
+    /// reaching them. Only the anchors in the window can order nodes in the window.
+    fn ordered_by_anchor(&self) -> HashMap<HashValue, Round> {
+        let mut ordered_by_anchor = HashMap::new();
+        for (anchor_round, anchor_author) in &self.ordered_anchors {
+            let Some(anchor) = self.get(*anchor_round, anchor_author) else {
+                continue;
+            };
+            let mut reachable = HashSet::from([anchor.as_node().digest()]);
+            for round in (self.from_round..=*anchor_round).rev() {
+                let Some(round_nodes) = self.rounds.get(&round) else {
+                    continue;
+                };
+                for node_status in round_nodes.iter().flatten() {
+                    let node = node_status.as_node();
+                    if !matches!(node_status, NodeStatus::Ordered(_))
+                        || !reachable.contains(&node.digest())
+                    {
+                        continue;
+                    }
+                    reachable.extend(node.parents_metadata().map(|parent| *parent.digest()));
+                    ordered_by_anchor
+                        .entry(node.digest())
+                        .or_insert(*anchor_round);
+                }
+            }
+        }
+        ordered_by_anchor
+    }
+
+    fn into_snapshot(self, pending_fetches: Vec<DagFetchSnapshot>) -> DagSnapshot {
+        let ordered_by_anchor = self.ordered_by_anchor();
+        let mut nodes = vec![];
+        let mut absent_nodes = vec![];
+        for round in self.from_round..=self.to_round {
+            // Rounds the DAG has no entry for yet, e.g., while fetching
+            let Some(round_nodes) = self.rounds.get(&round) else {
+                absent_nodes.extend(self.validators.iter().map(|author| (round, *author)));
+                continue;
+            };
+            for (index, maybe_node_status) in round_nodes.iter().enumerate() {
+                let Some(node_status) = maybe_node_status else {
+                    absent_nodes.push((round, self.validators[index]));
+                    continue;
+                };
+                let node = node_status.as_node();
+                let ordered_by_anchor = ordered_by_anchor.get(&node.digest()).copied();
+                let (status, strong_voting_power, weak_voting_power) = match node_status {
+                    NodeStatus::Unordered {
+                        aggregated_strong_voting_power,
+                        aggregated_weak_voting_power,
+                        ..
+                    } => (
+                        DagNodeStatus::Unordered,
+                        Some(*aggregated_strong_voting_power),
+                        Some(*aggregated_weak_voting_power),
+                    ),
+                    NodeStatus::Ordered(_) => {
+                        let committed = ordered_by_anchor
+                            .zip(self.highest_committed_anchor_round)
+                            .map_or(false, |(anchor_round, committed_round)| {
+                                anchor_round <= committed_round
+                            });
+                        if committed {
+                            (DagNodeStatus::Committed, None, None)
+                        } else {
+                            (DagNodeStatus::Ordered, None, None)
+                        }
+                    },
+                };
+                nodes.push(DagNodeSnapshot {
+                    round,
+                    author: *node.author(),
+                    digest: node.digest(),
+                    timestamp: node.timestamp(),
+                    status,
+                    is_anchor: self.ordered_anchors.get(&round) == Some(node.author()),
+                    ordered_by_anchor,
+                    strong_voting_power,
+                    weak_voting_power,
+                    parents: node.parents_metadata().map(DagNodeRef::from).collect(),
+                    missing_parents: node
+                        .parents_metadata()
+                        .filter(|parent| !self.contains_parent(parent))
+                        .map(DagNodeRef::from)
+                        .collect(),
+                });
+            }
+        }
+        absent_nodes.sort();
+
+        DagSnapshot {
+            epoch: self.epoch,
+            validators: self.validators,
+            lowest_round: self.lowest_round,
+            highest_round: self.highest_round,
+            from_round: self.from_round,
+            to_round: self.to_round,
+            highest_committed_anchor_round: self.highest_committed_anchor_round,
+            nodes,
+            absent_nodes,
+            pending_fetches,
+        }
+    }
+}
@@ -14,6 +14,7 @@ mod dag_state_sync;
 mod dag_store;
 mod errors;
 mod health;
+mod inspection;
 mod observability;
 mod order_rule;
 mod rb_handler;
@@ -27,6 +28,9 @@ pub use adapter::{ProofNotifier, StorageAdapter};
 pub use bootstrap::DagBootstrapper;
 pub use commit_signer::DagCommitSigner;
 pub use dag_network::{RpcHandler, RpcWithFallback, TDAGNetworkSender};
+pub use inspection::{
+    DagFetchSnapshot, DagInspector, DagNodeRef, DagNodeSnapshot, DagNodeStatus, DagSnapshot,
+};
 #[cfg(test)]
 pub use types::Extensions;
 pub use types::{CertifiedNode, DAGMessage, DAGNetworkMessage, DAGRpcResult, Node, NodeId, Vote};
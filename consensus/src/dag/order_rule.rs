@@ -57,9 +57,11 @@ impl OrderRule {
                         .get_node_by_round_author(event.round(), event.author())
                         .cloned();
                     if let Some(anchor) = maybe_anchor {
-                        dag.write()
+                        let mut dag_writer = dag.write();
+                        dag_writer
                             .reachable_mut(&anchor, None)
                             .for_each(|node_status| node_status.mark_as_ordered());
+                        dag_writer.record_ordered_anchor(anchor.metadata());
                     }
                 }
                 anchor_election.update_reputation(event);
@@ -201,6 +203,7 @@ impl OrderRule {
                 node_status.as_node().clone()
             })
             .collect();
+        dag_writer.record_ordered_anchor(anchor.metadata());
 
         observe_node(anchor.timestamp(), NodeStage::AnchorOrdered);
         for node in ordered_nodes.iter().skip(1) {
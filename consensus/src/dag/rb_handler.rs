@@ -15,7 +15,7 @@ use crate::{
         types::{Node, NodeCertificate, Vote},
         NodeId,
     },
-    util::is_vtxn_expected,
+    util::{is_vtxn_expected, verify_vtxn},
 };
 use anyhow::{bail, ensure};
 use aptos_config::config::DagPayloadConfig;
@@ -24,7 +24,9 @@ use aptos_infallible::Mutex;
 use aptos_logger::{debug, error};
 use aptos_types::{
     epoch_state::EpochState,
-    on_chain_config::{OnChainJWKConsensusConfig, OnChainRandomnessConfig, ValidatorTxnConfig},
+    on_chain_config::{
+        Features, OnChainJWKConsensusConfig, OnChainRandomnessConfig, ValidatorTxnConfig,
+    },
     validator_signer::ValidatorSigner,
     validator_txn::ValidatorTransaction,
 };
@@ -48,6 +50,7 @@ pub(crate) struct NodeBroadcastHandler {
     vtxn_config: ValidatorTxnConfig,
     randomness_config: OnChainRandomnessConfig,
     jwk_consensus_config: OnChainJWKConsensusConfig,
+    features: Features,
     health_backoff: HealthBackoff,
 }
 
@@ -63,6 +66,7 @@ impl NodeBroadcastHandler {
         vtxn_config: ValidatorTxnConfig,
         randomness_config: OnChainRandomnessConfig,
         jwk_consensus_config: OnChainJWKConsensusConfig,
+        features: Features,
         health_backoff: HealthBackoff,
     ) -> Self {
         let epoch = epoch_state.epoch;
@@ -81,6 +85,7 @@ impl NodeBroadcastHandler {
             vtxn_config,
             randomness_config,
             jwk_consensus_config,
+            features,
             health_backoff,
         }
     }
@@ -121,10 +126,16 @@ impl NodeBroadcastHandler {
         ensure!(num_vtxns <= self.vtxn_config.per_block_limit_txn_count());
         for vtxn in node.validator_txns() {
             ensure!(
-                is_vtxn_expected(&self.randomness_config, &self.jwk_consensus_config, vtxn),
+                is_vtxn_expected(
+                    &self.randomness_config,
+                    &self.jwk_consensus_config,
+                    &self.features,
+                    vtxn
+                ),
                 "unexpected validator transaction: {:?}",
                 vtxn.topic()
             );
+            verify_vtxn(&self.epoch_state, *node.author(), vtxn)?;
         }
         let vtxn_total_bytes = node
             .validator_txns()
@@ -0,0 +1,129 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{dag_test::MockStorage, helpers::MockPayloadManager};
+use crate::dag::{
+    dag_fetcher::PendingFetches,
+    dag_store::DagStore,
+    inspection::{DagInspector, DagNodeStatus},
+    tests::helpers::{new_certified_node, TEST_DAG_WINDOW},
+};
+use aptos_time_service::TimeService;
+use aptos_types::{
+    epoch_state::EpochState, validator_signer::ValidatorSigner,
+    validator_verifier::random_validator_verifier,
+};
+use std::sync::Arc;
+
+fn setup() -> (Vec<ValidatorSigner>, Arc<EpochState>, Arc<DagStore>) {
+    let (signers, validator_verifier) = random_validator_verifier(4, None, false);
+    let epoch_state = Arc::new(EpochState {
+        epoch: 1,
+        verifier: validator_verifier,
+    });
+    let dag = Arc::new(DagStore::new(
+        epoch_state.clone(),
+        Arc::new(MockStorage::new()),
+        Arc::new(MockPayloadManager {}),
+        1,
+        TEST_DAG_WINDOW,
+    ));
+    (signers, epoch_state, dag)
+}
+
+#[test]
+fn test_snapshot_without_dag() {
+    let inspector = DagInspector::new(TimeService::mock());
+    assert!(inspector.snapshot(None, None).is_err());
+
+    // The DAG of an epoch is not kept alive by the inspector
+    let (_, _, dag) = setup();
+    let pending_fetches = Arc::new(PendingFetches::default());
+    inspector.set_dag(&dag, &pending_fetches);
+    assert!(inspector.snapshot(None, None).is_ok());
+    drop(dag);
+    assert!(inspector.snapshot(None, None).is_err());
+}
+
+#[test]
+fn test_snapshot_node_status() {
+    let (signers, epoch_state, dag) = setup();
+    let pending_fetches = Arc::new(PendingFetches::default());
+    let inspector = DagInspector::new(TimeService::mock());
+    inspector.set_dag(&dag, &pending_fetches);
+
+    // Rounds 1 to 3 have nodes from validators 0, 1, 2 only
+    for round in 1..=3 {
+        let parents = dag
+            .read()
+            .get_strong_links_for_round(round - 1, &epoch_state.verifier)
+            .unwrap_or_default();
+        for signer in &signers[0..3] {
+            let node = new_certified_node(round, signer.author(), parents.clone());
+            dag.add_node(node).unwrap();
+        }
+    }
+
+    // Order the anchor of round 2 and commit it
+    let anchor = dag
+        .read()
+        .get_node_by_round_author(2, &signers[0].author())
+        .unwrap()
+        .clone();
+    {
+        let mut dag_writer = dag.write();
+        for node_status in dag_writer.reachable_mut(&anchor, None) {
+            node_status.mark_as_ordered();
+        }
+        dag_writer.record_ordered_anchor(anchor.metadata());
+    }
+    dag.commit_callback(2);
+
+    let snapshot = inspector.snapshot(None, Some(10)).unwrap();
+    assert_eq!(snapshot.epoch, 1);
+    assert_eq!((snapshot.from_round, snapshot.to_round), (1, 3));
+    assert_eq!(snapshot.highest_committed_anchor_round, Some(2));
+    assert_eq!(snapshot.nodes.len(), 9);
+    assert_eq!(
+        snapshot.absent_nodes,
+        (1..=3)
+            .map(|round| (round, signers[3].author()))
+            .collect::<Vec<_>>()
+    );
+    assert!(snapshot.pending_fetches.is_empty());
+
+    for node in &snapshot.nodes {
+        assert!(node.missing_parents.is_empty());
+        assert_eq!(
+            node.is_anchor,
+            node.round == 2 && node.author == signers[0].author()
+        );
+        if node.round == 3 {
+            assert_eq!(node.status, DagNodeStatus::Unordered);
+            assert_eq!(node.ordered_by_anchor, None);
+            assert!(node.strong_voting_power.is_some());
+        } else if node.round == 2 && node.author != signers[0].author() {
+            // Not reachable from the anchor
+            assert_eq!(node.status, DagNodeStatus::Unordered);
+        } else {
+            assert_eq!(node.status, DagNodeStatus::Committed);
+            assert_eq!(node.ordered_by_anchor, Some(2));
+        }
+    }
+
+    let snapshot = inspector.snapshot(Some(3), None).unwrap();
+    assert_eq!((snapshot.from_round, snapshot.to_round), (3, 3));
+    assert_eq!(snapshot.nodes.len(), 3);
+    assert!(inspector.snapshot(Some(5), Some(4)).is_err());
+
+    // Only the window is walked, so anchors above it don't attribute its nodes
+    let snapshot = inspector.snapshot(Some(1), Some(1)).unwrap();
+    assert!(snapshot
+        .nodes
+        .iter()
+        .all(|node| node.status == DagNodeStatus::Ordered && node.ordered_by_anchor.is_none()));
+
+    let html = snapshot.to_html();
+    assert!(html.contains("<svg"));
+    assert!(html.contains("No fetches in progress."));
+}
@@ -7,6 +7,7 @@ mod dag_state_sync_tests;
 mod dag_test;
 mod fetcher_test;
 mod helpers;
+mod inspection_tests;
 mod integration_tests;
 mod order_rule_tests;
 mod rb_handler_tests;
@@ -19,7 +19,9 @@ use aptos_config::config::DagPayloadConfig;
 use aptos_types::{
     aggregate_signature::PartialSignatures,
     epoch_state::EpochState,
-    on_chain_config::{OnChainJWKConsensusConfig, OnChainRandomnessConfig, ValidatorTxnConfig},
+    on_chain_config::{
+        Features, OnChainJWKConsensusConfig, OnChainRandomnessConfig, ValidatorTxnConfig,
+    },
     validator_verifier::random_validator_verifier,
 };
 use claims::{assert_ok, assert_ok_eq};
@@ -80,6 +82,7 @@ async fn test_node_broadcast_receiver_succeed() {
         ValidatorTxnConfig::default_disabled(),
         OnChainRandomnessConfig::default_disabled(),
         OnChainJWKConsensusConfig::default_disabled(),
+        Features::default(),
         health_backoff,
     );
 
@@ -131,6 +134,7 @@ async fn test_node_broadcast_receiver_failure() {
                 ValidatorTxnConfig::default_disabled(),
                 OnChainRandomnessConfig::default_disabled(),
                 OnChainJWKConsensusConfig::default_disabled(),
+                Features::default(),
                 HealthBackoff::new(
                     epoch_state.clone(),
                     NoChainHealth::new(),
@@ -223,6 +227,7 @@ async fn test_node_broadcast_receiver_storage() {
         ValidatorTxnConfig::default_disabled(),
         OnChainRandomnessConfig::default_disabled(),
         OnChainJWKConsensusConfig::default_disabled(),
+        Features::default(),
         HealthBackoff::new(
             epoch_state.clone(),
             NoChainHealth::new(),
@@ -247,6 +252,7 @@ async fn test_node_broadcast_receiver_storage() {
         ValidatorTxnConfig::default_disabled(),
         OnChainRandomnessConfig::default_disabled(),
         OnChainJWKConsensusConfig::default_disabled(),
+        Features::default(),
         HealthBackoff::new(
             epoch_state,
             NoChainHealth::new(),
@@ -10,13 +10,14 @@ use crate::{
     },
     consensus_observer::publisher::ConsensusPublisher,
     counters,
-    dag::{DagBootstrapper, DagCommitSigner, StorageAdapter},
+    dag::{DagBootstrapper, DagCommitSigner, DagInspector, StorageAdapter},
     error::{error_kind, DbError},
+    flight_recorder::recorder::{FlightRecorder, FLIGHT_RECORDER_DIR_NAME},
     liveness::{
         cached_proposer_election::CachedProposerElection,
         leader_reputation::{
-            extract_epoch_to_proposers, AptosDBBackend, LeaderReputation,
-            ProposerAndVoterHeuristic, ReputationHeuristic,
+            create_reputation_heuristic, extract_epoch_to_proposers, AptosDBBackend,
+            LeaderReputation,
         },
         proposal_generator::{
             ChainHealthBackoffConfig, PipelineBackpressureConfig, ProposalGenerator,
@@ -38,9 +39,12 @@ use crate::{
         mixed::MixedPayloadClient, user::quorum_store_client::QuorumStoreClient, PayloadClient,
     },
     payload_manager::{DirectMempoolPayloadManager, TPayloadManager},
-    persistent_liveness_storage::{LedgerRecoveryData, PersistentLivenessStorage, RecoveryData},
+    persistent_liveness_storage::{
+        LedgerRecoveryData, LivenessStorageDbs, PersistentLivenessStorage, RecoveryData,
+    },
     pipeline::execution_client::TExecutionClient,
     quorum_store::{
+        inspection::QuorumStoreInspector,
         quorum_store_builder::{DirectMempoolInnerBuilder, InnerBuilder, QuorumStoreBuilder},
         quorum_store_coordinator::CoordinatorCommand,
         quorum_store_db::QuorumStoreStorage,
@@ -58,7 +62,7 @@ use aptos_bounded_executor::BoundedExecutor;
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
 use aptos_config::config::{
     ConsensusConfig, DagConsensusConfig, ExecutionConfig, NodeConfig, QcAggregatorType,
-    SafetyRulesConfig, SecureBackend,
+    SafetyRulesConfig,
 };
 use aptos_consensus_types::{
     common::{Author, Round},
@@ -66,19 +70,18 @@ use aptos_consensus_types::{
     epoch_retrieval::EpochRetrievalRequest,
     proof_of_store::ProofCache,
 };
-use aptos_crypto::bls12381;
 use aptos_dkg::{
     pvss::{traits::Transcript, Player},
     weighted_vuf::traits::WeightedVUF,
 };
 use aptos_event_notifications::ReconfigNotificationListener;
-use aptos_global_constants::CONSENSUS_KEY;
 use aptos_infallible::{duration_since_epoch, Mutex};
 use aptos_logger::prelude::*;
 use aptos_mempool::QuorumStoreRequest;
 use aptos_network::{application::interface::NetworkClient, protocols::network::Event};
-use aptos_safety_rules::SafetyRulesManager;
-use aptos_secure_storage::{KVStorage, Storage};
+use aptos_safety_rules::{
+    safety_rules_manager::load_consensus_key_from_secure_storage, SafetyRulesManager,
+};
 use aptos_types::{
     account_address::AccountAddress,
     dkg::{real_dkg::maybe_dk_from_bls_sk, DKGState, DKGTrait, DefaultDKG},
@@ -86,10 +89,9 @@ use aptos_types::{
     epoch_state::EpochState,
     jwks::SupportedOIDCProviders,
     on_chain_config::{
-        Features, LeaderReputationType, OnChainConfigPayload, OnChainConfigProvider,
-        OnChainConsensusConfig, OnChainExecutionConfig, OnChainJWKConsensusConfig,
-        OnChainRandomnessConfig, ProposerElectionType, RandomnessConfigMoveStruct,
-        RandomnessConfigSeqNum, ValidatorSet,
+        Features, OnChainConfigPayload, OnChainConfigProvider, OnChainConsensusConfig,
+        OnChainExecutionConfig, OnChainJWKConsensusConfig, OnChainRandomnessConfig,
+        ProposerElectionType, RandomnessConfigMoveStruct, RandomnessConfigSeqNum, ValidatorSet,
     },
     randomness::{RandKeys, WvufPP, WVUF},
     validator_signer::ValidatorSigner,
@@ -112,6 +114,7 @@ use std::{
     collections::HashMap,
     hash::Hash,
     mem::{discriminant, Discriminant},
+    path::PathBuf,
     sync::Arc,
     time::Duration,
 };
@@ -145,6 +148,7 @@ pub struct EpochManager<P: OnChainConfigProvider> {
     quorum_store_to_mempool_sender: Sender<QuorumStoreRequest>,
     execution_client: Arc<dyn TExecutionClient>,
     storage: Arc<dyn PersistentLivenessStorage>,
+    storage_dbs: Arc<dyn LivenessStorageDbs>,
     safety_rules_manager: SafetyRulesManager,
     vtxn_pool: VTxnPoolState,
     reconfig_events: ReconfigNotificationListener<P>,
@@ -162,6 +166,8 @@ pub struct EpochManager<P: OnChainConfigProvider> {
     quorum_store_msg_tx: Option<aptos_channel::Sender<AccountAddress, VerifiedEvent>>,
     quorum_store_coordinator_tx: Option<Sender<CoordinatorCommand>>,
     quorum_store_storage: Arc<dyn QuorumStoreStorage>,
+    quorum_store_inspector: Arc<QuorumStoreInspector>,
+    dag_inspector: Arc<DagInspector>,
     batch_retrieval_tx:
         Option<aptos_channel::Sender<AccountAddress, IncomingBatchRetrievalRequest>>,
     bounded_executor: BoundedExecutor,
@@ -177,6 +183,7 @@ pub struct EpochManager<P: OnChainConfigProvider> {
     proof_cache: ProofCache,
     consensus_publisher: Option<Arc<ConsensusPublisher>>,
     pending_blocks: Arc<Mutex<PendingBlocks>>,
+    flight_recorder_dir: PathBuf,
 }
 
 impl<P: OnChainConfigProvider> EpochManager<P> {
@@ -190,7 +197,10 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         quorum_store_to_mempool_sender: Sender<QuorumStoreRequest>,
         execution_client: Arc<dyn TExecutionClient>,
         storage: Arc<dyn PersistentLivenessStorage>,
+        storage_dbs: Arc<dyn LivenessStorageDbs>,
         quorum_store_storage: Arc<dyn QuorumStoreStorage>,
+        quorum_store_inspector: Arc<QuorumStoreInspector>,
+        dag_inspector: Arc<DagInspector>,
         reconfig_events: ReconfigNotificationListener<P>,
         bounded_executor: BoundedExecutor,
         aptos_time_service: aptos_time_service::TimeService,
@@ -218,6 +228,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             quorum_store_to_mempool_sender,
             execution_client,
             storage,
+            storage_dbs,
             safety_rules_manager,
             vtxn_pool,
             reconfig_events,
@@ -230,6 +241,8 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             quorum_store_msg_tx: None,
             quorum_store_coordinator_tx: None,
             quorum_store_storage,
+            quorum_store_inspector,
+            dag_inspector,
             batch_retrieval_tx: None,
             bounded_executor,
             recovery_mode: false,
@@ -246,6 +259,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
                 .build(),
             consensus_publisher,
             pending_blocks: Arc::new(Mutex::new(PendingBlocks::new())),
+            flight_recorder_dir: node_config.storage.dir().join(FLIGHT_RECORDER_DIR_NAME),
         }
     }
 
@@ -305,32 +319,11 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
                     window_size,
                     weight_by_voting_power,
                     use_history_from_previous_epoch_max_count,
-                ) = match &leader_reputation_type {
-                    LeaderReputationType::ProposerAndVoter(proposer_and_voter_config)
-                    | LeaderReputationType::ProposerAndVoterV2(proposer_and_voter_config) => {
-                        let proposer_window_size = proposers.len()
-                            * proposer_and_voter_config.proposer_window_num_validators_multiplier;
-                        let voter_window_size = proposers.len()
-                            * proposer_and_voter_config.voter_window_num_validators_multiplier;
-                        let heuristic: Box<dyn ReputationHeuristic> =
-                            Box::new(ProposerAndVoterHeuristic::new(
-                                self.author,
-                                proposer_and_voter_config.active_weight,
-                                proposer_and_voter_config.inactive_weight,
-                                proposer_and_voter_config.failed_weight,
-                                proposer_and_voter_config.failure_threshold_percent,
-                                voter_window_size,
-                                proposer_window_size,
-                                leader_reputation_type.use_reputation_window_from_stale_end(),
-                            ));
-                        (
-                            heuristic,
-                            std::cmp::max(proposer_window_size, voter_window_size),
-                            proposer_and_voter_config.weight_by_voting_power,
-                            proposer_and_voter_config.use_history_from_previous_epoch_max_count,
-                        )
-                    },
-                };
+                ) = create_reputation_heuristic(
+                    self.author,
+                    leader_reputation_type,
+                    proposers.len(),
+                );
 
                 let seek_len = onchain_config.leader_reputation_exclude_round() as usize
                     + onchain_config.max_failed_authors_to_store()
@@ -339,7 +332,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
                 let backend = Arc::new(AptosDBBackend::new(
                     window_size,
                     seek_len,
-                    self.storage.aptos_db(),
+                    self.storage_dbs.aptos_db(),
                 ));
                 let voting_powers: Vec<_> = if weight_by_voting_power {
                     proposers
@@ -422,7 +415,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         );
         // If we are considering beyond the current epoch, we need to fetch validators for those epochs
         if epoch_state.epoch > first_epoch_to_consider {
-            self.storage
+            self.storage_dbs
                 .aptos_db()
                 .get_epoch_ending_ledger_infos(first_epoch_to_consider - 1, epoch_state.epoch)
                 .map_err(Into::into)
@@ -457,7 +450,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             "[EpochManager] receive {}", request,
         );
         let proof = self
-            .storage
+            .storage_dbs
             .aptos_db()
             .get_epoch_ending_ledger_infos(request.start_epoch, request.end_epoch)
             .map_err(DbError::from)
@@ -704,7 +697,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
                 consensus_to_quorum_store_rx,
                 self.quorum_store_to_mempool_sender.clone(),
                 self.config.mempool_txn_pull_timeout_ms,
-                self.storage.aptos_db().clone(),
+                self.storage_dbs.aptos_db().clone(),
                 network_sender,
                 epoch_state.verifier.clone(),
                 self.proof_cache.clone(),
@@ -723,6 +716,9 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
 
         let (payload_manager, quorum_store_msg_tx) =
             quorum_store_builder.init_payload_manager(self.consensus_publisher.clone());
+        if let Some(batch_store) = quorum_store_builder.batch_store() {
+            self.quorum_store_inspector.set_batch_store(&batch_store);
+        }
         self.quorum_store_msg_tx = quorum_store_msg_tx;
         self.payload_manager = payload_manager.clone();
 
@@ -764,6 +760,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         onchain_execution_config: OnChainExecutionConfig,
         onchain_randomness_config: OnChainRandomnessConfig,
         onchain_jwk_consensus_config: OnChainJWKConsensusConfig,
+        features: Features,
         network_sender: Arc<NetworkSender>,
         payload_client: Arc<dyn PayloadClient>,
         payload_manager: Arc<dyn TPayloadManager>,
@@ -898,10 +895,24 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             self.config.clone(),
             onchain_randomness_config,
             onchain_jwk_consensus_config,
+            features,
             fast_rand_config,
         );
 
+        round_manager.set_vtxn_pool(self.vtxn_pool.clone());
         round_manager.init(last_vote).await;
+        if self.config.flight_recorder.enabled {
+            match FlightRecorder::new_file_recorder(
+                &self.flight_recorder_dir,
+                &self.config.flight_recorder,
+            ) {
+                Ok(flight_recorder) => round_manager.set_flight_recorder(flight_recorder),
+                Err(error) => error!(
+                    epoch = epoch,
+                    "Failed to create the consensus flight recorder: {:?}", error
+                ),
+            }
+        }
 
         let (close_tx, close_rx) = oneshot::channel();
         self.round_manager_close_tx = Some(close_tx);
@@ -1078,6 +1089,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         });
 
         self.epoch_state = Some(epoch_state.clone());
+        self.quorum_store_inspector.set_epoch(epoch_state.epoch);
 
         let onchain_consensus_config: anyhow::Result<OnChainConsensusConfig> = payload.get();
         let onchain_execution_config: anyhow::Result<OnChainExecutionConfig> = payload.get();
@@ -1128,6 +1140,10 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             // `jwk_consensus_config` not yet initialized, falling back to the old configs.
             Self::equivalent_jwk_consensus_config_from_deprecated_resources(&payload)
         });
+        let features = payload.get::<Features>().unwrap_or_else(|error| {
+            error!("Failed to read on-chain features {}", error);
+            Features::default()
+        });
         let rand_configs = self.try_get_rand_config_for_new_epoch(
             &epoch_state,
             &onchain_randomness_config,
@@ -1173,20 +1189,29 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         self.rand_manager_msg_tx = Some(rand_msg_tx);
 
         if consensus_config.is_dag_enabled() {
-            self.start_new_epoch_with_dag(
-                epoch_state,
-                consensus_config,
-                execution_config,
-                onchain_randomness_config,
-                jwk_consensus_config,
-                network_sender,
-                payload_client,
-                payload_manager,
-                rand_config,
-                fast_rand_config,
-                rand_msg_rx,
-            )
-            .await
+            let epoch = epoch_state.epoch;
+            if let Err(error) = self
+                .start_new_epoch_with_dag(
+                    epoch_state,
+                    consensus_config,
+                    execution_config,
+                    onchain_randomness_config,
+                    jwk_consensus_config,
+                    features,
+                    network_sender,
+                    payload_client,
+                    payload_manager,
+                    rand_config,
+                    fast_rand_config,
+                    rand_msg_rx,
+                )
+                .await
+            {
+                error!(
+                    epoch = epoch,
+                    "Failed to start DAG consensus for the new epoch: {:#}", error
+                );
+            }
         } else {
             self.start_new_epoch_with_joltean(
                 epoch_state,
@@ -1194,6 +1219,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
                 execution_config,
                 onchain_randomness_config,
                 jwk_consensus_config,
+                features,
                 network_sender,
                 payload_client,
                 payload_manager,
@@ -1242,6 +1268,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         execution_config: OnChainExecutionConfig,
         onchain_randomness_config: OnChainRandomnessConfig,
         jwk_consensus_config: OnChainJWKConsensusConfig,
+        features: Features,
         network_sender: NetworkSender,
         payload_client: Arc<dyn PayloadClient>,
         payload_manager: Arc<dyn TPayloadManager>,
@@ -1259,6 +1286,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
                     execution_config,
                     onchain_randomness_config,
                     jwk_consensus_config,
+                    features,
                     Arc::new(network_sender),
                     payload_client,
                     payload_manager,
@@ -1288,16 +1316,19 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         on_chain_execution_config: OnChainExecutionConfig,
         onchain_randomness_config: OnChainRandomnessConfig,
         onchain_jwk_consensus_config: OnChainJWKConsensusConfig,
+        features: Features,
         network_sender: NetworkSender,
         payload_client: Arc<dyn PayloadClient>,
         payload_manager: Arc<dyn TPayloadManager>,
         rand_config: Option<RandConfig>,
         fast_rand_config: Option<RandConfig>,
         rand_msg_rx: aptos_channel::Receiver<AccountAddress, IncomingRandGenRequest>,
-    ) {
+    ) -> anyhow::Result<()> {
         let epoch = epoch_state.epoch;
-        let consensus_key = new_consensus_key_from_storage(&self.config.safety_rules.backend)
-            .expect("unable to get private key");
+        // DAG consensus signs nodes with the consensus key directly (this isn't supported
+        // when the consensus key is held by a remote signer).
+        let consensus_key = load_consensus_key_from_secure_storage(&self.config.safety_rules)
+            .context("Failed to load the consensus key for DAG consensus")?;
         let signer = Arc::new(ValidatorSigner::new(self.author, consensus_key));
         let commit_signer = Arc::new(DagCommitSigner::new(signer.clone()));
 
@@ -1306,7 +1337,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             "decoupled execution must be enabled"
         );
         let highest_committed_round = self
-            .storage
+            .storage_dbs
             .aptos_db()
             .get_latest_ledger_info()
             .expect("unable to get latest ledger info")
@@ -1338,8 +1369,8 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         let dag_storage = Arc::new(StorageAdapter::new(
             epoch,
             epoch_to_validators,
-            self.storage.consensus_db(),
-            self.storage.aptos_db(),
+            self.storage_dbs.consensus_db(),
+            self.storage_dbs.aptos_db(),
         ));
 
         let network_sender_arc = Arc::new(network_sender);
@@ -1365,10 +1396,12 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             onchain_consensus_config.effective_validator_txn_config(),
             onchain_randomness_config,
             onchain_jwk_consensus_config,
+            features,
             self.bounded_executor.clone(),
             self.config
                 .quorum_store
                 .allow_batches_without_pos_in_proposal,
+            self.dag_inspector.clone(),
         );
 
         let (dag_rpc_tx, dag_rpc_rx) = aptos_channel::new(QueueStyle::FIFO, 10, None);
@@ -1377,6 +1410,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         self.dag_shutdown_tx = Some(dag_shutdown_tx);
 
         tokio::spawn(bootstrapper.start(dag_rpc_rx, dag_shutdown_rx));
+        Ok(())
     }
 
     fn enable_quorum_store(&mut self, onchain_config: &OnChainConsensusConfig) -> bool {
@@ -1748,17 +1782,6 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
     }
 }
 
-fn new_consensus_key_from_storage(backend: &SecureBackend) -> anyhow::Result<bls12381::PrivateKey> {
-    let storage: Storage = backend.into();
-    storage
-        .available()
-        .map_err(|e| anyhow!("Storage is not available: {e}"))?;
-    storage
-        .get(CONSENSUS_KEY)
-        .map(|v| v.value)
-        .map_err(|e| anyhow!("storage get and map err: {e}"))
-}
-
 fn load_dkg_decrypt_key_from_identity_blob(
     config: &SafetyRulesConfig,
 ) -> anyhow::Result<<DefaultDKG as DKGTrait>::NewValidatorDecryptKey> {
@@ -1769,13 +1792,20 @@ fn load_dkg_decrypt_key_from_identity_blob(
 fn load_dkg_decrypt_key_from_secure_storage(
     config: &SafetyRulesConfig,
 ) -> anyhow::Result<<DefaultDKG as DKGTrait>::NewValidatorDecryptKey> {
-    let consensus_key = new_consensus_key_from_storage(&config.backend)?;
+    let consensus_key = load_consensus_key_from_secure_storage(config)?;
     maybe_dk_from_bls_sk(&consensus_key)
 }
 
 fn load_dkg_decrypt_key(
     config: &SafetyRulesConfig,
 ) -> Option<<DefaultDKG as DKGTrait>::NewValidatorDecryptKey> {
+    // The decrypt key is derived from the consensus key, which never leaves a remote
+    // signer (so neither the local backend nor the identity blob may be used instead).
+    if config.signer.is_remote() {
+        warn!("The DKG decrypt key is unavailable: the consensus key is held by a remote signer");
+        return None;
+    }
+
     match load_dkg_decrypt_key_from_secure_storage(config) {
         Ok(dk) => {
             return Some(dk);
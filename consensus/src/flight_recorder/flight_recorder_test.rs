@@ -0,0 +1,132 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::flight_recorder::{
+    recorder::{decode_records, encode_record, list_segments, read_segment, FlightRecorder},
+    types::{FlightEvent, FlightRecord, RecordedDecision, RoundManagerCheckpoint},
+};
+use aptos_config::config::ConsensusFlightRecorderConfig;
+use aptos_consensus_types::{
+    block::{block_test_utils::certificate_for_genesis, Block},
+    common::Author,
+    safety_data::SafetyData,
+};
+use aptos_temppath::TempPath;
+use aptos_types::{
+    epoch_state::EpochState,
+    on_chain_config::{
+        Features, OnChainConsensusConfig, OnChainJWKConsensusConfig, OnChainRandomnessConfig,
+    },
+};
+
+#[test]
+fn test_encode_decode_records() {
+    // Encode several records
+    let proposer = Author::random();
+    let records = vec![
+        create_checkpoint_record(),
+        FlightRecord::new(1, 1, FlightEvent::NewRound { proposer }),
+        FlightRecord::new(1, 1, FlightEvent::LocalTimeout(1)),
+    ];
+    let mut bytes = vec![];
+    for record in &records {
+        bytes.extend(encode_record(record).unwrap());
+    }
+
+    // Decode the records and verify they match
+    let decoded_records = decode_records(bytes.as_slice()).unwrap();
+    assert_eq!(decoded_records.len(), records.len());
+    assert!(matches!(
+        decoded_records[0].event,
+        FlightEvent::Checkpoint(_)
+    ));
+    assert_eq!(
+        decoded_records[1].get_decision(),
+        Some(RecordedDecision::NewRound { round: 1, proposer })
+    );
+    assert!(matches!(
+        decoded_records[2].event,
+        FlightEvent::LocalTimeout(1)
+    ));
+
+    // Truncate the last record and verify it is ignored
+    bytes.truncate(bytes.len() - 1);
+    let decoded_records = decode_records(bytes.as_slice()).unwrap();
+    assert_eq!(decoded_records.len(), records.len() - 1);
+}
+
+#[test]
+fn test_segment_rotation() {
+    // Create a file recorder with tiny segments
+    let recorder_dir = TempPath::new();
+    let max_num_segments = 3;
+    let config = ConsensusFlightRecorderConfig {
+        enabled: true,
+        max_segment_size_bytes: 1,
+        max_num_segments,
+        max_pending_records: 100,
+    };
+    let mut flight_recorder =
+        FlightRecorder::new_file_recorder(recorder_dir.path(), &config).unwrap();
+
+    // Record several checkpoints and events (every record fills a segment)
+    let num_segments_to_write = 5;
+    for round in 0..num_segments_to_write {
+        assert!(flight_recorder.is_checkpoint_required());
+        flight_recorder.record(create_checkpoint_record()).unwrap();
+        flight_recorder
+            .record(FlightRecord::new(
+                1,
+                round,
+                FlightEvent::LocalTimeout(round),
+            ))
+            .unwrap();
+    }
+
+    // Verify only the newest segments were retained
+    flight_recorder.flush().unwrap();
+    let segments = list_segments(recorder_dir.path()).unwrap();
+    let segment_indices: Vec<_> = segments.iter().map(|(index, _)| *index).collect();
+    assert_eq!(segment_indices, vec![2, 3, 4]);
+
+    // Verify that each segment starts with a checkpoint (followed by the event)
+    for (index, segment_path) in segments {
+        let records = read_segment(&segment_path).unwrap();
+        assert_eq!(records.len(), 2);
+        assert!(matches!(records[0].event, FlightEvent::Checkpoint(_)));
+        assert!(matches!(records[1].event, FlightEvent::LocalTimeout(round) if round == index));
+    }
+
+    // Create a new recorder and verify it continues from the highest segment
+    drop(flight_recorder);
+    FlightRecorder::new_file_recorder(recorder_dir.path(), &config).unwrap();
+    let segments = list_segments(recorder_dir.path()).unwrap();
+    assert_eq!(segments.len(), max_num_segments);
+    assert_eq!(segments.last().unwrap().0, num_segments_to_write);
+}
+
+/// Creates a checkpoint record for the genesis block
+fn create_checkpoint_record() -> FlightRecord {
+    let root_quorum_cert = certificate_for_genesis();
+    let checkpoint = RoundManagerCheckpoint {
+        author: Author::random(),
+        epoch_state: EpochState::empty(),
+        onchain_config: OnChainConsensusConfig::default(),
+        randomness_config: OnChainRandomnessConfig::default_disabled().into(),
+        jwk_consensus_config: OnChainJWKConsensusConfig::default_disabled(),
+        features: Features::default(),
+        max_receiving_block_txns: 100,
+        max_receiving_block_bytes: 1024,
+        vote_back_pressure_limit: 10,
+        sync_only: false,
+        root_block: Block::make_genesis_block(),
+        root_commit_cert: root_quorum_cert.into_wrapped_ledger_info(),
+        root_quorum_cert,
+        blocks: vec![],
+        quorum_certs: vec![],
+        highest_2chain_timeout_cert: None,
+        last_vote: None,
+        safety_data: SafetyData::new(1, 0, 0, 0, None, 0),
+    };
+    FlightRecord::new(1, 0, FlightEvent::Checkpoint(Box::new(checkpoint)))
+}
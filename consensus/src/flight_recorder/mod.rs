@@ -0,0 +1,15 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! The consensus flight recorder captures (per round) the events processed by the
+//! round manager (e.g., received proposals, votes, timeouts and sync info), as well
+//! as the decisions made by the round manager and safety rules. The recording is
+//! stored on disk in bounded segments, and can be replayed offline through a round
+//! manager (with mocked network and storage) to reproduce the node's decisions.
+
+pub mod recorder;
+pub mod replay;
+pub mod types;
+
+#[cfg(test)]
+mod flight_recorder_test;
@@ -0,0 +1,401 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::flight_recorder::types::{FlightEvent, FlightRecord};
+use anyhow::{bail, Context};
+use aptos_config::config::ConsensusFlightRecorderConfig;
+use aptos_logger::prelude::*;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufReader, ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, SyncSender, TrySendError},
+    thread::JoinHandle,
+};
+
+/// The name of the flight recorder directory (inside the node's storage directory)
+pub const FLIGHT_RECORDER_DIR_NAME: &str = "consensus_flight_recorder";
+
+// Each record is written as a little-endian u32 length, followed by the BCS bytes
+const RECORD_LENGTH_PREFIX_BYTES: usize = 4;
+
+// Segment files are named "segment_<index>.bin"
+const SEGMENT_FILE_PREFIX: &str = "segment_";
+const SEGMENT_FILE_SUFFIX: &str = ".bin";
+
+/// A bounded flight recorder for the round manager. Records are appended to the
+/// current segment, and once a segment is full, a new segment is started at the
+/// next checkpoint (and the oldest segments are deleted). Every segment starts
+/// with a checkpoint, so that each segment can be replayed independently.
+///
+/// Records are written to disk by a dedicated writer thread (behind a bounded
+/// channel), so that the round manager never blocks on disk IO. If the writer
+/// falls behind, records are dropped and a new segment is started at the next
+/// checkpoint (as the current segment can no longer be replayed past the gap).
+pub struct FlightRecorder {
+    sink: FlightRecorderSink,
+    max_segment_size_bytes: u64,
+    segment_size_bytes: u64,
+    checkpoint_required: bool,
+}
+
+enum FlightRecorderSink {
+    File(SegmentWriterHandle),
+    Memory(Vec<FlightRecord>),
+}
+
+impl FlightRecorder {
+    /// Creates a flight recorder that writes segments to the given directory. A new
+    /// segment is always started (i.e., existing segments are never appended to).
+    pub fn new_file_recorder(
+        directory: &Path,
+        config: &ConsensusFlightRecorderConfig,
+    ) -> anyhow::Result<Self> {
+        if config.max_num_segments == 0 {
+            bail!("The flight recorder must retain at least one segment!");
+        }
+        if config.max_pending_records == 0 {
+            bail!("The flight recorder must allow at least one pending record!");
+        }
+        let segment_writer = SegmentWriter::new(directory, config.max_num_segments)?;
+        let segment_writer_handle =
+            SegmentWriterHandle::spawn(segment_writer, config.max_pending_records)?;
+        Ok(Self {
+            sink: FlightRecorderSink::File(segment_writer_handle),
+            max_segment_size_bytes: config.max_segment_size_bytes,
+            segment_size_bytes: 0,
+            checkpoint_required: true,
+        })
+    }
+
+    /// Creates an unbounded flight recorder that keeps all records in memory
+    /// (e.g., to capture the decisions made during a replay).
+    pub fn new_memory_recorder() -> Self {
+        Self {
+            sink: FlightRecorderSink::Memory(vec![]),
+            max_segment_size_bytes: u64::MAX,
+            segment_size_bytes: 0,
+            checkpoint_required: false,
+        }
+    }
+
+    /// Returns true iff a checkpoint should be recorded before the next received
+    /// event (i.e., the current segment is full, or no checkpoint was recorded yet).
+    pub fn is_checkpoint_required(&self) -> bool {
+        self.checkpoint_required
+    }
+
+    /// Appends the given record to the recording. Segments are only rotated when
+    /// a checkpoint is recorded, so decisions made while processing an event are
+    /// always stored in the same segment as the event itself.
+    pub fn record(&mut self, record: FlightRecord) -> anyhow::Result<()> {
+        let is_checkpoint = matches!(record.event, FlightEvent::Checkpoint(_));
+        if is_checkpoint && self.segment_size_bytes > 0 {
+            if let FlightRecorderSink::File(segment_writer_handle) = &self.sink {
+                segment_writer_handle.send(SegmentWriterCommand::StartNewSegment)?;
+            }
+            self.segment_size_bytes = 0;
+        }
+
+        // Send the record to the sink
+        let record_size_bytes = match &mut self.sink {
+            FlightRecorderSink::File(segment_writer_handle) => {
+                let record_bytes = encode_record(&record)?;
+                let record_size_bytes = record_bytes.len() as u64;
+                if let Err(error) =
+                    segment_writer_handle.send(SegmentWriterCommand::WriteRecord(record_bytes))
+                {
+                    // The current segment now has a gap, so start a new one
+                    self.checkpoint_required = true;
+                    return Err(error);
+                }
+                record_size_bytes
+            },
+            FlightRecorderSink::Memory(records) => {
+                records.push(record);
+                0
+            },
+        };
+        if is_checkpoint {
+            self.checkpoint_required = false;
+        }
+
+        // Require a checkpoint (and a new segment) if the current segment is full
+        self.segment_size_bytes = self.segment_size_bytes.saturating_add(record_size_bytes);
+        if self.segment_size_bytes >= self.max_segment_size_bytes {
+            self.checkpoint_required = true;
+        }
+
+        Ok(())
+    }
+
+    /// Blocks until all the pending records have been written to disk (this is
+    /// a no-op for memory recorders).
+    pub fn flush(&self) -> anyhow::Result<()> {
+        match &self.sink {
+            FlightRecorderSink::File(segment_writer_handle) => segment_writer_handle.flush(),
+            FlightRecorderSink::Memory(_) => Ok(()),
+        }
+    }
+
+    /// Takes all the records held in memory (this is a no-op for file recorders)
+    pub fn take_records(&mut self) -> Vec<FlightRecord> {
+        match &mut self.sink {
+            FlightRecorderSink::File(_) => vec![],
+            FlightRecorderSink::Memory(records) => std::mem::take(records),
+        }
+    }
+}
+
+/// The commands processed by the segment writer thread
+enum SegmentWriterCommand {
+    WriteRecord(Vec<u8>),
+    StartNewSegment,
+    Flush(SyncSender<()>),
+}
+
+/// A handle to the thread that owns the segment writer. Dropping the handle
+/// waits for the thread to write all pending records.
+struct SegmentWriterHandle {
+    command_sender: Option<SyncSender<SegmentWriterCommand>>,
+    writer_thread: Option<JoinHandle<()>>,
+}
+
+impl SegmentWriterHandle {
+    fn spawn(segment_writer: SegmentWriter, max_pending_records: usize) -> anyhow::Result<Self> {
+        let (command_sender, command_receiver) = mpsc::sync_channel(max_pending_records);
+        let writer_thread = std::thread::Builder::new()
+            .name("flight-recorder".into())
+            .spawn(move || run_segment_writer(segment_writer, command_receiver))
+            .context("Failed to spawn the flight recorder writer thread")?;
+        Ok(Self {
+            command_sender: Some(command_sender),
+            writer_thread: Some(writer_thread),
+        })
+    }
+
+    /// Sends the command to the writer thread (without blocking)
+    fn send(&self, command: SegmentWriterCommand) -> anyhow::Result<()> {
+        let command_sender = self
+            .command_sender
+            .as_ref()
+            .context("The flight recorder writer has already been stopped")?;
+        match command_sender.try_send(command) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_)) => {
+                bail!("The flight recorder writer is falling behind! Dropping the command.")
+            },
+            Err(TrySendError::Disconnected(_)) => {
+                bail!("The flight recorder writer thread has stopped!")
+            },
+        }
+    }
+
+    fn flush(&self) -> anyhow::Result<()> {
+        let command_sender = self
+            .command_sender
+            .as_ref()
+            .context("The flight recorder writer has already been stopped")?;
+        let (flush_sender, flush_receiver) = mpsc::sync_channel(1);
+        command_sender
+            .send(SegmentWriterCommand::Flush(flush_sender))
+            .context("The flight recorder writer thread has stopped!")?;
+        flush_receiver
+            .recv()
+            .context("The flight recorder writer thread has stopped!")
+    }
+}
+
+impl Drop for SegmentWriterHandle {
+    fn drop(&mut self) {
+        // Close the channel and wait for the pending records to be written
+        self.command_sender.take();
+        if let Some(writer_thread) = self.writer_thread.take() {
+            if writer_thread.join().is_err() {
+                warn!("The flight recorder writer thread panicked!");
+            }
+        }
+    }
+}
+
+/// Processes the segment writer commands until the channel is closed
+fn run_segment_writer(
+    mut segment_writer: SegmentWriter,
+    command_receiver: Receiver<SegmentWriterCommand>,
+) {
+    for command in command_receiver {
+        let result = match command {
+            SegmentWriterCommand::WriteRecord(record_bytes) => {
+                segment_writer.write_record(&record_bytes)
+            },
+            SegmentWriterCommand::StartNewSegment => segment_writer.start_new_segment(),
+            SegmentWriterCommand::Flush(flush_sender) => {
+                let _ = flush_sender.send(());
+                Ok(())
+            },
+        };
+        if let Err(error) = result {
+            warn!("Failed to write to the flight recorder: {:?}", error);
+        }
+    }
+}
+
+/// Writes records to the segment files in the flight recorder directory
+struct SegmentWriter {
+    directory: PathBuf,
+    max_num_segments: usize,
+    segment_index: u64,
+    segment_file: File,
+}
+
+impl SegmentWriter {
+    fn new(directory: &Path, max_num_segments: usize) -> anyhow::Result<Self> {
+        fs::create_dir_all(directory).with_context(|| {
+            format!(
+                "Failed to create the flight recorder directory: {:?}",
+                directory
+            )
+        })?;
+
+        // Continue from the highest existing segment index
+        let segment_index = list_segments(directory)?
+            .last()
+            .map(|(segment_index, _)| segment_index + 1)
+            .unwrap_or(0);
+        let segment_file = create_segment_file(directory, segment_index)?;
+
+        let mut segment_writer = Self {
+            directory: directory.to_path_buf(),
+            max_num_segments,
+            segment_index,
+            segment_file,
+        };
+        segment_writer.delete_old_segments();
+        Ok(segment_writer)
+    }
+
+    /// Writes the (encoded) record to the current segment
+    fn write_record(&mut self, record_bytes: &[u8]) -> anyhow::Result<()> {
+        self.segment_file
+            .write_all(record_bytes)
+            .and_then(|_| self.segment_file.flush())
+            .context("Failed to write the record to the flight recorder segment")
+    }
+
+    fn start_new_segment(&mut self) -> anyhow::Result<()> {
+        self.segment_index += 1;
+        self.segment_file = create_segment_file(&self.directory, self.segment_index)?;
+        self.delete_old_segments();
+        Ok(())
+    }
+
+    /// Deletes the oldest segments so that at most max_num_segments are retained
+    fn delete_old_segments(&mut self) {
+        let segments = match list_segments(&self.directory) {
+            Ok(segments) => segments,
+            Err(error) => {
+                warn!("Failed to list the flight recorder segments: {:?}", error);
+                return;
+            },
+        };
+        let num_segments_to_delete = segments.len().saturating_sub(self.max_num_segments);
+        for (_, segment_path) in segments.into_iter().take(num_segments_to_delete) {
+            if let Err(error) = fs::remove_file(&segment_path) {
+                warn!(
+                    "Failed to delete the flight recorder segment {:?}: {:?}",
+                    segment_path, error
+                );
+            }
+        }
+    }
+}
+
+/// Encodes the record into its on-disk representation (length prefix and BCS bytes)
+pub fn encode_record(record: &FlightRecord) -> anyhow::Result<Vec<u8>> {
+    let bytes = bcs::to_bytes(record).context("Failed to serialize the flight record")?;
+    let length = u32::try_from(bytes.len()).context("The flight record is too large")?;
+
+    let mut record_bytes = Vec::with_capacity(RECORD_LENGTH_PREFIX_BYTES + bytes.len());
+    record_bytes.extend_from_slice(&length.to_le_bytes());
+    record_bytes.extend_from_slice(&bytes);
+    Ok(record_bytes)
+}
+
+/// Decodes all records from the given reader. A truncated record at the end of
+/// the input (e.g., caused by a crash mid-write) is ignored.
+pub fn decode_records<R: Read>(mut reader: R) -> anyhow::Result<Vec<FlightRecord>> {
+    let mut records = vec![];
+    loop {
+        let mut length_bytes = [0u8; RECORD_LENGTH_PREFIX_BYTES];
+        match reader.read_exact(&mut length_bytes) {
+            Ok(()) => {},
+            Err(error) if error.kind() == ErrorKind::UnexpectedEof => break,
+            Err(error) => return Err(error).context("Failed to read the flight record length"),
+        }
+
+        let mut record_bytes = vec![0u8; u32::from_le_bytes(length_bytes) as usize];
+        match reader.read_exact(&mut record_bytes) {
+            Ok(()) => {},
+            Err(error) if error.kind() == ErrorKind::UnexpectedEof => {
+                warn!(
+                    "Ignoring a truncated flight record after {} records!",
+                    records.len()
+                );
+                break;
+            },
+            Err(error) => return Err(error).context("Failed to read the flight record"),
+        }
+
+        let record = bcs::from_bytes(&record_bytes)
+            .with_context(|| format!("Failed to deserialize flight record {}", records.len()))?;
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Reads all records from the given segment file
+pub fn read_segment(segment_path: &Path) -> anyhow::Result<Vec<FlightRecord>> {
+    let segment_file = File::open(segment_path).with_context(|| {
+        format!(
+            "Failed to open the flight recorder segment: {:?}",
+            segment_path
+        )
+    })?;
+    decode_records(BufReader::new(segment_file))
+}
+
+/// Returns the segments in the given directory, sorted by segment index
+pub fn list_segments(directory: &Path) -> anyhow::Result<Vec<(u64, PathBuf)>> {
+    let mut segments = vec![];
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        let segment_index = path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .and_then(|file_name| file_name.strip_prefix(SEGMENT_FILE_PREFIX))
+            .and_then(|file_name| file_name.strip_suffix(SEGMENT_FILE_SUFFIX))
+            .and_then(|segment_index| segment_index.parse::<u64>().ok());
+        if let Some(segment_index) = segment_index {
+            segments.push((segment_index, path));
+        }
+    }
+    segments.sort();
+    Ok(segments)
+}
+
+fn create_segment_file(directory: &Path, segment_index: u64) -> anyhow::Result<File> {
+    let segment_path = directory.join(format!(
+        "{}{}{}",
+        SEGMENT_FILE_PREFIX, segment_index, SEGMENT_FILE_SUFFIX
+    ));
+    OpenOptions::new()
+        .create_new(true)
+        .write(true)
+        .open(&segment_path)
+        .with_context(|| {
+            format!(
+                "Failed to create the flight recorder segment: {:?}",
+                segment_path
+            )
+        })
+}
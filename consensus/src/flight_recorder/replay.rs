@@ -0,0 +1,608 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    block_storage::{pending_blocks::PendingBlocks, BlockStore},
+    counters,
+    epoch_manager::LivenessStorageData,
+    error::QuorumStoreError,
+    flight_recorder::{
+        recorder::{read_segment, FlightRecorder},
+        types::{FlightEvent, FlightRecord, RecordedDecision, RoundManagerCheckpoint},
+    },
+    liveness::{
+        proposal_generator::{
+            ChainHealthBackoffConfig, PipelineBackpressureConfig, ProposalGenerator,
+        },
+        proposer_election::ProposerElection,
+        round_state::{ExponentialTimeInterval, RoundState},
+    },
+    metrics_safety_rules::MetricsSafetyRules,
+    network::NetworkSender,
+    network_interface::{ConsensusNetworkClient, DIRECT_SEND, RPC},
+    payload_client::PayloadClient,
+    payload_manager::TPayloadManager,
+    persistent_liveness_storage::{
+        LedgerRecoveryData, PersistentLivenessStorage, RecoveryData, RootInfo, RootMetadata,
+    },
+    pipeline::execution_client::DummyExecutionClient,
+    round_manager::RoundManager,
+    util::time_service::ClockTimeService,
+};
+use anyhow::{bail, Result};
+use aptos_channels::{aptos_channel, message_queues::QueueStyle};
+use aptos_config::{
+    config::{ConsensusConfig, QcAggregatorType},
+    network_id::NetworkId,
+};
+use aptos_consensus_types::{
+    block::Block,
+    common::{Author, Payload, PayloadFilter, Round},
+    equivocation_evidence::EquivocationEvidence,
+    quorum_cert::QuorumCert,
+    safety_data::SafetyData,
+    timeout_2chain::TwoChainTimeoutCertificate,
+    vote::Vote,
+};
+use aptos_crypto::HashValue;
+use aptos_executor_types::ExecutorResult;
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_network::{
+    application::{interface::NetworkClient, storage::PeersAndMetadata},
+    peer_manager::{ConnectionRequestSender, PeerManagerRequestSender},
+    protocols::{network, network::NewNetworkSender},
+};
+use aptos_safety_rules::{PersistentSafetyStorage, SafetyRules, TSafetyRules};
+use aptos_secure_storage::{InMemoryStorage, Storage};
+use aptos_types::{
+    aggregate_signature::AggregateSignature,
+    block_info::BlockInfo,
+    epoch_change::EpochChangeProof,
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    on_chain_config::OnChainRandomnessConfig,
+    transaction::SignedTransaction,
+    validator_txn::ValidatorTransaction,
+    waypoint::Waypoint,
+};
+use aptos_validator_transaction_pool::TransactionFilter;
+use async_trait::async_trait;
+use clap::Parser;
+use futures::future::BoxFuture;
+use std::{
+    any::Any,
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::{Display, Formatter},
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
+
+#[derive(Parser)]
+#[clap(about = "Replay a consensus flight recorder segment through the round manager.")]
+pub struct Command {
+    #[clap(long, value_parser)]
+    pub segment: PathBuf,
+}
+
+impl Command {
+    pub async fn run(self) -> Result<()> {
+        let records = read_segment(&self.segment)?;
+        let replay_report = replay_recording(records).await?;
+        println!("{}", replay_report);
+
+        Ok(())
+    }
+}
+
+/// The result of replaying a flight recording
+pub struct ReplayReport {
+    pub num_events_replayed: usize,
+    pub num_event_errors: usize,
+    pub recorded_decisions: Vec<RecordedDecision>,
+    pub replayed_decisions: Vec<RecordedDecision>,
+}
+
+impl ReplayReport {
+    /// Returns the index of the first decision that differs between the
+    /// recording and the replay (or None, if the replay was deterministic).
+    pub fn get_first_divergence(&self) -> Option<usize> {
+        let num_decisions = self
+            .recorded_decisions
+            .len()
+            .max(self.replayed_decisions.len());
+        (0..num_decisions).find(|index| {
+            self.recorded_decisions.get(*index) != self.replayed_decisions.get(*index)
+        })
+    }
+}
+
+impl Display for ReplayReport {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Replayed {} events ({} returned errors), recorded decisions: {}, replayed decisions: {}",
+            self.num_events_replayed,
+            self.num_event_errors,
+            self.recorded_decisions.len(),
+            self.replayed_decisions.len()
+        )?;
+        match self.get_first_divergence() {
+            None => write!(f, "The replay matches the recording!"),
+            Some(index) => {
+                let display_decision = |decision: Option<&RecordedDecision>| {
+                    decision.map_or("None".into(), |decision| decision.to_string())
+                };
+                write!(
+                    f,
+                    "The replay diverges at decision {}! Recorded: {}, replayed: {}",
+                    index,
+                    display_decision(self.recorded_decisions.get(index)),
+                    display_decision(self.replayed_decisions.get(index))
+                )
+            },
+        }
+    }
+}
+
+/// Replays the given recording (which must start with a checkpoint) through a
+/// round manager with mocked network and storage, and compares the decisions
+/// made during the replay against the recorded decisions.
+///
+/// Note: safety rules are re-run from the checkpointed safety data, but the
+/// consensus key is not available during replay, so the replayed votes and
+/// timeouts are signed with a throwaway key (only the decisions are compared).
+pub async fn replay_recording(records: Vec<FlightRecord>) -> Result<ReplayReport> {
+    let mut records = records.into_iter();
+    let checkpoint = match records.next() {
+        Some(FlightRecord {
+            event: FlightEvent::Checkpoint(checkpoint),
+            ..
+        }) => checkpoint,
+        _ => bail!("The flight recording must start with a checkpoint!"),
+    };
+    let records: Vec<FlightRecord> = records.collect();
+
+    // Create the round manager from the checkpoint. Note: the recording starts
+    // after init() was called, so the decisions made by init() are not compared.
+    let last_vote = checkpoint.last_vote.clone();
+    let (mut round_manager, _receivers) = create_replay_round_manager(*checkpoint, &records)?;
+    round_manager.init(last_vote).await;
+    round_manager.set_flight_recorder(FlightRecorder::new_memory_recorder());
+
+    // Replay all the received events through the round manager
+    let mut num_events_replayed = 0;
+    let mut num_event_errors = 0;
+    for record in &records {
+        let result = match record.event.clone() {
+            FlightEvent::ProposalReceived(proposal_msg) => {
+                round_manager.process_proposal_msg(*proposal_msg).await
+            },
+            FlightEvent::DelayedProposalReceived(proposal) => {
+                round_manager.process_delayed_proposal_msg(*proposal).await
+            },
+            FlightEvent::ProposalPayloadAvailable(proposal) => {
+                round_manager
+                    .process_proposal_with_available_payload(*proposal)
+                    .await
+            },
+            FlightEvent::VoteReceived(vote_msg) => round_manager.process_vote_msg(*vote_msg).await,
+            FlightEvent::OrderVoteReceived(order_vote_msg) => {
+                round_manager.process_order_vote_msg(*order_vote_msg).await
+            },
+            FlightEvent::DelayedQcReceived(delayed_qc_msg) => {
+                round_manager.process_delayed_qc_msg(*delayed_qc_msg).await
+            },
+            FlightEvent::SyncInfoReceived(sync_info, peer) => {
+                round_manager.process_sync_info_msg(*sync_info, peer).await
+            },
+            FlightEvent::LocalTimeout(round) => round_manager.process_local_timeout(round).await,
+            FlightEvent::Checkpoint(_)
+            | FlightEvent::NewRound { .. }
+            | FlightEvent::SafetyRulesDecision(_) => continue,
+        };
+
+        num_events_replayed += 1;
+        if let Err(error) = result {
+            num_event_errors += 1;
+            debug!(
+                "Replayed {} event (round {}) returned an error: {:?}",
+                record.event.get_label(),
+                record.round,
+                error
+            );
+        }
+    }
+
+    // Collect the recorded and replayed decisions
+    let replayed_records = round_manager
+        .take_flight_recorder()
+        .map(|mut flight_recorder| flight_recorder.take_records())
+        .unwrap_or_default();
+    Ok(ReplayReport {
+        num_events_replayed,
+        num_event_errors,
+        recorded_decisions: records
+            .iter()
+            .filter_map(FlightRecord::get_decision)
+            .collect(),
+        replayed_decisions: replayed_records
+            .iter()
+            .filter_map(FlightRecord::get_decision)
+            .collect(),
+    })
+}
+
+/// Creates a round manager (with mocked dependencies) from the given checkpoint.
+/// Also returns the receivers of the mocked channels, which must be kept alive.
+fn create_replay_round_manager(
+    checkpoint: RoundManagerCheckpoint,
+    records: &[FlightRecord],
+) -> Result<(RoundManager, Vec<Box<dyn Any + Send>>)> {
+    let RoundManagerCheckpoint {
+        author,
+        epoch_state,
+        onchain_config,
+        randomness_config,
+        jwk_consensus_config,
+        features,
+        max_receiving_block_txns,
+        max_receiving_block_bytes,
+        vote_back_pressure_limit,
+        sync_only,
+        root_block,
+        root_quorum_cert,
+        root_commit_cert,
+        blocks,
+        quorum_certs,
+        highest_2chain_timeout_cert,
+        last_vote,
+        safety_data,
+    } = checkpoint;
+    let config = ConsensusConfig {
+        max_receiving_block_txns,
+        max_receiving_block_bytes,
+        vote_back_pressure_limit,
+        sync_only,
+        ..ConsensusConfig::default()
+    };
+    let randomness_config = OnChainRandomnessConfig::try_from(randomness_config)?;
+    let (safety_rules, epoch_change_proof) =
+        create_replay_safety_rules(author, &epoch_state, safety_data)?;
+    let epoch_state = Arc::new(epoch_state);
+    let mut receivers: Vec<Box<dyn Any + Send>> = vec![];
+
+    // Create the block store from the checkpointed block tree
+    let storage = Arc::new(ReplayStorage::new(
+        root_commit_cert.ledger_info().clone(),
+        epoch_change_proof,
+    ));
+    let root_metadata = RootMetadata {
+        accu_hash: root_quorum_cert.certified_block().executed_state_id(),
+        frozen_root_hashes: vec![],
+        num_leaves: root_quorum_cert.certified_block().version() + 1,
+    };
+    let recovery_data = RecoveryData::new_with_root(
+        last_vote,
+        RootInfo(
+            Box::new(root_block),
+            root_quorum_cert,
+            root_commit_cert.clone(),
+            root_commit_cert,
+        ),
+        root_metadata,
+        blocks,
+        quorum_certs,
+        highest_2chain_timeout_cert,
+    );
+    let time_service = Arc::new(ClockTimeService::new(tokio::runtime::Handle::current()));
+    let block_store = Arc::new(BlockStore::new(
+        storage.clone(),
+        recovery_data,
+        Arc::new(DummyExecutionClient),
+        config.max_pruned_blocks_in_mem,
+        time_service.clone(),
+        config.vote_back_pressure_limit,
+        Arc::new(ReplayPayloadManager::new(records)),
+        onchain_config.order_vote_enabled(),
+        Arc::new(Mutex::new(PendingBlocks::new())),
+    ));
+
+    // Create a proposal generator (proposals are never generated during replay)
+    let proposal_generator = ProposalGenerator::new(
+        author,
+        block_store.clone(),
+        Arc::new(ReplayPayloadClient {}),
+        time_service.clone(),
+        Duration::from_millis(config.quorum_store_poll_time_ms),
+        config.max_sending_block_txns,
+        config.max_sending_block_txns_after_filtering,
+        config.max_sending_block_bytes,
+        config.max_sending_inline_txns,
+        config.max_sending_inline_bytes,
+        onchain_config.max_failed_authors_to_store(),
+        config.min_max_txns_in_block_after_filtering_from_backpressure,
+        PipelineBackpressureConfig::new_no_backoff(),
+        ChainHealthBackoffConfig::new_no_backoff(),
+        onchain_config.quorum_store_enabled(),
+        onchain_config.effective_validator_txn_config(),
+        config.quorum_store.allow_batches_without_pos_in_proposal,
+    );
+
+    // Create the round state
+    let (timeout_sender, timeout_receiver) =
+        aptos_channels::new(1_024, &counters::PENDING_ROUND_TIMEOUTS);
+    let (delayed_qc_tx, delayed_qc_rx) = futures::channel::mpsc::unbounded();
+    receivers.push(Box::new(timeout_receiver));
+    receivers.push(Box::new(delayed_qc_rx));
+    let round_state = RoundState::new(
+        Box::new(ExponentialTimeInterval::new(
+            Duration::from_millis(config.round_initial_timeout_ms),
+            config.round_timeout_backoff_exponent_base,
+            config.round_timeout_backoff_max_exponent,
+        )),
+        time_service,
+        timeout_sender,
+        delayed_qc_tx,
+        QcAggregatorType::NoDelay,
+    );
+
+    // Create a network sender that never delivers any messages
+    let (network_reqs_tx, network_reqs_rx) = aptos_channel::new(QueueStyle::FIFO, 8, None);
+    let (connection_reqs_tx, connection_reqs_rx) = aptos_channel::new(QueueStyle::FIFO, 8, None);
+    receivers.push(Box::new(network_reqs_rx));
+    receivers.push(Box::new(connection_reqs_rx));
+    let network_client = NetworkClient::new(
+        DIRECT_SEND.into(),
+        RPC.into(),
+        HashMap::from([(
+            NetworkId::Validator,
+            network::NetworkSender::new(
+                PeerManagerRequestSender::new(network_reqs_tx),
+                ConnectionRequestSender::new(connection_reqs_tx),
+            ),
+        )]),
+        PeersAndMetadata::new(&[NetworkId::Validator]),
+    );
+    let (self_sender, self_receiver) =
+        aptos_channels::new_unbounded(&counters::PENDING_SELF_MESSAGES);
+    receivers.push(Box::new(self_receiver));
+    let network = Arc::new(NetworkSender::new(
+        author,
+        ConsensusNetworkClient::new(network_client),
+        self_sender,
+        epoch_state.verifier.clone(),
+    ));
+    let (buffered_proposal_tx, buffered_proposal_rx) =
+        aptos_channel::new(QueueStyle::KLAST, 10, None);
+    receivers.push(Box::new(buffered_proposal_rx));
+
+    // Create the round manager
+    let safety_rules = MetricsSafetyRules::new(Box::new(safety_rules), storage.clone());
+    let round_manager = RoundManager::new(
+        epoch_state,
+        block_store,
+        round_state,
+        Arc::new(RecordedProposerElection::new(records)),
+        proposal_generator,
+        Arc::new(Mutex::new(safety_rules)),
+        network,
+        storage,
+        onchain_config,
+        buffered_proposal_tx,
+        config,
+        randomness_config,
+        jwk_consensus_config,
+        features,
+        None,
+    );
+    Ok((round_manager, receivers))
+}
+
+/// Creates safety rules that start from the checkpointed safety data, so that all
+/// safety checks are re-run during replay. Also returns the (locally created)
+/// epoch change proof used to initialize the safety rules.
+fn create_replay_safety_rules(
+    author: Author,
+    epoch_state: &EpochState,
+    safety_data: SafetyData,
+) -> Result<(SafetyRules, EpochChangeProof)> {
+    // Create an epoch change ledger info that starts the checkpointed epoch. The
+    // waypoint is derived from the ledger info, so no signatures are required.
+    let block_info = BlockInfo::new(
+        epoch_state.epoch.saturating_sub(1),
+        0,
+        HashValue::zero(),
+        HashValue::zero(),
+        0,
+        0,
+        Some(epoch_state.clone()),
+    );
+    let ledger_info = LedgerInfo::new(block_info, HashValue::zero());
+    let waypoint = Waypoint::new_epoch_boundary(&ledger_info)?;
+    let epoch_change_proof = EpochChangeProof::new(
+        vec![LedgerInfoWithSignatures::new(
+            ledger_info,
+            AggregateSignature::empty(),
+        )],
+        false,
+    );
+
+    // Create the safety rules from the checkpointed safety data
+    let mut persistent_storage = PersistentSafetyStorage::initialize_without_consensus_key(
+        Storage::from(InMemoryStorage::new()),
+        author,
+        waypoint,
+        true,
+    );
+    persistent_storage.set_safety_data(safety_data)?;
+    let mut safety_rules = SafetyRules::new_for_replay(persistent_storage);
+    safety_rules.initialize(&epoch_change_proof)?;
+
+    Ok((safety_rules, epoch_change_proof))
+}
+
+/// A proposer election that returns the proposers observed in the recording
+/// (i.e., the proposers of new rounds, received proposals and failed authors).
+struct RecordedProposerElection {
+    proposers: BTreeMap<Round, Author>,
+}
+
+impl RecordedProposerElection {
+    fn new(records: &[FlightRecord]) -> Self {
+        let mut proposers = BTreeMap::new();
+        let mut add_block_proposers = |block: &Block| {
+            if let Some(author) = block.author() {
+                proposers.insert(block.round(), author);
+            }
+            for (round, author) in block.block_data().failed_authors().into_iter().flatten() {
+                proposers.insert(*round, *author);
+            }
+        };
+        for record in records {
+            match &record.event {
+                FlightEvent::ProposalReceived(proposal_msg) => {
+                    add_block_proposers(proposal_msg.proposal())
+                },
+                FlightEvent::DelayedProposalReceived(proposal)
+                | FlightEvent::ProposalPayloadAvailable(proposal) => add_block_proposers(proposal),
+                _ => {},
+            }
+        }
+
+        // The proposers of new rounds take precedence
+        for record in records {
+            if let FlightEvent::NewRound { proposer } = &record.event {
+                proposers.insert(record.round, *proposer);
+            }
+        }
+        Self { proposers }
+    }
+}
+
+impl ProposerElection for RecordedProposerElection {
+    fn get_valid_proposer(&self, round: Round) -> Author {
+        self.proposers.get(&round).copied().unwrap_or(Author::ZERO)
+    }
+}
+
+/// A liveness storage that discards all writes during replay (it isn't backed by any
+/// databases, so it doesn't implement `LivenessStorageDbs`)
+struct ReplayStorage {
+    commit_ledger_info: LedgerInfoWithSignatures,
+    epoch_change_proof: EpochChangeProof,
+}
+
+impl ReplayStorage {
+    fn new(
+        commit_ledger_info: LedgerInfoWithSignatures,
+        epoch_change_proof: EpochChangeProof,
+    ) -> Self {
+        Self {
+            commit_ledger_info,
+            epoch_change_proof,
+        }
+    }
+}
+
+impl PersistentLivenessStorage for ReplayStorage {
+    fn save_tree(&self, _blocks: Vec<Block>, _quorum_certs: Vec<QuorumCert>) -> Result<()> {
+        Ok(())
+    }
+
+    fn prune_tree(&self, _block_ids: Vec<HashValue>) -> Result<()> {
+        Ok(())
+    }
+
+    fn save_vote(&self, _vote: &Vote) -> Result<()> {
+        Ok(())
+    }
+
+    fn recover_from_ledger(&self) -> LedgerRecoveryData {
+        LedgerRecoveryData::new(self.commit_ledger_info.clone())
+    }
+
+    fn start(&self, _order_vote_enabled: bool) -> LivenessStorageData {
+        LivenessStorageData::PartialRecoveryData(self.recover_from_ledger())
+    }
+
+    fn save_highest_2chain_timeout_cert(
+        &self,
+        _highest_timeout_cert: &TwoChainTimeoutCertificate,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn save_equivocation_evidence(&self, _evidence: &EquivocationEvidence) -> Result<()> {
+        Ok(())
+    }
+
+    fn retrieve_epoch_change_proof(&self, _version: u64) -> Result<EpochChangeProof> {
+        Ok(self.epoch_change_proof.clone())
+    }
+}
+
+/// A payload manager that reports the payloads as missing for the proposals that
+/// were waiting on their payloads in the recording (and available otherwise).
+struct ReplayPayloadManager {
+    delayed_proposal_ids: HashSet<HashValue>,
+}
+
+impl ReplayPayloadManager {
+    fn new(records: &[FlightRecord]) -> Self {
+        let delayed_proposal_ids = records
+            .iter()
+            .filter_map(|record| match &record.event {
+                FlightEvent::ProposalPayloadAvailable(proposal) => Some(proposal.id()),
+                _ => None,
+            })
+            .collect();
+        Self {
+            delayed_proposal_ids,
+        }
+    }
+}
+
+#[async_trait]
+impl TPayloadManager for ReplayPayloadManager {
+    fn notify_commit(&self, _block_timestamp: u64, _payloads: Vec<Payload>) {}
+
+    fn prefetch_payload_data(&self, _payload: &Payload, _timestamp: u64) {}
+
+    fn check_payload_availability(&self, block: &Block) -> bool {
+        !self.delayed_proposal_ids.contains(&block.id())
+    }
+
+    async fn get_transactions(
+        &self,
+        _block: &Block,
+    ) -> ExecutorResult<(Vec<SignedTransaction>, Option<u64>)> {
+        Ok((vec![], None))
+    }
+}
+
+/// A payload client that never returns payloads (proposals aren't generated during replay)
+struct ReplayPayloadClient {}
+
+#[async_trait]
+impl PayloadClient for ReplayPayloadClient {
+    async fn pull_payload(
+        &self,
+        _max_poll_time: Duration,
+        _max_items: u64,
+        _max_unique_items: u64,
+        _max_bytes: u64,
+        _max_inline_items: u64,
+        _max_inline_bytes: u64,
+        _validator_txn_filter: TransactionFilter,
+        _user_txn_filter: PayloadFilter,
+        _wait_callback: BoxFuture<'static, ()>,
+        _pending_ordering: bool,
+        _pending_uncommitted_blocks: usize,
+        _recent_max_fill_fraction: f32,
+        _block_timestamp: Duration,
+    ) -> Result<(Vec<ValidatorTransaction>, Payload), QuorumStoreError> {
+        Err(anyhow::anyhow!("Payloads are not available during replay!").into())
+    }
+}
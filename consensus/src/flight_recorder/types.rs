@@ -0,0 +1,232 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_consensus_types::{
+    block::Block,
+    common::{Author, Round},
+    delayed_qc_msg::DelayedQcMsg,
+    order_vote::OrderVote,
+    order_vote_msg::OrderVoteMsg,
+    proposal_msg::ProposalMsg,
+    quorum_cert::QuorumCert,
+    safety_data::SafetyData,
+    sync_info::SyncInfo,
+    timeout_2chain::TwoChainTimeoutCertificate,
+    vote::Vote,
+    vote_msg::VoteMsg,
+    wrapped_ledger_info::WrappedLedgerInfo,
+};
+use aptos_crypto::{bls12381, HashValue};
+use aptos_safety_rules::Error as SafetyRulesError;
+use aptos_types::{
+    epoch_state::EpochState,
+    on_chain_config::{
+        Features, OnChainConsensusConfig, OnChainJWKConsensusConfig, RandomnessConfigMoveStruct,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// A single entry in the flight recording
+#[derive(Clone, Deserialize, Serialize)]
+pub struct FlightRecord {
+    pub epoch: u64,
+    pub round: Round, // The round of the round manager when the event was recorded
+    pub timestamp_usecs: u64,
+    pub event: FlightEvent,
+}
+
+impl FlightRecord {
+    pub fn new(epoch: u64, round: Round, event: FlightEvent) -> Self {
+        Self {
+            epoch,
+            round,
+            timestamp_usecs: aptos_infallible::duration_since_epoch().as_micros() as u64,
+            event,
+        }
+    }
+
+    /// Returns the decision made by the node (if this record holds one)
+    pub fn get_decision(&self) -> Option<RecordedDecision> {
+        match &self.event {
+            FlightEvent::NewRound { proposer } => Some(RecordedDecision::NewRound {
+                round: self.round,
+                proposer: *proposer,
+            }),
+            FlightEvent::SafetyRulesDecision(decision) => Some(decision.into()),
+            _ => None,
+        }
+    }
+}
+
+/// The events captured by the flight recorder. Received messages are replayed
+/// through the round manager, while decisions are compared against the replay.
+#[derive(Clone, Deserialize, Serialize)]
+pub enum FlightEvent {
+    // The state of the round manager (written at the start of every segment)
+    Checkpoint(Box<RoundManagerCheckpoint>),
+
+    // Events processed by the round manager
+    ProposalReceived(Box<ProposalMsg>),
+    DelayedProposalReceived(Box<Block>),
+    ProposalPayloadAvailable(Box<Block>),
+    VoteReceived(Box<VoteMsg>),
+    OrderVoteReceived(Box<OrderVoteMsg>),
+    DelayedQcReceived(Box<DelayedQcMsg>),
+    SyncInfoReceived(Box<SyncInfo>, Author),
+    LocalTimeout(Round),
+
+    // Decisions made by the round manager
+    NewRound { proposer: Author },
+    SafetyRulesDecision(SafetyRulesDecision),
+}
+
+impl FlightEvent {
+    /// Returns a short label for the event (e.g., for logging)
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            FlightEvent::Checkpoint(_) => "checkpoint",
+            FlightEvent::ProposalReceived(_) => "proposal_received",
+            FlightEvent::DelayedProposalReceived(_) => "delayed_proposal_received",
+            FlightEvent::ProposalPayloadAvailable(_) => "proposal_payload_available",
+            FlightEvent::VoteReceived(_) => "vote_received",
+            FlightEvent::OrderVoteReceived(_) => "order_vote_received",
+            FlightEvent::DelayedQcReceived(_) => "delayed_qc_received",
+            FlightEvent::SyncInfoReceived(_, _) => "sync_info_received",
+            FlightEvent::LocalTimeout(_) => "local_timeout",
+            FlightEvent::NewRound { .. } => "new_round",
+            FlightEvent::SafetyRulesDecision(_) => "safety_rules_decision",
+        }
+    }
+}
+
+/// The results returned by the local safety rules. During replay, safety rules
+/// are re-run (from the checkpointed safety data) and the results are compared.
+#[derive(Clone, Deserialize, Serialize)]
+pub enum SafetyRulesDecision {
+    Vote {
+        block_id: HashValue,
+        round: Round,
+        result: Result<Vote, SafetyRulesError>,
+    },
+    TimeoutSignature {
+        round: Round,
+        result: Result<bls12381::Signature, SafetyRulesError>,
+    },
+    OrderVote {
+        block_id: HashValue,
+        round: Round,
+        result: Result<OrderVote, SafetyRulesError>,
+    },
+}
+
+/// A compact (and comparable) summary of a decision made by the node
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RecordedDecision {
+    NewRound {
+        round: Round,
+        proposer: Author,
+    },
+    Vote {
+        block_id: HashValue,
+        round: Round,
+        succeeded: bool,
+    },
+    TimeoutSignature {
+        round: Round,
+        succeeded: bool,
+    },
+    OrderVote {
+        block_id: HashValue,
+        round: Round,
+        succeeded: bool,
+    },
+}
+
+impl From<&SafetyRulesDecision> for RecordedDecision {
+    fn from(decision: &SafetyRulesDecision) -> Self {
+        match decision {
+            SafetyRulesDecision::Vote {
+                block_id,
+                round,
+                result,
+            } => RecordedDecision::Vote {
+                block_id: *block_id,
+                round: *round,
+                succeeded: result.is_ok(),
+            },
+            SafetyRulesDecision::TimeoutSignature { round, result } => {
+                RecordedDecision::TimeoutSignature {
+                    round: *round,
+                    succeeded: result.is_ok(),
+                }
+            },
+            SafetyRulesDecision::OrderVote {
+                block_id,
+                round,
+                result,
+            } => RecordedDecision::OrderVote {
+                block_id: *block_id,
+                round: *round,
+                succeeded: result.is_ok(),
+            },
+        }
+    }
+}
+
+impl Display for RecordedDecision {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            RecordedDecision::NewRound { round, proposer } => {
+                write!(f, "NewRound(round: {}, proposer: {})", round, proposer)
+            },
+            RecordedDecision::Vote {
+                block_id,
+                round,
+                succeeded,
+            } => write!(
+                f,
+                "Vote(block: {}, round: {}, succeeded: {})",
+                block_id, round, succeeded
+            ),
+            RecordedDecision::TimeoutSignature { round, succeeded } => write!(
+                f,
+                "TimeoutSignature(round: {}, succeeded: {})",
+                round, succeeded
+            ),
+            RecordedDecision::OrderVote {
+                block_id,
+                round,
+                succeeded,
+            } => write!(
+                f,
+                "OrderVote(block: {}, round: {}, succeeded: {})",
+                block_id, round, succeeded
+            ),
+        }
+    }
+}
+
+/// The state required to recreate the round manager during replay. The block tree
+/// is rooted at the commit root, and the blocks are sorted by round.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct RoundManagerCheckpoint {
+    pub author: Author,
+    pub epoch_state: EpochState,
+    pub onchain_config: OnChainConsensusConfig,
+    pub randomness_config: RandomnessConfigMoveStruct,
+    pub jwk_consensus_config: OnChainJWKConsensusConfig,
+    pub features: Features,
+    pub max_receiving_block_txns: u64,
+    pub max_receiving_block_bytes: u64,
+    pub vote_back_pressure_limit: u64,
+    pub sync_only: bool,
+    pub root_block: Block,
+    pub root_quorum_cert: QuorumCert,
+    pub root_commit_cert: WrappedLedgerInfo,
+    pub blocks: Vec<Block>,
+    pub quorum_certs: Vec<QuorumCert>,
+    pub highest_2chain_timeout_cert: Option<TwoChainTimeoutCertificate>,
+    pub last_vote: Option<Vote>,
+    pub safety_data: SafetyData,
+}
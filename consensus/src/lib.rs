@@ -22,6 +22,7 @@ mod consensusdb;
 mod dag;
 mod epoch_manager;
 mod error;
+pub mod flight_recorder;
 mod liveness;
 mod logging;
 mod metrics_safety_rules;
@@ -68,9 +69,16 @@ use aptos_metrics_core::IntGauge;
 pub use consensusdb::create_checkpoint;
 /// Required by the smoke tests
 pub use consensusdb::CONSENSUS_DB_NAME;
+/// Required by the admin service
+pub use dag::{DagInspector, DagSnapshot};
 pub use quorum_store::quorum_store_db::QUORUM_STORE_DB_NAME;
+/// Required by the node, to share the randomness proofs with the REST API
+pub use rand::rand_gen::storage::db::RandDb;
 #[cfg(feature = "fuzzing")]
 pub use round_manager::round_manager_fuzzing;
+/// Required by the transaction shuffler benchmarks
+#[cfg(feature = "fuzzing")]
+pub use transaction_shuffler::{create_transaction_shuffler, TransactionShuffler};
 
 struct IntGaugeGuard {
     gauge: IntGauge,
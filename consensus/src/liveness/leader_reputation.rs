@@ -22,6 +22,7 @@ use aptos_logger::prelude::*;
 use aptos_storage_interface::DbReader;
 use aptos_types::{
     account_config::NewBlockEvent, epoch_change::EpochChangeProof, epoch_state::EpochState,
+    on_chain_config::LeaderReputationType,
 };
 use std::{
     cmp::max,
@@ -551,6 +552,41 @@ impl ReputationHeuristic for ProposerAndVoterHeuristic {
     }
 }
 
+/// Creates the reputation heuristic for the given config, and returns it together with the
+/// history window size it needs, whether to weight by voting power, and the maximum number
+/// of previous epochs to use history from.
+pub fn create_reputation_heuristic(
+    author: Author,
+    leader_reputation_type: &LeaderReputationType,
+    num_proposers: usize,
+) -> (Box<dyn ReputationHeuristic>, usize, bool, u32) {
+    match leader_reputation_type {
+        LeaderReputationType::ProposerAndVoter(proposer_and_voter_config)
+        | LeaderReputationType::ProposerAndVoterV2(proposer_and_voter_config) => {
+            let proposer_window_size =
+                num_proposers * proposer_and_voter_config.proposer_window_num_validators_multiplier;
+            let voter_window_size =
+                num_proposers * proposer_and_voter_config.voter_window_num_validators_multiplier;
+            let heuristic: Box<dyn ReputationHeuristic> = Box::new(ProposerAndVoterHeuristic::new(
+                author,
+                proposer_and_voter_config.active_weight,
+                proposer_and_voter_config.inactive_weight,
+                proposer_and_voter_config.failed_weight,
+                proposer_and_voter_config.failure_threshold_percent,
+                voter_window_size,
+                proposer_window_size,
+                leader_reputation_type.use_reputation_window_from_stale_end(),
+            ));
+            (
+                heuristic,
+                std::cmp::max(proposer_window_size, voter_window_size),
+                proposer_and_voter_config.weight_by_voting_power,
+                proposer_and_voter_config.use_history_from_previous_epoch_max_count,
+            )
+        },
+    }
+}
+
 /// Committed history based proposer election implementation that could help bias towards
 /// successful leaders to help improve performance.
 pub struct LeaderReputation {
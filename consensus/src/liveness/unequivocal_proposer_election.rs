@@ -2,22 +2,27 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::proposer_election::ProposerElection;
+use crate::counters;
 use aptos_consensus_types::{
     block::Block,
     common::{Author, Round},
+    equivocation_evidence::EquivocationEvidence,
 };
-use aptos_crypto::HashValue;
 use aptos_infallible::Mutex;
 use aptos_logger::{error, warn, SecurityEvent};
+use aptos_types::equivocation::EquivocationKind;
 use std::{cmp::Ordering, sync::Arc};
 
 // Wrapper around ProposerElection.
 //
 // Provides is_valid_proposal that remembers, and rejects if
-// the same leader proposes multiple blocks.
+// the same leader proposes multiple blocks. The first proposal of the
+// latest round is kept, so that the evidence of an equivocating leader
+// (i.e., both signed proposals) can be collected.
 pub struct UnequivocalProposerElection {
     proposer_election: Arc<dyn ProposerElection + Send + Sync>,
-    already_proposed: Mutex<(Round, HashValue)>,
+    already_proposed: Mutex<Option<Block>>,
+    equivocation_evidence: Mutex<Option<EquivocationEvidence>>,
 }
 
 impl ProposerElection for UnequivocalProposerElection {
@@ -35,10 +40,17 @@ impl UnequivocalProposerElection {
     pub fn new(proposer_election: Arc<dyn ProposerElection + Send + Sync>) -> Self {
         Self {
             proposer_election,
-            already_proposed: Mutex::new((0, HashValue::zero())),
+            already_proposed: Mutex::new(None),
+            equivocation_evidence: Mutex::new(None),
         }
     }
 
+    /// Returns (and clears) the evidence of the last equivocating proposal rejected
+    /// by `is_valid_proposal`
+    pub fn take_equivocation_evidence(&self) -> Option<EquivocationEvidence> {
+        self.equivocation_evidence.lock().take()
+    }
+
     // Return if a given proposed block is valid:
     // - if a given author is a valid candidate for being a proposer
     // - if this is the first block proposer has submitted in this round
@@ -59,27 +71,35 @@ impl UnequivocalProposerElection {
                 return false;
             }
             let mut already_proposed = self.already_proposed.lock();
+            let already_proposed_round = already_proposed.as_ref().map_or(0, |b| b.round());
             // detect if the leader proposes more than once in this round
-            match block.round().cmp(&already_proposed.0) {
+            match block.round().cmp(&already_proposed_round) {
                 Ordering::Greater => {
-                    already_proposed.0 = block.round();
-                    already_proposed.1 = block.id();
+                    *already_proposed = Some(block.clone());
                     true
                 },
-                Ordering::Equal => {
-                    if already_proposed.1 != block.id() {
+                Ordering::Equal => match already_proposed.as_ref() {
+                    Some(first_proposal) if first_proposal.id() == block.id() => true,
+                    Some(first_proposal) => {
                         error!(
                             SecurityEvent::InvalidConsensusProposal,
                             "Multiple proposals from {} for round {}: {} and {}",
                             author,
                             block.round(),
-                            already_proposed.1,
+                            first_proposal.id(),
                             block.id()
                         );
+                        counters::EQUIVOCATION_EVIDENCE_COLLECTED
+                            .with_label_values(&[EquivocationKind::Proposal.as_str()])
+                            .inc();
+                        *self.equivocation_evidence.lock() =
+                            Some(EquivocationEvidence::conflicting_proposals(
+                                first_proposal.clone(),
+                                block.clone(),
+                            ));
                         false
-                    } else {
-                        true
-                    }
+                    },
+                    None => false,
                 },
                 Ordering::Less => false,
             }
@@ -6,6 +6,7 @@ use crate::liveness::unequivocal_proposer_election::UnequivocalProposerElection;
 use aptos_consensus_types::{
     block::{block_test_utils::certificate_for_genesis, Block},
     common::{Author, Payload, Round},
+    equivocation_evidence::EquivocationEvidence,
 };
 use aptos_types::validator_signer::ValidatorSigner;
 use std::{collections::HashMap, sync::Arc};
@@ -91,9 +92,19 @@ fn test_is_valid_proposal() {
     assert!(pe.is_valid_proposer(chosen_author, 1));
     assert!(pe.is_valid_proposal(&good_proposal));
     assert!(!pe.is_valid_proposal(&bad_author_proposal));
+    assert_eq!(pe.take_equivocation_evidence(), None);
 
-    // another proposal from the valid proposer should fail
+    // another proposal from the valid proposer should fail, and both proposals
+    // are kept as evidence
     assert!(!pe.is_valid_proposal(&bad_duplicate_proposal));
+    assert_eq!(
+        pe.take_equivocation_evidence(),
+        Some(EquivocationEvidence::conflicting_proposals(
+            good_proposal.clone(),
+            bad_duplicate_proposal
+        ))
+    );
+    assert_eq!(pe.take_equivocation_evidence(), None);
     // good proposal still passes
     assert!(pe.is_valid_proposal(&good_proposal));
 
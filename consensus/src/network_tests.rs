@@ -77,6 +77,8 @@ pub struct NetworkPlayground {
     drop_config: Arc<RwLock<DropConfig>>,
     /// Allow test code to drop direct-send messages between peers per round.
     drop_config_round: DropConfigRound,
+    /// Allow test code to drop or delay specific direct-send messages per round.
+    message_rules_round: MessageRulesRound,
     /// An executor for spawning node outbound network event handlers
     executor: Handle,
     /// Maps authors to twins IDs
@@ -97,6 +99,7 @@ impl NetworkPlayground {
             timeout_config: Arc::new(RwLock::new(TimeoutConfig::default())),
             drop_config: Arc::new(RwLock::new(DropConfig::default())),
             drop_config_round: DropConfigRound::default(),
+            message_rules_round: MessageRulesRound::default(),
             executor,
             author_to_twin_ids: Arc::new(RwLock::new(AuthorToTwinIds::default())),
             peers_and_metadata: PeersAndMetadata::new(&[NetworkId::Validator]),
@@ -224,12 +227,14 @@ impl NetworkPlayground {
 
     /// Deliver a `PeerManagerRequest` from peer `src` to the destination peer.
     /// Returns a copy of the delivered message and the sending peer id, and
-    /// whether the message was successfully delivered
+    /// whether the message was successfully delivered. If a delay is given,
+    /// the message is pushed to the destination in the background.
     async fn deliver_message(
         &mut self,
         src_twin_id: TwinId,
         dst_twin_id: TwinId,
         rmsg: ReceivedMessage,
+        delay: Option<Duration>,
     ) -> (Author, ConsensusMsg) {
         let node_consensus_tx = self
             .node_consensus_txs
@@ -252,10 +257,23 @@ impl NetworkPlayground {
                 );
             },
         };
-        let _ = node_consensus_tx.push(
-            (src_twin_id.author, ProtocolId::ConsensusDirectSendBcs),
-            rmsg,
-        );
+        match delay {
+            Some(delay) => {
+                self.executor.spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    let _ = node_consensus_tx.push(
+                        (src_twin_id.author, ProtocolId::ConsensusDirectSendBcs),
+                        rmsg,
+                    );
+                });
+            },
+            None => {
+                let _ = node_consensus_tx.push(
+                    (src_twin_id.author, ProtocolId::ConsensusDirectSendBcs),
+                    rmsg,
+                );
+            },
+        }
         (source_address, consensus_msg)
     }
 
@@ -291,7 +309,9 @@ impl NetworkPlayground {
 
             let dst_twin_ids = self.get_twin_ids(dst);
             for (idx, dst_twin_id) in dst_twin_ids.iter().enumerate() {
-                let consensus_msg = msg.to_message().unwrap();
+                let consensus_msg: ConsensusMsg = msg.to_message().unwrap();
+                let delay =
+                    self.get_message_delay(&src_twin_id, dst_twin_id, consensus_msg.clone());
 
                 // Deliver and copy message if it's not dropped
                 if !self.is_message_dropped(&src_twin_id, dst_twin_id, consensus_msg) {
@@ -305,7 +325,9 @@ impl NetworkPlayground {
                         receive_timestamp_micros: 0,
                         rpc_replier: None,
                     };
-                    let msg_copy = self.deliver_message(src_twin_id, *dst_twin_id, rmsg).await;
+                    let msg_copy = self
+                        .deliver_message(src_twin_id, *dst_twin_id, rmsg, delay)
+                        .await;
 
                     // Only insert msg_copy once for twins (if delivered)
                     if idx == 0 && msg_inspector(&msg_copy) {
@@ -344,6 +366,16 @@ impl NetworkPlayground {
         matches!(&msg.1, ConsensusMsg::VoteMsg(_))
     }
 
+    /// Returns true for sync info messages only.
+    pub fn sync_info_only(msg: &(Author, ConsensusMsg)) -> bool {
+        matches!(&msg.1, ConsensusMsg::SyncInfo(_))
+    }
+
+    /// Returns true for commit vote messages only.
+    pub fn commit_votes_only(msg: &(Author, ConsensusMsg)) -> bool {
+        matches!(&msg.1, ConsensusMsg::CommitVoteMsg(_))
+    }
+
     pub fn extend_author_to_twin_ids(&mut self, author: Author, twin_id: TwinId) {
         self.author_to_twin_ids
             .write()
@@ -356,9 +388,39 @@ impl NetworkPlayground {
 
     fn is_message_dropped(&self, src: &TwinId, dst: &TwinId, msg: ConsensusMsg) -> bool {
         self.drop_config.read().is_message_dropped(src, dst)
-            || Self::get_message_round(msg).map_or(false, |r| {
+            || Self::get_message_round(msg.clone()).map_or(false, |r| {
                 self.drop_config_round.is_message_dropped(src, dst, r)
             })
+            || matches!(
+                self.get_message_action(src, dst, msg),
+                Some(MessageAction::Drop)
+            )
+    }
+
+    /// Returns the delivery delay of the message (if a delay rule matches it)
+    fn get_message_delay(&self, src: &TwinId, dst: &TwinId, msg: ConsensusMsg) -> Option<Duration> {
+        match self.get_message_action(src, dst, msg) {
+            Some(MessageAction::Delay(delay)) => Some(delay),
+            _ => None,
+        }
+    }
+
+    /// Returns the action of the first message rule (for the message's round) that matches
+    fn get_message_action(
+        &self,
+        src: &TwinId,
+        dst: &TwinId,
+        msg: ConsensusMsg,
+    ) -> Option<MessageAction> {
+        let round = Self::get_message_round(msg.clone())?;
+        self.message_rules_round
+            .get_message_action(src, dst, round, &(src.author, msg))
+    }
+
+    /// Adds a rule to drop or delay the matching messages of the given round
+    pub fn add_message_rule_round(&mut self, round: u64, message_rule: MessageRule) {
+        self.message_rules_round
+            .add_message_rule(round, message_rule);
     }
 
     pub fn split_network(
@@ -427,11 +489,14 @@ impl NetworkPlayground {
                     receive_timestamp_micros: 0,
                     rpc_replier: None,
                 };
-                let consensus_msg = msg.to_message().unwrap();
+                let consensus_msg: ConsensusMsg = msg.to_message().unwrap();
+                let delay =
+                    self.get_message_delay(&src_twin_id, dst_twin_id, consensus_msg.clone());
 
                 // Deliver and copy message it if it's not dropped
                 if !self.is_message_dropped(&src_twin_id, dst_twin_id, consensus_msg) {
-                    self.deliver_message(src_twin_id, *dst_twin_id, rmsg).await;
+                    self.deliver_message(src_twin_id, *dst_twin_id, rmsg, delay)
+                        .await;
                 }
             }
         }
@@ -526,6 +591,51 @@ impl DropConfigRound {
     }
 }
 
+/// The action taken for a direct-send message that matches a message rule
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageAction {
+    /// The message is never delivered
+    Drop,
+    /// The message is delivered after the given delay
+    Delay(Duration),
+}
+
+/// A rule that drops or delays the messages sent from `src` to `dst`
+/// that satisfy the given msg inspector (e.g., `proposals_only`).
+#[derive(Clone, Copy)]
+pub struct MessageRule {
+    pub src: TwinId,
+    pub dst: TwinId,
+    pub msg_inspector: fn(&(Author, ConsensusMsg)) -> bool,
+    pub action: MessageAction,
+}
+
+/// Table of per round message rules
+#[derive(Default)]
+struct MessageRulesRound(HashMap<u64, Vec<MessageRule>>);
+
+impl MessageRulesRound {
+    /// Returns the action of the first matching rule for the message in the given round
+    fn get_message_action(
+        &self,
+        src: &TwinId,
+        dst: &TwinId,
+        round: u64,
+        msg: &(Author, ConsensusMsg),
+    ) -> Option<MessageAction> {
+        self.0.get(&round).and_then(|message_rules| {
+            message_rules
+                .iter()
+                .find(|rule| &rule.src == src && &rule.dst == dst && (rule.msg_inspector)(msg))
+                .map(|rule| rule.action)
+        })
+    }
+
+    fn add_message_rule(&mut self, round: u64, message_rule: MessageRule) {
+        self.0.entry(round).or_default().push(message_rule);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -17,6 +17,7 @@ use aptos_config::config::QcAggregatorType;
 use aptos_consensus_types::{
     common::Author,
     delayed_qc_msg::DelayedQcMsg,
+    equivocation_evidence::EquivocationEvidence,
     quorum_cert::QuorumCert,
     timeout_2chain::{TwoChainTimeoutCertificate, TwoChainTimeoutWithPartialSignatures},
     vote::Vote,
@@ -26,6 +27,7 @@ use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_logger::prelude::*;
 use aptos_types::{
     aggregate_signature::PartialSignatures,
+    equivocation::EquivocationKind,
     ledger_info::LedgerInfoWithPartialSignatures,
     validator_verifier::{ValidatorVerifier, VerifyError},
 };
@@ -49,7 +51,8 @@ pub enum VoteReceptionResult {
     /// The very same vote message has been processed in past.
     DuplicateVote,
     /// The very same author has already voted for another proposal in this round (equivocation).
-    EquivocateVote,
+    /// Carries both conflicting votes as evidence.
+    EquivocateVote(Box<EquivocationEvidence>),
     /// This block has just been certified after adding the vote.
     NewQuorumCertificate(Arc<QuorumCert>),
     /// The vote completes a new TwoChainTimeoutCertificate
@@ -133,7 +136,15 @@ impl PendingVotes {
                     previous_vote = previously_seen_vote
                 );
 
-                return VoteReceptionResult::EquivocateVote;
+                counters::EQUIVOCATION_EVIDENCE_COLLECTED
+                    .with_label_values(&[EquivocationKind::Vote.as_str()])
+                    .inc();
+                return VoteReceptionResult::EquivocateVote(Box::new(
+                    EquivocationEvidence::conflicting_votes(
+                        previously_seen_vote.clone(),
+                        vote.clone(),
+                    ),
+                ));
             }
         }
 
@@ -408,7 +419,8 @@ mod tests {
     use crate::util::mock_time_service::SimulatedTimeService;
     use aptos_config::config::QcAggregatorType;
     use aptos_consensus_types::{
-        block::block_test_utils::certificate_for_genesis, vote::Vote, vote_data::VoteData,
+        block::block_test_utils::certificate_for_genesis,
+        equivocation_evidence::EquivocationEvidence, vote::Vote, vote_data::VoteData,
     };
     use aptos_crypto::HashValue;
     use aptos_types::{
@@ -475,10 +487,19 @@ mod tests {
             &signers[0],
         )
         .unwrap();
-        assert_eq!(
-            pending_votes.insert_vote(&vote_data_2_author_0, &validator),
-            VoteReceptionResult::EquivocateVote
-        );
+        match pending_votes.insert_vote(&vote_data_2_author_0, &validator) {
+            VoteReceptionResult::EquivocateVote(evidence) => {
+                assert_eq!(
+                    *evidence,
+                    EquivocationEvidence::conflicting_votes(
+                        vote_data_1_author_0.clone(),
+                        vote_data_2_author_0.clone()
+                    )
+                );
+                assert_eq!(evidence.offender(), signers[0].author());
+            },
+            _ => panic!("Equivocating vote should be detected"),
+        }
 
         // a different author voting for a different result -> VoteAdded
         let vote_data_2_author_1 = Vote::new(
@@ -2,12 +2,17 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{consensusdb::ConsensusDB, epoch_manager::LivenessStorageData, error::DbError};
+use crate::{
+    consensusdb::{ConsensusDB, EquivocationEvidenceSchema},
+    epoch_manager::LivenessStorageData,
+    error::DbError,
+};
 use anyhow::{format_err, Context, Result};
 use aptos_config::config::NodeConfig;
 use aptos_consensus_types::{
-    block::Block, quorum_cert::QuorumCert, timeout_2chain::TwoChainTimeoutCertificate, vote::Vote,
-    vote_data::VoteData, wrapped_ledger_info::WrappedLedgerInfo,
+    block::Block, equivocation_evidence::EquivocationEvidence, quorum_cert::QuorumCert,
+    timeout_2chain::TwoChainTimeoutCertificate, vote::Vote, vote_data::VoteData,
+    wrapped_ledger_info::WrappedLedgerInfo,
 };
 use aptos_crypto::HashValue;
 use aptos_logger::prelude::*;
@@ -46,10 +51,19 @@ pub trait PersistentLivenessStorage: Send + Sync {
         highest_timeout_cert: &TwoChainTimeoutCertificate,
     ) -> Result<()>;
 
+    /// Persist the evidence of a validator equivocating, so that it can be inspected
+    /// (and submitted on-chain) later
+    fn save_equivocation_evidence(&self, evidence: &EquivocationEvidence) -> Result<()>;
+
     /// Retrieve a epoch change proof for SafetyRules so it can instantiate its
     /// ValidatorVerifier.
     fn retrieve_epoch_change_proof(&self, version: u64) -> Result<EpochChangeProof>;
+}
 
+/// LivenessStorageDbs provides the databases that back the PersistentLivenessStorage of a
+/// node. This is kept separate, as not every liveness storage is backed by databases (e.g.,
+/// the storage used to replay flight recordings).
+pub trait LivenessStorageDbs: Send + Sync {
     /// Returns a handle of the aptosdb.
     fn aptos_db(&self) -> Arc<dyn DbReader>;
 
@@ -273,6 +287,28 @@ impl RecoveryData {
         })
     }
 
+    /// Creates the recovery data from an already known root (e.g., a root restored
+    /// from a consensus flight recorder checkpoint). The given blocks and quorum
+    /// certs must all descend from the root, and there is nothing to prune.
+    pub fn new_with_root(
+        last_vote: Option<Vote>,
+        root: RootInfo,
+        root_metadata: RootMetadata,
+        blocks: Vec<Block>,
+        quorum_certs: Vec<QuorumCert>,
+        highest_2chain_timeout_certificate: Option<TwoChainTimeoutCertificate>,
+    ) -> Self {
+        RecoveryData {
+            last_vote,
+            root,
+            root_metadata,
+            blocks,
+            quorum_certs,
+            blocks_to_prune: Some(vec![]),
+            highest_2chain_timeout_certificate,
+        }
+    }
+
     pub fn root_block(&self) -> &Block {
         &self.root.0
     }
@@ -458,6 +494,12 @@ impl PersistentLivenessStorage for StorageWriteProxy {
             .save_highest_2chain_timeout_certificate(bcs::to_bytes(highest_timeout_cert)?)?)
     }
 
+    fn save_equivocation_evidence(&self, evidence: &EquivocationEvidence) -> Result<()> {
+        Ok(self
+            .db
+            .put::<EquivocationEvidenceSchema>(&evidence.id(), evidence)?)
+    }
+
     fn retrieve_epoch_change_proof(&self, version: u64) -> Result<EpochChangeProof> {
         let (_, proofs) = self
             .aptos_db
@@ -466,7 +508,9 @@ impl PersistentLivenessStorage for StorageWriteProxy {
             .into_inner();
         Ok(proofs)
     }
+}
 
+impl LivenessStorageDbs for StorageWriteProxy {
     fn aptos_db(&self) -> Arc<dyn DbReader> {
         self.aptos_db.clone()
     }
@@ -38,6 +38,10 @@ use aptos_infallible::RwLock;
 use aptos_logger::prelude::*;
 use aptos_network::{application::interface::NetworkClient, protocols::network::Event};
 use aptos_safety_rules::safety_rules_manager::load_consensus_key_from_secure_storage;
+use aptos_storage_interface::{
+    state_view::{DbStateView, DbStateViewAtVersion},
+    AptosDbError, DbReader,
+};
 use aptos_types::{
     epoch_state::EpochState,
     ledger_info::LedgerInfoWithSignatures,
@@ -153,6 +157,7 @@ pub struct ExecutionProxyClient {
     rand_storage: Arc<dyn RandStorage<AugmentedData>>,
     consensus_observer_config: ConsensusObserverConfig,
     consensus_publisher: Option<Arc<ConsensusPublisher>>,
+    db_reader: Arc<dyn DbReader>,
 }
 
 impl ExecutionProxyClient {
@@ -166,6 +171,7 @@ impl ExecutionProxyClient {
         rand_storage: Arc<dyn RandStorage<AugmentedData>>,
         consensus_observer_config: ConsensusObserverConfig,
         consensus_publisher: Option<Arc<ConsensusPublisher>>,
+        db_reader: Arc<dyn DbReader>,
     ) -> Self {
         Self {
             consensus_config,
@@ -178,6 +184,36 @@ impl ExecutionProxyClient {
             rand_storage,
             consensus_observer_config,
             consensus_publisher,
+            db_reader,
+        }
+    }
+
+    // The state as of the reconfiguration which started the epoch, which is the same on every
+    // validator throughout the epoch, unlike the latest state.
+    fn epoch_start_state_view(&self, epoch_state: &EpochState) -> Option<DbStateView> {
+        let epoch_start_version = self
+            .db_reader
+            .get_epoch_ending_ledger_infos(epoch_state.epoch.saturating_sub(1), epoch_state.epoch)
+            .and_then(|proof| {
+                proof
+                    .ledger_info_with_sigs
+                    .first()
+                    .map(|ledger_info| ledger_info.ledger_info().version())
+                    .ok_or_else(|| {
+                        AptosDbError::NotFound("Ledger info ending the previous epoch".to_string())
+                    })
+            });
+        match epoch_start_version
+            .and_then(|version| self.db_reader.state_view_at_version(Some(version)))
+        {
+            Ok(state_view) => Some(state_view),
+            Err(error) => {
+                warn!(
+                    "Failed to read the state at the start of epoch {}: {}",
+                    epoch_state.epoch, error
+                );
+                None
+            },
         }
     }
 
@@ -209,15 +245,28 @@ impl ExecutionProxyClient {
                 Some(&counters::BUFFER_MANAGER_MSGS),
             );
 
+        // The rand manager signs with the consensus key. If the key can't be loaded,
+        // randomness is disabled for the epoch (as if the rand config was unavailable).
+        let rand_config_and_key = rand_config.and_then(|rand_config| {
+            match load_consensus_key_from_secure_storage(&self.consensus_config.safety_rules) {
+                Ok(consensus_key) => Some((rand_config, consensus_key)),
+                Err(error) => {
+                    error!(
+                        epoch = epoch_state.epoch,
+                        "Failed to load the consensus key for the rand manager, randomness is disabled: {}",
+                        error
+                    );
+                    None
+                },
+            }
+        });
+
         let (execution_ready_block_tx, execution_ready_block_rx, maybe_reset_tx_to_rand_manager) =
-            if let Some(rand_config) = rand_config {
+            if let Some((rand_config, consensus_key)) = rand_config_and_key {
                 let (ordered_block_tx, ordered_block_rx) = unbounded::<OrderedBlocks>();
                 let (rand_ready_block_tx, rand_ready_block_rx) = unbounded::<OrderedBlocks>();
 
                 let (reset_tx_to_rand_manager, reset_rand_manager_rx) = unbounded::<ResetRequest>();
-                let consensus_key =
-                    load_consensus_key_from_secure_storage(&self.consensus_config.safety_rules)
-                        .expect("Failed in loading consensus key for ExecutionProxyClient.");
                 let signer = Arc::new(ValidatorSigner::new(self.author, consensus_key));
 
                 let rand_manager = RandManager::<Share, AugmentedData>::new(
@@ -231,6 +280,7 @@ impl ExecutionProxyClient {
                     self.rand_storage.clone(),
                     self.bounded_executor.clone(),
                     &self.consensus_config.rand_rb_config,
+                    self.consensus_config.randomness_proof_retention_epochs,
                 );
 
                 tokio::spawn(rand_manager.start(
@@ -315,8 +365,10 @@ impl TExecutionClient for ExecutionProxyClient {
             self.consensus_publisher.clone(),
         );
 
-        let transaction_shuffler =
-            create_transaction_shuffler(onchain_execution_config.transaction_shuffler_type());
+        let transaction_shuffler = create_transaction_shuffler(
+            onchain_execution_config.transaction_shuffler_type(),
+            self.epoch_start_state_view(&epoch_state),
+        );
         let block_executor_onchain_config =
             onchain_execution_config.block_executor_onchain_config();
         let transaction_deduper =
@@ -23,7 +23,9 @@ use dashmap::{
 };
 use fail::fail_point;
 use once_cell::sync::OnceCell;
+use serde::Serialize;
 use std::{
+    collections::HashMap,
     sync::{
         atomic::{AtomicU64, Ordering},
         Arc, Mutex,
@@ -89,6 +91,17 @@ impl QuotaManager {
         );
     }
 
+    pub(crate) fn usage(&self) -> QuotaUsage {
+        QuotaUsage {
+            num_batches: self.batch_quota - self.batch_balance,
+            batch_quota: self.batch_quota,
+            memory_bytes: self.memory_quota - self.memory_balance,
+            memory_quota: self.memory_quota,
+            db_bytes: self.db_quota - self.db_balance,
+            db_quota: self.db_quota,
+        }
+    }
+
     pub(crate) fn free_quota(&mut self, num_bytes: usize, storage_mode: StorageMode) {
         Self::assert_quota(self.batch_balance, 1, self.batch_quota, "Batch");
         self.batch_balance += 1;
@@ -103,6 +116,17 @@ impl QuotaManager {
     }
 }
 
+/// The quota used by the batches of a single author
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct QuotaUsage {
+    pub num_batches: usize,
+    pub batch_quota: usize,
+    pub memory_bytes: usize,
+    pub memory_quota: usize,
+    pub db_bytes: usize,
+    pub db_quota: usize,
+}
+
 /// Provides in memory representation of stored batches (strong cache), and allows
 /// efficient concurrent readers.
 pub struct BatchStore {
@@ -181,7 +205,7 @@ impl BatchStore {
         batch_store
     }
 
-    fn epoch(&self) -> u64 {
+    pub(crate) fn epoch(&self) -> u64 {
         *self.epoch.get().expect("Epoch should always be set")
     }
 
@@ -350,10 +374,34 @@ impl BatchStore {
         }
     }
 
-    fn last_certified_time(&self) -> u64 {
+    pub(crate) fn last_certified_time(&self) -> u64 {
         self.last_certified_time.load(Ordering::Relaxed)
     }
 
+    /// Returns the storage mode of the batch, if it is tracked by the batch store
+    pub(crate) fn get_storage_mode(&self, digest: &HashValue) -> Option<StorageMode> {
+        self.db_cache
+            .get(digest)
+            .map(|value| value.payload_storage_mode())
+    }
+
+    /// Returns the quota used by each author
+    pub(crate) fn get_quota_usage(&self) -> HashMap<PeerId, QuotaUsage> {
+        self.peer_quota
+            .iter()
+            .map(|entry| (*entry.key(), entry.value().usage()))
+            .collect()
+    }
+
+    /// Removes the expired batches from the cache and the db, and returns their digests.
+    /// This normally happens whenever the certified timestamp is updated, but deletion
+    /// from the db is best effort.
+    pub(crate) fn gc_expired_batches(&self) -> anyhow::Result<Vec<HashValue>> {
+        let expired_keys = self.clear_expired_payload(self.last_certified_time());
+        self.db.delete_batches(expired_keys.clone())?;
+        Ok(expired_keys)
+    }
+
     fn get_batch_from_db(&self, digest: &HashValue) -> ExecutorResult<PersistedValue> {
         counters::GET_BATCH_FROM_DB_COUNT.inc();
 
@@ -0,0 +1,374 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+pub use crate::quorum_store::batch_store::QuotaUsage;
+use crate::quorum_store::{
+    batch_store::BatchStore, quorum_store_db::QuorumStoreStorage, types::StorageMode,
+};
+use anyhow::{bail, ensure, Result};
+use aptos_consensus_types::{
+    block::Block,
+    common::{Payload, Round},
+    proof_of_store::{BatchId, BatchInfo},
+};
+use aptos_crypto::HashValue;
+use aptos_infallible::RwLock;
+use aptos_logger::prelude::*;
+use aptos_types::PeerId;
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    str::FromStr,
+    sync::{Arc, Weak},
+};
+
+/// A summary of a batch persisted in the quorum store db
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct BatchSummary {
+    pub digest: HashValue,
+    pub author: PeerId,
+    pub batch_id: BatchId,
+    pub epoch: u64,
+    pub expiration: u64,
+    pub num_txns: u64,
+    pub num_bytes: u64,
+    /// Whether the payload (and not only the batch info) is persisted in the db
+    pub has_payload: bool,
+    /// Whether the batch store of the current epoch tracks (and can serve) the batch.
+    /// Untracked batches only take up space in the db.
+    pub tracked: bool,
+    /// Whether the payload is also cached in memory
+    pub in_memory: bool,
+    /// Whether the batch expired (None if no batch store is running)
+    pub expired: Option<bool>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BatchOrder {
+    #[default]
+    Digest,
+    /// Earliest expiration first
+    Expiration,
+    /// Largest batch first
+    Size,
+}
+
+impl FromStr for BatchOrder {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "digest" => Ok(BatchOrder::Digest),
+            "expiration" => Ok(BatchOrder::Expiration),
+            "size" => Ok(BatchOrder::Size),
+            _ => bail!(
+                "Unknown batch order {}, expected digest, expiration or size",
+                s
+            ),
+        }
+    }
+}
+
+/// Selects (and orders) the batches to list
+#[derive(Clone, Debug, Default)]
+pub struct BatchFilter {
+    pub author: Option<PeerId>,
+    pub expired: Option<bool>,
+    pub tracked: Option<bool>,
+    pub min_bytes: Option<u64>,
+    pub order: BatchOrder,
+    pub limit: Option<usize>,
+}
+
+impl BatchFilter {
+    fn matches(&self, summary: &BatchSummary) -> bool {
+        self.author.map_or(true, |author| summary.author == author)
+            && self
+                .expired
+                .map_or(true, |expired| summary.expired == Some(expired))
+            && self
+                .tracked
+                .map_or(true, |tracked| summary.tracked == tracked)
+            && self
+                .min_bytes
+                .map_or(true, |min_bytes| summary.num_bytes >= min_bytes)
+    }
+}
+
+/// The quota used by an author in the batch store, compared to what the author
+/// actually takes up in the db
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct AuthorQuotaUsage {
+    /// The quota tracked by the batch store (None if no batch store is running)
+    pub quota_usage: Option<QuotaUsage>,
+    pub num_batches_on_disk: usize,
+    pub payload_bytes_on_disk: u64,
+    pub num_untracked_batches_on_disk: usize,
+    pub untracked_payload_bytes_on_disk: u64,
+}
+
+/// The result of garbage collecting the quorum store db
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct GarbageCollectionSummary {
+    pub num_expired_batches: usize,
+    pub num_untracked_batches: usize,
+    pub untracked_payload_bytes: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchReferenceKind {
+    /// The block includes a proof of store of the batch
+    Proof,
+    /// The block includes the batch with its transactions
+    Inline,
+    /// The block includes the batch (optimistically) without a proof
+    OptBatch,
+}
+
+/// A block that references a batch
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct BatchReference {
+    pub block_id: HashValue,
+    pub epoch: u64,
+    pub round: Round,
+    pub kind: BatchReferenceKind,
+}
+
+/// Returns the references of the given blocks to the batch with the given digest
+pub fn find_batch_references(blocks: &[Block], digest: &HashValue) -> Vec<BatchReference> {
+    let mut references = vec![];
+    for block in blocks {
+        let mut add_references = |batch_infos: Vec<&BatchInfo>, kind| {
+            for batch_info in batch_infos {
+                if batch_info.digest() == digest {
+                    references.push(BatchReference {
+                        block_id: block.id(),
+                        epoch: block.epoch(),
+                        round: block.round(),
+                        kind,
+                    });
+                }
+            }
+        };
+        match block.payload() {
+            Some(Payload::InQuorumStore(proof_with_data)) => add_references(
+                proof_with_data.proofs.iter().map(|p| p.info()).collect(),
+                BatchReferenceKind::Proof,
+            ),
+            Some(Payload::InQuorumStoreWithLimit(proof_with_data)) => add_references(
+                proof_with_data
+                    .proof_with_data
+                    .proofs
+                    .iter()
+                    .map(|p| p.info())
+                    .collect(),
+                BatchReferenceKind::Proof,
+            ),
+            Some(Payload::QuorumStoreInlineHybrid(inline_batches, proof_with_data, _)) => {
+                add_references(
+                    inline_batches.iter().map(|(info, _)| info).collect(),
+                    BatchReferenceKind::Inline,
+                );
+                add_references(
+                    proof_with_data.proofs.iter().map(|p| p.info()).collect(),
+                    BatchReferenceKind::Proof,
+                );
+            },
+            Some(Payload::OptQuorumStore(opt_qs_payload)) => {
+                let inline_batch_infos = opt_qs_payload.inline_batches().batch_infos();
+                add_references(
+                    inline_batch_infos.iter().collect(),
+                    BatchReferenceKind::Inline,
+                );
+                add_references(
+                    opt_qs_payload
+                        .proof_with_data()
+                        .iter()
+                        .map(|p| p.info())
+                        .collect(),
+                    BatchReferenceKind::Proof,
+                );
+                add_references(
+                    opt_qs_payload.opt_batches().iter().collect(),
+                    BatchReferenceKind::OptBatch,
+                );
+            },
+            Some(Payload::DirectMempool(_)) | None => {},
+        }
+    }
+    references
+}
+
+/// Provides structured access to the persisted batches, and to the batch store of the
+/// current epoch (e.g., for the admin service).
+pub struct QuorumStoreInspector {
+    storage: Arc<dyn QuorumStoreStorage>,
+    // The current epoch (None until the first epoch starts)
+    epoch: RwLock<Option<u64>>,
+    // Weak, so that the batch store of an epoch is dropped once the epoch ends
+    batch_store: RwLock<Weak<BatchStore>>,
+}
+
+impl QuorumStoreInspector {
+    pub fn new(storage: Arc<dyn QuorumStoreStorage>) -> Self {
+        Self {
+            storage,
+            epoch: RwLock::new(None),
+            batch_store: RwLock::new(Weak::new()),
+        }
+    }
+
+    /// Sets the current epoch (before the batch store of the epoch is created)
+    pub(crate) fn set_epoch(&self, epoch: u64) {
+        *self.epoch.write() = Some(epoch);
+    }
+
+    /// Sets the batch store of the current epoch
+    pub(crate) fn set_batch_store(&self, batch_store: &Arc<BatchStore>) {
+        *self.batch_store.write() = Arc::downgrade(batch_store);
+    }
+
+    /// Returns the batch store of the current epoch. Fails if no batch store is running,
+    /// or if the batch store belongs to another epoch (e.g., while the epoch changes).
+    fn current_batch_store(&self) -> Result<Arc<BatchStore>> {
+        let batch_store = match self.batch_store.read().upgrade() {
+            Some(batch_store) => batch_store,
+            None => bail!("No batch store is running"),
+        };
+        self.ensure_current_epoch(&batch_store)?;
+        Ok(batch_store)
+    }
+
+    fn ensure_current_epoch(&self, batch_store: &BatchStore) -> Result<()> {
+        let epoch = *self.epoch.read();
+        ensure!(
+            epoch == Some(batch_store.epoch()),
+            "The batch store is of epoch {}, but the current epoch is {:?}",
+            batch_store.epoch(),
+            epoch
+        );
+        Ok(())
+    }
+
+    fn batch_store(&self) -> Option<Arc<BatchStore>> {
+        self.current_batch_store().ok()
+    }
+
+    fn get_all_batch_summaries(&self) -> Result<Vec<BatchSummary>> {
+        let batch_store = self.batch_store();
+        let last_certified_time = batch_store
+            .as_ref()
+            .map(|batch_store| batch_store.last_certified_time());
+
+        let mut summaries: Vec<_> = self
+            .storage
+            .get_all_batch_metadata()?
+            .into_iter()
+            .map(|(digest, metadata)| {
+                let storage_mode = batch_store
+                    .as_ref()
+                    .and_then(|batch_store| batch_store.get_storage_mode(&digest));
+                let info = metadata.batch_info();
+                BatchSummary {
+                    digest,
+                    author: info.author(),
+                    batch_id: info.batch_id(),
+                    epoch: info.epoch(),
+                    expiration: info.expiration(),
+                    num_txns: info.num_txns(),
+                    num_bytes: info.num_bytes(),
+                    has_payload: metadata.has_payload(),
+                    tracked: storage_mode.is_some(),
+                    in_memory: storage_mode == Some(StorageMode::MemoryAndPersisted),
+                    expired: last_certified_time.map(|time| info.expiration() <= time),
+                }
+            })
+            .collect();
+        summaries.sort_by_key(|summary| summary.digest);
+        Ok(summaries)
+    }
+
+    /// Lists the persisted batches selected by the filter
+    pub fn list_batches(&self, filter: &BatchFilter) -> Result<Vec<BatchSummary>> {
+        let mut summaries: Vec<_> = self
+            .get_all_batch_summaries()?
+            .into_iter()
+            .filter(|summary| filter.matches(summary))
+            .collect();
+        match filter.order {
+            BatchOrder::Digest => {},
+            BatchOrder::Expiration => summaries.sort_by_key(|summary| summary.expiration),
+            BatchOrder::Size => {
+                summaries.sort_by_key(|summary| std::cmp::Reverse(summary.num_bytes))
+            },
+        }
+        if let Some(limit) = filter.limit {
+            summaries.truncate(limit);
+        }
+        Ok(summaries)
+    }
+
+    /// Returns the quota used by each author, together with the space it takes up in the db
+    pub fn get_quota_usage(&self) -> Result<BTreeMap<PeerId, AuthorQuotaUsage>> {
+        let mut usage: BTreeMap<PeerId, AuthorQuotaUsage> = BTreeMap::new();
+        if let Some(batch_store) = self.batch_store() {
+            for (author, quota_usage) in batch_store.get_quota_usage() {
+                usage.entry(author).or_default().quota_usage = Some(quota_usage);
+            }
+        }
+        for summary in self.get_all_batch_summaries()? {
+            let payload_bytes = if summary.has_payload {
+                summary.num_bytes
+            } else {
+                0
+            };
+            let author_usage = usage.entry(summary.author).or_default();
+            author_usage.num_batches_on_disk += 1;
+            author_usage.payload_bytes_on_disk += payload_bytes;
+            if !summary.tracked {
+                author_usage.num_untracked_batches_on_disk += 1;
+                author_usage.untracked_payload_bytes_on_disk += payload_bytes;
+            }
+        }
+        Ok(usage)
+    }
+
+    /// Deletes the expired batches, as well as the batches that are not tracked by the
+    /// batch store of the current epoch (e.g., persisted by the batch store of a previous
+    /// epoch after this one was created), as those can never be served. Refuses to run
+    /// unless the batch store of the current epoch is running, as otherwise it can't tell
+    /// which batches are needed.
+    pub fn garbage_collect(&self) -> Result<GarbageCollectionSummary> {
+        let batch_store = self.current_batch_store()?;
+        let num_expired_batches = batch_store.gc_expired_batches()?.len();
+
+        // A batch is inserted into the batch store before it is persisted, so the batches
+        // that are not tracked (anymore) can't be needed.
+        let untracked: Vec<_> = self
+            .get_all_batch_summaries()?
+            .into_iter()
+            .filter(|summary| !summary.tracked)
+            .collect();
+        let summary = GarbageCollectionSummary {
+            num_expired_batches,
+            num_untracked_batches: untracked.len(),
+            untracked_payload_bytes: untracked
+                .iter()
+                .filter(|summary| summary.has_payload)
+                .map(|summary| summary.num_bytes)
+                .sum(),
+        };
+        // The epoch may have changed while collecting the untracked batches
+        self.ensure_current_epoch(&batch_store)?;
+        self.storage.delete_batches(
+            untracked
+                .into_iter()
+                .map(|summary| summary.digest)
+                .collect(),
+        )?;
+
+        info!("QS: garbage collected the quorum store db: {:?}", summary);
+        Ok(summary)
+    }
+}
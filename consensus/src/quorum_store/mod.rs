@@ -9,6 +9,7 @@ pub(crate) mod batch_coordinator;
 pub(crate) mod batch_generator;
 pub(crate) mod batch_requester;
 pub(crate) mod batch_store;
+pub mod inspection;
 pub(crate) mod network_listener;
 pub(crate) mod proof_coordinator;
 pub(crate) mod proof_manager;
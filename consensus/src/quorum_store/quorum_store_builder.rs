@@ -62,6 +62,14 @@ impl QuorumStoreBuilder {
         }
     }
 
+    /// Returns the batch store, once the payload manager is initialized
+    pub fn batch_store(&self) -> Option<Arc<BatchStore>> {
+        match self {
+            QuorumStoreBuilder::DirectMempool(_) => None,
+            QuorumStoreBuilder::QuorumStore(inner) => inner.batch_store.clone(),
+        }
+    }
+
     pub fn start(
         self,
     ) -> Option<(
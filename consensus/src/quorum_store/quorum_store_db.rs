@@ -4,8 +4,10 @@
 use crate::{
     error::DbError,
     quorum_store::{
-        schema::{BatchIdSchema, BatchSchema, BATCH_CF_NAME, BATCH_ID_CF_NAME},
-        types::PersistedValue,
+        schema::{
+            BatchIdSchema, BatchMetadataSchema, BatchSchema, BATCH_CF_NAME, BATCH_ID_CF_NAME,
+        },
+        types::{PersistedBatchMetadata, PersistedValue},
     },
 };
 use anyhow::Result;
@@ -20,6 +22,9 @@ pub trait QuorumStoreStorage: Sync + Send {
 
     fn get_all_batches(&self) -> Result<HashMap<HashValue, PersistedValue>>;
 
+    /// Returns the batch info of all batches, without deserializing the payloads
+    fn get_all_batch_metadata(&self) -> Result<HashMap<HashValue, PersistedBatchMetadata>>;
+
     fn save_batch(&self, batch: PersistedValue) -> Result<(), DbError>;
 
     fn get_batch(&self, digest: &HashValue) -> Result<Option<PersistedValue>, DbError>;
@@ -79,6 +84,13 @@ impl QuorumStoreStorage for QuorumStoreDB {
             .collect::<Result<HashMap<HashValue, PersistedValue>>>()
     }
 
+    fn get_all_batch_metadata(&self) -> Result<HashMap<HashValue, PersistedBatchMetadata>> {
+        let mut iter = self.db.iter::<BatchMetadataSchema>()?;
+        iter.seek_to_first();
+        iter.map(|res| res.map_err(Into::into))
+            .collect::<Result<HashMap<HashValue, PersistedBatchMetadata>>>()
+    }
+
     fn save_batch(&self, batch: PersistedValue) -> Result<(), DbError> {
         trace!(
             "QS: db persists digest {} expiration {:?}",
@@ -151,6 +163,10 @@ pub mod mock {
             Ok(HashMap::new())
         }
 
+        fn get_all_batch_metadata(&self) -> Result<HashMap<HashValue, PersistedBatchMetadata>> {
+            Ok(HashMap::new())
+        }
+
         fn save_batch(&self, _: PersistedValue) -> Result<(), DbError> {
             Ok(())
         }
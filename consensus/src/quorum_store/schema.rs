@@ -1,14 +1,16 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::quorum_store::types::PersistedValue;
-use anyhow::Result;
-use aptos_consensus_types::proof_of_store::BatchId;
+use crate::quorum_store::types::{PersistedBatchMetadata, PersistedValue};
+use anyhow::{bail, ensure, Result};
+use aptos_consensus_types::proof_of_store::{BatchId, BatchInfo};
 use aptos_crypto::HashValue;
 use aptos_schemadb::{
     schema::{KeyCodec, Schema, ValueCodec},
     ColumnFamilyName,
 };
+use aptos_types::PeerId;
+use once_cell::sync::Lazy;
 
 pub(crate) const BATCH_CF_NAME: ColumnFamilyName = "batch";
 pub(crate) const BATCH_ID_CF_NAME: ColumnFamilyName = "batch_ID";
@@ -43,6 +45,68 @@ impl ValueCodec<BatchSchema> for PersistedValue {
     }
 }
 
+/// The size of a serialized `BatchInfo`. All fields of a `BatchInfo` have a fixed size,
+/// so this is the size of every `BatchInfo` (if that changes, decoding fails instead of
+/// returning corrupted values, as the decoded `BatchInfo` must consume the exact bytes).
+static BATCH_INFO_SIZE: Lazy<usize> = Lazy::new(|| {
+    bcs::serialized_size(&BatchInfo::new(
+        PeerId::ZERO,
+        BatchId::new(0),
+        0,
+        0,
+        HashValue::zero(),
+        0,
+        0,
+        0,
+    ))
+    .expect("BatchInfo should be serializable")
+});
+
+/// A read-only view of `BatchSchema` that only decodes the batch info of each batch
+/// (a `PersistedValue` is serialized as the batch info, followed by the optional payload).
+/// Used to inspect the db without deserializing the payloads.
+#[derive(Debug)]
+pub(crate) struct BatchMetadataSchema;
+
+impl Schema for BatchMetadataSchema {
+    type Key = HashValue;
+    type Value = PersistedBatchMetadata;
+
+    const COLUMN_FAMILY_NAME: aptos_schemadb::ColumnFamilyName = BATCH_CF_NAME;
+}
+
+impl KeyCodec<BatchMetadataSchema> for HashValue {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(self.to_vec())
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        Ok(HashValue::from_slice(data)?)
+    }
+}
+
+impl ValueCodec<BatchMetadataSchema> for PersistedBatchMetadata {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        bail!("BatchMetadataSchema is read-only, batches are written via BatchSchema")
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        let info_size = *BATCH_INFO_SIZE;
+        ensure!(
+            data.len() > info_size,
+            "Persisted batch is too short: {} bytes",
+            data.len()
+        );
+        let info: BatchInfo = bcs::from_bytes(&data[..info_size])?;
+        let has_payload = match data[info_size] {
+            0 => false,
+            1 => true,
+            tag => bail!("Invalid payload option tag {} of persisted batch", tag),
+        };
+        Ok(PersistedBatchMetadata::new(info, has_payload))
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct BatchIdSchema;
 
@@ -0,0 +1,164 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::quorum_store::{
+    batch_store::{BatchStore, BatchWriter},
+    inspection::{BatchFilter, BatchOrder, QuorumStoreInspector, QuotaUsage},
+    quorum_store_db::{QuorumStoreDB, QuorumStoreStorage},
+    types::PersistedValue,
+};
+use aptos_consensus_types::proof_of_store::{BatchId, BatchInfo};
+use aptos_crypto::HashValue;
+use aptos_temppath::TempPath;
+use aptos_types::{account_address::AccountAddress, validator_verifier::random_validator_verifier};
+use std::sync::Arc;
+
+fn batch_for_test(author: AccountAddress, expiration: u64, num_bytes: u64) -> PersistedValue {
+    PersistedValue::new(
+        BatchInfo::new(
+            author,
+            BatchId::new_for_test(1),
+            10,
+            expiration,
+            HashValue::random(),
+            1,
+            num_bytes,
+            0,
+        ),
+        Some(vec![]),
+    )
+}
+
+#[test]
+fn test_inspect_and_garbage_collect_batches() {
+    let tmp_dir = TempPath::new();
+    let db = Arc::new(QuorumStoreDB::new(&tmp_dir));
+    let (signers, _validator_verifier) = random_validator_verifier(4, None, false);
+    let batch_store = Arc::new(BatchStore::new(
+        10, // epoch
+        10, // last certified time
+        db.clone(),
+        30,   // memory quota
+        2001, // db quota
+        2001, // batch quota
+        signers[0].clone(),
+    ));
+    let inspector = QuorumStoreInspector::new(db.clone());
+    inspector.set_batch_store(&batch_store);
+    // The batch store is only used once the inspector knows it belongs to the current epoch
+    assert!(inspector.garbage_collect().is_err());
+    inspector.set_epoch(11);
+    assert!(inspector.garbage_collect().is_err());
+    inspector.set_epoch(10);
+
+    let tracked_author = AccountAddress::random();
+    let untracked_author = AccountAddress::random();
+    let small_batch = batch_for_test(tracked_author, 20, 10);
+    let large_batch = batch_for_test(tracked_author, 30, 50);
+    assert_eq!(
+        batch_store
+            .persist(vec![small_batch.clone(), large_batch.clone()])
+            .len(),
+        2
+    );
+    // Written after the batch store was created, e.g., by the batch store of the previous epoch
+    let untracked_batch = batch_for_test(untracked_author, 5, 20);
+    db.save_batch(untracked_batch.clone()).unwrap();
+
+    // The metadata is decoded without the payloads, and matches the persisted batches
+    let mut batch_without_payload = batch_for_test(untracked_author, 5, 0);
+    batch_without_payload.remove_payload();
+    let metadata_dir = TempPath::new();
+    let metadata_db = QuorumStoreDB::new(&metadata_dir);
+    for batch in [&small_batch, &untracked_batch, &batch_without_payload] {
+        metadata_db.save_batch(batch.clone()).unwrap();
+    }
+    let batches = metadata_db.get_all_batches().unwrap();
+    let metadata = metadata_db.get_all_batch_metadata().unwrap();
+    assert_eq!(metadata.len(), 3);
+    for (digest, batch) in batches {
+        assert_eq!(metadata[&digest].batch_info(), batch.batch_info());
+        assert_eq!(metadata[&digest].has_payload(), batch.payload().is_some());
+    }
+    assert!(!metadata[batch_without_payload.digest()].has_payload());
+
+    let all_batches = inspector.list_batches(&BatchFilter::default()).unwrap();
+    assert_eq!(all_batches.len(), 3);
+    assert!(all_batches.windows(2).all(|w| w[0].digest < w[1].digest));
+
+    let by_author = inspector
+        .list_batches(&BatchFilter {
+            author: Some(tracked_author),
+            order: BatchOrder::Size,
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(by_author.len(), 2);
+    assert_eq!(by_author[0].digest, *large_batch.digest());
+    assert!(by_author[0].tracked && !by_author[0].in_memory);
+    assert_eq!(by_author[1].digest, *small_batch.digest());
+    assert!(by_author[1].tracked && by_author[1].in_memory);
+
+    let untracked = inspector
+        .list_batches(&BatchFilter {
+            tracked: Some(false),
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(untracked.len(), 1);
+    assert_eq!(untracked[0].digest, *untracked_batch.digest());
+    assert_eq!(untracked[0].expired, Some(true));
+
+    let earliest = inspector
+        .list_batches(&BatchFilter {
+            min_bytes: Some(15),
+            order: BatchOrder::Expiration,
+            limit: Some(1),
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(earliest.len(), 1);
+    assert_eq!(earliest[0].digest, *untracked_batch.digest());
+
+    let quota_usage = inspector.get_quota_usage().unwrap();
+    let tracked_usage = &quota_usage[&tracked_author];
+    assert_eq!(
+        tracked_usage.quota_usage,
+        Some(QuotaUsage {
+            num_batches: 2,
+            batch_quota: 2001,
+            memory_bytes: 10,
+            memory_quota: 30,
+            db_bytes: 60,
+            db_quota: 2001,
+        })
+    );
+    assert_eq!(tracked_usage.num_batches_on_disk, 2);
+    assert_eq!(tracked_usage.payload_bytes_on_disk, 60);
+    assert_eq!(tracked_usage.num_untracked_batches_on_disk, 0);
+    let untracked_usage = &quota_usage[&untracked_author];
+    assert_eq!(untracked_usage.quota_usage, None);
+    assert_eq!(untracked_usage.num_untracked_batches_on_disk, 1);
+    assert_eq!(untracked_usage.untracked_payload_bytes_on_disk, 20);
+
+    let summary = inspector.garbage_collect().unwrap();
+    assert_eq!(summary.num_expired_batches, 0);
+    assert_eq!(summary.num_untracked_batches, 1);
+    assert_eq!(summary.untracked_payload_bytes, 20);
+    assert!(db.get_batch(untracked_batch.digest()).unwrap().is_none());
+    assert_eq!(
+        inspector
+            .list_batches(&BatchFilter::default())
+            .unwrap()
+            .len(),
+        2
+    );
+
+    // Without a running batch store, batches can still be listed, but not garbage collected
+    drop(batch_store);
+    let batches = inspector.list_batches(&BatchFilter::default()).unwrap();
+    assert!(batches
+        .iter()
+        .all(|batch| !batch.tracked && batch.expired.is_none()));
+    assert!(inspector.garbage_collect().is_err());
+}
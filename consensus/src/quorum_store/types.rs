@@ -20,6 +20,28 @@ pub struct PersistedValue {
     maybe_payload: Option<Vec<SignedTransaction>>,
 }
 
+/// The batch info of a persisted batch, and whether its payload is persisted as well
+/// (decoded without the payload, see `BatchMetadataSchema`)
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct PersistedBatchMetadata {
+    info: BatchInfo,
+    has_payload: bool,
+}
+
+impl PersistedBatchMetadata {
+    pub(crate) fn new(info: BatchInfo, has_payload: bool) -> Self {
+        Self { info, has_payload }
+    }
+
+    pub fn batch_info(&self) -> &BatchInfo {
+        &self.info
+    }
+
+    pub fn has_payload(&self) -> bool {
+        self.has_payload
+    }
+}
+
 #[derive(PartialEq, Debug)]
 pub(crate) enum StorageMode {
     PersistedOnly,
@@ -10,7 +10,7 @@ use crate::{
         aug_data_store::AugDataStore,
         block_queue::{BlockQueue, QueueItem},
         network_messages::{RandMessage, RpcRequest},
-        rand_store::RandStore,
+        rand_store::{RandDecision, RandStore},
         reliable_broadcast_state::{
             AugDataCertBuilder, CertifiedAugDataAckState, ShareAggregateState,
         },
@@ -29,7 +29,7 @@ use aptos_reliable_broadcast::{DropGuard, ReliableBroadcast};
 use aptos_time_service::TimeService;
 use aptos_types::{
     epoch_state::EpochState,
-    randomness::{FullRandMetadata, RandMetadata, Randomness},
+    randomness::{FullRandMetadata, RandMetadata, RandomnessProof},
     validator_signer::ValidatorSigner,
 };
 use bytes::Bytes;
@@ -57,13 +57,18 @@ pub struct RandManager<S: TShare, D: TAugmentedData> {
     network_sender: Arc<NetworkSender>,
 
     // local channel received from rand_store
-    decision_rx: Receiver<Randomness>,
+    decision_rx: Receiver<RandDecision>,
     // downstream channels
     outgoing_blocks: Sender<OrderedBlocks>,
     // local state
     rand_store: Arc<Mutex<RandStore<S>>>,
     aug_data_store: AugDataStore<D>,
     block_queue: BlockQueue,
+    db: Arc<dyn RandStorage<D>>,
+    // The proofs of the aggregated randomness are persisted by a background task (e.g.,
+    // for the REST API), if enabled
+    randomness_proof_retention_epochs: u64,
+    proof_tx: Option<Sender<RandomnessProof>>,
 
     // for randomness fast path
     fast_config: Option<RandConfig>,
@@ -81,6 +86,7 @@ impl<S: TShare, D: TAugmentedData> RandManager<S, D> {
         db: Arc<dyn RandStorage<D>>,
         bounded_executor: BoundedExecutor,
         rb_config: &ReliableBroadcastConfig,
+        randomness_proof_retention_epochs: u64,
     ) -> Self {
         let rb_backoff_policy = ExponentialBackoff::from_millis(rb_config.backoff_policy_base_ms)
             .factor(rb_config.backoff_policy_factor)
@@ -100,6 +106,7 @@ impl<S: TShare, D: TAugmentedData> RandManager<S, D> {
             author,
             config.clone(),
             fast_config.clone(),
+            randomness_proof_retention_epochs > 0,
             decision_tx,
         )));
         let aug_data_store = AugDataStore::new(
@@ -107,9 +114,8 @@ impl<S: TShare, D: TAugmentedData> RandManager<S, D> {
             signer,
             config.clone(),
             fast_config.clone(),
-            db,
+            db.clone(),
         );
-
         Self {
             author,
             epoch_state,
@@ -124,6 +130,9 @@ impl<S: TShare, D: TAugmentedData> RandManager<S, D> {
             rand_store,
             aug_data_store,
             block_queue: BlockQueue::new(),
+            db,
+            randomness_proof_retention_epochs,
+            proof_tx: None,
 
             fast_config,
         }
@@ -195,11 +204,14 @@ impl<S: TShare, D: TAugmentedData> RandManager<S, D> {
         let _ = tx.send(ResetAck::default());
     }
 
-    fn process_randomness(&mut self, randomness: Randomness) {
+    fn process_randomness(&mut self, (randomness, maybe_proof): RandDecision) {
         info!(
             metadata = randomness.metadata(),
             "Processing decisioned randomness."
         );
+        if let (Some(proof), Some(proof_tx)) = (maybe_proof, &self.proof_tx) {
+            let _ = proof_tx.unbounded_send(proof);
+        }
         if let Some(block) = self.block_queue.item_mut(randomness.round()) {
             block.set_randomness(randomness.round(), randomness);
         }
@@ -218,6 +230,40 @@ impl<S: TShare, D: TAugmentedData> RandManager<S, D> {
             .into()));
     }
 
+    /// Removes the proofs of the epochs before `lowest_epoch_to_keep`, then persists the
+    /// proofs of the aggregated randomness (off the main loop) until the manager stops.
+    async fn proof_writer_task(
+        db: Arc<dyn RandStorage<D>>,
+        lowest_epoch_to_keep: u64,
+        mut proof_rx: Receiver<RandomnessProof>,
+    ) {
+        let db_clone = db.clone();
+        if let Err(e) = tokio::task::spawn_blocking(move || {
+            db_clone.remove_randomness_proofs_before(lowest_epoch_to_keep)
+        })
+        .await
+        .map_err(anyhow::Error::from)
+        .and_then(|result| result)
+        {
+            error!("[RandManager] failed to remove randomness proofs: {:?}", e);
+        }
+
+        while let Some(proof) = proof_rx.next().await {
+            let db = db.clone();
+            let metadata = proof.metadata.clone();
+            if let Err(e) = tokio::task::spawn_blocking(move || db.save_randomness_proof(&proof))
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result)
+            {
+                warn!(
+                    metadata = metadata,
+                    "[RandManager] failed to save randomness proof: {:?}", e
+                );
+            }
+        }
+    }
+
     async fn verification_task(
         epoch_state: Arc<EpochState>,
         mut incoming_rpc_request: aptos_channel::Receiver<Author, IncomingRandGenRequest>,
@@ -372,6 +418,18 @@ impl<S: TShare, D: TAugmentedData> RandManager<S, D> {
                 bounded_executor,
             )
         );
+        // Keep the proofs of the last `randomness_proof_retention_epochs` epochs (including
+        // the current one)
+        let (proof_tx, proof_rx) = unbounded();
+        self.proof_tx = (self.randomness_proof_retention_epochs > 0).then_some(proof_tx);
+        spawn_named!(
+            "rand manager proof writer",
+            Self::proof_writer_task(
+                self.db.clone(),
+                (self.epoch_state.epoch + 1).saturating_sub(self.randomness_proof_retention_epochs),
+                proof_rx,
+            )
+        );
 
         let _guard = self.broadcast_aug_data().await;
         let mut interval = tokio::time::interval(Duration::from_millis(5000));
@@ -11,10 +11,13 @@ use crate::{
 use anyhow::ensure;
 use aptos_consensus_types::common::{Author, Round};
 use aptos_logger::warn;
-use aptos_types::randomness::{FullRandMetadata, RandMetadata, Randomness};
+use aptos_types::randomness::{FullRandMetadata, RandMetadata, Randomness, RandomnessProof};
 use itertools::Either;
 use std::collections::{BTreeMap, HashMap, HashSet};
 
+/// The aggregated randomness of a round, with the proof of the aggregation (if any)
+pub type RandDecision = (Randomness, Option<RandomnessProof>);
+
 pub struct ShareAggregator<S> {
     author: Author,
     shares: HashMap<Author, RandShare<S>>,
@@ -42,7 +45,8 @@ impl<S: TShare> ShareAggregator<S> {
         self,
         rand_config: &RandConfig,
         rand_metadata: FullRandMetadata,
-        decision_tx: Sender<Randomness>,
+        with_proof: bool,
+        decision_tx: Sender<RandDecision>,
     ) -> Either<Self, RandShare<S>> {
         if self.total_weight < rand_config.threshold() {
             return Either::Left(self);
@@ -71,10 +75,12 @@ impl<S: TShare> ShareAggregator<S> {
                 self.shares.values(),
                 &rand_config,
                 rand_metadata.metadata.clone(),
+                self.path_type,
+                with_proof,
             );
             match maybe_randomness {
-                Ok(randomness) => {
-                    let _ = decision_tx.unbounded_send(randomness);
+                Ok(decision) => {
+                    let _ = decision_tx.unbounded_send(decision);
                 },
                 Err(e) => {
                     warn!(
@@ -159,13 +165,23 @@ impl<S: TShare> RandItem<S> {
         }
     }
 
-    fn try_aggregate(&mut self, rand_config: &RandConfig, decision_tx: Sender<Randomness>) {
+    fn try_aggregate(
+        &mut self,
+        rand_config: &RandConfig,
+        with_proof: bool,
+        decision_tx: Sender<RandDecision>,
+    ) {
         let item = std::mem::replace(self, Self::new(Author::ONE, PathType::Slow));
         let new_item = match item {
             RandItem::PendingDecision {
                 share_aggregator,
                 metadata,
-            } => match share_aggregator.try_aggregate(rand_config, metadata.clone(), decision_tx) {
+            } => match share_aggregator.try_aggregate(
+                rand_config,
+                metadata.clone(),
+                with_proof,
+                decision_tx,
+            ) {
                 Either::Left(share_aggregator) => Self::PendingDecision {
                     metadata,
                     share_aggregator,
@@ -223,7 +239,9 @@ pub struct RandStore<S> {
     fast_rand_config: Option<RandConfig>,
     fast_rand_map: Option<BTreeMap<Round, RandItem<S>>>,
     highest_known_round: u64,
-    decision_tx: Sender<Randomness>,
+    // Whether to build the proofs of the aggregated randomness (to persist them)
+    with_proofs: bool,
+    decision_tx: Sender<RandDecision>,
 }
 
 impl<S: TShare> RandStore<S> {
@@ -232,7 +250,8 @@ impl<S: TShare> RandStore<S> {
         author: Author,
         rand_config: RandConfig,
         fast_rand_config: Option<RandConfig>,
-        decision_tx: Sender<Randomness>,
+        with_proofs: bool,
+        decision_tx: Sender<RandDecision>,
     ) -> Self {
         Self {
             epoch,
@@ -242,6 +261,7 @@ impl<S: TShare> RandStore<S> {
             fast_rand_config: fast_rand_config.clone(),
             fast_rand_map: fast_rand_config.map(|_| BTreeMap::new()),
             highest_known_round: 0,
+            with_proofs,
             decision_tx,
         }
     }
@@ -256,7 +276,11 @@ impl<S: TShare> RandStore<S> {
             .entry(rand_metadata.round())
             .or_insert_with(|| RandItem::new(self.author, PathType::Slow));
         rand_item.add_metadata(&self.rand_config, rand_metadata.clone());
-        rand_item.try_aggregate(&self.rand_config, self.decision_tx.clone());
+        rand_item.try_aggregate(
+            &self.rand_config,
+            self.with_proofs,
+            self.decision_tx.clone(),
+        );
         // fast path
         if let (Some(fast_rand_map), Some(fast_rand_config)) =
             (self.fast_rand_map.as_mut(), self.fast_rand_config.as_ref())
@@ -265,7 +289,11 @@ impl<S: TShare> RandStore<S> {
                 .entry(rand_metadata.round())
                 .or_insert_with(|| RandItem::new(self.author, PathType::Fast));
             fast_rand_item.add_metadata(fast_rand_config, rand_metadata.clone());
-            fast_rand_item.try_aggregate(fast_rand_config, self.decision_tx.clone());
+            fast_rand_item.try_aggregate(
+                fast_rand_config,
+                self.with_proofs,
+                self.decision_tx.clone(),
+            );
         }
     }
 
@@ -300,7 +328,7 @@ impl<S: TShare> RandStore<S> {
         };
 
         rand_item.add_share(share, rand_config)?;
-        rand_item.try_aggregate(rand_config, self.decision_tx.clone());
+        rand_item.try_aggregate(rand_config, self.with_proofs, self.decision_tx.clone());
         Ok(rand_item.has_decision())
     }
 
@@ -502,7 +530,7 @@ mod tests {
             FullRandMetadata::new(ctxt.target_epoch, 1, HashValue::zero(), 1700000000),
         );
         assert_eq!(item.total_weights().unwrap(), 5);
-        item.try_aggregate(&ctxt.rand_config, tx);
+        item.try_aggregate(&ctxt.rand_config, false, tx);
         assert!(item.has_decision());
 
         let mut item = RandItem::<MockShare>::new(ctxt.authors[0], PathType::Slow);
@@ -525,6 +553,7 @@ mod tests {
             ctxt.authors[1],
             ctxt.rand_config.clone(),
             None,
+            false,
             decision_tx,
         );
 
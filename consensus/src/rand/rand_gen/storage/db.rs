@@ -7,30 +7,40 @@ use crate::{
         storage::{
             interface::RandStorage,
             schema::{
-                AugDataSchema, CertifiedAugDataSchema, KeyPairSchema, AUG_DATA_CF_NAME,
-                CERTIFIED_AUG_DATA_CF_NAME, KEY_PAIR_CF_NAME,
+                AugDataSchema, CertifiedAugDataSchema, KeyPairSchema, RandomnessDeltaKey,
+                RandomnessDeltaSchema, RandomnessProofSchema, AUG_DATA_CF_NAME,
+                CERTIFIED_AUG_DATA_CF_NAME, KEY_PAIR_CF_NAME, RANDOMNESS_DELTA_CF_NAME,
+                RANDOMNESS_PROOF_CF_NAME,
             },
         },
         types::{AugData, AugDataId, CertifiedAugData, TAugmentedData},
     },
 };
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use aptos_infallible::Mutex;
 use aptos_logger::info;
 use aptos_schemadb::{schema::Schema, Options, SchemaBatch, DB};
-use std::{path::Path, sync::Arc, time::Instant};
+use aptos_types::randomness::{
+    RandMetadata, RandomnessProof, RandomnessProofReader, RandomnessProofShare,
+};
+use std::{collections::HashSet, path::Path, sync::Arc, time::Instant};
 
 pub struct RandDb {
     db: Arc<DB>,
+    // The deltas are the same for all the proofs of an epoch, so they are only written once
+    saved_deltas: Mutex<HashSet<RandomnessDeltaKey>>,
 }
 
 pub const RAND_DB_NAME: &str = "rand_db";
 
 impl RandDb {
-    pub(crate) fn new<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
+    pub fn new<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
         let column_families = vec![
             KEY_PAIR_CF_NAME,
             AUG_DATA_CF_NAME,
             CERTIFIED_AUG_DATA_CF_NAME,
+            RANDOMNESS_PROOF_CF_NAME,
+            RANDOMNESS_DELTA_CF_NAME,
         ];
 
         let path = db_root_path.as_ref().join(RAND_DB_NAME);
@@ -49,7 +59,10 @@ impl RandDb {
             instant.elapsed().as_millis()
         );
 
-        Self { db }
+        Self {
+            db,
+            saved_deltas: Mutex::new(HashSet::new()),
+        }
     }
 
     fn commit(&self, batch: SchemaBatch) -> Result<(), DbError> {
@@ -80,6 +93,55 @@ impl RandDb {
             })
             .collect::<Vec<(S::Key, S::Value)>>())
     }
+
+    /// Returns the first keys of the schema, while `is_before` holds
+    fn get_keys_while<S: Schema>(
+        &self,
+        is_before: impl Fn(&S::Key) -> bool,
+    ) -> Result<Vec<S::Key>, DbError> {
+        let mut iter = self.db.iter::<S>()?;
+        iter.seek_to_first();
+        Ok(iter
+            .filter_map(|e| e.ok().map(|(k, _)| k))
+            .take_while(is_before)
+            .collect())
+    }
+
+    fn get_proof(&self, metadata: &RandMetadata) -> Result<Option<RandomnessProof>> {
+        let Some((fast_path, shares)) = self.db.get::<RandomnessProofSchema>(metadata)? else {
+            return Ok(None);
+        };
+        let shares = shares
+            .into_iter()
+            .map(|(player, share)| {
+                let key = RandomnessDeltaKey {
+                    epoch: metadata.epoch,
+                    fast_path,
+                    player,
+                };
+                let delta = self
+                    .db
+                    .get::<RandomnessDeltaSchema>(&key)?
+                    .ok_or_else(|| anyhow!("Missing delta of {:?}", key))?;
+                Ok(RandomnessProofShare {
+                    player,
+                    delta,
+                    share,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Some(RandomnessProof::new(
+            metadata.clone(),
+            fast_path,
+            shares,
+        )))
+    }
+}
+
+impl RandomnessProofReader for RandDb {
+    fn get_randomness_proof(&self, metadata: &RandMetadata) -> Result<Option<RandomnessProof>> {
+        self.get_proof(metadata)
+    }
 }
 
 impl<D: TAugmentedData> RandStorage<D> for RandDb {
@@ -118,4 +180,44 @@ impl<D: TAugmentedData> RandStorage<D> for RandDb {
         Ok(self
             .delete::<CertifiedAugDataSchema<D>>(certified_aug_data.into_iter().map(|d| d.id()))?)
     }
+
+    fn save_randomness_proof(&self, proof: &RandomnessProof) -> Result<()> {
+        let batch = SchemaBatch::new();
+        let mut saved_deltas = self.saved_deltas.lock();
+        let mut new_deltas = vec![];
+        for share in &proof.shares {
+            let key = RandomnessDeltaKey {
+                epoch: proof.metadata.epoch,
+                fast_path: proof.fast_path,
+                player: share.player,
+            };
+            if !saved_deltas.contains(&key) {
+                batch.put::<RandomnessDeltaSchema>(&key, &share.delta)?;
+                new_deltas.push(key);
+            }
+        }
+        let shares = proof
+            .shares
+            .iter()
+            .map(|share| (share.player, share.share))
+            .collect();
+        batch.put::<RandomnessProofSchema>(&proof.metadata, &(proof.fast_path, shares))?;
+        self.commit(batch)?;
+        saved_deltas.extend(new_deltas);
+        Ok(())
+    }
+
+    fn get_randomness_proof(&self, metadata: &RandMetadata) -> Result<Option<RandomnessProof>> {
+        self.get_proof(metadata)
+    }
+
+    fn remove_randomness_proofs_before(&self, epoch: u64) -> Result<()> {
+        let proof_keys =
+            self.get_keys_while::<RandomnessProofSchema>(|metadata| metadata.epoch < epoch)?;
+        self.delete::<RandomnessProofSchema>(proof_keys.into_iter())?;
+        let delta_keys = self.get_keys_while::<RandomnessDeltaSchema>(|key| key.epoch < epoch)?;
+        self.delete::<RandomnessDeltaSchema>(delta_keys.into_iter())?;
+        self.saved_deltas.lock().retain(|key| key.epoch >= epoch);
+        Ok(())
+    }
 }
@@ -6,12 +6,14 @@ use crate::rand::rand_gen::{
     types::{AugData, AugDataId, CertifiedAugData, TAugmentedData},
 };
 use aptos_infallible::RwLock;
+use aptos_types::randomness::{RandMetadata, RandomnessProof};
 use std::collections::HashMap;
 
 pub struct InMemRandDb<D> {
     key_pair: RwLock<Option<(u64, Vec<u8>)>>,
     aug_data: RwLock<HashMap<AugDataId, AugData<D>>>,
     certified_aug_data: RwLock<HashMap<AugDataId, CertifiedAugData<D>>>,
+    randomness_proofs: RwLock<HashMap<RandMetadata, RandomnessProof>>,
 }
 
 impl<D> InMemRandDb<D> {
@@ -20,6 +22,7 @@ impl<D> InMemRandDb<D> {
             key_pair: RwLock::new(None),
             aug_data: RwLock::new(HashMap::new()),
             certified_aug_data: RwLock::new(HashMap::new()),
+            randomness_proofs: RwLock::new(HashMap::new()),
         }
     }
 }
@@ -75,4 +78,25 @@ impl<D: TAugmentedData> RandStorage<D> for InMemRandDb<D> {
         }
         Ok(())
     }
+
+    fn save_randomness_proof(&self, proof: &RandomnessProof) -> anyhow::Result<()> {
+        self.randomness_proofs
+            .write()
+            .insert(proof.metadata.clone(), proof.clone());
+        Ok(())
+    }
+
+    fn get_randomness_proof(
+        &self,
+        metadata: &RandMetadata,
+    ) -> anyhow::Result<Option<RandomnessProof>> {
+        Ok(self.randomness_proofs.read().get(metadata).cloned())
+    }
+
+    fn remove_randomness_proofs_before(&self, epoch: u64) -> anyhow::Result<()> {
+        self.randomness_proofs
+            .write()
+            .retain(|metadata, _| metadata.epoch >= epoch);
+        Ok(())
+    }
 }
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::rand::rand_gen::types::{AugData, AugDataId, CertifiedAugData};
+use aptos_types::randomness::{RandMetadata, RandomnessProof};
 
 pub trait RandStorage<D>: Send + Sync + 'static {
     fn save_key_pair_bytes(&self, epoch: u64, key_pair: Vec<u8>) -> anyhow::Result<()>;
@@ -20,4 +21,12 @@ pub trait RandStorage<D>: Send + Sync + 'static {
         &self,
         certified_aug_data: Vec<CertifiedAugData<D>>,
     ) -> anyhow::Result<()>;
+
+    fn save_randomness_proof(&self, proof: &RandomnessProof) -> anyhow::Result<()>;
+    fn get_randomness_proof(
+        &self,
+        metadata: &RandMetadata,
+    ) -> anyhow::Result<Option<RandomnessProof>>;
+    /// Removes the proofs of the epochs before `epoch`
+    fn remove_randomness_proofs_before(&self, epoch: u64) -> anyhow::Result<()>;
 }
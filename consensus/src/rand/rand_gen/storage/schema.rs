@@ -2,12 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::rand::rand_gen::types::{AugData, AugDataId, CertifiedAugData, TAugmentedData};
+use anyhow::ensure;
+use aptos_dkg::pvss::Player;
 use aptos_schemadb::{
     define_schema,
     schema::{KeyCodec, Schema, ValueCodec},
     ColumnFamilyName,
 };
-use std::marker::PhantomData;
+use aptos_types::randomness::{Delta, ProofShare, RandMetadata};
+use std::{marker::PhantomData, mem::size_of};
 
 pub(crate) const KEY_PAIR_CF_NAME: ColumnFamilyName = "key_pair";
 
@@ -94,3 +97,99 @@ impl<D: TAugmentedData> ValueCodec<CertifiedAugDataSchema<D>> for CertifiedAugDa
         Ok(bcs::from_bytes(data)?)
     }
 }
+
+pub(crate) const RANDOMNESS_PROOF_CF_NAME: ColumnFamilyName = "randomness_proof";
+
+/// The shares of a randomness proof (the deltas are stored once per epoch, see
+/// `RandomnessDeltaSchema`), and whether they were created by the fast path.
+pub(crate) type RandomnessProofShares = (bool, Vec<(Player, ProofShare)>);
+
+define_schema!(
+    RandomnessProofSchema,
+    RandMetadata,
+    RandomnessProofShares,
+    RANDOMNESS_PROOF_CF_NAME
+);
+
+// Big endian, so that the proofs are ordered by epoch and round
+impl KeyCodec<RandomnessProofSchema> for RandMetadata {
+    fn encode_key(&self) -> anyhow::Result<Vec<u8>> {
+        Ok([self.epoch.to_be_bytes(), self.round.to_be_bytes()].concat())
+    }
+
+    fn decode_key(data: &[u8]) -> anyhow::Result<Self> {
+        ensure!(
+            data.len() == 2 * size_of::<u64>(),
+            "Unexpected key length {}",
+            data.len()
+        );
+        Ok(RandMetadata {
+            epoch: u64::from_be_bytes(data[..8].try_into()?),
+            round: u64::from_be_bytes(data[8..].try_into()?),
+        })
+    }
+}
+
+impl ValueCodec<RandomnessProofSchema> for RandomnessProofShares {
+    fn encode_value(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(bcs::to_bytes(self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> anyhow::Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
+
+pub(crate) const RANDOMNESS_DELTA_CF_NAME: ColumnFamilyName = "randomness_delta";
+
+/// Identifies the delta of a validator for an epoch and path (fast or not)
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub(crate) struct RandomnessDeltaKey {
+    pub epoch: u64,
+    pub fast_path: bool,
+    pub player: Player,
+}
+
+define_schema!(
+    RandomnessDeltaSchema,
+    RandomnessDeltaKey,
+    Delta,
+    RANDOMNESS_DELTA_CF_NAME
+);
+
+// Big endian, so that the deltas are ordered by epoch
+impl KeyCodec<RandomnessDeltaSchema> for RandomnessDeltaKey {
+    fn encode_key(&self) -> anyhow::Result<Vec<u8>> {
+        Ok([
+            &self.epoch.to_be_bytes()[..],
+            &[self.fast_path as u8],
+            &(self.player.id as u64).to_be_bytes(),
+        ]
+        .concat())
+    }
+
+    fn decode_key(data: &[u8]) -> anyhow::Result<Self> {
+        ensure!(
+            data.len() == 2 * size_of::<u64>() + 1,
+            "Unexpected key length {}",
+            data.len()
+        );
+        Ok(RandomnessDeltaKey {
+            epoch: u64::from_be_bytes(data[..8].try_into()?),
+            fast_path: data[8] != 0,
+            player: Player {
+                id: u64::from_be_bytes(data[9..].try_into()?) as usize,
+            },
+        })
+    }
+}
+
+impl ValueCodec<RandomnessDeltaSchema> for Delta {
+    fn encode_value(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(bcs::to_bytes(self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> anyhow::Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
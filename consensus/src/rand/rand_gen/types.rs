@@ -14,7 +14,8 @@ use aptos_logger::debug;
 use aptos_types::{
     aggregate_signature::AggregateSignature,
     randomness::{
-        Delta, PKShare, ProofShare, RandKeys, RandMetadata, Randomness, WvufPP, APK, WVUF,
+        Delta, PKShare, ProofShare, RandKeys, RandMetadata, Randomness, RandomnessProof,
+        RandomnessProofShare, WvufPP, APK, WVUF,
     },
     validator_verifier::ValidatorVerifier,
 };
@@ -25,7 +26,7 @@ use std::{fmt::Debug, sync::Arc};
 pub const NUM_THREADS_FOR_WVUF_DERIVATION: usize = 8;
 pub const FUTURE_ROUNDS_TO_ACCEPT: u64 = 200;
 
-#[derive(PartialEq)]
+#[derive(Clone, Copy, PartialEq)]
 pub enum PathType {
     Fast,
     Slow,
@@ -98,7 +99,9 @@ impl TShare for Share {
         shares: impl Iterator<Item = &'a RandShare<Self>>,
         rand_config: &RandConfig,
         rand_metadata: RandMetadata,
-    ) -> anyhow::Result<Randomness>
+        path_type: PathType,
+        with_proof: bool,
+    ) -> anyhow::Result<(Randomness, Option<RandomnessProof>)>
     where
         Self: Sized,
     {
@@ -144,7 +147,23 @@ impl TShare for Share {
         let eval_bytes = bcs::to_bytes(&eval)
             .map_err(|e| anyhow!("Share::aggregate failed with eval serialization error: {e}"))?;
         let rand_bytes = Sha3_256::digest(eval_bytes.as_slice()).to_vec();
-        Ok(Randomness::new(rand_metadata, rand_bytes))
+        let maybe_proof = with_proof.then(|| {
+            // The augmented public key share is the delta and the dealt public key share
+            let proof_shares = apks_and_proofs
+                .into_iter()
+                .map(|(player, (delta, _), share)| RandomnessProofShare {
+                    player,
+                    delta,
+                    share,
+                })
+                .collect();
+            RandomnessProof::new(
+                rand_metadata.clone(),
+                path_type == PathType::Fast,
+                proof_shares,
+            )
+        });
+        Ok((Randomness::new(rand_metadata, rand_bytes), maybe_proof))
     }
 }
 
@@ -236,11 +255,13 @@ impl TShare for MockShare {
         _shares: impl Iterator<Item = &'a RandShare<Self>>,
         _rand_config: &RandConfig,
         rand_metadata: RandMetadata,
-    ) -> anyhow::Result<Randomness>
+        _path_type: PathType,
+        _with_proof: bool,
+    ) -> anyhow::Result<(Randomness, Option<RandomnessProof>)>
     where
         Self: Sized,
     {
-        Ok(Randomness::new(rand_metadata, vec![]))
+        Ok((Randomness::new(rand_metadata, vec![]), None))
     }
 }
 
@@ -284,11 +305,15 @@ pub trait TShare:
     where
         Self: Sized;
 
+    /// Aggregates the shares (of the given path) into the randomness. The proof of the
+    /// randomness is only built if `with_proof` is set.
     fn aggregate<'a>(
         shares: impl Iterator<Item = &'a RandShare<Self>>,
         rand_config: &RandConfig,
         rand_metadata: RandMetadata,
-    ) -> anyhow::Result<Randomness>
+        path_type: PathType,
+        with_proof: bool,
+    ) -> anyhow::Result<(Randomness, Option<RandomnessProof>)>
     where
         Self: Sized;
 }
@@ -14,6 +14,10 @@ use crate::{
         QC_AGGREGATED_FROM_VOTES, SYNC_INFO_RECEIVED_WITH_NEWER_CERT,
     },
     error::{error_kind, VerifyError},
+    flight_recorder::{
+        recorder::FlightRecorder,
+        types::{FlightEvent, FlightRecord, RoundManagerCheckpoint, SafetyRulesDecision},
+    },
     liveness::{
         proposal_generator::ProposalGenerator,
         proposer_election::ProposerElection,
@@ -30,9 +34,9 @@ use crate::{
     persistent_liveness_storage::PersistentLivenessStorage,
     quorum_store::types::BatchMsg,
     rand::rand_gen::types::{FastShare, RandConfig, Share, TShare},
-    util::is_vtxn_expected,
+    util::{is_vtxn_expected, verify_vtxn},
 };
-use anyhow::{bail, ensure, Context};
+use anyhow::{anyhow, bail, ensure, Context};
 use aptos_channels::aptos_channel;
 use aptos_config::config::ConsensusConfig;
 use aptos_consensus_types::{
@@ -40,6 +44,7 @@ use aptos_consensus_types::{
     block_data::BlockType,
     common::{Author, Round},
     delayed_qc_msg::DelayedQcMsg,
+    equivocation_evidence::EquivocationEvidence,
     order_vote_msg::OrderVoteMsg,
     proof_of_store::{ProofCache, ProofOfStoreMsg, SignedBatchInfoMsg},
     proposal_msg::ProposalMsg,
@@ -61,19 +66,21 @@ use aptos_types::{
     block_info::BlockInfo,
     epoch_state::EpochState,
     on_chain_config::{
-        OnChainConsensusConfig, OnChainJWKConsensusConfig, OnChainRandomnessConfig,
+        Features, OnChainConsensusConfig, OnChainJWKConsensusConfig, OnChainRandomnessConfig,
         ValidatorTxnConfig,
     },
     randomness::RandMetadata,
+    validator_txn::ValidatorTransaction,
     validator_verifier::ValidatorVerifier,
     PeerId,
 };
+use aptos_validator_transaction_pool::{TxnGuard, VTxnPoolState};
 use fail::fail_point;
 use futures::{channel::oneshot, stream::FuturesUnordered, Future, FutureExt, StreamExt};
 use futures_channel::mpsc::UnboundedReceiver;
 use lru::LruCache;
 use serde::Serialize;
-use std::{mem::Discriminant, pin::Pin, sync::Arc, time::Duration};
+use std::{collections::HashMap, mem::Discriminant, pin::Pin, sync::Arc, time::Duration};
 use tokio::{
     sync::oneshot as TokioOneshot,
     time::{sleep, Instant},
@@ -243,6 +250,7 @@ pub struct RoundManager {
     local_config: ConsensusConfig,
     randomness_config: OnChainRandomnessConfig,
     jwk_consensus_config: OnChainJWKConsensusConfig,
+    features: Features,
     fast_rand_config: Option<RandConfig>,
     // Stores the order votes from all the rounds above highest_ordered_round
     pending_order_votes: PendingOrderVotes,
@@ -251,6 +259,12 @@ pub struct RoundManager {
     // which we recently broadcasted fast shares.
     blocks_with_broadcasted_fast_shares: LruCache<HashValue, ()>,
     futures: FuturesUnordered<Pin<Box<dyn Future<Output = (anyhow::Result<()>, Block)> + Send>>>,
+    // An optional recorder for the events and decisions of the round manager
+    flight_recorder: Option<FlightRecorder>,
+    // The pool used to submit equivocation reports on-chain (if one is attached), and the
+    // guards of the submitted reports (by evidence hash), which keep them in the pool
+    vtxn_pool: Option<VTxnPoolState>,
+    equivocation_report_guards: HashMap<HashValue, TxnGuard>,
 }
 
 impl RoundManager {
@@ -269,6 +283,7 @@ impl RoundManager {
         local_config: ConsensusConfig,
         randomness_config: OnChainRandomnessConfig,
         jwk_consensus_config: OnChainJWKConsensusConfig,
+        features: Features,
         fast_rand_config: Option<RandConfig>,
     ) -> Self {
         // when decoupled execution is false,
@@ -296,10 +311,14 @@ impl RoundManager {
             local_config,
             randomness_config,
             jwk_consensus_config,
+            features,
             fast_rand_config,
             pending_order_votes: PendingOrderVotes::new(),
             blocks_with_broadcasted_fast_shares: LruCache::new(5),
             futures: FuturesUnordered::new(),
+            flight_recorder: None,
+            vtxn_pool: None,
+            equivocation_report_guards: HashMap::new(),
         }
     }
 
@@ -350,6 +369,10 @@ impl RoundManager {
         );
         self.pending_order_votes
             .garbage_collect(self.block_store.sync_info().highest_ordered_round());
+        let proposer = self
+            .proposer_election
+            .get_valid_proposer(new_round_event.round);
+        self.record_flight_event(|| FlightEvent::NewRound { proposer });
 
         if self
             .proposer_election
@@ -540,6 +563,7 @@ impl RoundManager {
         fail_point!("consensus::process_proposal_msg", |_| {
             Err(anyhow::anyhow!("Injected error in process_proposal_msg"))
         });
+        self.record_flight_event(|| FlightEvent::ProposalReceived(Box::new(proposal_msg.clone())));
 
         observe_block(
             proposal_msg.proposal().timestamp_usecs(),
@@ -570,6 +594,9 @@ impl RoundManager {
     }
 
     pub async fn process_delayed_proposal_msg(&mut self, proposal: Block) -> anyhow::Result<()> {
+        self.record_flight_event(|| {
+            FlightEvent::DelayedProposalReceived(Box::new(proposal.clone()))
+        });
         if proposal.round() != self.round_state.current_round() {
             bail!(
                 "Discarding stale delayed proposal {}, current round {}",
@@ -582,6 +609,7 @@ impl RoundManager {
     }
 
     pub async fn process_delayed_qc_msg(&mut self, msg: DelayedQcMsg) -> anyhow::Result<()> {
+        self.record_flight_event(|| FlightEvent::DelayedQcReceived(Box::new(msg.clone())));
         ensure!(
             msg.vote.vote_data().proposed().round() == self.round_state.current_round(),
             "Discarding stale delayed QC for round {}, current round {}",
@@ -671,6 +699,9 @@ impl RoundManager {
         fail_point!("consensus::process_sync_info_msg", |_| {
             Err(anyhow::anyhow!("Injected error in process_sync_info_msg"))
         });
+        self.record_flight_event(|| {
+            FlightEvent::SyncInfoReceived(Box::new(sync_info.clone()), peer)
+        });
         info!(
             self.new_log(LogEvent::ReceiveSyncInfo).remote_peer(peer),
             "{}", sync_info
@@ -702,6 +733,7 @@ impl RoundManager {
     /// Note this function returns Err even if messages are broadcasted successfully because timeout
     /// is considered as error. It only returns Ok(()) when the timeout is stale.
     pub async fn process_local_timeout(&mut self, round: Round) -> anyhow::Result<()> {
+        self.record_flight_event(|| FlightEvent::LocalTimeout(round));
         if !self.round_state.process_local_timeout(round) {
             return Ok(());
         }
@@ -735,14 +767,18 @@ impl RoundManager {
         if !timeout_vote.is_timeout() {
             let timeout = timeout_vote
                 .generate_2chain_timeout(self.block_store.highest_quorum_cert().as_ref().clone());
-            let signature = self
-                .safety_rules
-                .lock()
-                .sign_timeout_with_qc(
-                    &timeout,
-                    self.block_store.highest_2chain_timeout_cert().as_deref(),
-                )
-                .context("[RoundManager] SafetyRules signs 2-chain timeout")?;
+            let signature_result = self.safety_rules.lock().sign_timeout_with_qc(
+                &timeout,
+                self.block_store.highest_2chain_timeout_cert().as_deref(),
+            );
+            self.record_flight_event(|| {
+                FlightEvent::SafetyRulesDecision(SafetyRulesDecision::TimeoutSignature {
+                    round,
+                    result: signature_result.clone(),
+                })
+            });
+            let signature =
+                signature_result.context("[RoundManager] SafetyRules signs 2-chain timeout")?;
             timeout_vote.add_2chain_timeout(timeout, signature);
         }
 
@@ -791,10 +827,16 @@ impl RoundManager {
         if let Some(vtxns) = proposal.validator_txns() {
             for vtxn in vtxns {
                 ensure!(
-                    is_vtxn_expected(&self.randomness_config, &self.jwk_consensus_config, vtxn),
+                    is_vtxn_expected(
+                        &self.randomness_config,
+                        &self.jwk_consensus_config,
+                        &self.features,
+                        vtxn
+                    ),
                     "unexpected validator txn: {:?}",
                     vtxn.topic()
                 );
+                verify_vtxn(&self.epoch_state, author, vtxn)?;
             }
         }
 
@@ -854,12 +896,17 @@ impl RoundManager {
             self.local_config.max_receiving_block_bytes,
         );
 
-        ensure!(
-            self.proposer_election.is_valid_proposal(&proposal),
-            "[RoundManager] Proposer {} for block {} is not a valid proposer for this round or created duplicate proposal",
-            author,
-            proposal,
-        );
+        if !self.proposer_election.is_valid_proposal(&proposal) {
+            if let Some(evidence) = self.proposer_election.take_equivocation_evidence() {
+                self.record_equivocation_evidence(evidence);
+            }
+            bail!(
+                "[RoundManager] Proposer {} for block {} is not a valid proposer for this round or created duplicate proposal",
+                author,
+                proposal,
+            );
+        }
+        self.release_certified_equivocation_reports(&proposal);
 
         // Validate that failed_authors list is correctly specified in the block.
         let expected_failed_authors = self.proposal_generator.compute_failed_authors(
@@ -946,6 +993,17 @@ impl RoundManager {
         self.process_verified_proposal(proposal).await
     }
 
+    /// Processes a proposal once its (previously missing) payload becomes available
+    pub(crate) async fn process_proposal_with_available_payload(
+        &mut self,
+        proposal: Block,
+    ) -> anyhow::Result<()> {
+        self.record_flight_event(|| {
+            FlightEvent::ProposalPayloadAvailable(Box::new(proposal.clone()))
+        });
+        self.check_backpressure_and_process_proposal(proposal).await
+    }
+
     async fn resend_verified_proposal_to_self(
         block_store: Arc<BlockStore>,
         self_sender: aptos_channel::Sender<Author, VerifiedEvent>,
@@ -1051,6 +1109,13 @@ impl RoundManager {
             &vote_proposal,
             self.block_store.highest_2chain_timeout_cert().as_deref(),
         );
+        self.record_flight_event(|| {
+            FlightEvent::SafetyRulesDecision(SafetyRulesDecision::Vote {
+                block_id: block_arc.id(),
+                round: block_arc.round(),
+                result: vote_result.clone(),
+            })
+        });
         let vote = vote_result.context(format!(
             "[RoundManager] SafetyRules Rejected {}",
             block_arc.block()
@@ -1066,7 +1131,13 @@ impl RoundManager {
         Ok(vote)
     }
 
-    async fn process_order_vote_msg(&mut self, order_vote_msg: OrderVoteMsg) -> anyhow::Result<()> {
+    pub(crate) async fn process_order_vote_msg(
+        &mut self,
+        order_vote_msg: OrderVoteMsg,
+    ) -> anyhow::Result<()> {
+        self.record_flight_event(|| {
+            FlightEvent::OrderVoteReceived(Box::new(order_vote_msg.clone()))
+        });
         if self.onchain_config.order_vote_enabled() {
             fail_point!("consensus::process_order_vote_msg", |_| {
                 Err(anyhow::anyhow!("Injected error in process_order_vote_msg"))
@@ -1122,6 +1193,13 @@ impl RoundManager {
                 .safety_rules
                 .lock()
                 .construct_and_sign_order_vote(&order_vote_proposal);
+            self.record_flight_event(|| {
+                FlightEvent::SafetyRulesDecision(SafetyRulesDecision::OrderVote {
+                    block_id: proposed_block.id(),
+                    round: proposed_block.round(),
+                    result: order_vote_result.clone(),
+                })
+            });
             let order_vote = order_vote_result.context(format!(
                 "[RoundManager] SafetyRules Rejected {} for order vote",
                 proposed_block.block()
@@ -1153,6 +1231,7 @@ impl RoundManager {
         fail_point!("consensus::process_vote_msg", |_| {
             Err(anyhow::anyhow!("Injected error in process_vote_msg"))
         });
+        self.record_flight_event(|| FlightEvent::VoteReceived(Box::new(vote_msg.clone())));
         // Check whether this validator is a valid recipient of the vote.
         if self
             .ensure_round_and_sync_up(
@@ -1274,6 +1353,11 @@ impl RoundManager {
             VoteReceptionResult::VoteAddedQCDelayed(_)
             | VoteReceptionResult::EchoTimeout(_)
             | VoteReceptionResult::DuplicateVote => Ok(()),
+            VoteReceptionResult::EquivocateVote(evidence) => {
+                let offender = evidence.offender();
+                self.record_equivocation_evidence(*evidence);
+                bail!("Equivocating vote from {} for round {}", offender, round)
+            },
             e => Err(anyhow::anyhow!("{:?}", e)),
         }
     }
@@ -1410,6 +1494,193 @@ impl RoundManager {
         &self.epoch_state
     }
 
+    /// Attaches a flight recorder to the round manager. This should be called after
+    /// `init()`, as the recording starts with a checkpoint of the current state
+    /// (and replays recreate the round manager from the checkpoint using `init()`).
+    pub fn set_flight_recorder(&mut self, flight_recorder: FlightRecorder) {
+        self.flight_recorder = Some(flight_recorder);
+        self.record_checkpoint_if_required();
+    }
+
+    /// Attaches the validator transaction pool, which is used to submit the collected
+    /// equivocation evidence on-chain
+    pub fn set_vtxn_pool(&mut self, vtxn_pool: VTxnPoolState) {
+        self.vtxn_pool = Some(vtxn_pool);
+    }
+
+    /// Persists the evidence of an equivocating validator, and submits it on-chain as a
+    /// validator transaction (if validator transactions are enabled)
+    fn record_equivocation_evidence(&mut self, evidence: EquivocationEvidence) {
+        warn!("Collected equivocation evidence: {}", evidence);
+        if let Err(error) = self.storage.save_equivocation_evidence(&evidence) {
+            error!(
+                error = ?error,
+                "Failed to persist the equivocation evidence {}", evidence
+            );
+        }
+
+        let vtxn_pool = match &self.vtxn_pool {
+            Some(vtxn_pool)
+                if self.vtxn_config.enabled()
+                    && self.features.is_equivocation_reports_enabled() =>
+            {
+                vtxn_pool
+            },
+            _ => return,
+        };
+        match evidence.to_report(self.proposal_generator.author()) {
+            Ok(report) => {
+                let evidence_hash = report.evidence_hash();
+                let txn = ValidatorTransaction::EquivocationReport(report);
+                let guard = vtxn_pool.put(txn.topic(), Arc::new(txn), None);
+                self.equivocation_report_guards.insert(evidence_hash, guard);
+            },
+            Err(error) => error!(
+                error = ?error,
+                "Failed to create the equivocation report for {}", evidence
+            ),
+        }
+    }
+
+    /// Removes the equivocation reports included in the block certified by the given
+    /// proposal from the validator transaction pool (as they are about to be committed)
+    fn release_certified_equivocation_reports(&mut self, proposal: &Block) {
+        if self.equivocation_report_guards.is_empty() {
+            return;
+        }
+        let certified_block_id = proposal.quorum_cert().certified_block().id();
+        if let Some(certified_block) = self.block_store.get_block(certified_block_id) {
+            for vtxn in certified_block
+                .block()
+                .validator_txns()
+                .into_iter()
+                .flatten()
+            {
+                if let ValidatorTransaction::EquivocationReport(report) = vtxn {
+                    self.equivocation_report_guards
+                        .remove(&report.evidence_hash());
+                }
+            }
+        }
+    }
+
+    /// Detaches and returns the flight recorder (if one is attached)
+    pub(crate) fn take_flight_recorder(&mut self) -> Option<FlightRecorder> {
+        self.flight_recorder.take()
+    }
+
+    /// Records the given event using the flight recorder (if one is attached).
+    /// Decisions never trigger a checkpoint, so that they are always recorded in
+    /// the same segment as the event that caused them.
+    fn record_flight_event(&mut self, create_event: impl FnOnce() -> FlightEvent) {
+        if self.flight_recorder.is_none() {
+            return;
+        }
+
+        let event = create_event();
+        if !matches!(
+            event,
+            FlightEvent::NewRound { .. } | FlightEvent::SafetyRulesDecision(_)
+        ) {
+            self.record_checkpoint_if_required();
+        }
+        self.write_flight_record(event);
+    }
+
+    fn record_checkpoint_if_required(&mut self) {
+        let checkpoint_required = self
+            .flight_recorder
+            .as_ref()
+            .map_or(false, |flight_recorder| {
+                flight_recorder.is_checkpoint_required()
+            });
+        if checkpoint_required {
+            match self.create_flight_recorder_checkpoint() {
+                Ok(checkpoint) => {
+                    self.write_flight_record(FlightEvent::Checkpoint(Box::new(checkpoint)))
+                },
+                Err(error) => warn!(
+                    error = ?error,
+                    "[RoundManager] Failed to create a flight recorder checkpoint"
+                ),
+            }
+        }
+    }
+
+    fn write_flight_record(&mut self, event: FlightEvent) {
+        let record = FlightRecord::new(
+            self.epoch_state.epoch,
+            self.round_state.current_round(),
+            event,
+        );
+        if let Some(flight_recorder) = self.flight_recorder.as_mut() {
+            if let Err(error) = flight_recorder.record(record) {
+                warn!(
+                    error = ?error,
+                    "[RoundManager] Failed to write to the flight recorder"
+                );
+            }
+        }
+    }
+
+    /// Creates a checkpoint of the state required to recreate the round manager.
+    /// Only the block ids are snapshotted while holding the block tree lock, so
+    /// blocks that are pruned concurrently cause the checkpoint to fail.
+    fn create_flight_recorder_checkpoint(&self) -> anyhow::Result<RoundManagerCheckpoint> {
+        let root_block = self.block_store.commit_root();
+        let root_quorum_cert = self
+            .block_store
+            .get_quorum_cert_for_block(root_block.id())
+            .ok_or_else(|| {
+                anyhow!(
+                    "The commit root {} is missing its quorum cert",
+                    root_block.id()
+                )
+            })?;
+        let mut blocks = vec![];
+        let mut quorum_certs = vec![];
+        for block_id in self.block_store.get_block_ids_from_commit_root() {
+            let block = self
+                .block_store
+                .get_block(block_id)
+                .ok_or_else(|| anyhow!("Block {} was pruned during the checkpoint", block_id))?;
+            blocks.push(block.block().clone());
+            if let Some(quorum_cert) = self.block_store.get_quorum_cert_for_block(block_id) {
+                quorum_certs.push(quorum_cert.as_ref().clone());
+            }
+        }
+        let safety_data = self
+            .safety_rules
+            .lock()
+            .consensus_state()
+            .context("Failed to get the safety rules state for the checkpoint")?
+            .safety_data();
+
+        Ok(RoundManagerCheckpoint {
+            author: self.proposal_generator.author(),
+            epoch_state: self.epoch_state.as_ref().clone(),
+            onchain_config: self.onchain_config.clone(),
+            randomness_config: self.randomness_config.clone().into(),
+            jwk_consensus_config: self.jwk_consensus_config.clone(),
+            features: self.features.clone(),
+            max_receiving_block_txns: self.local_config.max_receiving_block_txns,
+            max_receiving_block_bytes: self.local_config.max_receiving_block_bytes,
+            vote_back_pressure_limit: self.local_config.vote_back_pressure_limit,
+            sync_only: self.local_config.sync_only,
+            root_block: root_block.block().clone(),
+            root_quorum_cert: root_quorum_cert.as_ref().clone(),
+            root_commit_cert: self.block_store.highest_commit_cert().as_ref().clone(),
+            blocks,
+            quorum_certs,
+            highest_2chain_timeout_cert: self
+                .block_store
+                .highest_2chain_timeout_cert()
+                .map(|timeout_cert| timeout_cert.as_ref().clone()),
+            last_vote: self.round_state.vote_sent(),
+            safety_data,
+        })
+    }
+
     pub fn round_state(&self) -> &RoundState {
         &self.round_state
     }
@@ -1509,7 +1780,7 @@ impl RoundManager {
                 Some((result, block)) = self.futures.next() => {
                     match result {
                         Ok(_) => {
-                            if let Err(e) = self.check_backpressure_and_process_proposal(block).await {
+                            if let Err(e) = self.process_proposal_with_available_payload(block).await {
                                 warn!("error {}", e);
                             }
                         },
@@ -42,8 +42,8 @@ use aptos_types::{
     epoch_state::EpochState,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     on_chain_config::{
-        OnChainConsensusConfig, OnChainJWKConsensusConfig, OnChainRandomnessConfig, ValidatorSet,
-        ValidatorTxnConfig,
+        Features, OnChainConsensusConfig, OnChainJWKConsensusConfig, OnChainRandomnessConfig,
+        ValidatorSet, ValidatorTxnConfig,
     },
     validator_info::ValidatorInfo,
     validator_signer::ValidatorSigner,
@@ -224,6 +224,7 @@ fn create_node_for_fuzzing() -> RoundManager {
         ConsensusConfig::default(),
         OnChainRandomnessConfig::default_enabled(),
         OnChainJWKConsensusConfig::default_enabled(),
+        Features::default(),
         None,
     )
 }
@@ -4,6 +4,11 @@
 
 use crate::{
     block_storage::{pending_blocks::PendingBlocks, BlockReader, BlockStore},
+    flight_recorder::{
+        recorder::{list_segments, read_segment, FlightRecorder},
+        replay::replay_recording,
+        types::{FlightEvent, RecordedDecision},
+    },
     liveness::{
         proposal_generator::{
             ChainHealthBackoffConfig, PipelineBackpressureConfig, ProposalGenerator,
@@ -29,7 +34,7 @@ use crate::{
 };
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
 use aptos_config::{
-    config::{ConsensusConfig, QcAggregatorType},
+    config::{ConsensusConfig, ConsensusFlightRecorderConfig, QcAggregatorType},
     network_id::{NetworkId, PeerNetworkId},
 };
 use aptos_consensus_types::{
@@ -61,12 +66,13 @@ use aptos_network::{
 };
 use aptos_safety_rules::{PersistentSafetyStorage, SafetyRulesManager};
 use aptos_secure_storage::Storage;
+use aptos_temppath::TempPath;
 use aptos_types::{
     epoch_state::EpochState,
     jwks::QuorumCertifiedUpdate,
     ledger_info::LedgerInfo,
     on_chain_config::{
-        ConsensusAlgorithmConfig, ConsensusConfigV1, OnChainConsensusConfig,
+        ConsensusAlgorithmConfig, ConsensusConfigV1, Features, OnChainConsensusConfig,
         OnChainJWKConsensusConfig, OnChainRandomnessConfig, ValidatorTxnConfig,
     },
     transaction::SignedTransaction,
@@ -341,6 +347,7 @@ impl NodeSetup {
             local_config,
             onchain_randomness_config.clone(),
             onchain_jwk_consensus_config.clone(),
+            Features::default(),
             None,
         );
         block_on(round_manager.init(last_vote_sent));
@@ -2438,3 +2445,78 @@ fn no_vote_on_proposal_ext_when_receiving_limit_exceeded() {
             .is_ok());
     });
 }
+
+#[test]
+fn flight_recorder_replay_matches_recording() {
+    let runtime = consensus_runtime();
+    let mut playground = NetworkPlayground::new(runtime.handle().clone());
+    let mut nodes = NodeSetup::create_nodes(
+        &mut playground,
+        runtime.handle().clone(),
+        1,
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    let node = &mut nodes[0];
+
+    // Attach a flight recorder to the round manager
+    let recorder_dir = TempPath::new();
+    let flight_recorder = FlightRecorder::new_file_recorder(
+        recorder_dir.path(),
+        &ConsensusFlightRecorderConfig::default(),
+    )
+    .unwrap();
+    node.round_manager.set_flight_recorder(flight_recorder);
+
+    timed_block_on(&runtime, async {
+        // Vote on the proposal for round 1 and form a QC
+        let proposal_msg = node.next_proposal().await;
+        node.round_manager
+            .process_proposal_msg(proposal_msg)
+            .await
+            .unwrap();
+        let vote_msg = node.next_vote().await;
+        node.round_manager.process_vote_msg(vote_msg).await.unwrap();
+
+        // Timeout in round 2 (this generates a nil vote and a timeout signature)
+        node.next_proposal().await;
+        assert!(node.round_manager.process_local_timeout(2).await.is_err());
+
+        // Replay the recording and verify the decisions match
+        node.round_manager
+            .take_flight_recorder()
+            .unwrap()
+            .flush()
+            .unwrap();
+        let segments = list_segments(recorder_dir.path()).unwrap();
+        assert_eq!(segments.len(), 1);
+        let mut records = read_segment(&segments[0].1).unwrap();
+        let replay_report = replay_recording(records.clone()).await.unwrap();
+        assert_eq!(replay_report.recorded_decisions.len(), 4);
+        assert_eq!(
+            replay_report.recorded_decisions,
+            replay_report.replayed_decisions
+        );
+        assert_eq!(replay_report.get_first_divergence(), None);
+
+        // Replay the recording with safety data that already voted in round 1,
+        // and verify that safety rules are re-run (i.e., the vote is rejected).
+        match &mut records[0].event {
+            FlightEvent::Checkpoint(checkpoint) => checkpoint.safety_data.last_voted_round = 1,
+            _ => panic!("The recording must start with a checkpoint!"),
+        }
+        let replay_report = replay_recording(records).await.unwrap();
+        let divergence_index = replay_report.get_first_divergence().unwrap();
+        assert!(matches!(
+            replay_report.replayed_decisions[divergence_index],
+            RecordedDecision::Vote {
+                round: 1,
+                succeeded: false,
+                ..
+            }
+        ));
+    });
+}
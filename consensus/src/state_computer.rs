@@ -554,7 +554,7 @@ async fn test_commit_sync_race() {
     executor.new_epoch(
         &EpochState::empty(),
         Arc::new(DirectMempoolPayloadManager {}),
-        create_transaction_shuffler(TransactionShufflerType::NoShuffling),
+        create_transaction_shuffler(TransactionShufflerType::NoShuffling, None),
         BlockExecutorConfigFromOnchain::new_no_block_limit(),
         create_transaction_deduper(TransactionDeduperType::NoDedup),
         false,
@@ -5,12 +5,14 @@
 use crate::{
     epoch_manager::LivenessStorageData,
     persistent_liveness_storage::{
-        LedgerRecoveryData, PersistentLivenessStorage, RecoveryData, RootMetadata,
+        LedgerRecoveryData, LivenessStorageDbs, PersistentLivenessStorage, RecoveryData,
+        RootMetadata,
     },
 };
 use anyhow::Result;
 use aptos_consensus_types::{
-    block::Block, quorum_cert::QuorumCert, timeout_2chain::TwoChainTimeoutCertificate, vote::Vote,
+    block::Block, equivocation_evidence::EquivocationEvidence, quorum_cert::QuorumCert,
+    timeout_2chain::TwoChainTimeoutCertificate, vote::Vote,
 };
 use aptos_crypto::HashValue;
 use aptos_infallible::Mutex;
@@ -32,6 +34,7 @@ pub struct MockSharedStorage {
 
     // Liveness state
     pub highest_2chain_timeout_certificate: Mutex<Option<TwoChainTimeoutCertificate>>,
+    pub equivocation_evidence: Mutex<HashMap<HashValue, EquivocationEvidence>>,
     pub validator_set: ValidatorSet,
 }
 
@@ -43,6 +46,7 @@ impl MockSharedStorage {
             lis: Mutex::new(HashMap::new()),
             last_vote: Mutex::new(None),
             highest_2chain_timeout_certificate: Mutex::new(None),
+            equivocation_evidence: Mutex::new(HashMap::new()),
             validator_set,
         }
     }
@@ -221,6 +225,14 @@ impl PersistentLivenessStorage for MockStorage {
         Ok(())
     }
 
+    fn save_equivocation_evidence(&self, evidence: &EquivocationEvidence) -> Result<()> {
+        self.shared_storage
+            .equivocation_evidence
+            .lock()
+            .insert(evidence.id(), evidence.clone());
+        Ok(())
+    }
+
     fn retrieve_epoch_change_proof(&self, version: u64) -> Result<EpochChangeProof> {
         let lis = self
             .shared_storage
@@ -231,7 +243,9 @@ impl PersistentLivenessStorage for MockStorage {
             .ok_or_else(|| anyhow::anyhow!("LedgerInfo for version not found"))?;
         Ok(EpochChangeProof::new(vec![lis], false))
     }
+}
 
+impl LivenessStorageDbs for MockStorage {
     fn aptos_db(&self) -> Arc<dyn DbReader> {
         unimplemented!()
     }
@@ -301,15 +315,11 @@ impl PersistentLivenessStorage for EmptyStorage {
         Ok(())
     }
 
-    fn retrieve_epoch_change_proof(&self, _version: u64) -> Result<EpochChangeProof> {
-        Ok(EpochChangeProof::new(vec![], false))
-    }
-
-    fn aptos_db(&self) -> Arc<dyn DbReader> {
-        unimplemented!()
+    fn save_equivocation_evidence(&self, _: &EquivocationEvidence) -> Result<()> {
+        Ok(())
     }
 
-    fn consensus_db(&self) -> Arc<crate::consensusdb::ConsensusDB> {
-        unimplemented!()
+    fn retrieve_epoch_change_proof(&self, _version: u64) -> Result<EpochChangeProof> {
+        Ok(EpochChangeProof::new(vec![], false))
     }
 }
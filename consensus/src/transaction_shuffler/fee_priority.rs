@@ -0,0 +1,459 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Orders transactions by fee priority, while keeping the block friendly to parallel execution
+//! and fair to whoever pays for the transactions:
+//!
+//! 1. Transactions calling into a single contract beyond `max_contract_share_percentage` of the
+//!    block are deferred to the tail of the block, which is the first to be cut off by the block
+//!    gas limit. Deferral never reorders the transactions of a sender: once a transaction of a
+//!    sender is deferred, all of its later transactions are deferred as well.
+//! 2. The remaining transactions are packed into conflict-free groups of at most
+//!    `conflict_window_size` transactions, where no two transactions of a group share a sender
+//!    or a (non-platform) contract, or access the same resource where one of them writes it, as
+//!    declared in the resource access metadata of the called modules. Groups are filled round robin across fee payers (the sender,
+//!    for self-paid transactions), so that a single sponsor cannot crowd out the others.
+//! 3. Within a group, transactions are ordered by gas unit price, highest first. As a group
+//!    never holds two transactions of the same sender, this preserves sequence number order.
+//!
+//! The deferred transactions are then grouped and ordered the same way.
+
+use crate::transaction_shuffler::{
+    resource_access_hints::ResourceAccessHints, TransactionShuffler,
+};
+use aptos_types::{
+    state_store::state_key::StateKey,
+    transaction::{
+        use_case::{UseCaseAwareTransaction, UseCaseKey},
+        SignedTransaction,
+    },
+};
+use move_core_types::account_address::AccountAddress;
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet, VecDeque},
+};
+
+/// The transaction properties the fee priority shuffler orders by, on top of the sender and
+/// use case.
+pub(crate) trait FeePriorityTransaction: UseCaseAwareTransaction {
+    fn parse_gas_unit_price(&self) -> u64;
+
+    /// The account paying for the transaction: the fee payer if set, otherwise the sender.
+    fn parse_fee_payer(&self) -> AccountAddress;
+}
+
+impl FeePriorityTransaction for SignedTransaction {
+    fn parse_gas_unit_price(&self) -> u64 {
+        self.gas_unit_price()
+    }
+
+    fn parse_fee_payer(&self) -> AccountAddress {
+        self.authenticator_ref()
+            .fee_payer_address()
+            .unwrap_or_else(|| self.sender())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Config {
+    pub conflict_window_size: usize,
+    pub max_contract_share_percentage: usize,
+}
+
+impl Config {
+    fn max_txns_per_contract(&self, num_txns: usize) -> usize {
+        (num_txns * self.max_contract_share_percentage)
+            .div_ceil(100)
+            .max(1)
+    }
+}
+
+pub struct FeePriorityShuffler {
+    pub(crate) config: Config,
+    pub(crate) resource_access_hints: Option<ResourceAccessHints>,
+}
+
+impl TransactionShuffler for FeePriorityShuffler {
+    fn shuffle(&self, txns: Vec<SignedTransaction>) -> Vec<SignedTransaction> {
+        match &self.resource_access_hints {
+            Some(hints) => shuffle(&self.config, txns, |txn| hints.accessed_state_keys(txn)),
+            None => shuffle(&self.config, txns, |_| vec![]),
+        }
+    }
+}
+
+/// Shuffles the transactions, given the state keys each of them declares to access, with whether
+/// they are written.
+pub(crate) fn shuffle<Txn: FeePriorityTransaction>(
+    config: &Config,
+    txns: Vec<Txn>,
+    accessed_state_keys: impl Fn(&Txn) -> Vec<(StateKey, bool)>,
+) -> Vec<Txn> {
+    let (admitted, deferred) = defer_over_share(config, txns);
+
+    let mut shuffled = order_in_groups(config.conflict_window_size, admitted, &accessed_state_keys);
+    shuffled.extend(order_in_groups(
+        config.conflict_window_size,
+        deferred,
+        &accessed_state_keys,
+    ));
+    shuffled
+}
+
+/// Splits the transactions into the ones admitted to the head of the block and the ones deferred
+/// to its tail because their contract already took up its share of the block.
+fn defer_over_share<Txn: FeePriorityTransaction>(
+    config: &Config,
+    txns: Vec<Txn>,
+) -> (Vec<Txn>, Vec<Txn>) {
+    let max_txns_per_contract = config.max_txns_per_contract(txns.len());
+    let mut txns_per_contract = HashMap::new();
+    let mut deferred_senders = HashSet::new();
+
+    let mut admitted = Vec::with_capacity(txns.len());
+    let mut deferred = Vec::new();
+    for txn in txns {
+        let sender = txn.parse_sender();
+        if deferred_senders.contains(&sender) {
+            deferred.push(txn);
+            continue;
+        }
+
+        if let use_case @ UseCaseKey::ContractAddress(..) = txn.parse_use_case() {
+            let num_txns = txns_per_contract.entry(use_case).or_insert(0);
+            if *num_txns >= max_txns_per_contract {
+                deferred_senders.insert(sender);
+                deferred.push(txn);
+                continue;
+            }
+            *num_txns += 1;
+        }
+        admitted.push(txn);
+    }
+
+    (admitted, deferred)
+}
+
+/// Orders the transactions as a sequence of conflict-free groups, see the module documentation.
+/// Relies on the transactions of each sender being given in sequence number order.
+///
+/// Pending transactions are kept in per fee payer queues, and only the fee payers with pending
+/// transactions are visited. Within a group, a fee payer whose next transaction cannot join the
+/// group (because of a conflict, or because an earlier transaction of the same sender is still
+/// pending) is not visited again, as that does not change until the next group. So building a
+/// group visits each fee payer with pending transactions at most once, plus once per added
+/// transaction.
+fn order_in_groups<Txn: FeePriorityTransaction>(
+    conflict_window_size: usize,
+    txns: Vec<Txn>,
+    accessed_state_keys: &impl Fn(&Txn) -> Vec<(StateKey, bool)>,
+) -> Vec<Txn> {
+    let conflict_window_size = conflict_window_size.max(1);
+    let num_txns = txns.len();
+
+    // Pending transactions (by index) of each fee payer, fee payers in order of first appearance.
+    let mut fee_payer_queues: Vec<VecDeque<usize>> = Vec::new();
+    let mut fee_payer_positions: HashMap<AccountAddress, usize> = HashMap::new();
+    // Pending transactions (by index) of each sender.
+    let mut sender_queues: HashMap<AccountAddress, VecDeque<usize>> = HashMap::new();
+    let mut keys = Vec::with_capacity(num_txns);
+    let mut accesses = Vec::with_capacity(num_txns);
+    for (idx, txn) in txns.iter().enumerate() {
+        let sender = txn.parse_sender();
+        let position = *fee_payer_positions
+            .entry(txn.parse_fee_payer())
+            .or_insert_with(|| {
+                fee_payer_queues.push(VecDeque::new());
+                fee_payer_queues.len() - 1
+            });
+        fee_payer_queues[position].push_back(idx);
+        sender_queues.entry(sender).or_default().push_back(idx);
+        keys.push((sender, txn.parse_use_case(), txn.parse_gas_unit_price()));
+        accesses.push(accessed_state_keys(txn));
+    }
+
+    // The fee payers with pending transactions, in round robin order.
+    let mut active_fee_payers: VecDeque<usize> = (0..fee_payer_queues.len()).collect();
+    let mut txns: Vec<_> = txns.into_iter().map(Some).collect();
+    let mut shuffled = Vec::with_capacity(num_txns);
+    while !active_fee_payers.is_empty() {
+        let mut group = Vec::new();
+        let mut group_senders = HashSet::new();
+        let mut group_contracts = HashSet::new();
+        let mut group_reads = HashSet::new();
+        let mut group_writes = HashSet::new();
+
+        // Take at most one transaction per fee payer and pass, until the group is full or no
+        // fee payer has a transaction that does not conflict with the group. Each pass only
+        // visits the fee payers that added a transaction in the previous pass. The earliest
+        // pending transaction is always eligible for an empty group, so every group makes
+        // progress.
+        let mut candidates: Vec<usize> = active_fee_payers.iter().copied().collect();
+        while !candidates.is_empty() && group.len() < conflict_window_size {
+            let mut next_candidates = Vec::new();
+            for position in candidates {
+                if group.len() >= conflict_window_size {
+                    break;
+                }
+
+                let queue = &mut fee_payer_queues[position];
+                let Some(&idx) = queue.front() else {
+                    continue;
+                };
+                let (sender, use_case, _) = &keys[idx];
+                let sender_queue = sender_queues
+                    .get_mut(sender)
+                    .expect("Sender of a pending transaction must have a queue");
+                let is_contract = matches!(use_case, UseCaseKey::ContractAddress(..));
+                if sender_queue.front() != Some(&idx)
+                    || group_senders.contains(sender)
+                    || (is_contract && group_contracts.contains(use_case))
+                    || accesses[idx].iter().any(|(state_key, write)| {
+                        group_writes.contains(state_key)
+                            || (*write && group_reads.contains(state_key))
+                    })
+                {
+                    continue;
+                }
+
+                queue.pop_front();
+                sender_queue.pop_front();
+                group_senders.insert(*sender);
+                if is_contract {
+                    group_contracts.insert(use_case.clone());
+                }
+                for (state_key, write) in &accesses[idx] {
+                    if *write {
+                        group_writes.insert(state_key);
+                    } else {
+                        group_reads.insert(state_key);
+                    }
+                }
+                group.push(idx);
+                next_candidates.push(position);
+            }
+            candidates = next_candidates;
+        }
+
+        // The next group starts from the next fee payer, skipping the ones without pending
+        // transactions.
+        active_fee_payers.rotate_left(1);
+        active_fee_payers.retain(|position| !fee_payer_queues[*position].is_empty());
+
+        group.sort_by_key(|idx| (Reverse(keys[*idx].2), *idx));
+        shuffled.extend(group.into_iter().map(|idx| {
+            txns[idx]
+                .take()
+                .expect("Transaction must not be taken twice")
+        }));
+    }
+
+    shuffled
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::transaction_shuffler::fee_priority::{shuffle, Config, FeePriorityTransaction};
+    use aptos_types::{
+        state_store::state_key::StateKey,
+        transaction::use_case::{UseCaseAwareTransaction, UseCaseKey},
+    };
+    use itertools::Itertools;
+    use move_core_types::account_address::AccountAddress;
+    use proptest::{collection::vec, prelude::*};
+    use std::collections::HashMap;
+
+    #[derive(Clone, Debug)]
+    struct Transaction {
+        sender: u8,
+        contract: Option<u8>,
+        fee_payer: u8,
+        gas_unit_price: u64,
+        original_idx: usize,
+    }
+
+    fn address(byte: u8) -> AccountAddress {
+        let mut addr = [0u8; 32];
+        addr[31] = byte;
+        AccountAddress::new(addr)
+    }
+
+    impl UseCaseAwareTransaction for Transaction {
+        fn parse_sender(&self) -> AccountAddress {
+            address(self.sender)
+        }
+
+        fn parse_use_case(&self) -> UseCaseKey {
+            match self.contract {
+                Some(contract) => UseCaseKey::ContractAddress(address(contract)),
+                None => UseCaseKey::Platform,
+            }
+        }
+    }
+
+    impl FeePriorityTransaction for Transaction {
+        fn parse_gas_unit_price(&self) -> u64 {
+            self.gas_unit_price
+        }
+
+        fn parse_fee_payer(&self) -> AccountAddress {
+            address(self.fee_payer)
+        }
+    }
+
+    /// Creates transactions from (sender, contract, fee payer, gas unit price) tuples.
+    fn into_txns(txns: impl IntoIterator<Item = (u8, Option<u8>, u8, u64)>) -> Vec<Transaction> {
+        txns.into_iter()
+            .enumerate()
+            .map(
+                |(original_idx, (sender, contract, fee_payer, gas_unit_price))| Transaction {
+                    sender,
+                    contract,
+                    fee_payer,
+                    gas_unit_price,
+                    original_idx,
+                },
+            )
+            .collect()
+    }
+
+    fn shuffled_order(
+        conflict_window_size: usize,
+        max_contract_share_percentage: usize,
+        txns: Vec<Transaction>,
+    ) -> Vec<usize> {
+        let config = Config {
+            conflict_window_size,
+            max_contract_share_percentage,
+        };
+        shuffle(&config, txns, |_| vec![])
+            .into_iter()
+            .map(|txn| txn.original_idx)
+            .collect()
+    }
+
+    #[test]
+    fn test_fee_priority_within_group() {
+        let txns = into_txns([
+            (1, None, 1, 100),
+            (2, None, 2, 300),
+            (3, None, 3, 200),
+            (4, None, 4, 300),
+        ]);
+
+        assert_eq!(shuffled_order(4, 100, txns.clone()), vec![1, 3, 2, 0]);
+        // Groups of two are ordered by fee separately.
+        assert_eq!(shuffled_order(2, 100, txns), vec![1, 0, 3, 2]);
+    }
+
+    #[test]
+    fn test_sender_order_preserved() {
+        // The second transaction of sender 1 pays more, but must not overtake the first one.
+        let txns = into_txns([(1, None, 1, 100), (1, None, 1, 500), (2, None, 2, 200)]);
+
+        assert_eq!(shuffled_order(4, 100, txns), vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn test_contract_conflicts_split_groups() {
+        let txns = into_txns([
+            (1, Some(10), 1, 100),
+            (2, Some(10), 2, 300),
+            (3, Some(11), 3, 200),
+        ]);
+
+        // Transactions 0 and 1 call into the same contract, so they end up in separate groups.
+        assert_eq!(shuffled_order(4, 100, txns), vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn test_resource_access_conflicts_split_groups() {
+        let txns = into_txns([
+            (1, None, 1, 100),
+            (2, None, 2, 300),
+            (3, None, 3, 200),
+            (4, None, 4, 400),
+        ]);
+        let config = Config {
+            conflict_window_size: 4,
+            max_contract_share_percentage: 100,
+        };
+        let written = StateKey::raw(b"written");
+        let read = StateKey::raw(b"read");
+        let accessed_state_keys = |txn: &Transaction| match txn.original_idx {
+            0 | 1 => vec![(written.clone(), true)],
+            _ => vec![(read.clone(), false)],
+        };
+
+        // Transactions 0 and 1 write the same resource, so they end up in separate groups, while
+        // transactions 2 and 3 only read the same resource.
+        let order: Vec<_> = shuffle(&config, txns, accessed_state_keys)
+            .into_iter()
+            .map(|txn| txn.original_idx)
+            .collect();
+        assert_eq!(order, vec![3, 2, 0, 1]);
+    }
+
+    #[test]
+    fn test_contract_share_cap() {
+        let txns = into_txns([
+            (1, Some(10), 1, 100),
+            (2, Some(10), 2, 100),
+            (3, Some(10), 3, 100),
+            (3, None, 3, 100),
+            (4, None, 4, 100),
+        ]);
+
+        // Contract 10 may take 2 out of 5 transactions, so the third one is deferred, along
+        // with the later transaction of the same sender.
+        assert_eq!(shuffled_order(1, 40, txns), vec![0, 1, 4, 2, 3]);
+    }
+
+    #[test]
+    fn test_fee_payer_fairness() {
+        // A sponsor paying for many transactions, followed by two self-paid transactions.
+        let txns = into_txns([
+            (1, None, 100, 100),
+            (2, None, 100, 100),
+            (3, None, 100, 100),
+            (4, None, 100, 100),
+            (5, None, 5, 100),
+            (6, None, 6, 100),
+        ]);
+
+        // The first group takes one transaction of each fee payer before the second of the
+        // sponsor, and the following groups start from a different fee payer.
+        assert_eq!(shuffled_order(3, 100, txns), vec![0, 4, 5, 1, 2, 3]);
+    }
+
+    fn arb_txns() -> impl Strategy<Value = Vec<Transaction>> {
+        vec((0u8..10, prop::option::of(0u8..5), 0u8..5, 0u64..5), 0..100).prop_map(into_txns)
+    }
+
+    proptest! {
+        #[test]
+        fn test_shuffle_invariants(
+            txns in arb_txns(),
+            conflict_window_size in 1usize..10,
+            max_contract_share_percentage in 1usize..=100,
+        ) {
+            let order = shuffled_order(
+                conflict_window_size,
+                max_contract_share_percentage,
+                txns.clone(),
+            );
+
+            // The result is a permutation of the input.
+            prop_assert_eq!(order.iter().copied().sorted().collect_vec(), (0..txns.len()).collect_vec());
+
+            // Transactions of each sender keep their relative order.
+            let mut last_idx_per_sender = HashMap::new();
+            for idx in order {
+                if let Some(last_idx) = last_idx_per_sender.insert(txns[idx].sender, idx) {
+                    prop_assert!(last_idx < idx);
+                }
+            }
+        }
+    }
+}
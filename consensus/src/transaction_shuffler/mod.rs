@@ -2,15 +2,26 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_logger::info;
+use aptos_storage_interface::state_view::DbStateView;
 use aptos_types::{on_chain_config::TransactionShufflerType, transaction::SignedTransaction};
 use sender_aware::SenderAwareShuffler;
 use std::sync::Arc;
 
 mod deprecated_fairness;
+mod fee_priority;
+mod resource_access_hints;
 mod sender_aware;
 mod use_case_aware;
 
 /// Interface to shuffle transactions
+///
+/// New ordering policies are added by implementing this trait in a submodule, adding a variant
+/// to the on-chain `TransactionShufflerType` and constructing the implementation for it in
+/// `create_transaction_shuffler`. An implementation must:
+/// - return a permutation of the given transactions, without dropping or adding any;
+/// - preserve the relative order of the transactions of each sender, as they are executed in
+///   sequence number order;
+/// - be deterministic, as every validator shuffles the same block independently.
 pub trait TransactionShuffler: Send + Sync {
     fn shuffle(&self, txns: Vec<SignedTransaction>) -> Vec<SignedTransaction>;
 }
@@ -24,8 +35,11 @@ impl TransactionShuffler for NoOpShuffler {
     }
 }
 
+/// The state at the start of the epoch, if available, lets shufflers take the resource access
+/// metadata of the called modules into account.
 pub fn create_transaction_shuffler(
     shuffler_type: TransactionShufflerType,
+    epoch_start_state_view: Option<DbStateView>,
 ) -> Arc<dyn TransactionShuffler> {
     use TransactionShufflerType::*;
 
@@ -78,5 +92,25 @@ pub fn create_transaction_shuffler(
             );
             Arc::new(use_case_aware::UseCaseAwareShuffler { config })
         },
+        FeePriority {
+            conflict_window_size,
+            max_contract_share_percentage,
+        } => {
+            let config = fee_priority::Config {
+                conflict_window_size,
+                max_contract_share_percentage,
+            };
+            let resource_access_hints =
+                epoch_start_state_view.and_then(resource_access_hints::ResourceAccessHints::new);
+            info!(
+                config = ?config,
+                resource_access_hints = resource_access_hints.is_some(),
+                "Using fee priority transaction shuffling."
+            );
+            Arc::new(fee_priority::FeePriorityShuffler {
+                config,
+                resource_access_hints,
+            })
+        },
     }
 }
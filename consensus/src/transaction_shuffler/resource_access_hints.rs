@@ -0,0 +1,66 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! The resources transactions declare to access in the metadata of the called modules, for the
+//! shufflers to avoid putting conflicting transactions next to each other.
+
+use aptos_infallible::Mutex;
+use aptos_storage_interface::state_view::DbStateView;
+use aptos_types::{
+    on_chain_config::{FeatureFlag, Features, OnChainConfig},
+    state_store::state_key::StateKey,
+    transaction::{SignedTransaction, TransactionPayload},
+};
+use aptos_vm::resource_access_hints::{
+    accessed_state_keys, declared_resource_accesses, ResourceAccess,
+};
+use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+use std::{collections::HashMap, sync::Arc};
+
+/// Bound on the number of entry functions whose declared accesses are cached.
+const MAX_CACHED_FUNCTIONS: usize = 10_000;
+
+/// Reads the metadata from the state at the start of the epoch, rather than the latest state, so
+/// that every validator derives the same hints for a block, and so shuffles it the same way.
+pub(crate) struct ResourceAccessHints {
+    state_view: DbStateView,
+    declared_accesses: Mutex<HashMap<(ModuleId, Identifier), Option<Arc<Vec<ResourceAccess>>>>>,
+}
+
+impl ResourceAccessHints {
+    /// Returns `None` if resource access metadata is disabled at the start of the epoch.
+    pub(crate) fn new(epoch_start_state_view: DbStateView) -> Option<Self> {
+        Features::fetch_config(&epoch_start_state_view)?
+            .is_enabled(FeatureFlag::RESOURCE_ACCESS_METADATA)
+            .then(|| Self {
+                state_view: epoch_start_state_view,
+                declared_accesses: Mutex::new(HashMap::new()),
+            })
+    }
+
+    /// The state keys the transaction declares to access, with whether they are written. Empty
+    /// if the called function doesn't declare its accesses.
+    pub(crate) fn accessed_state_keys(&self, txn: &SignedTransaction) -> Vec<(StateKey, bool)> {
+        let TransactionPayload::EntryFunction(entry_fun) = txn.payload() else {
+            return vec![];
+        };
+        let accesses = {
+            let mut declared_accesses = self.declared_accesses.lock();
+            let function = (entry_fun.module().clone(), entry_fun.function().to_owned());
+            if declared_accesses.len() >= MAX_CACHED_FUNCTIONS
+                && !declared_accesses.contains_key(&function)
+            {
+                declared_accesses.clear();
+            }
+            declared_accesses
+                .entry(function)
+                .or_insert_with(|| {
+                    declared_resource_accesses(entry_fun, &self.state_view).map(Arc::new)
+                })
+                .clone()
+        };
+        accesses
+            .and_then(|accesses| accessed_state_keys(txn, &accesses))
+            .unwrap_or_default()
+    }
+}
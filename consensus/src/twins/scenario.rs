@@ -0,0 +1,326 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{network_interface::ConsensusMsg, network_tests::NetworkPlayground};
+use aptos_consensus_types::common::{Author, Round};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+/// The default duration (in milliseconds) that a scenario is run for
+const DEFAULT_SCENARIO_DURATION_MS: u64 = 30_000;
+
+/// A reference to a node in a twins scenario. In the scenario description,
+/// "n<i>" refers to the i-th node, and "t<i>" refers to the twin of the i-th node.
+/// Twins are only created for the first `num_twins` nodes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum ScenarioNode {
+    Node(usize),
+    Twin(usize),
+}
+
+impl ScenarioNode {
+    /// Returns the index of the node that this node (or twin) shares an author with
+    pub fn get_author_index(&self) -> usize {
+        match self {
+            ScenarioNode::Node(index) | ScenarioNode::Twin(index) => *index,
+        }
+    }
+}
+
+impl Display for ScenarioNode {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            ScenarioNode::Node(index) => write!(f, "n{}", index),
+            ScenarioNode::Twin(index) => write!(f, "t{}", index),
+        }
+    }
+}
+
+impl FromStr for ScenarioNode {
+    type Err = String;
+
+    fn from_str(node: &str) -> Result<Self, Self::Err> {
+        let parse_index = |index: &str| {
+            index
+                .parse::<usize>()
+                .map_err(|error| format!("Invalid scenario node {}: {}", node, error))
+        };
+        if let Some(index) = node.strip_prefix('n') {
+            parse_index(index).map(ScenarioNode::Node)
+        } else if let Some(index) = node.strip_prefix('t') {
+            parse_index(index).map(ScenarioNode::Twin)
+        } else {
+            Err(format!(
+                "Invalid scenario node {}! Expected n<index> or t<index>.",
+                node
+            ))
+        }
+    }
+}
+
+impl TryFrom<String> for ScenarioNode {
+    type Error = String;
+
+    fn try_from(node: String) -> Result<Self, Self::Error> {
+        node.parse()
+    }
+}
+
+impl From<ScenarioNode> for String {
+    fn from(node: ScenarioNode) -> Self {
+        node.to_string()
+    }
+}
+
+/// The kinds of (direct-send) messages that a message rule applies to
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScenarioMessageKind {
+    #[default]
+    All,
+    Proposals,
+    Votes,
+    SyncInfo,
+    CommitVotes,
+}
+
+impl ScenarioMessageKind {
+    /// Returns the network playground msg inspector for the message kind
+    pub fn get_msg_inspector(&self) -> fn(&(Author, ConsensusMsg)) -> bool {
+        match self {
+            ScenarioMessageKind::All => NetworkPlayground::take_all,
+            ScenarioMessageKind::Proposals => NetworkPlayground::proposals_only,
+            ScenarioMessageKind::Votes => NetworkPlayground::votes_only,
+            ScenarioMessageKind::SyncInfo => NetworkPlayground::sync_info_only,
+            ScenarioMessageKind::CommitVotes => NetworkPlayground::commit_votes_only,
+        }
+    }
+}
+
+/// A rule that drops (or delays) the messages of a round sent by a node.
+/// Messages are dropped, unless a delay is specified.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioMessageRule {
+    pub from: ScenarioNode,
+    /// The recipients of the messages (if empty, the rule applies to all other nodes)
+    #[serde(default)]
+    pub to: Vec<ScenarioNode>,
+    #[serde(default)]
+    pub messages: ScenarioMessageKind,
+    #[serde(default)]
+    pub delay_ms: Option<u64>,
+}
+
+/// The network conditions (and the leader) for a single round
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioRound {
+    pub round: Round,
+    /// The proposer of the round (the twin of the leader will also propose).
+    /// If unspecified, the first node is the leader.
+    #[serde(default)]
+    pub leader: Option<ScenarioNode>,
+    /// The network partitions for the round. Messages are only delivered between
+    /// nodes in the same partition. Nodes not in any partition are unaffected.
+    #[serde(default)]
+    pub partitions: Vec<Vec<ScenarioNode>>,
+    #[serde(default)]
+    pub message_rules: Vec<ScenarioMessageRule>,
+}
+
+/// The liveness requirement of a scenario: at least `num_nodes` honest nodes must
+/// commit a block with a round of at least `commit_round` before the scenario ends.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct LivenessBound {
+    pub commit_round: Round,
+    /// The number of honest nodes that must commit (if unspecified, all honest nodes)
+    #[serde(default)]
+    pub num_nodes: Option<usize>,
+}
+
+/// A declarative description of an adversarial twins scenario. For example:
+///
+/// ```yaml
+/// name: equivocating_leader
+/// num_nodes: 4
+/// num_twins: 1
+/// rounds:
+///   - round: 1
+///     leader: n0
+///     partitions: [[n0, n1, n2], [t0, n3]]
+///     message_rules:
+///       - from: n1
+///         messages: votes
+///         delay_ms: 200
+/// liveness:
+///   commit_round: 5
+/// ```
+///
+/// Nodes with twins (i.e., the first `num_twins` nodes) are considered byzantine,
+/// so the safety and liveness checks only apply to the remaining (honest) nodes.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TwinsScenario {
+    pub name: String,
+    pub num_nodes: usize,
+    #[serde(default)]
+    pub num_twins: usize,
+    #[serde(default)]
+    pub rounds: Vec<ScenarioRound>,
+    #[serde(default)]
+    pub liveness: Option<LivenessBound>,
+    /// The maximum duration of the scenario (it ends early once liveness is reached)
+    #[serde(default = "default_scenario_duration_ms")]
+    pub duration_ms: u64,
+}
+
+fn default_scenario_duration_ms() -> u64 {
+    DEFAULT_SCENARIO_DURATION_MS
+}
+
+impl TwinsScenario {
+    pub fn new(name: impl Into<String>, num_nodes: usize, num_twins: usize) -> Self {
+        Self {
+            name: name.into(),
+            num_nodes,
+            num_twins,
+            rounds: vec![],
+            liveness: None,
+            duration_ms: DEFAULT_SCENARIO_DURATION_MS,
+        }
+    }
+
+    /// Parses and validates a scenario from the given YAML string
+    pub fn from_yaml(yaml: &str) -> Result<Self, String> {
+        let scenario: Self = serde_yaml::from_str(yaml)
+            .map_err(|error| format!("Failed to parse the twins scenario: {}", error))?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    /// Serializes the scenario to a YAML string
+    pub fn to_yaml(&self) -> String {
+        serde_yaml::to_string(self).expect("Failed to serialize the twins scenario!")
+    }
+
+    /// Returns all nodes in the scenario, in the order in which they are started
+    /// by the twins harness (i.e., all nodes, followed by all twins).
+    pub fn get_all_nodes(&self) -> Vec<ScenarioNode> {
+        (0..self.num_nodes)
+            .map(ScenarioNode::Node)
+            .chain((0..self.num_twins).map(ScenarioNode::Twin))
+            .collect()
+    }
+
+    /// Returns the honest nodes in the scenario (i.e., the nodes without twins)
+    pub fn get_honest_nodes(&self) -> Vec<ScenarioNode> {
+        (self.num_twins..self.num_nodes)
+            .map(ScenarioNode::Node)
+            .collect()
+    }
+
+    /// Returns the index of the given node in the list of started nodes
+    pub fn get_node_index(&self, node: &ScenarioNode) -> usize {
+        match node {
+            ScenarioNode::Node(index) => *index,
+            ScenarioNode::Twin(index) => self.num_nodes + *index,
+        }
+    }
+
+    /// Returns the round leaders (as node indices) for the twins harness
+    pub fn get_round_leaders(&self) -> HashMap<Round, usize> {
+        self.rounds
+            .iter()
+            .filter_map(|round| {
+                round
+                    .leader
+                    .map(|leader| (round.round, leader.get_author_index()))
+            })
+            .collect()
+    }
+
+    /// Verifies that the scenario is well-formed
+    pub fn validate(&self) -> Result<(), String> {
+        if self.num_nodes == 0 {
+            return Err("The scenario must contain at least one node!".into());
+        }
+        if self.num_twins > self.num_nodes {
+            return Err(format!(
+                "The number of twins ({}) exceeds the number of nodes ({})!",
+                self.num_twins, self.num_nodes
+            ));
+        }
+
+        let mut rounds = HashSet::new();
+        for round in &self.rounds {
+            if round.round == 0 {
+                return Err("Round 0 is the genesis round and cannot be configured!".into());
+            }
+            if !rounds.insert(round.round) {
+                return Err(format!(
+                    "Round {} is configured more than once!",
+                    round.round
+                ));
+            }
+            if let Some(leader) = &round.leader {
+                self.verify_node(leader)?;
+                if matches!(leader, ScenarioNode::Twin(_)) {
+                    return Err(format!(
+                        "The leader of round {} is a twin ({}). Twins propose when their node does!",
+                        round.round, leader
+                    ));
+                }
+            }
+
+            let mut partitioned_nodes = HashSet::new();
+            for node in round.partitions.iter().flatten() {
+                self.verify_node(node)?;
+                if !partitioned_nodes.insert(*node) {
+                    return Err(format!(
+                        "Node {} is in multiple partitions in round {}!",
+                        node, round.round
+                    ));
+                }
+            }
+
+            for message_rule in &round.message_rules {
+                self.verify_node(&message_rule.from)?;
+                for node in &message_rule.to {
+                    self.verify_node(node)?;
+                }
+            }
+        }
+
+        if let Some(liveness) = &self.liveness {
+            let num_honest_nodes = self.get_honest_nodes().len();
+            let num_nodes = liveness.num_nodes.unwrap_or(num_honest_nodes);
+            if num_nodes == 0 || num_nodes > num_honest_nodes {
+                return Err(format!(
+                    "The liveness bound requires {} nodes to commit, but there are {} honest nodes!",
+                    num_nodes, num_honest_nodes
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verifies that the given node exists in the scenario
+    fn verify_node(&self, node: &ScenarioNode) -> Result<(), String> {
+        let num_nodes = match node {
+            ScenarioNode::Node(_) => self.num_nodes,
+            ScenarioNode::Twin(_) => self.num_twins,
+        };
+        if node.get_author_index() >= num_nodes {
+            return Err(format!("Unknown scenario node: {}!", node));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,210 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::twins::scenario::{LivenessBound, ScenarioNode, ScenarioRound, TwinsScenario};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::HashSet;
+
+/// The configuration of a single round: the leader, and the network partitions
+pub type RoundConfiguration = (ScenarioNode, Vec<Vec<ScenarioNode>>);
+
+/// Systematically enumerates twins scenarios. The generator (i) enumerates all
+/// partitions of the nodes (and twins) into at most `max_partitions` partitions,
+/// (ii) combines each partition with every possible leader, and (iii) arranges
+/// these round configurations over `num_rounds` rounds (with repetition).
+///
+/// The number of scenarios grows exponentially in the number of rounds, so each
+/// scenario is decoded from its index on demand (rather than being materialized).
+pub struct ScenarioGenerator {
+    num_nodes: usize,
+    num_twins: usize,
+    num_rounds: usize,
+    round_configurations: Vec<RoundConfiguration>,
+    liveness: Option<LivenessBound>,
+    duration_ms: Option<u64>,
+}
+
+impl ScenarioGenerator {
+    /// Creates a generator for the given number of nodes, twins and rounds. If
+    /// `require_quorum_partition` is set, only partitions where one partition
+    /// contains a quorum of (distinct) authors are enumerated.
+    pub fn new(
+        num_nodes: usize,
+        num_twins: usize,
+        num_rounds: usize,
+        max_partitions: usize,
+        require_quorum_partition: bool,
+    ) -> Self {
+        assert!(num_nodes >= num_twins);
+        assert!(max_partitions > 0);
+
+        let all_nodes = TwinsScenario::new("", num_nodes, num_twins).get_all_nodes();
+        let quorum_size = num_nodes * 2 / 3 + 1;
+        let round_configurations = enumerate_partitions(&all_nodes, max_partitions)
+            .into_iter()
+            .filter(|partitions| {
+                !require_quorum_partition
+                    || partitions
+                        .iter()
+                        .any(|partition| count_authors(partition) >= quorum_size)
+            })
+            .flat_map(|partitions| {
+                (0..num_nodes).map(move |leader| (ScenarioNode::Node(leader), partitions.clone()))
+            })
+            .collect();
+
+        Self {
+            num_nodes,
+            num_twins,
+            num_rounds,
+            round_configurations,
+            liveness: None,
+            duration_ms: None,
+        }
+    }
+
+    /// Sets the liveness bound of all generated scenarios
+    pub fn with_liveness(mut self, liveness: LivenessBound) -> Self {
+        self.liveness = Some(liveness);
+        self
+    }
+
+    /// Sets the duration of all generated scenarios
+    pub fn with_duration_ms(mut self, duration_ms: u64) -> Self {
+        self.duration_ms = Some(duration_ms);
+        self
+    }
+
+    /// Returns the possible configurations of a single round
+    pub fn get_round_configurations(&self) -> &[RoundConfiguration] {
+        &self.round_configurations
+    }
+
+    /// Returns the total number of scenarios (or None, if the number overflows)
+    pub fn get_num_scenarios(&self) -> Option<u64> {
+        (self.round_configurations.len() as u64).checked_pow(self.num_rounds as u32)
+    }
+
+    /// Returns the scenario with the given index. The index is decoded as a
+    /// number (in base: the number of round configurations), where each digit
+    /// selects the configuration of a round (starting with the last round).
+    pub fn get_scenario(&self, index: u64) -> TwinsScenario {
+        let mut scenario = TwinsScenario::new(
+            format!("generated_scenario_{}", index),
+            self.num_nodes,
+            self.num_twins,
+        );
+        scenario.liveness = self.liveness.clone();
+        if let Some(duration_ms) = self.duration_ms {
+            scenario.duration_ms = duration_ms;
+        }
+
+        let num_configurations = self.round_configurations.len() as u64;
+        let mut remaining_index = index;
+        for round in 1..=self.num_rounds {
+            let (leader, partitions) =
+                &self.round_configurations[(remaining_index % num_configurations) as usize];
+            remaining_index /= num_configurations;
+            scenario.rounds.push(ScenarioRound {
+                round: round as u64,
+                leader: Some(*leader),
+                partitions: partitions.clone(),
+                message_rules: vec![],
+            });
+        }
+        scenario
+    }
+
+    /// Returns an iterator over all scenarios (in index order)
+    pub fn scenarios(&self) -> impl Iterator<Item = TwinsScenario> + '_ {
+        let num_scenarios = self.get_num_scenarios().unwrap_or(u64::MAX);
+        (0..num_scenarios).map(move |index| self.get_scenario(index))
+    }
+
+    /// Returns the given number of distinct scenarios (or all scenarios, if there
+    /// are fewer), sampled uniformly using the given seed.
+    pub fn sample_scenarios(&self, num_scenarios: usize, seed: u64) -> Vec<TwinsScenario> {
+        let total_num_scenarios = self.get_num_scenarios().unwrap_or(u64::MAX);
+        if total_num_scenarios <= num_scenarios as u64 {
+            return self.scenarios().collect();
+        }
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut sampled_indices = HashSet::new();
+        let mut scenarios = vec![];
+        while scenarios.len() < num_scenarios {
+            let index = rng.gen_range(0, total_num_scenarios);
+            if sampled_indices.insert(index) {
+                scenarios.push(self.get_scenario(index));
+            }
+        }
+        scenarios
+    }
+}
+
+/// Enumerates all partitions of the given nodes into (at most) max_partitions
+/// non-empty partitions. Partitions are built using restricted growth strings,
+/// so each partitioning is enumerated exactly once.
+pub fn enumerate_partitions(
+    nodes: &[ScenarioNode],
+    max_partitions: usize,
+) -> Vec<Vec<Vec<ScenarioNode>>> {
+    let mut all_partitions = vec![];
+    let mut current_partitions: Vec<Vec<ScenarioNode>> = vec![];
+    enumerate_partitions_helper(
+        nodes,
+        max_partitions,
+        &mut current_partitions,
+        &mut all_partitions,
+    );
+    all_partitions
+}
+
+fn enumerate_partitions_helper(
+    remaining_nodes: &[ScenarioNode],
+    max_partitions: usize,
+    current_partitions: &mut Vec<Vec<ScenarioNode>>,
+    all_partitions: &mut Vec<Vec<Vec<ScenarioNode>>>,
+) {
+    let (node, remaining_nodes) = match remaining_nodes.split_first() {
+        Some(split) => split,
+        None => {
+            all_partitions.push(current_partitions.clone());
+            return;
+        },
+    };
+
+    // Add the node to each existing partition
+    for partition_index in 0..current_partitions.len() {
+        current_partitions[partition_index].push(*node);
+        enumerate_partitions_helper(
+            remaining_nodes,
+            max_partitions,
+            current_partitions,
+            all_partitions,
+        );
+        current_partitions[partition_index].pop();
+    }
+
+    // Add the node to a new partition
+    if current_partitions.len() < max_partitions {
+        current_partitions.push(vec![*node]);
+        enumerate_partitions_helper(
+            remaining_nodes,
+            max_partitions,
+            current_partitions,
+            all_partitions,
+        );
+        current_partitions.pop();
+    }
+}
+
+/// Returns the number of distinct authors in the partition (a node and its twin
+/// share the same author, so their votes are only counted once).
+fn count_authors(partition: &[ScenarioNode]) -> usize {
+    partition
+        .iter()
+        .map(|node| node.get_author_index())
+        .collect::<HashSet<_>>()
+        .len()
+}
@@ -0,0 +1,312 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    network_tests::{MessageAction, MessageRule, NetworkPlayground, TwinId},
+    test_utils::consensus_runtime,
+    twins::{
+        scenario::{ScenarioNode, TwinsScenario},
+        twins_node::SMRNode,
+    },
+};
+use aptos_consensus_types::common::Round;
+use aptos_crypto::HashValue;
+use aptos_types::{block_info::BlockInfo, on_chain_config::ProposerElectionType::RoundProposer};
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+use thiserror::Error;
+
+/// The interval at which the runner polls the nodes for new commits
+const COMMIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum ScenarioError {
+    #[error("Invalid twins scenario: {0}")]
+    InvalidScenario(String),
+    #[error(
+        "Safety violation! Honest nodes committed conflicting blocks: {first_node} committed \
+        {first_block_id} (round {first_round}), but {second_node} committed {second_block_id} \
+        (round {second_round}), which is not an ancestor of it"
+    )]
+    ConflictingCommits {
+        first_node: ScenarioNode,
+        first_block_id: HashValue,
+        first_round: Round,
+        second_node: ScenarioNode,
+        second_block_id: HashValue,
+        second_round: Round,
+    },
+    #[error(
+        "Safety violation! Honest node {node} committed round {round} after round {previous_round}"
+    )]
+    NonIncreasingCommits {
+        node: ScenarioNode,
+        round: Round,
+        previous_round: Round,
+    },
+    #[error(
+        "Liveness violation! Only {num_committed} honest nodes committed round {commit_round} \
+        (expected at least {num_nodes})"
+    )]
+    LivenessViolation {
+        commit_round: Round,
+        num_nodes: usize,
+        num_committed: usize,
+    },
+}
+
+/// The commits observed for each node while running a scenario
+#[derive(Clone, Debug, Default)]
+pub struct ScenarioOutcome {
+    committed_blocks: BTreeMap<ScenarioNode, Vec<BlockInfo>>,
+    // The round and parent of every block ordered by any node (by block id)
+    ordered_blocks: HashMap<HashValue, (Round, HashValue)>,
+}
+
+impl ScenarioOutcome {
+    /// Records a block ordered by any node (used to check the ancestry of commits)
+    pub fn add_ordered_block(&mut self, block_id: HashValue, round: Round, parent_id: HashValue) {
+        self.ordered_blocks.insert(block_id, (round, parent_id));
+    }
+
+    /// Records a commit made by the given node
+    pub fn add_commit(&mut self, node: ScenarioNode, commit_info: BlockInfo) {
+        self.committed_blocks
+            .entry(node)
+            .or_default()
+            .push(commit_info);
+    }
+
+    /// Returns the blocks committed by the given node (in commit order)
+    pub fn get_committed_blocks(&self, node: &ScenarioNode) -> &[BlockInfo] {
+        self.committed_blocks
+            .get(node)
+            .map(|blocks| blocks.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Returns the highest round committed by the given node (if any)
+    pub fn get_highest_committed_round(&self, node: &ScenarioNode) -> Option<Round> {
+        self.get_committed_blocks(node)
+            .iter()
+            .map(|block_info| block_info.round())
+            .max()
+    }
+
+    /// Verifies that every honest node committed blocks in increasing rounds, and
+    /// that all blocks committed by honest nodes lie on a single chain, i.e., every
+    /// commit is an ancestor of (or equal to) the highest commit across all nodes.
+    ///
+    /// Note: ancestry is derived from the blocks ordered by the nodes. If part of the
+    /// chain is unknown (e.g., every node state synced past those blocks), the
+    /// commits below the unknown part cannot be checked.
+    pub fn check_safety(&self, scenario: &TwinsScenario) -> Result<(), ScenarioError> {
+        let mut highest_commit: Option<(ScenarioNode, &BlockInfo)> = None;
+        for node in scenario.get_honest_nodes() {
+            let mut highest_committed_round = None;
+            for block_info in self.get_committed_blocks(&node) {
+                let round = block_info.round();
+                if let Some(previous_round) = highest_committed_round {
+                    if round <= previous_round {
+                        return Err(ScenarioError::NonIncreasingCommits {
+                            node,
+                            round,
+                            previous_round,
+                        });
+                    }
+                }
+                highest_committed_round = Some(round);
+
+                if highest_commit.map_or(true, |(_, highest_block)| round > highest_block.round()) {
+                    highest_commit = Some((node, block_info));
+                }
+            }
+        }
+
+        let (highest_node, highest_block) = match highest_commit {
+            Some(highest_commit) => highest_commit,
+            None => return Ok(()), // No honest node committed anything
+        };
+        for node in scenario.get_honest_nodes() {
+            for block_info in self.get_committed_blocks(&node) {
+                if self.is_conflicting_commit(highest_block, block_info) {
+                    return Err(ScenarioError::ConflictingCommits {
+                        first_node: highest_node,
+                        first_block_id: highest_block.id(),
+                        first_round: highest_block.round(),
+                        second_node: node,
+                        second_block_id: block_info.id(),
+                        second_round: block_info.round(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true iff the given commit is known to not be an ancestor of (or equal
+    /// to) the highest commit. The parents of the highest commit are followed until
+    /// the given commit (or a block at or below its round) is found.
+    fn is_conflicting_commit(&self, highest_block: &BlockInfo, block_info: &BlockInfo) -> bool {
+        if highest_block.round() == block_info.round() {
+            return highest_block.id() != block_info.id();
+        }
+
+        let mut block_id = highest_block.id();
+        loop {
+            if block_id == block_info.id() {
+                return false;
+            }
+            let (round, parent_id) = match self.ordered_blocks.get(&block_id) {
+                Some(round_and_parent_id) => *round_and_parent_id,
+                None => return false, // The rest of the chain is unknown
+            };
+            if round <= block_info.round() {
+                return true; // The chain skipped over the round of the commit
+            }
+            block_id = parent_id;
+        }
+    }
+
+    /// Verifies that the liveness bound of the scenario (if any) was reached
+    pub fn check_liveness(&self, scenario: &TwinsScenario) -> Result<(), ScenarioError> {
+        let liveness = match &scenario.liveness {
+            Some(liveness) => liveness,
+            None => return Ok(()),
+        };
+
+        let honest_nodes = scenario.get_honest_nodes();
+        let num_nodes = liveness.num_nodes.unwrap_or(honest_nodes.len());
+        let num_committed = honest_nodes
+            .iter()
+            .filter(|node| {
+                self.get_highest_committed_round(node)
+                    .map_or(false, |round| round >= liveness.commit_round)
+            })
+            .count();
+        if num_committed < num_nodes {
+            return Err(ScenarioError::LivenessViolation {
+                commit_round: liveness.commit_round,
+                num_nodes,
+                num_committed,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Runs the given scenario using the twins harness, and verifies the safety
+/// and liveness of the honest nodes. Returns the observed commits.
+pub fn run_scenario(scenario: &TwinsScenario) -> Result<ScenarioOutcome, ScenarioError> {
+    scenario
+        .validate()
+        .map_err(ScenarioError::InvalidScenario)?;
+
+    // Start the nodes (and twins) with the round leaders of the scenario
+    let runtime = consensus_runtime();
+    let mut playground = NetworkPlayground::new(runtime.handle().clone());
+    let mut nodes = SMRNode::start_num_nodes_with_twins(
+        scenario.num_nodes,
+        scenario.num_twins,
+        &mut playground,
+        RoundProposer(HashMap::new()),
+        Some(scenario.get_round_leaders()),
+    );
+    let twin_ids: Vec<TwinId> = nodes.iter().map(|node| node.id).collect();
+
+    // Configure the network partitions and message rules for each round
+    configure_network(scenario, &mut playground, &twin_ids)?;
+    runtime.spawn(playground.start());
+
+    // Observe the commits of all nodes, and verify safety and liveness
+    let outcome = runtime.block_on(observe_commits(scenario, &mut nodes));
+    outcome.check_safety(scenario)?;
+    outcome.check_liveness(scenario)?;
+    Ok(outcome)
+}
+
+/// Applies the per round partitions and message rules to the network playground
+fn configure_network(
+    scenario: &TwinsScenario,
+    playground: &mut NetworkPlayground,
+    twin_ids: &[TwinId],
+) -> Result<(), ScenarioError> {
+    let get_twin_id = |node: &ScenarioNode| twin_ids[scenario.get_node_index(node)];
+
+    let round_partitions: HashMap<u64, Vec<Vec<TwinId>>> = scenario
+        .rounds
+        .iter()
+        .filter(|round| !round.partitions.is_empty())
+        .map(|round| {
+            let partitions = round
+                .partitions
+                .iter()
+                .map(|partition| partition.iter().map(get_twin_id).collect())
+                .collect();
+            (round.round, partitions)
+        })
+        .collect();
+    if !playground.split_network_round(&round_partitions) {
+        return Err(ScenarioError::InvalidScenario(
+            "Failed to create the round partitions!".into(),
+        ));
+    }
+
+    for round in &scenario.rounds {
+        for message_rule in &round.message_rules {
+            let recipients = if message_rule.to.is_empty() {
+                scenario
+                    .get_all_nodes()
+                    .into_iter()
+                    .filter(|node| node != &message_rule.from)
+                    .collect()
+            } else {
+                message_rule.to.clone()
+            };
+            let action = match message_rule.delay_ms {
+                Some(delay_ms) => MessageAction::Delay(Duration::from_millis(delay_ms)),
+                None => MessageAction::Drop,
+            };
+            for recipient in recipients {
+                playground.add_message_rule_round(round.round, MessageRule {
+                    src: get_twin_id(&message_rule.from),
+                    dst: get_twin_id(&recipient),
+                    msg_inspector: message_rule.messages.get_msg_inspector(),
+                    action,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Collects the commits of all nodes until the scenario duration elapses,
+/// or until the liveness bound of the scenario is reached.
+async fn observe_commits(scenario: &TwinsScenario, nodes: &mut [SMRNode]) -> ScenarioOutcome {
+    let deadline = Instant::now() + Duration::from_millis(scenario.duration_ms);
+    let all_nodes = scenario.get_all_nodes();
+
+    let mut outcome = ScenarioOutcome::default();
+    loop {
+        for (node, smr_node) in all_nodes.iter().zip(nodes.iter_mut()) {
+            while let Ok(Some(blocks)) = smr_node.ordered_blocks_cb_receiver.try_next() {
+                for block in blocks {
+                    outcome.add_ordered_block(block.id(), block.round(), block.parent_id());
+                }
+            }
+            while let Ok(Some(commit)) = smr_node.commit_cb_receiver.try_next() {
+                outcome.add_commit(*node, commit.ledger_info().commit_info().clone());
+            }
+        }
+
+        let liveness_reached =
+            scenario.liveness.is_some() && outcome.check_liveness(scenario).is_ok();
+        if liveness_reached || Instant::now() >= deadline {
+            return outcome;
+        }
+        tokio::time::sleep(COMMIT_POLL_INTERVAL).await;
+    }
+}
@@ -0,0 +1,325 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::twins::{
+    scenario::{LivenessBound, ScenarioMessageKind, ScenarioNode, ScenarioRound, TwinsScenario},
+    scenario_generator::{enumerate_partitions, ScenarioGenerator},
+    scenario_runner::{run_scenario, ScenarioError, ScenarioOutcome},
+};
+use aptos_crypto::HashValue;
+use aptos_types::block_info::BlockInfo;
+use std::collections::HashSet;
+
+const EQUIVOCATION_SCENARIO: &str = r#"
+name: equivocating_leader
+num_nodes: 4
+num_twins: 1
+rounds:
+  - round: 1
+    leader: n0
+    partitions: [[n0, n1, n2], [t0, n3]]
+  - round: 2
+    leader: n1
+    message_rules:
+      - from: n1
+        to: [n3]
+        messages: proposals
+      - from: n2
+        messages: votes
+        delay_ms: 200
+liveness:
+  commit_round: 5
+  num_nodes: 2
+duration_ms: 10000
+"#;
+
+#[test]
+fn test_parse_scenario() {
+    // Parse the scenario and verify the rounds
+    let scenario = TwinsScenario::from_yaml(EQUIVOCATION_SCENARIO).unwrap();
+    assert_eq!(scenario.num_nodes, 4);
+    assert_eq!(scenario.num_twins, 1);
+    assert_eq!(scenario.rounds.len(), 2);
+    assert_eq!(scenario.rounds[0].partitions, vec![
+        vec![
+            ScenarioNode::Node(0),
+            ScenarioNode::Node(1),
+            ScenarioNode::Node(2)
+        ],
+        vec![ScenarioNode::Twin(0), ScenarioNode::Node(3)],
+    ]);
+
+    // Verify the message rules
+    let message_rules = &scenario.rounds[1].message_rules;
+    assert_eq!(message_rules[0].to, vec![ScenarioNode::Node(3)]);
+    assert_eq!(message_rules[0].messages, ScenarioMessageKind::Proposals);
+    assert_eq!(message_rules[0].delay_ms, None);
+    assert!(message_rules[1].to.is_empty());
+    assert_eq!(message_rules[1].delay_ms, Some(200));
+
+    // Verify the node helpers
+    assert_eq!(scenario.get_honest_nodes(), vec![
+        ScenarioNode::Node(1),
+        ScenarioNode::Node(2),
+        ScenarioNode::Node(3)
+    ]);
+    assert_eq!(scenario.get_node_index(&ScenarioNode::Twin(0)), 4);
+    assert_eq!(scenario.get_round_leaders().get(&2), Some(&1));
+
+    // Verify the scenario can be serialized and parsed again
+    let serialized_scenario = scenario.to_yaml();
+    assert_eq!(
+        TwinsScenario::from_yaml(&serialized_scenario).unwrap(),
+        scenario
+    );
+}
+
+#[test]
+fn test_invalid_scenarios() {
+    let invalid_scenarios = [
+        // Unknown twin
+        "{name: s, num_nodes: 4, num_twins: 1, rounds: [{round: 1, partitions: [[t1]]}]}",
+        // Twin leader
+        "{name: s, num_nodes: 4, num_twins: 1, rounds: [{round: 1, leader: t0}]}",
+        // Node in multiple partitions
+        "{name: s, num_nodes: 4, rounds: [{round: 1, partitions: [[n0, n1], [n1, n2]]}]}",
+        // Duplicate round
+        "{name: s, num_nodes: 4, rounds: [{round: 1}, {round: 1}]}",
+        // Genesis round
+        "{name: s, num_nodes: 4, rounds: [{round: 0}]}",
+        // Too many twins
+        "{name: s, num_nodes: 1, num_twins: 2}",
+        // Liveness requires more than the honest nodes
+        "{name: s, num_nodes: 4, num_twins: 1, liveness: {commit_round: 2, num_nodes: 4}}",
+        // Invalid node name
+        "{name: s, num_nodes: 4, rounds: [{round: 1, leader: x0}]}",
+    ];
+    for invalid_scenario in invalid_scenarios {
+        assert!(TwinsScenario::from_yaml(invalid_scenario).is_err());
+    }
+}
+
+#[test]
+fn test_enumerate_partitions() {
+    // The number of partitions should match the Stirling numbers of the second kind
+    let nodes: Vec<_> = (0..3).map(ScenarioNode::Node).collect();
+    assert_eq!(enumerate_partitions(&nodes, 1).len(), 1);
+    assert_eq!(enumerate_partitions(&nodes, 2).len(), 4);
+    assert_eq!(enumerate_partitions(&nodes, 3).len(), 5);
+
+    // Verify every partitioning contains every node exactly once
+    for partitions in enumerate_partitions(&nodes, 3) {
+        let mut partitioned_nodes: Vec<_> = partitions.into_iter().flatten().collect();
+        partitioned_nodes.sort();
+        assert_eq!(partitioned_nodes, nodes);
+    }
+}
+
+#[test]
+fn test_scenario_generator() {
+    // 4 nodes and 1 twin can be split into 16 partitions, with 4 possible leaders
+    let num_rounds = 2;
+    let generator = ScenarioGenerator::new(4, 1, num_rounds, 2, false).with_duration_ms(1_000);
+    assert_eq!(generator.get_round_configurations().len(), 64);
+    assert_eq!(generator.get_num_scenarios(), Some(64 * 64));
+
+    // Verify the first and last scenarios
+    let first_scenario = generator.get_scenario(0);
+    assert_eq!(first_scenario.duration_ms, 1_000);
+    assert!(first_scenario.validate().is_ok());
+    assert_eq!(first_scenario.rounds.len(), num_rounds);
+    assert_eq!(first_scenario.rounds[0], first_scenario.rounds[1]);
+    let last_scenario = generator.get_scenario(64 * 64 - 1);
+    assert!(last_scenario.validate().is_ok());
+    assert_ne!(last_scenario.rounds[0], first_scenario.rounds[0]);
+    assert_eq!(generator.scenarios().count(), 64 * 64);
+
+    // Verify that sampled scenarios are distinct and deterministic
+    let sampled_scenarios = generator.sample_scenarios(10, 0);
+    assert_eq!(sampled_scenarios, generator.sample_scenarios(10, 0));
+    let sampled_names: HashSet<_> = sampled_scenarios.iter().map(|s| &s.name).collect();
+    assert_eq!(sampled_names.len(), 10);
+
+    // Only 13 of the partitions contain a quorum of distinct authors (3 of 4)
+    let generator = ScenarioGenerator::new(4, 1, num_rounds, 2, true);
+    assert_eq!(generator.get_round_configurations().len(), 52);
+}
+
+#[test]
+fn test_check_safety() {
+    let scenario = TwinsScenario::new("safety", 4, 1);
+
+    // Honest nodes commit the same blocks, and the twins commit conflicting blocks
+    let block_id = HashValue::random();
+    let mut outcome = ScenarioOutcome::default();
+    for node in [ScenarioNode::Node(1), ScenarioNode::Node(2)] {
+        outcome.add_commit(node, create_block_info(1, block_id));
+    }
+    outcome.add_commit(
+        ScenarioNode::Node(0),
+        create_block_info(1, HashValue::random()),
+    );
+    outcome.add_commit(
+        ScenarioNode::Twin(0),
+        create_block_info(1, HashValue::random()),
+    );
+    assert!(outcome.check_safety(&scenario).is_ok());
+
+    // An honest node commits a lower round
+    let mut non_increasing_outcome = outcome.clone();
+    non_increasing_outcome.add_commit(ScenarioNode::Node(1), create_block_info(1, block_id));
+    assert_eq!(
+        non_increasing_outcome.check_safety(&scenario),
+        Err(ScenarioError::NonIncreasingCommits {
+            node: ScenarioNode::Node(1),
+            round: 1,
+            previous_round: 1,
+        })
+    );
+
+    // An honest node commits a conflicting block
+    let conflicting_block_id = HashValue::random();
+    outcome.add_commit(
+        ScenarioNode::Node(3),
+        create_block_info(1, conflicting_block_id),
+    );
+    assert_eq!(
+        outcome.check_safety(&scenario),
+        Err(ScenarioError::ConflictingCommits {
+            first_node: ScenarioNode::Node(1),
+            first_block_id: block_id,
+            first_round: 1,
+            second_node: ScenarioNode::Node(3),
+            second_block_id: conflicting_block_id,
+            second_round: 1,
+        })
+    );
+}
+
+#[test]
+fn test_check_safety_ancestry() {
+    let scenario = TwinsScenario::new("safety_ancestry", 4, 1);
+
+    // Create a chain of blocks (rounds 1 to 3), and a fork at round 2
+    let genesis_id = HashValue::zero();
+    let block_ids: Vec<_> = (0..3).map(|_| HashValue::random()).collect();
+    let fork_block_id = HashValue::random();
+    let mut outcome = ScenarioOutcome::default();
+    outcome.add_ordered_block(block_ids[0], 1, genesis_id);
+    outcome.add_ordered_block(block_ids[1], 2, block_ids[0]);
+    outcome.add_ordered_block(block_ids[2], 3, block_ids[1]);
+    outcome.add_ordered_block(fork_block_id, 2, genesis_id);
+
+    // Honest nodes commit different rounds of the same chain
+    outcome.add_commit(ScenarioNode::Node(1), create_block_info(1, block_ids[0]));
+    outcome.add_commit(ScenarioNode::Node(2), create_block_info(3, block_ids[2]));
+    assert!(outcome.check_safety(&scenario).is_ok());
+
+    // An honest node commits a block (in a lower round) that is not an ancestor
+    outcome.add_commit(ScenarioNode::Node(3), create_block_info(2, fork_block_id));
+    assert_eq!(
+        outcome.check_safety(&scenario),
+        Err(ScenarioError::ConflictingCommits {
+            first_node: ScenarioNode::Node(2),
+            first_block_id: block_ids[2],
+            first_round: 3,
+            second_node: ScenarioNode::Node(3),
+            second_block_id: fork_block_id,
+            second_round: 2,
+        })
+    );
+}
+
+#[test]
+fn test_check_liveness() {
+    let mut scenario = TwinsScenario::new("liveness", 4, 1);
+    scenario.liveness = Some(LivenessBound {
+        commit_round: 3,
+        num_nodes: Some(2),
+    });
+
+    // Only one honest node reaches the commit round (the twin is ignored)
+    let mut outcome = ScenarioOutcome::default();
+    outcome.add_commit(
+        ScenarioNode::Node(1),
+        create_block_info(3, HashValue::random()),
+    );
+    outcome.add_commit(
+        ScenarioNode::Node(2),
+        create_block_info(2, HashValue::random()),
+    );
+    outcome.add_commit(
+        ScenarioNode::Twin(0),
+        create_block_info(5, HashValue::random()),
+    );
+    assert_eq!(
+        outcome.check_liveness(&scenario),
+        Err(ScenarioError::LivenessViolation {
+            commit_round: 3,
+            num_nodes: 2,
+            num_committed: 1,
+        })
+    );
+
+    // A second honest node reaches the commit round
+    outcome.add_commit(
+        ScenarioNode::Node(2),
+        create_block_info(4, HashValue::random()),
+    );
+    assert!(outcome.check_liveness(&scenario).is_ok());
+    assert_eq!(
+        outcome.get_committed_blocks(&ScenarioNode::Node(2)).len(),
+        2
+    );
+}
+
+#[test]
+/// This test runs a scenario with rotating leaders and no network faults,
+/// and checks that all nodes commit.
+///
+/// Run the test:
+/// cargo xtest -p consensus twins_scenario_runner_test -- --nocapture
+fn twins_scenario_runner_test() {
+    let mut scenario = TwinsScenario::new("rotating_leaders", 4, 0);
+    scenario.rounds = (1..10)
+        .map(|round| ScenarioRound {
+            round,
+            leader: Some(ScenarioNode::Node(round as usize % 4)),
+            partitions: vec![],
+            message_rules: vec![],
+        })
+        .collect();
+    scenario.liveness = Some(LivenessBound {
+        commit_round: 3,
+        num_nodes: None,
+    });
+
+    let outcome = run_scenario(&scenario).unwrap();
+    for node in scenario.get_honest_nodes() {
+        assert!(outcome.get_highest_committed_round(&node).unwrap() >= 3);
+    }
+}
+
+#[test]
+#[ignore] // This test is slow, as it runs many generated scenarios
+/// This test runs a sample of generated scenarios (with a single twin),
+/// and checks that the honest nodes never commit conflicting blocks.
+///
+/// Run the test:
+/// cargo xtest -p consensus twins_generated_scenarios_test -- --ignored --nocapture
+fn twins_generated_scenarios_test() {
+    let generator = ScenarioGenerator::new(4, 1, 4, 2, true).with_duration_ms(5_000);
+    for scenario in generator.sample_scenarios(10, 0) {
+        if let Err(error) = run_scenario(&scenario) {
+            panic!(
+                "Scenario failed: {}. Scenario:\n{}",
+                error,
+                scenario.to_yaml()
+            );
+        }
+    }
+}
+
+fn create_block_info(round: u64, block_id: HashValue) -> BlockInfo {
+    BlockInfo::new(1, round, block_id, HashValue::zero(), 0, 0, None)
+}
@@ -4,13 +4,14 @@
 
 use crate::{
     counters,
+    dag::DagInspector,
     epoch_manager::EpochManager,
     network::NetworkTask,
     network_interface::{ConsensusNetworkClient, DIRECT_SEND, RPC},
     network_tests::{NetworkPlayground, TwinId},
     payload_manager::DirectMempoolPayloadManager,
     pipeline::buffer_manager::OrderedBlocks,
-    quorum_store::quorum_store_db::MockQuorumStoreDB,
+    quorum_store::{inspection::QuorumStoreInspector, quorum_store_db::MockQuorumStoreDB},
     rand::rand_gen::storage::in_memory::InMemRandDb,
     test_utils::{mock_execution_client::MockExecutionClient, MockStorage},
     util::time_service::ClockTimeService,
@@ -22,7 +23,10 @@ use aptos_config::{
     generator::{self, ValidatorSwarm},
     network_id::{NetworkId, PeerNetworkId},
 };
-use aptos_consensus_types::common::{Author, Round};
+use aptos_consensus_types::{
+    block::Block,
+    common::{Author, Round},
+};
 use aptos_event_notifications::{ReconfigNotification, ReconfigNotificationListener};
 use aptos_mempool::mocks::MockSharedMempool;
 use aptos_network::{
@@ -59,6 +63,8 @@ pub struct SMRNode {
     pub id: TwinId,
     pub storage: Arc<MockStorage>,
     pub commit_cb_receiver: mpsc::UnboundedReceiver<LedgerInfoWithSignatures>,
+    // The blocks ordered by the node (sent along with each commit)
+    pub ordered_blocks_cb_receiver: mpsc::UnboundedReceiver<Vec<Block>>,
     _runtime: Runtime,
     _shared_mempool: MockSharedMempool,
     _state_sync: mpsc::UnboundedReceiver<Vec<SignedTransaction>>,
@@ -157,7 +163,10 @@ impl SMRNode {
             quorum_store_to_mempool_sender,
             execution_client.clone(),
             storage.clone(),
-            quorum_store_storage,
+            storage.clone(),
+            quorum_store_storage.clone(),
+            Arc::new(QuorumStoreInspector::new(quorum_store_storage)),
+            Arc::new(DagInspector::new(aptos_time_service::TimeService::real())),
             reconfig_listener,
             bounded_executor,
             aptos_time_service::TimeService::real(),
@@ -172,15 +181,23 @@ impl SMRNode {
         runtime.spawn(epoch_mgr.start(timeout_receiver, network_receiver));
 
         let (commit_cb_sender, commit_cb_receiver) = mpsc::unbounded::<LedgerInfoWithSignatures>();
+        let (ordered_blocks_cb_sender, ordered_blocks_cb_receiver) =
+            mpsc::unbounded::<Vec<Block>>();
         runtime.spawn(async move {
             loop {
                 let ordered_blocks = ordered_blocks_events.next().await.unwrap();
                 let commit = ordered_blocks.ordered_proof.clone();
+                let blocks = ordered_blocks
+                    .ordered_blocks
+                    .iter()
+                    .map(|block| block.block().clone())
+                    .collect();
                 execution_client
                     .commit_to_storage(ordered_blocks)
                     .await
                     .unwrap();
 
+                ordered_blocks_cb_sender.unbounded_send(blocks).unwrap();
                 commit_cb_sender.unbounded_send(commit.clone()).unwrap();
             }
         });
@@ -189,6 +206,7 @@ impl SMRNode {
             id: twin_id,
             _runtime: runtime,
             commit_cb_receiver,
+            ordered_blocks_cb_receiver,
             storage,
             _shared_mempool: shared_mempool,
             _state_sync: state_sync,
@@ -0,0 +1,674 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Offline simulation of leader election over the committed history of NewBlockEvents.
+//!
+//! Leader election is replayed against the recorded history, i.e., the simulation does not
+//! model how the history would have changed had other leaders been elected. Instead, failed
+//! rounds under a simulated config are estimated from the failure rate that each validator
+//! had as a leader in the recorded history.
+//!
+//! The command line tool (that opens the DB and prints the reports) lives in aptos-debugger.
+
+use crate::liveness::{
+    leader_reputation::{
+        create_reputation_heuristic, extract_epoch_to_proposers_impl, LeaderReputation,
+        MetadataBackend, VersionedNewBlockEvent,
+    },
+    proposer_election::ProposerElection,
+    rotating_proposer_election::{choose_leader, RotatingProposer},
+    round_proposer_election::RoundProposer,
+};
+use anyhow::{anyhow, ensure, Result};
+use aptos_config::config::ConsensusConfig;
+use aptos_consensus_types::common::{Author, Round};
+use aptos_crypto::HashValue;
+use aptos_logger::prelude::*;
+use aptos_storage_interface::DbReader;
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::NewBlockEvent,
+    epoch_state::EpochState,
+    on_chain_config::{LeaderReputationType, ProposerElectionType},
+};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{Display, Formatter},
+    ops::RangeInclusive,
+    sync::Arc,
+};
+
+/// Returns the number of previous epochs the given config uses history from
+pub fn num_history_epochs(proposer_election_type: &ProposerElectionType) -> u64 {
+    match proposer_election_type {
+        ProposerElectionType::LeaderReputation(
+            LeaderReputationType::ProposerAndVoter(config)
+            | LeaderReputationType::ProposerAndVoterV2(config),
+        ) => config.use_history_from_previous_epoch_max_count as u64,
+        _ => 0,
+    }
+}
+
+/// The committed history needed to simulate leader election for a range of epochs
+pub struct LeaderElectionHistory {
+    /// The state of each epoch, together with the number of rounds in the previous epoch
+    epoch_states: BTreeMap<u64, (EpochState, Round)>,
+    /// The NewBlockEvents of all epochs, in increasing (epoch, round) order
+    events: Arc<Vec<VersionedNewBlockEvent>>,
+}
+
+impl LeaderElectionHistory {
+    pub fn new(
+        epoch_states: BTreeMap<u64, (EpochState, Round)>,
+        events: Vec<VersionedNewBlockEvent>,
+    ) -> Self {
+        Self {
+            epoch_states,
+            events: Arc::new(events),
+        }
+    }
+
+    /// Loads the epoch states and NewBlockEvents of the given (inclusive) epoch range
+    pub fn load(aptos_db: &dyn DbReader, first_epoch: u64, last_epoch: u64) -> Result<Self> {
+        ensure!(first_epoch >= 1, "Epoch 0 (genesis) has no leaders");
+
+        // The ledger info ending each epoch contains the state of the next one
+        let mut epoch_states = BTreeMap::new();
+        let mut start_epoch = first_epoch - 1;
+        while start_epoch < last_epoch {
+            let proof = aptos_db.get_epoch_ending_ledger_infos(start_epoch, last_epoch)?;
+            ensure!(
+                !proof.ledger_info_with_sigs.is_empty(),
+                "No epoch ending ledger infos found from epoch {}",
+                start_epoch
+            );
+            for ledger_info in &proof.ledger_info_with_sigs {
+                let ledger_info = ledger_info.ledger_info();
+                let next_epoch_state = ledger_info.next_epoch_state().ok_or_else(|| {
+                    anyhow!(
+                        "Epoch {} ending ledger info has no next epoch state",
+                        ledger_info.epoch()
+                    )
+                })?;
+                epoch_states.insert(
+                    next_epoch_state.epoch,
+                    (next_epoch_state.clone(), ledger_info.round()),
+                );
+            }
+            start_epoch += proof.ledger_info_with_sigs.len() as u64;
+        }
+
+        let latest_height = match aptos_db.get_latest_block_events(1)?.first() {
+            Some(event) => bcs::from_bytes::<NewBlockEvent>(event.event.event_data())?.height(),
+            None => return Ok(Self::new(epoch_states, vec![])),
+        };
+        let mut events = vec![];
+        for height in first_height_of_epoch(aptos_db, first_epoch, latest_height)?..=latest_height {
+            let (version, _, event) = aptos_db.get_block_info_by_height(height)?;
+            if event.epoch() > last_epoch {
+                break;
+            }
+            events.push(VersionedNewBlockEvent { event, version });
+        }
+        info!(
+            "Loaded {} NewBlockEvents of epochs [{}, {}]",
+            events.len(),
+            first_epoch,
+            last_epoch
+        );
+
+        Ok(Self::new(epoch_states, events))
+    }
+
+    fn epoch_events(&self, epoch: u64) -> &[VersionedNewBlockEvent] {
+        let start = self.events.partition_point(|e| e.event.epoch() < epoch);
+        let end = self.events.partition_point(|e| e.event.epoch() <= epoch);
+        &self.events[start..end]
+    }
+}
+
+/// Binary searches for the height of the first block of the given epoch
+fn first_height_of_epoch(aptos_db: &dyn DbReader, epoch: u64, latest_height: u64) -> Result<u64> {
+    let (mut low, mut high) = (0, latest_height + 1);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        let (_, _, event) = aptos_db.get_block_info_by_height(mid)?;
+        if event.epoch() < epoch {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    Ok(low)
+}
+
+/// A MetadataBackend that serves windows of the loaded history
+struct HistoryBackend {
+    window_size: usize,
+    events: Arc<Vec<VersionedNewBlockEvent>>,
+    aptos_db: Option<Arc<dyn DbReader>>,
+}
+
+impl MetadataBackend for HistoryBackend {
+    fn get_block_metadata(
+        &self,
+        target_epoch: u64,
+        target_round: Round,
+    ) -> (Vec<NewBlockEvent>, HashValue) {
+        let end = self.events.partition_point(|e| {
+            (e.event.epoch(), e.event.round()) <= (target_epoch, target_round)
+        });
+        let window = &self.events[end.saturating_sub(self.window_size)..end];
+
+        // Without a db (e.g., in tests), the root hash used for the seed is unknown
+        let root_hash = match (window.last(), &self.aptos_db) {
+            (Some(latest), Some(aptos_db)) => aptos_db
+                .get_accumulator_root_hash(latest.version)
+                .unwrap_or_else(|e| {
+                    warn!(
+                        "Couldn't fetch accumulator hash for version {}: {:?}",
+                        latest.version, e
+                    );
+                    HashValue::zero()
+                }),
+            _ => HashValue::zero(),
+        };
+
+        // The history is expected from the latest to the oldest event
+        (
+            window.iter().rev().map(|e| e.event.clone()).collect(),
+            root_hash,
+        )
+    }
+}
+
+/// Replays leader election under a given config over the loaded history
+pub struct LeaderElectionSimulator {
+    history: LeaderElectionHistory,
+    exclude_round: u64,
+    aptos_db: Option<Arc<dyn DbReader>>,
+}
+
+impl LeaderElectionSimulator {
+    pub fn new(
+        history: LeaderElectionHistory,
+        exclude_round: u64,
+        aptos_db: Option<Arc<dyn DbReader>>,
+    ) -> Self {
+        Self {
+            history,
+            exclude_round,
+            aptos_db,
+        }
+    }
+
+    /// Simulates leader election for all rounds of the given epochs, and compares
+    /// the elected leaders with the recorded history.
+    pub fn simulate(
+        &self,
+        name: String,
+        proposer_election_type: &ProposerElectionType,
+        epochs: RangeInclusive<u64>,
+    ) -> Result<SimulationReport> {
+        let mut report = SimulationReport::new(name);
+        for epoch in epochs {
+            let (epoch_state, _) = self
+                .history
+                .epoch_states
+                .get(&epoch)
+                .ok_or_else(|| anyhow!("Epoch {} state is not loaded", epoch))?;
+            let events = self.history.epoch_events(epoch);
+            let (first_round, last_round) = match (events.first(), events.last()) {
+                (Some(first), Some(last)) => (first.event.round(), last.event.round()),
+                _ => continue,
+            };
+            let proposers = epoch_state.verifier.get_ordered_account_addresses();
+            let total_voting_power = epoch_state.verifier.total_voting_power() as f64;
+            let proposer_election =
+                self.create_proposer_election(proposer_election_type, epoch_state)?;
+
+            // Reconstruct the leaders of the recorded history. Failed proposers are those of
+            // the rounds immediately preceding the block that records them.
+            let mut recorded_leaders = HashMap::new();
+            for versioned_event in events {
+                let event = &versioned_event.event;
+                let failed_proposer_indices = event.failed_proposer_indices();
+                for (i, index) in failed_proposer_indices.iter().enumerate() {
+                    let round = (event.round() + i as u64)
+                        .saturating_sub(failed_proposer_indices.len() as u64);
+                    if let Some(failed_proposer) = proposers.get(*index as usize) {
+                        recorded_leaders.insert(round, (*failed_proposer, false));
+                    }
+                }
+                if proposers.contains(&event.proposer()) {
+                    recorded_leaders.insert(event.round(), (event.proposer(), true));
+                }
+            }
+
+            for round in first_round..=last_round {
+                report.num_rounds += 1;
+                for author in &proposers {
+                    let stats = report.validators.entry(*author).or_default();
+                    stats.expected_rounds +=
+                        epoch_state.verifier.get_voting_power(author).unwrap_or(0) as f64
+                            / total_voting_power;
+                }
+
+                let elected = proposer_election.get_valid_proposer(round);
+                report
+                    .validators
+                    .entry(elected)
+                    .or_default()
+                    .simulated_rounds += 1;
+
+                match recorded_leaders.get(&round) {
+                    Some((recorded, succeeded)) => {
+                        let stats = report.validators.entry(*recorded).or_default();
+                        if *succeeded {
+                            stats.recorded_successful_rounds += 1;
+                        } else {
+                            stats.recorded_failed_rounds += 1;
+                            report.recorded_failed_rounds += 1;
+                        }
+                        report.rounds_with_recorded_leader += 1;
+                        if *recorded == elected {
+                            report.matching_leaders += 1;
+                        }
+                    },
+                    None => {
+                        report.recorded_failed_rounds += 1;
+                        report.unattributed_failed_rounds += 1;
+                    },
+                }
+            }
+        }
+        report.finalize();
+        Ok(report)
+    }
+
+    fn create_proposer_election(
+        &self,
+        proposer_election_type: &ProposerElectionType,
+        epoch_state: &EpochState,
+    ) -> Result<Box<dyn ProposerElection>> {
+        let epoch = epoch_state.epoch;
+        let proposers = epoch_state.verifier.get_ordered_account_addresses();
+        Ok(match proposer_election_type {
+            ProposerElectionType::RotatingProposer(contiguous_rounds) => {
+                Box::new(RotatingProposer::new(proposers, *contiguous_rounds))
+            },
+            ProposerElectionType::FixedProposer(contiguous_rounds) => {
+                let proposer = choose_leader(proposers);
+                Box::new(RotatingProposer::new(vec![proposer], *contiguous_rounds))
+            },
+            ProposerElectionType::RoundProposer(round_proposers) => {
+                let default_proposer = *proposers
+                    .first()
+                    .ok_or_else(|| anyhow!("Epoch {} has no proposers", epoch))?;
+                Box::new(RoundProposer::new(
+                    round_proposers.clone(),
+                    default_proposer,
+                ))
+            },
+            ProposerElectionType::LeaderReputation(leader_reputation_type) => {
+                let (
+                    heuristic,
+                    window_size,
+                    weight_by_voting_power,
+                    use_history_from_previous_epoch_max_count,
+                ) = create_reputation_heuristic(
+                    AccountAddress::ZERO,
+                    leader_reputation_type,
+                    proposers.len(),
+                );
+                let voting_powers: Vec<_> = if weight_by_voting_power {
+                    proposers
+                        .iter()
+                        .map(|p| epoch_state.verifier.get_voting_power(p).unwrap_or(0))
+                        .collect()
+                } else {
+                    vec![1; proposers.len()]
+                };
+
+                // Same as the epoch manager: genesis is epoch 0, and the only block in
+                // epoch 1 has no votes, so it is skipped unless we are in epoch 1.
+                let first_epoch_to_consider = std::cmp::max(
+                    if epoch == 1 { 1 } else { 2 },
+                    epoch.saturating_sub(use_history_from_previous_epoch_max_count as u64),
+                );
+                let epoch_states_and_rounds = (first_epoch_to_consider..=epoch)
+                    .filter_map(|epoch| self.history.epoch_states.get(&epoch))
+                    .map(|(epoch_state, rounds)| (epoch_state, *rounds))
+                    .collect::<Vec<_>>();
+                let epoch_to_proposers = extract_epoch_to_proposers_impl(
+                    &epoch_states_and_rounds,
+                    epoch,
+                    &proposers,
+                    window_size as u64 + self.exclude_round,
+                )?;
+
+                let backend = Arc::new(HistoryBackend {
+                    window_size,
+                    events: self.history.events.clone(),
+                    aptos_db: self.aptos_db.clone(),
+                });
+                Box::new(LeaderReputation::new(
+                    epoch,
+                    epoch_to_proposers,
+                    voting_powers,
+                    backend,
+                    heuristic,
+                    self.exclude_round,
+                    leader_reputation_type.use_root_hash_for_seed(),
+                    ConsensusConfig::default().window_for_chain_health,
+                ))
+            },
+        })
+    }
+}
+
+/// Leader statistics of a single validator
+#[derive(Debug, Default, Serialize)]
+pub struct ValidatorLeaderStats {
+    /// The number of rounds the validator would lead, if leaders were chosen by stake
+    pub expected_rounds: f64,
+    /// The number of rounds the validator successfully led in the recorded history
+    pub recorded_successful_rounds: u64,
+    /// The number of rounds the validator failed to lead in the recorded history
+    pub recorded_failed_rounds: u64,
+    /// The number of rounds the validator was elected in the simulation
+    pub simulated_rounds: u64,
+    /// The number of simulated rounds expected to fail, based on the recorded failure rate
+    pub expected_failed_rounds: f64,
+}
+
+impl ValidatorLeaderStats {
+    fn recorded_rounds(&self) -> u64 {
+        self.recorded_successful_rounds + self.recorded_failed_rounds
+    }
+
+    fn recorded_failure_rate(&self) -> f64 {
+        if self.recorded_rounds() == 0 {
+            0.0
+        } else {
+            self.recorded_failed_rounds as f64 / self.recorded_rounds() as f64
+        }
+    }
+}
+
+/// Measures how proportional the leader shares are to the stake shares of the validators
+#[derive(Debug, Default, Serialize)]
+pub struct FairnessMetrics {
+    /// Jain's fairness index of the (leader share / stake share) ratios, in (0, 1]
+    pub jain_index: f64,
+    /// The smallest (leader share / stake share) ratio
+    pub min_share_ratio: f64,
+    /// The largest (leader share / stake share) ratio
+    pub max_share_ratio: f64,
+    /// The number of validators (with stake) that never led a round
+    pub num_starved_validators: usize,
+}
+
+impl FairnessMetrics {
+    /// Computes the metrics from the (leader rounds, expected leader rounds) of each validator
+    fn new(rounds: impl Iterator<Item = (f64, f64)>) -> Self {
+        let mut ratios = vec![];
+        let mut num_starved_validators = 0;
+        for (leader_rounds, expected_rounds) in rounds {
+            if expected_rounds > 0.0 {
+                ratios.push(leader_rounds / expected_rounds);
+                if leader_rounds == 0.0 {
+                    num_starved_validators += 1;
+                }
+            }
+        }
+        if ratios.is_empty() {
+            return Self::default();
+        }
+
+        let sum: f64 = ratios.iter().sum();
+        let sum_of_squares: f64 = ratios.iter().map(|ratio| ratio * ratio).sum();
+        Self {
+            jain_index: if sum_of_squares > 0.0 {
+                sum * sum / (ratios.len() as f64 * sum_of_squares)
+            } else {
+                0.0
+            },
+            min_share_ratio: ratios.iter().cloned().fold(f64::INFINITY, f64::min),
+            max_share_ratio: ratios.iter().cloned().fold(0.0, f64::max),
+            num_starved_validators,
+        }
+    }
+}
+
+/// The result of simulating leader election under a given config
+#[derive(Debug, Serialize)]
+pub struct SimulationReport {
+    pub name: String,
+    pub num_rounds: u64,
+    /// The number of rounds without a committed block in the recorded history
+    pub recorded_failed_rounds: u64,
+    /// The number of failed rounds whose leader is not recorded (e.g., because more
+    /// consecutive rounds failed than the failed authors stored in a block)
+    pub unattributed_failed_rounds: u64,
+    /// The number of rounds expected to fail under the simulated config
+    pub expected_failed_rounds: f64,
+    /// The number of rounds with a known leader in the recorded history
+    pub rounds_with_recorded_leader: u64,
+    /// The number of those rounds in which the simulation elected the recorded leader
+    pub matching_leaders: u64,
+    pub recorded_fairness: FairnessMetrics,
+    pub simulated_fairness: FairnessMetrics,
+    pub validators: BTreeMap<Author, ValidatorLeaderStats>,
+}
+
+impl SimulationReport {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            num_rounds: 0,
+            recorded_failed_rounds: 0,
+            unattributed_failed_rounds: 0,
+            expected_failed_rounds: 0.0,
+            rounds_with_recorded_leader: 0,
+            matching_leaders: 0,
+            recorded_fairness: FairnessMetrics::default(),
+            simulated_fairness: FairnessMetrics::default(),
+            validators: BTreeMap::new(),
+        }
+    }
+
+    fn finalize(&mut self) {
+        for stats in self.validators.values_mut() {
+            stats.expected_failed_rounds =
+                stats.simulated_rounds as f64 * stats.recorded_failure_rate();
+        }
+        self.expected_failed_rounds = self
+            .validators
+            .values()
+            .map(|stats| stats.expected_failed_rounds)
+            .sum::<f64>()
+            + self.unattributed_failed_rounds as f64;
+
+        // Only the rounds with a recorded leader count for the recorded shares
+        let recorded_fraction = if self.num_rounds == 0 {
+            0.0
+        } else {
+            self.rounds_with_recorded_leader as f64 / self.num_rounds as f64
+        };
+        self.recorded_fairness = FairnessMetrics::new(self.validators.values().map(|stats| {
+            (
+                stats.recorded_rounds() as f64,
+                stats.expected_rounds * recorded_fraction,
+            )
+        }));
+        self.simulated_fairness = FairnessMetrics::new(
+            self.validators
+                .values()
+                .map(|stats| (stats.simulated_rounds as f64, stats.expected_rounds)),
+        );
+    }
+}
+
+impl Display for SimulationReport {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let num_rounds = std::cmp::max(self.num_rounds, 1) as f64;
+        writeln!(f, "Simulated leader election config: {}", self.name)?;
+        writeln!(
+            f,
+            "Rounds: {}, recorded failed rounds: {} ({} unattributed), expected failed rounds: {:.1}",
+            self.num_rounds,
+            self.recorded_failed_rounds,
+            self.unattributed_failed_rounds,
+            self.expected_failed_rounds,
+        )?;
+        writeln!(
+            f,
+            "Elected the recorded leader in {} of {} rounds",
+            self.matching_leaders, self.rounds_with_recorded_leader,
+        )?;
+        for (name, fairness) in [
+            ("recorded", &self.recorded_fairness),
+            ("simulated", &self.simulated_fairness),
+        ] {
+            writeln!(
+                f,
+                "Fairness ({}): jain index {:.4}, leader/stake share ratio in [{:.3}, {:.3}], {} validators never led",
+                name,
+                fairness.jain_index,
+                fairness.min_share_ratio,
+                fairness.max_share_ratio,
+                fairness.num_starved_validators,
+            )?;
+        }
+        writeln!(
+            f,
+            "{:<66} {:>8} {:>10} {:>10} {:>10} {:>10}",
+            "validator", "stake %", "recorded %", "failed", "simulated %", "exp failed"
+        )?;
+        for (author, stats) in &self.validators {
+            writeln!(
+                f,
+                "{:<66} {:>8.2} {:>10.2} {:>10} {:>10.2} {:>10.1}",
+                author,
+                stats.expected_rounds * 100.0 / num_rounds,
+                stats.recorded_rounds() as f64 * 100.0 / num_rounds,
+                stats.recorded_failed_rounds,
+                stats.simulated_rounds as f64 * 100.0 / num_rounds,
+                stats.expected_failed_rounds,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        liveness::leader_reputation::VersionedNewBlockEvent,
+        util::leader_reputation_simulator::{LeaderElectionHistory, LeaderElectionSimulator},
+    };
+    use aptos_bitvec::BitVec;
+    use aptos_types::{
+        account_address::AccountAddress,
+        account_config::NewBlockEvent,
+        epoch_state::EpochState,
+        on_chain_config::{LeaderReputationType, ProposerAndVoterConfig, ProposerElectionType},
+        validator_verifier::random_validator_verifier,
+    };
+    use std::collections::BTreeMap;
+
+    /// Creates a history of an epoch with rotating leaders, in which the last
+    /// validator always fails to propose.
+    fn create_history(num_rounds: u64) -> LeaderElectionHistory {
+        let (_, verifier) = random_validator_verifier(4, None, false);
+        let proposers = verifier.get_ordered_account_addresses();
+        let mut votes = BitVec::with_num_bits(proposers.len() as u16);
+        for index in 0..3 {
+            votes.set(index);
+        }
+
+        let mut events = vec![];
+        for round in 1..=num_rounds {
+            let index = round % proposers.len() as u64;
+            if index == 3 {
+                continue;
+            }
+            let failed_proposer_indices = if index == 0 { vec![3] } else { vec![] };
+            events.push(VersionedNewBlockEvent {
+                event: NewBlockEvent::new(
+                    AccountAddress::random(),
+                    2,
+                    round,
+                    round,
+                    votes.clone().into(),
+                    proposers[index as usize],
+                    failed_proposer_indices,
+                    round,
+                ),
+                version: round,
+            });
+        }
+
+        let epoch_states = BTreeMap::from([(2, (EpochState::new(2, verifier), 100))]);
+        LeaderElectionHistory::new(epoch_states, events)
+    }
+
+    #[test]
+    fn test_simulate_recorded_config() {
+        let simulator = LeaderElectionSimulator::new(create_history(80), 0, None);
+        let report = simulator
+            .simulate(
+                "rotating".into(),
+                &ProposerElectionType::RotatingProposer(1),
+                2..=2,
+            )
+            .unwrap();
+
+        // The recorded history is reproduced exactly, and all leaders are elected equally often
+        assert_eq!(report.num_rounds, 80);
+        assert_eq!(report.rounds_with_recorded_leader, 80);
+        assert_eq!(report.matching_leaders, 80);
+        assert_eq!(report.recorded_failed_rounds, 20);
+        assert_eq!(report.unattributed_failed_rounds, 0);
+        assert!((report.expected_failed_rounds - 20.0).abs() < 1e-9);
+        assert!((report.simulated_fairness.jain_index - 1.0).abs() < 1e-9);
+        assert!((report.recorded_fairness.jain_index - 1.0).abs() < 1e-9);
+        assert!(report
+            .validators
+            .values()
+            .all(|stats| stats.simulated_rounds == 20));
+    }
+
+    #[test]
+    fn test_simulate_leader_reputation() {
+        let simulator = LeaderElectionSimulator::new(create_history(400), 0, None);
+        let config = ProposerElectionType::LeaderReputation(
+            LeaderReputationType::ProposerAndVoterV2(ProposerAndVoterConfig {
+                active_weight: 1000,
+                inactive_weight: 10,
+                failed_weight: 1,
+                failure_threshold_percent: 10,
+                proposer_window_num_validators_multiplier: 10,
+                voter_window_num_validators_multiplier: 1,
+                weight_by_voting_power: true,
+                use_history_from_previous_epoch_max_count: 5,
+            }),
+        );
+        let report = simulator
+            .simulate("leader reputation".into(), &config, 2..=2)
+            .unwrap();
+
+        // The failing validator is (almost) never elected, which trades fairness for
+        // fewer failed rounds
+        assert_eq!(report.num_rounds, 400);
+        assert!(report.expected_failed_rounds < 20.0);
+        assert!(report.simulated_fairness.jain_index < report.recorded_fairness.jain_index);
+        let failing_validator = simulator.history.epoch_states[&2]
+            .0
+            .verifier
+            .get_ordered_account_addresses()[3];
+        assert!(report.validators[&failing_validator].simulated_rounds < 20);
+    }
+}
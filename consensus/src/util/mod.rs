@@ -2,12 +2,16 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::ensure;
+use aptos_consensus_types::{common::Author, equivocation_evidence::EquivocationEvidence};
 use aptos_types::{
-    on_chain_config::{OnChainJWKConsensusConfig, OnChainRandomnessConfig},
+    epoch_state::EpochState,
+    on_chain_config::{Features, OnChainJWKConsensusConfig, OnChainRandomnessConfig},
     validator_txn::ValidatorTransaction,
 };
 
 pub mod db_tool;
+pub mod leader_reputation_simulator;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod mock_time_service;
 pub mod time_service;
@@ -15,10 +19,40 @@ pub mod time_service;
 pub fn is_vtxn_expected(
     randomness_config: &OnChainRandomnessConfig,
     jwk_consensus_config: &OnChainJWKConsensusConfig,
+    features: &Features,
     vtxn: &ValidatorTransaction,
 ) -> bool {
     match vtxn {
         ValidatorTransaction::DKGResult(_) => randomness_config.randomness_enabled(),
         ValidatorTransaction::ObservedJWKUpdate(_) => jwk_consensus_config.jwk_consensus_enabled(),
+        ValidatorTransaction::EquivocationReport(_) => features.is_equivocation_reports_enabled(),
     }
 }
+
+/// Verifies the validator transactions that can only be verified by consensus. The evidence
+/// of equivocation reports consists of consensus messages (which the VM can't decode), so the
+/// VM relies on consensus to only certify blocks with valid reports. Reports must be submitted
+/// by the author of the block (or node) that includes them, so reporters can't be spoofed.
+/// The other validator transactions are verified by the VM.
+pub fn verify_vtxn(
+    epoch_state: &EpochState,
+    author: Author,
+    vtxn: &ValidatorTransaction,
+) -> anyhow::Result<()> {
+    if let ValidatorTransaction::EquivocationReport(report) = vtxn {
+        ensure!(
+            report.metadata.reporter == author,
+            "equivocation report by {}, included by {}",
+            report.metadata.reporter,
+            author
+        );
+        ensure!(
+            report.metadata.epoch == epoch_state.epoch,
+            "equivocation report for epoch {}, current epoch {}",
+            report.metadata.epoch,
+            epoch_state.epoch
+        );
+        EquivocationEvidence::from_report(report)?.verify(&epoch_state.verifier)?;
+    }
+    Ok(())
+}
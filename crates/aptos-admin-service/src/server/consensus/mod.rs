@@ -3,7 +3,7 @@
 
 use anyhow::{bail, Error};
 use aptos_consensus::{
-    persistent_liveness_storage::PersistentLivenessStorage,
+    persistent_liveness_storage::LivenessStorageDbs,
     quorum_store::quorum_store_db::QuorumStoreStorage, util::db_tool::extract_txns_from_block,
 };
 use aptos_crypto::HashValue;
@@ -16,7 +16,7 @@ use std::{collections::HashMap, sync::Arc};
 
 pub async fn handle_dump_consensus_db_request(
     _req: Request<Body>,
-    consensus_db: Arc<dyn PersistentLivenessStorage>,
+    consensus_db: Arc<dyn LivenessStorageDbs>,
 ) -> hyper::Result<Response<Body>> {
     info!("Dumping consensus db.");
 
@@ -37,6 +37,46 @@ pub async fn handle_dump_consensus_db_request(
     }
 }
 
+pub async fn handle_dump_equivocation_evidence_request(
+    req: Request<Body>,
+    consensus_db: Arc<dyn LivenessStorageDbs>,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    let bcs: bool = match query_pairs.get("bcs") {
+        Some(val) => match val.parse() {
+            Ok(val) => val,
+            Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+        },
+        None => false,
+    };
+
+    info!("Dumping equivocation evidence.");
+
+    match spawn_blocking(move || {
+        if bcs {
+            dump_equivocation_evidence_bcs(consensus_db.as_ref()).map(Into::<Body>::into)
+        } else {
+            dump_equivocation_evidence(consensus_db.as_ref()).map(Into::into)
+        }
+    })
+    .await
+    {
+        Ok(result) => {
+            info!("Finished dumping equivocation evidence.");
+            Ok(reply_with(vec![], result))
+        },
+        Err(e) => {
+            info!("Failed to dump equivocation evidence: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
 pub async fn handle_dump_quorum_store_db_request(
     req: Request<Body>,
     quorum_store_db: Arc<dyn QuorumStoreStorage>,
@@ -73,7 +113,7 @@ pub async fn handle_dump_quorum_store_db_request(
 
 pub async fn handle_dump_block_request(
     req: Request<Body>,
-    consensus_db: Arc<dyn PersistentLivenessStorage>,
+    consensus_db: Arc<dyn LivenessStorageDbs>,
     quorum_store_db: Arc<dyn QuorumStoreStorage>,
 ) -> hyper::Result<Response<Body>> {
     let query = req.uri().query().unwrap_or("");
@@ -127,7 +167,7 @@ pub async fn handle_dump_block_request(
     }
 }
 
-fn dump_consensus_db(consensus_db: &dyn PersistentLivenessStorage) -> anyhow::Result<String> {
+fn dump_consensus_db(consensus_db: &dyn LivenessStorageDbs) -> anyhow::Result<String> {
     let mut body = String::new();
 
     let (last_vote, highest_tc, consensus_blocks, consensus_qcs) =
@@ -155,6 +195,21 @@ fn dump_consensus_db(consensus_db: &dyn PersistentLivenessStorage) -> anyhow::Re
     Ok(body)
 }
 
+fn dump_equivocation_evidence(consensus_db: &dyn LivenessStorageDbs) -> anyhow::Result<String> {
+    let mut body = String::new();
+    for evidence in consensus_db.consensus_db().get_equivocation_evidence()? {
+        body.push_str(&format!("{evidence}\n{evidence:?}\n\n"));
+    }
+    Ok(body)
+}
+
+fn dump_equivocation_evidence_bcs(
+    consensus_db: &dyn LivenessStorageDbs,
+) -> anyhow::Result<Vec<u8>> {
+    let evidence = consensus_db.consensus_db().get_equivocation_evidence()?;
+    Ok(bcs::to_bytes(&evidence)?)
+}
+
 fn dump_quorum_store_db(
     quorum_store_db: &dyn QuorumStoreStorage,
     digest: Option<HashValue>,
@@ -177,7 +232,7 @@ fn dump_quorum_store_db(
 }
 
 fn dump_blocks(
-    consensus_db: &dyn PersistentLivenessStorage,
+    consensus_db: &dyn LivenessStorageDbs,
     quorum_store_db: &dyn QuorumStoreStorage,
     block_id: Option<HashValue>,
 ) -> anyhow::Result<String> {
@@ -215,7 +270,7 @@ fn dump_blocks(
 }
 
 fn dump_blocks_bcs(
-    consensus_db: &dyn PersistentLivenessStorage,
+    consensus_db: &dyn LivenessStorageDbs,
     quorum_store_db: &dyn QuorumStoreStorage,
     block_id: Option<HashValue>,
 ) -> anyhow::Result<Vec<u8>> {
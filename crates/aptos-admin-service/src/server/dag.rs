@@ -0,0 +1,56 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::server::utils::{get_query_pairs, parse_query_param, reply_with_json};
+use aptos_consensus::DagInspector;
+use aptos_logger::info;
+use aptos_system_utils::utils::{reply_with, reply_with_status, spawn_blocking};
+use http::header::{HeaderValue, CONTENT_TYPE};
+use hyper::{Body, Request, Response, StatusCode};
+use std::sync::Arc;
+
+pub async fn handle_dag_snapshot_request(
+    req: Request<Body>,
+    dag_inspector: Arc<DagInspector>,
+) -> hyper::Result<Response<Body>> {
+    let query_pairs = get_query_pairs(&req);
+    let (from_round, to_round) = match (
+        parse_query_param(&query_pairs, "from_round"),
+        parse_query_param(&query_pairs, "to_round"),
+    ) {
+        (Ok(from_round), Ok(to_round)) => (from_round, to_round),
+        (Err(err), _) | (_, Err(err)) => {
+            return Ok(reply_with_status(StatusCode::BAD_REQUEST, err))
+        },
+    };
+    let as_html = match query_pairs.get("format").map(|format| format.as_ref()) {
+        None | Some("json") => false,
+        Some("html") => true,
+        Some(format) => {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                format!("Unknown format {format}, expected json or html."),
+            ))
+        },
+    };
+
+    info!("Exporting DAG snapshot (from_round: {from_round:?}, to_round: {to_round:?}).");
+    let result = spawn_blocking(move || dag_inspector.snapshot(from_round, to_round)).await;
+    if !as_html {
+        return reply_with_json("export DAG snapshot", result);
+    }
+    match result {
+        Ok(snapshot) => {
+            info!("Finished to export DAG snapshot.");
+            let headers = vec![(CONTENT_TYPE, HeaderValue::from_static("text/html"))];
+            Ok(reply_with(headers, snapshot.to_html()))
+        },
+        Err(e) => {
+            info!("Failed to export DAG snapshot: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
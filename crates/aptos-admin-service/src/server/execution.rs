@@ -0,0 +1,23 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::server::utils::{get_query_pairs, parse_query_param, reply_with_json};
+use aptos_block_executor::conflict_report::recent_conflict_reports;
+use aptos_logger::info;
+use aptos_system_utils::utils::reply_with_status;
+use hyper::{Body, Request, Response, StatusCode};
+
+pub async fn handle_conflict_reports_request(req: Request<Body>) -> hyper::Result<Response<Body>> {
+    let query_pairs = get_query_pairs(&req);
+    let limit: Option<usize> = match parse_query_param(&query_pairs, "limit") {
+        Ok(limit) => limit,
+        Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err)),
+    };
+
+    info!("Exporting conflict reports (limit: {limit:?}).");
+    let mut reports = recent_conflict_reports();
+    if let Some(limit) = limit {
+        reports.drain(..reports.len().saturating_sub(limit));
+    }
+    reply_with_json("export conflict reports", Ok(reports))
+}
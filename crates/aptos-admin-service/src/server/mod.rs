@@ -3,7 +3,9 @@
 
 use aptos_config::config::{AuthenticationConfig, NodeConfig};
 use aptos_consensus::{
-    persistent_liveness_storage::StorageWriteProxy, quorum_store::quorum_store_db::QuorumStoreDB,
+    persistent_liveness_storage::StorageWriteProxy,
+    quorum_store::{inspection::QuorumStoreInspector, quorum_store_db::QuorumStoreDB},
+    DagInspector,
 };
 use aptos_infallible::RwLock;
 use aptos_logger::info;
@@ -26,6 +28,10 @@ use std::{
 use tokio::runtime::Runtime;
 
 mod consensus;
+mod dag;
+mod execution;
+mod quorum_store;
+mod utils;
 
 #[derive(Default)]
 pub struct Context {
@@ -34,6 +40,8 @@ pub struct Context {
     aptos_db: RwLock<Option<Arc<DbReaderWriter>>>,
     consensus_db: RwLock<Option<Arc<StorageWriteProxy>>>,
     quorum_store_db: RwLock<Option<Arc<QuorumStoreDB>>>,
+    quorum_store_inspector: RwLock<Option<Arc<QuorumStoreInspector>>>,
+    dag_inspector: RwLock<Option<Arc<DagInspector>>>,
 }
 
 impl Context {
@@ -49,6 +57,14 @@ impl Context {
         *self.consensus_db.write() = Some(consensus_db);
         *self.quorum_store_db.write() = Some(quorum_store_db);
     }
+
+    fn set_quorum_store_inspector(&self, quorum_store_inspector: Arc<QuorumStoreInspector>) {
+        *self.quorum_store_inspector.write() = Some(quorum_store_inspector);
+    }
+
+    fn set_dag_inspector(&self, dag_inspector: Arc<DagInspector>) {
+        *self.dag_inspector.write() = Some(dag_inspector);
+    }
 }
 
 pub struct AdminService {
@@ -107,6 +123,15 @@ impl AdminService {
             .set_consensus_dbs(consensus_db, quorum_store_db)
     }
 
+    pub fn set_quorum_store_inspector(&self, quorum_store_inspector: Arc<QuorumStoreInspector>) {
+        self.context
+            .set_quorum_store_inspector(quorum_store_inspector)
+    }
+
+    pub fn set_dag_inspector(&self, dag_inspector: Arc<DagInspector>) {
+        self.context.set_dag_inspector(dag_inspector)
+    }
+
     fn start(&self, address: SocketAddr, enabled: bool) {
         let context = self.context.clone();
         self.runtime.spawn(async move {
@@ -182,6 +207,17 @@ impl AdminService {
                     ))
                 }
             },
+            (hyper::Method::GET, "/debug/consensus/equivocation_evidence") => {
+                let consensus_db = context.consensus_db.read().clone();
+                if let Some(consensus_db) = consensus_db {
+                    consensus::handle_dump_equivocation_evidence_request(req, consensus_db).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Consensus db is not available.",
+                    ))
+                }
+            },
             (hyper::Method::GET, "/debug/consensus/quorumstoredb") => {
                 let quorum_store_db = context.quorum_store_db.read().clone();
                 if let Some(quorum_store_db) = quorum_store_db {
@@ -193,6 +229,78 @@ impl AdminService {
                     ))
                 }
             },
+            (hyper::Method::GET, "/debug/consensus/quorumstore/batches") => {
+                let quorum_store_inspector = context.quorum_store_inspector.read().clone();
+                if let Some(quorum_store_inspector) = quorum_store_inspector {
+                    quorum_store::handle_list_batches_request(req, quorum_store_inspector).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Quorum store is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/consensus/quorumstore/batch_references") => {
+                let consensus_db = context.consensus_db.read().clone();
+                if let Some(consensus_db) = consensus_db {
+                    quorum_store::handle_batch_references_request(req, consensus_db).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Consensus db is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/consensus/quorumstore/quota") => {
+                let quorum_store_inspector = context.quorum_store_inspector.read().clone();
+                if let Some(quorum_store_inspector) = quorum_store_inspector {
+                    quorum_store::handle_quota_usage_request(req, quorum_store_inspector).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Quorum store is not available.",
+                    ))
+                }
+            },
+            // Unlike the read-only debug endpoints, this one deletes batches, so it is only
+            // served if authentication is configured.
+            (hyper::Method::POST, "/consensus/quorumstore/gc") => {
+                let quorum_store_inspector = context.quorum_store_inspector.read().clone();
+                if context.authentication_configs.is_empty() {
+                    Ok(reply_with_status(
+                        StatusCode::FORBIDDEN,
+                        format!(
+                            "{} endpoint is disabled when no authentication is configured.",
+                            req.uri().path()
+                        ),
+                    ))
+                } else if let Some(quorum_store_inspector) = quorum_store_inspector {
+                    quorum_store::handle_garbage_collect_batches_request(
+                        req,
+                        quorum_store_inspector,
+                    )
+                    .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Quorum store is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/consensus/dag") => {
+                let dag_inspector = context.dag_inspector.read().clone();
+                if let Some(dag_inspector) = dag_inspector {
+                    dag::handle_dag_snapshot_request(req, dag_inspector).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "DAG consensus is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/execution/conflicts") => {
+                execution::handle_conflict_reports_request(req).await
+            },
             (hyper::Method::GET, "/debug/consensus/block") => {
                 let consensus_db = context.consensus_db.read().clone();
                 let quorum_store_db = context.quorum_store_db.read().clone();
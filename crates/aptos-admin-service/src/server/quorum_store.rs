@@ -0,0 +1,93 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::server::utils::{get_query_pairs, parse_query_param, reply_with_json};
+use anyhow::Result;
+use aptos_consensus::{
+    persistent_liveness_storage::LivenessStorageDbs,
+    quorum_store::inspection::{find_batch_references, BatchFilter, QuorumStoreInspector},
+};
+use aptos_crypto::HashValue;
+use aptos_logger::info;
+use aptos_system_utils::utils::{reply_with_status, spawn_blocking};
+use hyper::{Body, Request, Response, StatusCode};
+use std::{borrow::Cow, collections::HashMap, sync::Arc};
+
+pub async fn handle_list_batches_request(
+    req: Request<Body>,
+    quorum_store_inspector: Arc<QuorumStoreInspector>,
+) -> hyper::Result<Response<Body>> {
+    let query_pairs = get_query_pairs(&req);
+    let filter = match parse_batch_filter(&query_pairs) {
+        Ok(filter) => filter,
+        Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err)),
+    };
+
+    info!("Listing quorum store batches ({filter:?}).");
+    reply_with_json(
+        "list quorum store batches",
+        spawn_blocking(move || quorum_store_inspector.list_batches(&filter)).await,
+    )
+}
+
+pub async fn handle_batch_references_request(
+    req: Request<Body>,
+    consensus_db: Arc<dyn LivenessStorageDbs>,
+) -> hyper::Result<Response<Body>> {
+    let query_pairs = get_query_pairs(&req);
+    let digest: HashValue = match parse_query_param(&query_pairs, "digest") {
+        Ok(Some(digest)) => digest,
+        Ok(None) => {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                "Missing digest parameter.",
+            ))
+        },
+        Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err)),
+    };
+
+    info!("Finding references to batch ({digest:?}).");
+    reply_with_json(
+        "find batch references",
+        spawn_blocking(move || {
+            let (_, _, blocks, _) = consensus_db.consensus_db().get_data()?;
+            Ok(find_batch_references(&blocks, &digest))
+        })
+        .await,
+    )
+}
+
+pub async fn handle_quota_usage_request(
+    _req: Request<Body>,
+    quorum_store_inspector: Arc<QuorumStoreInspector>,
+) -> hyper::Result<Response<Body>> {
+    info!("Reporting quorum store quota usage.");
+    reply_with_json(
+        "report quorum store quota usage",
+        spawn_blocking(move || quorum_store_inspector.get_quota_usage()).await,
+    )
+}
+
+pub async fn handle_garbage_collect_batches_request(
+    _req: Request<Body>,
+    quorum_store_inspector: Arc<QuorumStoreInspector>,
+) -> hyper::Result<Response<Body>> {
+    info!("Garbage collecting quorum store batches.");
+    reply_with_json(
+        "garbage collect quorum store batches",
+        spawn_blocking(move || quorum_store_inspector.garbage_collect()).await,
+    )
+}
+
+fn parse_batch_filter(
+    query_pairs: &HashMap<Cow<'_, str>, Cow<'_, str>>,
+) -> Result<BatchFilter, String> {
+    Ok(BatchFilter {
+        author: parse_query_param(query_pairs, "author")?,
+        expired: parse_query_param(query_pairs, "expired")?,
+        tracked: parse_query_param(query_pairs, "tracked")?,
+        min_bytes: parse_query_param(query_pairs, "min_bytes")?,
+        order: parse_query_param(query_pairs, "order")?.unwrap_or_default(),
+        limit: parse_query_param(query_pairs, "limit")?,
+    })
+}
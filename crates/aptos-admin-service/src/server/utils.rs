@@ -0,0 +1,53 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers shared by the request handlers of the admin service
+
+use anyhow::Result;
+use aptos_logger::info;
+use aptos_system_utils::utils::{reply_with, reply_with_status};
+use http::header::{HeaderValue, CONTENT_TYPE};
+use hyper::{Body, Request, Response, StatusCode};
+use serde::Serialize;
+use std::{borrow::Cow, collections::HashMap, str::FromStr};
+
+pub(super) fn get_query_pairs(req: &Request<Body>) -> HashMap<Cow<'_, str>, Cow<'_, str>> {
+    let query = req.uri().query().unwrap_or("");
+    url::form_urlencoded::parse(query.as_bytes()).collect()
+}
+
+pub(super) fn parse_query_param<T: FromStr>(
+    query_pairs: &HashMap<Cow<'_, str>, Cow<'_, str>>,
+    name: &str,
+) -> Result<Option<T>, String>
+where
+    T::Err: std::fmt::Display,
+{
+    match query_pairs.get(name) {
+        Some(val) => val
+            .parse()
+            .map(Some)
+            .map_err(|err| format!("Invalid {name}: {err}")),
+        None => Ok(None),
+    }
+}
+
+pub(super) fn reply_with_json<T: Serialize>(
+    action: &str,
+    result: Result<T>,
+) -> hyper::Result<Response<Body>> {
+    match result.and_then(|value| Ok(serde_json::to_string_pretty(&value)?)) {
+        Ok(json) => {
+            info!("Finished to {action}.");
+            let headers = vec![(CONTENT_TYPE, HeaderValue::from_static("application/json"))];
+            Ok(reply_with(headers, json))
+        },
+        Err(e) => {
+            info!("Failed to {action}: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
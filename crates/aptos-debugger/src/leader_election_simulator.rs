@@ -0,0 +1,108 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, Result};
+use aptos_config::config::{
+    RocksdbConfigs, StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS,
+    DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_consensus::util::leader_reputation_simulator::{
+    num_history_epochs, LeaderElectionHistory, LeaderElectionSimulator,
+};
+use aptos_db::AptosDB;
+use aptos_storage_interface::DbReader;
+use aptos_types::on_chain_config::{OnChainConsensusConfig, ProposerElectionType};
+use clap::Parser;
+use std::{path::PathBuf, sync::Arc};
+
+#[derive(Parser)]
+#[clap(
+    about = "Simulate leader election under alternative configs over historical NewBlockEvents."
+)]
+pub struct Command {
+    #[clap(long, value_parser)]
+    pub db_dir: PathBuf,
+
+    /// The first epoch to simulate.
+    #[clap(long)]
+    pub start_epoch: u64,
+
+    /// The last epoch to simulate. If None, will simulate up to the latest epoch.
+    #[clap(long)]
+    pub end_epoch: Option<u64>,
+
+    /// YAML files, each containing a `ProposerElectionType` to simulate.
+    #[clap(long = "config", value_parser, required = true)]
+    pub configs: Vec<PathBuf>,
+
+    /// The number of recent rounds that don't count into reputations.
+    #[clap(long, default_value_t = OnChainConsensusConfig::default().leader_reputation_exclude_round())]
+    pub exclude_round: u64,
+
+    /// Print the reports as JSON.
+    #[clap(long)]
+    pub json: bool,
+}
+
+impl Command {
+    pub async fn run(self) -> Result<()> {
+        let configs = self
+            .configs
+            .iter()
+            .map(|path| {
+                let config = std::fs::read_to_string(path)?;
+                let proposer_election_type: ProposerElectionType = serde_yaml::from_str(&config)?;
+                Ok((path.display().to_string(), proposer_election_type))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let aptos_db: Arc<dyn DbReader> = Arc::new(
+            AptosDB::open(
+                StorageDirPaths::from_path(&self.db_dir),
+                true, /* readonly */
+                NO_OP_STORAGE_PRUNER_CONFIG,
+                RocksdbConfigs::default(),
+                false, /* indexer */
+                BUFFERED_STATE_TARGET_ITEMS,
+                DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+                None,
+            )
+            .map_err(anyhow::Error::from)?,
+        );
+        let end_epoch = match self.end_epoch {
+            Some(end_epoch) => end_epoch,
+            None => aptos_db
+                .get_latest_ledger_info()?
+                .ledger_info()
+                .next_block_epoch(),
+        };
+        ensure!(
+            self.start_epoch >= 1 && self.start_epoch <= end_epoch,
+            "Invalid epoch range [{}, {}]",
+            self.start_epoch,
+            end_epoch
+        );
+
+        // Leader reputation uses history from previous epochs, so load those as well
+        let num_history_epochs = configs
+            .iter()
+            .map(|(_, proposer_election_type)| num_history_epochs(proposer_election_type))
+            .max()
+            .unwrap_or(0);
+        let first_epoch = std::cmp::max(1, self.start_epoch.saturating_sub(num_history_epochs));
+        let history = LeaderElectionHistory::load(aptos_db.as_ref(), first_epoch, end_epoch)?;
+
+        let simulator = LeaderElectionSimulator::new(history, self.exclude_round, Some(aptos_db));
+        for (name, proposer_election_type) in configs {
+            let report =
+                simulator.simulate(name, &proposer_election_type, self.start_epoch..=end_epoch)?;
+            if self.json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                println!("{}", report);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -4,6 +4,8 @@
 use anyhow::Result;
 use clap::Parser;
 
+pub mod leader_election_simulator;
+
 #[derive(Parser)]
 pub enum Cmd {
     #[clap(subcommand)]
@@ -15,6 +17,10 @@ pub enum Cmd {
 
     #[clap(subcommand)]
     Move(aptos_move_debugger::common::Command),
+
+    ReplayConsensusFlightRecording(aptos_consensus::flight_recorder::replay::Command),
+
+    SimulateLeaderElection(leader_election_simulator::Command),
 }
 
 impl Cmd {
@@ -24,6 +30,8 @@ impl Cmd {
             Cmd::Decode(cmd) => cmd.run().await,
             Cmd::DumpPendingTxns(cmd) => cmd.run().await,
             Cmd::Move(cmd) => cmd.run().await,
+            Cmd::ReplayConsensusFlightRecording(cmd) => cmd.run().await,
+            Cmd::SimulateLeaderElection(cmd) => cmd.run().await,
         }
     }
 }
@@ -5,6 +5,11 @@ use crate::common::types::{CliError, CliTypedResult};
 use aptos_crypto::HashValue;
 use aptos_gas_profiling::FrameName;
 use aptos_move_debugger::aptos_debugger::AptosDebugger;
+#[cfg(feature = "debugging")]
+use aptos_move_debugger::{
+    move_debugger::{Breakpoint, ConsoleFrontend, DapFrontend, DebugFrontend, SourceResolver},
+    move_profiler::ExecutionProfile,
+};
 use aptos_types::transaction::SignedTransaction;
 use aptos_vm::{data_cache::AsMoveResolver, AptosVM};
 use aptos_vm_logging::log_schema::AdapterLogSchema;
@@ -133,3 +138,100 @@ pub fn profile_transaction_using_debugger(
 
     Ok((vm_status, vm_output))
 }
+
+#[cfg(feature = "debugging")]
+pub fn profile_execution_using_debugger(
+    debugger: &AptosDebugger,
+    version: u64,
+    transaction: SignedTransaction,
+    hash: HashValue,
+) -> CliTypedResult<(VMStatus, VMOutput)> {
+    let (vm_status, vm_output, profile) =
+        debugger.profile_transaction_at_version(version, transaction);
+    save_execution_profile(
+        &profile,
+        &format!("txn-{}", hash),
+        &mut debugger.source_resolver_at_version(version),
+    )?;
+    Ok((vm_status, vm_output))
+}
+
+#[cfg(feature = "debugging")]
+/// Saves the profile as collapsed stacks in the `execution-profiling` directory, and prints the
+/// locations with the most time spent.
+pub fn save_execution_profile(
+    profile: &ExecutionProfile,
+    name: &str,
+    sources: &mut SourceResolver,
+) -> CliTypedResult<()> {
+    if profile.is_empty() {
+        println!("No Move code was executed, the execution profile is empty.");
+        return Ok(());
+    }
+    println!();
+    println!("{}", profile.hottest_locations(sources, 20));
+
+    let dir = Path::new("execution-profiling");
+    let paths = profile
+        .write_collapsed_stacks(dir, name, sources)
+        .map_err(|err| {
+            CliError::UnexpectedError(format!("Failed to save the execution profile: {}", err))
+        })?;
+    for path in paths {
+        println!("Execution profile saved to {}.", path.display());
+    }
+    Ok(())
+}
+
+pub fn trace_transaction_using_debugger(
+    debugger: &AptosDebugger,
+    version: u64,
+    transaction: SignedTransaction,
+    hash: HashValue,
+) -> CliTypedResult<(VMStatus, VMOutput)> {
+    let (vm_status, vm_output, trace) = debugger
+        .execute_transaction_at_version_with_tracer(version, transaction)
+        .map_err(|err| {
+            CliError::UnexpectedError(format!("failed to simulate txn with tracer: {}", err))
+        })?;
+
+    let dir = Path::new("execution-traces");
+    std::fs::create_dir_all(dir).map_err(|err| CliError::IO(dir.display().to_string(), err))?;
+    let path = dir.join(format!("txn-{}.json", hash));
+    let json = serde_json::to_string_pretty(&trace)
+        .map_err(|err| CliError::UnexpectedError(format!("failed to serialize trace: {}", err)))?;
+    std::fs::write(&path, json).map_err(|err| CliError::IO(path.display().to_string(), err))?;
+
+    println!("Execution trace saved to {}.", path.display());
+
+    Ok((vm_status, vm_output))
+}
+
+#[cfg(feature = "debugging")]
+/// Steps through the transaction with the Move debugger, on the command line or, if a port is
+/// given, with an editor attached over the Debug Adapter Protocol.
+pub fn debug_transaction_using_debugger(
+    debugger: &AptosDebugger,
+    version: u64,
+    transaction: SignedTransaction,
+    debug_adapter_port: Option<u16>,
+    breakpoints: Vec<Breakpoint>,
+    stop_on_entry: bool,
+) -> CliTypedResult<(VMStatus, VMOutput)> {
+    let frontend: Box<dyn DebugFrontend> = match debug_adapter_port {
+        Some(port) => Box::new(DapFrontend::listen(port).map_err(|err| {
+            CliError::UnexpectedError(format!("Failed to start the debug adapter: {}", err))
+        })?),
+        None => Box::new(ConsoleFrontend::new()),
+    };
+    debugger
+        .debug_transaction_at_version(
+            version,
+            transaction,
+            frontend,
+            breakpoints,
+            stop_on_entry,
+            true,
+        )
+        .map_err(|err| CliError::UnexpectedError(format!("Failed to debug txn: {}", err)))
+}
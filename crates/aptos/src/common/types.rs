@@ -1541,6 +1541,15 @@ pub struct TransactionOptions {
     /// flamegraphs that reflect the gas usage.
     #[clap(long)]
     pub(crate) profile_gas: bool,
+
+    /// If this option is set, simulate the transaction locally with the Move profiler, which
+    /// attributes the wall time and the instructions executed to Move functions and source lines,
+    /// and save the profile as collapsed stacks.
+    ///
+    /// Not to be confused with `--profile`, which selects the CLI config profile.
+    #[cfg(feature = "debugging")]
+    #[clap(long)]
+    pub(crate) profile_execution: bool,
 }
 
 impl TransactionOptions {
@@ -1893,6 +1902,44 @@ impl TransactionOptions {
         .await
     }
 
+    /// Whether the transaction is simulated with the Move profiler
+    #[cfg(feature = "debugging")]
+    pub fn profile_execution_requested(&self) -> bool {
+        self.profile_execution
+    }
+
+    /// The Move profiler hooks into the interpreter, which is only compiled in with the
+    /// `debugging` feature, enabled by default (and `--profile-execution` is not available
+    /// without it)
+    #[cfg(not(feature = "debugging"))]
+    pub fn profile_execution_requested(&self) -> bool {
+        false
+    }
+
+    /// Simulates the transaction locally with the Move profiler enabled.
+    #[cfg(feature = "debugging")]
+    pub async fn profile_execution(
+        &self,
+        payload: TransactionPayload,
+    ) -> CliTypedResult<TransactionSummary> {
+        println!();
+        println!("Simulating transaction locally using the Move profiler...");
+
+        self.simulate_using_debugger(payload, local_simulation::profile_execution_using_debugger)
+            .await
+    }
+
+    #[cfg(not(feature = "debugging"))]
+    pub async fn profile_execution(
+        &self,
+        _payload: TransactionPayload,
+    ) -> CliTypedResult<TransactionSummary> {
+        Err(CliError::UnexpectedError(
+            "The Move profiler requires the CLI to be built with the `debugging` feature"
+                .to_string(),
+        ))
+    }
+
     pub async fn estimate_gas_price(&self) -> CliTypedResult<u64> {
         let client = self.rest_client()?;
         client
@@ -32,6 +32,11 @@ use aptos_framework::{
 };
 use aptos_gas_schedule::{MiscGasParameters, NativeGasParameters};
 use aptos_move_debugger::aptos_debugger::AptosDebugger;
+#[cfg(feature = "debugging")]
+use aptos_move_debugger::{
+    move_debugger::{Breakpoint, ModuleSource, SourceResolver},
+    move_profiler,
+};
 use aptos_rest_client::{
     aptos_api_types::{EntryFunctionId, HexEncodedBytes, IdentifierWrapper, MoveModuleId},
     Client,
@@ -40,15 +45,22 @@ use aptos_types::{
     account_address::{create_resource_address, AccountAddress},
     object_address::create_object_code_deployment_address,
     on_chain_config::aptos_test_feature_flags_genesis,
-    transaction::{Transaction, TransactionArgument, TransactionPayload, TransactionStatus},
+    transaction::{
+        SignedTransaction, Transaction, TransactionArgument, TransactionPayload, TransactionStatus,
+    },
 };
 use aptos_vm::data_cache::AsMoveResolver;
+use aptos_vm_types::output::VMOutput;
 use async_trait::async_trait;
 use clap::{Parser, Subcommand, ValueEnum};
 use itertools::Itertools;
 use move_cli::{self, base::test::UnitTestResult};
 use move_command_line_common::env::MOVE_HOME;
-use move_core_types::{identifier::Identifier, language_storage::ModuleId, u256::U256};
+#[cfg(feature = "debugging")]
+use move_compiler::compiled_unit::{CompiledUnit, NamedCompiledModule};
+use move_core_types::{
+    identifier::Identifier, language_storage::ModuleId, u256::U256, vm_status::VMStatus,
+};
 use move_model::metadata::{CompilerVersion, LanguageVersion};
 use move_package::{
     source_package::{layout::SourcePackageLayout, std_lib::StdVersion},
@@ -518,6 +530,15 @@ pub struct TestPackage {
     /// Dump storage state on failure.
     #[clap(long = "dump")]
     pub dump_state: bool,
+
+    /// Profile the Move code executed by the tests, and save the wall time and the instructions
+    /// by Move call stack and source line as collapsed stacks
+    ///
+    /// Named like the flag of the commands submitting transactions, where `--profile` selects
+    /// the CLI config profile.
+    #[cfg(feature = "debugging")]
+    #[clap(long)]
+    pub profile_execution: bool,
 }
 
 #[async_trait]
@@ -546,27 +567,45 @@ impl CliCommand<&'static str> for TestPackage {
         };
 
         let path = self.move_options.get_package_path()?;
-        let result = move_cli::base::test::run_move_unit_tests(
-            path.as_path(),
-            config.clone(),
-            UnitTestingConfig {
-                filter: self.filter.clone(),
-                report_stacktrace_on_abort: true,
-                report_storage_on_error: self.dump_state,
-                ignore_compile_warnings: self.ignore_compile_warnings,
-                ..UnitTestingConfig::default_with_bound(None)
-            },
-            // TODO(Gas): we may want to switch to non-zero costs in the future
-            aptos_debug_natives::aptos_debug_natives(
-                NativeGasParameters::zeros(),
-                MiscGasParameters::zeros(),
-            ),
-            aptos_test_feature_flags_genesis(),
-            None,
-            self.compute_coverage,
-            &mut std::io::stdout(),
-        )
-        .map_err(|err| CliError::UnexpectedError(format!("Failed to run tests: {:#}", err)))?;
+        let run_tests = || {
+            move_cli::base::test::run_move_unit_tests(
+                path.as_path(),
+                config.clone(),
+                UnitTestingConfig {
+                    filter: self.filter.clone(),
+                    report_stacktrace_on_abort: true,
+                    report_storage_on_error: self.dump_state,
+                    ignore_compile_warnings: self.ignore_compile_warnings,
+                    ..UnitTestingConfig::default_with_bound(None)
+                },
+                // TODO(Gas): we may want to switch to non-zero costs in the future
+                aptos_debug_natives::aptos_debug_natives(
+                    NativeGasParameters::zeros(),
+                    MiscGasParameters::zeros(),
+                ),
+                aptos_test_feature_flags_genesis(),
+                None,
+                self.compute_coverage,
+                &mut std::io::stdout(),
+            )
+        };
+        #[cfg(feature = "debugging")]
+        let (result, profile) = if self.profile_execution {
+            let (result, profile) = move_profiler::profile(run_tests);
+            (result, Some(profile))
+        } else {
+            (run_tests(), None)
+        };
+        #[cfg(not(feature = "debugging"))]
+        let result = run_tests();
+        let result = result
+            .map_err(|err| CliError::UnexpectedError(format!("Failed to run tests: {:#}", err)))?;
+
+        #[cfg(feature = "debugging")]
+        if let Some(profile) = profile {
+            let mut sources = package_sources(path.as_path(), config.clone())?;
+            local_simulation::save_execution_profile(&profile, "tests", &mut sources)?;
+        }
 
         // Print coverage summary if --coverage is set
         if self.compute_coverage {
@@ -590,6 +629,34 @@ impl CliCommand<&'static str> for TestPackage {
     }
 }
 
+/// Compiles the package with the config, and returns the sources and source maps of its modules
+/// and the modules of its dependencies
+#[cfg(feature = "debugging")]
+fn package_sources(path: &Path, config: BuildConfig) -> CliTypedResult<SourceResolver> {
+    let package = config
+        .compile_package(path, &mut Vec::new())
+        .map_err(|e| CliError::MoveCompilationError(format!("{:#}", e)))?;
+    let mut sources = SourceResolver::new(Box::new(|_| Ok(None)));
+    let units = package
+        .root_compiled_units
+        .iter()
+        .chain(package.deps_compiled_units.iter().map(|(_, unit)| unit));
+    for unit in units {
+        if let CompiledUnit::Module(NamedCompiledModule {
+            module, source_map, ..
+        }) = &unit.unit
+        {
+            let source = std::fs::read_to_string(&unit.source_path).ok();
+            sources.insert(ModuleSource::new(
+                module.self_id(),
+                source,
+                Some(source_map.clone()),
+            ));
+        }
+    }
+    Ok(sources)
+}
+
 /// Proves a Move package
 ///
 /// This is a tool for formal verification of a Move package using
@@ -677,6 +744,7 @@ impl CliCommand<&'static str> for DocumentPackage {
             language_version: move_options.language_version,
             skip_attribute_checks: move_options.skip_attribute_checks,
             check_test_code: move_options.check_test_code,
+            with_resource_access: false,
             known_attributes: extended_checks::get_all_attribute_names().clone(),
             experiments: vec![],
         };
@@ -1778,9 +1846,40 @@ pub struct Replay {
     #[clap(long)]
     pub(crate) profile_gas: bool,
 
+    /// If this option is set, record the execution trace of the transaction and save it as JSON:
+    /// the call tree with the arguments and return values of every call, and the events emitted
+    /// and the resources accessed by each call.
+    #[clap(long)]
+    pub(crate) trace: bool,
+
     /// If present, skip the comparison against the expected transaction output.
     #[clap(long)]
     pub(crate) skip_comparison: bool,
+
+    /// If this option is set, step through the transaction with the Move debugger.
+    ///
+    /// The debugger stops on entry of the entry function, on breakpoints and before aborts.
+    /// Sources are shown if the packages were published with their sources and source maps.
+    #[cfg(feature = "debugging")]
+    #[clap(long)]
+    pub(crate) debug: bool,
+
+    /// Serve the Move debugger over the Debug Adapter Protocol on this port, for editors to
+    /// attach to, instead of on the command line. Implies `--debug`.
+    #[cfg(feature = "debugging")]
+    #[clap(long)]
+    pub(crate) debug_adapter_port: Option<u16>,
+
+    /// Breakpoints of the Move debugger, in the form
+    /// `<address>::<module>::<function>[+<bytecode offset>]` or `<file>.move:<line>`
+    #[cfg(feature = "debugging")]
+    #[clap(long = "break", value_parser = Breakpoint::from_str)]
+    pub(crate) breakpoints: Vec<Breakpoint>,
+
+    /// If present, the Move debugger does not stop on entry of the entry function.
+    #[cfg(feature = "debugging")]
+    #[clap(long)]
+    pub(crate) no_stop_on_entry: bool,
 }
 
 impl FromStr for ReplayNetworkSelection {
@@ -1796,6 +1895,48 @@ impl FromStr for ReplayNetworkSelection {
     }
 }
 
+impl Replay {
+    #[cfg(feature = "debugging")]
+    fn debug_requested(&self) -> bool {
+        self.debug || self.debug_adapter_port.is_some()
+    }
+
+    #[cfg(not(feature = "debugging"))]
+    fn debug_requested(&self) -> bool {
+        false
+    }
+
+    #[cfg(feature = "debugging")]
+    fn debug_transaction(
+        &self,
+        debugger: &AptosDebugger,
+        txn: SignedTransaction,
+    ) -> CliTypedResult<(VMStatus, VMOutput)> {
+        local_simulation::debug_transaction_using_debugger(
+            debugger,
+            self.txn_id,
+            txn,
+            self.debug_adapter_port,
+            self.breakpoints.clone(),
+            !self.no_stop_on_entry,
+        )
+    }
+
+    /// The Move debugger hooks into the interpreter, which is only compiled in with the
+    /// `debugging` feature (and the debug options are not available without it)
+    #[cfg(not(feature = "debugging"))]
+    fn debug_transaction(
+        &self,
+        _debugger: &AptosDebugger,
+        _txn: SignedTransaction,
+    ) -> CliTypedResult<(VMStatus, VMOutput)> {
+        Err(CliError::UnexpectedError(
+            "The Move debugger requires the CLI to be built with the `debugging` feature"
+                .to_string(),
+        ))
+    }
+}
+
 #[async_trait]
 impl CliCommand<TransactionSummary> for Replay {
     fn command_name(&self) -> &'static str {
@@ -1810,6 +1951,17 @@ impl CliCommand<TransactionSummary> for Replay {
                 "Cannot perform benchmarking and gas profiling at the same time.".to_string(),
             ));
         }
+        let debug = self.debug_requested();
+        if debug && (self.profile_gas || self.benchmark) {
+            return Err(CliError::UnexpectedError(
+                "Cannot debug while benchmarking or profiling gas.".to_string(),
+            ));
+        }
+        if self.trace && (debug || self.profile_gas || self.benchmark) {
+            return Err(CliError::UnexpectedError(
+                "Cannot trace while debugging, benchmarking or profiling gas.".to_string(),
+            ));
+        }
 
         let rest_endpoint = match &self.network {
             Mainnet => "https://fullnode.mainnet.aptoslabs.com",
@@ -1849,6 +2001,17 @@ impl CliCommand<TransactionSummary> for Replay {
                 txn.clone(),
                 hash,
             )?
+        } else if debug {
+            println!("Debugging transaction...");
+            self.debug_transaction(&debugger, txn.clone())?
+        } else if self.trace {
+            println!("Tracing transaction...");
+            local_simulation::trace_transaction_using_debugger(
+                &debugger,
+                self.txn_id,
+                txn.clone(),
+                hash,
+            )?
         } else if self.benchmark {
             println!("Benchmarking transaction...");
             local_simulation::benchmark_transaction_using_debugger(
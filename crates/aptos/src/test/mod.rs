@@ -862,6 +862,8 @@ impl CliTestFramework {
             ignore_compile_warnings: false,
             compute_coverage: false,
             dump_state: false,
+            #[cfg(feature = "debugging")]
+            profile_execution: false,
         }
         .execute()
         .await
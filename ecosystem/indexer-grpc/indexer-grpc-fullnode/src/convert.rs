@@ -938,6 +938,21 @@ fn convert_validator_transaction(
                     )
                 )
             },
+            ApiValidatorTransactionEnum::EquivocationReport(equivocation_report) => {
+                let report = &equivocation_report.equivocation_report;
+                Some(
+                    validator_transaction::ValidatorTransactionType::EquivocationReport(
+                        validator_transaction::EquivocationReport {
+                            epoch: report.epoch.0,
+                            round: report.round.0,
+                            kind: report.kind.clone(),
+                            offender: report.offender.to_string(),
+                            reporter: report.reporter.to_string(),
+                            evidence: report.evidence.0.clone(),
+                        },
+                    )
+                )
+            },
         },
         events: convert_events(api_validator_txn.events()),
     })
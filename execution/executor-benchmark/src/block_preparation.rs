@@ -6,10 +6,12 @@ use aptos_block_partitioner::{BlockPartitioner, PartitionerConfig};
 use aptos_crypto::HashValue;
 use aptos_experimental_runtimes::thread_manager::optimal_min_len;
 use aptos_logger::info;
+use aptos_storage_interface::{state_view::LatestDbStateCheckpointView, DbReader};
 use aptos_types::{
     block_executor::partitioner::{ExecutableBlock, ExecutableTransactions},
     transaction::{signature_verified_transaction::SignatureVerifiedTransaction, Transaction},
 };
+use aptos_vm::resource_access_hints::analyze_transaction;
 use once_cell::sync::Lazy;
 use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
 use std::{sync::Arc, time::Instant};
@@ -28,10 +30,16 @@ pub(crate) struct BlockPreparationStage {
     num_executor_shards: usize,
     num_blocks_processed: usize,
     maybe_partitioner: Option<Box<dyn BlockPartitioner>>,
+    // To read the resource access metadata of the called modules for the partitioner hints.
+    db_reader: Arc<dyn DbReader>,
 }
 
 impl BlockPreparationStage {
-    pub fn new(num_shards: usize, partitioner_config: &dyn PartitionerConfig) -> Self {
+    pub fn new(
+        num_shards: usize,
+        partitioner_config: &dyn PartitionerConfig,
+        db_reader: Arc<dyn DbReader>,
+    ) -> Self {
         let maybe_partitioner = if num_shards == 0 {
             None
         } else {
@@ -43,6 +51,7 @@ impl BlockPreparationStage {
             num_executor_shards: num_shards,
             num_blocks_processed: 0,
             maybe_partitioner,
+            db_reader,
         }
     }
 
@@ -64,8 +73,11 @@ impl BlockPreparationStage {
         let block: ExecutableBlock = match &self.maybe_partitioner {
             None => (block_id, sig_verified_txns).into(),
             Some(partitioner) => {
-                let analyzed_transactions =
-                    sig_verified_txns.into_iter().map(|t| t.into()).collect();
+                let state_view = self.db_reader.latest_state_checkpoint_view().unwrap();
+                let analyzed_transactions = sig_verified_txns
+                    .into_iter()
+                    .map(|t| analyze_transaction(t, &state_view))
+                    .collect();
                 let timer = TIMER.with_label_values(&["partition"]).start_timer();
                 let partitioned_txns =
                     partitioner.partition(analyzed_transactions, self.num_executor_shards);
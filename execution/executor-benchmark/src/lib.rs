@@ -517,6 +517,9 @@ struct ExecutionTimeMeasurement {
     partitioning_total: f64,
     execution_total: f64,
     vm_only: f64,
+    prefetch: f64,
+    predicted_keys: f64,
+    prefetched_keys: f64,
     by_other: HashMap<&'static str, f64>,
     ledger_update_total: f64,
     commit_total: f64,
@@ -533,6 +536,15 @@ impl ExecutionTimeMeasurement {
         let partitioning_total = BLOCK_PARTITIONING_SECONDS.get_sample_sum();
         let execution_total = APTOS_EXECUTOR_EXECUTE_BLOCK_SECONDS.get_sample_sum();
         let vm_only = APTOS_EXECUTOR_VM_EXECUTE_BLOCK_SECONDS.get_sample_sum();
+        let prefetch = block_executor_counters::READ_SET_PREFETCH_SECONDS
+            .with_label_values(&["prefetch"])
+            .get_sample_sum();
+        let predicted_keys = block_executor_counters::READ_SET_PREFETCH_KEYS
+            .with_label_values(&["predicted"])
+            .get_sample_sum();
+        let prefetched_keys = block_executor_counters::READ_SET_PREFETCH_KEYS
+            .with_label_values(&["prefetched"])
+            .get_sample_sum();
 
         let by_other = OTHER_LABELS
             .iter()
@@ -555,6 +567,9 @@ impl ExecutionTimeMeasurement {
             partitioning_total,
             execution_total,
             vm_only,
+            prefetch,
+            predicted_keys,
+            prefetched_keys,
             by_other,
             ledger_update_total,
             commit_total,
@@ -570,6 +585,9 @@ impl ExecutionTimeMeasurement {
             partitioning_total: end.partitioning_total - self.partitioning_total,
             execution_total: end.execution_total - self.execution_total,
             vm_only: end.vm_only - self.vm_only,
+            prefetch: end.prefetch - self.prefetch,
+            predicted_keys: end.predicted_keys - self.predicted_keys,
+            prefetched_keys: end.prefetched_keys - self.prefetched_keys,
             by_other: end
                 .by_other
                 .into_iter()
@@ -674,6 +692,16 @@ impl OverallMeasuring {
             delta_execution.vm_only / delta_execution.execution_total,
             num_txns / delta_execution.vm_only
         );
+        if delta_execution.predicted_keys > 0.0 {
+            info!(
+                "{} fraction of execution {:.3} overlapped with read set prefetch ({} keys/txn, \
+                fraction {:.3} prefetched before the end of execution)",
+                prefix,
+                delta_execution.prefetch / delta_execution.execution_total,
+                delta_execution.predicted_keys / num_txns,
+                delta_execution.prefetched_keys / delta_execution.predicted_keys
+            );
+        }
         for (prefix, top_level, other_label) in OTHER_LABELS {
             let time_in_label = delta_execution.by_other.get(other_label).unwrap();
             if *top_level || time_in_label / delta_execution.execution_total > 0.01 {
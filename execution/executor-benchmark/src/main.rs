@@ -15,7 +15,7 @@ use aptos_config::config::{
 };
 use aptos_executor::block_executor::TransactionBlockExecutor;
 use aptos_executor_benchmark::{native_executor::NativeExecutor, pipeline::PipelineConfig};
-use aptos_executor_service::remote_executor_client;
+use aptos_executor_service::remote_executor_client::{self, ShardFailureConfig};
 use aptos_experimental_ptx_executor::PtxBlockExecutor;
 #[cfg(target_os = "linux")]
 use aptos_experimental_runtimes::thread_manager::{ThreadConfigStrategy, ThreadManagerBuilder};
@@ -33,7 +33,7 @@ use once_cell::sync::Lazy;
 use std::{
     net::SocketAddr,
     path::PathBuf,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 #[cfg(unix)]
@@ -148,6 +148,16 @@ struct ShardingOpt {
     remote_executor_addresses: Option<Vec<SocketAddr>>,
     #[clap(long)]
     coordinator_address: Option<SocketAddr>,
+    /// Milliseconds the coordinator waits for the results of the remote shards before it
+    /// considers them failed. Waits forever if not specified.
+    #[clap(long)]
+    remote_shard_timeout_ms: Option<u64>,
+    /// Number of times a block is sent to the remote shards again after they failed to execute it.
+    #[clap(long, default_value = "0")]
+    remote_shard_max_retries: usize,
+    /// Executes the blocks on the coordinator once the remote shards failed, instead of failing.
+    #[clap(long)]
+    execute_locally_on_shard_failure: bool,
     #[clap(long, default_value = "4")]
     max_partitioning_rounds: usize,
     #[clap(long, default_value = "0.90")]
@@ -266,6 +276,11 @@ struct Opt {
 
     #[clap(long)]
     skip_paranoid_checks: bool,
+
+    /// Prefetch the state that the transactions of each block are predicted to read, before
+    /// executing the block. The prefetch time is exported as a metric.
+    #[clap(long)]
+    prefetch_read_sets: bool,
 }
 
 impl Opt {
@@ -555,6 +570,18 @@ fn main() {
         remote_executor_client::set_coordinator_address(
             opt.pipeline_opt.sharding_opt.coordinator_address.unwrap(),
         );
+        remote_executor_client::set_shard_failure_config(ShardFailureConfig {
+            result_timeout: opt
+                .pipeline_opt
+                .sharding_opt
+                .remote_shard_timeout_ms
+                .map(Duration::from_millis),
+            max_retries: opt.pipeline_opt.sharding_opt.remote_shard_max_retries,
+            execute_locally_on_failure: opt
+                .pipeline_opt
+                .sharding_opt
+                .execute_locally_on_shard_failure,
+        });
         // it does not matter because shards are on remote node, but for sake of correctness lets
         // set it
         execution_threads_per_shard = execution_threads;
@@ -567,6 +594,7 @@ fn main() {
     AptosVM::set_concurrency_level_once(execution_threads_per_shard);
     NativeExecutor::set_concurrency_level_once(execution_threads_per_shard);
     AptosVM::set_processed_transactions_detailed_counters();
+    AptosVM::set_prefetch_read_sets(opt.prefetch_read_sets);
 
     let config = ProfilerConfig::new_with_defaults();
     let handler = ProfilerHandler::new(config);
@@ -110,8 +110,11 @@ where
 
         let mut join_handles = vec![];
 
-        let mut partitioning_stage =
-            BlockPreparationStage::new(num_partitioner_shards, &config.partitioner_config);
+        let mut partitioning_stage = BlockPreparationStage::new(
+            num_partitioner_shards,
+            &config.partitioner_config,
+            executor_1.db.reader.clone(),
+        );
 
         let mut exe = TransactionExecutor::new(
             executor_1,
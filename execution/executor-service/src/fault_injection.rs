@@ -0,0 +1,153 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Fault injection for remote executor shards, to test how the coordinator and the other shards
+//! handle failures of a shard. Faults are only injected in tests, and in builds with the `testing`
+//! feature (which also exposes the fault injection flags of the executor service binary).
+
+use aptos_logger::warn;
+use clap::Parser;
+use std::{
+    process,
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    thread,
+    time::Duration,
+};
+
+/// The exit code of a shard process which crashes because of an injected fault.
+pub const INJECTED_CRASH_EXIT_CODE: i32 = 17;
+
+/// Faults to inject into a remote executor shard.
+#[derive(Clone, Debug, Default, Parser)]
+pub struct FaultInjectionConfig {
+    /// Crashes the shard when it receives the block with the given index (counting from 0),
+    /// before executing it.
+    #[clap(long)]
+    pub crash_at_block: Option<usize>,
+
+    /// Delays every cross-shard message sent by the shard by the given number of milliseconds.
+    #[clap(long, default_value_t = 0)]
+    pub cross_shard_message_delay_ms: u64,
+
+    /// Fraction of the state-view responses received by the shard which are dropped, spread
+    /// evenly over the responses.
+    #[clap(long, default_value_t = 0.0)]
+    pub kv_response_drop_rate: f64,
+}
+
+impl FaultInjectionConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.crash_at_block.is_some()
+            || self.cross_shard_message_delay_ms > 0
+            || self.kv_response_drop_rate > 0.0
+    }
+}
+
+pub struct FaultInjector {
+    config: FaultInjectionConfig,
+    /// Whether an injected crash exits the process. Otherwise, only the executor service of the
+    /// shard stops, which is used for shards running in threads of the test process.
+    exit_on_crash: bool,
+    num_blocks: AtomicUsize,
+    num_kv_responses: AtomicU64,
+}
+
+impl FaultInjector {
+    pub fn new(config: FaultInjectionConfig, exit_on_crash: bool) -> Self {
+        let config = if cfg!(any(test, feature = "testing")) {
+            config
+        } else {
+            if config.is_enabled() {
+                warn!(
+                    "Fault injection requires the testing feature! Ignoring: {:?}",
+                    config
+                );
+            }
+            FaultInjectionConfig::default()
+        };
+        if config.is_enabled() {
+            warn!(
+                "Injecting faults into the remote executor shard: {:?}",
+                config
+            );
+        }
+        Self {
+            config,
+            exit_on_crash,
+            num_blocks: AtomicUsize::new(0),
+            num_kv_responses: AtomicU64::new(0),
+        }
+    }
+
+    /// Called when the shard receives a block. Returns whether the shard crashes, and exits the
+    /// process if configured so.
+    pub fn crash_on_block(&self) -> bool {
+        let block_idx = self.num_blocks.fetch_add(1, Ordering::Relaxed);
+        if self.config.crash_at_block != Some(block_idx) {
+            return false;
+        }
+        warn!("Injected crash of the shard at block {}", block_idx);
+        if self.exit_on_crash {
+            process::exit(INJECTED_CRASH_EXIT_CODE);
+        }
+        true
+    }
+
+    /// Called before the shard sends a cross-shard message. The message is delayed in the
+    /// sending thread, so that the order of the messages is preserved.
+    pub fn delay_cross_shard_message(&self) {
+        if self.config.cross_shard_message_delay_ms > 0 {
+            thread::sleep(Duration::from_millis(
+                self.config.cross_shard_message_delay_ms,
+            ));
+        }
+    }
+
+    /// Called when the shard receives a state-view response. Returns whether it is dropped.
+    pub fn drop_kv_response(&self) -> bool {
+        let rate = self.config.kv_response_drop_rate;
+        if rate <= 0.0 {
+            return false;
+        }
+        // Drops a response whenever the expected number of dropped responses reaches the next
+        // integer.
+        let idx = self.num_kv_responses.fetch_add(1, Ordering::Relaxed) as f64;
+        ((idx + 1.0) * rate).floor() > (idx * rate).floor()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_kv_responses_evenly() {
+        let injector = FaultInjector::new(
+            FaultInjectionConfig {
+                kv_response_drop_rate: 0.25,
+                ..FaultInjectionConfig::default()
+            },
+            false,
+        );
+        let dropped = (0..8)
+            .map(|_| injector.drop_kv_response())
+            .collect::<Vec<_>>();
+        assert_eq!(dropped, vec![
+            false, false, false, true, false, false, false, true
+        ]);
+    }
+
+    #[test]
+    fn crashes_at_block() {
+        let injector = FaultInjector::new(
+            FaultInjectionConfig {
+                crash_at_block: Some(1),
+                ..FaultInjectionConfig::default()
+            },
+            false,
+        );
+        assert!(!injector.crash_on_block());
+        assert!(injector.crash_on_block());
+        assert!(!injector.crash_on_block());
+    }
+}
@@ -10,8 +10,10 @@ use aptos_types::{
     vm_status::VMStatus,
 };
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 mod error;
+pub mod fault_injection;
 pub mod local_executor_helper;
 mod metrics;
 pub mod process_executor_service;
@@ -21,8 +23,8 @@ pub mod remote_executor_client;
 pub mod remote_executor_service;
 mod remote_state_view;
 mod remote_state_view_service;
-#[cfg(test)]
-mod test_utils;
+#[cfg(any(test, feature = "testing"))]
+pub mod test_utils;
 #[cfg(test)]
 mod tests;
 #[cfg(test)]
@@ -30,12 +32,17 @@ mod thread_executor_service;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct RemoteExecutionResult {
+    /// The id of the [ExecuteBlockCommand] this is the result of.
+    pub execution_id: u64,
     pub inner: Result<Vec<Vec<TransactionOutput>>, VMStatus>,
 }
 
 impl RemoteExecutionResult {
-    pub fn new(inner: Result<Vec<Vec<TransactionOutput>>, VMStatus>) -> Self {
-        Self { inner }
+    pub fn new(execution_id: u64, inner: Result<Vec<Vec<TransactionOutput>>, VMStatus>) -> Self {
+        Self {
+            execution_id,
+            inner,
+        }
     }
 }
 
@@ -46,9 +53,16 @@ pub enum RemoteExecutionRequest {
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ExecuteBlockCommand {
+    /// Identifies the execution attempt, so that the coordinator can discard the results of
+    /// attempts it gave up on.
+    pub(crate) execution_id: u64,
     pub(crate) sub_blocks: SubBlocksForShard<AnalyzedTransaction>,
     pub(crate) concurrency_level: usize,
     pub(crate) onchain_config: BlockExecutorConfigFromOnchain,
+    /// The time after which the coordinator gives up on the execution, or `None` if it waits
+    /// forever. The shard then aborts the execution once the cross-shard messages it waits for
+    /// did not arrive within it, and drops the messages it can't deliver to crashed shards.
+    pub(crate) result_timeout: Option<Duration>,
 }
 
 impl ExecuteBlockCommand {
@@ -1,7 +1,9 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_executor_service::process_executor_service::ProcessExecutorService;
+use aptos_executor_service::{
+    fault_injection::FaultInjectionConfig, process_executor_service::ProcessExecutorService,
+};
 use aptos_logger::info;
 use clap::Parser;
 use std::net::SocketAddr;
@@ -22,6 +24,10 @@ struct Args {
 
     #[clap(long)]
     pub coordinator_address: SocketAddr,
+
+    #[cfg(feature = "testing")]
+    #[clap(flatten)]
+    pub fault_injection: FaultInjectionConfig,
 }
 
 fn main() {
@@ -34,12 +40,18 @@ fn main() {
     })
     .expect("Error setting Ctrl-C handler");
 
+    #[cfg(feature = "testing")]
+    let fault_injection = args.fault_injection;
+    #[cfg(not(feature = "testing"))]
+    let fault_injection = FaultInjectionConfig::default();
+
     let _exe_service = ProcessExecutorService::new(
         args.shard_id,
         args.num_shards,
         args.num_executor_threads,
         args.coordinator_address,
         args.remote_executor_addresses,
+        fault_injection,
     );
 
     rx.recv()
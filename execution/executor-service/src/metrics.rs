@@ -38,9 +38,25 @@ pub static REMOTE_EXECUTOR_REMOTE_KV_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
         "KV counts on a shard for: \
          1. kv_responses: the number of remote key value responses received on a shard; \
          2. non_prefetch_kv: the number of remote key value responses received on a shard that were not prefetched; \
-         3. prefetch_kv: the number of remote key value responses received on a shard that were prefetched; ",
+         3. prefetch_kv: the number of remote key value responses received on a shard that were prefetched; \
+         4. kv_retries: the number of state values requested again because they did not arrive in time; ",
         // metric labels (dimensions)
         &["shard_id", "name"],
     )
     .unwrap()
 });
+
+pub static REMOTE_EXECUTOR_SHARD_FAILURE_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        // metric name
+        "remote_executor_shard_failure_count",
+        // metric description
+        "The number of times the coordinator handled executor shards failing to execute a block by: \
+         1. retry: sending the block to the shards again; \
+         2. execute_locally: executing the block on the coordinator; \
+         3. fail_block: failing the block;",
+        // metric labels (dimensions)
+        &["name"],
+    )
+    .unwrap()
+});
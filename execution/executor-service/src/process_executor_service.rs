@@ -1,12 +1,15 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::remote_executor_service::ExecutorService;
+use crate::{
+    fault_injection::{FaultInjectionConfig, FaultInjector},
+    remote_executor_service::ExecutorService,
+};
 use aptos_logger::info;
 use aptos_push_metrics::MetricsPusher;
 use aptos_types::block_executor::partitioner::ShardId;
 use aptos_vm::AptosVM;
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::Arc};
 
 /// An implementation of the remote executor service that runs in a standalone process.
 pub struct ProcessExecutorService {
@@ -20,6 +23,7 @@ impl ProcessExecutorService {
         num_threads: usize,
         coordinator_address: SocketAddr,
         remote_shard_addresses: Vec<SocketAddr>,
+        fault_injection: FaultInjectionConfig,
     ) -> Self {
         let self_address = remote_shard_addresses[shard_id];
         info!(
@@ -39,6 +43,7 @@ impl ProcessExecutorService {
             self_address,
             coordinator_address,
             remote_shard_addresses,
+            Arc::new(FaultInjector::new(fault_injection, true)),
         );
         executor_service.start();
         Self { executor_service }
@@ -1,8 +1,9 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
-    metrics::REMOTE_EXECUTOR_TIMER, remote_state_view::RemoteStateViewClient, ExecuteBlockCommand,
-    RemoteExecutionRequest, RemoteExecutionResult,
+    fault_injection::FaultInjector, metrics::REMOTE_EXECUTOR_TIMER,
+    remote_state_view::RemoteStateViewClient, ExecuteBlockCommand, RemoteExecutionRequest,
+    RemoteExecutionResult,
 };
 use aptos_secure_net::network_controller::{Message, NetworkController};
 use aptos_types::{
@@ -14,20 +15,75 @@ use aptos_vm::sharded_block_executor::{
 };
 use crossbeam_channel::{Receiver, Sender};
 use rayon::prelude::*;
-use std::{net::SocketAddr, sync::Arc};
+use std::{
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// The execution the shard is running, i.e. of the last [ExecuteBlockCommand] it received. It is
+/// shared with the cross-shard client, which tags the cross-shard messages with it and aborts it
+/// when the messages of the other shards do not arrive.
+#[derive(Default)]
+pub(crate) struct CurrentExecution {
+    state: Mutex<ExecutionState>,
+}
+
+#[derive(Default)]
+struct ExecutionState {
+    execution_id: u64,
+    // The time the coordinator gives up on the execution, if it does.
+    deadline: Option<Instant>,
+    aborted: bool,
+}
+
+impl CurrentExecution {
+    pub(crate) fn start(&self, execution_id: u64, result_timeout: Option<Duration>) {
+        *self.state.lock().unwrap() = ExecutionState {
+            execution_id,
+            deadline: result_timeout.map(|timeout| Instant::now() + timeout),
+            aborted: false,
+        };
+    }
+
+    pub(crate) fn execution_id(&self) -> u64 {
+        self.state.lock().unwrap().execution_id
+    }
+
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        self.state.lock().unwrap().deadline
+    }
+
+    pub(crate) fn abort(&self) {
+        self.state.lock().unwrap().aborted = true;
+    }
+
+    pub(crate) fn is_aborted(&self) -> bool {
+        self.state.lock().unwrap().aborted
+    }
+}
 
 pub struct RemoteCoordinatorClient {
     state_view_client: Arc<RemoteStateViewClient>,
     command_rx: Receiver<Message>,
     result_tx: Sender<Message>,
     shard_id: ShardId,
+    // The execution of the command being executed, to tag its result with.
+    current_execution: Arc<CurrentExecution>,
+    drop_undeliverable_messages: Arc<AtomicBool>,
+    fault_injector: Arc<FaultInjector>,
 }
 
 impl RemoteCoordinatorClient {
-    pub fn new(
+    pub(crate) fn new(
         shard_id: ShardId,
         controller: &mut NetworkController,
         coordinator_address: SocketAddr,
+        current_execution: Arc<CurrentExecution>,
+        fault_injector: Arc<FaultInjector>,
     ) -> Self {
         let execute_command_type = format!("execute_command_{}", shard_id);
         let execute_result_type = format!("execute_result_{}", shard_id);
@@ -35,14 +91,21 @@ impl RemoteCoordinatorClient {
         let result_tx =
             controller.create_outbound_channel(coordinator_address, execute_result_type);
 
-        let state_view_client =
-            RemoteStateViewClient::new(shard_id, controller, coordinator_address);
+        let state_view_client = RemoteStateViewClient::new(
+            shard_id,
+            controller,
+            coordinator_address,
+            fault_injector.clone(),
+        );
 
         Self {
             state_view_client: Arc::new(state_view_client),
             command_rx,
             result_tx,
             shard_id,
+            current_execution,
+            drop_undeliverable_messages: controller.drop_undeliverable_messages_switch(),
+            fault_injector,
         }
     }
 
@@ -80,6 +143,9 @@ impl CoordinatorClient<RemoteStateViewClient> for RemoteCoordinatorClient {
     fn receive_execute_command(&self) -> ExecutorShardCommand<RemoteStateViewClient> {
         match self.command_rx.recv() {
             Ok(message) => {
+                if self.fault_injector.crash_on_block() {
+                    return ExecutorShardCommand::Stop;
+                }
                 let _rx_timer = REMOTE_EXECUTOR_TIMER
                     .with_label_values(&[&self.shard_id.to_string(), "cmd_rx"])
                     .start_timer();
@@ -98,6 +164,13 @@ impl CoordinatorClient<RemoteStateViewClient> for RemoteCoordinatorClient {
                         self.state_view_client.init_for_block(state_keys);
                         drop(init_prefetch_timer);
 
+                        self.current_execution
+                            .start(command.execution_id, command.result_timeout);
+                        // Messages to crashed shards can only be dropped if the coordinator
+                        // recovers from failed executions.
+                        self.drop_undeliverable_messages
+                            .store(command.result_timeout.is_some(), Ordering::Relaxed);
+
                         let (sub_blocks, concurrency, onchain_config) = command.into();
                         ExecutorShardCommand::ExecuteSubBlocks(
                             self.state_view_client.clone(),
@@ -113,7 +186,8 @@ impl CoordinatorClient<RemoteStateViewClient> for RemoteCoordinatorClient {
     }
 
     fn send_execution_result(&self, result: Result<Vec<Vec<TransactionOutput>>, VMStatus>) {
-        let remote_execution_result = RemoteExecutionResult::new(result);
+        let remote_execution_result =
+            RemoteExecutionResult::new(self.current_execution.execution_id(), result);
         let output_message = bcs::to_bytes(&remote_execution_result).unwrap();
         self.result_tx.send(Message::new(output_message)).unwrap();
     }
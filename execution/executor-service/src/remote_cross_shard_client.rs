@@ -1,25 +1,46 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
+use crate::{fault_injection::FaultInjector, remote_cordinator_client::CurrentExecution};
+use aptos_logger::warn;
 use aptos_secure_net::network_controller::{Message, NetworkController};
 use aptos_types::block_executor::partitioner::{RoundId, ShardId, MAX_ALLOWED_PARTITIONING_ROUNDS};
 use aptos_vm::sharded_block_executor::{
     cross_shard_client::CrossShardClient, messages::CrossShardMsg,
 };
 use crossbeam_channel::{Receiver, Sender};
+use serde::{Deserialize, Serialize};
 use std::{
+    cmp::Ordering,
+    collections::VecDeque,
     net::SocketAddr,
     sync::{Arc, Mutex},
 };
 
+/// A cross shard message tagged with the execution it belongs to, so that the messages of
+/// executions the coordinator gave up on are not mixed up with the messages of the current one.
+#[derive(Debug, Deserialize, Serialize)]
+struct RemoteCrossShardMsg {
+    execution_id: u64,
+    msg: CrossShardMsg,
+}
+
 pub struct RemoteCrossShardClient {
     // The senders of cross-shard messages to other shards per round.
     message_txs: Arc<Vec<Vec<Mutex<Sender<Message>>>>>,
-    // The receivers of cross shard messages from other shards per round.
-    message_rxs: Arc<Vec<Mutex<Receiver<Message>>>>,
+    // The receivers of cross shard messages from other shards per round, along with the messages
+    // of newer executions received while the shard was still running an older one.
+    message_rxs: Arc<Vec<Mutex<(Receiver<Message>, VecDeque<RemoteCrossShardMsg>)>>>,
+    current_execution: Arc<CurrentExecution>,
+    fault_injector: Arc<FaultInjector>,
 }
 
 impl RemoteCrossShardClient {
-    pub fn new(controller: &mut NetworkController, shard_addresses: Vec<SocketAddr>) -> Self {
+    pub(crate) fn new(
+        controller: &mut NetworkController,
+        shard_addresses: Vec<SocketAddr>,
+        current_execution: Arc<CurrentExecution>,
+        fault_injector: Arc<FaultInjector>,
+    ) -> Self {
         let mut message_txs = vec![];
         let mut message_rxs = vec![];
         // Create outbound channels for each shard per round.
@@ -37,12 +58,14 @@ impl RemoteCrossShardClient {
         for round in 0..MAX_ALLOWED_PARTITIONING_ROUNDS {
             let message_type = format!("cross_shard_{}", round);
             let rx = controller.create_inbound_channel(message_type);
-            message_rxs.push(Mutex::new(rx));
+            message_rxs.push(Mutex::new((rx, VecDeque::new())));
         }
 
         Self {
             message_txs: Arc::new(message_txs),
             message_rxs: Arc::new(message_rxs),
+            current_execution,
+            fault_injector,
         }
     }
 }
@@ -53,15 +76,162 @@ impl CrossShardClient for RemoteCrossShardClient {
     }
 
     fn send_cross_shard_msg(&self, shard_id: ShardId, round: RoundId, msg: CrossShardMsg) {
-        let input_message = bcs::to_bytes(&msg).unwrap();
+        let input_message = bcs::to_bytes(&RemoteCrossShardMsg {
+            execution_id: self.current_execution.execution_id(),
+            msg,
+        })
+        .unwrap();
+        self.fault_injector.delay_cross_shard_message();
         let tx = self.message_txs[shard_id][round].lock().unwrap();
         tx.send(Message::new(input_message)).unwrap();
     }
 
     fn receive_cross_shard_msg(&self, current_round: RoundId) -> CrossShardMsg {
-        let rx = self.message_rxs[current_round].lock().unwrap();
-        let message = rx.recv().unwrap();
-        let msg: CrossShardMsg = bcs::from_bytes(&message.to_bytes()).unwrap();
-        msg
+        let mut guard = self.message_rxs[current_round].lock().unwrap();
+        let (rx, newer_msgs) = &mut *guard;
+        if self.current_execution.is_aborted() {
+            return CrossShardMsg::AbortMsg;
+        }
+        let execution_id = self.current_execution.execution_id();
+        newer_msgs.retain(|msg| msg.execution_id >= execution_id);
+        if let Some(idx) = newer_msgs
+            .iter()
+            .position(|msg| msg.execution_id == execution_id)
+        {
+            return newer_msgs.remove(idx).unwrap().msg;
+        }
+
+        loop {
+            let message = match self.current_execution.deadline() {
+                Some(deadline) => match rx.recv_deadline(deadline) {
+                    Ok(message) => message,
+                    Err(_) => {
+                        warn!(
+                            "Cross shard messages of execution {} for round {} did not arrive \
+                             before the coordinator gave up on it, aborting it",
+                            execution_id, current_round
+                        );
+                        self.current_execution.abort();
+                        return CrossShardMsg::AbortMsg;
+                    },
+                },
+                None => rx.recv().unwrap(),
+            };
+            let msg: RemoteCrossShardMsg = bcs::from_bytes(&message.to_bytes()).unwrap();
+            match msg.execution_id.cmp(&execution_id) {
+                Ordering::Equal => return msg.msg,
+                Ordering::Less => {
+                    warn!(
+                        "Discarding cross shard message of execution {} for round {}",
+                        msg.execution_id, current_round
+                    );
+                },
+                Ordering::Greater => {
+                    // The other shards only start a newer execution once the coordinator gave up
+                    // on the current one.
+                    warn!(
+                        "Received cross shard message of execution {} for round {} while running \
+                         execution {}, aborting it",
+                        msg.execution_id, current_round, execution_id
+                    );
+                    newer_msgs.push_back(msg);
+                    self.current_execution.abort();
+                    return CrossShardMsg::AbortMsg;
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fault_injection::FaultInjectionConfig;
+    use aptos_config::utils;
+    use aptos_types::state_store::state_key::StateKey;
+    use aptos_vm::sharded_block_executor::messages::RemoteTxnWrite;
+    use std::{
+        net::{IpAddr, Ipv4Addr},
+        time::Duration,
+    };
+
+    fn create_self_client() -> (
+        NetworkController,
+        RemoteCrossShardClient,
+        Arc<CurrentExecution>,
+    ) {
+        let address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), utils::get_available_port());
+        let mut controller = NetworkController::new("cross-shard-test".to_string(), address, 1000);
+        let current_execution = Arc::new(CurrentExecution::default());
+        let client = RemoteCrossShardClient::new(
+            &mut controller,
+            vec![address],
+            current_execution.clone(),
+            Arc::new(FaultInjector::new(FaultInjectionConfig::default(), false)),
+        );
+        controller.start();
+        (controller, client, current_execution)
+    }
+
+    fn send_write(client: &RemoteCrossShardClient, key: &[u8]) {
+        client.send_cross_shard_msg(
+            0,
+            0,
+            CrossShardMsg::RemoteTxnWriteMsg(RemoteTxnWrite::new(StateKey::raw(key), None)),
+        );
+    }
+
+    fn written_key(msg: CrossShardMsg) -> Option<StateKey> {
+        match msg {
+            CrossShardMsg::RemoteTxnWriteMsg(write) => Some(write.take().0),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_messages_of_other_executions() {
+        let (mut controller, client, current_execution) = create_self_client();
+        current_execution.start(0, None);
+        send_write(&client, b"key0");
+        current_execution.start(1, None);
+        send_write(&client, b"key1");
+        // The other shards are already running the next execution.
+        current_execution.start(2, None);
+        send_write(&client, b"key2");
+        current_execution.start(1, None);
+
+        // The message of the previous execution is discarded.
+        assert_eq!(
+            written_key(client.receive_cross_shard_msg(0)),
+            Some(StateKey::raw(b"key1"))
+        );
+        // The message of the next execution aborts the current one, and is kept for the next.
+        assert!(matches!(
+            client.receive_cross_shard_msg(0),
+            CrossShardMsg::AbortMsg
+        ));
+        assert!(current_execution.is_aborted());
+        assert!(matches!(
+            client.receive_cross_shard_msg(0),
+            CrossShardMsg::AbortMsg
+        ));
+        current_execution.start(2, None);
+        assert_eq!(
+            written_key(client.receive_cross_shard_msg(0)),
+            Some(StateKey::raw(b"key2"))
+        );
+        controller.shutdown();
+    }
+
+    #[test]
+    fn test_abort_when_messages_do_not_arrive() {
+        let (mut controller, client, current_execution) = create_self_client();
+        current_execution.start(0, Some(Duration::from_millis(100)));
+        assert!(matches!(
+            client.receive_cross_shard_msg(0),
+            CrossShardMsg::AbortMsg
+        ));
+        assert!(current_execution.is_aborted());
+        controller.shutdown();
     }
 }
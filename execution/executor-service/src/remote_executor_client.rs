@@ -2,36 +2,49 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
+    metrics::REMOTE_EXECUTOR_SHARD_FAILURE_COUNT,
     remote_state_view_service::RemoteStateViewService, ExecuteBlockCommand, RemoteExecutionRequest,
     RemoteExecutionResult,
 };
-use aptos_logger::{info, trace};
+use aptos_logger::{info, trace, warn};
 use aptos_secure_net::network_controller::{Message, NetworkController};
 use aptos_storage_interface::cached_state_view::CachedStateView;
 use aptos_types::{
     block_executor::{
-        config::BlockExecutorConfigFromOnchain, partitioner::PartitionedTransactions,
+        config::BlockExecutorConfigFromOnchain,
+        partitioner::{PartitionedTransactions, SubBlocksForShard},
     },
     state_store::StateView,
-    transaction::TransactionOutput,
-    vm_status::VMStatus,
+    transaction::{
+        analyzed_transaction::AnalyzedTransaction,
+        signature_verified_transaction::SignatureVerifiedTransaction, TransactionOutput,
+    },
+    vm_status::{StatusCode, VMStatus},
 };
-use aptos_vm::sharded_block_executor::{
-    executor_client::{ExecutorClient, ShardedExecutionOutput},
-    ShardedBlockExecutor,
+use aptos_vm::{
+    sharded_block_executor::{
+        executor_client::{ExecutorClient, ShardedExecutionOutput},
+        ShardedBlockExecutor,
+    },
+    AptosVM, VMExecutor,
 };
 use crossbeam_channel::{Receiver, Sender};
 use once_cell::sync::{Lazy, OnceCell};
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
     thread,
+    time::{Duration, Instant},
 };
 
 pub static COORDINATOR_PORT: u16 = 52200;
 
 static REMOTE_ADDRESSES: OnceCell<Vec<SocketAddr>> = OnceCell::new();
 static COORDINATOR_ADDRESS: OnceCell<SocketAddr> = OnceCell::new();
+static SHARD_FAILURE_CONFIG: OnceCell<ShardFailureConfig> = OnceCell::new();
 
 pub fn set_remote_addresses(addresses: Vec<SocketAddr>) {
     REMOTE_ADDRESSES.set(addresses).ok();
@@ -55,6 +68,28 @@ pub fn get_coordinator_address() -> SocketAddr {
     }
 }
 
+pub fn set_shard_failure_config(config: ShardFailureConfig) {
+    SHARD_FAILURE_CONFIG.set(config).ok();
+}
+
+pub fn get_shard_failure_config() -> ShardFailureConfig {
+    SHARD_FAILURE_CONFIG.get().cloned().unwrap_or_default()
+}
+
+/// How the coordinator handles executor shards failing to execute a block, e.g. because a shard
+/// crashed or lost messages. By default, the coordinator waits for the results forever.
+#[derive(Clone, Debug, Default)]
+pub struct ShardFailureConfig {
+    /// Time to wait for the results of all the shards, or `None` to wait forever.
+    pub result_timeout: Option<Duration>,
+    /// Number of times the block is sent to the shards again after they failed to execute it.
+    pub max_retries: usize,
+    /// Whether the coordinator executes the block itself once the retries are exhausted, instead
+    /// of failing the block. Once this happened, all the following blocks are executed by the
+    /// coordinator too.
+    pub execute_locally_on_failure: bool,
+}
+
 pub static REMOTE_SHARDED_BLOCK_EXECUTOR: Lazy<
     Arc<
         aptos_infallible::Mutex<
@@ -68,6 +103,7 @@ pub static REMOTE_SHARDED_BLOCK_EXECUTOR: Lazy<
             get_coordinator_address(),
             get_remote_addresses(),
             None,
+            get_shard_failure_config(),
         ),
     ))
 });
@@ -85,6 +121,11 @@ pub struct RemoteExecutorClient<S: StateView + Sync + Send + 'static> {
     result_rxs: Vec<Receiver<Message>>,
     // Thread pool used to pre-fetch the state values for the block in parallel and create an in-memory state view.
     thread_pool: Arc<rayon::ThreadPool>,
+    shard_failure_config: ShardFailureConfig,
+    // The id of the next execution attempt, to match the results of the shards with it.
+    next_execution_id: AtomicU64,
+    // Whether the shards failed to execute a block and the coordinator executes blocks itself.
+    remote_shards_failed: AtomicBool,
 
     phantom: std::marker::PhantomData<S>,
     _join_handle: Option<thread::JoinHandle<()>>,
@@ -141,33 +182,153 @@ impl<S: StateView + Sync + Send + 'static> RemoteExecutorClient<S> {
             command_txs: Arc::new(command_txs),
             result_rxs,
             thread_pool,
+            shard_failure_config: ShardFailureConfig::default(),
+            next_execution_id: AtomicU64::new(0),
+            remote_shards_failed: AtomicBool::new(false),
             phantom: std::marker::PhantomData,
         }
     }
 
+    pub fn with_shard_failure_config(mut self, shard_failure_config: ShardFailureConfig) -> Self {
+        // Messages to crashed shards can only be dropped if their results are waited for with a
+        // timeout.
+        self.network_controller
+            .set_drop_undeliverable_messages(shard_failure_config.result_timeout.is_some());
+        self.shard_failure_config = shard_failure_config;
+        self
+    }
+
     pub fn create_remote_sharded_block_executor(
         coordinator_address: SocketAddr,
         remote_shard_addresses: Vec<SocketAddr>,
         num_threads: Option<usize>,
+        shard_failure_config: ShardFailureConfig,
     ) -> ShardedBlockExecutor<S, RemoteExecutorClient<S>> {
-        ShardedBlockExecutor::new(RemoteExecutorClient::new(
-            remote_shard_addresses,
-            NetworkController::new(
-                "remote-executor-coordinator".to_string(),
-                coordinator_address,
-                5000,
-            ),
-            num_threads,
-        ))
+        ShardedBlockExecutor::new(
+            RemoteExecutorClient::new(
+                remote_shard_addresses,
+                NetworkController::new(
+                    "remote-executor-coordinator".to_string(),
+                    coordinator_address,
+                    5000,
+                ),
+                num_threads,
+            )
+            .with_shard_failure_config(shard_failure_config),
+        )
     }
 
-    fn get_output_from_shards(&self) -> Result<Vec<Vec<Vec<TransactionOutput>>>, VMStatus> {
+    fn get_output_from_shards(
+        &self,
+        execution_id: u64,
+    ) -> Result<Vec<Vec<Vec<TransactionOutput>>>, VMStatus> {
         trace!("RemoteExecutorClient Waiting for results");
+        let deadline = self
+            .shard_failure_config
+            .result_timeout
+            .map(|timeout| Instant::now() + timeout);
+        let shard_error = |shard_id: usize, error: &str| {
+            VMStatus::error(
+                StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR,
+                Some(format!("Executor shard {} {}", shard_id, error)),
+            )
+        };
         let mut results = vec![];
-        for rx in self.result_rxs.iter() {
-            let received_bytes = rx.recv().unwrap().to_bytes();
-            let result: RemoteExecutionResult = bcs::from_bytes(&received_bytes).unwrap();
-            results.push(result.inner?);
+        for (shard_id, rx) in self.result_rxs.iter().enumerate() {
+            loop {
+                let message = match deadline {
+                    Some(deadline) => rx.recv_deadline(deadline).map_err(|_| {
+                        shard_error(shard_id, "did not return the results of the block in time")
+                    })?,
+                    None => rx
+                        .recv()
+                        .map_err(|_| shard_error(shard_id, "result channel is disconnected"))?,
+                };
+                let result: RemoteExecutionResult =
+                    bcs::from_bytes(&message.to_bytes()).map_err(|err| {
+                        shard_error(shard_id, &format!("returned malformed results: {}", err))
+                    })?;
+                // Results of previous attempts, which the coordinator gave up on, are discarded.
+                if result.execution_id == execution_id {
+                    results.push(result.inner?);
+                    break;
+                }
+                warn!(
+                    "Discarding results of execution {} from shard {}",
+                    result.execution_id, shard_id
+                );
+            }
+        }
+        Ok(results)
+    }
+
+    // Sends the block to the shards, again after every failure until the retries are exhausted.
+    fn execute_block_remotely(
+        &self,
+        requests: &mut [RemoteExecutionRequest],
+    ) -> Result<Vec<Vec<Vec<TransactionOutput>>>, VMStatus> {
+        let mut attempt = 0;
+        loop {
+            let execution_id = self.next_execution_id.fetch_add(1, Ordering::Relaxed);
+            for (shard_id, request) in requests.iter_mut().enumerate() {
+                let RemoteExecutionRequest::ExecuteBlock(command) = request;
+                command.execution_id = execution_id;
+                self.command_txs[shard_id]
+                    .lock()
+                    .unwrap()
+                    .send(Message::new(bcs::to_bytes(&*request).unwrap()))
+                    .unwrap();
+            }
+
+            match self.get_output_from_shards(execution_id) {
+                Err(err) if attempt < self.shard_failure_config.max_retries => {
+                    warn!(
+                        "Executor shards failed to execute the block: {:?}, retrying",
+                        err
+                    );
+                    REMOTE_EXECUTOR_SHARD_FAILURE_COUNT
+                        .with_label_values(&["retry"])
+                        .inc();
+                    attempt += 1;
+                },
+                result => return result,
+            }
+        }
+    }
+
+    // Executes the block on the coordinator, and splits the outputs by shard and round like the
+    // shards would have.
+    fn execute_block_locally(
+        &self,
+        state_view: &S,
+        sub_blocks: Vec<SubBlocksForShard<AnalyzedTransaction>>,
+        onchain_config: BlockExecutorConfigFromOnchain,
+    ) -> Result<Vec<Vec<Vec<TransactionOutput>>>, VMStatus> {
+        let num_shards = sub_blocks.len();
+        let sub_block_sizes = sub_blocks
+            .iter()
+            .map(|sub_blocks| {
+                sub_blocks
+                    .sub_block_iter()
+                    .map(|sub_block| sub_block.num_txns())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let num_rounds = sub_block_sizes.first().map_or(0, |sizes| sizes.len());
+        let txns = SubBlocksForShard::flatten(sub_blocks)
+            .into_iter()
+            .map(SignatureVerifiedTransaction::from)
+            .collect::<Vec<_>>();
+        let mut outputs = AptosVM::execute_block(&txns, state_view, onchain_config)?
+            .into_transaction_outputs_forced()
+            .into_iter();
+
+        // The transactions are flattened in the order of the rounds, and then of the shards.
+        let mut results = vec![vec![vec![]; num_rounds]; num_shards];
+        for round in 0..num_rounds {
+            for (shard_id, sizes) in sub_block_sizes.iter().enumerate() {
+                results[shard_id][round] = outputs.by_ref().take(sizes[round]).collect();
+            }
         }
         Ok(results)
     }
@@ -185,30 +346,58 @@ impl<S: StateView + Sync + Send + 'static> ExecutorClient<S> for RemoteExecutorC
         concurrency_level_per_shard: usize,
         onchain_config: BlockExecutorConfigFromOnchain,
     ) -> Result<ShardedExecutionOutput, VMStatus> {
-        trace!("RemoteExecutorClient Sending block to shards");
-        self.state_view_service.set_state_view(state_view);
         let (sub_blocks, global_txns) = transactions.into();
         if !global_txns.is_empty() {
             panic!("Global transactions are not supported yet");
         }
-        for (shard_id, sub_blocks) in sub_blocks.into_iter().enumerate() {
-            let senders = self.command_txs.clone();
-            let execution_request = RemoteExecutionRequest::ExecuteBlock(ExecuteBlockCommand {
-                sub_blocks,
-                concurrency_level: concurrency_level_per_shard,
-                onchain_config: onchain_config.clone(),
-            });
-
-            senders[shard_id]
-                .lock()
-                .unwrap()
-                .send(Message::new(bcs::to_bytes(&execution_request).unwrap()))
-                .unwrap();
+        if self.remote_shards_failed.load(Ordering::Relaxed) {
+            let execution_results =
+                self.execute_block_locally(&state_view, sub_blocks, onchain_config)?;
+            return Ok(ShardedExecutionOutput::new(execution_results, vec![]));
         }
 
-        let execution_results = self.get_output_from_shards()?;
-
+        trace!("RemoteExecutorClient Sending block to shards");
+        self.state_view_service.set_state_view(state_view.clone());
+        let mut requests = sub_blocks
+            .into_iter()
+            .map(|sub_blocks| {
+                RemoteExecutionRequest::ExecuteBlock(ExecuteBlockCommand {
+                    execution_id: 0,
+                    sub_blocks,
+                    concurrency_level: concurrency_level_per_shard,
+                    onchain_config: onchain_config.clone(),
+                    result_timeout: self.shard_failure_config.result_timeout,
+                })
+            })
+            .collect::<Vec<_>>();
+        let result = self.execute_block_remotely(&mut requests);
         self.state_view_service.drop_state_view();
+
+        let execution_results = match result {
+            Ok(execution_results) => execution_results,
+            Err(err) if self.shard_failure_config.execute_locally_on_failure => {
+                warn!(
+                    "Executor shards failed to execute the block: {:?}, executing it and all the \
+                     following blocks on the coordinator",
+                    err
+                );
+                REMOTE_EXECUTOR_SHARD_FAILURE_COUNT
+                    .with_label_values(&["execute_locally"])
+                    .inc();
+                self.remote_shards_failed.store(true, Ordering::Relaxed);
+                let sub_blocks = requests
+                    .into_iter()
+                    .map(|RemoteExecutionRequest::ExecuteBlock(command)| command.sub_blocks)
+                    .collect();
+                self.execute_block_locally(&state_view, sub_blocks, onchain_config)?
+            },
+            Err(err) => {
+                REMOTE_EXECUTOR_SHARD_FAILURE_COUNT
+                    .with_label_values(&["fail_block"])
+                    .inc();
+                return Err(err);
+            },
+        };
         Ok(ShardedExecutionOutput::new(execution_results, vec![]))
     }
 
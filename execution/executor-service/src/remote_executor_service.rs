@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    remote_cordinator_client::RemoteCoordinatorClient,
-    remote_cross_shard_client::RemoteCrossShardClient, remote_state_view::RemoteStateViewClient,
+    fault_injection::FaultInjector,
+    remote_cordinator_client::{CurrentExecution, RemoteCoordinatorClient},
+    remote_cross_shard_client::RemoteCrossShardClient,
+    remote_state_view::RemoteStateViewClient,
 };
 use aptos_secure_net::network_controller::NetworkController;
 use aptos_types::block_executor::partitioner::ShardId;
@@ -26,17 +28,23 @@ impl ExecutorService {
         self_address: SocketAddr,
         coordinator_address: SocketAddr,
         remote_shard_addresses: Vec<SocketAddr>,
+        fault_injector: Arc<FaultInjector>,
     ) -> Self {
         let service_name = format!("executor_service-{}", shard_id);
         let mut controller = NetworkController::new(service_name, self_address, 5000);
+        let current_execution = Arc::new(CurrentExecution::default());
         let coordinator_client = Arc::new(RemoteCoordinatorClient::new(
             shard_id,
             &mut controller,
             coordinator_address,
+            current_execution.clone(),
+            fault_injector.clone(),
         ));
         let cross_shard_client = Arc::new(RemoteCrossShardClient::new(
             &mut controller,
             remote_shard_addresses,
+            current_execution,
+            fault_injector,
         ));
 
         let executor_service = Arc::new(ShardedExecutorService::new(
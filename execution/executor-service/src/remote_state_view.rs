@@ -1,6 +1,6 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
-use crate::{RemoteKVRequest, RemoteKVResponse};
+use crate::{fault_injection::FaultInjector, RemoteKVRequest, RemoteKVResponse};
 use aptos_secure_net::network_controller::{Message, NetworkController};
 use aptos_types::state_store::state_key::StateKey;
 use aptos_vm::sharded_block_executor::remote_state_value::RemoteStateValue;
@@ -9,21 +9,27 @@ use std::{
     net::SocketAddr,
     sync::{Arc, RwLock},
     thread,
+    time::Duration,
 };
 
 extern crate itertools;
 use crate::metrics::{REMOTE_EXECUTOR_REMOTE_KV_COUNT, REMOTE_EXECUTOR_TIMER};
-use aptos_logger::trace;
+use aptos_logger::{trace, warn};
 use aptos_types::{
     block_executor::partitioner::ShardId,
     state_store::{
-        state_storage_usage::StateStorageUsage, state_value::StateValue, Result, TStateView,
+        errors::StateviewError, state_storage_usage::StateStorageUsage, state_value::StateValue,
+        Result, TStateView,
     },
 };
 use dashmap::DashMap;
 use rayon::ThreadPool;
 
 pub static REMOTE_STATE_KEY_BATCH_SIZE: usize = 200;
+/// Time to wait for a state value before requesting it again, e.g. because the response was lost.
+const REMOTE_STATE_VALUE_TIMEOUT: Duration = Duration::from_secs(1);
+/// Number of times a state value is requested again before reading it fails.
+const MAX_STATE_VALUE_REQUEST_RETRIES: usize = 5;
 
 pub struct RemoteStateView {
     state_values: DashMap<StateKey, RemoteStateValue>,
@@ -41,10 +47,10 @@ impl RemoteStateView {
     }
 
     pub fn set_state_value(&self, state_key: &StateKey, state_value: Option<StateValue>) {
-        self.state_values
-            .get(state_key)
-            .unwrap()
-            .set_value(state_value);
+        // The key may be missing if the response is for a request of a previous block.
+        if let Some(value) = self.state_values.get(state_key) {
+            value.set_value(state_value);
+        }
     }
 
     pub fn insert_state_key(&self, state_key: StateKey) {
@@ -60,10 +66,34 @@ impl RemoteStateView {
             // case we explicitly drop the value to relinquish the read lock on the value. Cloning the
             // value should be in expensive as this is just cloning the underlying Arc.
             drop(value);
-            return Ok(value_clone.get_value());
+            return value_clone.get_value();
         }
         Ok(None)
     }
+
+    /// Like [Self::get_state_value], but returns `None` if the value is not available within the
+    /// timeout.
+    pub fn get_state_value_timeout(
+        &self,
+        state_key: &StateKey,
+        timeout: Duration,
+    ) -> Option<Option<StateValue>> {
+        if let Some(value) = self.state_values.get(state_key) {
+            let value_clone = value.clone();
+            drop(value);
+            return value_clone.get_value_timeout(timeout);
+        }
+        Some(None)
+    }
+
+    /// The keys whose values were requested but did not arrive yet.
+    pub fn pending_state_keys(&self) -> Vec<StateKey> {
+        self.state_values
+            .iter()
+            .filter(|entry| !entry.value().is_ready())
+            .map(|entry| entry.key().clone())
+            .collect()
+    }
 }
 
 pub struct RemoteStateViewClient {
@@ -79,6 +109,7 @@ impl RemoteStateViewClient {
         shard_id: ShardId,
         controller: &mut NetworkController,
         coordinator_address: SocketAddr,
+        fault_injector: Arc<FaultInjector>,
     ) -> Self {
         let thread_pool = Arc::new(
             rayon::ThreadPoolBuilder::new()
@@ -98,6 +129,7 @@ impl RemoteStateViewClient {
             state_view.clone(),
             result_rx,
             thread_pool.clone(),
+            fault_injector,
         );
 
         let join_handle = thread::Builder::new()
@@ -177,6 +209,49 @@ impl RemoteStateViewClient {
         let request_message = bcs::to_bytes(&request).unwrap();
         sender.send(Message::new(request_message)).unwrap();
     }
+
+    // Waits for a state value that was requested. Whenever it does not arrive in time, e.g.
+    // because a response was lost, all the values which did not arrive yet are requested again.
+    fn wait_for_state_value(
+        &self,
+        state_view: &RemoteStateView,
+        state_key: &StateKey,
+    ) -> Result<Option<StateValue>> {
+        for _ in 0..MAX_STATE_VALUE_REQUEST_RETRIES {
+            if let Some(state_value) =
+                state_view.get_state_value_timeout(state_key, REMOTE_STATE_VALUE_TIMEOUT)
+            {
+                return Ok(state_value);
+            }
+            let pending_state_keys = state_view.pending_state_keys();
+            warn!(
+                "Shard {} timed out waiting for state value of {:?}, requesting {} pending state values again",
+                self.shard_id,
+                state_key,
+                pending_state_keys.len()
+            );
+            REMOTE_EXECUTOR_REMOTE_KV_COUNT
+                .with_label_values(&[&self.shard_id.to_string(), "kv_retries"])
+                .inc_by(pending_state_keys.len() as u64);
+            pending_state_keys
+                .chunks(REMOTE_STATE_KEY_BATCH_SIZE)
+                .for_each(|state_keys| {
+                    Self::send_state_value_request(
+                        self.shard_id,
+                        self.kv_tx.clone(),
+                        state_keys.to_vec(),
+                    );
+                });
+        }
+        state_view
+            .get_state_value_timeout(state_key, REMOTE_STATE_VALUE_TIMEOUT)
+            .ok_or_else(|| {
+                StateviewError::Other(format!(
+                    "Shard {} did not receive the state value of {:?}",
+                    self.shard_id, state_key
+                ))
+            })
+    }
 }
 
 impl TStateView for RemoteStateViewClient {
@@ -189,7 +264,7 @@ impl TStateView for RemoteStateViewClient {
             let _timer = REMOTE_EXECUTOR_TIMER
                 .with_label_values(&[&self.shard_id.to_string(), "prefetch_wait"])
                 .start_timer();
-            return state_view_reader.get_state_value(state_key);
+            return self.wait_for_state_value(&state_view_reader, state_key);
         }
         // If the value is not already in the cache then we pre-fetch it and wait for it to arrive.
         let _timer = REMOTE_EXECUTOR_TIMER
@@ -199,7 +274,7 @@ impl TStateView for RemoteStateViewClient {
             .with_label_values(&[&self.shard_id.to_string(), "non_prefetch_kv"])
             .inc();
         self.pre_fetch_state_values(vec![state_key.clone()], true);
-        state_view_reader.get_state_value(state_key)
+        self.wait_for_state_value(&state_view_reader, state_key)
     }
 
     fn get_usage(&self) -> Result<StateStorageUsage> {
@@ -212,6 +287,7 @@ struct RemoteStateValueReceiver {
     state_view: Arc<RwLock<RemoteStateView>>,
     kv_rx: Receiver<Message>,
     thread_pool: Arc<rayon::ThreadPool>,
+    fault_injector: Arc<FaultInjector>,
 }
 
 impl RemoteStateValueReceiver {
@@ -220,17 +296,26 @@ impl RemoteStateValueReceiver {
         state_view: Arc<RwLock<RemoteStateView>>,
         kv_rx: Receiver<Message>,
         thread_pool: Arc<rayon::ThreadPool>,
+        fault_injector: Arc<FaultInjector>,
     ) -> Self {
         Self {
             shard_id,
             state_view,
             kv_rx,
             thread_pool,
+            fault_injector,
         }
     }
 
     fn start(&self) {
         while let Ok(message) = self.kv_rx.recv() {
+            if self.fault_injector.drop_kv_response() {
+                warn!(
+                    "Injected drop of a state value response on shard {}",
+                    self.shard_id
+                );
+                continue;
+            }
             let state_view = self.state_view.clone();
             let shard_id = self.shard_id;
             self.thread_pool.spawn(move || {
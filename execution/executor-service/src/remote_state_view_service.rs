@@ -10,7 +10,7 @@ use std::{
 
 extern crate itertools;
 use crate::metrics::REMOTE_EXECUTOR_TIMER;
-use aptos_logger::trace;
+use aptos_logger::{trace, warn};
 use aptos_types::state_store::{StateView, TStateView};
 use itertools::Itertools;
 
@@ -92,19 +92,24 @@ impl<S: StateView + Sync + Send + 'static> RemoteStateViewService<S> {
             shard_id,
             state_keys.len()
         );
+        let state_view_lock = state_view.read().unwrap();
+        let Some(state_view) = state_view_lock.as_ref() else {
+            // A request of a shard for a block which is not executed anymore, e.g. because the
+            // coordinator gave up on the shard.
+            warn!(
+                "remote state view service - dropping request of shard {} without a block being executed",
+                shard_id
+            );
+            return;
+        };
         let resp = state_keys
             .into_iter()
             .map(|state_key| {
-                let state_value = state_view
-                    .read()
-                    .unwrap()
-                    .as_ref()
-                    .unwrap()
-                    .get_state_value(&state_key)
-                    .unwrap();
+                let state_value = state_view.get_state_value(&state_key).unwrap();
                 (state_key, state_value)
             })
             .collect_vec();
+        drop(state_view_lock);
         let len = resp.len();
         let resp = RemoteKVResponse::new(resp);
         let bcs_ser_timer = REMOTE_EXECUTOR_TIMER
@@ -1,6 +1,8 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+//! Helpers to test sharded block execution, shared by the unit and integration tests.
+
 use aptos_block_partitioner::{v2::config::PartitionerV2Config, PartitionerConfig};
 use aptos_language_e2e_tests::{
     account::AccountData, common_transactions::peer_to_peer_txn, data_store::FakeDataStore,
@@ -22,10 +24,7 @@ use aptos_vm::{
     sharded_block_executor::{executor_client::ExecutorClient, ShardedBlockExecutor},
     AptosVM, VMExecutor,
 };
-use std::{
-    collections::HashMap,
-    sync::{Arc, Mutex},
-};
+use std::sync::{Arc, Mutex};
 
 pub fn generate_account_at(executor: &mut FakeExecutor, address: AccountAddress) -> AccountData {
     executor.new_account_data_at(address)
@@ -107,11 +106,12 @@ pub fn compare_txn_outputs(
     }
 }
 
-pub fn test_sharded_block_executor_no_conflict<E: ExecutorClient<FakeDataStore>>(
-    mut sharded_block_executor: ShardedBlockExecutor<FakeDataStore, E>,
-) {
-    let num_txns = 400;
-    let num_shards = sharded_block_executor.num_shards();
+/// Generates a block of non-conflicting transactions partitioned across the shards, along with
+/// the executor holding the state they are executed on.
+pub fn generate_non_conflicting_block(
+    num_txns: usize,
+    num_shards: usize,
+) -> (FakeExecutor, PartitionedTransactions) {
     let mut executor = FakeExecutor::from_head_genesis();
     let mut transactions = Vec::new();
     for _ in 0..num_txns {
@@ -122,7 +122,16 @@ pub fn test_sharded_block_executor_no_conflict<E: ExecutorClient<FakeDataStore>>
         .cross_shard_dep_avoid_threshold(0.9)
         .partition_last_round(true)
         .build();
-    let partitioned_txns = partitioner.partition(transactions.clone(), num_shards);
+    let partitioned_txns = partitioner.partition(transactions, num_shards);
+    (executor, partitioned_txns)
+}
+
+pub fn test_sharded_block_executor_no_conflict<E: ExecutorClient<FakeDataStore>>(
+    mut sharded_block_executor: ShardedBlockExecutor<FakeDataStore, E>,
+) {
+    let num_txns = 400;
+    let num_shards = sharded_block_executor.num_shards();
+    let (executor, partitioned_txns) = generate_non_conflicting_block(num_txns, num_shards);
     let sharded_txn_output = sharded_block_executor
         .execute_block(
             Arc::new(executor.data_store().clone()),
@@ -142,17 +151,16 @@ pub fn test_sharded_block_executor_no_conflict<E: ExecutorClient<FakeDataStore>>
     sharded_block_executor.shutdown();
 }
 
-pub fn sharded_block_executor_with_conflict<E: ExecutorClient<FakeDataStore>>(
-    mut sharded_block_executor: ShardedBlockExecutor<FakeDataStore, E>,
-    concurrency: usize,
-) {
-    let num_txns = 800;
-    let num_shards = sharded_block_executor.num_shards();
-    let num_accounts = 80;
+/// Generates a block of transfers between a few accounts, which conflict across the shards, along
+/// with the executor holding the state they are executed on.
+pub fn generate_conflicting_block(
+    num_txns: usize,
+    num_accounts: usize,
+    num_shards: usize,
+) -> (FakeExecutor, PartitionedTransactions) {
     let mut executor = FakeExecutor::from_head_genesis();
     let mut transactions = Vec::new();
     let mut accounts = Vec::new();
-    let mut txn_hash_to_account = HashMap::new();
     for _ in 0..num_accounts {
         let account = generate_account_at(&mut executor, AccountAddress::random());
         accounts.push(Mutex::new(account));
@@ -160,12 +168,9 @@ pub fn sharded_block_executor_with_conflict<E: ExecutorClient<FakeDataStore>>(
     for i in 1..num_txns / num_accounts {
         for j in 0..num_accounts {
             let sender = &mut accounts[j].lock().unwrap();
-            let sender_addr = *sender.address();
             let receiver = &accounts[(j + i) % num_accounts].lock().unwrap();
             let transfer_amount = 1_000;
-            let txn = generate_p2p_txn(sender, receiver, transfer_amount);
-            txn_hash_to_account.insert(txn.transaction().hash(), sender_addr);
-            transactions.push(txn)
+            transactions.push(generate_p2p_txn(sender, receiver, transfer_amount))
         }
     }
 
@@ -174,7 +179,16 @@ pub fn sharded_block_executor_with_conflict<E: ExecutorClient<FakeDataStore>>(
         .cross_shard_dep_avoid_threshold(0.9)
         .partition_last_round(true)
         .build();
-    let partitioned_txns = partitioner.partition(transactions.clone(), num_shards);
+    let partitioned_txns = partitioner.partition(transactions, num_shards);
+    (executor, partitioned_txns)
+}
+
+pub fn sharded_block_executor_with_conflict<E: ExecutorClient<FakeDataStore>>(
+    mut sharded_block_executor: ShardedBlockExecutor<FakeDataStore, E>,
+    concurrency: usize,
+) {
+    let num_shards = sharded_block_executor.num_shards();
+    let (executor, partitioned_txns) = generate_conflicting_block(800, 80, num_shards);
 
     let execution_ordered_txns: Vec<SignatureVerifiedTransaction> =
         PartitionedTransactions::flatten(partitioned_txns.clone())
@@ -2,14 +2,28 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    remote_executor_client::RemoteExecutorClient, test_utils,
+    fault_injection::FaultInjectionConfig,
+    remote_executor_client::{RemoteExecutorClient, ShardFailureConfig},
+    test_utils,
     thread_executor_service::ThreadExecutorService,
 };
 use aptos_config::utils;
-use aptos_language_e2e_tests::data_store::FakeDataStore;
+use aptos_language_e2e_tests::{data_store::FakeDataStore, executor::FakeExecutor};
 use aptos_secure_net::network_controller::NetworkController;
-use aptos_vm::sharded_block_executor::ShardedBlockExecutor;
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use aptos_types::{
+    block_executor::{
+        config::BlockExecutorConfigFromOnchain,
+        partitioner::{PartitionedTransactions, ShardId},
+    },
+    transaction::signature_verified_transaction::SignatureVerifiedTransaction,
+};
+use aptos_vm::{sharded_block_executor::ShardedBlockExecutor, AptosVM, VMExecutor};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+    thread,
+    time::Duration,
+};
 
 pub fn create_thread_remote_executor_shards(
     num_shards: usize,
@@ -17,6 +31,23 @@ pub fn create_thread_remote_executor_shards(
 ) -> (
     RemoteExecutorClient<FakeDataStore>,
     Vec<ThreadExecutorService>,
+) {
+    create_thread_remote_executor_shards_with_faults(
+        num_shards,
+        num_threads,
+        |_| FaultInjectionConfig::default(),
+        ShardFailureConfig::default(),
+    )
+}
+
+pub fn create_thread_remote_executor_shards_with_faults(
+    num_shards: usize,
+    num_threads: Option<usize>,
+    fault_injection: impl Fn(ShardId) -> FaultInjectionConfig,
+    shard_failure_config: ShardFailureConfig,
+) -> (
+    RemoteExecutorClient<FakeDataStore>,
+    Vec<ThreadExecutorService>,
 ) {
     // First create the coordinator.
     let listen_port = utils::get_available_port();
@@ -44,19 +75,19 @@ pub fn create_thread_remote_executor_shards(
                 num_threads,
                 coordinator_address,
                 remote_shard_addresses.clone(),
+                fault_injection(shard_id),
             )
         })
         .collect::<Vec<_>>();
 
     let remote_executor_client =
-        RemoteExecutorClient::new(remote_shard_addresses, controller, None);
+        RemoteExecutorClient::new(remote_shard_addresses, controller, None)
+            .with_shard_failure_config(shard_failure_config);
     (remote_executor_client, remote_executor_services)
 }
 
 #[test]
 fn test_sharded_block_executor_no_conflict() {
-    use std::thread;
-
     let num_shards = 8;
     let (executor_client, mut executor_services) =
         create_thread_remote_executor_shards(num_shards, Some(2));
@@ -75,8 +106,6 @@ fn test_sharded_block_executor_no_conflict() {
 
 #[test]
 fn test_sharded_block_executor_with_conflict() {
-    use std::thread;
-
     let num_shards = 8;
     let (executor_client, mut executor_services) =
         create_thread_remote_executor_shards(num_shards, Some(2));
@@ -92,3 +121,204 @@ fn test_sharded_block_executor_with_conflict() {
         executor_service.shutdown();
     });
 }
+
+#[test]
+fn test_sharded_block_executor_with_delayed_cross_shard_messages() {
+    let num_shards = 4;
+    let (executor_client, mut executor_services) = create_thread_remote_executor_shards_with_faults(
+        num_shards,
+        Some(2),
+        |_| FaultInjectionConfig {
+            cross_shard_message_delay_ms: 5,
+            ..FaultInjectionConfig::default()
+        },
+        ShardFailureConfig::default(),
+    );
+    let sharded_block_executor = ShardedBlockExecutor::new(executor_client);
+    thread::sleep(Duration::from_millis(10));
+
+    test_utils::sharded_block_executor_with_conflict(sharded_block_executor, 2);
+
+    executor_services.iter_mut().for_each(|executor_service| {
+        executor_service.shutdown();
+    });
+}
+
+#[test]
+fn test_sharded_block_executor_with_dropped_kv_responses() {
+    let num_shards = 4;
+    let (executor_client, mut executor_services) = create_thread_remote_executor_shards_with_faults(
+        num_shards,
+        Some(2),
+        |_| FaultInjectionConfig {
+            kv_response_drop_rate: 0.1,
+            ..FaultInjectionConfig::default()
+        },
+        ShardFailureConfig::default(),
+    );
+    let sharded_block_executor = ShardedBlockExecutor::new(executor_client);
+    thread::sleep(Duration::from_millis(10));
+
+    test_utils::sharded_block_executor_with_conflict(sharded_block_executor, 2);
+
+    executor_services.iter_mut().for_each(|executor_service| {
+        executor_service.shutdown();
+    });
+}
+
+fn crash_first_shard(shard_id: ShardId) -> FaultInjectionConfig {
+    FaultInjectionConfig {
+        crash_at_block: (shard_id == 0).then_some(0),
+        ..FaultInjectionConfig::default()
+    }
+}
+
+fn execute_and_compare_block(
+    sharded_block_executor: &ShardedBlockExecutor<
+        FakeDataStore,
+        RemoteExecutorClient<FakeDataStore>,
+    >,
+    executor: &FakeExecutor,
+    partitioned_txns: PartitionedTransactions,
+) {
+    let txns: Vec<SignatureVerifiedTransaction> =
+        PartitionedTransactions::flatten(partitioned_txns.clone())
+            .into_iter()
+            .map(|t| t.into_txn())
+            .collect();
+    let sharded_txn_output = sharded_block_executor
+        .execute_block(
+            Arc::new(executor.data_store().clone()),
+            partitioned_txns,
+            2,
+            BlockExecutorConfigFromOnchain::new_no_block_limit(),
+        )
+        .unwrap();
+    let unsharded_txn_output =
+        AptosVM::execute_block_no_limit(&txns, executor.data_store()).unwrap();
+    test_utils::compare_txn_outputs(unsharded_txn_output, sharded_txn_output);
+}
+
+#[test]
+fn test_sharded_block_executor_executes_locally_on_shard_crash() {
+    let num_shards = 2;
+    let (executor_client, mut executor_services) = create_thread_remote_executor_shards_with_faults(
+        num_shards,
+        Some(2),
+        crash_first_shard,
+        ShardFailureConfig {
+            result_timeout: Some(Duration::from_secs(5)),
+            max_retries: 1,
+            execute_locally_on_failure: true,
+        },
+    );
+    let mut sharded_block_executor = ShardedBlockExecutor::new(executor_client);
+    thread::sleep(Duration::from_millis(10));
+
+    // The following blocks are executed on the coordinator without waiting for the shards.
+    for _ in 0..2 {
+        let (executor, partitioned_txns) =
+            test_utils::generate_non_conflicting_block(100, num_shards);
+        execute_and_compare_block(&sharded_block_executor, &executor, partitioned_txns);
+    }
+    sharded_block_executor.shutdown();
+
+    executor_services.iter_mut().for_each(|executor_service| {
+        executor_service.shutdown();
+    });
+}
+
+#[test]
+fn test_sharded_block_executor_fails_block_on_shard_crash() {
+    let num_shards = 2;
+    let (executor_client, mut executor_services) = create_thread_remote_executor_shards_with_faults(
+        num_shards,
+        Some(2),
+        crash_first_shard,
+        ShardFailureConfig {
+            result_timeout: Some(Duration::from_secs(5)),
+            max_retries: 0,
+            execute_locally_on_failure: false,
+        },
+    );
+    let mut sharded_block_executor = ShardedBlockExecutor::new(executor_client);
+    thread::sleep(Duration::from_millis(10));
+
+    let (executor, partitioned_txns) = test_utils::generate_non_conflicting_block(100, num_shards);
+    assert!(sharded_block_executor
+        .execute_block(
+            Arc::new(executor.data_store().clone()),
+            partitioned_txns,
+            2,
+            BlockExecutorConfigFromOnchain::new_no_block_limit(),
+        )
+        .is_err());
+    sharded_block_executor.shutdown();
+
+    executor_services.iter_mut().for_each(|executor_service| {
+        executor_service.shutdown();
+    });
+}
+
+#[test]
+fn test_sharded_block_executor_with_conflict_executes_locally_on_shard_crash() {
+    let num_shards = 2;
+    let (executor_client, mut executor_services) = create_thread_remote_executor_shards_with_faults(
+        num_shards,
+        Some(2),
+        crash_first_shard,
+        ShardFailureConfig {
+            result_timeout: Some(Duration::from_secs(5)),
+            max_retries: 1,
+            execute_locally_on_failure: true,
+        },
+    );
+    let mut sharded_block_executor = ShardedBlockExecutor::new(executor_client);
+    thread::sleep(Duration::from_millis(10));
+
+    // The other shard waits for the cross shard messages of the crashed one until the coordinator
+    // gives up on the execution, and then executes the retry.
+    for _ in 0..2 {
+        let (executor, partitioned_txns) =
+            test_utils::generate_conflicting_block(400, 40, num_shards);
+        execute_and_compare_block(&sharded_block_executor, &executor, partitioned_txns);
+    }
+    sharded_block_executor.shutdown();
+
+    executor_services.iter_mut().for_each(|executor_service| {
+        executor_service.shutdown();
+    });
+}
+
+#[test]
+fn test_sharded_block_executor_with_conflict_fails_block_on_shard_crash() {
+    let num_shards = 2;
+    let (executor_client, mut executor_services) = create_thread_remote_executor_shards_with_faults(
+        num_shards,
+        Some(2),
+        crash_first_shard,
+        ShardFailureConfig {
+            result_timeout: Some(Duration::from_secs(5)),
+            max_retries: 0,
+            execute_locally_on_failure: false,
+        },
+    );
+    let mut sharded_block_executor = ShardedBlockExecutor::new(executor_client);
+    thread::sleep(Duration::from_millis(10));
+
+    let (executor, partitioned_txns) = test_utils::generate_conflicting_block(400, 40, num_shards);
+    assert!(sharded_block_executor
+        .execute_block(
+            Arc::new(executor.data_store().clone()),
+            partitioned_txns,
+            2,
+            BlockExecutorConfigFromOnchain::new_no_block_limit(),
+        )
+        .is_err());
+
+    sharded_block_executor.shutdown();
+
+    executor_services.iter_mut().for_each(|executor_service| {
+        executor_service.shutdown();
+    });
+}
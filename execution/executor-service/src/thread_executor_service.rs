@@ -1,8 +1,11 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
-use crate::remote_executor_service::ExecutorService;
+use crate::{
+    fault_injection::{FaultInjectionConfig, FaultInjector},
+    remote_executor_service::ExecutorService,
+};
 use aptos_types::block_executor::partitioner::ShardId;
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::Arc};
 
 /// This is a simple implementation of RemoteExecutorService that runs the executor service in a
 /// separate thread. This should be used for testing only.
@@ -18,6 +21,7 @@ impl ThreadExecutorService {
         num_threads: usize,
         coordinator_address: SocketAddr,
         remote_shard_addresses: Vec<SocketAddr>,
+        fault_injection: FaultInjectionConfig,
     ) -> Self {
         let self_address = remote_shard_addresses[shard_id];
         let mut executor_service = ExecutorService::new(
@@ -27,6 +31,7 @@ impl ThreadExecutorService {
             self_address,
             coordinator_address,
             remote_shard_addresses,
+            Arc::new(FaultInjector::new(fault_injection, false)),
         );
         executor_service.start();
         Self {
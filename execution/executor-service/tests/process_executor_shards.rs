@@ -0,0 +1,211 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests the remote executor with shards running as local processes, with faults injected into
+//! the shards.
+
+use aptos_config::utils;
+use aptos_executor_service::{
+    fault_injection::INJECTED_CRASH_EXIT_CODE,
+    remote_executor_client::{RemoteExecutorClient, ShardFailureConfig},
+    test_utils,
+};
+use aptos_language_e2e_tests::data_store::FakeDataStore;
+use aptos_secure_net::network_controller::NetworkController;
+use aptos_types::block_executor::config::BlockExecutorConfigFromOnchain;
+use aptos_vm::sharded_block_executor::ShardedBlockExecutor;
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr, TcpStream},
+    process::{Child, Command},
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+
+const SHARD_STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Shard processes, which are killed when dropped.
+struct ShardProcesses {
+    processes: Vec<Child>,
+}
+
+impl ShardProcesses {
+    fn start(
+        coordinator_address: SocketAddr,
+        shard_addresses: &[SocketAddr],
+        fault_args: impl Fn(usize) -> Vec<String>,
+    ) -> Self {
+        let processes = (0..shard_addresses.len())
+            .map(|shard_id| {
+                Command::new(env!("CARGO_BIN_EXE_aptos-executor-service"))
+                    .arg("--shard-id")
+                    .arg(shard_id.to_string())
+                    .arg("--num-shards")
+                    .arg(shard_addresses.len().to_string())
+                    .arg("--num-executor-threads")
+                    .arg("2")
+                    .arg("--coordinator-address")
+                    .arg(coordinator_address.to_string())
+                    .arg("--remote-executor-addresses")
+                    .args(shard_addresses.iter().map(SocketAddr::to_string))
+                    .args(fault_args(shard_id))
+                    .spawn()
+                    .expect("Failed to start shard process")
+            })
+            .collect();
+        let shards = Self { processes };
+        shards.wait_until_listening(shard_addresses);
+        shards
+    }
+
+    fn wait_until_listening(&self, shard_addresses: &[SocketAddr]) {
+        let deadline = Instant::now() + SHARD_STARTUP_TIMEOUT;
+        for address in shard_addresses {
+            while TcpStream::connect(address).is_err() {
+                assert!(
+                    Instant::now() < deadline,
+                    "Shard at {} did not start in time",
+                    address
+                );
+                thread::sleep(Duration::from_millis(100));
+            }
+        }
+    }
+}
+
+impl Drop for ShardProcesses {
+    fn drop(&mut self) {
+        for process in self.processes.iter_mut() {
+            process.kill().ok();
+            process.wait().ok();
+        }
+    }
+}
+
+fn local_address() -> SocketAddr {
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), utils::get_available_port())
+}
+
+fn create_process_remote_executor_shards(
+    num_shards: usize,
+    fault_args: impl Fn(usize) -> Vec<String>,
+    shard_failure_config: ShardFailureConfig,
+) -> (
+    ShardedBlockExecutor<FakeDataStore, RemoteExecutorClient<FakeDataStore>>,
+    ShardProcesses,
+) {
+    let coordinator_address = local_address();
+    let shard_addresses = (0..num_shards).map(|_| local_address()).collect::<Vec<_>>();
+    let shards = ShardProcesses::start(coordinator_address, &shard_addresses, fault_args);
+
+    let controller = NetworkController::new(
+        "remote-executor-coordinator".to_string(),
+        coordinator_address,
+        5000,
+    );
+    let executor_client = RemoteExecutorClient::new(shard_addresses, controller, None)
+        .with_shard_failure_config(shard_failure_config);
+    (ShardedBlockExecutor::new(executor_client), shards)
+}
+
+#[test]
+fn test_process_shards_with_delayed_messages_and_dropped_kv_responses() {
+    let (sharded_block_executor, _shards) = create_process_remote_executor_shards(
+        2,
+        |_| {
+            vec![
+                "--cross-shard-message-delay-ms".to_string(),
+                "5".to_string(),
+                "--kv-response-drop-rate".to_string(),
+                "0.1".to_string(),
+            ]
+        },
+        ShardFailureConfig::default(),
+    );
+
+    test_utils::sharded_block_executor_with_conflict(sharded_block_executor, 2);
+}
+
+#[test]
+fn test_process_shard_crash_executes_block_locally() {
+    let (sharded_block_executor, mut shards) = create_process_remote_executor_shards(
+        2,
+        |shard_id| {
+            if shard_id == 0 {
+                vec!["--crash-at-block".to_string(), "0".to_string()]
+            } else {
+                vec![]
+            }
+        },
+        ShardFailureConfig {
+            result_timeout: Some(Duration::from_secs(10)),
+            max_retries: 1,
+            execute_locally_on_failure: true,
+        },
+    );
+
+    test_utils::test_sharded_block_executor_no_conflict(sharded_block_executor);
+
+    let status = shards.processes[0].wait().unwrap();
+    assert_eq!(status.code(), Some(INJECTED_CRASH_EXIT_CODE));
+}
+
+#[test]
+fn test_process_shard_crash_with_conflict_executes_block_locally() {
+    let (sharded_block_executor, mut shards) = create_process_remote_executor_shards(
+        2,
+        |shard_id| {
+            if shard_id == 0 {
+                vec!["--crash-at-block".to_string(), "0".to_string()]
+            } else {
+                vec![]
+            }
+        },
+        ShardFailureConfig {
+            result_timeout: Some(Duration::from_secs(10)),
+            max_retries: 1,
+            execute_locally_on_failure: true,
+        },
+    );
+
+    // The other shard can't deliver its cross shard messages to the crashed one, and waits for
+    // the messages of the crashed one until the coordinator gives up on the execution.
+    test_utils::sharded_block_executor_with_conflict(sharded_block_executor, 2);
+
+    let status = shards.processes[0].wait().unwrap();
+    assert_eq!(status.code(), Some(INJECTED_CRASH_EXIT_CODE));
+}
+
+#[test]
+fn test_process_shard_crash_fails_block() {
+    let num_shards = 2;
+    let (mut sharded_block_executor, mut shards) = create_process_remote_executor_shards(
+        num_shards,
+        |shard_id| {
+            if shard_id == 1 {
+                vec!["--crash-at-block".to_string(), "0".to_string()]
+            } else {
+                vec![]
+            }
+        },
+        ShardFailureConfig {
+            result_timeout: Some(Duration::from_secs(10)),
+            max_retries: 0,
+            execute_locally_on_failure: false,
+        },
+    );
+
+    let (executor, partitioned_txns) = test_utils::generate_non_conflicting_block(100, num_shards);
+    assert!(sharded_block_executor
+        .execute_block(
+            Arc::new(executor.data_store().clone()),
+            partitioned_txns,
+            2,
+            BlockExecutorConfigFromOnchain::new_no_block_limit(),
+        )
+        .is_err());
+    sharded_block_executor.shutdown();
+
+    let status = shards.processes[1].wait().unwrap();
+    assert_eq!(status.code(), Some(INJECTED_CRASH_EXIT_CODE));
+}
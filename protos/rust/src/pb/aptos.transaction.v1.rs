@@ -151,7 +151,7 @@ pub struct StateCheckpointTransaction {
 pub struct ValidatorTransaction {
     #[prost(message, repeated, tag="3")]
     pub events: ::prost::alloc::vec::Vec<Event>,
-    #[prost(oneof="validator_transaction::ValidatorTransactionType", tags="1, 2")]
+    #[prost(oneof="validator_transaction::ValidatorTransactionType", tags="1, 2, 4")]
     pub validator_transaction_type: ::core::option::Option<validator_transaction::ValidatorTransactionType>,
 }
 /// Nested message and enum types in `ValidatorTransaction`.
@@ -254,12 +254,32 @@ pub mod validator_transaction {
         }
     }
     #[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct EquivocationReport {
+        #[prost(uint64, tag="1")]
+        pub epoch: u64,
+        #[prost(uint64, tag="2")]
+        pub round: u64,
+        /// The kind of the conflicting messages (`vote` or `proposal`).
+        #[prost(string, tag="3")]
+        pub kind: ::prost::alloc::string::String,
+        #[prost(string, tag="4")]
+        pub offender: ::prost::alloc::string::String,
+        #[prost(string, tag="5")]
+        pub reporter: ::prost::alloc::string::String,
+        /// The BCS encoded consensus evidence (i.e., both conflicting signed messages).
+        #[prost(bytes="vec", tag="6")]
+        pub evidence: ::prost::alloc::vec::Vec<u8>,
+    }
+    #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum ValidatorTransactionType {
         #[prost(message, tag="1")]
         ObservedJwkUpdate(ObservedJwkUpdate),
         #[prost(message, tag="2")]
         DkgUpdate(DkgUpdate),
+        #[prost(message, tag="4")]
+        EquivocationReport(EquivocationReport),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
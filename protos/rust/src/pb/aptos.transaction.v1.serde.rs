@@ -7961,6 +7961,9 @@ impl serde::Serialize for ValidatorTransaction {
                 validator_transaction::ValidatorTransactionType::DkgUpdate(v) => {
                     struct_ser.serialize_field("dkgUpdate", v)?;
                 }
+                validator_transaction::ValidatorTransactionType::EquivocationReport(v) => {
+                    struct_ser.serialize_field("equivocationReport", v)?;
+                }
             }
         }
         struct_ser.end()
@@ -7978,6 +7981,8 @@ impl<'de> serde::Deserialize<'de> for ValidatorTransaction {
             "observedJwkUpdate",
             "dkg_update",
             "dkgUpdate",
+            "equivocation_report",
+            "equivocationReport",
         ];
 
         #[allow(clippy::enum_variant_names)]
@@ -7985,6 +7990,7 @@ impl<'de> serde::Deserialize<'de> for ValidatorTransaction {
             Events,
             ObservedJwkUpdate,
             DkgUpdate,
+            EquivocationReport,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
             fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
@@ -8009,6 +8015,7 @@ impl<'de> serde::Deserialize<'de> for ValidatorTransaction {
                             "events" => Ok(GeneratedField::Events),
                             "observedJwkUpdate" | "observed_jwk_update" => Ok(GeneratedField::ObservedJwkUpdate),
                             "dkgUpdate" | "dkg_update" => Ok(GeneratedField::DkgUpdate),
+                            "equivocationReport" | "equivocation_report" => Ok(GeneratedField::EquivocationReport),
                             _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -8050,6 +8057,13 @@ impl<'de> serde::Deserialize<'de> for ValidatorTransaction {
                                 return Err(serde::de::Error::duplicate_field("dkgUpdate"));
                             }
                             validator_transaction_type__ = map.next_value::<::std::option::Option<_>>()?.map(validator_transaction::ValidatorTransactionType::DkgUpdate)
+;
+                        }
+                        GeneratedField::EquivocationReport => {
+                            if validator_transaction_type__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("equivocationReport"));
+                            }
+                            validator_transaction_type__ = map.next_value::<::std::option::Option<_>>()?.map(validator_transaction::ValidatorTransactionType::EquivocationReport)
 ;
                         }
                     }
@@ -8284,6 +8298,188 @@ impl<'de> serde::Deserialize<'de> for validator_transaction::dkg_update::DkgTran
         deserializer.deserialize_struct("aptos.transaction.v1.ValidatorTransaction.DkgUpdate.DkgTranscript", FIELDS, GeneratedVisitor)
     }
 }
+impl serde::Serialize for validator_transaction::EquivocationReport {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.epoch != 0 {
+            len += 1;
+        }
+        if self.round != 0 {
+            len += 1;
+        }
+        if !self.kind.is_empty() {
+            len += 1;
+        }
+        if !self.offender.is_empty() {
+            len += 1;
+        }
+        if !self.reporter.is_empty() {
+            len += 1;
+        }
+        if !self.evidence.is_empty() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("aptos.transaction.v1.ValidatorTransaction.EquivocationReport", len)?;
+        if self.epoch != 0 {
+            struct_ser.serialize_field("epoch", ToString::to_string(&self.epoch).as_str())?;
+        }
+        if self.round != 0 {
+            struct_ser.serialize_field("round", ToString::to_string(&self.round).as_str())?;
+        }
+        if !self.kind.is_empty() {
+            struct_ser.serialize_field("kind", &self.kind)?;
+        }
+        if !self.offender.is_empty() {
+            struct_ser.serialize_field("offender", &self.offender)?;
+        }
+        if !self.reporter.is_empty() {
+            struct_ser.serialize_field("reporter", &self.reporter)?;
+        }
+        if !self.evidence.is_empty() {
+            struct_ser.serialize_field("evidence", pbjson::private::base64::encode(&self.evidence).as_str())?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for validator_transaction::EquivocationReport {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "epoch",
+            "round",
+            "kind",
+            "offender",
+            "reporter",
+            "evidence",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Epoch,
+            Round,
+            Kind,
+            Offender,
+            Reporter,
+            Evidence,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "epoch" => Ok(GeneratedField::Epoch),
+                            "round" => Ok(GeneratedField::Round),
+                            "kind" => Ok(GeneratedField::Kind),
+                            "offender" => Ok(GeneratedField::Offender),
+                            "reporter" => Ok(GeneratedField::Reporter),
+                            "evidence" => Ok(GeneratedField::Evidence),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = validator_transaction::EquivocationReport;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.transaction.v1.ValidatorTransaction.EquivocationReport")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<validator_transaction::EquivocationReport, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut epoch__ = None;
+                let mut round__ = None;
+                let mut kind__ = None;
+                let mut offender__ = None;
+                let mut reporter__ = None;
+                let mut evidence__ = None;
+                while let Some(k) = map.next_key()? {
+                    match k {
+                        GeneratedField::Epoch => {
+                            if epoch__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("epoch"));
+                            }
+                            epoch__ =
+                                Some(map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::Round => {
+                            if round__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("round"));
+                            }
+                            round__ =
+                                Some(map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::Kind => {
+                            if kind__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("kind"));
+                            }
+                            kind__ = Some(map.next_value()?);
+                        }
+                        GeneratedField::Offender => {
+                            if offender__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("offender"));
+                            }
+                            offender__ = Some(map.next_value()?);
+                        }
+                        GeneratedField::Reporter => {
+                            if reporter__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("reporter"));
+                            }
+                            reporter__ = Some(map.next_value()?);
+                        }
+                        GeneratedField::Evidence => {
+                            if evidence__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("evidence"));
+                            }
+                            evidence__ =
+                                Some(map.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(validator_transaction::EquivocationReport {
+                    epoch: epoch__.unwrap_or_default(),
+                    round: round__.unwrap_or_default(),
+                    kind: kind__.unwrap_or_default(),
+                    offender: offender__.unwrap_or_default(),
+                    reporter: reporter__.unwrap_or_default(),
+                    evidence: evidence__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.transaction.v1.ValidatorTransaction.EquivocationReport", FIELDS, GeneratedVisitor)
+    }
+}
 impl serde::Serialize for validator_transaction::ObservedJwkUpdate {
     #[allow(deprecated)]
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::network_controller::{metrics::NETWORK_HANDLER_TIMER, Message, MessageType};
-use aptos_logger::{error, info};
+use aptos_logger::{error, info, warn};
 use aptos_protos::remote_executor::v1::{
     network_message_service_client::NetworkMessageServiceClient,
     network_message_service_server::{NetworkMessageService, NetworkMessageServiceServer},
@@ -12,7 +12,11 @@ use crossbeam_channel::Sender;
 use std::{
     collections::HashMap,
     net::SocketAddr,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
 };
 use tokio::{runtime::Runtime, sync::oneshot};
 use tonic::{
@@ -21,6 +25,10 @@ use tonic::{
 };
 
 const MAX_MESSAGE_SIZE: usize = 1024 * 1024 * 80;
+/// Number of times sending a message is retried before giving up on it.
+const MAX_SEND_RETRIES: u32 = 5;
+/// Backoff before the first retry of sending a message, doubled for every further retry.
+const INITIAL_SEND_RETRY_BACKOFF: Duration = Duration::from_millis(10);
 
 pub struct GRPCNetworkMessageServiceServerWrapper {
     inbound_handlers: Arc<Mutex<HashMap<MessageType, Sender<Message>>>>,
@@ -118,14 +126,21 @@ impl NetworkMessageService for GRPCNetworkMessageServiceServerWrapper {
 pub struct GRPCNetworkMessageServiceClientWrapper {
     remote_addr: String,
     remote_channel: NetworkMessageServiceClient<Channel>,
+    // Whether messages which can't be delivered are dropped, instead of panicking.
+    drop_undeliverable_messages: Arc<AtomicBool>,
 }
 
 impl GRPCNetworkMessageServiceClientWrapper {
-    pub fn new(rt: &Runtime, remote_addr: SocketAddr) -> Self {
+    pub fn new(
+        rt: &Runtime,
+        remote_addr: SocketAddr,
+        drop_undeliverable_messages: Arc<AtomicBool>,
+    ) -> Self {
         Self {
             remote_addr: remote_addr.to_string(),
             remote_channel: rt
                 .block_on(async { Self::get_channel(format!("http://{}", remote_addr)).await }),
+            drop_undeliverable_messages,
         }
     }
 
@@ -143,19 +158,39 @@ impl GRPCNetworkMessageServiceClientWrapper {
         message: Message,
         mt: &MessageType,
     ) {
-        let request = tonic::Request::new(NetworkMessage {
-            message: message.data,
-            message_type: mt.get_type(),
-        });
-        // TODO: Retry with exponential backoff on failures
-        match self.remote_channel.simple_msg_exchange(request).await {
-            Ok(_) => {},
-            Err(e) => {
-                panic!(
-                    "Error '{}' sending message to {} on node {:?}",
-                    e, self.remote_addr, sender_addr
-                );
-            },
+        // Retry with exponential backoff on failures, e.g. while the remote server is starting.
+        // If the remote server remains unreachable, e.g. because it crashed, the message is only
+        // dropped if the service detects and recovers from lost messages, so that the messages to
+        // other servers are still delivered.
+        let mut backoff = INITIAL_SEND_RETRY_BACKOFF;
+        for attempt in 0..=MAX_SEND_RETRIES {
+            let request = tonic::Request::new(NetworkMessage {
+                message: message.data.clone(),
+                message_type: mt.get_type(),
+            });
+            match self.remote_channel.simple_msg_exchange(request).await {
+                Ok(_) => return,
+                Err(e) if attempt < MAX_SEND_RETRIES => {
+                    warn!(
+                        "Error '{}' sending message to {} on node {:?}, retrying in {:?}",
+                        e, self.remote_addr, sender_addr, backoff
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                },
+                Err(e) if self.drop_undeliverable_messages.load(Ordering::Relaxed) => {
+                    error!(
+                        "Error '{}' sending message to {} on node {:?}, dropping the message",
+                        e, self.remote_addr, sender_addr
+                    );
+                },
+                Err(e) => {
+                    panic!(
+                        "Error '{}' sending message to {} on node {:?}",
+                        e, self.remote_addr, sender_addr
+                    );
+                },
+            }
         }
     }
 }
@@ -190,7 +225,11 @@ fn basic_test() {
         server_shutdown_rx,
     );
 
-    let mut grpc_client = GRPCNetworkMessageServiceClientWrapper::new(&rt, server_addr);
+    let mut grpc_client = GRPCNetworkMessageServiceClientWrapper::new(
+        &rt,
+        server_addr,
+        Arc::new(AtomicBool::new(false)),
+    );
 
     let client_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), utils::get_available_port());
     let test_message_content = "test1".as_bytes().to_vec();
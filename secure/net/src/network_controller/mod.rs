@@ -9,7 +9,10 @@ use crossbeam_channel::{unbounded, Receiver, Sender};
 use serde::{Deserialize, Serialize};
 use std::{
     net::SocketAddr,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
 };
 use tokio::{runtime::Runtime, sync::oneshot};
 
@@ -88,6 +91,7 @@ pub struct NetworkController {
     inbound_server_shutdown_tx: Option<oneshot::Sender<()>>,
     outbound_task_shutdown_tx: Option<Sender<Message>>,
     listen_addr: SocketAddr,
+    drop_undeliverable_messages: Arc<AtomicBool>,
 }
 
 impl NetworkController {
@@ -97,7 +101,13 @@ impl NetworkController {
             listen_addr,
             timeout_ms,
         )));
-        let outbound_handler = OutboundHandler::new(service, listen_addr, inbound_handler.clone());
+        let drop_undeliverable_messages = Arc::new(AtomicBool::new(false));
+        let outbound_handler = OutboundHandler::new(
+            service,
+            listen_addr,
+            inbound_handler.clone(),
+            drop_undeliverable_messages.clone(),
+        );
         info!("Network controller created for node {}", listen_addr);
         Self {
             inbound_handler,
@@ -108,9 +118,25 @@ impl NetworkController {
             inbound_server_shutdown_tx: None,
             outbound_task_shutdown_tx: None,
             listen_addr,
+            drop_undeliverable_messages,
         }
     }
 
+    /// Sets whether messages which can't be delivered to a remote node, e.g. because it crashed,
+    /// are dropped after retrying. Otherwise, which is the default, sending them panics. Only
+    /// services which detect and recover from lost messages should drop them. Can be changed
+    /// while the controller is running.
+    pub fn set_drop_undeliverable_messages(&self, drop_undeliverable_messages: bool) {
+        self.drop_undeliverable_messages
+            .store(drop_undeliverable_messages, Ordering::Relaxed);
+    }
+
+    /// The switch behind [Self::set_drop_undeliverable_messages], for the components which set it
+    /// after the controller is moved.
+    pub fn drop_undeliverable_messages_switch(&self) -> Arc<AtomicBool> {
+        self.drop_undeliverable_messages.clone()
+    }
+
     pub fn create_outbound_channel(
         &mut self,
         remote_peer_addr: SocketAddr,
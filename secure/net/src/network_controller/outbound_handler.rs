@@ -13,7 +13,7 @@ use std::{
     collections::{HashMap, HashSet},
     mem,
     net::SocketAddr,
-    sync::{Arc, Mutex},
+    sync::{atomic::AtomicBool, Arc, Mutex},
 };
 use tokio::runtime::Runtime;
 
@@ -24,6 +24,7 @@ pub struct OutboundHandler {
     // Used to route outgoing messages to correct network client with the correct message type
     handlers: Vec<(Receiver<Message>, SocketAddr, MessageType)>,
     inbound_handler: Arc<Mutex<InboundHandler>>,
+    drop_undeliverable_messages: Arc<AtomicBool>,
 }
 
 impl OutboundHandler {
@@ -31,6 +32,7 @@ impl OutboundHandler {
         service: String,
         listen_addr: SocketAddr,
         inbound_handler: Arc<Mutex<InboundHandler>>,
+        drop_undeliverable_messages: Arc<AtomicBool>,
     ) -> Self {
         Self {
             _service: service,
@@ -38,6 +40,7 @@ impl OutboundHandler {
             address: listen_addr,
             handlers: Vec::new(),
             inbound_handler,
+            drop_undeliverable_messages,
         }
     }
 
@@ -71,7 +74,11 @@ impl OutboundHandler {
         self.remote_addresses.iter().for_each(|remote_addr| {
             grpc_clients.insert(
                 *remote_addr,
-                GRPCNetworkMessageServiceClientWrapper::new(rt, *remote_addr),
+                GRPCNetworkMessageServiceClientWrapper::new(
+                    rt,
+                    *remote_addr,
+                    self.drop_undeliverable_messages.clone(),
+                ),
             );
         });
 
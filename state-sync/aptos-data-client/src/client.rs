@@ -13,11 +13,11 @@ use crate::{
     metrics::{
         increment_request_counter, set_gauge, start_request_timer, PRIORITIZED_PEER, REGULAR_PEER,
     },
+    peer_selection,
+    peer_selection::{DefaultPeerSelectionPolicy, PeerSelectionPolicy},
     peer_states::{ErrorType, PeerStates},
     poller::DataSummaryPoller,
-    priority,
     priority::PeerPriority,
-    utils,
 };
 use aptos_config::{
     config::{AptosDataClientConfig, BaseConfig},
@@ -36,6 +36,7 @@ use aptos_storage_service_types::{
     requests::{
         DataRequest, EpochEndingLedgerInfoRequest, NewTransactionOutputsWithProofRequest,
         NewTransactionsOrOutputsWithProofRequest, NewTransactionsWithProofRequest,
+        StateValueWithProofRequest, StateValuesByPrefixWithProofRequest,
         StateValuesWithProofRequest, StorageServiceRequest,
         SubscribeTransactionOutputsWithProofRequest,
         SubscribeTransactionsOrOutputsWithProofRequest, SubscribeTransactionsWithProofRequest,
@@ -47,9 +48,15 @@ use aptos_storage_service_types::{
 };
 use aptos_time_service::TimeService;
 use aptos_types::{
+    account_address::AccountAddress,
     epoch_change::EpochChangeProof,
     ledger_info::LedgerInfoWithSignatures,
-    state_store::state_value::StateValueChunkWithProof,
+    state_store::{
+        state_key::StateKey,
+        state_value::{
+            StateValueChunkWithProof, StateValueWithProof, StateValuesByPrefixWithProof,
+        },
+    },
     transaction::{TransactionListWithProof, TransactionOutputListWithProof, Version},
 };
 use arc_swap::ArcSwap;
@@ -89,12 +96,12 @@ const PEER_METRICS_FREQ_SECS: u64 = 5; // The frequency to update peer metrics a
 /// and/or threads.
 #[derive(Clone, Debug)]
 pub struct AptosDataClient {
-    /// The base config of the node.
-    base_config: Arc<BaseConfig>,
     /// The config for the AptosNet data client.
     data_client_config: Arc<AptosDataClientConfig>,
     /// The underlying AptosNet storage service client.
     storage_service_client: StorageServiceClient<NetworkClient<StorageServiceMessage>>,
+    /// The policy used to prioritize and select peers for requests.
+    peer_selection_policy: Arc<dyn PeerSelectionPolicy>,
     /// The state of the active subscription stream.
     active_subscription_state: Arc<Mutex<Option<SubscriptionState>>>,
     /// All of the data-client specific data we have on each network peer.
@@ -108,6 +115,7 @@ pub struct AptosDataClient {
 }
 
 impl AptosDataClient {
+    /// Creates a new data client that uses the default peer selection policy
     pub fn new(
         data_client_config: AptosDataClientConfig,
         base_config: BaseConfig,
@@ -116,15 +124,40 @@ impl AptosDataClient {
         storage_service_client: StorageServiceClient<NetworkClient<StorageServiceMessage>>,
         runtime: Option<Handle>,
     ) -> (Self, DataSummaryPoller) {
-        // Wrap the configs in an Arc (to be shared across components)
-        let base_config = Arc::new(base_config);
+        // Create the default peer selection policy
+        let peer_selection_policy = Arc::new(DefaultPeerSelectionPolicy::new(
+            Arc::new(base_config),
+            Arc::new(data_client_config),
+            storage_service_client.get_peers_and_metadata(),
+        ));
+
+        Self::new_with_peer_selection_policy(
+            data_client_config,
+            time_service,
+            storage,
+            storage_service_client,
+            runtime,
+            peer_selection_policy,
+        )
+    }
+
+    /// Creates a new data client that uses the given peer selection policy
+    pub fn new_with_peer_selection_policy(
+        data_client_config: AptosDataClientConfig,
+        time_service: TimeService,
+        storage: Arc<dyn DbReader>,
+        storage_service_client: StorageServiceClient<NetworkClient<StorageServiceMessage>>,
+        runtime: Option<Handle>,
+        peer_selection_policy: Arc<dyn PeerSelectionPolicy>,
+    ) -> (Self, DataSummaryPoller) {
+        // Wrap the config in an Arc (to be shared across components)
         let data_client_config = Arc::new(data_client_config);
 
         // Create the data client
         let data_client = Self {
-            base_config,
             data_client_config: data_client_config.clone(),
             storage_service_client: storage_service_client.clone(),
+            peer_selection_policy,
             active_subscription_state: Arc::new(Mutex::new(None)),
             peer_states: Arc::new(PeerStates::new(data_client_config.clone())),
             global_summary_cache: Arc::new(ArcSwap::from(Arc::new(GlobalDataSummary::empty()))),
@@ -231,24 +264,6 @@ impl AptosDataClient {
         Ok(())
     }
 
-    /// Chooses peers randomly weighted by distance and
-    /// latency from the given set of serviceable peers.
-    fn choose_random_peers_by_distance_and_latency(
-        &self,
-        serviceable_peers: HashSet<PeerNetworkId>,
-        num_peers_to_choose: usize,
-    ) -> HashSet<PeerNetworkId> {
-        // Choose peers weighted by distance and latency
-        let selected_peers = utils::choose_random_peers_by_distance_and_latency(
-            serviceable_peers.clone(),
-            self.get_peers_and_metadata(),
-            num_peers_to_choose,
-        );
-
-        // Extend the selected peers with random peers (if necessary)
-        utils::extend_with_random_peers(selected_peers, serviceable_peers, num_peers_to_choose)
-    }
-
     /// Chooses several connected peers to service the given request.
     /// Returns an error if no single peer can service the request.
     pub(crate) fn choose_peers_for_request(
@@ -316,100 +331,28 @@ impl AptosDataClient {
             )));
         }
 
-        // Choose the peers based on the request type
-        if request.data_request.is_optimistic_fetch() {
-            self.choose_peers_for_optimistic_fetch(
-                request,
-                serviceable_peers_by_priorities,
-                num_peers_for_request,
-            )
-        } else {
-            self.choose_peers_for_specific_data_request(
-                request,
-                serviceable_peers_by_priorities,
-                num_peers_for_request,
-            )
-        }
-    }
-
-    /// Chooses several peers to service the given optimistic fetch
-    /// request. Peers are selected first by priority, and then by
-    /// validator distance and latency (within priority groups).
-    fn choose_peers_for_optimistic_fetch(
-        &self,
-        request: &StorageServiceRequest,
-        serviceable_peers_by_priorities: Vec<HashSet<PeerNetworkId>>,
-        num_peers_for_request: usize,
-    ) -> crate::error::Result<HashSet<PeerNetworkId>, Error> {
-        // Select peers by priority (starting with the highest priority first)
-        let mut selected_peers = HashSet::new();
-        for serviceable_peers in serviceable_peers_by_priorities {
-            // Select peers by distance and latency
-            let num_peers_remaining = num_peers_for_request.saturating_sub(selected_peers.len());
-            let peers = self.choose_random_peers_by_distance_and_latency(
-                serviceable_peers,
-                num_peers_remaining,
-            );
-
-            // Add the peers to the entire set
-            selected_peers.extend(peers);
-
-            // If we have selected enough peers, return early
-            if selected_peers.len() >= num_peers_for_request {
-                return Ok(selected_peers);
-            }
-        }
-
-        // If selected peers is empty, return an error
-        if !selected_peers.is_empty() {
-            Ok(selected_peers)
-        } else {
-            Err(Error::DataIsUnavailable(format!(
-                "Unable to select peers for optimistic fetch request: {:?}",
-                request
-            )))
-        }
-    }
-
-    /// Chooses several peers to service the specific data request.
-    /// Peers are selected first by priority, and then by latency
-    /// (within priority groups).
-    fn choose_peers_for_specific_data_request(
-        &self,
-        request: &StorageServiceRequest,
-        serviceable_peers_by_priorities: Vec<HashSet<PeerNetworkId>>,
-        num_peers_for_request: usize,
-    ) -> crate::error::Result<HashSet<PeerNetworkId>, Error> {
-        // Select peers by priority (starting with the highest priority first)
-        let mut selected_peers = HashSet::new();
-        for serviceable_peers in serviceable_peers_by_priorities {
-            // Select peers by distance and latency
-            let num_peers_remaining = num_peers_for_request.saturating_sub(selected_peers.len());
-            let peers = self.choose_random_peers_by_latency(serviceable_peers, num_peers_remaining);
-
-            // Add the peers to the entire set
-            selected_peers.extend(peers);
-
-            // If we have selected enough peers, return early
-            if selected_peers.len() >= num_peers_for_request {
-                return Ok(selected_peers);
-            }
-        }
+        // Choose the peers by priority (using the peer selection policy)
+        let selected_peers = peer_selection::choose_peers_by_priority(
+            self.peer_selection_policy.as_ref(),
+            request,
+            serviceable_peers_by_priorities,
+            num_peers_for_request,
+        );
 
         // If selected peers is empty, return an error
         if !selected_peers.is_empty() {
             Ok(selected_peers)
         } else {
             Err(Error::DataIsUnavailable(format!(
-                "Unable to select peers for specific data request: {:?}",
+                "Unable to select peers for request: {:?}",
                 request
             )))
         }
     }
 
     /// Chooses a single peer to service the given subscription request.
-    /// Peers are selected first by priority, and then by validator
-    /// distance and latency (within priority groups).
+    /// Peers are selected first by priority, and then by the peer
+    /// selection policy (within priority groups).
     fn choose_peer_for_subscription_request(
         &self,
         request: &StorageServiceRequest,
@@ -490,7 +433,8 @@ impl AptosDataClient {
 
         // Otherwise, choose a new peer to handle the subscription request
         let selected_peer = self
-            .choose_random_peers_by_distance_and_latency(serviceable_peers, 1)
+            .peer_selection_policy
+            .choose_peers(request, serviceable_peers, 1)
             .into_iter()
             .next();
 
@@ -503,25 +447,6 @@ impl AptosDataClient {
         Ok(selected_peer)
     }
 
-    /// Chooses peers randomly weighted by latency from the given set of serviceable peers
-    fn choose_random_peers_by_latency(
-        &self,
-        serviceable_peers: HashSet<PeerNetworkId>,
-        num_peers_to_choose: usize,
-    ) -> HashSet<PeerNetworkId> {
-        // Choose peers weighted by latency
-        let selected_peers = utils::choose_peers_by_latency(
-            self.data_client_config.clone(),
-            num_peers_to_choose as u64,
-            serviceable_peers.clone(),
-            self.get_peers_and_metadata(),
-            true,
-        );
-
-        // Extend the selected peers with random peers (if necessary)
-        utils::extend_with_random_peers(selected_peers, serviceable_peers, num_peers_to_choose)
-    }
-
     /// Identifies the peers with the specified priority that can service the given request
     fn identify_serviceable(
         &self,
@@ -568,11 +493,7 @@ impl AptosDataClient {
         let mut peers_by_priorities = BTreeMap::new();
         for peer in all_connected_peers {
             // Get the priority for the peer
-            let priority = priority::get_peer_priority(
-                self.base_config.clone(),
-                self.get_peers_and_metadata(),
-                &peer,
-            );
+            let priority = self.peer_selection_policy.get_peer_priority(&peer);
 
             // Insert the peer into the priority map
             peers_by_priorities
@@ -596,11 +517,11 @@ impl AptosDataClient {
         let mut priority_peers = hashset![];
         let mut regular_peers = hashset![];
         for peer in all_connected_peers {
-            if priority::is_high_priority_peer(
-                self.base_config.clone(),
-                self.get_peers_and_metadata(),
-                &peer,
-            ) {
+            if self
+                .peer_selection_policy
+                .get_peer_priority(&peer)
+                .is_high_priority()
+            {
                 priority_peers.insert(peer);
             } else {
                 regular_peers.insert(peer);
@@ -773,6 +694,7 @@ impl AptosDataClient {
         self.update_sent_request_metrics(peer, &request);
 
         // Send the request and process the result
+        let request_start_time = self.time_service.now();
         let result = self
             .storage_service_client
             .send_request(
@@ -794,6 +716,14 @@ impl AptosDataClient {
                 // Update the received response metrics
                 self.update_received_response_metrics(peer, &request);
 
+                // Notify the peer selection policy of the response
+                let response_latency = self.time_service.now().duration_since(request_start_time);
+                self.peer_selection_policy.notify_response_received(
+                    &peer,
+                    &request,
+                    response_latency,
+                );
+
                 // For now, record all responses that at least pass the data
                 // client layer successfully. An alternative might also have the
                 // consumer notify both success and failure via the callback.
@@ -810,7 +740,9 @@ impl AptosDataClient {
                     peer,
                     request,
                 };
-                let context = ResponseContext::new(id, Box::new(response_callback));
+                let response_size_bytes = get_response_size_bytes(&response);
+                let context =
+                    ResponseContext::new(id, response_size_bytes, Box::new(response_callback));
                 Ok(Response::new(context, response))
             },
             Err(error) => {
@@ -848,6 +780,8 @@ impl AptosDataClient {
                     peer,
                 );
 
+                self.peer_selection_policy
+                    .notify_request_failed(&peer, &request);
                 self.notify_bad_response(id, peer, &request, ErrorType::NotUseful);
                 Err(client_error)
             },
@@ -1013,6 +947,35 @@ impl AptosDataClientInterface for AptosDataClient {
             .await
     }
 
+    async fn get_state_values_by_prefix_with_proof(
+        &self,
+        version: u64,
+        account_address: AccountAddress,
+        start_key: Option<StateKey>,
+        request_timeout_ms: u64,
+    ) -> crate::error::Result<Response<StateValuesByPrefixWithProof>> {
+        let data_request =
+            DataRequest::GetStateValuesByPrefixWithProof(StateValuesByPrefixWithProofRequest {
+                version,
+                account_address,
+                start_key,
+            });
+        self.create_and_send_storage_request(request_timeout_ms, data_request)
+            .await
+    }
+
+    async fn get_state_value_with_proof(
+        &self,
+        version: u64,
+        state_key: StateKey,
+        request_timeout_ms: u64,
+    ) -> crate::error::Result<Response<StateValueWithProof>> {
+        let data_request =
+            DataRequest::GetStateValueWithProof(StateValueWithProofRequest { version, state_key });
+        self.create_and_send_storage_request(request_timeout_ms, data_request)
+            .await
+    }
+
     async fn get_transaction_outputs_with_proof(
         &self,
         proof_version: Version,
@@ -1179,6 +1142,12 @@ impl SubscriptionState {
     }
 }
 
+/// Returns the size (in bytes) of the given storage service response, as sent
+/// over the network. If the response is compressed, this is the compressed size.
+fn get_response_size_bytes(response: &StorageServiceResponse) -> u64 {
+    bcs::serialized_size(response).unwrap_or(0) as u64
+}
+
 /// Updates the metrics for the number of connected peers (priority and regular)
 fn update_priority_and_regular_peer_metrics(
     priority_peers: &HashSet<PeerNetworkId>,
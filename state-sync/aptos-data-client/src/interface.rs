@@ -4,8 +4,14 @@
 use crate::{error, error::Error, global_summary::GlobalDataSummary};
 use aptos_storage_service_types::{responses::TransactionOrOutputListWithProof, Epoch};
 use aptos_types::{
+    account_address::AccountAddress,
     ledger_info::LedgerInfoWithSignatures,
-    state_store::state_value::StateValueChunkWithProof,
+    state_store::{
+        state_key::StateKey,
+        state_value::{
+            StateValueChunkWithProof, StateValueWithProof, StateValuesByPrefixWithProof,
+        },
+    },
     transaction::{TransactionListWithProof, TransactionOutputListWithProof, Version},
 };
 use async_trait::async_trait;
@@ -87,6 +93,33 @@ pub trait AptosDataClientInterface {
         request_timeout_ms: u64,
     ) -> error::Result<Response<StateValueChunkWithProof>>;
 
+    /// Fetches the state values (with proofs) under the specified account
+    /// at the given version, starting at `start_key` (inclusive), or the
+    /// first key under the account if no start key is specified. In some
+    /// cases, not all state values may be returned (e.g., to tolerate
+    /// network or chunk limits), in which case the response will contain
+    /// the key to resume from. If the data cannot be fetched, an error is
+    /// returned.
+    async fn get_state_values_by_prefix_with_proof(
+        &self,
+        version: u64,
+        account_address: AccountAddress,
+        start_key: Option<StateKey>,
+        request_timeout_ms: u64,
+    ) -> error::Result<Response<StateValuesByPrefixWithProof>>;
+
+    /// Fetches the state value (with proof) for the given key at the
+    /// specified version. If the key does not exist at the version, the
+    /// response will prove its absence. The proof is relative to the
+    /// ledger at the same version. If the data cannot be fetched, an
+    /// error is returned.
+    async fn get_state_value_with_proof(
+        &self,
+        version: u64,
+        state_key: StateKey,
+        request_timeout_ms: u64,
+    ) -> error::Result<Response<StateValueWithProof>>;
+
     /// Fetches a transaction output list with proof, with transaction
     /// outputs from start to end versions (inclusive). The proof is relative
     /// to the specified `proof_version`. In some cases, fewer outputs may be
@@ -214,16 +247,24 @@ pub struct ResponseContext {
     /// A unique identifier for this request/response pair. Intended mostly for
     /// debugging.
     pub id: ResponseId,
+    /// The size (in bytes) of the response sent over the network (i.e., the
+    /// serialized storage service response, which may be compressed).
+    pub response_size_bytes: u64,
     /// A callback for notifying the data-client source about an error with this
     /// response.
     pub response_callback: Box<dyn ResponseCallback>,
 }
 
 impl ResponseContext {
-    pub fn new(id: ResponseId, response_callback: Box<dyn ResponseCallback>) -> Self {
+    pub fn new(
+        id: ResponseId,
+        response_size_bytes: u64,
+        response_callback: Box<dyn ResponseCallback>,
+    ) -> Self {
         Self {
             creation_time: Instant::now(),
             id,
+            response_size_bytes,
             response_callback,
         }
     }
@@ -270,6 +311,8 @@ pub enum ResponsePayload {
     StateValuesWithProof(StateValueChunkWithProof),
     TransactionOutputsWithProof(TransactionOutputListWithProof),
     TransactionsWithProof(TransactionListWithProof),
+    StateValuesByPrefixWithProof(StateValuesByPrefixWithProof),
+    StateValueWithProof(StateValueWithProof),
 }
 
 impl ResponsePayload {
@@ -284,6 +327,8 @@ impl ResponsePayload {
             Self::StateValuesWithProof(_) => "state_values_with_proof",
             Self::TransactionOutputsWithProof(_) => "transaction_outputs_with_proof",
             Self::TransactionsWithProof(_) => "transactions_with_proof",
+            Self::StateValuesByPrefixWithProof(_) => "state_values_by_prefix_with_proof",
+            Self::StateValueWithProof(_) => "state_value_with_proof",
         }
     }
 
@@ -312,6 +357,10 @@ impl ResponsePayload {
             Self::TransactionsWithProof(transactions_with_proof) => {
                 transactions_with_proof.transactions.len()
             },
+            Self::StateValuesByPrefixWithProof(state_values_with_proof) => {
+                state_values_with_proof.raw_values.len()
+            },
+            Self::StateValueWithProof(_) => 1,
         }
     }
 }
@@ -322,6 +371,18 @@ impl From<StateValueChunkWithProof> for ResponsePayload {
     }
 }
 
+impl From<StateValuesByPrefixWithProof> for ResponsePayload {
+    fn from(inner: StateValuesByPrefixWithProof) -> Self {
+        Self::StateValuesByPrefixWithProof(inner)
+    }
+}
+
+impl From<StateValueWithProof> for ResponsePayload {
+    fn from(inner: StateValueWithProof) -> Self {
+        Self::StateValueWithProof(inner)
+    }
+}
+
 impl From<Vec<LedgerInfoWithSignatures>> for ResponsePayload {
     fn from(inner: Vec<LedgerInfoWithSignatures>) -> Self {
         Self::EpochEndingLedgerInfos(inner)
@@ -0,0 +1,222 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{priority, priority::PeerPriority, utils};
+use aptos_config::{
+    config::{AptosDataClientConfig, BaseConfig},
+    network_id::PeerNetworkId,
+};
+use aptos_network::application::storage::PeersAndMetadata;
+use aptos_storage_service_types::requests::StorageServiceRequest;
+use aptos_types::PeerId;
+use std::{collections::HashSet, fmt::Debug, sync::Arc, time::Duration};
+
+/// A policy that determines which peers the data client sends requests to.
+///
+/// Peer selection happens in two stages: (i) every connected peer is assigned a
+/// priority (see `get_peer_priority`); and (ii) for each priority group, starting
+/// with the highest, the policy chooses from the peers that can service the request
+/// (see `choose_peers`) until enough peers have been selected. The data client also
+/// notifies the policy of each response (or failure), which allows policies to
+/// adapt to the observed behaviour of peers (e.g., to weight peers by throughput).
+pub trait PeerSelectionPolicy: Debug + Send + Sync {
+    /// Returns the priority for the specified peer
+    fn get_peer_priority(&self, peer: &PeerNetworkId) -> PeerPriority;
+
+    /// Chooses (at most) the specified number of peers from the given set of
+    /// serviceable peers (all of which share the same priority) to service the request.
+    fn choose_peers(
+        &self,
+        request: &StorageServiceRequest,
+        serviceable_peers: HashSet<PeerNetworkId>,
+        num_peers_to_choose: usize,
+    ) -> HashSet<PeerNetworkId>;
+
+    /// Notifies the policy that the given peer responded to the request
+    /// after the specified latency. By default, this is ignored.
+    fn notify_response_received(
+        &self,
+        _peer: &PeerNetworkId,
+        _request: &StorageServiceRequest,
+        _response_latency: Duration,
+    ) {
+    }
+
+    /// Notifies the policy that the given peer failed to
+    /// respond to the request. By default, this is ignored.
+    fn notify_request_failed(&self, _peer: &PeerNetworkId, _request: &StorageServiceRequest) {}
+}
+
+/// The default peer selection policy. Peers are prioritized according to the
+/// node's role and network (see `priority::get_peer_priority`). Within each
+/// priority group, optimistic fetch and subscription requests are sent to peers
+/// chosen randomly by validator distance and latency, and all other requests
+/// are sent to peers chosen randomly by latency.
+#[derive(Clone, Debug)]
+pub struct DefaultPeerSelectionPolicy {
+    base_config: Arc<BaseConfig>,
+    data_client_config: Arc<AptosDataClientConfig>,
+    peers_and_metadata: Arc<PeersAndMetadata>,
+}
+
+impl DefaultPeerSelectionPolicy {
+    pub fn new(
+        base_config: Arc<BaseConfig>,
+        data_client_config: Arc<AptosDataClientConfig>,
+        peers_and_metadata: Arc<PeersAndMetadata>,
+    ) -> Self {
+        Self {
+            base_config,
+            data_client_config,
+            peers_and_metadata,
+        }
+    }
+}
+
+impl PeerSelectionPolicy for DefaultPeerSelectionPolicy {
+    fn get_peer_priority(&self, peer: &PeerNetworkId) -> PeerPriority {
+        priority::get_peer_priority(
+            self.base_config.clone(),
+            self.peers_and_metadata.clone(),
+            peer,
+        )
+    }
+
+    fn choose_peers(
+        &self,
+        request: &StorageServiceRequest,
+        serviceable_peers: HashSet<PeerNetworkId>,
+        num_peers_to_choose: usize,
+    ) -> HashSet<PeerNetworkId> {
+        // Choose peers by distance and latency (for optimistic fetches and
+        // subscriptions), or by latency only (for all other requests).
+        let data_request = &request.data_request;
+        let selected_peers =
+            if data_request.is_optimistic_fetch() || data_request.is_subscription_request() {
+                utils::choose_random_peers_by_distance_and_latency(
+                    serviceable_peers.clone(),
+                    self.peers_and_metadata.clone(),
+                    num_peers_to_choose,
+                )
+            } else {
+                utils::choose_peers_by_latency(
+                    self.data_client_config.clone(),
+                    num_peers_to_choose as u64,
+                    serviceable_peers.clone(),
+                    self.peers_and_metadata.clone(),
+                    true,
+                )
+            };
+
+        // Extend the selected peers with random peers (if necessary)
+        utils::extend_with_random_peers(selected_peers, serviceable_peers, num_peers_to_choose)
+    }
+}
+
+/// A peer selection policy that favours a set of operator preferred peers
+/// (e.g., the other VFNs run by the same validator operator, or a pinned
+/// set of trusted peers). Preferred peers are always assigned the highest
+/// priority, and are chosen before any other peers within the same priority
+/// group. All other decisions are delegated to the inner policy.
+#[derive(Clone, Debug)]
+pub struct PreferredPeersPolicy {
+    preferred_peers: HashSet<PeerId>,
+    inner_policy: Arc<dyn PeerSelectionPolicy>,
+}
+
+impl PreferredPeersPolicy {
+    pub fn new(
+        preferred_peers: HashSet<PeerId>,
+        inner_policy: Arc<dyn PeerSelectionPolicy>,
+    ) -> Self {
+        Self {
+            preferred_peers,
+            inner_policy,
+        }
+    }
+
+    /// Returns true iff the given peer is a preferred peer
+    fn is_preferred_peer(&self, peer: &PeerNetworkId) -> bool {
+        self.preferred_peers.contains(&peer.peer_id())
+    }
+}
+
+impl PeerSelectionPolicy for PreferredPeersPolicy {
+    fn get_peer_priority(&self, peer: &PeerNetworkId) -> PeerPriority {
+        if self.is_preferred_peer(peer) {
+            PeerPriority::HighPriority
+        } else {
+            self.inner_policy.get_peer_priority(peer)
+        }
+    }
+
+    fn choose_peers(
+        &self,
+        request: &StorageServiceRequest,
+        serviceable_peers: HashSet<PeerNetworkId>,
+        num_peers_to_choose: usize,
+    ) -> HashSet<PeerNetworkId> {
+        // Split the serviceable peers into preferred and other peers
+        let (preferred_peers, other_peers): (HashSet<_>, HashSet<_>) = serviceable_peers
+            .into_iter()
+            .partition(|peer| self.is_preferred_peer(peer));
+
+        // Choose the preferred peers first
+        let mut selected_peers =
+            self.inner_policy
+                .choose_peers(request, preferred_peers, num_peers_to_choose);
+
+        // Fill any remaining slots using the other peers
+        let num_peers_remaining = num_peers_to_choose.saturating_sub(selected_peers.len());
+        if num_peers_remaining > 0 {
+            selected_peers.extend(self.inner_policy.choose_peers(
+                request,
+                other_peers,
+                num_peers_remaining,
+            ));
+        }
+
+        selected_peers
+    }
+
+    fn notify_response_received(
+        &self,
+        peer: &PeerNetworkId,
+        request: &StorageServiceRequest,
+        response_latency: Duration,
+    ) {
+        self.inner_policy
+            .notify_response_received(peer, request, response_latency)
+    }
+
+    fn notify_request_failed(&self, peer: &PeerNetworkId, request: &StorageServiceRequest) {
+        self.inner_policy.notify_request_failed(peer, request)
+    }
+}
+
+/// Chooses (at most) the specified number of peers to service the request, using
+/// the given policy. Peers are selected first by priority (starting with the highest
+/// priority group), and then by the policy (within each priority group).
+pub fn choose_peers_by_priority(
+    policy: &dyn PeerSelectionPolicy,
+    request: &StorageServiceRequest,
+    serviceable_peers_by_priorities: Vec<HashSet<PeerNetworkId>>,
+    num_peers_for_request: usize,
+) -> HashSet<PeerNetworkId> {
+    let mut selected_peers = HashSet::new();
+    for serviceable_peers in serviceable_peers_by_priorities {
+        // Select peers using the policy
+        let num_peers_remaining = num_peers_for_request.saturating_sub(selected_peers.len());
+        let peers = policy.choose_peers(request, serviceable_peers, num_peers_remaining);
+
+        // Add the peers to the entire set
+        selected_peers.extend(peers);
+
+        // If we have selected enough peers, return early
+        if selected_peers.len() >= num_peers_for_request {
+            break;
+        }
+    }
+
+    selected_peers
+}
@@ -35,8 +35,14 @@ use aptos_storage_service_types::{
 };
 use aptos_time_service::{MockTimeService, TimeService};
 use aptos_types::{
+    account_address::AccountAddress,
     ledger_info::LedgerInfoWithSignatures,
-    state_store::state_value::StateValueChunkWithProof,
+    state_store::{
+        state_key::StateKey,
+        state_value::{
+            StateValueChunkWithProof, StateValueWithProof, StateValuesByPrefixWithProof,
+        },
+    },
     transaction::{TransactionListWithProof, TransactionOutputListWithProof, Version},
     PeerId,
 };
@@ -315,6 +321,21 @@ mock! {
             request_timeout_ms: u64,
         ) -> Result<Response<StateValueChunkWithProof>>;
 
+        async fn get_state_values_by_prefix_with_proof(
+            &self,
+            version: u64,
+            account_address: AccountAddress,
+            start_key: Option<StateKey>,
+            request_timeout_ms: u64,
+        ) -> Result<Response<StateValuesByPrefixWithProof>>;
+
+        async fn get_state_value_with_proof(
+            &self,
+            version: u64,
+            state_key: StateKey,
+            request_timeout_ms: u64,
+        ) -> Result<Response<StateValueWithProof>>;
+
         async fn get_transaction_outputs_with_proof(
             &self,
             proof_version: Version,
@@ -5,8 +5,10 @@ mod advertise;
 mod compression;
 pub mod mock;
 mod multi_fetch;
+mod peer_selection;
 mod peers;
 mod poller;
 mod priority;
+mod simulator;
 mod utils;
 mod weighted_selection;
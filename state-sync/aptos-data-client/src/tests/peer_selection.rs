@@ -0,0 +1,288 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    peer_selection::{PeerSelectionPolicy, PreferredPeersPolicy},
+    priority::PeerPriority,
+    tests::simulator::{PeerSelectionSimulator, SimulatedPeer},
+};
+use aptos_config::network_id::{NetworkId, PeerNetworkId};
+use aptos_infallible::Mutex;
+use aptos_storage_service_types::requests::{DataRequest, StorageServiceRequest};
+use aptos_types::PeerId;
+use maplit::{btreeset, hashset};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+#[test]
+fn test_simulator_priority_ordering() {
+    // Create peers with different priorities
+    let high_priority_peer = create_peer(NetworkId::Validator, 0);
+    let medium_priority_peer = create_peer(NetworkId::Vfn, 1);
+    let low_priority_peer = create_peer(NetworkId::Public, 2);
+
+    // Create a simulator with all peers
+    let policy = Arc::new(LowestLatencyPolicy::new(HashMap::from([
+        (high_priority_peer, PeerPriority::HighPriority),
+        (medium_priority_peer, PeerPriority::MediumPriority),
+        (low_priority_peer, PeerPriority::LowPriority),
+    ])));
+    let mut simulator = PeerSelectionSimulator::new(policy);
+    for peer in [high_priority_peer, medium_priority_peer, low_priority_peer] {
+        simulator.add_peer(peer, SimulatedPeer::new(Duration::from_millis(100)));
+    }
+
+    // Verify the high priority peer is selected first
+    let request = create_storage_request();
+    assert_eq!(simulator.simulate_request(&request, 1), btreeset![
+        high_priority_peer
+    ]);
+
+    // Verify the remaining peers are selected by priority (if more peers are required)
+    assert_eq!(simulator.simulate_request(&request, 2), btreeset![
+        high_priority_peer,
+        medium_priority_peer
+    ]);
+
+    // Make the high priority peer unserviceable and verify the medium priority peer is selected
+    simulator
+        .get_peer_mut(&high_priority_peer)
+        .unwrap()
+        .can_service_requests = false;
+    assert_eq!(simulator.simulate_request(&request, 1), btreeset![
+        medium_priority_peer
+    ]);
+
+    // Disconnect the medium priority peer and verify the low priority peer is selected
+    simulator.remove_peer(&medium_priority_peer);
+    assert_eq!(simulator.simulate_request(&request, 1), btreeset![
+        low_priority_peer
+    ]);
+
+    // Verify the selection counts
+    assert_eq!(simulator.get_selection_count(&high_priority_peer), 2);
+    assert_eq!(simulator.get_selection_count(&medium_priority_peer), 2);
+    assert_eq!(simulator.get_selection_count(&low_priority_peer), 1);
+}
+
+#[test]
+fn test_simulator_deterministic() {
+    // Run the same simulation several times
+    let mut selection_histories = vec![];
+    for _ in 0..3 {
+        // Create a simulator with peers of varying latencies
+        let peers = create_peers(NetworkId::Public, 10);
+        let policy = Arc::new(LowestLatencyPolicy::new(HashMap::new()));
+        let mut simulator = PeerSelectionSimulator::new(policy);
+        for (index, peer) in peers.iter().enumerate() {
+            let response_latency = Duration::from_millis(((index * 37) % 10) as u64 * 10);
+            simulator.add_peer(*peer, SimulatedPeer::new(response_latency));
+        }
+
+        // Simulate several requests
+        simulator.simulate_requests(&create_storage_request(), 3, 20);
+        selection_histories.push(simulator.selection_history().to_vec());
+    }
+
+    // Verify that all simulations produced identical selections
+    assert_eq!(selection_histories[0].len(), 20);
+    for selection_history in &selection_histories {
+        assert_eq!(selection_history, &selection_histories[0]);
+    }
+}
+
+#[test]
+fn test_simulator_latency_feedback() {
+    // Create a simulator with a fast peer, a slow peer and a failing peer
+    let peers = create_peers(NetworkId::Public, 3);
+    let (fast_peer, slow_peer, failing_peer) = (peers[0], peers[1], peers[2]);
+    let policy = Arc::new(LowestLatencyPolicy::new(HashMap::new()));
+    let mut simulator = PeerSelectionSimulator::new(policy);
+    simulator.add_peer(fast_peer, SimulatedPeer::new(Duration::from_millis(10)));
+    simulator.add_peer(slow_peer, SimulatedPeer::new(Duration::from_millis(500)));
+    simulator.add_peer(failing_peer, SimulatedPeer {
+        fails_requests: true,
+        ..SimulatedPeer::new(Duration::from_millis(1))
+    });
+
+    // Simulate several requests
+    let num_requests = 50;
+    simulator.simulate_requests(&create_storage_request(), 1, num_requests);
+
+    // Verify that each peer was tried once, and the fast peer was chosen for the rest
+    assert_eq!(simulator.get_selection_count(&slow_peer), 1);
+    assert_eq!(simulator.get_selection_count(&failing_peer), 1);
+    assert_eq!(simulator.get_selection_count(&fast_peer), num_requests - 2);
+
+    // Slow down the fast peer and verify the slow peer is now preferred
+    simulator.add_peer(fast_peer, SimulatedPeer::new(Duration::from_secs(1)));
+    simulator.simulate_requests(&create_storage_request(), 1, 2);
+    let selection_history = simulator.selection_history();
+    assert_eq!(selection_history[selection_history.len() - 1], btreeset![
+        slow_peer
+    ]);
+}
+
+#[test]
+fn test_preferred_peers_policy() {
+    // Create several peers, with a single preferred (but slow) peer
+    let peers = create_peers(NetworkId::Public, 5);
+    let preferred_peer = peers[4];
+
+    // Create a preferred peers policy wrapping a latency based policy
+    let inner_policy = Arc::new(LowestLatencyPolicy::new(HashMap::new()));
+    let policy = PreferredPeersPolicy::new(hashset![preferred_peer.peer_id()], inner_policy);
+
+    // Verify the preferred peer is high priority and other peers use the inner policy
+    assert_eq!(
+        policy.get_peer_priority(&preferred_peer),
+        PeerPriority::HighPriority
+    );
+    for peer in &peers[0..4] {
+        assert_eq!(policy.get_peer_priority(peer), PeerPriority::MediumPriority);
+    }
+
+    // Create a simulator with all peers (the preferred peer is the slowest)
+    let mut simulator = PeerSelectionSimulator::new(Arc::new(policy));
+    for (index, peer) in peers.iter().enumerate() {
+        let response_latency = Duration::from_millis((index as u64 + 1) * 100);
+        simulator.add_peer(*peer, SimulatedPeer::new(response_latency));
+    }
+
+    // Verify the preferred peer is always selected
+    let request = create_storage_request();
+    simulator.simulate_requests(&request, 1, 10);
+    assert_eq!(simulator.get_selection_count(&preferred_peer), 10);
+
+    // Verify that additional peers are selected from the other peers
+    let selected_peers = simulator.simulate_request(&request, 3);
+    assert_eq!(selected_peers.len(), 3);
+    assert!(selected_peers.contains(&preferred_peer));
+
+    // Make the preferred peer unserviceable and verify the other peers are used
+    simulator
+        .get_peer_mut(&preferred_peer)
+        .unwrap()
+        .can_service_requests = false;
+    let selected_peers = simulator.simulate_request(&request, 2);
+    assert_eq!(selected_peers.len(), 2);
+    assert!(!selected_peers.contains(&preferred_peer));
+}
+
+#[test]
+fn test_preferred_peers_across_networks() {
+    // Create a preferred peer that is connected on multiple networks
+    let peer_id = PeerId::random();
+    let vfn_peer = PeerNetworkId::new(NetworkId::Vfn, peer_id);
+    let public_peer = PeerNetworkId::new(NetworkId::Public, peer_id);
+    let other_peer = create_peer(NetworkId::Vfn, 0);
+
+    // Create a preferred peers policy
+    let inner_policy = Arc::new(LowestLatencyPolicy::new(HashMap::from([(
+        other_peer,
+        PeerPriority::HighPriority,
+    )])));
+    let policy = PreferredPeersPolicy::new(hashset![peer_id], inner_policy);
+
+    // Verify the preferred peer is high priority on all networks
+    assert_eq!(
+        policy.get_peer_priority(&vfn_peer),
+        PeerPriority::HighPriority
+    );
+    assert_eq!(
+        policy.get_peer_priority(&public_peer),
+        PeerPriority::HighPriority
+    );
+
+    // Verify the preferred peer is chosen before other peers of the same priority
+    let selected_peers = policy.choose_peers(
+        &create_storage_request(),
+        hashset![other_peer, public_peer],
+        1,
+    );
+    assert_eq!(selected_peers, hashset![public_peer]);
+}
+
+/// A simple (deterministic) policy that chooses peers by the lowest observed
+/// latency. Unobserved peers are tried first, failed peers are tried last, and
+/// ties are broken by peer ID. Peers have medium priority unless specified.
+#[derive(Debug)]
+struct LowestLatencyPolicy {
+    peer_latencies: Mutex<BTreeMap<PeerNetworkId, Duration>>,
+    peer_priorities: HashMap<PeerNetworkId, PeerPriority>,
+}
+
+impl LowestLatencyPolicy {
+    fn new(peer_priorities: HashMap<PeerNetworkId, PeerPriority>) -> Self {
+        Self {
+            peer_latencies: Mutex::new(BTreeMap::new()),
+            peer_priorities,
+        }
+    }
+}
+
+impl PeerSelectionPolicy for LowestLatencyPolicy {
+    fn get_peer_priority(&self, peer: &PeerNetworkId) -> PeerPriority {
+        self.peer_priorities
+            .get(peer)
+            .copied()
+            .unwrap_or(PeerPriority::MediumPriority)
+    }
+
+    fn choose_peers(
+        &self,
+        _request: &StorageServiceRequest,
+        serviceable_peers: HashSet<PeerNetworkId>,
+        num_peers_to_choose: usize,
+    ) -> HashSet<PeerNetworkId> {
+        let peer_latencies = self.peer_latencies.lock();
+        let mut sorted_peers: Vec<_> = serviceable_peers
+            .into_iter()
+            .map(|peer| {
+                let latency = peer_latencies.get(&peer).copied().unwrap_or_default();
+                (latency, peer)
+            })
+            .collect();
+        sorted_peers.sort();
+        sorted_peers
+            .into_iter()
+            .take(num_peers_to_choose)
+            .map(|(_, peer)| peer)
+            .collect()
+    }
+
+    fn notify_response_received(
+        &self,
+        peer: &PeerNetworkId,
+        _request: &StorageServiceRequest,
+        response_latency: Duration,
+    ) {
+        self.peer_latencies.lock().insert(*peer, response_latency);
+    }
+
+    fn notify_request_failed(&self, peer: &PeerNetworkId, _request: &StorageServiceRequest) {
+        self.peer_latencies.lock().insert(*peer, Duration::MAX);
+    }
+}
+
+/// Creates a peer with a deterministic peer ID on the given network
+fn create_peer(network_id: NetworkId, index: u8) -> PeerNetworkId {
+    let mut peer_id_bytes = [0; PeerId::LENGTH];
+    peer_id_bytes[PeerId::LENGTH - 1] = index;
+    PeerNetworkId::new(network_id, PeerId::new(peer_id_bytes))
+}
+
+/// Creates the given number of (deterministic) peers on the given network
+fn create_peers(network_id: NetworkId, num_peers: u8) -> Vec<PeerNetworkId> {
+    (0..num_peers)
+        .map(|index| create_peer(network_id, index))
+        .collect()
+}
+
+/// Creates a simple storage service request
+fn create_storage_request() -> StorageServiceRequest {
+    StorageServiceRequest::new(DataRequest::GetStorageServerSummary, true)
+}
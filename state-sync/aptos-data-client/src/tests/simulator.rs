@@ -0,0 +1,150 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    peer_selection::{choose_peers_by_priority, PeerSelectionPolicy},
+    priority::PeerPriority,
+};
+use aptos_config::network_id::PeerNetworkId;
+use aptos_storage_service_types::requests::StorageServiceRequest;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+    time::Duration,
+};
+
+/// The simulated behaviour of a single peer
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SimulatedPeer {
+    /// Whether or not the peer can service requests
+    pub can_service_requests: bool,
+    /// Whether or not the peer fails requests sent to it
+    pub fails_requests: bool,
+    /// The latency of the peer's responses
+    pub response_latency: Duration,
+}
+
+impl SimulatedPeer {
+    /// Returns a healthy peer that responds with the given latency
+    pub fn new(response_latency: Duration) -> Self {
+        Self {
+            can_service_requests: true,
+            fails_requests: false,
+            response_latency,
+        }
+    }
+}
+
+/// A deterministic harness for evaluating peer selection policies without a
+/// network. Each simulated request groups the simulated peers by priority and
+/// asks the policy to choose peers (in the same way as the data client). The
+/// policy is then notified of each (simulated) response or failure. Given a
+/// deterministic policy, the selections are fully reproducible.
+#[derive(Debug)]
+pub struct PeerSelectionSimulator {
+    policy: Arc<dyn PeerSelectionPolicy>,
+    peers: BTreeMap<PeerNetworkId, SimulatedPeer>,
+    selection_counts: BTreeMap<PeerNetworkId, u64>,
+    selection_history: Vec<BTreeSet<PeerNetworkId>>,
+}
+
+impl PeerSelectionSimulator {
+    pub fn new(policy: Arc<dyn PeerSelectionPolicy>) -> Self {
+        Self {
+            policy,
+            peers: BTreeMap::new(),
+            selection_counts: BTreeMap::new(),
+            selection_history: vec![],
+        }
+    }
+
+    /// Adds (or updates) the simulated peer
+    pub fn add_peer(&mut self, peer: PeerNetworkId, simulated_peer: SimulatedPeer) {
+        self.peers.insert(peer, simulated_peer);
+    }
+
+    /// Removes the simulated peer (e.g., to simulate a disconnection)
+    pub fn remove_peer(&mut self, peer: &PeerNetworkId) {
+        self.peers.remove(peer);
+    }
+
+    /// Returns a mutable reference to the simulated peer (if it exists)
+    pub fn get_peer_mut(&mut self, peer: &PeerNetworkId) -> Option<&mut SimulatedPeer> {
+        self.peers.get_mut(peer)
+    }
+
+    /// Simulates a single request and returns the selected peers
+    pub fn simulate_request(
+        &mut self,
+        request: &StorageServiceRequest,
+        num_peers_for_request: usize,
+    ) -> BTreeSet<PeerNetworkId> {
+        // Group the serviceable peers by priority
+        let mut serviceable_peers_by_priorities = vec![];
+        for priority in PeerPriority::get_all_ordered_priorities() {
+            let serviceable_peers = self
+                .peers
+                .iter()
+                .filter(|(peer, simulated_peer)| {
+                    simulated_peer.can_service_requests
+                        && self.policy.get_peer_priority(peer) == priority
+                })
+                .map(|(peer, _)| *peer)
+                .collect();
+            serviceable_peers_by_priorities.push(serviceable_peers);
+        }
+
+        // Choose the peers using the policy
+        let selected_peers: BTreeSet<_> = choose_peers_by_priority(
+            self.policy.as_ref(),
+            request,
+            serviceable_peers_by_priorities,
+            num_peers_for_request,
+        )
+        .into_iter()
+        .collect();
+
+        // Notify the policy of the (simulated) responses
+        for peer in &selected_peers {
+            if let Some(simulated_peer) = self.peers.get(peer) {
+                if simulated_peer.fails_requests {
+                    self.policy.notify_request_failed(peer, request);
+                } else {
+                    self.policy.notify_response_received(
+                        peer,
+                        request,
+                        simulated_peer.response_latency,
+                    );
+                }
+            }
+            *self.selection_counts.entry(*peer).or_default() += 1;
+        }
+
+        // Update the selection history
+        self.selection_history.push(selected_peers.clone());
+
+        selected_peers
+    }
+
+    /// Simulates the given number of identical requests
+    pub fn simulate_requests(
+        &mut self,
+        request: &StorageServiceRequest,
+        num_peers_for_request: usize,
+        num_requests: u64,
+    ) {
+        for _ in 0..num_requests {
+            self.simulate_request(request, num_peers_for_request);
+        }
+    }
+
+    /// Returns the number of times the given peer has been selected
+    pub fn get_selection_count(&self, peer: &PeerNetworkId) -> u64 {
+        self.selection_counts.get(peer).copied().unwrap_or(0)
+    }
+
+    /// Returns the selected peers for each simulated request (in order)
+    pub fn selection_history(&self) -> &[BTreeSet<PeerNetworkId>] {
+        &self.selection_history
+    }
+}
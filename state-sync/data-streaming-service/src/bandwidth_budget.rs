@@ -0,0 +1,812 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{data_notification::DataClientRequest, metrics, streaming_client::StreamRequest};
+use aptos_config::config::StreamBandwidthBudgetConfig;
+use aptos_data_client::global_summary::OptimalChunkSizes;
+use aptos_infallible::Mutex;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use std::{
+    cmp::{max, min},
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+// The duration of each bandwidth accounting window
+const BUDGET_WINDOW_DURATION: Duration = Duration::from_secs(1);
+
+// The number of windows after which an idle priority class is considered inactive
+const NUM_WINDOWS_UNTIL_INACTIVE: u32 = 2;
+
+// The smoothing factor for the moving averages (i.e., each new observation
+// contributes 1/SMOOTHING_FACTOR of the new average).
+const SMOOTHING_FACTOR: u64 = 4;
+
+/// The priority classes of data streams. Each class receives a (weighted)
+/// share of the bandwidth budget, as defined by the config.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum StreamPriorityClass {
+    Bootstrapping,     // Streams that bootstrap the node (e.g., fast syncing all states)
+    CatchUp,           // Continuous streams fetching data that already exists
+    ContinuousSyncing, // Continuous streams waiting for new data at the head of the chain
+}
+
+impl StreamPriorityClass {
+    /// Returns the initial priority class for the given stream request
+    pub fn from_stream_request(stream_request: &StreamRequest) -> Self {
+        match stream_request {
+            StreamRequest::GetAllEpochEndingLedgerInfos(_)
+            | StreamRequest::GetAllStates(_)
+            | StreamRequest::GetAllTransactions(_)
+            | StreamRequest::GetAllTransactionOutputs(_)
+            | StreamRequest::GetAllTransactionsOrOutputs(_) => Self::Bootstrapping,
+            StreamRequest::GetAccountStates(_)
+            | StreamRequest::ContinuouslyStreamTransactions(_)
+            | StreamRequest::ContinuouslyStreamTransactionOutputs(_)
+            | StreamRequest::ContinuouslyStreamTransactionsOrOutputs(_) => Self::CatchUp,
+            StreamRequest::TerminateStream(_) => Self::CatchUp, // Never creates a data stream
+        }
+    }
+
+    /// Returns the priority class of the given client request (sent by a
+    /// stream of this class). Bootstrapping streams are always bootstrapping,
+    /// but other streams are only continuously syncing when requesting new data.
+    pub fn for_client_request(&self, client_request: &DataClientRequest) -> Self {
+        match self {
+            Self::Bootstrapping => Self::Bootstrapping,
+            _ => {
+                if client_request.is_new_data_request() {
+                    Self::ContinuousSyncing
+                } else {
+                    Self::CatchUp
+                }
+            },
+        }
+    }
+
+    /// Returns a summary label for the priority class
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            Self::Bootstrapping => "bootstrapping",
+            Self::CatchUp => "catch_up",
+            Self::ContinuousSyncing => "continuous_syncing",
+        }
+    }
+}
+
+/// A simple estimator that tracks the (moving) average sizes of the data
+/// responses received by a single data stream.
+#[derive(Clone, Debug, Default)]
+pub struct ResponseSizeEstimator {
+    // The average number of (network) bytes per response (if known)
+    average_response_bytes: Option<u64>,
+
+    // The average number of bytes per data item (if known)
+    average_item_bytes: Option<u64>,
+}
+
+impl ResponseSizeEstimator {
+    /// Returns the expected number of bytes for a single response (if known)
+    pub fn get_expected_response_bytes(&self) -> Option<u64> {
+        self.average_response_bytes
+    }
+
+    /// Updates the estimator with the size of a newly received response
+    pub fn observe_response(&mut self, num_response_bytes: u64, num_data_items: u64) {
+        self.average_response_bytes = Some(update_moving_average(
+            self.average_response_bytes,
+            num_response_bytes,
+        ));
+
+        if num_data_items > 0 {
+            let num_item_bytes = max(1, num_response_bytes / num_data_items);
+            self.average_item_bytes = Some(update_moving_average(
+                self.average_item_bytes,
+                num_item_bytes,
+            ));
+        }
+    }
+}
+
+/// Updates the given moving average with the new value
+fn update_moving_average(average: Option<u64>, value: u64) -> u64 {
+    match average {
+        Some(average) => {
+            let weighted_average = (average as u128) * (SMOOTHING_FACTOR as u128 - 1);
+            ((weighted_average + value as u128) / SMOOTHING_FACTOR as u128) as u64
+        },
+        None => value, // This is the first value
+    }
+}
+
+/// The bandwidth budget shared by all data streams. The budget is enforced
+/// over fixed time windows, and each active priority class receives a share
+/// of the budget proportional to its configured weight.
+#[derive(Clone, Debug)]
+pub struct BandwidthBudget {
+    // The bandwidth budget config
+    config: StreamBandwidthBudgetConfig,
+
+    // The bandwidth consumed by each priority class (shared across streams)
+    budget_state: Arc<Mutex<BandwidthBudgetState>>,
+
+    // The time service used to track the budget windows
+    time_service: TimeService,
+}
+
+/// The (mutable) bandwidth accounting state of the current window
+#[derive(Debug)]
+struct BandwidthBudgetState {
+    // The time at which the current window started
+    window_start_time: Instant,
+
+    // The number of bytes consumed by each priority class in the current window
+    consumed_bytes: HashMap<StreamPriorityClass, u64>,
+
+    // The last time each priority class was active (i.e., wanted bandwidth)
+    last_active_times: HashMap<StreamPriorityClass, Instant>,
+
+    // The number of bytes reserved by the in-flight requests of each priority
+    // class (i.e., the expected sizes of responses that haven't been received).
+    // Reservations span windows, so they are not reset with each new window.
+    reserved_bytes: HashMap<StreamPriorityClass, u64>,
+}
+
+impl BandwidthBudget {
+    pub fn new(config: StreamBandwidthBudgetConfig, time_service: TimeService) -> Self {
+        let budget_state = BandwidthBudgetState {
+            window_start_time: time_service.now(),
+            consumed_bytes: HashMap::new(),
+            last_active_times: HashMap::new(),
+            reserved_bytes: HashMap::new(),
+        };
+
+        Self {
+            config,
+            budget_state: Arc::new(Mutex::new(budget_state)),
+            time_service,
+        }
+    }
+
+    /// Returns true iff the bandwidth budget is enforced
+    pub fn is_enabled(&self) -> bool {
+        self.config.enable_bandwidth_budget
+    }
+
+    /// Returns the maximum number of concurrent requests that the given stream
+    /// (of the priority class) can have in-flight, without exceeding the remaining
+    /// budget of the class. The remaining budget already accounts for the bytes
+    /// reserved by all in-flight requests (across streams), so only new requests
+    /// are bounded by it. The result is bounded by `max_concurrent_requests`.
+    pub fn get_max_concurrent_requests(
+        &self,
+        priority_class: StreamPriorityClass,
+        max_concurrent_requests: u64,
+        num_in_flight_requests: u64,
+        response_size_estimator: &ResponseSizeEstimator,
+    ) -> u64 {
+        // If the budget is disabled, there's nothing to bound
+        if !self.is_enabled() {
+            return max_concurrent_requests;
+        }
+
+        // Calculate the number of new requests that fit within the remaining budget
+        let remaining_bytes = self.get_remaining_bytes(priority_class);
+        let mut max_new_requests = match response_size_estimator.get_expected_response_bytes() {
+            Some(expected_response_bytes) if expected_response_bytes > 0 => {
+                remaining_bytes / expected_response_bytes
+            },
+            _ => 0,
+        };
+
+        // If the stream has no requests in-flight, always allow a single request while
+        // there is remaining budget. This is required when the response sizes are still
+        // unknown, or when a single response is expected to exceed the remaining budget
+        // (otherwise the stream would stall, as the chunk sizes are only scaled down to
+        // fit the budget when new requests are sent).
+        if max_new_requests == 0 && remaining_bytes > 0 && num_in_flight_requests == 0 {
+            max_new_requests = 1;
+        }
+        min(
+            max_concurrent_requests,
+            num_in_flight_requests.saturating_add(max_new_requests),
+        )
+    }
+
+    /// Reserves the given number of bytes for an in-flight request of the
+    /// priority class. The reservation is released when it is dropped (e.g.,
+    /// when the response is received, or the request is abandoned).
+    pub fn reserve_bytes(
+        &self,
+        priority_class: StreamPriorityClass,
+        num_bytes: u64,
+    ) -> BandwidthReservation {
+        let mut budget_state = self.budget_state.lock();
+        let reserved_bytes = budget_state
+            .reserved_bytes
+            .entry(priority_class)
+            .or_insert(0);
+        *reserved_bytes = reserved_bytes.saturating_add(num_bytes);
+
+        BandwidthReservation {
+            budget_state: self.budget_state.clone(),
+            priority_class,
+            num_bytes,
+        }
+    }
+
+    /// Bounds the given chunk sizes so that a single response (for the
+    /// priority class) is not expected to exceed the class allowance.
+    pub fn scale_chunk_sizes(
+        &self,
+        priority_class: StreamPriorityClass,
+        response_size_estimator: &ResponseSizeEstimator,
+        optimal_chunk_sizes: &mut OptimalChunkSizes,
+    ) {
+        // If the budget is disabled, or the item sizes are unknown, do nothing
+        if !self.is_enabled() {
+            return;
+        }
+        let average_item_bytes = match response_size_estimator.average_item_bytes {
+            Some(average_item_bytes) if average_item_bytes > 0 => average_item_bytes,
+            _ => return,
+        };
+
+        // Calculate the maximum number of items per response
+        let allowance_bytes = self.get_allowance_bytes(priority_class);
+        let max_chunk_size = max(1, allowance_bytes / average_item_bytes);
+
+        // Bound each of the chunk sizes
+        optimal_chunk_sizes.epoch_chunk_size =
+            min(optimal_chunk_sizes.epoch_chunk_size, max_chunk_size);
+        optimal_chunk_sizes.state_chunk_size =
+            min(optimal_chunk_sizes.state_chunk_size, max_chunk_size);
+        optimal_chunk_sizes.transaction_chunk_size =
+            min(optimal_chunk_sizes.transaction_chunk_size, max_chunk_size);
+        optimal_chunk_sizes.transaction_output_chunk_size = min(
+            optimal_chunk_sizes.transaction_output_chunk_size,
+            max_chunk_size,
+        );
+    }
+
+    /// Records the number of (network) response bytes consumed by the given priority class
+    pub fn record_response_bytes(&self, priority_class: StreamPriorityClass, num_bytes: u64) {
+        // Update the consumed bytes for the current window
+        let mut budget_state = self.budget_state.lock();
+        self.refresh_budget_window(&mut budget_state);
+        budget_state.mark_active(priority_class, self.time_service.now());
+        let consumed_bytes = budget_state
+            .consumed_bytes
+            .entry(priority_class)
+            .or_insert(0);
+        *consumed_bytes = consumed_bytes.saturating_add(num_bytes);
+
+        // Update the consumed bytes metrics
+        metrics::increment_counter_by(
+            &metrics::BANDWIDTH_BUDGET_CONSUMED_BYTES,
+            priority_class.get_label(),
+            num_bytes,
+        );
+    }
+
+    /// Returns the number of bytes (per window) allocated to the given
+    /// priority class. The class is marked as active (i.e., it wants bandwidth).
+    fn get_allowance_bytes(&self, priority_class: StreamPriorityClass) -> u64 {
+        let mut budget_state = self.budget_state.lock();
+        self.refresh_budget_window(&mut budget_state);
+        budget_state.mark_active(priority_class, self.time_service.now());
+        self.calculate_allowance_bytes(&budget_state, priority_class)
+    }
+
+    /// Returns the number of bytes that the given priority class can still
+    /// consume in the current window (excluding the bytes reserved by any
+    /// in-flight requests). The class is marked as active.
+    fn get_remaining_bytes(&self, priority_class: StreamPriorityClass) -> u64 {
+        // Calculate the remaining bytes for the priority class
+        let mut budget_state = self.budget_state.lock();
+        self.refresh_budget_window(&mut budget_state);
+        budget_state.mark_active(priority_class, self.time_service.now());
+        let allowance_bytes = self.calculate_allowance_bytes(&budget_state, priority_class);
+        let consumed_bytes = budget_state
+            .consumed_bytes
+            .get(&priority_class)
+            .copied()
+            .unwrap_or(0);
+        let reserved_bytes = budget_state
+            .reserved_bytes
+            .get(&priority_class)
+            .copied()
+            .unwrap_or(0);
+        let remaining_bytes = allowance_bytes
+            .saturating_sub(consumed_bytes)
+            .saturating_sub(reserved_bytes);
+
+        // Update the remaining bytes metrics
+        metrics::set_gauge(
+            &metrics::BANDWIDTH_BUDGET_REMAINING_BYTES,
+            priority_class.get_label(),
+            remaining_bytes,
+        );
+
+        remaining_bytes
+    }
+
+    /// Calculates the allowance of the given priority class, based on the
+    /// weights of all currently active classes.
+    fn calculate_allowance_bytes(
+        &self,
+        budget_state: &BandwidthBudgetState,
+        priority_class: StreamPriorityClass,
+    ) -> u64 {
+        // Sum the weights of all active priority classes
+        let now = self.time_service.now();
+        let total_weight: u64 = [
+            StreamPriorityClass::Bootstrapping,
+            StreamPriorityClass::CatchUp,
+            StreamPriorityClass::ContinuousSyncing,
+        ]
+        .iter()
+        .filter(|class| **class == priority_class || budget_state.is_active(class, now))
+        .map(|class| self.get_class_weight(class))
+        .sum();
+
+        // If no weights are configured, the class receives the entire budget
+        let max_bytes_per_window = self.config.max_bytes_per_second;
+        if total_weight == 0 {
+            return max_bytes_per_window;
+        }
+
+        // Otherwise, return the weighted share of the budget
+        let class_weight = self.get_class_weight(&priority_class) as u128;
+        ((max_bytes_per_window as u128 * class_weight) / total_weight as u128) as u64
+    }
+
+    /// Returns the configured weight for the given priority class
+    fn get_class_weight(&self, priority_class: &StreamPriorityClass) -> u64 {
+        match priority_class {
+            StreamPriorityClass::Bootstrapping => self.config.bootstrapping_weight,
+            StreamPriorityClass::CatchUp => self.config.catch_up_weight,
+            StreamPriorityClass::ContinuousSyncing => self.config.continuous_syncing_weight,
+        }
+    }
+
+    /// Starts a new budget window (if the current window has elapsed)
+    fn refresh_budget_window(&self, budget_state: &mut BandwidthBudgetState) {
+        let now = self.time_service.now();
+        if now.duration_since(budget_state.window_start_time) >= BUDGET_WINDOW_DURATION {
+            budget_state.window_start_time = now;
+            budget_state.consumed_bytes.clear();
+        }
+    }
+}
+
+/// The bytes reserved (against the bandwidth budget) by a single in-flight
+/// request. The reservation is released when dropped.
+#[derive(Debug)]
+pub struct BandwidthReservation {
+    budget_state: Arc<Mutex<BandwidthBudgetState>>,
+    priority_class: StreamPriorityClass,
+    num_bytes: u64,
+}
+
+impl Drop for BandwidthReservation {
+    fn drop(&mut self) {
+        let mut budget_state = self.budget_state.lock();
+        if let Some(reserved_bytes) = budget_state.reserved_bytes.get_mut(&self.priority_class) {
+            *reserved_bytes = reserved_bytes.saturating_sub(self.num_bytes);
+        }
+    }
+}
+
+impl BandwidthBudgetState {
+    /// Marks the given priority class as active
+    fn mark_active(&mut self, priority_class: StreamPriorityClass, now: Instant) {
+        self.last_active_times.insert(priority_class, now);
+    }
+
+    /// Returns true iff the given priority class was recently active
+    fn is_active(&self, priority_class: &StreamPriorityClass, now: Instant) -> bool {
+        match self.last_active_times.get(priority_class) {
+            Some(last_active_time) => {
+                now.duration_since(*last_active_time)
+                    < BUDGET_WINDOW_DURATION * NUM_WINDOWS_UNTIL_INACTIVE
+            },
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        data_notification::{NewTransactionsWithProofRequest, TransactionsWithProofRequest},
+        streaming_client::{ContinuouslyStreamTransactionsRequest, GetAllStatesRequest},
+    };
+
+    #[test]
+    fn test_priority_classes() {
+        // Verify the initial priority classes of stream requests
+        let bootstrapping_request = StreamRequest::GetAllStates(GetAllStatesRequest {
+            version: 0,
+            start_index: 0,
+        });
+        let continuous_request =
+            StreamRequest::ContinuouslyStreamTransactions(ContinuouslyStreamTransactionsRequest {
+                known_version: 0,
+                known_epoch: 0,
+                include_events: false,
+                target: None,
+            });
+        assert_eq!(
+            StreamPriorityClass::from_stream_request(&bootstrapping_request),
+            StreamPriorityClass::Bootstrapping
+        );
+        assert_eq!(
+            StreamPriorityClass::from_stream_request(&continuous_request),
+            StreamPriorityClass::CatchUp
+        );
+
+        // Create a request for existing data and a request for new data
+        let existing_data_request =
+            DataClientRequest::TransactionsWithProof(TransactionsWithProofRequest {
+                start_version: 0,
+                end_version: 10,
+                proof_version: 10,
+                include_events: false,
+            });
+        let new_data_request =
+            DataClientRequest::NewTransactionsWithProof(NewTransactionsWithProofRequest {
+                known_version: 0,
+                known_epoch: 0,
+                include_events: false,
+            });
+
+        // Verify that bootstrapping streams are always bootstrapping
+        for client_request in [&existing_data_request, &new_data_request] {
+            assert_eq!(
+                StreamPriorityClass::Bootstrapping.for_client_request(client_request),
+                StreamPriorityClass::Bootstrapping
+            );
+        }
+
+        // Verify that other streams depend on the type of data requested
+        for priority_class in [
+            StreamPriorityClass::CatchUp,
+            StreamPriorityClass::ContinuousSyncing,
+        ] {
+            assert_eq!(
+                priority_class.for_client_request(&existing_data_request),
+                StreamPriorityClass::CatchUp
+            );
+            assert_eq!(
+                priority_class.for_client_request(&new_data_request),
+                StreamPriorityClass::ContinuousSyncing
+            );
+        }
+    }
+
+    #[test]
+    fn test_budget_disabled() {
+        // Create a bandwidth budget that is disabled
+        let bandwidth_budget = BandwidthBudget::new(
+            StreamBandwidthBudgetConfig {
+                enable_bandwidth_budget: false,
+                max_bytes_per_second: 1,
+                ..Default::default()
+            },
+            TimeService::mock(),
+        );
+
+        // Exhaust the budget and create a response size estimator with large responses
+        bandwidth_budget.record_response_bytes(StreamPriorityClass::CatchUp, 1_000_000);
+        let mut response_size_estimator = ResponseSizeEstimator::default();
+        response_size_estimator.observe_response(1_000_000, 10);
+
+        // Verify the max concurrent requests is not bounded
+        let max_concurrent_requests = bandwidth_budget.get_max_concurrent_requests(
+            StreamPriorityClass::CatchUp,
+            10,
+            0,
+            &response_size_estimator,
+        );
+        assert_eq!(max_concurrent_requests, 10);
+
+        // Verify the chunk sizes are not bounded
+        let mut optimal_chunk_sizes = create_optimal_chunk_sizes(1000);
+        bandwidth_budget.scale_chunk_sizes(
+            StreamPriorityClass::CatchUp,
+            &response_size_estimator,
+            &mut optimal_chunk_sizes,
+        );
+        assert_eq!(optimal_chunk_sizes, create_optimal_chunk_sizes(1000));
+    }
+
+    #[test]
+    fn test_max_concurrent_requests() {
+        // Create a bandwidth budget with a 10 KB limit
+        let time_service = TimeService::mock();
+        let bandwidth_budget =
+            BandwidthBudget::new(create_budget_config(10_000), time_service.clone());
+
+        // Verify a single request is allowed when the response sizes are unknown
+        let mut response_size_estimator = ResponseSizeEstimator::default();
+        verify_max_concurrent_requests(
+            &bandwidth_budget,
+            StreamPriorityClass::Bootstrapping,
+            &response_size_estimator,
+            1,
+        );
+
+        // Observe a 1 KB response and verify the concurrent requests are bounded
+        response_size_estimator.observe_response(1_000, 10);
+        verify_max_concurrent_requests(
+            &bandwidth_budget,
+            StreamPriorityClass::Bootstrapping,
+            &response_size_estimator,
+            10,
+        );
+
+        // Consume some of the budget and verify the concurrent requests are reduced
+        bandwidth_budget.record_response_bytes(StreamPriorityClass::Bootstrapping, 6_500);
+        verify_max_concurrent_requests(
+            &bandwidth_budget,
+            StreamPriorityClass::Bootstrapping,
+            &response_size_estimator,
+            3,
+        );
+
+        // Consume the remaining budget and verify no requests are allowed
+        bandwidth_budget.record_response_bytes(StreamPriorityClass::Bootstrapping, 3_500);
+        verify_max_concurrent_requests(
+            &bandwidth_budget,
+            StreamPriorityClass::Bootstrapping,
+            &response_size_estimator,
+            0,
+        );
+
+        // Elapse the budget window and verify the budget is replenished
+        time_service
+            .into_mock()
+            .advance(BUDGET_WINDOW_DURATION + Duration::from_millis(1));
+        verify_max_concurrent_requests(
+            &bandwidth_budget,
+            StreamPriorityClass::Bootstrapping,
+            &response_size_estimator,
+            10,
+        );
+    }
+
+    #[test]
+    fn test_max_concurrent_requests_large_responses() {
+        // Create a bandwidth budget with a 70 KB limit (and make all classes active)
+        let bandwidth_budget =
+            BandwidthBudget::new(create_budget_config(70_000), TimeService::mock());
+        bandwidth_budget.record_response_bytes(StreamPriorityClass::CatchUp, 0);
+        bandwidth_budget.record_response_bytes(StreamPriorityClass::ContinuousSyncing, 0);
+
+        // Observe a response that is larger than the share of the bootstrapping class
+        let mut response_size_estimator = ResponseSizeEstimator::default();
+        response_size_estimator.observe_response(50_000, 10);
+        assert!(
+            response_size_estimator
+                .get_expected_response_bytes()
+                .unwrap()
+                > bandwidth_budget.get_allowance_bytes(StreamPriorityClass::Bootstrapping)
+        );
+
+        // Verify a single request is still allowed (but only if none are in-flight)
+        for (num_in_flight_requests, expected_max_concurrent_requests) in [(0, 1), (1, 1)] {
+            let max_concurrent_requests = bandwidth_budget.get_max_concurrent_requests(
+                StreamPriorityClass::Bootstrapping,
+                100,
+                num_in_flight_requests,
+                &response_size_estimator,
+            );
+            assert_eq!(max_concurrent_requests, expected_max_concurrent_requests);
+        }
+
+        // Verify the chunk sizes are scaled down to fit the share of the class
+        let mut optimal_chunk_sizes = create_optimal_chunk_sizes(1000);
+        bandwidth_budget.scale_chunk_sizes(
+            StreamPriorityClass::Bootstrapping,
+            &response_size_estimator,
+            &mut optimal_chunk_sizes,
+        );
+        assert_eq!(optimal_chunk_sizes, create_optimal_chunk_sizes(2));
+    }
+
+    #[test]
+    fn test_reserved_bytes() {
+        // Create a bandwidth budget with a 10 KB limit
+        let bandwidth_budget =
+            BandwidthBudget::new(create_budget_config(10_000), TimeService::mock());
+
+        // Observe 1 KB responses and reserve the bytes for 4 in-flight requests
+        let mut response_size_estimator = ResponseSizeEstimator::default();
+        response_size_estimator.observe_response(1_000, 10);
+        let reservations: Vec<_> = (0..4)
+            .map(|_| bandwidth_budget.reserve_bytes(StreamPriorityClass::Bootstrapping, 1_000))
+            .collect();
+        assert_eq!(
+            bandwidth_budget.get_remaining_bytes(StreamPriorityClass::Bootstrapping),
+            6_000
+        );
+
+        // Verify the in-flight requests are still allowed (but only 6 new requests)
+        let max_concurrent_requests = bandwidth_budget.get_max_concurrent_requests(
+            StreamPriorityClass::Bootstrapping,
+            100,
+            4,
+            &response_size_estimator,
+        );
+        assert_eq!(max_concurrent_requests, 10);
+
+        // Verify another stream (with no in-flight requests) is also bounded
+        let max_concurrent_requests = bandwidth_budget.get_max_concurrent_requests(
+            StreamPriorityClass::Bootstrapping,
+            100,
+            0,
+            &response_size_estimator,
+        );
+        assert_eq!(max_concurrent_requests, 6);
+
+        // Drop the reservations and verify the bytes are released
+        drop(reservations);
+        assert_eq!(
+            bandwidth_budget.get_remaining_bytes(StreamPriorityClass::Bootstrapping),
+            10_000
+        );
+
+        // Verify only a single request is allowed in-flight when the sizes are unknown
+        let response_size_estimator = ResponseSizeEstimator::default();
+        for (num_in_flight_requests, expected_max_concurrent_requests) in [(0, 1), (1, 1)] {
+            let max_concurrent_requests = bandwidth_budget.get_max_concurrent_requests(
+                StreamPriorityClass::Bootstrapping,
+                100,
+                num_in_flight_requests,
+                &response_size_estimator,
+            );
+            assert_eq!(max_concurrent_requests, expected_max_concurrent_requests);
+        }
+    }
+
+    #[test]
+    fn test_weighted_priority_classes() {
+        // Create a bandwidth budget with a 70 KB limit (and weights 1, 2 and 4)
+        let time_service = TimeService::mock();
+        let bandwidth_budget =
+            BandwidthBudget::new(create_budget_config(70_000), time_service.clone());
+
+        // Verify a single active class receives the entire budget
+        assert_eq!(
+            bandwidth_budget.get_remaining_bytes(StreamPriorityClass::Bootstrapping),
+            70_000
+        );
+
+        // Make the other classes active and verify the budget is split by weight
+        bandwidth_budget.record_response_bytes(StreamPriorityClass::CatchUp, 0);
+        bandwidth_budget.record_response_bytes(StreamPriorityClass::ContinuousSyncing, 0);
+        assert_eq!(
+            bandwidth_budget.get_remaining_bytes(StreamPriorityClass::Bootstrapping),
+            10_000
+        );
+        assert_eq!(
+            bandwidth_budget.get_remaining_bytes(StreamPriorityClass::CatchUp),
+            20_000
+        );
+        assert_eq!(
+            bandwidth_budget.get_remaining_bytes(StreamPriorityClass::ContinuousSyncing),
+            40_000
+        );
+
+        // Elapse enough time for the other classes to become inactive
+        let time_service = time_service.into_mock();
+        time_service.advance(BUDGET_WINDOW_DURATION);
+        bandwidth_budget.get_remaining_bytes(StreamPriorityClass::Bootstrapping);
+        time_service.advance(BUDGET_WINDOW_DURATION);
+
+        // Verify the active class receives the entire budget again
+        assert_eq!(
+            bandwidth_budget.get_remaining_bytes(StreamPriorityClass::Bootstrapping),
+            70_000
+        );
+    }
+
+    #[test]
+    fn test_scale_chunk_sizes() {
+        // Create a bandwidth budget with a 10 KB limit
+        let bandwidth_budget =
+            BandwidthBudget::new(create_budget_config(10_000), TimeService::mock());
+
+        // Verify the chunk sizes are unchanged when the item sizes are unknown
+        let mut response_size_estimator = ResponseSizeEstimator::default();
+        let mut optimal_chunk_sizes = create_optimal_chunk_sizes(1000);
+        bandwidth_budget.scale_chunk_sizes(
+            StreamPriorityClass::CatchUp,
+            &response_size_estimator,
+            &mut optimal_chunk_sizes,
+        );
+        assert_eq!(optimal_chunk_sizes, create_optimal_chunk_sizes(1000));
+
+        // Observe 100 byte items and verify the chunk sizes are bounded
+        response_size_estimator.observe_response(1_000, 10);
+        bandwidth_budget.scale_chunk_sizes(
+            StreamPriorityClass::CatchUp,
+            &response_size_estimator,
+            &mut optimal_chunk_sizes,
+        );
+        assert_eq!(optimal_chunk_sizes, create_optimal_chunk_sizes(100));
+
+        // Observe very large items and verify the chunk sizes are at least 1
+        let mut response_size_estimator = ResponseSizeEstimator::default();
+        response_size_estimator.observe_response(1_000_000, 1);
+        bandwidth_budget.scale_chunk_sizes(
+            StreamPriorityClass::CatchUp,
+            &response_size_estimator,
+            &mut optimal_chunk_sizes,
+        );
+        assert_eq!(optimal_chunk_sizes, create_optimal_chunk_sizes(1));
+    }
+
+    #[test]
+    fn test_response_size_estimator() {
+        // Create an empty estimator and verify the sizes are unknown
+        let mut response_size_estimator = ResponseSizeEstimator::default();
+        assert_eq!(response_size_estimator.average_response_bytes, None);
+        assert_eq!(response_size_estimator.average_item_bytes, None);
+
+        // Observe a response and verify the sizes
+        response_size_estimator.observe_response(4_000, 40);
+        assert_eq!(response_size_estimator.average_response_bytes, Some(4_000));
+        assert_eq!(response_size_estimator.average_item_bytes, Some(100));
+
+        // Observe a larger response and verify the moving averages
+        response_size_estimator.observe_response(8_000, 40);
+        assert_eq!(response_size_estimator.average_response_bytes, Some(5_000));
+        assert_eq!(response_size_estimator.average_item_bytes, Some(125));
+
+        // Observe an empty response and verify the item size is unchanged
+        response_size_estimator.observe_response(1_000, 0);
+        assert_eq!(response_size_estimator.average_response_bytes, Some(4_000));
+        assert_eq!(response_size_estimator.average_item_bytes, Some(125));
+    }
+
+    /// Creates an enabled bandwidth budget config with the given limit
+    fn create_budget_config(max_bytes_per_second: u64) -> StreamBandwidthBudgetConfig {
+        StreamBandwidthBudgetConfig {
+            enable_bandwidth_budget: true,
+            max_bytes_per_second,
+            bootstrapping_weight: 1,
+            catch_up_weight: 2,
+            continuous_syncing_weight: 4,
+        }
+    }
+
+    /// Creates a set of optimal chunk sizes with the given value
+    fn create_optimal_chunk_sizes(chunk_size: u64) -> OptimalChunkSizes {
+        OptimalChunkSizes {
+            epoch_chunk_size: chunk_size,
+            state_chunk_size: chunk_size,
+            transaction_chunk_size: chunk_size,
+            transaction_output_chunk_size: chunk_size,
+        }
+    }
+
+    /// Verifies the max concurrent requests for the given priority class
+    fn verify_max_concurrent_requests(
+        bandwidth_budget: &BandwidthBudget,
+        priority_class: StreamPriorityClass,
+        response_size_estimator: &ResponseSizeEstimator,
+        expected_max_concurrent_requests: u64,
+    ) {
+        let max_concurrent_requests = bandwidth_budget.get_max_concurrent_requests(
+            priority_class,
+            100,
+            0,
+            response_size_estimator,
+        );
+        assert_eq!(max_concurrent_requests, expected_max_concurrent_requests);
+    }
+}
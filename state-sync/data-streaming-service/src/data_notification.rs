@@ -2,11 +2,15 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::streaming_client::Epoch;
+use crate::{bandwidth_budget::BandwidthReservation, streaming_client::Epoch};
 use aptos_data_client::interface::{Response, ResponsePayload};
 use aptos_types::{
+    account_address::AccountAddress,
     ledger_info::LedgerInfoWithSignatures,
-    state_store::state_value::StateValueChunkWithProof,
+    state_store::{
+        state_key::StateKey,
+        state_value::{StateValueChunkWithProof, StateValuesByPrefixWithProof},
+    },
     transaction::{TransactionListWithProof, TransactionOutputListWithProof, Version},
 };
 use std::{
@@ -39,6 +43,7 @@ impl DataNotification {
 #[allow(clippy::large_enum_variant)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DataPayload {
+    AccountStatesWithProof(StateValuesByPrefixWithProof),
     ContinuousTransactionOutputsWithProof(LedgerInfoWithSignatures, TransactionOutputListWithProof),
     ContinuousTransactionsWithProof(LedgerInfoWithSignatures, TransactionListWithProof),
     EpochEndingLedgerInfos(Vec<LedgerInfoWithSignatures>),
@@ -63,6 +68,7 @@ pub enum DataClientRequest {
     SubscribeTransactionsWithProof(SubscribeTransactionsWithProofRequest),
     SubscribeTransactionOutputsWithProof(SubscribeTransactionOutputsWithProofRequest),
     SubscribeTransactionsOrOutputsWithProof(SubscribeTransactionsOrOutputsWithProofRequest),
+    StateValuesByPrefixWithProof(StateValuesByPrefixWithProofRequest),
 }
 
 impl DataClientRequest {
@@ -85,6 +91,7 @@ impl DataClientRequest {
             Self::SubscribeTransactionsOrOutputsWithProof(_) => {
                 "subscribe_transactions_or_outputs_with_proof"
             },
+            Self::StateValuesByPrefixWithProof(_) => "state_values_by_prefix_with_proof",
         }
     }
 
@@ -125,6 +132,14 @@ pub struct StateValuesWithProofRequest {
     pub end_index: u64,
 }
 
+/// A request for fetching the state values under an account.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StateValuesByPrefixWithProofRequest {
+    pub version: Version,
+    pub account_address: AccountAddress,
+    pub start_key: Option<StateKey>,
+}
+
 /// A client request for fetching epoch ending ledger infos.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct EpochEndingLedgerInfosRequest {
@@ -221,6 +236,7 @@ pub struct TransactionsOrOutputsWithProofRequest {
 pub struct PendingClientResponse {
     pub client_request: DataClientRequest,
     pub client_response: Option<Result<Response<ResponsePayload>, aptos_data_client::error::Error>>,
+    pub bandwidth_reservation: Option<BandwidthReservation>,
 }
 
 impl PendingClientResponse {
@@ -228,6 +244,7 @@ impl PendingClientResponse {
         Self {
             client_request,
             client_response: None,
+            bandwidth_reservation: None,
         }
     }
 
@@ -240,6 +257,7 @@ impl PendingClientResponse {
         Self {
             client_request,
             client_response: Some(client_response),
+            bandwidth_reservation: None,
         }
     }
 }
@@ -3,12 +3,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    bandwidth_budget::{
+        BandwidthBudget, BandwidthReservation, ResponseSizeEstimator, StreamPriorityClass,
+    },
     data_notification,
     data_notification::{
         DataClientRequest, DataNotification, DataPayload, EpochEndingLedgerInfosRequest,
         NewTransactionOutputsWithProofRequest, NewTransactionsOrOutputsWithProofRequest,
         NewTransactionsWithProofRequest, NotificationId, NumberOfStatesRequest,
-        StateValuesWithProofRequest, SubscribeTransactionOutputsWithProofRequest,
+        StateValuesByPrefixWithProofRequest, StateValuesWithProofRequest,
+        SubscribeTransactionOutputsWithProofRequest,
         SubscribeTransactionsOrOutputsWithProofRequest, SubscribeTransactionsWithProofRequest,
         TransactionOutputsWithProofRequest, TransactionsOrOutputsWithProofRequest,
         TransactionsWithProofRequest,
@@ -123,6 +127,15 @@ pub struct DataStream<T> {
 
     // The dynamic prefetching state (if enabled)
     dynamic_prefetching_state: DynamicPrefetchingState,
+
+    // The bandwidth budget shared by all data streams (if enabled)
+    bandwidth_budget: BandwidthBudget,
+
+    // The current priority class of the stream (used for the bandwidth budget)
+    priority_class: StreamPriorityClass,
+
+    // The estimator for the response sizes received by the stream
+    response_size_estimator: ResponseSizeEstimator,
 }
 
 impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStream<T> {
@@ -136,6 +149,7 @@ impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStream<T> {
         notification_id_generator: Arc<U64IdGenerator>,
         advertised_data: &AdvertisedData,
         time_service: TimeService,
+        bandwidth_budget: BandwidthBudget,
     ) -> Result<(Self, DataStreamListener), Error> {
         // Create a new data stream listener
         let (notification_sender, notification_receiver) =
@@ -149,6 +163,9 @@ impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStream<T> {
         let dynamic_prefetching_state =
             DynamicPrefetchingState::new(data_stream_config, time_service.clone());
 
+        // Identify the initial priority class of the stream
+        let priority_class = StreamPriorityClass::from_stream_request(stream_request);
+
         // Create a new data stream
         let data_stream = Self {
             data_client_config,
@@ -168,6 +185,9 @@ impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStream<T> {
             subscription_stream_lag: None,
             time_service,
             dynamic_prefetching_state,
+            bandwidth_budget,
+            priority_class,
+            response_size_estimator: ResponseSizeEstimator::default(),
         };
 
         Ok((data_stream, data_stream_listener))
@@ -278,12 +298,36 @@ impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStream<T> {
         let max_pending_requests = self.streaming_service_config.max_pending_requests;
         let max_num_requests_to_send = max_pending_requests.saturating_sub(num_pending_requests);
 
-        // Send the client requests iff we have enough room in the queue
-        if max_num_requests_to_send > 0 {
-            // Get the max number of in-flight requests from the prefetching state
-            let max_in_flight_requests = self
-                .dynamic_prefetching_state
-                .get_max_concurrent_requests(&self.stream_engine);
+        // Get the max number of in-flight requests from the prefetching state,
+        // and bound it by the remaining bandwidth budget of the stream.
+        let max_in_flight_requests = self
+            .dynamic_prefetching_state
+            .get_max_concurrent_requests(&self.stream_engine);
+        let max_in_flight_requests = self.bandwidth_budget.get_max_concurrent_requests(
+            self.priority_class,
+            max_in_flight_requests,
+            num_in_flight_requests,
+            &self.response_size_estimator,
+        );
+
+        // Send the client requests iff we have enough room in the queue (and budget)
+        let within_bandwidth_budget =
+            !self.bandwidth_budget.is_enabled() || max_in_flight_requests > num_in_flight_requests;
+        if max_num_requests_to_send > 0 && within_bandwidth_budget {
+            // If the bandwidth budget is enabled, bound the chunk sizes by the budget
+            let budgeted_data_summary;
+            let global_data_summary = if self.bandwidth_budget.is_enabled() {
+                let mut data_summary = global_data_summary.clone();
+                self.bandwidth_budget.scale_chunk_sizes(
+                    self.priority_class,
+                    &self.response_size_estimator,
+                    &mut data_summary.optimal_chunk_sizes,
+                );
+                budgeted_data_summary = data_summary;
+                &budgeted_data_summary
+            } else {
+                global_data_summary
+            };
 
             // Create the client requests
             let client_requests = self.stream_engine.create_data_client_requests(
@@ -294,6 +338,12 @@ impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStream<T> {
                 self.notification_id_generator.clone(),
             )?;
 
+            // Update the priority class of the stream (e.g., if the stream
+            // has caught up and is now requesting new data).
+            if let Some(client_request) = client_requests.last() {
+                self.priority_class = self.priority_class.for_client_request(client_request);
+            }
+
             // Add the client requests to the sent data requests queue
             for client_request in &client_requests {
                 // Send the client request
@@ -335,10 +385,13 @@ impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStream<T> {
         request_retry: bool,
         data_client_request: DataClientRequest,
     ) -> PendingClientResponse {
-        // Create a new pending client response
-        let pending_client_response = Arc::new(Mutex::new(Box::new(
-            data_notification::PendingClientResponse::new(data_client_request.clone()),
-        )));
+        // Create a new pending client response (and reserve the expected
+        // response bytes against the bandwidth budget, if required).
+        let mut pending_client_response =
+            data_notification::PendingClientResponse::new(data_client_request.clone());
+        pending_client_response.bandwidth_reservation =
+            self.reserve_response_bandwidth(&data_client_request);
+        let pending_client_response = Arc::new(Mutex::new(Box::new(pending_client_response)));
 
         // Calculate the request timeout to use, based on the
         // request type and the number of previous failures.
@@ -456,6 +509,9 @@ impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStream<T> {
 
         // Continuously process any ready data responses
         while let Some(pending_response) = self.pop_pending_response_queue()? {
+            // Release any bandwidth reserved for the response
+            pending_response.lock().bandwidth_reservation = None;
+
             // Get the client request and response information
             let maybe_client_response = pending_response.lock().client_response.take();
             let client_response = maybe_client_response.ok_or_else(|| {
@@ -466,6 +522,11 @@ impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStream<T> {
             // Process the client response
             match client_response {
                 Ok(client_response) => {
+                    // Record the bandwidth consumed by the response
+                    if self.bandwidth_budget.is_enabled() {
+                        self.record_response_bandwidth(client_request, &client_response);
+                    }
+
                     // Sanity check and process the response
                     if sanity_check_client_response_type(client_request, &client_response) {
                         // If the response wasn't enough to satisfy the original request (e.g.,
@@ -545,6 +606,45 @@ impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStream<T> {
         self.create_and_send_client_requests(&global_data_summary)
     }
 
+    /// Reserves the expected response bytes for the given request against the
+    /// bandwidth budget. Returns None if the budget is disabled, or if the
+    /// response sizes are not yet known.
+    fn reserve_response_bandwidth(
+        &self,
+        client_request: &DataClientRequest,
+    ) -> Option<BandwidthReservation> {
+        if !self.bandwidth_budget.is_enabled() {
+            return None;
+        }
+
+        let expected_response_bytes = self.response_size_estimator.get_expected_response_bytes()?;
+        let priority_class = self.priority_class.for_client_request(client_request);
+        Some(
+            self.bandwidth_budget
+                .reserve_bytes(priority_class, expected_response_bytes),
+        )
+    }
+
+    /// Records the bandwidth consumed by the given response (against the
+    /// bandwidth budget) and updates the response size estimates. The size
+    /// of the response is the size sent over the network (e.g., compressed).
+    fn record_response_bandwidth(
+        &mut self,
+        client_request: &DataClientRequest,
+        client_response: &Response<ResponsePayload>,
+    ) {
+        // Update the response size estimates
+        let num_response_bytes = client_response.context.response_size_bytes;
+        let num_data_items = client_response.payload.get_data_chunk_size() as u64;
+        self.response_size_estimator
+            .observe_response(num_response_bytes, num_data_items);
+
+        // Record the consumed bytes for the priority class of the request
+        let priority_class = self.priority_class.for_client_request(client_request);
+        self.bandwidth_budget
+            .record_response_bytes(priority_class, num_response_bytes);
+    }
+
     /// Verifies that the subscription stream is not lagging too much (i.e.,
     /// behind the data advertisements). If it is, an error is returned.
     fn check_subscription_stream_lag(
@@ -1344,6 +1444,12 @@ fn sanity_check_client_response_type(
                 ResponsePayload::StateValuesWithProof(_)
             )
         },
+        DataClientRequest::StateValuesByPrefixWithProof(_) => {
+            matches!(
+                data_client_response.payload,
+                ResponsePayload::StateValuesByPrefixWithProof(_)
+            )
+        },
         DataClientRequest::SubscribeTransactionsWithProof(_) => {
             matches!(
                 data_client_response.payload,
@@ -1458,6 +1564,14 @@ fn spawn_request_task<T: AptosDataClientInterface + Send + Clone + 'static>(
             DataClientRequest::StateValuesWithProof(request) => {
                 get_states_values_with_proof(aptos_data_client, request, request_timeout_ms).await
             },
+            DataClientRequest::StateValuesByPrefixWithProof(request) => {
+                get_state_values_by_prefix_with_proof(
+                    aptos_data_client,
+                    request,
+                    request_timeout_ms,
+                )
+                .await
+            },
             DataClientRequest::SubscribeTransactionsWithProof(request) => {
                 subscribe_to_transactions_with_proof(aptos_data_client, request, request_timeout_ms)
                     .await
@@ -1533,6 +1647,24 @@ async fn get_states_values_with_proof<T: AptosDataClientInterface + Send + Clone
         .map(|response| response.map(ResponsePayload::from))
 }
 
+async fn get_state_values_by_prefix_with_proof<
+    T: AptosDataClientInterface + Send + Clone + 'static,
+>(
+    aptos_data_client: T,
+    request: StateValuesByPrefixWithProofRequest,
+    request_timeout_ms: u64,
+) -> Result<Response<ResponsePayload>, aptos_data_client::error::Error> {
+    let client_response = aptos_data_client.get_state_values_by_prefix_with_proof(
+        request.version,
+        request.account_address,
+        request.start_key,
+        request_timeout_ms,
+    );
+    client_response
+        .await
+        .map(|response| response.map(ResponsePayload::from))
+}
+
 async fn get_epoch_ending_ledger_infos<T: AptosDataClientInterface + Send + Clone + 'static>(
     aptos_data_client: T,
     request: EpochEndingLedgerInfosRequest,
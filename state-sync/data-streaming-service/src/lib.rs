@@ -4,6 +4,7 @@
 
 #![forbid(unsafe_code)]
 
+mod bandwidth_budget;
 pub mod data_notification;
 pub mod data_stream;
 mod dynamic_prefetching;
@@ -4,8 +4,8 @@
 
 use aptos_metrics_core::{
     exponential_buckets, histogram_opts, register_histogram_vec, register_int_counter,
-    register_int_counter_vec, register_int_gauge, HistogramTimer, HistogramVec, IntCounter,
-    IntCounterVec, IntGauge,
+    register_int_counter_vec, register_int_gauge, register_int_gauge_vec, HistogramTimer,
+    HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
 };
 use once_cell::sync::Lazy;
 use std::time::Instant;
@@ -143,6 +143,26 @@ pub static MAX_CONCURRENT_PREFETCHING_REQUESTS: Lazy<IntGauge> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter for the number of response bytes consumed by each stream priority class
+pub static BANDWIDTH_BUDGET_CONSUMED_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_data_streaming_service_bandwidth_budget_consumed_bytes",
+        "Counters related to the response bytes consumed by each stream priority class",
+        &["priority_class"]
+    )
+    .unwrap()
+});
+
+/// Gauge for the remaining bandwidth budget (bytes) of each stream priority class
+pub static BANDWIDTH_BUDGET_REMAINING_BYTES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_data_streaming_service_bandwidth_budget_remaining_bytes",
+        "The remaining bandwidth budget (in the current window) of each stream priority class",
+        &["priority_class"]
+    )
+    .unwrap()
+});
+
 /// Counter for the number of pending data responses
 pub static PENDING_DATA_RESPONSES: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge!(
@@ -226,6 +246,11 @@ pub fn increment_counter(counter: &Lazy<IntCounterVec>, label: &str) {
     counter.with_label_values(&[label]).inc();
 }
 
+/// Increments the given counter with the single label value by the given amount
+pub fn increment_counter_by(counter: &Lazy<IntCounterVec>, label: &str, value: u64) {
+    counter.with_label_values(&[label]).inc_by(value);
+}
+
 /// Increments the given counter with two label values.
 pub fn increment_counter_multiple_labels(
     counter: &Lazy<IntCounterVec>,
@@ -256,6 +281,11 @@ pub fn observe_values(
         .observe(value as f64);
 }
 
+/// Sets the gauge with the single label value
+pub fn set_gauge(gauge: &Lazy<IntGaugeVec>, label: &str, value: u64) {
+    gauge.with_label_values(&[label]).set(value as i64);
+}
+
 /// Sets the number of active data streams
 pub fn set_active_data_streams(value: usize) {
     ACTIVE_DATA_STREAMS.set(value as i64);
@@ -8,13 +8,15 @@ use crate::{
         DataClientRequest::{
             EpochEndingLedgerInfos, NewTransactionOutputsWithProof,
             NewTransactionsOrOutputsWithProof, NewTransactionsWithProof, NumberOfStates,
-            StateValuesWithProof, SubscribeTransactionOutputsWithProof,
-            SubscribeTransactionsOrOutputsWithProof, SubscribeTransactionsWithProof,
-            TransactionOutputsWithProof, TransactionsOrOutputsWithProof, TransactionsWithProof,
+            StateValuesByPrefixWithProof, StateValuesWithProof,
+            SubscribeTransactionOutputsWithProof, SubscribeTransactionsOrOutputsWithProof,
+            SubscribeTransactionsWithProof, TransactionOutputsWithProof,
+            TransactionsOrOutputsWithProof, TransactionsWithProof,
         },
         DataNotification, DataPayload, EpochEndingLedgerInfosRequest,
         NewTransactionOutputsWithProofRequest, NewTransactionsOrOutputsWithProofRequest,
-        NewTransactionsWithProofRequest, NumberOfStatesRequest, StateValuesWithProofRequest,
+        NewTransactionsWithProofRequest, NumberOfStatesRequest,
+        StateValuesByPrefixWithProofRequest, StateValuesWithProofRequest,
         SubscribeTransactionOutputsWithProofRequest,
         SubscribeTransactionsOrOutputsWithProofRequest, SubscribeTransactionsWithProofRequest,
         TransactionOutputsWithProofRequest, TransactionsOrOutputsWithProofRequest,
@@ -24,7 +26,8 @@ use crate::{
     logging::{LogEntry, LogEvent, LogSchema},
     metrics,
     streaming_client::{
-        Epoch, GetAllEpochEndingLedgerInfosRequest, GetAllStatesRequest, StreamRequest,
+        Epoch, GetAccountStatesRequest, GetAllEpochEndingLedgerInfosRequest, GetAllStatesRequest,
+        StreamRequest,
     },
 };
 use aptos_config::config::DataStreamingServiceConfig;
@@ -34,7 +37,9 @@ use aptos_data_client::{
 };
 use aptos_id_generator::{IdGenerator, U64IdGenerator};
 use aptos_logger::prelude::*;
-use aptos_types::{ledger_info::LedgerInfoWithSignatures, transaction::Version};
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures, state_store::state_key::StateKey, transaction::Version,
+};
 use enum_dispatch::enum_dispatch;
 use std::{cmp, cmp::min, sync::Arc};
 
@@ -121,6 +126,7 @@ pub trait DataStreamEngine {
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 pub enum StreamEngine {
+    AccountStateStreamEngine,
     ContinuousTransactionStreamEngine,
     EpochEndingStreamEngine,
     StateStreamEngine,
@@ -134,6 +140,9 @@ impl StreamEngine {
         advertised_data: &AdvertisedData,
     ) -> Result<Self, Error> {
         match stream_request {
+            StreamRequest::GetAccountStates(request) => {
+                Ok(AccountStateStreamEngine::new(request)?.into())
+            },
             StreamRequest::ContinuouslyStreamTransactionOutputs(_) => Ok(
                 ContinuousTransactionStreamEngine::new(data_stream_config, stream_request)?.into(),
             ),
@@ -164,6 +173,192 @@ impl StreamEngine {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct AccountStateStreamEngine {
+    // The original account states request made by the client
+    pub request: GetAccountStatesRequest,
+
+    // The index of the account (in the original request) that we're
+    // currently fetching state values for.
+    pub next_account_index: usize,
+
+    // The state key to resume fetching from for the current account
+    // (or None, if we should fetch from the first key of the account).
+    pub next_start_key: Option<StateKey>,
+
+    // True iff a request has been created for the current account
+    // and start key, and we're still waiting for the response.
+    pub request_in_flight: bool,
+
+    // True iff all data has been sent across the stream.
+    pub stream_is_complete: bool,
+}
+
+impl AccountStateStreamEngine {
+    fn new(request: &GetAccountStatesRequest) -> Result<Self, Error> {
+        if request.account_addresses.is_empty() {
+            return Err(Error::NoDataToFetch(
+                "No accounts were specified in the account states request!".into(),
+            ));
+        }
+
+        Ok(AccountStateStreamEngine {
+            request: request.clone(),
+            next_account_index: 0,
+            next_start_key: None,
+            request_in_flight: false,
+            stream_is_complete: false,
+        })
+    }
+
+    /// Creates the next state values by prefix request
+    fn create_next_request(&self) -> Result<DataClientRequest, Error> {
+        let account_address = self
+            .request
+            .account_addresses
+            .get(self.next_account_index)
+            .ok_or_else(|| {
+                Error::UnexpectedErrorEncountered(format!(
+                    "The next account index is out of bounds: {:?}",
+                    self.next_account_index
+                ))
+            })?;
+        Ok(StateValuesByPrefixWithProof(
+            StateValuesByPrefixWithProofRequest {
+                version: self.request.version,
+                account_address: *account_address,
+                start_key: self.next_start_key.clone(),
+            },
+        ))
+    }
+
+    /// Verifies that the given client request matches the
+    /// next account and start key expected by the stream.
+    fn verify_client_request(
+        &self,
+        request: &StateValuesByPrefixWithProofRequest,
+    ) -> Result<(), Error> {
+        let expected_account = self.request.account_addresses.get(self.next_account_index);
+        if expected_account != Some(&request.account_address)
+            || self.next_start_key != request.start_key
+        {
+            return Err(Error::UnexpectedErrorEncountered(format!(
+                "The account states request does not match the stream progress! \
+                Expected account: {:?}, start key: {:?}. Request: {:?}",
+                expected_account, self.next_start_key, request
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl DataStreamEngine for AccountStateStreamEngine {
+    fn create_data_client_requests(
+        &mut self,
+        max_number_of_requests: u64,
+        max_in_flight_requests: u64,
+        num_in_flight_requests: u64,
+        _global_data_summary: &GlobalDataSummary,
+        _unique_id_generator: Arc<U64IdGenerator>,
+    ) -> Result<Vec<DataClientRequest>, Error> {
+        // Each request depends on the response to the previous request (i.e.,
+        // the next key to resume from), so only a single request can be in-flight.
+        if self.stream_is_complete || self.request_in_flight {
+            return Ok(vec![]);
+        }
+        let num_requests_to_send = calculate_num_requests_to_send(
+            max_number_of_requests,
+            max_in_flight_requests,
+            num_in_flight_requests,
+        );
+        if num_requests_to_send == 0 {
+            return Ok(vec![]);
+        }
+
+        // Create the next request
+        let client_request = self.create_next_request()?;
+        self.request_in_flight = true;
+        Ok(vec![client_request])
+    }
+
+    fn is_remaining_data_available(&self, advertised_data: &AdvertisedData) -> Result<bool, Error> {
+        Ok(AdvertisedData::contains_range(
+            self.request.version,
+            self.request.version,
+            &advertised_data.states,
+        ))
+    }
+
+    fn is_stream_complete(&self) -> bool {
+        self.stream_is_complete
+    }
+
+    fn transform_client_response_into_notification(
+        &mut self,
+        client_request: &DataClientRequest,
+        client_response_payload: ResponsePayload,
+        notification_id_generator: Arc<U64IdGenerator>,
+    ) -> Result<Option<DataNotification>, Error> {
+        // Update the metrics for the number of received items
+        update_response_chunk_size_metrics(client_request, &client_response_payload);
+
+        // Handle and transform the response
+        match client_request {
+            StateValuesByPrefixWithProof(request) => {
+                // Verify the client request matches the stream progress
+                self.verify_client_request(request)?;
+                self.request_in_flight = false;
+
+                // Identify the next key and the number of state values received
+                let (next_key, num_state_values) = match &client_response_payload {
+                    ResponsePayload::StateValuesByPrefixWithProof(state_values_with_proof) => {
+                        if state_values_with_proof.version != request.version {
+                            return Err(Error::AptosDataClientResponseIsInvalid(format!(
+                                "Received account states at the wrong version! Request: {:?}, response version: {:?}",
+                                client_request, state_values_with_proof.version
+                            )));
+                        }
+                        (
+                            state_values_with_proof.next_key.clone(),
+                            state_values_with_proof.raw_values.len(),
+                        )
+                    },
+                    _ => invalid_response_type!(client_response_payload),
+                };
+
+                // Update the stream progress
+                if next_key.is_some() {
+                    if num_state_values == 0 {
+                        return Err(Error::AptosDataClientResponseIsInvalid(format!(
+                            "Received an empty account states response with a next key! Request: {:?}",
+                            client_request
+                        )));
+                    }
+                    self.next_start_key = next_key;
+                } else {
+                    self.next_start_key = None;
+                    self.next_account_index += 1;
+                    if self.next_account_index >= self.request.account_addresses.len() {
+                        self.stream_is_complete = true;
+                    }
+                }
+
+                // Note: accounts without state values still produce a (empty)
+                // notification, so that the client can verify every account was covered.
+                // Create a new data notification
+                let data_notification = create_data_notification(
+                    notification_id_generator,
+                    client_response_payload,
+                    None,
+                    self.clone().into(),
+                )?;
+                Ok(Some(data_notification))
+            },
+            request => invalid_client_request!(request, self),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct StateStreamEngine {
     // The original states request made by the client
@@ -2164,6 +2359,9 @@ fn create_data_notification(
         ResponsePayload::StateValuesWithProof(states_chunk) => {
             DataPayload::StateValuesWithProof(states_chunk)
         },
+        ResponsePayload::StateValuesByPrefixWithProof(account_states) => {
+            DataPayload::AccountStatesWithProof(account_states)
+        },
         ResponsePayload::EpochEndingLedgerInfos(ledger_infos) => {
             DataPayload::EpochEndingLedgerInfos(ledger_infos)
         },
@@ -7,7 +7,9 @@ use crate::{
     data_stream::{DataStreamId, DataStreamListener},
     error::Error,
 };
-use aptos_types::{ledger_info::LedgerInfoWithSignatures, transaction::Version};
+use aptos_types::{
+    account_address::AccountAddress, ledger_info::LedgerInfoWithSignatures, transaction::Version,
+};
 use async_trait::async_trait;
 use futures::{
     channel::{mpsc, oneshot},
@@ -45,6 +47,17 @@ pub trait DataStreamingClient {
         start_index: Option<u64>,
     ) -> Result<DataStreamListener, Error>;
 
+    /// Fetches the state values under each of the specified accounts at the
+    /// given version (e.g., for partial-state nodes that only track a small
+    /// set of accounts). Accounts are streamed in the order specified, and
+    /// each state value is accompanied by an inclusion proof at the version.
+    /// Accounts without any state values are skipped.
+    async fn get_account_state_values(
+        &self,
+        version: Version,
+        account_addresses: Vec<AccountAddress>,
+    ) -> Result<DataStreamListener, Error>;
+
     /// Fetches all epoch ending ledger infos starting at `start_epoch`
     /// (inclusive) and ending at the last known epoch advertised in the network.
     async fn get_all_epoch_ending_ledger_infos(
@@ -171,6 +184,7 @@ pub struct StreamRequestMessage {
 /// The data streaming request from the client.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum StreamRequest {
+    GetAccountStates(GetAccountStatesRequest),
     GetAllEpochEndingLedgerInfos(GetAllEpochEndingLedgerInfosRequest),
     GetAllStates(GetAllStatesRequest),
     GetAllTransactions(GetAllTransactionsRequest),
@@ -186,6 +200,7 @@ impl StreamRequest {
     /// Returns a summary label for the stream request
     pub fn get_label(&self) -> &'static str {
         match self {
+            Self::GetAccountStates(_) => "get_account_states",
             Self::GetAllEpochEndingLedgerInfos(_) => "get_all_epoch_ending_ledger_infos",
             Self::GetAllStates(_) => "get_all_states",
             Self::GetAllTransactions(_) => "get_all_transactions",
@@ -203,6 +218,13 @@ impl StreamRequest {
     }
 }
 
+/// A client request for fetching the states under a set of accounts at a specified version.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GetAccountStatesRequest {
+    pub version: Version,
+    pub account_addresses: Vec<AccountAddress>,
+}
+
 /// A client request for fetching all available epoch ending ledger infos.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct GetAllEpochEndingLedgerInfosRequest {
@@ -348,6 +370,18 @@ impl DataStreamingClient for StreamingServiceClient {
         self.send_request_and_await_response(client_request).await
     }
 
+    async fn get_account_state_values(
+        &self,
+        version: u64,
+        account_addresses: Vec<AccountAddress>,
+    ) -> Result<DataStreamListener, Error> {
+        let client_request = StreamRequest::GetAccountStates(GetAccountStatesRequest {
+            version,
+            account_addresses,
+        });
+        self.send_request_and_await_response(client_request).await
+    }
+
     async fn get_all_epoch_ending_ledger_infos(
         &self,
         start_epoch: u64,
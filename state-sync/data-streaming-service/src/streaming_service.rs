@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    bandwidth_budget::BandwidthBudget,
     data_stream::{DataStream, DataStreamId, DataStreamListener},
     error::Error,
     logging::{LogEntry, LogEvent, LogSchema},
@@ -83,6 +84,9 @@ pub struct DataStreamingService<T> {
     stream_id_generator: U64IdGenerator,
     notification_id_generator: Arc<U64IdGenerator>,
 
+    // The bandwidth budget shared by all data streams
+    bandwidth_budget: BandwidthBudget,
+
     // The time service used to track elapsed time (e.g., for stream progress checks)
     time_service: TimeService,
 }
@@ -99,6 +103,12 @@ impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStreamingService<
         let (stream_update_notifier, stream_update_listener) =
             aptos_channel::new(QueueStyle::LIFO, STREAM_PROGRESS_UPDATE_CHANNEL_SIZE, None);
 
+        // Create the bandwidth budget shared by all data streams
+        let bandwidth_budget = BandwidthBudget::new(
+            streaming_service_config.bandwidth_budget,
+            time_service.clone(),
+        );
+
         // Create the streaming service
         Self {
             data_client_config,
@@ -111,6 +121,7 @@ impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStreamingService<
             stream_update_listener,
             stream_id_generator: U64IdGenerator::new(),
             notification_id_generator: Arc::new(U64IdGenerator::new()),
+            bandwidth_budget,
             time_service,
         }
     }
@@ -282,6 +293,7 @@ impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStreamingService<
             self.notification_id_generator.clone(),
             &advertised_data,
             self.time_service.clone(),
+            self.bandwidth_budget.clone(),
         )?;
 
         // Verify the data stream can be fulfilled using the currently advertised data
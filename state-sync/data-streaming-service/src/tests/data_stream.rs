@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    bandwidth_budget::BandwidthBudget,
     data_notification::{
         DataClientRequest, DataPayload, EpochEndingLedgerInfosRequest,
         NewTransactionOutputsWithProofRequest, NewTransactionsOrOutputsWithProofRequest,
@@ -33,6 +34,7 @@ use crate::{
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
 use aptos_config::config::{
     AptosDataClientConfig, DataStreamingServiceConfig, DynamicPrefetchingConfig,
+    StreamBandwidthBudgetConfig,
 };
 use aptos_data_client::{
     global_summary::{AdvertisedData, GlobalDataSummary, OptimalChunkSizes},
@@ -78,7 +80,7 @@ async fn test_stream_blocked() {
                 start_epoch: 0,
                 end_epoch: 0,
             });
-        let context = ResponseContext::new(0, Box::new(NoopResponseCallback));
+        let context = ResponseContext::new(0, 0, Box::new(NoopResponseCallback));
         let pending_response = PendingClientResponse::new_with_response(
             client_request.clone(),
             Ok(Response::new(context, ResponsePayload::NumberOfStates(10))),
@@ -184,6 +186,31 @@ async fn test_stream_initialization() {
     assert_ne!(sent_requests.as_ref().unwrap().len(), 0);
 }
 
+#[tokio::test]
+async fn test_stream_initialization_bandwidth_budget() {
+    // Create an epoch ending data stream with the bandwidth budget enabled
+    let streaming_service_config = DataStreamingServiceConfig {
+        bandwidth_budget: StreamBandwidthBudgetConfig {
+            enable_bandwidth_budget: true,
+            ..Default::default()
+        },
+        max_concurrent_requests: 3,
+        ..Default::default()
+    };
+    let (mut data_stream, _) = create_epoch_ending_stream(
+        AptosDataClientConfig::default(),
+        streaming_service_config,
+        MIN_ADVERTISED_EPOCH_END,
+    );
+
+    // Initialize the data stream
+    let global_data_summary = create_global_data_summary(1);
+    initialize_data_requests(&mut data_stream, &global_data_summary);
+
+    // Verify that only a single request was sent (the response sizes are unknown)
+    verify_num_sent_requests(&mut data_stream, 1);
+}
+
 #[tokio::test]
 async fn test_stream_data_error() {
     // Create an epoch ending data stream
@@ -236,7 +263,7 @@ async fn test_stream_invalid_response() {
         start_epoch: MIN_ADVERTISED_EPOCH_END,
         end_epoch: MIN_ADVERTISED_EPOCH_END + 1,
     });
-    let context = ResponseContext::new(0, Box::new(NoopResponseCallback));
+    let context = ResponseContext::new(0, 0, Box::new(NoopResponseCallback));
     let pending_response = PendingClientResponse::new_with_response(
         client_request.clone(),
         Ok(Response::new(context, ResponsePayload::NumberOfStates(10))),
@@ -3501,6 +3528,10 @@ fn create_data_stream(
         notification_generator,
         &advertised_data,
         time_service.clone(),
+        BandwidthBudget::new(
+            streaming_service_config.bandwidth_budget,
+            time_service.clone(),
+        ),
     )
     .unwrap();
 
@@ -3,25 +3,151 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    data_notification::{DataClientRequest, EpochEndingLedgerInfosRequest},
+    data_notification::{
+        DataClientRequest, DataPayload, EpochEndingLedgerInfosRequest,
+        StateValuesByPrefixWithProofRequest,
+    },
     error::Error,
-    stream_engine::{DataStreamEngine, EpochEndingStreamEngine, StreamEngine},
-    streaming_client::{GetAllEpochEndingLedgerInfosRequest, StreamRequest},
+    stream_engine::{
+        AccountStateStreamEngine, DataStreamEngine, EpochEndingStreamEngine, StreamEngine,
+    },
+    streaming_client::{
+        GetAccountStatesRequest, GetAllEpochEndingLedgerInfosRequest, StreamRequest,
+    },
     tests::{
         utils,
         utils::{create_ledger_info, initialize_logger},
     },
 };
 use aptos_config::config::DataStreamingServiceConfig;
+use aptos_crypto::HashValue;
 use aptos_data_client::{
     global_summary::{GlobalDataSummary, OptimalChunkSizes},
     interface::ResponsePayload,
 };
 use aptos_id_generator::U64IdGenerator;
 use aptos_storage_service_types::responses::CompleteDataRange;
-use claims::{assert_matches, assert_ok};
+use aptos_types::{
+    account_address::AccountAddress,
+    proof::{SparseMerkleProof, TransactionInfoListWithProof},
+    state_store::{
+        state_key::StateKey,
+        state_value::{StateValue, StateValuesByPrefixWithProof},
+    },
+};
+use claims::{assert_matches, assert_ok, assert_some};
 use std::{cmp, sync::Arc};
 
+#[test]
+fn test_account_state_stream_engine() {
+    // Create an account state stream engine for two accounts
+    let version = 100;
+    let account_addresses = vec![AccountAddress::random(), AccountAddress::random()];
+    let mut stream_engine = create_account_state_stream_engine(version, account_addresses.clone());
+
+    // Verify that only a single request is created for the first account
+    let client_requests = create_account_state_requests(&mut stream_engine);
+    let expected_request =
+        DataClientRequest::StateValuesByPrefixWithProof(StateValuesByPrefixWithProofRequest {
+            version,
+            account_address: account_addresses[0],
+            start_key: None,
+        });
+    assert_eq!(client_requests, vec![expected_request.clone()]);
+
+    // Verify that no more requests are created while the request is in-flight
+    assert!(create_account_state_requests(&mut stream_engine).is_empty());
+
+    // Handle a partial response for the first account and verify a notification is created
+    let next_key = StateKey::raw(HashValue::random().as_ref());
+    let data_notification = stream_engine
+        .transform_client_response_into_notification(
+            &expected_request,
+            create_account_states_payload(version, 10, Some(next_key.clone())),
+            create_notification_id_generator(),
+        )
+        .unwrap();
+    assert_matches!(
+        assert_some!(data_notification).data_payload,
+        DataPayload::AccountStatesWithProof(_)
+    );
+
+    // Verify the next request resumes from the next key of the first account
+    let client_requests = create_account_state_requests(&mut stream_engine);
+    let expected_request =
+        DataClientRequest::StateValuesByPrefixWithProof(StateValuesByPrefixWithProofRequest {
+            version,
+            account_address: account_addresses[0],
+            start_key: Some(next_key),
+        });
+    assert_eq!(client_requests, vec![expected_request.clone()]);
+
+    // Handle the last response for the first account
+    let _ = stream_engine
+        .transform_client_response_into_notification(
+            &expected_request,
+            create_account_states_payload(version, 5, None),
+            create_notification_id_generator(),
+        )
+        .unwrap();
+    assert!(!stream_engine.is_stream_complete());
+
+    // Verify the next request is for the second account
+    let client_requests = create_account_state_requests(&mut stream_engine);
+    let expected_request =
+        DataClientRequest::StateValuesByPrefixWithProof(StateValuesByPrefixWithProofRequest {
+            version,
+            account_address: account_addresses[1],
+            start_key: None,
+        });
+    assert_eq!(client_requests, vec![expected_request.clone()]);
+
+    // Handle an empty response for the second account and verify a notification is created
+    let data_notification = stream_engine
+        .transform_client_response_into_notification(
+            &expected_request,
+            create_account_states_payload(version, 0, None),
+            create_notification_id_generator(),
+        )
+        .unwrap();
+    assert_some!(data_notification);
+
+    // Verify the stream is now complete
+    assert!(stream_engine.is_stream_complete());
+    assert!(create_account_state_requests(&mut stream_engine).is_empty());
+}
+
+#[test]
+fn test_account_state_stream_engine_invalid_response() {
+    // Create an account state stream engine for a single account
+    let version = 100;
+    let account_address = AccountAddress::random();
+    let mut stream_engine = create_account_state_stream_engine(version, vec![account_address]);
+    let client_request = create_account_state_requests(&mut stream_engine)[0].clone();
+
+    // Handle a response at the wrong version and verify an error is returned
+    let result = stream_engine.transform_client_response_into_notification(
+        &client_request,
+        create_account_states_payload(version + 1, 10, None),
+        create_notification_id_generator(),
+    );
+    assert_matches!(result, Err(Error::AptosDataClientResponseIsInvalid(_)));
+
+    // Handle an empty response with a next key and verify an error is returned
+    let mut stream_engine = create_account_state_stream_engine(version, vec![account_address]);
+    let client_request = create_account_state_requests(&mut stream_engine)[0].clone();
+    let result = stream_engine.transform_client_response_into_notification(
+        &client_request,
+        create_account_states_payload(
+            version,
+            0,
+            Some(StateKey::raw(HashValue::random().as_ref())),
+        ),
+        create_notification_id_generator(),
+    );
+    assert_matches!(result, Err(Error::AptosDataClientResponseIsInvalid(_)));
+}
+
 #[test]
 fn test_create_epoch_ending_requests() {
     // Create a batch of large client requests and verify the result
@@ -304,6 +430,76 @@ fn test_update_epoch_ending_stream_panic() {
         .unwrap();
 }
 
+/// Creates an account state stream engine for the given version and accounts
+fn create_account_state_stream_engine(
+    version: u64,
+    account_addresses: Vec<AccountAddress>,
+) -> AccountStateStreamEngine {
+    initialize_logger();
+
+    // Create an account states stream request
+    let stream_request = StreamRequest::GetAccountStates(GetAccountStatesRequest {
+        version,
+        account_addresses,
+    });
+
+    // Create a new account state stream engine
+    let data_streaming_config = DataStreamingServiceConfig::default();
+    match StreamEngine::new(
+        data_streaming_config,
+        &stream_request,
+        &GlobalDataSummary::empty().advertised_data,
+    )
+    .unwrap()
+    {
+        StreamEngine::AccountStateStreamEngine(stream_engine) => stream_engine,
+        unexpected_engine => {
+            panic!(
+                "Expected account state stream engine but got {:?}",
+                unexpected_engine
+            );
+        },
+    }
+}
+
+/// Creates a batch of client requests using the given account state stream engine
+fn create_account_state_requests(
+    stream_engine: &mut AccountStateStreamEngine,
+) -> Vec<DataClientRequest> {
+    stream_engine
+        .create_data_client_requests(
+            10,
+            10,
+            0,
+            &GlobalDataSummary::empty(),
+            create_notification_id_generator(),
+        )
+        .unwrap()
+}
+
+/// Creates a response payload with the given number of account state values
+fn create_account_states_payload(
+    version: u64,
+    num_state_values: usize,
+    next_key: Option<StateKey>,
+) -> ResponsePayload {
+    let raw_values = (0..num_state_values)
+        .map(|_| {
+            (
+                StateKey::raw(HashValue::random().as_ref()),
+                StateValue::from(vec![]),
+                SparseMerkleProof::new(None, vec![]),
+            )
+        })
+        .collect();
+    ResponsePayload::StateValuesByPrefixWithProof(StateValuesByPrefixWithProof {
+        version,
+        raw_values,
+        next_key,
+        transaction_info_with_proof: TransactionInfoListWithProof::new_empty(),
+    })
+}
+
 fn create_epoch_ending_stream_engine(start_epoch: u64, end_epoch: u64) -> EpochEndingStreamEngine {
     initialize_logger();
 
@@ -18,6 +18,7 @@ use aptos_storage_service_types::{
     requests::{
         DataRequest, EpochEndingLedgerInfoRequest, NewTransactionOutputsWithProofRequest,
         NewTransactionsOrOutputsWithProofRequest, NewTransactionsWithProofRequest,
+        StateValueWithProofRequest, StateValuesByPrefixWithProofRequest,
         StateValuesWithProofRequest, SubscribeTransactionOutputsWithProofRequest,
         SubscribeTransactionsOrOutputsWithProofRequest, SubscribeTransactionsWithProofRequest,
         SubscriptionStreamMetadata, TransactionOutputsWithProofRequest,
@@ -33,15 +34,20 @@ use aptos_types::{
     chain_id::ChainId,
     epoch_state::EpochState,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
-    proof::SparseMerkleRangeProof,
+    proof::{
+        SparseMerkleProof, SparseMerkleRangeProof, TransactionAccumulatorRangeProof,
+        TransactionInfoListWithProof,
+    },
     state_store::{
         state_key::StateKey,
-        state_value::{StateValue, StateValueChunkWithProof},
+        state_value::{
+            StateValue, StateValueChunkWithProof, StateValueWithProof, StateValuesByPrefixWithProof,
+        },
     },
     transaction::{
-        RawTransaction, Script, SignedTransaction, Transaction, TransactionAuxiliaryData,
-        TransactionListWithProof, TransactionOutput, TransactionOutputListWithProof,
-        TransactionPayload, TransactionStatus, Version,
+        ExecutionStatus, RawTransaction, Script, SignedTransaction, Transaction,
+        TransactionAuxiliaryData, TransactionInfo, TransactionListWithProof, TransactionOutput,
+        TransactionOutputListWithProof, TransactionPayload, TransactionStatus, Version,
     },
     write_set::WriteSet,
 };
@@ -327,6 +333,93 @@ impl AptosDataClientInterface for MockAptosDataClient {
         Ok(create_data_client_response(state_value_chunk_with_proof))
     }
 
+    async fn get_state_values_by_prefix_with_proof(
+        &self,
+        version: Version,
+        account_address: AccountAddress,
+        start_key: Option<StateKey>,
+        request_timeout_ms: u64,
+    ) -> Result<Response<StateValuesByPrefixWithProof>, aptos_data_client::error::Error> {
+        // Verify the request timeout
+        let data_request =
+            DataRequest::GetStateValuesByPrefixWithProof(StateValuesByPrefixWithProofRequest {
+                version,
+                account_address,
+                start_key,
+            });
+        self.verify_request_timeout_value(request_timeout_ms, false, false, data_request);
+
+        // Emulate network latencies
+        self.emulate_network_latencies().await;
+
+        // Create a random number of state values (with proofs) for the account
+        let num_state_values = create_range_random_u64(1, 10);
+        let mut raw_values = vec![];
+        for _ in 0..num_state_values {
+            raw_values.push((
+                StateKey::raw(HashValue::random().as_ref()),
+                StateValue::from(vec![]),
+                SparseMerkleProof::new(None, vec![]),
+            ));
+        }
+
+        // Create the state values by prefix with proof
+        let state_values_by_prefix_with_proof = StateValuesByPrefixWithProof {
+            version,
+            raw_values,
+            next_key: None,
+            transaction_info_with_proof: TransactionInfoListWithProof::new_empty(),
+        };
+
+        // Create and send a data client response
+        Ok(create_data_client_response(
+            state_values_by_prefix_with_proof,
+        ))
+    }
+
+    async fn get_state_value_with_proof(
+        &self,
+        version: Version,
+        state_key: StateKey,
+        request_timeout_ms: u64,
+    ) -> Result<Response<StateValueWithProof>, aptos_data_client::error::Error> {
+        // Verify the request timeout
+        let data_request = DataRequest::GetStateValueWithProof(StateValueWithProofRequest {
+            version,
+            state_key: state_key.clone(),
+        });
+        self.verify_request_timeout_value(request_timeout_ms, false, false, data_request);
+
+        // Emulate network latencies
+        self.emulate_network_latencies().await;
+
+        // Create the transaction info with proof
+        let transaction_info = TransactionInfo::new(
+            HashValue::random(),
+            HashValue::random(),
+            HashValue::random(),
+            Some(HashValue::random()),
+            0,
+            ExecutionStatus::Success,
+        );
+        let transaction_info_with_proof =
+            TransactionInfoListWithProof::new(TransactionAccumulatorRangeProof::new_empty(), vec![
+                transaction_info,
+            ]);
+
+        // Create the state value with proof
+        let state_value_with_proof = StateValueWithProof {
+            version,
+            state_key,
+            state_value: Some(StateValue::from(vec![])),
+            sparse_merkle_proof: SparseMerkleProof::new(None, vec![]),
+            transaction_info_with_proof,
+        };
+
+        // Create and send a data client response
+        Ok(create_data_client_response(state_value_with_proof))
+    }
+
     async fn get_epoch_ending_ledger_infos(
         &self,
         start_epoch: Epoch,
@@ -863,7 +956,7 @@ impl ResponseCallback for NoopResponseCallback {
 /// Creates a data client response using a specified payload and random id
 pub fn create_data_client_response<T>(payload: T) -> Response<T> {
     let id = create_random_u64(MAX_RESPONSE_ID);
-    let context = ResponseContext::new(id, Box::new(NoopResponseCallback));
+    let context = ResponseContext::new(id, 0, Box::new(NoopResponseCallback));
     Response::new(context, payload)
 }
 
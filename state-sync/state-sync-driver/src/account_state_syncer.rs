@@ -0,0 +1,361 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    driver::DriverConfiguration,
+    error::Error,
+    light_client::LightClient,
+    logging::{LogEntry, LogSchema},
+    utils,
+};
+use aptos_data_client::interface::AptosDataClientInterface;
+use aptos_data_streaming_service::{
+    data_notification::{DataNotification, DataPayload, NotificationId},
+    data_stream::DataStreamListener,
+    streaming_client::{DataStreamingClient, NotificationAndFeedback, NotificationFeedback},
+};
+use aptos_logger::prelude::*;
+use aptos_types::{
+    account_address::AccountAddress,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    state_store::{
+        state_key::{prefix::StateKeyPrefix, StateKey},
+        state_value::{StateValue, StateValuesByPrefixWithProof},
+    },
+};
+use std::collections::HashMap;
+
+/// The progress of syncing the tracked account states at a single ledger info
+struct PendingAccountStates {
+    // The (verified) ledger info at which the account states are being synced
+    ledger_info: LedgerInfoWithSignatures,
+
+    // The index of the tracked account that the next page must belong to. The
+    // stream sends at least one (possibly empty) page for every tracked account,
+    // so no account can be skipped.
+    next_account_index: usize,
+
+    // The key that the next page must start with (if the current account has
+    // more state values to sync).
+    next_start_key: Option<StateKey>,
+
+    // The verified state values synced so far
+    state_values: HashMap<StateKey, StateValue>,
+}
+
+/// A simple component that syncs (and verifies) all state values under the
+/// tracked accounts at the latest ledger info verified by the light client.
+/// This allows light clients to hold a partial state (e.g., for the accounts
+/// they care about) without syncing any transactions or other states.
+pub struct AccountStateSyncer<DataClient, StreamingClient> {
+    // The currently active data stream (provided by the data streaming service)
+    active_data_stream: Option<DataStreamListener>,
+
+    // The config of the state sync driver
+    driver_configuration: DriverConfiguration,
+
+    // The light client that provides the verified ledger infos
+    light_client: LightClient<DataClient>,
+
+    // The progress of the account states currently being synced (if any)
+    pending_account_states: Option<PendingAccountStates>,
+
+    // The client through which to stream data from the Aptos network
+    streaming_client: StreamingClient,
+}
+
+impl<
+        DataClient: AptosDataClientInterface + Send + Clone + 'static,
+        StreamingClient: DataStreamingClient + Clone,
+    > AccountStateSyncer<DataClient, StreamingClient>
+{
+    pub fn new(
+        driver_configuration: DriverConfiguration,
+        light_client: LightClient<DataClient>,
+        streaming_client: StreamingClient,
+    ) -> Self {
+        Self {
+            active_data_stream: None,
+            driver_configuration,
+            light_client,
+            pending_account_states: None,
+            streaming_client,
+        }
+    }
+
+    /// Drives progress by syncing the tracked account states at the latest
+    /// ledger info verified by the light client.
+    pub async fn drive_progress(&mut self) -> Result<(), Error> {
+        if self.tracked_accounts().is_empty() {
+            return Ok(()); // There are no accounts to track
+        }
+
+        if self.active_data_stream.is_some() {
+            // We have an active data stream. Process any notifications!
+            self.process_active_stream_notifications().await
+        } else {
+            // We don't have an active data stream. Initialize one (if required).
+            self.initialize_active_data_stream().await
+        }
+    }
+
+    /// Initializes an account states stream at the latest verified ledger
+    /// info (if the tracked account states are not already at that version).
+    async fn initialize_active_data_stream(&mut self) -> Result<(), Error> {
+        // Get the latest verified ledger info
+        let ledger_info = match self.light_client.latest_ledger_info() {
+            Some(ledger_info) => ledger_info,
+            None => return Ok(()), // The light client hasn't synced yet
+        };
+
+        // Check if the tracked account states are already up-to-date
+        let version = ledger_info.ledger_info().version();
+        if self.light_client.tracked_account_states_version() == Some(version) {
+            return Ok(());
+        }
+
+        // Initialize a new account states stream
+        info!(LogSchema::new(LogEntry::LightClient).message(&format!(
+            "Syncing the tracked account states at version: {:?}",
+            version
+        )));
+        let data_stream = self
+            .streaming_client
+            .get_account_state_values(version, self.tracked_accounts().to_vec())
+            .await?;
+        self.active_data_stream = Some(data_stream);
+        self.pending_account_states = Some(PendingAccountStates {
+            ledger_info,
+            next_account_index: 0,
+            next_start_key: None,
+            state_values: HashMap::new(),
+        });
+
+        Ok(())
+    }
+
+    /// Processes any notifications already pending on the active stream
+    async fn process_active_stream_notifications(&mut self) -> Result<(), Error> {
+        let max_consecutive_stream_notifications = self
+            .driver_configuration
+            .config
+            .max_consecutive_stream_notifications;
+        for _ in 0..max_consecutive_stream_notifications {
+            // Fetch and process any data notifications
+            let data_notification = self.fetch_next_data_notification().await?;
+            match data_notification.data_payload {
+                DataPayload::AccountStatesWithProof(state_values_with_proof) => {
+                    self.process_account_states_payload(
+                        data_notification.notification_id,
+                        state_values_with_proof,
+                    )
+                    .await?;
+                },
+                _ => {
+                    return self
+                        .handle_end_of_stream_or_invalid_payload(data_notification)
+                        .await
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verifies the given page of account states and adds the state values
+    /// to the pending account states.
+    async fn process_account_states_payload(
+        &mut self,
+        notification_id: NotificationId,
+        state_values_with_proof: StateValuesByPrefixWithProof,
+    ) -> Result<(), Error> {
+        // Verify the page and identify the tracked account it belongs to
+        let result = self.verify_account_states_payload(&state_values_with_proof);
+        let account_index = match result {
+            Ok(account_index) => account_index,
+            Err(error) => {
+                self.reset_active_stream(Some(NotificationAndFeedback::new(
+                    notification_id,
+                    NotificationFeedback::PayloadProofFailed,
+                )))
+                .await?;
+                return Err(error);
+            },
+        };
+
+        // Update the pending account states
+        let pending_account_states = self.pending_account_states.as_mut().ok_or_else(|| {
+            Error::UnexpectedError("No account states are pending for the active stream!".into())
+        })?;
+        match state_values_with_proof.next_key {
+            Some(next_key) => {
+                pending_account_states.next_account_index = account_index;
+                pending_account_states.next_start_key = Some(next_key);
+            },
+            None => {
+                pending_account_states.next_account_index = account_index + 1;
+                pending_account_states.next_start_key = None;
+            },
+        }
+        for (state_key, state_value, _) in state_values_with_proof.raw_values {
+            pending_account_states
+                .state_values
+                .insert(state_key, state_value);
+        }
+
+        Ok(())
+    }
+
+    /// Verifies the given page of account states against the pending ledger
+    /// info, and returns the index of the tracked account it belongs to.
+    fn verify_account_states_payload(
+        &self,
+        state_values_with_proof: &StateValuesByPrefixWithProof,
+    ) -> Result<usize, Error> {
+        let pending_account_states = self.pending_account_states.as_ref().ok_or_else(|| {
+            Error::UnexpectedError("No account states are pending for the active stream!".into())
+        })?;
+        verify_account_states_page(
+            self.tracked_accounts(),
+            pending_account_states.ledger_info.ledger_info(),
+            pending_account_states.next_account_index,
+            pending_account_states.next_start_key.as_ref(),
+            state_values_with_proof,
+        )?;
+        Ok(pending_account_states.next_account_index)
+    }
+
+    /// Handles the end of stream notification or an invalid payload. If the
+    /// stream is complete, the synced account states are handed to the light
+    /// client.
+    async fn handle_end_of_stream_or_invalid_payload(
+        &mut self,
+        data_notification: DataNotification,
+    ) -> Result<(), Error> {
+        // Calculate the feedback based on the notification
+        let notification_feedback = match data_notification.data_payload {
+            DataPayload::EndOfStream => NotificationFeedback::EndOfStream,
+            _ => NotificationFeedback::PayloadTypeIsIncorrect,
+        };
+        let notification_and_feedback =
+            NotificationAndFeedback::new(data_notification.notification_id, notification_feedback);
+
+        // Take the pending account states and reset the stream
+        let pending_account_states = self.pending_account_states.take();
+        self.reset_active_stream(Some(notification_and_feedback))
+            .await?;
+
+        // Return an error if the payload was invalid
+        if !matches!(data_notification.data_payload, DataPayload::EndOfStream) {
+            return Err(Error::InvalidPayload("Unexpected payload type!".into()));
+        }
+
+        // Update the tracked account states
+        let pending_account_states = pending_account_states.ok_or_else(|| {
+            Error::UnexpectedError("No account states are pending for the active stream!".into())
+        })?;
+        if pending_account_states.next_start_key.is_some()
+            || pending_account_states.next_account_index < self.tracked_accounts().len()
+        {
+            return Err(Error::InvalidPayload(format!(
+                "The account states stream ended before all tracked accounts were synced! \
+                Next account index: {:?}, number of tracked accounts: {:?}",
+                pending_account_states.next_account_index,
+                self.tracked_accounts().len()
+            )));
+        }
+        let version = pending_account_states.ledger_info.ledger_info().version();
+        info!(LogSchema::new(LogEntry::LightClient).message(&format!(
+            "Synced {:?} state values for the tracked accounts at version: {:?}",
+            pending_account_states.state_values.len(),
+            version
+        )));
+        self.light_client
+            .update_tracked_account_states(version, pending_account_states.state_values);
+
+        Ok(())
+    }
+
+    /// Attempts to fetch a data notification from the active stream
+    async fn fetch_next_data_notification(&mut self) -> Result<DataNotification, Error> {
+        let max_stream_wait_time_ms = self.driver_configuration.config.max_stream_wait_time_ms;
+        let max_num_stream_timeouts = self.driver_configuration.config.max_num_stream_timeouts;
+        let result = utils::get_data_notification(
+            max_stream_wait_time_ms,
+            max_num_stream_timeouts,
+            self.active_data_stream.as_mut(),
+        )
+        .await;
+        if matches!(result, Err(Error::CriticalDataStreamTimeout(_))) {
+            // If the stream has timed out too many times, we need to reset it
+            warn!("Resetting the currently active account states stream due to too many timeouts!");
+            self.reset_active_stream(None).await?;
+        }
+        result
+    }
+
+    /// Terminates the currently active stream with the provided feedback (if any)
+    async fn reset_active_stream(
+        &mut self,
+        notification_and_feedback: Option<NotificationAndFeedback>,
+    ) -> Result<(), Error> {
+        if let Some(active_data_stream) = &self.active_data_stream {
+            let data_stream_id = active_data_stream.data_stream_id;
+            utils::terminate_stream_with_feedback(
+                &mut self.streaming_client,
+                data_stream_id,
+                notification_and_feedback,
+            )
+            .await?;
+        }
+
+        self.active_data_stream = None;
+        self.pending_account_states = None;
+        Ok(())
+    }
+
+    /// Returns the accounts to track
+    fn tracked_accounts(&self) -> &[AccountAddress] {
+        &self
+            .driver_configuration
+            .light_client_config
+            .tracked_accounts
+    }
+}
+
+/// Verifies that the given page of account states belongs to the tracked
+/// account at the given index (and continues from the given start key), and
+/// that it is committed by the given ledger info.
+///
+/// Note: the values under an account are not contiguous in the state tree
+/// (state keys are hashed), so the page cannot prove that no values under the
+/// account were omitted. However, because every tracked account must be covered
+/// by at least one page, and pages must chain via their next keys, a peer cannot
+/// silently skip an account or drop the pages between two keys.
+pub(crate) fn verify_account_states_page(
+    tracked_accounts: &[AccountAddress],
+    ledger_info: &LedgerInfo,
+    account_index: usize,
+    start_key: Option<&StateKey>,
+    state_values_with_proof: &StateValuesByPrefixWithProof,
+) -> Result<(), Error> {
+    let account_address = tracked_accounts.get(account_index).ok_or_else(|| {
+        Error::VerificationError(format!(
+            "Received a page of account states after all tracked accounts were synced! \
+            Account index: {:?}",
+            account_index
+        ))
+    })?;
+    state_values_with_proof
+        .verify(
+            ledger_info,
+            &StateKeyPrefix::from(*account_address),
+            start_key,
+        )
+        .map_err(|error| {
+            Error::VerificationError(format!(
+                "The account states failed verification: {:?}",
+                error
+            ))
+        })
+}
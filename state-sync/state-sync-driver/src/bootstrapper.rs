@@ -501,6 +501,7 @@ impl<
         if self.get_bootstrapping_mode().is_fast_sync() {
             // We're fast syncing
             self.fetch_missing_state_snapshot_data(
+                global_data_summary,
                 highest_synced_version,
                 highest_known_ledger_info,
             )
@@ -515,6 +516,7 @@ impl<
     /// Fetches all missing state snapshot data in order to bootstrap the node
     async fn fetch_missing_state_snapshot_data(
         &mut self,
+        global_data_summary: &GlobalDataSummary,
         highest_synced_version: Version,
         highest_known_ledger_info: LedgerInfoWithSignatures,
     ) -> Result<(), Error> {
@@ -538,6 +540,22 @@ impl<
                             target
                         )))
                     }
+                } else if self.is_snapshot_sync_target_unserviceable(
+                    global_data_summary,
+                    &target,
+                    &highest_known_ledger_info,
+                ) {
+                    // The previous target can no longer be serviced. The states that were
+                    // already restored for the target can't be reused at a newer target
+                    // (e.g., states deleted between the two versions would remain readable),
+                    // so the snapshot sync can only be restarted on an empty database.
+                    Err(Error::AdvertisedDataError(format!(
+                        "The previous snapshot sync target is no longer serviceable, and the \
+                        partially restored states can't be reused at a newer target! Please \
+                        delete your storage and restart your node. Previous target: {:?}, \
+                        highest known ledger info: {:?}",
+                        target, highest_known_ledger_info
+                    )))
                 } else {
                     // Continue snapshot syncing to the target
                     self.fetch_missing_state_values(target, true).await
@@ -657,6 +675,35 @@ impl<
         Ok(())
     }
 
+    /// Returns true iff the previous (incomplete) snapshot sync target can no longer
+    /// be serviced by the network. This is only the case if the target hasn't yet
+    /// been used by this run (e.g., the node has just rebooted), a higher ledger info
+    /// is known, and the states at the target are no longer advertised by any peers
+    /// (e.g., the target has been pruned).
+    fn is_snapshot_sync_target_unserviceable(
+        &self,
+        global_data_summary: &GlobalDataSummary,
+        previous_target: &LedgerInfoWithSignatures,
+        highest_known_ledger_info: &LedgerInfoWithSignatures,
+    ) -> bool {
+        // If the state value syncer has already started, the target is being serviced
+        if self.state_value_syncer.ledger_info_to_sync.is_some() {
+            return false;
+        }
+
+        // Verify the highest known ledger info is higher than the previous target
+        let previous_target_version = previous_target.ledger_info().version();
+        if highest_known_ledger_info.ledger_info().version() <= previous_target_version {
+            return false;
+        }
+
+        // The target is unserviceable if peers advertise states, but none for the target
+        match global_data_summary.advertised_data.lowest_state_version() {
+            Some(lowest_state_version) => lowest_state_version > previous_target_version,
+            None => false,
+        }
+    }
+
     /// Fetches state values (as required to bootstrap the node)
     async fn fetch_missing_state_values(
         &mut self,
@@ -681,8 +728,15 @@ impl<
                 .set_ledger_info_to_sync(target_ledger_info.clone());
         }
 
-        // Fetch the data that we're missing
+        // If the snapshot sync is resuming, reuse the previously verified target output
         let target_ledger_info_version = target_ledger_info.ledger_info().version();
+        if existing_snapshot_progress
+            && self.state_value_syncer.transaction_output_to_sync.is_none()
+        {
+            self.load_transaction_output_from_manifest(&target_ledger_info)?;
+        }
+
+        // Fetch the data that we're missing
         let data_stream = if self.state_value_syncer.transaction_output_to_sync.is_none() {
             // Fetch the transaction info first, before the states
             self.streaming_client
@@ -699,15 +753,7 @@ impl<
                 // rewrite the last persisted index (again!). This is a limitation
                 // of how the snapshot is persisted (i.e., in-memory sibling freezing).
                 // Thus, on each stream reset, we overlap every chunk by a single item.
-                self
-                    .metadata_storage
-                    .get_last_persisted_state_value_index(&target_ledger_info)
-                    .map_err(|error| {
-                        Error::StorageError(format!(
-                            "Failed to get the last persisted state value index at version {:?}! Error: {:?}",
-                            target_ledger_info_version, error
-                        ))
-                    })?
+                self.get_last_committed_state_index(&target_ledger_info)?
             } else {
                 0 // We need to start the snapshot sync from index 0
             };
@@ -727,6 +773,86 @@ impl<
         Ok(())
     }
 
+    /// Returns the index of the last state value committed for the snapshot sync
+    /// at the given target. This is the highest of the last persisted state value
+    /// index and the end of the contiguous completed state range in the manifest
+    /// (both are only recorded once the state values have been committed).
+    fn get_last_committed_state_index(
+        &self,
+        target_ledger_info: &LedgerInfoWithSignatures,
+    ) -> Result<u64, Error> {
+        let target_ledger_info_version = target_ledger_info.ledger_info().version();
+        let last_persisted_state_value_index = self
+            .metadata_storage
+            .get_last_persisted_state_value_index(target_ledger_info)
+            .map_err(|error| {
+                Error::StorageError(format!(
+                    "Failed to get the last persisted state value index at version {:?}! Error: {:?}",
+                    target_ledger_info_version, error
+                ))
+            })?;
+        let last_contiguous_state_index = self
+            .metadata_storage
+            .get_completed_state_ranges(target_ledger_info)
+            .map_err(|error| {
+                Error::StorageError(format!(
+                    "Failed to get the completed state ranges at version {:?}! Error: {:?}",
+                    target_ledger_info_version, error
+                ))
+            })?
+            .get_last_contiguous_state_index();
+
+        Ok(last_contiguous_state_index.map_or(
+            last_persisted_state_value_index,
+            |last_contiguous_state_index| {
+                last_persisted_state_value_index.max(last_contiguous_state_index)
+            },
+        ))
+    }
+
+    /// Loads the verified target transaction output from the snapshot manifest
+    /// (if one exists). This avoids having to refetch the target transaction
+    /// output every time the snapshot sync is resumed (e.g., after a reboot).
+    fn load_transaction_output_from_manifest(
+        &mut self,
+        target_ledger_info: &LedgerInfoWithSignatures,
+    ) -> Result<(), Error> {
+        // Fetch the snapshot manifest for the target
+        let snapshot_manifest = match self
+            .metadata_storage
+            .get_snapshot_manifest(target_ledger_info)?
+        {
+            Some(snapshot_manifest) => snapshot_manifest,
+            None => return Ok(()), // No manifest was recorded
+        };
+        // Fetch the target output from the snapshot manifest
+        let target_output_with_proof = match snapshot_manifest.target_output_with_proof {
+            Some(target_output_with_proof) => target_output_with_proof,
+            None => return Ok(()), // No target output was recorded
+        };
+
+        // Verify the target output again (in case the manifest was corrupted)
+        let target_version = target_ledger_info.ledger_info().version();
+        if let Err(error) =
+            target_output_with_proof.verify(target_ledger_info.ledger_info(), Some(target_version))
+        {
+            warn!(LogSchema::new(LogEntry::Bootstrapper).message(&format!(
+                "The target output in the snapshot manifest failed verification! Error: {:?}",
+                error
+            )));
+            return Ok(()); // The target output will be refetched
+        }
+
+        info!(LogSchema::new(LogEntry::Bootstrapper).message(&format!(
+            "Resuming the snapshot sync using the target output from the manifest! Target version: {:?}",
+            target_version
+        )));
+        self.state_value_syncer
+            .set_transaction_output_to_sync(target_output_with_proof);
+
+        Ok(())
+    }
+
     /// Fetches all missing transaction data in order to bootstrap the node
     async fn fetch_missing_transaction_data(
         &mut self,
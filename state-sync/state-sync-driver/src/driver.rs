@@ -3,10 +3,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    account_state_syncer::AccountStateSyncer,
     bootstrapper::Bootstrapper,
     continuous_syncer::ContinuousSyncer,
     driver_client::{ClientNotificationListener, DriverNotification},
     error::Error,
+    light_client::LightClient,
     logging::{LogEntry, LogSchema},
     metadata_storage::MetadataStorageInterface,
     metrics,
@@ -20,7 +22,9 @@ use crate::{
     utils,
     utils::{OutputFallbackHandler, PENDING_DATA_LOG_FREQ_SECS},
 };
-use aptos_config::config::{ConsensusObserverConfig, RoleType, StateSyncDriverConfig};
+use aptos_config::config::{
+    ConsensusObserverConfig, LightClientConfig, RoleType, StateSyncDriverConfig,
+};
 use aptos_consensus_notifications::{
     ConsensusCommitNotification, ConsensusNotification, ConsensusSyncNotification,
 };
@@ -56,6 +60,9 @@ pub struct DriverConfiguration {
     // The config for consensus observer
     pub consensus_observer_config: ConsensusObserverConfig,
 
+    // The config for the light client
+    pub light_client_config: LightClientConfig,
+
     // The role of the node
     pub role: RoleType,
 
@@ -67,12 +74,14 @@ impl DriverConfiguration {
     pub fn new(
         config: StateSyncDriverConfig,
         consensus_observer_config: ConsensusObserverConfig,
+        light_client_config: LightClientConfig,
         role: RoleType,
         waypoint: Waypoint,
     ) -> Self {
         Self {
             config,
             consensus_observer_config,
+            light_client_config,
             role,
             waypoint,
         }
@@ -88,6 +97,10 @@ pub struct StateSyncDriver<
     StorageSyncer,
     StreamingClient,
 > {
+    // The component that syncs the states of the tracked accounts (if the
+    // node is running as a light client).
+    account_state_syncer: Option<AccountStateSyncer<DataClient, StreamingClient>>,
+
     // The component that manages the initial bootstrapping of the node
     bootstrapper: Bootstrapper<MetadataStorage, StorageSyncer, StreamingClient>,
 
@@ -115,6 +128,12 @@ pub struct StateSyncDriver<
     // The event subscription service to notify listeners of on-chain events
     event_subscription_service: Arc<Mutex<EventSubscriptionService>>,
 
+    // The time at which the light client was last synced
+    last_light_client_sync: Option<Instant>,
+
+    // The light client (if the node is running as a light client)
+    light_client: Option<LightClient<DataClient>>,
+
     // The handler for notifications to mempool
     mempool_notification_handler: MempoolNotificationHandler<MempoolNotifier>,
 
@@ -166,6 +185,7 @@ impl<
         >,
         storage_synchronizer: StorageSyncer,
         aptos_data_client: DataClient,
+        light_client: Option<LightClient<DataClient>>,
         streaming_client: StreamingClient,
         storage: Arc<dyn DbReader>,
         time_service: TimeService,
@@ -180,6 +200,13 @@ impl<
             storage.clone(),
             storage_synchronizer.clone(),
         );
+        let account_state_syncer = light_client.clone().map(|light_client| {
+            AccountStateSyncer::new(
+                driver_configuration.clone(),
+                light_client,
+                streaming_client.clone(),
+            )
+        });
         let continuous_syncer = ContinuousSyncer::new(
             driver_configuration.clone(),
             streaming_client,
@@ -189,6 +216,7 @@ impl<
         );
 
         Self {
+            account_state_syncer,
             bootstrapper,
             client_notification_listener,
             commit_notification_listener,
@@ -198,6 +226,8 @@ impl<
             driver_configuration,
             error_notification_listener,
             event_subscription_service,
+            last_light_client_sync: None,
+            light_client,
             mempool_notification_handler,
             start_time: None,
             storage,
@@ -604,6 +634,11 @@ impl<
             return self.check_auto_bootstrapping().await;
         }
 
+        // If the node is running as a light client, only the light client makes progress
+        if self.light_client.is_some() {
+            return self.drive_light_client_progress().await;
+        }
+
         // Check the progress of any sync requests
         if let Err(error) = self.check_sync_request_progress().await {
             warn!(LogSchema::new(LogEntry::Driver)
@@ -661,4 +696,82 @@ impl<
             }
         };
     }
+
+    /// Drives progress of the light client: syncs the latest ledger info and
+    /// then syncs the states of the tracked accounts at that ledger info.
+    async fn drive_light_client_progress(&mut self) {
+        // Sync the latest ledger info
+        metrics::increment_counter(
+            &metrics::EXECUTING_COMPONENT,
+            ExecutingComponent::LightClient.get_label(),
+        );
+        self.sync_light_client().await;
+
+        // Sync the tracked account states (once the light client has synced a ledger info)
+        if !self
+            .light_client
+            .as_ref()
+            .map_or(false, |light_client| light_client.is_synced())
+        {
+            return;
+        }
+        if let Some(account_state_syncer) = self.account_state_syncer.as_mut() {
+            if let Err(error) = account_state_syncer.drive_progress().await {
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(DRIVER_ERROR_LOG_FREQ_SECS)),
+                    warn!(LogSchema::new(LogEntry::LightClient)
+                        .error(&error)
+                        .message("Error found when syncing the tracked account states!"));
+                );
+                metrics::increment_counter(&metrics::LIGHT_CLIENT_ERRORS, error.get_label());
+            }
+        }
+    }
+
+    /// Syncs the light client to the latest ledger info (at most once per
+    /// sync interval). Note: the node is never marked as bootstrapped (the
+    /// light client doesn't sync any data to storage). Instead, the light
+    /// client notifies its own listeners once it has synced a ledger info.
+    async fn sync_light_client(&mut self) {
+        let light_client = match &self.light_client {
+            Some(light_client) => light_client.clone(),
+            None => return,
+        };
+
+        // Check if the sync interval has elapsed
+        let sync_interval = Duration::from_millis(
+            self.driver_configuration
+                .light_client_config
+                .sync_interval_ms,
+        );
+        let time_now = self.time_service.now();
+        if let Some(last_light_client_sync) = self.last_light_client_sync {
+            if time_now.duration_since(last_light_client_sync) < sync_interval {
+                return;
+            }
+        }
+        self.last_light_client_sync = Some(time_now);
+
+        // Sync the light client
+        let previously_synced = light_client.is_synced();
+        match light_client.sync().await {
+            Ok(latest_ledger_info) => {
+                if !previously_synced {
+                    info!(LogSchema::new(LogEntry::LightClient).message(&format!(
+                        "The light client has synced the ledger info at version: {:?}",
+                        latest_ledger_info.ledger_info().version()
+                    )));
+                }
+            },
+            Err(error) => {
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(DRIVER_ERROR_LOG_FREQ_SECS)),
+                    warn!(LogSchema::new(LogEntry::LightClient)
+                        .error(&error)
+                        .message("Error found when syncing the light client!"));
+                );
+                metrics::increment_counter(&metrics::LIGHT_CLIENT_ERRORS, error.get_label());
+            },
+        }
+    }
 }
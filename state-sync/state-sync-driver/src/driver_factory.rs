@@ -5,6 +5,7 @@
 use crate::{
     driver::{DriverConfiguration, StateSyncDriver},
     driver_client::{ClientNotificationListener, DriverClient, DriverNotification},
+    light_client::LightClient,
     metadata_storage::MetadataStorageInterface,
     notification_handlers::{
         CommitNotification, CommitNotificationListener, ConsensusNotificationHandler,
@@ -34,6 +35,7 @@ use tokio::runtime::Runtime;
 /// Creates a new state sync driver and client
 pub struct DriverFactory {
     client_notification_sender: mpsc::UnboundedSender<DriverNotification>,
+    light_client: Option<LightClient<AptosDataClient>>,
     _driver_runtime: Option<Runtime>,
 }
 
@@ -159,10 +161,22 @@ impl DriverFactory {
         let driver_configuration = DriverConfiguration::new(
             node_config.state_sync.state_sync_driver,
             node_config.consensus_observer,
+            node_config.state_sync.light_client.clone(),
             node_config.base.role,
             waypoint,
         );
 
+        // Create the light client (if enabled)
+        let light_client = if node_config.state_sync.light_client.enable_light_client {
+            Some(LightClient::new(
+                node_config.state_sync.aptos_data_client,
+                aptos_data_client.clone(),
+                waypoint,
+            ))
+        } else {
+            None
+        };
+
         // Create the state sync driver
         let state_sync_driver = StateSyncDriver::new(
             client_notification_listener,
@@ -176,6 +190,7 @@ impl DriverFactory {
             storage_service_notification_handler,
             storage_synchronizer,
             aptos_data_client,
+            light_client.clone(),
             streaming_service_client,
             storage.reader,
             time_service,
@@ -191,6 +206,7 @@ impl DriverFactory {
         // Create the driver factory
         let driver_factory = Self {
             client_notification_sender,
+            light_client,
             _driver_runtime: driver_runtime,
         };
 
@@ -201,6 +217,13 @@ impl DriverFactory {
     pub fn create_driver_client(&self) -> DriverClient {
         DriverClient::new(self.client_notification_sender.clone())
     }
+
+    /// Returns the light client (if the node is running as a light client).
+    /// The light client is kept up-to-date by the driver, and can be used
+    /// to answer verified state queries.
+    pub fn light_client(&self) -> Option<LightClient<AptosDataClient>> {
+        self.light_client.clone()
+    }
 }
 
 /// A struct for holding the various runtimes required by state sync v2.
@@ -228,7 +251,15 @@ impl StateSyncRuntimes {
         }
     }
 
+    /// Blocks until state sync has bootstrapped the node. If the node is running
+    /// as a light client, this instead blocks until the light client has synced
+    /// a ledger info (light clients never bootstrap, as no data is synced to storage).
     pub fn block_until_initialized(&self) {
+        if let Some(light_client) = self.state_sync.light_client() {
+            block_on(light_client.wait_until_synced());
+            return;
+        }
+
         let state_sync_client = self.state_sync.create_driver_client();
         block_on(state_sync_client.notify_once_bootstrapped())
             .expect("State sync v2 initialization failure");
@@ -19,6 +19,8 @@ pub enum Error {
     CallbackSendFailed(String),
     #[error("Timed-out waiting for a data stream too many times. Times: {0}")]
     CriticalDataStreamTimeout(String),
+    #[error("Error encountered in the data client: {0}")]
+    DataClientError(String),
     #[error("Timed-out waiting for a notification from the data stream. Timeout: {0}")]
     DataStreamNotificationTimeout(String),
     #[error("Error encountered in the event subscription service: {0}")]
@@ -58,6 +60,7 @@ impl Error {
             Error::BootstrapNotComplete(_) => "bootstrap_not_complete",
             Error::CallbackSendFailed(_) => "callback_send_failed",
             Error::CriticalDataStreamTimeout(_) => "critical_data_stream_timeout",
+            Error::DataClientError(_) => "data_client_error",
             Error::DataStreamNotificationTimeout(_) => "data_stream_notification_timeout",
             Error::EventNotificationError(_) => "event_notification_error",
             Error::FullNodeConsensusNotification(_) => "full_node_consensus_notification",
@@ -82,6 +85,12 @@ impl From<Canceled> for Error {
     }
 }
 
+impl From<aptos_data_client::error::Error> for Error {
+    fn from(error: aptos_data_client::error::Error) -> Self {
+        Error::DataClientError(error.to_string())
+    }
+}
+
 impl From<aptos_data_streaming_service::error::Error> for Error {
     fn from(error: aptos_data_streaming_service::error::Error) -> Self {
         Error::UnexpectedError(error.to_string())
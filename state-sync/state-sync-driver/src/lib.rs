@@ -4,12 +4,14 @@
 
 #![forbid(unsafe_code)]
 
+mod account_state_syncer;
 mod bootstrapper;
 mod continuous_syncer;
 mod driver;
 mod driver_client;
 pub mod driver_factory;
 mod error;
+pub mod light_client;
 mod logging;
 pub mod metadata_storage;
 pub mod metrics;
@@ -0,0 +1,467 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    error::Error,
+    logging::{LogEntry, LogSchema},
+};
+use aptos_config::config::AptosDataClientConfig;
+use aptos_data_client::interface::{AptosDataClientInterface, ResponseContext, ResponseError};
+use aptos_infallible::RwLock;
+use aptos_logger::prelude::*;
+use aptos_types::{
+    epoch_change::Verifier,
+    epoch_state::EpochState,
+    ledger_info::LedgerInfoWithSignatures,
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::Version,
+    waypoint::Waypoint,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+use tokio::sync::watch;
+
+/// A header-only light client that syncs and verifies the chain of epoch
+/// ending ledger infos (i.e., the validator set changes) and the latest
+/// ledger info, without storing any transactions, outputs or states.
+///
+/// State queries are answered on demand: state values (with proofs) are
+/// fetched from peers and verified against the latest verified ledger info.
+/// This allows trust-minimized verifiers to be embedded in other services
+/// without running a full node.
+///
+/// The light client is cheap to clone, and all clones share the verified
+/// ledger infos. This allows the state sync driver to keep the light client
+/// up-to-date, while other components use it to answer state queries.
+///
+/// Note: the light client never syncs any data to storage, so the node never
+/// completes state sync bootstrapping. Instead, components should wait until
+/// the light client has synced a ledger info (see `wait_until_synced()`).
+#[derive(Clone)]
+pub struct LightClient<DataClient> {
+    // The config of the data client (used for request timeouts)
+    data_client_config: AptosDataClientConfig,
+
+    // The client used to fetch data from peers
+    data_client: DataClient,
+
+    // The ledger infos that have been verified so far
+    verified_ledger_infos: Arc<RwLock<VerifiedLedgerInfos>>,
+
+    // The verified states of the tracked accounts (if any have been synced)
+    tracked_account_states: Arc<RwLock<Option<TrackedAccountStates>>>,
+
+    // The waypoint from which trust is established
+    waypoint: Waypoint,
+
+    // The notifier used to signal that a ledger info has been synced
+    synced_notifier: Arc<watch::Sender<bool>>,
+}
+
+/// The verified states of the tracked accounts at a single version
+struct TrackedAccountStates {
+    // The version at which the account states were synced
+    version: Version,
+
+    // The state values under the tracked accounts (by key)
+    state_values: HashMap<StateKey, StateValue>,
+}
+
+/// The ledger infos verified by the light client
+#[derive(Default)]
+struct VerifiedLedgerInfos {
+    // The epoch ending ledger infos that have been verified (by epoch)
+    epoch_ending_ledger_infos: BTreeMap<u64, LedgerInfoWithSignatures>,
+
+    // The latest epoch state that has been verified (once the waypoint has been verified)
+    latest_epoch_state: Option<EpochState>,
+
+    // The latest ledger info that has been verified
+    latest_ledger_info: Option<LedgerInfoWithSignatures>,
+
+    // The next epoch for which we require the epoch ending ledger info
+    next_epoch_to_fetch: u64,
+}
+
+impl<DataClient: AptosDataClientInterface> LightClient<DataClient> {
+    pub fn new(
+        data_client_config: AptosDataClientConfig,
+        data_client: DataClient,
+        waypoint: Waypoint,
+    ) -> Self {
+        let (synced_notifier, _) = watch::channel(false);
+        Self {
+            data_client_config,
+            data_client,
+            verified_ledger_infos: Arc::new(RwLock::new(VerifiedLedgerInfos::default())),
+            tracked_account_states: Arc::new(RwLock::new(None)),
+            waypoint,
+            synced_notifier: Arc::new(synced_notifier),
+        }
+    }
+
+    /// Returns the verified epoch ending ledger info for the given epoch (if any)
+    pub fn get_epoch_ending_ledger_info(&self, epoch: u64) -> Option<LedgerInfoWithSignatures> {
+        self.verified_ledger_infos
+            .read()
+            .epoch_ending_ledger_infos
+            .get(&epoch)
+            .cloned()
+    }
+
+    /// Returns the latest verified epoch state (if any)
+    pub fn latest_epoch_state(&self) -> Option<EpochState> {
+        self.verified_ledger_infos.read().latest_epoch_state.clone()
+    }
+
+    /// Returns the latest verified ledger info (if any)
+    pub fn latest_ledger_info(&self) -> Option<LedgerInfoWithSignatures> {
+        self.verified_ledger_infos.read().latest_ledger_info.clone()
+    }
+
+    /// Returns true iff the light client has synced (and verified) a ledger info
+    pub fn is_synced(&self) -> bool {
+        *self.synced_notifier.borrow()
+    }
+
+    /// Waits until the light client has synced (and verified) a ledger info.
+    /// This is the readiness signal of the light client.
+    pub async fn wait_until_synced(&self) {
+        let mut synced_listener = self.synced_notifier.subscribe();
+        // The notifier is owned by the light client, so it can't be dropped while waiting
+        let _ = synced_listener.wait_for(|synced| *synced).await;
+    }
+
+    /// Returns the version at which the tracked account states were synced (if any)
+    pub fn tracked_account_states_version(&self) -> Option<Version> {
+        self.tracked_account_states
+            .read()
+            .as_ref()
+            .map(|tracked_account_states| tracked_account_states.version)
+    }
+
+    /// Returns the synced state value for the given key under a tracked account,
+    /// along with the version at which it was synced. Returns None if the
+    /// tracked account states have not been synced. Note: state values are only
+    /// synced for the accounts in the light client config (`tracked_accounts`).
+    pub fn get_tracked_state_value(
+        &self,
+        state_key: &StateKey,
+    ) -> Option<(Version, Option<StateValue>)> {
+        self.tracked_account_states
+            .read()
+            .as_ref()
+            .map(|tracked_account_states| {
+                (
+                    tracked_account_states.version,
+                    tracked_account_states.state_values.get(state_key).cloned(),
+                )
+            })
+    }
+
+    /// Replaces the tracked account states with the given (verified) state
+    /// values, synced at the specified version.
+    pub(crate) fn update_tracked_account_states(
+        &self,
+        version: Version,
+        state_values: HashMap<StateKey, StateValue>,
+    ) {
+        *self.tracked_account_states.write() = Some(TrackedAccountStates {
+            version,
+            state_values,
+        });
+    }
+
+    /// Returns true iff the waypoint has been verified
+    pub fn verified_waypoint(&self) -> bool {
+        self.verified_ledger_infos
+            .read()
+            .latest_epoch_state
+            .is_some()
+    }
+
+    /// Syncs (and verifies) all epoch ending ledger infos advertised by the
+    /// network, followed by the highest advertised ledger info. Returns the
+    /// latest verified ledger info.
+    ///
+    /// Note: syncs should not run concurrently (e.g., only the state sync
+    /// driver should sync the light client), otherwise epochs may be fetched
+    /// more than once.
+    pub async fn sync(&self) -> Result<LedgerInfoWithSignatures, Error> {
+        // Fetch the global data summary
+        let global_data_summary = self.data_client.get_global_data_summary();
+        if global_data_summary.is_empty() {
+            return Err(Error::AdvertisedDataError(
+                "The global data summary is empty! Unable to sync the light client.".into(),
+            ));
+        }
+        let advertised_data = global_data_summary.advertised_data;
+
+        // Sync the epoch ending ledger infos
+        let highest_ended_epoch = advertised_data
+            .highest_epoch_ending_ledger_info()
+            .ok_or_else(|| {
+                Error::AdvertisedDataError("No epoch ending ledger infos are advertised!".into())
+            })?;
+        self.sync_epoch_ending_ledger_infos(highest_ended_epoch)
+            .await?;
+
+        // Verify the highest synced ledger info
+        let highest_synced_ledger_info =
+            advertised_data
+                .highest_synced_ledger_info()
+                .ok_or_else(|| {
+                    Error::AdvertisedDataError("No synced ledger infos are advertised!".into())
+                })?;
+        self.verified_ledger_infos
+            .write()
+            .verify_latest_ledger_info(highest_synced_ledger_info, &self.waypoint)?;
+
+        // Notify any listeners and return the latest verified ledger info
+        let latest_ledger_info = self.latest_ledger_info().ok_or_else(|| {
+            Error::UnexpectedError("The light client has no verified ledger info!".into())
+        })?;
+        self.synced_notifier.send_replace(true);
+        Ok(latest_ledger_info)
+    }
+
+    /// Fetches the state value for the given key at the latest verified
+    /// ledger info version, and verifies it before returning it. If the
+    /// key does not exist, None is returned (after verifying its absence).
+    pub async fn get_state_value(&self, state_key: StateKey) -> Result<Option<StateValue>, Error> {
+        // Get the latest verified ledger info
+        let latest_ledger_info = self.latest_ledger_info().ok_or_else(|| {
+            Error::BootstrapNotComplete(
+                "The light client has not synced a ledger info! Unable to serve state queries."
+                    .into(),
+            )
+        })?;
+        let version = latest_ledger_info.ledger_info().version();
+
+        // Fetch the state value with proof
+        let response = self
+            .data_client
+            .get_state_value_with_proof(
+                version,
+                state_key.clone(),
+                self.data_client_config.response_timeout_ms,
+            )
+            .await?;
+        let (context, state_value_with_proof) = response.into_parts();
+
+        // Verify the response corresponds to the request
+        if state_value_with_proof.state_key != state_key {
+            notify_bad_response(&context, ResponseError::InvalidData);
+            return Err(Error::InvalidPayload(format!(
+                "The state value has an unexpected key! Expected: {:?}, found: {:?}",
+                state_key, state_value_with_proof.state_key
+            )));
+        }
+
+        // Verify the state value against the latest ledger info
+        if let Err(error) = state_value_with_proof.verify(latest_ledger_info.ledger_info()) {
+            notify_bad_response(&context, ResponseError::ProofVerificationError);
+            return Err(Error::VerificationError(format!(
+                "The state value failed verification! Version: {:?}, error: {:?}",
+                version, error
+            )));
+        }
+
+        Ok(state_value_with_proof.state_value)
+    }
+
+    /// Fetches and verifies the epoch ending ledger infos up to (and
+    /// including) the specified highest ended epoch.
+    async fn sync_epoch_ending_ledger_infos(&self, highest_ended_epoch: u64) -> Result<(), Error> {
+        loop {
+            let next_epoch_to_fetch = self.verified_ledger_infos.read().next_epoch_to_fetch;
+            if next_epoch_to_fetch > highest_ended_epoch {
+                break;
+            }
+
+            // Fetch the next batch of epoch ending ledger infos
+            let response = self
+                .data_client
+                .get_epoch_ending_ledger_infos(
+                    next_epoch_to_fetch,
+                    highest_ended_epoch,
+                    self.data_client_config.response_timeout_ms,
+                )
+                .await?;
+            let (context, epoch_ending_ledger_infos) = response.into_parts();
+            if epoch_ending_ledger_infos.is_empty() {
+                notify_bad_response(&context, ResponseError::InvalidData);
+                return Err(Error::InvalidPayload(format!(
+                    "Received an empty epoch ending ledger info response! Start epoch: {:?}",
+                    next_epoch_to_fetch
+                )));
+            }
+
+            // Verify each epoch ending ledger info
+            let verification_result = {
+                let mut verified_ledger_infos = self.verified_ledger_infos.write();
+                epoch_ending_ledger_infos
+                    .into_iter()
+                    .try_for_each(|epoch_ending_ledger_info| {
+                        verified_ledger_infos.verify_epoch_ending_ledger_info(
+                            epoch_ending_ledger_info,
+                            &self.waypoint,
+                        )
+                    })
+            };
+            if let Err(error) = verification_result {
+                notify_bad_response(&context, ResponseError::ProofVerificationError);
+                return Err(error);
+            }
+        }
+
+        // Ensure that the waypoint has been verified
+        if !self.verified_waypoint() {
+            return Err(Error::UnsatisfiableWaypoint(format!(
+                "Unable to verify the waypoint using the advertised epoch ending ledger infos! \
+                Waypoint: {:?}, highest ended epoch: {:?}",
+                self.waypoint, highest_ended_epoch
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl VerifiedLedgerInfos {
+    /// Verifies the given epoch ending ledger info and updates the latest
+    /// epoch state. Ledger infos before the waypoint cannot be verified
+    /// (there is no trusted epoch state yet), so they are skipped.
+    fn verify_epoch_ending_ledger_info(
+        &mut self,
+        epoch_ending_ledger_info: LedgerInfoWithSignatures,
+        waypoint: &Waypoint,
+    ) -> Result<(), Error> {
+        let ledger_info = epoch_ending_ledger_info.ledger_info();
+
+        // Verify the ledger info is for the expected epoch
+        if ledger_info.epoch() != self.next_epoch_to_fetch {
+            return Err(Error::VerificationError(format!(
+                "The epoch ending ledger info has an unexpected epoch! Expected: {:?}, found: {:?}",
+                self.next_epoch_to_fetch,
+                ledger_info.epoch()
+            )));
+        }
+
+        // Verify the ledger info is signed by the trusted epoch state (or matches the waypoint)
+        let next_epoch_state = ledger_info.next_epoch_state().cloned().ok_or_else(|| {
+            Error::VerificationError("The ledger info was not epoch ending!".into())
+        })?;
+        match &self.latest_epoch_state {
+            Some(latest_epoch_state) => {
+                latest_epoch_state
+                    .verify(&epoch_ending_ledger_info)
+                    .map_err(|error| {
+                        Error::VerificationError(format!(
+                            "Ledger info failed verification: {:?}",
+                            error
+                        ))
+                    })?;
+            },
+            None => {
+                let ledger_info_version = ledger_info.version();
+                let waypoint_version = waypoint.version();
+                if ledger_info_version < waypoint_version {
+                    // We can't verify the ledger info yet, so skip it
+                    self.next_epoch_to_fetch += 1;
+                    return Ok(());
+                } else if ledger_info_version > waypoint_version {
+                    return Err(Error::UnsatisfiableWaypoint(format!(
+                        "The ledger info version is beyond the waypoint! Waypoint version: {:?}, \
+                        ledger info version: {:?}",
+                        waypoint_version, ledger_info_version
+                    )));
+                }
+                waypoint.verify(ledger_info).map_err(|error| {
+                    Error::UnsatisfiableWaypoint(format!(
+                        "Failed to verify the waypoint: {:?}! Waypoint: {:?}",
+                        error, waypoint
+                    ))
+                })?;
+                info!(LogSchema::new(LogEntry::LightClient).message(&format!(
+                    "The waypoint has been verified! Waypoint version: {:?}.",
+                    waypoint_version
+                )));
+            },
+        }
+
+        // Update the latest epoch state and ledger info
+        info!(LogSchema::new(LogEntry::LightClient).message(&format!(
+            "Verified the epoch ending ledger info for epoch: {:?}, version: {:?}",
+            ledger_info.epoch(),
+            ledger_info.version()
+        )));
+        self.latest_epoch_state = Some(next_epoch_state);
+        self.next_epoch_to_fetch += 1;
+        self.update_latest_ledger_info(epoch_ending_ledger_info.clone());
+        self.epoch_ending_ledger_infos
+            .insert(ledger_info.epoch(), epoch_ending_ledger_info);
+
+        Ok(())
+    }
+
+    /// Verifies the given ledger info using the latest epoch state and
+    /// updates the latest verified ledger info.
+    fn verify_latest_ledger_info(
+        &mut self,
+        ledger_info_with_sigs: LedgerInfoWithSignatures,
+        waypoint: &Waypoint,
+    ) -> Result<(), Error> {
+        let latest_epoch_state = self.latest_epoch_state.as_ref().ok_or_else(|| {
+            Error::UnsatisfiableWaypoint("The waypoint has not yet been verified!".into())
+        })?;
+
+        // If the ledger info is from an older epoch, the latest epoch ending
+        // ledger info is already at least as recent.
+        let ledger_info_epoch = ledger_info_with_sigs.ledger_info().epoch();
+        if ledger_info_epoch < latest_epoch_state.epoch {
+            return Ok(());
+        } else if ledger_info_epoch > latest_epoch_state.epoch {
+            return Err(Error::AdvertisedDataError(format!(
+                "The ledger info is from a future epoch! Epoch: {:?}, latest verified epoch: {:?}",
+                ledger_info_epoch, latest_epoch_state.epoch
+            )));
+        }
+
+        // If the ledger info ends the epoch, verify it as an epoch ending ledger info
+        if ledger_info_with_sigs.ledger_info().ends_epoch() {
+            return self.verify_epoch_ending_ledger_info(ledger_info_with_sigs, waypoint);
+        }
+
+        // Otherwise, verify the signatures and update the latest ledger info
+        latest_epoch_state
+            .verify(&ledger_info_with_sigs)
+            .map_err(|error| {
+                Error::VerificationError(format!("Ledger info failed verification: {:?}", error))
+            })?;
+        self.update_latest_ledger_info(ledger_info_with_sigs);
+
+        Ok(())
+    }
+
+    /// Updates the latest verified ledger info (if the given ledger info is newer)
+    fn update_latest_ledger_info(&mut self, ledger_info_with_sigs: LedgerInfoWithSignatures) {
+        let new_version = ledger_info_with_sigs.ledger_info().version();
+        let latest_version = self
+            .latest_ledger_info
+            .as_ref()
+            .map(|ledger_info| ledger_info.ledger_info().version());
+        if latest_version.map_or(true, |latest_version| new_version > latest_version) {
+            self.latest_ledger_info = Some(ledger_info_with_sigs);
+        }
+    }
+}
+
+/// Notifies the data client of a bad response
+fn notify_bad_response(response_context: &ResponseContext, response_error: ResponseError) {
+    response_context
+        .response_callback
+        .notify_bad_response(response_error);
+}
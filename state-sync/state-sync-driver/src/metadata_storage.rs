@@ -6,13 +6,17 @@ use crate::{
     metadata_storage::database_schema::{MetadataKey, MetadataSchema, MetadataValue},
 };
 use anyhow::{anyhow, Result};
+use aptos_crypto::HashValue;
 use aptos_logger::prelude::*;
 use aptos_schemadb::{
     define_schema,
     schema::{KeyCodec, ValueCodec},
     ColumnFamilyName, Options, SchemaBatch, DB,
 };
-use aptos_types::ledger_info::LedgerInfoWithSignatures;
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures,
+    transaction::{TransactionOutputListWithProof, Version},
+};
 use serde::{Deserialize, Serialize};
 use std::{path::Path, sync::Arc, time::Instant};
 
@@ -47,6 +51,39 @@ pub trait MetadataStorageInterface {
         last_persisted_state_value_index: u64,
         snapshot_sync_completed: bool,
     ) -> Result<(), Error>;
+
+    /// Returns the snapshot manifest for the specified target. If no manifest
+    /// is found (or the manifest is for a different target), None is returned.
+    fn get_snapshot_manifest(
+        &self,
+        target_ledger_info: &LedgerInfoWithSignatures,
+    ) -> Result<Option<StateSnapshotManifest>, Error>;
+
+    /// Returns the state ranges that have been committed for the snapshot sync at
+    /// the specified target (merged and ordered by index). If no ranges have been
+    /// recorded for the target, the returned ranges are empty.
+    fn get_completed_state_ranges(
+        &self,
+        target_ledger_info: &LedgerInfoWithSignatures,
+    ) -> Result<CompletedStateRanges, Error>;
+
+    /// Initializes the snapshot manifest for the specified target (using the verified
+    /// target transaction output). If a manifest already exists for the target, the
+    /// previously completed state ranges are preserved. Otherwise, any completed
+    /// state ranges recorded for other targets are removed.
+    fn initialize_snapshot_manifest(
+        &self,
+        target_ledger_info: &LedgerInfoWithSignatures,
+        target_output_with_proof: &TransactionOutputListWithProof,
+    ) -> Result<(), Error>;
+
+    /// Records the given state range as completed for the specified target. Each
+    /// range is stored under its own key (i.e., the manifest isn't rewritten).
+    fn record_completed_state_range(
+        &self,
+        target_ledger_info: &LedgerInfoWithSignatures,
+        completed_state_range: CompletedStateRange,
+    ) -> Result<(), Error>;
 }
 
 /// The name of the state sync db file
@@ -93,23 +130,47 @@ impl PersistentMetadataStorage {
         Self { database }
     }
 
+    /// Returns the metadata value for the given key. Returns None if no value is found.
+    fn get_metadata_value(
+        &self,
+        metadata_key: &MetadataKey,
+    ) -> Result<Option<MetadataValue>, Error> {
+        self.database
+            .get::<MetadataSchema>(metadata_key)
+            .map_err(|error| {
+                Error::StorageError(format!(
+                    "Failed to read metadata value for key: {:?}. Error: {:?}",
+                    metadata_key, error
+                ))
+            })
+    }
+
     /// Returns the existing snapshot sync progress. Returns None if no progress is found.
     fn get_snapshot_progress(&self) -> Result<Option<StateSnapshotProgress>, Error> {
         let metadata_key = MetadataKey::StateSnapshotSync;
-        let maybe_metadata_value =
-            self.database
-                .get::<MetadataSchema>(&metadata_key)
-                .map_err(|error| {
-                    Error::StorageError(format!(
-                        "Failed to read metadata value for key: {:?}. Error: {:?}",
-                        metadata_key, error
-                    ))
-                })?;
-        match maybe_metadata_value {
-            Some(metadata_value) => {
-                let MetadataValue::StateSnapshotSync(snapshot_progress) = metadata_value;
+        match self.get_metadata_value(&metadata_key)? {
+            Some(MetadataValue::StateSnapshotSync(snapshot_progress)) => {
                 Ok(Some(snapshot_progress))
             },
+            Some(metadata_value) => Err(Error::StorageError(format!(
+                "Found an unexpected metadata value for key: {:?}. Value: {:?}",
+                metadata_key, metadata_value
+            ))),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the existing snapshot manifest. Returns None if no manifest is found.
+    fn get_existing_snapshot_manifest(&self) -> Result<Option<StateSnapshotManifest>, Error> {
+        let metadata_key = MetadataKey::StateSnapshotManifest;
+        match self.get_metadata_value(&metadata_key)? {
+            Some(MetadataValue::StateSnapshotManifest(snapshot_manifest)) => {
+                Ok(Some(snapshot_manifest))
+            },
+            Some(metadata_value) => Err(Error::StorageError(format!(
+                "Found an unexpected metadata value for key: {:?}. Value: {:?}",
+                metadata_key, metadata_value
+            ))),
             None => Ok(None),
         }
     }
@@ -137,21 +198,90 @@ impl PersistentMetadataStorage {
         }
     }
 
+    /// Returns the keys of all completed state ranges that were recorded for
+    /// targets other than the specified target version (i.e., stale ranges).
+    fn get_stale_completed_state_range_keys(
+        &self,
+        target_version: Version,
+    ) -> Result<Vec<MetadataKey>, Error> {
+        let mut stale_keys = vec![];
+        for result in self.iter_metadata()? {
+            let (metadata_key, _) = result?;
+            if let MetadataKey::StateSnapshotCompletedRange(version, _) = metadata_key {
+                if version != target_version {
+                    stale_keys.push(metadata_key);
+                }
+            }
+        }
+        Ok(stale_keys)
+    }
+
+    /// Returns an iterator over all metadata key value pairs in the database
+    fn iter_metadata(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<(MetadataKey, MetadataValue), Error>> + '_, Error> {
+        let mut iterator = self.database.iter::<MetadataSchema>().map_err(|error| {
+            Error::StorageError(format!(
+                "Failed to create a metadata iterator. Error: {:?}",
+                error
+            ))
+        })?;
+        iterator.seek_to_first();
+        Ok(iterator.map(|result| {
+            result.map_err(|error| {
+                Error::StorageError(format!(
+                    "Failed to read a metadata key value pair. Error: {:?}",
+                    error
+                ))
+            })
+        }))
+    }
+
     /// Write the key value pair to the database
     fn commit_key_value(
         &self,
         metadata_key: MetadataKey,
         metadata_value: MetadataValue,
+    ) -> Result<(), Error> {
+        self.commit_key_values(vec![(metadata_key, metadata_value)])
+    }
+
+    /// Write all key value pairs to the database (atomically)
+    fn commit_key_values(
+        &self,
+        key_values: Vec<(MetadataKey, MetadataValue)>,
+    ) -> Result<(), Error> {
+        self.commit_key_values_and_deletions(key_values, vec![])
+    }
+
+    /// Write all key value pairs to the database and delete
+    /// all the specified keys (atomically).
+    fn commit_key_values_and_deletions(
+        &self,
+        key_values: Vec<(MetadataKey, MetadataValue)>,
+        keys_to_delete: Vec<MetadataKey>,
     ) -> Result<(), Error> {
         // Create the schema batch
         let batch = SchemaBatch::new();
-        batch
-            .put::<MetadataSchema>(&metadata_key, &metadata_value)
-            .map_err(|error| {
-                Error::StorageError(format!(
-                    "Failed to batch put the metadata key and value. Key: {:?}, Value: {:?}. Error: {:?}", metadata_key, metadata_value, error
-                ))
-            })?;
+        for metadata_key in keys_to_delete {
+            batch
+                .delete::<MetadataSchema>(&metadata_key)
+                .map_err(|error| {
+                    Error::StorageError(format!(
+                        "Failed to batch delete the metadata key: {:?}. Error: {:?}",
+                        metadata_key, error
+                    ))
+                })?;
+        }
+        for (metadata_key, metadata_value) in key_values {
+            batch
+                .put::<MetadataSchema>(&metadata_key, &metadata_value)
+                .map_err(|error| {
+                    Error::StorageError(format!(
+                        "Failed to batch put the metadata key and value. Key: {:?}, Value: {:?}. Error: {:?}", metadata_key, metadata_value, error
+                    ))
+                })?;
+        }
 
         // Write the schema batch to the database
         self.database.write_schemas(batch).map_err(|error| {
@@ -224,6 +354,92 @@ impl MetadataStorageInterface for PersistentMetadataStorage {
         // Insert the new key/value pair
         self.commit_key_value(metadata_key, metadata_value)
     }
+
+    fn get_snapshot_manifest(
+        &self,
+        target_ledger_info: &LedgerInfoWithSignatures,
+    ) -> Result<Option<StateSnapshotManifest>, Error> {
+        Ok(self
+            .get_existing_snapshot_manifest()?
+            .filter(|snapshot_manifest| {
+                &snapshot_manifest.target_ledger_info == target_ledger_info
+            }))
+    }
+
+    fn get_completed_state_ranges(
+        &self,
+        target_ledger_info: &LedgerInfoWithSignatures,
+    ) -> Result<CompletedStateRanges, Error> {
+        let target_version = target_ledger_info.ledger_info().version();
+        let mut completed_state_ranges = CompletedStateRanges::default();
+        for result in self.iter_metadata()? {
+            if let (
+                MetadataKey::StateSnapshotCompletedRange(version, _),
+                MetadataValue::StateSnapshotCompletedRange(completed_state_range),
+            ) = result?
+            {
+                if version == target_version {
+                    completed_state_ranges.insert(completed_state_range);
+                }
+            }
+        }
+        Ok(completed_state_ranges)
+    }
+
+    fn initialize_snapshot_manifest(
+        &self,
+        target_ledger_info: &LedgerInfoWithSignatures,
+        target_output_with_proof: &TransactionOutputListWithProof,
+    ) -> Result<(), Error> {
+        // If a manifest already exists for the target, only record
+        // the target output (if it's missing). Otherwise, create a
+        // new manifest.
+        let target_version = target_ledger_info.ledger_info().version();
+        let mut stale_range_keys = vec![];
+        let snapshot_manifest = match self.get_existing_snapshot_manifest()? {
+            Some(mut snapshot_manifest)
+                if &snapshot_manifest.target_ledger_info == target_ledger_info =>
+            {
+                if snapshot_manifest.target_output_with_proof.is_some() {
+                    return Ok(()); // There's nothing to do
+                }
+                snapshot_manifest.target_output_with_proof = Some(target_output_with_proof.clone());
+                snapshot_manifest
+            },
+            _ => {
+                stale_range_keys = self.get_stale_completed_state_range_keys(target_version)?;
+                StateSnapshotManifest::new(
+                    target_ledger_info.clone(),
+                    Some(target_output_with_proof.clone()),
+                )
+            },
+        };
+
+        // Insert the new manifest and remove any stale ranges
+        self.commit_key_values_and_deletions(
+            vec![(
+                MetadataKey::StateSnapshotManifest,
+                MetadataValue::StateSnapshotManifest(snapshot_manifest),
+            )],
+            stale_range_keys,
+        )
+    }
+
+    fn record_completed_state_range(
+        &self,
+        target_ledger_info: &LedgerInfoWithSignatures,
+        completed_state_range: CompletedStateRange,
+    ) -> Result<(), Error> {
+        // Insert the range under its own key (ranges are merged when read)
+        let target_version = target_ledger_info.ledger_info().version();
+        self.commit_key_value(
+            MetadataKey::StateSnapshotCompletedRange(
+                target_version,
+                completed_state_range.first_index,
+            ),
+            MetadataValue::StateSnapshotCompletedRange(completed_state_range),
+        )
+    }
 }
 
 /// A simple struct for recording the progress of a state snapshot sync
@@ -234,6 +450,105 @@ pub struct StateSnapshotProgress {
     pub snapshot_sync_completed: bool,
 }
 
+/// A manifest for a state snapshot sync. The manifest records the target ledger
+/// info and the (verified) transaction output used to verify the state root hash.
+/// The manifest is only written when the target changes. The state ranges that
+/// have been committed to storage are recorded separately (see `CompletedStateRanges`).
+/// Together, these allow a snapshot sync to resume where it stopped (e.g., after a
+/// reboot).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateSnapshotManifest {
+    pub target_ledger_info: LedgerInfoWithSignatures,
+    pub target_output_with_proof: Option<TransactionOutputListWithProof>,
+}
+
+impl StateSnapshotManifest {
+    pub fn new(
+        target_ledger_info: LedgerInfoWithSignatures,
+        target_output_with_proof: Option<TransactionOutputListWithProof>,
+    ) -> Self {
+        Self {
+            target_ledger_info,
+            target_output_with_proof,
+        }
+    }
+}
+
+/// The state ranges that have been committed for a state snapshot sync
+/// (merged and ordered by first index).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CompletedStateRanges {
+    ranges: Vec<CompletedStateRange>,
+}
+
+impl CompletedStateRanges {
+    /// Returns the completed state ranges
+    pub fn ranges(&self) -> &[CompletedStateRange] {
+        &self.ranges
+    }
+
+    /// Returns the last state index of the completed range starting at
+    /// index 0. If no such range exists, None is returned.
+    pub fn get_last_contiguous_state_index(&self) -> Option<u64> {
+        self.ranges
+            .first()
+            .filter(|completed_state_range| completed_state_range.first_index == 0)
+            .map(|completed_state_range| completed_state_range.last_index)
+    }
+
+    /// Inserts the completed state range. Adjacent and overlapping
+    /// ranges are merged, and the ranges are kept in order.
+    pub fn insert(&mut self, completed_state_range: CompletedStateRange) {
+        // Insert the new range (ordered by first index)
+        let insertion_index = self
+            .ranges
+            .partition_point(|range| range.first_index <= completed_state_range.first_index);
+        self.ranges.insert(insertion_index, completed_state_range);
+
+        // Merge any adjacent or overlapping ranges
+        let mut merged_ranges: Vec<CompletedStateRange> = vec![];
+        for range in self.ranges.drain(..) {
+            match merged_ranges.last_mut() {
+                Some(last_range)
+                    if range.first_index <= last_range.last_index.saturating_add(1) =>
+                {
+                    if range.last_index > last_range.last_index {
+                        last_range.last_index = range.last_index;
+                        last_range.last_key = range.last_key;
+                    }
+                },
+                _ => merged_ranges.push(range),
+            }
+        }
+        self.ranges = merged_ranges;
+    }
+}
+
+/// A range of state values (by index and hashed key) that has been committed
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CompletedStateRange {
+    pub first_index: u64,
+    pub last_index: u64,
+    pub first_key: HashValue,
+    pub last_key: HashValue,
+}
+
+impl CompletedStateRange {
+    pub fn new(
+        first_index: u64,
+        last_index: u64,
+        first_key: HashValue,
+        last_key: HashValue,
+    ) -> Self {
+        Self {
+            first_index,
+            last_index,
+            first_key,
+            last_key,
+        }
+    }
+}
+
 /// The raw schema format used by the database
 pub mod database_schema {
     use super::*;
@@ -251,7 +566,9 @@ pub mod database_schema {
     #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
     #[repr(u8)]
     pub enum MetadataKey {
-        StateSnapshotSync, // A state snapshot sync that was started
+        StateSnapshotSync,                         // A state snapshot sync that was started
+        StateSnapshotManifest,                     // The manifest of the state snapshot sync
+        StateSnapshotCompletedRange(Version, u64), // A completed state range (by target version and first index)
     }
 
     /// A metadata value that can be inserted into the database
@@ -259,6 +576,8 @@ pub mod database_schema {
     #[repr(u8)]
     pub enum MetadataValue {
         StateSnapshotSync(StateSnapshotProgress), // A state snapshot sync progress marker
+        StateSnapshotManifest(StateSnapshotManifest), // A state snapshot sync manifest
+        StateSnapshotCompletedRange(CompletedStateRange), // A completed state range
     }
 
     impl KeyCodec<MetadataSchema> for MetadataKey {
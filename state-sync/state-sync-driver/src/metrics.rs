@@ -36,6 +36,7 @@ pub enum ExecutingComponent {
     Bootstrapper,
     Consensus,
     ContinuousSyncer,
+    LightClient,
 }
 
 impl ExecutingComponent {
@@ -44,6 +45,7 @@ impl ExecutingComponent {
             ExecutingComponent::Bootstrapper => "bootstrapper",
             ExecutingComponent::Consensus => "consensus",
             ExecutingComponent::ContinuousSyncer => "continuous_syncer",
+            ExecutingComponent::LightClient => "light_client",
         }
     }
 }
@@ -78,6 +80,16 @@ const CHUNK_SIZE_BUCKETS: &[f64] = &[
     40_000.0, 45_000.0, 50_000.0, 75_000.0, 100_000.0,
 ];
 
+/// Counter for state sync light client errors
+pub static LIGHT_CLIENT_ERRORS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_state_sync_light_client_errors",
+        "Counters related to state sync light client errors",
+        &["error_label"]
+    )
+    .unwrap()
+});
+
 /// Counter for state sync bootstrapper errors
 pub static BOOTSTRAPPER_ERRORS: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
@@ -5,7 +5,7 @@
 use crate::{
     error::Error,
     logging::{LogEntry, LogSchema},
-    metadata_storage::MetadataStorageInterface,
+    metadata_storage::{CompletedStateRange, MetadataStorageInterface},
     metrics,
     notification_handlers::{
         CommitNotification, CommittedTransactions, ErrorNotification, MempoolNotificationHandler,
@@ -820,6 +820,19 @@ fn spawn_state_snapshot_receiver<
             .ensure_state_checkpoint_hash()
             .expect("Must be at state checkpoint.");
 
+        // Initialize the snapshot manifest (so that the verified target
+        // transaction output doesn't need to be refetched on a reboot).
+        if let Err(error) = metadata_storage
+            .initialize_snapshot_manifest(&target_ledger_info, &target_output_with_proof)
+        {
+            warn!(
+                LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+                    "Failed to initialize the snapshot manifest at version: {:?}! Error: {:?}",
+                    version, error
+                ))
+            );
+        }
+
         // Create the snapshot receiver
         let mut state_snapshot_receiver = storage
             .writer
@@ -841,6 +854,12 @@ fn spawn_state_snapshot_receiver<
                     let all_states_synced = states_with_proof.is_last_chunk();
                     let last_committed_state_index = states_with_proof.last_index;
                     let num_state_values = states_with_proof.raw_values.len();
+                    let completed_state_range = CompletedStateRange::new(
+                        states_with_proof.first_index,
+                        states_with_proof.last_index,
+                        states_with_proof.first_key,
+                        states_with_proof.last_key,
+                    );
 
                     let result = state_snapshot_receiver.add_chunk(
                         states_with_proof.raw_values,
@@ -873,6 +892,18 @@ fn spawn_state_snapshot_receiver<
                                 num_state_values as u64,
                             );
 
+                            // Record the completed state range (for resuming the snapshot sync)
+                            if let Err(error) = metadata_storage.record_completed_state_range(
+                                &target_ledger_info,
+                                completed_state_range,
+                            ) {
+                                warn!(LogSchema::new(LogEntry::StorageSynchronizer)
+                                    .message(&format!(
+                                    "Failed to record the completed state range: {:?}! Error: {:?}",
+                                    completed_state_range, error
+                                )));
+                            }
+
                             if !all_states_synced {
                                 // Update the metadata storage with the last committed state index
                                 if let Err(error) = metadata_storage
@@ -0,0 +1,153 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{account_state_syncer::verify_account_states_page, error::Error};
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_types::{
+    account_address::AccountAddress,
+    block_info::BlockInfo,
+    ledger_info::LedgerInfo,
+    proof::{
+        SparseMerkleLeafNode, SparseMerkleProof, TransactionAccumulatorRangeProof,
+        TransactionInfoListWithProof,
+    },
+    state_store::{
+        state_key::StateKey,
+        state_value::{StateValue, StateValuesByPrefixWithProof},
+    },
+    transaction::{ExecutionStatus, TransactionInfo},
+};
+use claims::{assert_matches, assert_ok};
+use move_core_types::identifier::Identifier;
+
+#[test]
+fn test_verify_account_states_page() {
+    // Create two tracked accounts
+    let tracked_accounts = vec![AccountAddress::random(), AccountAddress::random()];
+
+    // Verify that a page for the first account passes verification
+    let state_key = create_module_key(&tracked_accounts[0], "a");
+    let (page, ledger_info) = create_account_states_page(vec![state_key.clone()], None);
+    assert_ok!(verify_account_states_page(
+        &tracked_accounts,
+        &ledger_info,
+        0,
+        None,
+        &page
+    ));
+    assert_ok!(verify_account_states_page(
+        &tracked_accounts,
+        &ledger_info,
+        0,
+        Some(&state_key),
+        &page
+    ));
+
+    // Verify that an empty page for the second account passes verification
+    let (page, ledger_info) = create_account_states_page(vec![], None);
+    assert_ok!(verify_account_states_page(
+        &tracked_accounts,
+        &ledger_info,
+        1,
+        None,
+        &page
+    ));
+}
+
+#[test]
+fn test_verify_account_states_page_omitted_values() {
+    // Create two tracked accounts
+    let tracked_accounts = vec![AccountAddress::random(), AccountAddress::random()];
+
+    // Verify that skipping the first account (i.e., omitting all of its
+    // state values) fails verification.
+    let state_key = create_module_key(&tracked_accounts[1], "a");
+    let (page, ledger_info) = create_account_states_page(vec![state_key], None);
+    let result = verify_account_states_page(&tracked_accounts, &ledger_info, 0, None, &page);
+    assert_matches!(result, Err(Error::VerificationError(_)));
+
+    // Verify that omitting the values between two pages fails verification
+    let mut state_keys: Vec<_> = ["a", "b"]
+        .iter()
+        .map(|name| create_module_key(&tracked_accounts[0], name))
+        .collect();
+    state_keys.sort_by(|key_1, key_2| key_1.encoded().cmp(key_2.encoded()));
+    let (page, ledger_info) = create_account_states_page(vec![state_keys[1].clone()], None);
+    let result = verify_account_states_page(
+        &tracked_accounts,
+        &ledger_info,
+        0,
+        Some(&state_keys[0]),
+        &page,
+    );
+    assert_matches!(result, Err(Error::VerificationError(_)));
+
+    // Verify that a page after all tracked accounts fails verification
+    let (page, ledger_info) = create_account_states_page(vec![], None);
+    let result = verify_account_states_page(&tracked_accounts, &ledger_info, 2, None, &page);
+    assert_matches!(result, Err(Error::VerificationError(_)));
+}
+
+/// Creates a page of account states (at version 0) for the given keys, along
+/// with a ledger info that commits to the page. Note: only the first state
+/// value has a valid inclusion proof (the state tree contains a single leaf).
+fn create_account_states_page(
+    state_keys: Vec<StateKey>,
+    next_key: Option<StateKey>,
+) -> (StateValuesByPrefixWithProof, LedgerInfo) {
+    // Create the state values and the state root hash
+    let raw_values: Vec<_> = state_keys
+        .into_iter()
+        .map(|state_key| {
+            let state_value = StateValue::from(HashValue::random().to_vec());
+            let leaf = SparseMerkleLeafNode::new(state_key.hash(), state_value.hash());
+            (
+                state_key,
+                state_value,
+                SparseMerkleProof::new(Some(leaf), vec![]),
+            )
+        })
+        .collect();
+    let state_root_hash = raw_values
+        .first()
+        .map(|(_, _, proof)| proof.leaf().unwrap().hash())
+        .unwrap_or_else(HashValue::random);
+
+    // Create the transaction info and a ledger info that commits to it
+    let transaction_info = TransactionInfo::new(
+        HashValue::random(),
+        HashValue::random(),
+        HashValue::random(),
+        Some(state_root_hash),
+        0,
+        ExecutionStatus::Success,
+    );
+    let block_info = BlockInfo::new(
+        0,
+        0,
+        HashValue::random(),
+        transaction_info.hash(),
+        0,
+        0,
+        None,
+    );
+    let ledger_info = LedgerInfo::new(block_info, HashValue::zero());
+
+    // Create the page of account states
+    let page = StateValuesByPrefixWithProof {
+        version: 0,
+        raw_values,
+        next_key,
+        transaction_info_with_proof: TransactionInfoListWithProof::new(
+            TransactionAccumulatorRangeProof::new_empty(),
+            vec![transaction_info],
+        ),
+    };
+
+    (page, ledger_info)
+}
+
+/// Creates a module state key under the given account
+fn create_module_key(account: &AccountAddress, name: &str) -> StateKey {
+    StateKey::module(account, &Identifier::new(name).unwrap())
+}
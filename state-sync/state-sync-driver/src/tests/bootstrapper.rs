@@ -5,6 +5,7 @@ use crate::{
     bootstrapper::{Bootstrapper, GENESIS_TRANSACTION_VERSION},
     driver::DriverConfiguration,
     error::Error,
+    metadata_storage::{CompletedStateRange, CompletedStateRanges, StateSnapshotManifest},
     tests::{
         mocks::{
             create_mock_db_reader, create_mock_streaming_client, create_ready_storage_synchronizer,
@@ -21,11 +22,13 @@ use crate::{
     utils::OutputFallbackHandler,
 };
 use aptos_config::config::BootstrappingMode;
+use aptos_crypto::HashValue;
 use aptos_data_client::global_summary::GlobalDataSummary;
 use aptos_data_streaming_service::{
     data_notification::{DataNotification, DataPayload, NotificationId},
     streaming_client::{NotificationAndFeedback, NotificationFeedback},
 };
+use aptos_storage_service_types::responses::CompleteDataRange;
 use aptos_time_service::TimeService;
 use aptos_types::{
     transaction::{TransactionOutputListWithProof, Version},
@@ -989,6 +992,9 @@ async fn test_snapshot_sync_epoch_change() {
     metadata_storage
         .expect_get_last_persisted_state_value_index()
         .returning(move |_| Ok(last_persisted_index_clone));
+    metadata_storage
+        .expect_get_completed_state_ranges()
+        .returning(|_| Ok(CompletedStateRanges::default()));
 
     // Create the bootstrapper
     let mut bootstrapper = create_bootstrapper_with_storage(
@@ -1111,6 +1117,9 @@ async fn test_snapshot_sync_epoch_change_genesis_restart() {
     metadata_storage
         .expect_get_last_persisted_state_value_index()
         .returning(move |_| Ok(last_persisted_index_clone));
+    metadata_storage
+        .expect_get_completed_state_ranges()
+        .returning(|_| Ok(CompletedStateRanges::default()));
 
     // Create the bootstrapper
     let mut bootstrapper = create_bootstrapper_with_storage(
@@ -1142,6 +1151,201 @@ async fn test_snapshot_sync_epoch_change_genesis_restart() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn test_snapshot_sync_pruned_target() {
+    // Create test data
+    let synced_version = GENESIS_TRANSACTION_VERSION; // Genesis is the highest synced
+    let target_version = 1000;
+    let highest_version = 5000;
+    let target_ledger_info = create_random_epoch_ending_ledger_info(target_version, 1);
+    let highest_ledger_info = create_random_epoch_ending_ledger_info(highest_version, 2);
+
+    // Create a driver configuration with a genesis waypoint and state syncing
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.bootstrapping_mode = BootstrappingMode::DownloadLatestStates;
+
+    // Create the mock metadata storage (no writes are expected, so the partially
+    // restored states are never reused to sync the newer target).
+    let mut metadata_storage = MockMetadataStorage::new();
+    let target_ledger_info_clone = target_ledger_info.clone();
+    metadata_storage
+        .expect_previous_snapshot_sync_target()
+        .returning(move || Ok(Some(target_ledger_info_clone.clone())));
+    metadata_storage
+        .expect_is_snapshot_sync_complete()
+        .returning(|_| Ok(false));
+
+    // Create the bootstrapper (no data streams are expected)
+    let mut bootstrapper = create_bootstrapper_with_storage(
+        driver_configuration,
+        create_mock_streaming_client(),
+        metadata_storage,
+        None,
+        synced_version,
+        true,
+    );
+
+    // Insert an epoch ending ledger info into the verified states of the bootstrapper
+    manipulate_verified_epoch_states(&mut bootstrapper, true, true, Some(highest_version));
+
+    // Create a global data summary where the states at the target have been pruned
+    let mut global_data_summary = create_global_summary(1);
+    global_data_summary.advertised_data.synced_ledger_infos = vec![highest_ledger_info.clone()];
+    global_data_summary.advertised_data.states =
+        vec![CompleteDataRange::new(highest_version - 100, highest_version).unwrap()];
+
+    // Drive progress and verify the bootstrapper refuses to sync to the newer target
+    for _ in 0..2 {
+        let error = drive_progress(&mut bootstrapper, &global_data_summary, false)
+            .await
+            .unwrap_err();
+        assert_matches!(error, Error::AdvertisedDataError(_));
+    }
+}
+
+#[tokio::test]
+async fn test_snapshot_sync_invalid_manifest_output() {
+    // Create test data
+    let synced_version = GENESIS_TRANSACTION_VERSION; // Genesis is the highest synced
+    let target_version = 1000;
+    let highest_version = 5000;
+    let target_ledger_info = create_random_epoch_ending_ledger_info(target_version, 1);
+    let highest_ledger_info = create_random_epoch_ending_ledger_info(highest_version, 2);
+
+    // Create a driver configuration with a genesis waypoint and state syncing
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.bootstrapping_mode = BootstrappingMode::DownloadLatestStates;
+
+    // Create the mock streaming client (the target output should be refetched)
+    let mut mock_streaming_client = create_mock_streaming_client();
+    let (_notification_sender_1, data_stream_listener_1) = create_data_stream_listener();
+    mock_streaming_client
+        .expect_get_all_transaction_outputs()
+        .times(1)
+        .with(eq(target_version), eq(target_version), eq(target_version))
+        .return_once(move |_, _, _| Ok(data_stream_listener_1));
+
+    // Create the mock metadata storage (with a manifest containing an invalid target output)
+    let mut metadata_storage = MockMetadataStorage::new();
+    let target_ledger_info_clone = target_ledger_info.clone();
+    metadata_storage
+        .expect_previous_snapshot_sync_target()
+        .returning(move || Ok(Some(target_ledger_info_clone.clone())));
+    metadata_storage
+        .expect_is_snapshot_sync_complete()
+        .returning(|_| Ok(false));
+    let snapshot_manifest = StateSnapshotManifest::new(
+        target_ledger_info.clone(),
+        Some(create_output_list_with_proof()),
+    );
+    metadata_storage
+        .expect_get_snapshot_manifest()
+        .with(eq(target_ledger_info.clone()))
+        .return_once(move |_| Ok(Some(snapshot_manifest)));
+
+    // Create the bootstrapper
+    let mut bootstrapper = create_bootstrapper_with_storage(
+        driver_configuration,
+        mock_streaming_client,
+        metadata_storage,
+        None,
+        synced_version,
+        true,
+    );
+
+    // Insert an epoch ending ledger info into the verified states of the bootstrapper
+    manipulate_verified_epoch_states(&mut bootstrapper, true, true, Some(highest_version));
+
+    // Create a global data summary (where the states at the target are still available)
+    let mut global_data_summary = create_global_summary(1);
+    global_data_summary.advertised_data.synced_ledger_infos = vec![highest_ledger_info.clone()];
+    global_data_summary.advertised_data.states =
+        vec![CompleteDataRange::new(0, highest_version).unwrap()];
+
+    // Drive progress to start the transaction output stream
+    drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_snapshot_sync_resume_completed_ranges() {
+    // Create test data
+    let synced_version = GENESIS_TRANSACTION_VERSION; // Genesis is the highest synced
+    let target_version = 1000;
+    let highest_version = 5000;
+    let last_persisted_index = 1000;
+    let last_completed_index = 1999;
+    let target_ledger_info = create_random_epoch_ending_ledger_info(target_version, 1);
+    let highest_ledger_info = create_random_epoch_ending_ledger_info(highest_version, 2);
+
+    // Create a driver configuration with a genesis waypoint and state syncing
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.bootstrapping_mode = BootstrappingMode::DownloadLatestStates;
+
+    // Create the mock streaming client (the stream should resume at the last completed index)
+    let mut mock_streaming_client = create_mock_streaming_client();
+    let (_notification_sender_1, data_stream_listener_1) = create_data_stream_listener();
+    mock_streaming_client
+        .expect_get_all_state_values()
+        .times(1)
+        .with(eq(target_version), eq(Some(last_completed_index)))
+        .return_once(move |_, _| Ok(data_stream_listener_1));
+
+    // Create the mock metadata storage (where the completed ranges are ahead of the progress)
+    let mut metadata_storage = MockMetadataStorage::new();
+    let target_ledger_info_clone = target_ledger_info.clone();
+    metadata_storage
+        .expect_previous_snapshot_sync_target()
+        .returning(move || Ok(Some(target_ledger_info_clone.clone())));
+    metadata_storage
+        .expect_is_snapshot_sync_complete()
+        .returning(|_| Ok(false));
+    metadata_storage
+        .expect_get_last_persisted_state_value_index()
+        .returning(move |_| Ok(last_persisted_index));
+    let mut completed_state_ranges = CompletedStateRanges::default();
+    for (first_index, last_index) in [(0, 999), (1000, last_completed_index), (3000, 3999)] {
+        completed_state_ranges.insert(CompletedStateRange::new(
+            first_index,
+            last_index,
+            HashValue::random(),
+            HashValue::random(),
+        ));
+    }
+    metadata_storage
+        .expect_get_completed_state_ranges()
+        .with(eq(target_ledger_info.clone()))
+        .return_once(move |_| Ok(completed_state_ranges));
+
+    // Create the bootstrapper
+    let mut bootstrapper = create_bootstrapper_with_storage(
+        driver_configuration,
+        mock_streaming_client,
+        metadata_storage,
+        None,
+        synced_version,
+        true,
+    );
+
+    // Insert an epoch ending ledger info into the verified states of the bootstrapper
+    manipulate_verified_epoch_states(&mut bootstrapper, true, true, Some(highest_version));
+
+    // Manually insert a transaction output to sync
+    bootstrapper
+        .get_state_value_syncer()
+        .set_transaction_output_to_sync(create_output_list_with_proof());
+
+    // Create a global data summary
+    let mut global_data_summary = create_global_summary(1);
+    global_data_summary.advertised_data.synced_ledger_infos = vec![highest_ledger_info.clone()];
+
+    // Drive progress to resume the state value stream
+    drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap();
+}
+
 #[tokio::test]
 async fn test_snapshot_sync_existing_state() {
     // Create test data
@@ -1199,6 +1403,9 @@ async fn test_snapshot_sync_existing_state() {
     metadata_storage
         .expect_get_last_persisted_state_value_index()
         .returning(move |_| Ok(last_persisted_index_clone));
+    metadata_storage
+        .expect_get_completed_state_ranges()
+        .returning(|_| Ok(CompletedStateRanges::default()));
 
     // Create the bootstrapper
     let mut bootstrapper = create_bootstrapper_with_storage(
@@ -0,0 +1,313 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    error::Error,
+    light_client::LightClient,
+    tests::mocks::{create_mock_data_client, MockAptosDataClient},
+};
+use aptos_config::config::AptosDataClientConfig;
+use aptos_crypto::HashValue;
+use aptos_data_client::{
+    global_summary::GlobalDataSummary,
+    interface::{Response, ResponseCallback, ResponseContext, ResponseError},
+};
+use aptos_storage_service_types::responses::CompleteDataRange;
+use aptos_types::{
+    aggregate_signature::PartialSignatures,
+    block_info::BlockInfo,
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    proof::{SparseMerkleProof, TransactionAccumulatorRangeProof, TransactionInfoListWithProof},
+    state_store::{
+        state_key::StateKey,
+        state_value::{StateValue, StateValueWithProof},
+    },
+    transaction::{ExecutionStatus, TransactionInfo, Version},
+    validator_signer::ValidatorSigner,
+    validator_verifier::{ValidatorConsensusInfo, ValidatorVerifier},
+    waypoint::Waypoint,
+};
+use claims::{assert_err, assert_matches, assert_none, assert_ok, assert_some};
+use mockall::predicate::{always, eq};
+use std::{collections::BTreeMap, time::Duration};
+use tokio::time::timeout;
+
+#[tokio::test]
+async fn test_sync_epochs_and_latest_ledger_info() {
+    // Create a chain of three epochs (the genesis epoch, epoch 1 and epoch 2)
+    let signers = create_validator_signers(3);
+    let epoch_ending_ledger_infos = vec![
+        create_ledger_info(&signers, 0, 0, true),
+        create_ledger_info(&signers, 1, 100, true),
+    ];
+    let latest_ledger_info = create_ledger_info(&signers, 2, 150, false);
+
+    // Create a light client that trusts the genesis waypoint
+    let data_client = create_data_client(
+        epoch_ending_ledger_infos.clone(),
+        latest_ledger_info.clone(),
+    );
+    let waypoint =
+        Waypoint::new_epoch_boundary(epoch_ending_ledger_infos[0].ledger_info()).unwrap();
+    let light_client = create_light_client(data_client, waypoint);
+    assert!(!light_client.verified_waypoint());
+
+    // Verify the light client is not yet synced
+    assert!(!light_client.is_synced());
+    assert_err!(timeout(Duration::from_millis(100), light_client.wait_until_synced()).await);
+
+    // Sync the light client and verify the latest ledger info
+    let synced_ledger_info = light_client.sync().await.unwrap();
+    assert_eq!(synced_ledger_info, latest_ledger_info);
+    assert_eq!(
+        light_client.latest_ledger_info(),
+        Some(latest_ledger_info.clone())
+    );
+
+    // Verify the light client is now synced
+    assert!(light_client.is_synced());
+    assert_ok!(timeout(Duration::from_secs(10), light_client.wait_until_synced()).await);
+
+    // Verify the epoch states and epoch ending ledger infos
+    assert!(light_client.verified_waypoint());
+    assert_eq!(light_client.latest_epoch_state().unwrap().epoch, 2);
+    for (epoch, epoch_ending_ledger_info) in epoch_ending_ledger_infos.iter().enumerate() {
+        assert_eq!(
+            light_client.get_epoch_ending_ledger_info(epoch as u64),
+            Some(epoch_ending_ledger_info.clone())
+        );
+    }
+    assert_none!(light_client.get_epoch_ending_ledger_info(2));
+}
+
+#[tokio::test]
+async fn test_sync_invalid_signatures() {
+    // Create a chain of epochs where epoch 1 is signed by an unknown validator
+    let signers = create_validator_signers(3);
+    let genesis_ledger_info = create_ledger_info(&signers, 0, 0, true);
+    let invalid_signer = ValidatorSigner::random([u8::MAX; 32]);
+    let epoch_ending_ledger_infos = vec![
+        genesis_ledger_info.clone(),
+        resign_ledger_info(&invalid_signer, create_ledger_info(&signers, 1, 100, true)),
+    ];
+    let latest_ledger_info = create_ledger_info(&signers, 2, 150, false);
+
+    // Create a light client that trusts the genesis waypoint
+    let data_client = create_data_client(epoch_ending_ledger_infos, latest_ledger_info);
+    let waypoint = Waypoint::new_epoch_boundary(genesis_ledger_info.ledger_info()).unwrap();
+    let light_client = create_light_client(data_client, waypoint);
+
+    // Verify that syncing fails
+    let error = light_client.sync().await.unwrap_err();
+    assert_matches!(error, Error::VerificationError(_));
+
+    // Verify that only the genesis epoch was verified
+    assert_some!(light_client.get_epoch_ending_ledger_info(0));
+    assert_none!(light_client.get_epoch_ending_ledger_info(1));
+    assert_eq!(light_client.latest_epoch_state().unwrap().epoch, 1);
+}
+
+#[tokio::test]
+async fn test_sync_unsatisfiable_waypoint() {
+    // Create a chain of epochs
+    let signers = create_validator_signers(3);
+    let epoch_ending_ledger_infos = vec![
+        create_ledger_info(&signers, 0, 0, true),
+        create_ledger_info(&signers, 1, 100, true),
+    ];
+    let latest_ledger_info = create_ledger_info(&signers, 2, 150, false);
+
+    // Create a light client with a waypoint that doesn't match the chain
+    let data_client = create_data_client(epoch_ending_ledger_infos, latest_ledger_info);
+    let other_ledger_info = create_ledger_info(&signers, 0, 0, true);
+    let waypoint = Waypoint::new_epoch_boundary(other_ledger_info.ledger_info()).unwrap();
+    let light_client = create_light_client(data_client, waypoint);
+
+    // Verify that syncing fails and that nothing was verified
+    let error = light_client.sync().await.unwrap_err();
+    assert_matches!(error, Error::UnsatisfiableWaypoint(_));
+    assert!(!light_client.verified_waypoint());
+    assert!(!light_client.is_synced());
+    assert_none!(light_client.latest_ledger_info());
+}
+
+#[tokio::test]
+async fn test_get_state_value_before_sync() {
+    // Create a light client that hasn't synced
+    let data_client = create_mock_data_client();
+    let light_client = create_light_client(data_client, Waypoint::default());
+
+    // Verify that state queries are rejected
+    let error = light_client
+        .get_state_value(StateKey::raw(&[0]))
+        .await
+        .unwrap_err();
+    assert_matches!(error, Error::BootstrapNotComplete(_));
+}
+
+#[tokio::test]
+async fn test_get_state_value_invalid_proof() {
+    // Create a chain of epochs
+    let signers = create_validator_signers(2);
+    let genesis_ledger_info = create_ledger_info(&signers, 0, 0, true);
+    let latest_ledger_info = create_ledger_info(&signers, 1, 50, false);
+
+    // Create a data client that returns an invalid state value proof
+    let mut data_client = create_data_client(
+        vec![genesis_ledger_info.clone()],
+        latest_ledger_info.clone(),
+    );
+    let state_key = StateKey::raw(&[1, 2, 3]);
+    let state_value_with_proof = create_state_value_with_proof(state_key.clone(), 50);
+    data_client
+        .expect_get_state_value_with_proof()
+        .with(
+            eq(50),
+            eq(state_key.clone()),
+            eq(AptosDataClientConfig::default().response_timeout_ms),
+        )
+        .return_once(move |_, _, _| Ok(create_response(state_value_with_proof)));
+
+    // Sync the light client
+    let waypoint = Waypoint::new_epoch_boundary(genesis_ledger_info.ledger_info()).unwrap();
+    let light_client = create_light_client(data_client, waypoint);
+    assert_ok!(light_client.sync().await);
+
+    // Verify that the state value fails verification
+    let error = light_client.get_state_value(state_key).await.unwrap_err();
+    assert_matches!(error, Error::VerificationError(_));
+}
+
+/// A simple response callback that ignores all feedback
+#[derive(Debug)]
+struct NoopResponseCallback;
+
+impl ResponseCallback for NoopResponseCallback {
+    fn notify_bad_response(&self, _error: ResponseError) {}
+}
+
+/// Creates a mock data client that advertises and serves the given
+/// epoch ending ledger infos and latest ledger info.
+fn create_data_client(
+    epoch_ending_ledger_infos: Vec<LedgerInfoWithSignatures>,
+    latest_ledger_info: LedgerInfoWithSignatures,
+) -> MockAptosDataClient {
+    // Create the global data summary
+    let highest_ended_epoch = (epoch_ending_ledger_infos.len() - 1) as u64;
+    let mut global_data_summary = GlobalDataSummary::empty();
+    global_data_summary
+        .advertised_data
+        .epoch_ending_ledger_infos = vec![CompleteDataRange::new(0, highest_ended_epoch).unwrap()];
+    global_data_summary.advertised_data.synced_ledger_infos = vec![latest_ledger_info];
+
+    // Create the mock data client
+    let mut data_client = create_mock_data_client();
+    data_client
+        .expect_get_global_data_summary()
+        .return_const(global_data_summary);
+    data_client
+        .expect_get_epoch_ending_ledger_infos()
+        .with(eq(0), eq(highest_ended_epoch), always())
+        .return_once(move |_, _, _| Ok(create_response(epoch_ending_ledger_infos)));
+
+    data_client
+}
+
+/// Creates a ledger info for the given epoch and version, signed by the
+/// validator for that epoch. If `ends_epoch` is true, the ledger info
+/// will contain the epoch state for the next epoch.
+fn create_ledger_info(
+    signers: &[ValidatorSigner],
+    epoch: u64,
+    version: Version,
+    ends_epoch: bool,
+) -> LedgerInfoWithSignatures {
+    let next_epoch_state = if ends_epoch {
+        Some(create_epoch_state(&signers[epoch as usize + 1], epoch + 1))
+    } else {
+        None
+    };
+    let block_info = BlockInfo::new(
+        epoch,
+        0,
+        HashValue::random(),
+        HashValue::random(),
+        version,
+        0,
+        next_epoch_state,
+    );
+    let ledger_info = LedgerInfo::new(block_info, HashValue::zero());
+    sign_ledger_info(&signers[epoch as usize], ledger_info)
+}
+
+/// Returns an epoch state with a single validator (the given signer)
+fn create_epoch_state(signer: &ValidatorSigner, epoch: u64) -> EpochState {
+    let validator_info = ValidatorConsensusInfo::new(signer.author(), signer.public_key(), 1);
+    EpochState::new(epoch, ValidatorVerifier::new(vec![validator_info]))
+}
+
+/// Creates a light client using the given data client and waypoint
+fn create_light_client(
+    data_client: MockAptosDataClient,
+    waypoint: Waypoint,
+) -> LightClient<MockAptosDataClient> {
+    LightClient::new(AptosDataClientConfig::default(), data_client, waypoint)
+}
+
+/// Creates a data client response with the given payload
+fn create_response<T>(payload: T) -> Response<T> {
+    let context = ResponseContext::new(0, 0, Box::new(NoopResponseCallback));
+    Response::new(context, payload)
+}
+
+/// Creates a state value with an (invalid) proof at the given version
+fn create_state_value_with_proof(state_key: StateKey, version: Version) -> StateValueWithProof {
+    let transaction_info = TransactionInfo::new(
+        HashValue::random(),
+        HashValue::random(),
+        HashValue::random(),
+        Some(HashValue::random()),
+        0,
+        ExecutionStatus::Success,
+    );
+    StateValueWithProof {
+        version,
+        state_key,
+        state_value: Some(StateValue::from(vec![1, 2, 3])),
+        sparse_merkle_proof: SparseMerkleProof::new(None, vec![]),
+        transaction_info_with_proof: TransactionInfoListWithProof::new(
+            TransactionAccumulatorRangeProof::new_empty(),
+            vec![transaction_info],
+        ),
+    }
+}
+
+/// Creates the given number of (deterministic) validator signers
+fn create_validator_signers(num_signers: u8) -> Vec<ValidatorSigner> {
+    (0..num_signers)
+        .map(|index| ValidatorSigner::random([index; 32]))
+        .collect()
+}
+
+/// Re-signs the given ledger info using the given signer
+fn resign_ledger_info(
+    signer: &ValidatorSigner,
+    ledger_info: LedgerInfoWithSignatures,
+) -> LedgerInfoWithSignatures {
+    sign_ledger_info(signer, ledger_info.ledger_info().clone())
+}
+
+/// Signs the given ledger info using the given signer
+fn sign_ledger_info(signer: &ValidatorSigner, ledger_info: LedgerInfo) -> LedgerInfoWithSignatures {
+    let signature = signer.sign(&ledger_info).unwrap();
+    let partial_signatures = PartialSignatures::new(BTreeMap::from([(signer.author(), signature)]));
+    let aggregate_signature = ValidatorVerifier::new(vec![ValidatorConsensusInfo::new(
+        signer.author(),
+        signer.public_key(),
+        1,
+    )])
+    .aggregate_signatures(&partial_signatures)
+    .unwrap();
+    LedgerInfoWithSignatures::new(ledger_info, aggregate_signature)
+}
@@ -4,13 +4,18 @@
 use crate::{
     metadata_storage::{
         database_schema::{MetadataKey, MetadataSchema, MetadataValue},
-        MetadataStorageInterface, PersistentMetadataStorage, StateSnapshotProgress,
+        CompletedStateRange, CompletedStateRanges, MetadataStorageInterface,
+        PersistentMetadataStorage, StateSnapshotManifest, StateSnapshotProgress,
+    },
+    tests::utils::{
+        create_epoch_ending_ledger_info, create_ledger_info_at_version,
+        create_output_list_with_proof,
     },
-    tests::utils::{create_epoch_ending_ledger_info, create_ledger_info_at_version},
 };
+use aptos_crypto::HashValue;
 use aptos_schemadb::schema::fuzzing::assert_encode_decode;
 use aptos_temppath::TempPath;
-use claims::{assert_err, assert_none};
+use claims::{assert_err, assert_none, assert_ok};
 
 #[test]
 fn test_create_then_open() {
@@ -101,6 +106,130 @@ fn test_metadata_schema_encode_decode() {
     );
 }
 
+#[test]
+fn test_metadata_schema_encode_decode_manifest() {
+    let snapshot_manifest = StateSnapshotManifest::new(
+        create_epoch_ending_ledger_info(),
+        Some(create_output_list_with_proof()),
+    );
+    assert_encode_decode::<MetadataSchema>(
+        &MetadataKey::StateSnapshotManifest,
+        &MetadataValue::StateSnapshotManifest(snapshot_manifest),
+    );
+    assert_encode_decode::<MetadataSchema>(
+        &MetadataKey::StateSnapshotCompletedRange(100, 0),
+        &MetadataValue::StateSnapshotCompletedRange(create_completed_state_range(0, 99)),
+    );
+}
+
+#[test]
+fn test_insert_completed_state_ranges() {
+    // Create an empty set of ranges
+    let mut completed_state_ranges = CompletedStateRanges::default();
+    assert_none!(completed_state_ranges.get_last_contiguous_state_index());
+
+    // Insert a range that doesn't start at index 0 and verify there's no contiguous index
+    completed_state_ranges.insert(create_completed_state_range(200, 299));
+    assert_none!(completed_state_ranges.get_last_contiguous_state_index());
+
+    // Insert adjacent ranges and verify they are merged
+    completed_state_ranges.insert(create_completed_state_range(0, 99));
+    completed_state_ranges.insert(create_completed_state_range(100, 149));
+    assert_eq!(completed_state_ranges.ranges().len(), 2);
+    assert_eq!(
+        completed_state_ranges.get_last_contiguous_state_index(),
+        Some(149)
+    );
+
+    // Insert an overlapping range (e.g., after a reboot) and verify all ranges are merged
+    completed_state_ranges.insert(create_completed_state_range(149, 199));
+    assert_eq!(completed_state_ranges.ranges(), &[
+        CompletedStateRange::new(0, 299, create_key_hash(0), create_key_hash(299))
+    ]);
+    assert_eq!(
+        completed_state_ranges.get_last_contiguous_state_index(),
+        Some(299)
+    );
+
+    // Insert a range that is already covered and verify nothing changes
+    completed_state_ranges.insert(create_completed_state_range(10, 20));
+    assert_eq!(
+        completed_state_ranges.get_last_contiguous_state_index(),
+        Some(299)
+    );
+}
+
+#[test]
+fn test_manifest_resume_after_reboot() {
+    // Create a new metadata storage
+    let tmp_dir = TempPath::new();
+    let metadata_storage = PersistentMetadataStorage::new(tmp_dir.path());
+
+    // Verify no manifest or completed ranges exist
+    let target_ledger_info = create_ledger_info_at_version(5000);
+    assert_none!(metadata_storage
+        .get_snapshot_manifest(&target_ledger_info)
+        .unwrap());
+    assert_eq!(
+        metadata_storage
+            .get_completed_state_ranges(&target_ledger_info)
+            .unwrap(),
+        CompletedStateRanges::default()
+    );
+
+    // Initialize the manifest and record several completed ranges
+    let target_output_with_proof = create_output_list_with_proof();
+    metadata_storage
+        .initialize_snapshot_manifest(&target_ledger_info, &target_output_with_proof)
+        .unwrap();
+    for index in 0..10 {
+        let completed_state_range = create_completed_state_range(index * 10, (index * 10) + 9);
+        metadata_storage
+            .record_completed_state_range(&target_ledger_info, completed_state_range)
+            .unwrap();
+    }
+
+    // Drop the handle to the storage (mimic a reboot)
+    drop(metadata_storage);
+
+    // Reopen the storage and re-initialize the manifest (as done on startup)
+    let metadata_storage = PersistentMetadataStorage::new(tmp_dir.path());
+    metadata_storage
+        .initialize_snapshot_manifest(&target_ledger_info, &create_output_list_with_proof())
+        .unwrap();
+
+    // Verify the manifest was preserved
+    let snapshot_manifest = metadata_storage
+        .get_snapshot_manifest(&target_ledger_info)
+        .unwrap()
+        .unwrap();
+    assert_eq!(snapshot_manifest.target_ledger_info, target_ledger_info);
+    assert_eq!(
+        snapshot_manifest.target_output_with_proof,
+        Some(target_output_with_proof)
+    );
+
+    // Verify the completed ranges were preserved (and merged)
+    let completed_state_ranges = metadata_storage
+        .get_completed_state_ranges(&target_ledger_info)
+        .unwrap();
+    assert_eq!(completed_state_ranges.ranges().len(), 1);
+    assert_eq!(
+        completed_state_ranges.get_last_contiguous_state_index(),
+        Some(99)
+    );
+
+    // Verify the manifest and ranges aren't returned for a different target
+    let other_target_ledger_info = create_ledger_info_at_version(6000);
+    assert_none!(metadata_storage
+        .get_snapshot_manifest(&other_target_ledger_info)
+        .unwrap());
+    assert_none!(metadata_storage
+        .get_completed_state_ranges(&other_target_ledger_info)
+        .unwrap()
+        .get_last_contiguous_state_index());
+}
+
 #[test]
 fn test_multiple_reads_and_writes() {
     // Create a new metadata storage
@@ -167,3 +296,18 @@ fn test_writes_to_different_targets() {
         .update_last_persisted_state_value_index(&target_ledger_info, 10101, false)
         .unwrap_err();
 }
+
+/// Creates a completed state range with the given indices
+fn create_completed_state_range(first_index: u64, last_index: u64) -> CompletedStateRange {
+    CompletedStateRange::new(
+        first_index,
+        last_index,
+        create_key_hash(first_index),
+        create_key_hash(last_index),
+    )
+}
+
+/// Creates a (deterministic) key hash for the given state index
+fn create_key_hash(index: u64) -> HashValue {
+    HashValue::sha3_256_of(&index.to_le_bytes())
+}
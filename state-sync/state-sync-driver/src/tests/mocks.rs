@@ -3,12 +3,18 @@
 
 use crate::{
     error::Error,
-    metadata_storage::MetadataStorageInterface,
+    metadata_storage::{
+        CompletedStateRange, CompletedStateRanges, MetadataStorageInterface, StateSnapshotManifest,
+    },
     storage_synchronizer::{NotificationMetadata, StorageSynchronizerInterface},
     tests::utils::{create_empty_epoch_state, create_epoch_ending_ledger_info},
 };
 use anyhow::Result as AnyhowResult;
 use aptos_crypto::HashValue;
+use aptos_data_client::{
+    global_summary::GlobalDataSummary,
+    interface::{AptosDataClientInterface, Response, SubscriptionRequestMetadata},
+};
 use aptos_data_streaming_service::{
     data_notification::NotificationId,
     data_stream::{DataStreamId, DataStreamListener},
@@ -19,6 +25,7 @@ use aptos_storage_interface::{
     cached_state_view::ShardedStateCache, state_delta::StateDelta, DbReader, DbReaderWriter,
     DbWriter, ExecutedTrees, Order, Result, StateSnapshotReceiver,
 };
+use aptos_storage_service_types::responses::TransactionOrOutputListWithProof;
 use aptos_types::{
     account_address::AccountAddress,
     contract_event::EventWithVersion,
@@ -33,7 +40,9 @@ use aptos_types::{
     state_proof::StateProof,
     state_store::{
         state_key::StateKey,
-        state_value::{StateValue, StateValueChunkWithProof},
+        state_value::{
+            StateValue, StateValueChunkWithProof, StateValueWithProof, StateValuesByPrefixWithProof,
+        },
         ShardedStateUpdates,
     },
     transaction::{
@@ -54,6 +63,11 @@ pub fn create_mock_executor() -> MockChunkExecutor {
     MockChunkExecutor::new()
 }
 
+/// Creates a mock data client
+pub fn create_mock_data_client() -> MockAptosDataClient {
+    MockAptosDataClient::new()
+}
+
 /// Creates a mock database reader
 pub fn create_mock_db_reader() -> MockDatabaseReader {
     MockDatabaseReader::new()
@@ -173,6 +187,120 @@ mock! {
     }
 }
 
+// This automatically creates a MockAptosDataClient.
+mock! {
+    pub AptosDataClient {}
+    #[async_trait]
+    impl AptosDataClientInterface for AptosDataClient {
+        fn get_global_data_summary(&self) -> GlobalDataSummary;
+
+        async fn get_epoch_ending_ledger_infos(
+            &self,
+            start_epoch: Epoch,
+            expected_end_epoch: Epoch,
+            request_timeout_ms: u64,
+        ) -> aptos_data_client::error::Result<Response<Vec<LedgerInfoWithSignatures>>>;
+
+        async fn get_new_transaction_outputs_with_proof(
+            &self,
+            known_version: Version,
+            known_epoch: Epoch,
+            request_timeout_ms: u64,
+        ) -> aptos_data_client::error::Result<Response<(TransactionOutputListWithProof, LedgerInfoWithSignatures)>>;
+
+        async fn get_new_transactions_with_proof(
+            &self,
+            known_version: Version,
+            known_epoch: Epoch,
+            include_events: bool,
+            request_timeout_ms: u64,
+        ) -> aptos_data_client::error::Result<Response<(TransactionListWithProof, LedgerInfoWithSignatures)>>;
+
+        async fn get_new_transactions_or_outputs_with_proof(
+            &self,
+            known_version: Version,
+            known_epoch: Epoch,
+            include_events: bool,
+            request_timeout_ms: u64,
+        ) -> aptos_data_client::error::Result<Response<(TransactionOrOutputListWithProof, LedgerInfoWithSignatures)>>;
+
+        async fn get_number_of_states(
+            &self,
+            version: Version,
+            request_timeout_ms: u64,
+        ) -> aptos_data_client::error::Result<Response<u64>>;
+
+        async fn get_state_values_with_proof(
+            &self,
+            version: u64,
+            start_index: u64,
+            end_index: u64,
+            request_timeout_ms: u64,
+        ) -> aptos_data_client::error::Result<Response<StateValueChunkWithProof>>;
+
+        async fn get_state_values_by_prefix_with_proof(
+            &self,
+            version: u64,
+            account_address: AccountAddress,
+            start_key: Option<StateKey>,
+            request_timeout_ms: u64,
+        ) -> aptos_data_client::error::Result<Response<StateValuesByPrefixWithProof>>;
+
+        async fn get_state_value_with_proof(
+            &self,
+            version: u64,
+            state_key: StateKey,
+            request_timeout_ms: u64,
+        ) -> aptos_data_client::error::Result<Response<StateValueWithProof>>;
+
+        async fn get_transaction_outputs_with_proof(
+            &self,
+            proof_version: Version,
+            start_version: Version,
+            end_version: Version,
+            request_timeout_ms: u64,
+        ) -> aptos_data_client::error::Result<Response<TransactionOutputListWithProof>>;
+
+        async fn get_transactions_with_proof(
+            &self,
+            proof_version: Version,
+            start_version: Version,
+            end_version: Version,
+            include_events: bool,
+            request_timeout_ms: u64,
+        ) -> aptos_data_client::error::Result<Response<TransactionListWithProof>>;
+
+        async fn get_transactions_or_outputs_with_proof(
+            &self,
+            proof_version: Version,
+            start_version: Version,
+            end_version: Version,
+            include_events: bool,
+            request_timeout_ms: u64,
+        ) -> aptos_data_client::error::Result<Response<TransactionOrOutputListWithProof>>;
+
+        async fn subscribe_to_transaction_outputs_with_proof(
+            &self,
+            subscription_request_metadata: SubscriptionRequestMetadata,
+            request_timeout_ms: u64,
+        ) -> aptos_data_client::error::Result<Response<(TransactionOutputListWithProof, LedgerInfoWithSignatures)>>;
+
+        async fn subscribe_to_transactions_with_proof(
+            &self,
+            subscription_request_metadata: SubscriptionRequestMetadata,
+            include_events: bool,
+            request_timeout_ms: u64,
+        ) -> aptos_data_client::error::Result<Response<(TransactionListWithProof, LedgerInfoWithSignatures)>>;
+
+        async fn subscribe_to_transactions_or_outputs_with_proof(
+            &self,
+            subscription_request_metadata: SubscriptionRequestMetadata,
+            include_events: bool,
+            request_timeout_ms: u64,
+        ) -> aptos_data_client::error::Result<Response<(TransactionOrOutputListWithProof, LedgerInfoWithSignatures)>>;
+    }
+}
+
 // This automatically creates a MockDatabaseReader.
 mock! {
     pub DatabaseReader {}
@@ -355,6 +483,28 @@ mock! {
             last_persisted_state_value_index: u64,
             snapshot_sync_completed: bool,
         ) -> Result<(), Error>;
+
+        fn get_snapshot_manifest(
+            &self,
+            target_ledger_info: &LedgerInfoWithSignatures,
+        ) -> Result<Option<StateSnapshotManifest>, Error>;
+
+        fn get_completed_state_ranges(
+            &self,
+            target_ledger_info: &LedgerInfoWithSignatures,
+        ) -> Result<CompletedStateRanges, Error>;
+
+        fn initialize_snapshot_manifest(
+            &self,
+            target_ledger_info: &LedgerInfoWithSignatures,
+            target_output_with_proof: &TransactionOutputListWithProof,
+        ) -> Result<(), Error>;
+
+        fn record_completed_state_range(
+            &self,
+            target_ledger_info: &LedgerInfoWithSignatures,
+            completed_state_range: CompletedStateRange,
+        ) -> Result<(), Error>;
     }
 
     impl Clone for MetadataStorage {
@@ -385,6 +535,12 @@ mock! {
             start_index: Option<u64>,
         ) -> AnyhowResult<DataStreamListener, aptos_data_streaming_service::error::Error>;
 
+        async fn get_account_state_values(
+            &self,
+            version: Version,
+            account_addresses: Vec<AccountAddress>,
+        ) -> AnyhowResult<DataStreamListener, aptos_data_streaming_service::error::Error>;
+
         async fn get_all_epoch_ending_ledger_infos(
             &self,
             start_epoch: Epoch,
@@ -2,10 +2,12 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+mod account_state_syncer;
 mod bootstrapper;
 mod continuous_syncer;
 mod driver;
 mod driver_factory;
+mod light_client;
 mod metadata_storage;
 mod mocks;
 mod storage_synchronizer;
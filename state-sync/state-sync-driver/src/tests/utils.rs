@@ -3,7 +3,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::driver::DriverConfiguration;
-use aptos_config::config::{ConsensusObserverConfig, RoleType, StateSyncDriverConfig};
+use aptos_config::config::{
+    ConsensusObserverConfig, LightClientConfig, RoleType, StateSyncDriverConfig,
+};
 use aptos_crypto::{
     ed25519::{Ed25519PrivateKey, Ed25519Signature},
     HashValue, PrivateKey, Uniform,
@@ -86,12 +88,14 @@ pub fn create_event(event_key: Option<EventKey>) -> ContractEvent {
 pub fn create_full_node_driver_configuration() -> DriverConfiguration {
     let config = StateSyncDriverConfig::default();
     let consensus_observer_config = ConsensusObserverConfig::default();
+    let light_client_config = LightClientConfig::default();
     let role = RoleType::FullNode;
     let waypoint = Waypoint::default();
 
     DriverConfiguration {
         config,
         consensus_observer_config,
+        light_client_config,
         role,
         waypoint,
     }
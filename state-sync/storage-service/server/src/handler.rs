@@ -21,9 +21,10 @@ use aptos_logger::{debug, sample, sample::SampleRate, trace, warn};
 use aptos_network::protocols::wire::handshake::v1::ProtocolId;
 use aptos_storage_service_types::{
     requests::{
-        DataRequest, EpochEndingLedgerInfoRequest, StateValuesWithProofRequest,
-        StorageServiceRequest, TransactionOutputsWithProofRequest,
-        TransactionsOrOutputsWithProofRequest, TransactionsWithProofRequest,
+        DataRequest, EpochEndingLedgerInfoRequest, StateValueWithProofRequest,
+        StateValuesByPrefixWithProofRequest, StateValuesWithProofRequest, StorageServiceRequest,
+        TransactionOutputsWithProofRequest, TransactionsOrOutputsWithProofRequest,
+        TransactionsWithProofRequest,
     },
     responses::{
         DataResponse, ServerProtocolVersion, StorageServerSummary, StorageServiceResponse,
@@ -409,6 +410,12 @@ impl<T: StorageReaderInterface> Handler<T> {
             DataRequest::GetTransactionsOrOutputsWithProof(request) => {
                 self.get_transactions_or_outputs_with_proof(request)
             },
+            DataRequest::GetStateValuesByPrefixWithProof(request) => {
+                self.get_state_values_by_prefix_with_proof(request)
+            },
+            DataRequest::GetStateValueWithProof(request) => {
+                self.get_state_value_with_proof(request)
+            },
             _ => Err(Error::UnexpectedErrorEncountered(format!(
                 "Received an unexpected request: {:?}",
                 request
@@ -458,6 +465,33 @@ impl<T: StorageReaderInterface> Handler<T> {
         ))
     }
 
+    fn get_state_values_by_prefix_with_proof(
+        &self,
+        request: &StateValuesByPrefixWithProofRequest,
+    ) -> aptos_storage_service_types::Result<DataResponse, Error> {
+        let state_values_by_prefix_with_proof =
+            self.storage.get_state_values_by_prefix_with_proof(
+                request.version,
+                request.account_address,
+                request.start_key.clone(),
+            )?;
+
+        Ok(DataResponse::StateValuesByPrefixWithProof(
+            state_values_by_prefix_with_proof,
+        ))
+    }
+
+    fn get_state_value_with_proof(
+        &self,
+        request: &StateValueWithProofRequest,
+    ) -> aptos_storage_service_types::Result<DataResponse, Error> {
+        let state_value_with_proof = self
+            .storage
+            .get_state_value_with_proof(request.version, request.state_key.clone())?;
+
+        Ok(DataResponse::StateValueWithProof(state_value_with_proof))
+    }
+
     fn get_epoch_ending_ledger_infos(
         &self,
         request: &EpochEndingLedgerInfoRequest,
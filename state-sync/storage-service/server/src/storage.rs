@@ -9,14 +9,26 @@ use aptos_storage_service_types::responses::{
     CompleteDataRange, DataResponse, DataSummary, TransactionOrOutputListWithProof,
 };
 use aptos_types::{
+    account_address::AccountAddress,
     epoch_change::EpochChangeProof,
     ledger_info::LedgerInfoWithSignatures,
-    state_store::state_value::StateValueChunkWithProof,
-    transaction::{TransactionListWithProof, TransactionOutputListWithProof, Version},
+    proof::{SparseMerkleProofExt, TransactionAccumulatorRangeProof, TransactionInfoListWithProof},
+    state_store::{
+        state_key::{prefix::StateKeyPrefix, StateKey},
+        state_value::{
+            StateValue, StateValueChunkWithProof, StateValueWithProof, StateValuesByPrefixWithProof,
+        },
+    },
+    transaction::{
+        TransactionInfo, TransactionListWithProof, TransactionOutputListWithProof, Version,
+    },
 };
 use serde::Serialize;
 use std::{cmp::min, sync::Arc};
 
+// The response label for state values fetched by key prefix
+const STATE_VALUES_BY_PREFIX_LABEL: &str = "state_values_by_prefix_with_proof";
+
 /// The interface into local storage (e.g., the Aptos DB) used by the storage
 /// server to handle client requests and responses.
 pub trait StorageReaderInterface: Clone + Send + 'static {
@@ -89,6 +101,28 @@ pub trait StorageReaderInterface: Clone + Send + 'static {
         start_index: u64,
         end_index: u64,
     ) -> aptos_storage_service_types::Result<StateValueChunkWithProof, Error>;
+
+    /// Returns the list of state values under the specified account at the
+    /// given version, starting at `start_key` (inclusive), or the first key
+    /// under the account if no start key is specified. Each state value is
+    /// returned with an inclusion proof. In some cases, not all state values
+    /// may be returned (e.g., due to network or chunk limits), in which case
+    /// the response will contain the key to resume from.
+    fn get_state_values_by_prefix_with_proof(
+        &self,
+        version: u64,
+        account_address: AccountAddress,
+        start_key: Option<StateKey>,
+    ) -> aptos_storage_service_types::Result<StateValuesByPrefixWithProof, Error>;
+
+    /// Returns the state value for the specified key at the given version
+    /// (or proves its absence). The proof is relative to the ledger at the
+    /// same version.
+    fn get_state_value_with_proof(
+        &self,
+        version: u64,
+        state_key: StateKey,
+    ) -> aptos_storage_service_types::Result<StateValueWithProof, Error>;
 }
 
 /// The underlying implementation of the StorageReaderInterface, used by the
@@ -107,6 +141,46 @@ impl StorageReader {
         Self { config, storage }
     }
 
+    /// Returns the transaction info (and accumulator proof) at the specified
+    /// version. State proofs can only be created against the state checkpoint
+    /// hash of a transaction info, so requests for versions that are not state
+    /// checkpoints (i.e., not the end of a block) are rejected as invalid.
+    fn get_state_checkpoint_info_with_proof(
+        &self,
+        version: Version,
+    ) -> aptos_storage_service_types::Result<TransactionInfoListWithProof, Error> {
+        // Fetch the transaction info at the version
+        let transaction_info = self
+            .storage
+            .get_transaction_info_iterator(version, 1)?
+            .next()
+            .transpose()?
+            .ok_or_else(|| {
+                Error::StorageErrorEncountered(format!(
+                    "No transaction info found at version: {:?}",
+                    version
+                ))
+            })?;
+
+        // Verify the version is a state checkpoint
+        if transaction_info.state_checkpoint_hash().is_none() {
+            return Err(Error::InvalidRequest(format!(
+                "State proofs are only available at state checkpoint versions \
+                (e.g., ledger info versions)! Requested version: {:?}",
+                version
+            )));
+        }
+
+        // Fetch the proof that the transaction info is committed at the version
+        let accumulator_range_proof = self
+            .storage
+            .get_transaction_accumulator_range_proof(version, 1, version)?;
+        Ok(TransactionInfoListWithProof::new(
+            accumulator_range_proof,
+            vec![transaction_info],
+        ))
+    }
+
     /// Returns the state values range held in the database (lowest to highest).
     /// Note: it is currently assumed that if a node contains a transaction at a
     /// version, V, the node also contains all state values at V.
@@ -498,6 +572,96 @@ impl StorageReaderInterface for StorageReader {
             version, start_index, end_index
         )))
     }
+
+    fn get_state_values_by_prefix_with_proof(
+        &self,
+        version: u64,
+        account_address: AccountAddress,
+        start_key: Option<StateKey>,
+    ) -> aptos_storage_service_types::Result<StateValuesByPrefixWithProof, Error> {
+        // Fetch the transaction info (and proof) that commits to the state root
+        let transaction_info_with_proof = self.get_state_checkpoint_info_with_proof(version)?;
+
+        // Fetch the state values (and proofs) under the account, up to the max chunk size
+        let key_prefix = StateKeyPrefix::from(account_address);
+        let max_num_state_values = self.config.max_state_chunk_size as usize;
+        let mut raw_values = vec![];
+        let mut next_key = None;
+        for state_value in self.storage.get_prefixed_state_value_iterator(
+            &key_prefix,
+            start_key.as_ref(),
+            version,
+        )? {
+            let (state_key, state_value) = state_value?;
+            if raw_values.len() >= max_num_state_values {
+                next_key = Some(state_key);
+                break;
+            }
+            let proof = self
+                .storage
+                .get_state_proof_by_version_ext(&state_key, version, 0)?;
+            raw_values.push((state_key, state_value, proof.into()));
+        }
+
+        // Attempt to serve the request
+        let mut state_values_with_proof = StateValuesByPrefixWithProof {
+            version,
+            raw_values,
+            next_key,
+            transaction_info_with_proof,
+        };
+        loop {
+            let num_state_values = state_values_with_proof.raw_values.len();
+            if num_state_values <= 1 {
+                return Ok(state_values_with_proof); // We cannot return less than a single item
+            }
+
+            // Attempt to divide up the response if it overflows the message size
+            let (overflow_frame, num_bytes) = check_overflow_network_frame(
+                &state_values_with_proof,
+                self.config.max_network_chunk_bytes,
+            )?;
+            if !overflow_frame {
+                return Ok(state_values_with_proof);
+            } else {
+                increment_network_frame_overflow(STATE_VALUES_BY_PREFIX_LABEL);
+                let new_num_state_values = num_state_values / 2;
+                debug!("The request for {:?} state values under account {:?} was too large (num bytes: {:?}). Retrying with {:?}.",
+                    num_state_values, account_address, num_bytes, new_num_state_values);
+
+                // Drop the second half of the state values and resume from there next time
+                let dropped_values = state_values_with_proof
+                    .raw_values
+                    .split_off(new_num_state_values);
+                state_values_with_proof.next_key = dropped_values
+                    .into_iter()
+                    .next()
+                    .map(|(state_key, _, _)| state_key);
+            }
+        }
+    }
+
+    fn get_state_value_with_proof(
+        &self,
+        version: u64,
+        state_key: StateKey,
+    ) -> aptos_storage_service_types::Result<StateValueWithProof, Error> {
+        // Fetch the transaction info (and proof) that commits to the state root
+        let transaction_info_with_proof = self.get_state_checkpoint_info_with_proof(version)?;
+
+        // Fetch the state value and the sparse merkle proof
+        let (state_value, sparse_merkle_proof) = self
+            .storage
+            .get_state_value_with_proof_by_version_ext(&state_key, version, 0)?;
+
+        Ok(StateValueWithProof {
+            version,
+            state_key,
+            state_value,
+            sparse_merkle_proof: sparse_merkle_proof.into(),
+            transaction_info_with_proof,
+        })
+    }
 }
 
 // A simple macro that wraps each storage read call with a timer
@@ -578,6 +742,40 @@ impl DbReader for TimedStorageReader {
             start_idx: usize,
             chunk_size: usize,
         ) -> StorageResult<StateValueChunkWithProof>;
+
+        fn get_transaction_info_iterator(
+            &self,
+            start_version: Version,
+            limit: u64,
+        ) -> StorageResult<Box<dyn Iterator<Item = StorageResult<TransactionInfo>> + '_>>;
+
+        fn get_prefixed_state_value_iterator(
+            &self,
+            key_prefix: &StateKeyPrefix,
+            cursor: Option<&StateKey>,
+            version: Version,
+        ) -> StorageResult<Box<dyn Iterator<Item = StorageResult<(StateKey, StateValue)>> + '_>>;
+
+        fn get_state_proof_by_version_ext(
+            &self,
+            state_key: &StateKey,
+            version: Version,
+            root_depth: usize,
+        ) -> StorageResult<SparseMerkleProofExt>;
+
+        fn get_state_value_with_proof_by_version_ext(
+            &self,
+            state_key: &StateKey,
+            version: Version,
+            root_depth: usize,
+        ) -> StorageResult<(Option<StateValue>, SparseMerkleProofExt)>;
+
+        fn get_transaction_accumulator_range_proof(
+            &self,
+            start_version: Version,
+            limit: u64,
+            ledger_version: Version,
+        ) -> StorageResult<TransactionAccumulatorRangeProof>;
     );
 }
 
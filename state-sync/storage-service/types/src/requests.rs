@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::COMPRESSION_SUFFIX_LABEL;
-use aptos_types::transaction::Version;
+use aptos_types::{
+    account_address::AccountAddress, state_store::state_key::StateKey, transaction::Version,
+};
 use serde::{Deserialize, Serialize};
 
 /// A storage service request.
@@ -47,6 +49,8 @@ pub enum DataRequest {
     SubscribeTransactionOutputsWithProof(SubscribeTransactionOutputsWithProofRequest), // Subscribes to transaction outputs with a proof
     SubscribeTransactionsOrOutputsWithProof(SubscribeTransactionsOrOutputsWithProofRequest), // Subscribes to transactions or outputs with a proof
     SubscribeTransactionsWithProof(SubscribeTransactionsWithProofRequest), // Subscribes to transactions with a proof
+    GetStateValuesByPrefixWithProof(StateValuesByPrefixWithProofRequest), // Fetches the states under an account with proofs
+    GetStateValueWithProof(StateValueWithProofRequest), // Fetches a single state value with a proof
 }
 
 impl DataRequest {
@@ -73,6 +77,8 @@ impl DataRequest {
                 "subscribe_transactions_or_outputs_with_proof"
             },
             Self::SubscribeTransactionsWithProof(_) => "subscribe_transactions_with_proof",
+            Self::GetStateValuesByPrefixWithProof(_) => "get_state_values_by_prefix_with_proof",
+            Self::GetStateValueWithProof(_) => "get_state_value_with_proof",
         }
     }
 
@@ -130,6 +136,25 @@ pub struct StateValuesWithProofRequest {
     pub end_index: u64,   // The index to stop fetching state values (inclusive)
 }
 
+/// A storage service request for fetching the list of state values
+/// under an account (i.e., sharing the account's key prefix) at a
+/// specified version. Each state value is returned with a proof.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct StateValuesByPrefixWithProofRequest {
+    pub version: u64,                    // The version to fetch the state values at
+    pub account_address: AccountAddress, // The account to fetch the state values for
+    pub start_key: Option<StateKey>,     // The key to start fetching state values (inclusive)
+}
+
+/// A storage service request for fetching a single state value (or proving
+/// its absence) at a specified version. The proof is relative to the ledger
+/// at the same version.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct StateValueWithProofRequest {
+    pub version: u64,        // The version to fetch the state value at
+    pub state_key: StateKey, // The key of the state value to fetch
+}
+
 /// A storage service request for fetching a transaction output list with a
 /// corresponding proof.
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
@@ -5,8 +5,9 @@ use crate::{
     requests::DataRequest::{
         GetEpochEndingLedgerInfos, GetNewTransactionOutputsWithProof,
         GetNewTransactionsOrOutputsWithProof, GetNewTransactionsWithProof,
-        GetNumberOfStatesAtVersion, GetServerProtocolVersion, GetStateValuesWithProof,
-        GetStorageServerSummary, GetTransactionOutputsWithProof, GetTransactionsOrOutputsWithProof,
+        GetNumberOfStatesAtVersion, GetServerProtocolVersion, GetStateValueWithProof,
+        GetStateValuesByPrefixWithProof, GetStateValuesWithProof, GetStorageServerSummary,
+        GetTransactionOutputsWithProof, GetTransactionsOrOutputsWithProof,
         GetTransactionsWithProof, SubscribeTransactionOutputsWithProof,
         SubscribeTransactionsOrOutputsWithProof, SubscribeTransactionsWithProof,
     },
@@ -21,7 +22,9 @@ use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_types::{
     epoch_change::EpochChangeProof,
     ledger_info::LedgerInfoWithSignatures,
-    state_store::state_value::StateValueChunkWithProof,
+    state_store::state_value::{
+        StateValueChunkWithProof, StateValueWithProof, StateValuesByPrefixWithProof,
+    },
     transaction::{TransactionListWithProof, TransactionOutputListWithProof, Version},
 };
 use num_traits::{PrimInt, Zero};
@@ -139,6 +142,8 @@ pub enum DataResponse {
     TransactionsWithProof(TransactionListWithProof),
     NewTransactionsOrOutputsWithProof((TransactionOrOutputListWithProof, LedgerInfoWithSignatures)),
     TransactionsOrOutputsWithProof(TransactionOrOutputListWithProof),
+    StateValuesByPrefixWithProof(StateValuesByPrefixWithProof),
+    StateValueWithProof(StateValueWithProof),
 }
 
 impl DataResponse {
@@ -156,6 +161,8 @@ impl DataResponse {
             Self::TransactionsWithProof(_) => "transactions_with_proof",
             Self::NewTransactionsOrOutputsWithProof(_) => "new_transactions_or_outputs_with_proof",
             Self::TransactionsOrOutputsWithProof(_) => "transactions_or_outputs_with_proof",
+            Self::StateValuesByPrefixWithProof(_) => "state_values_by_prefix_with_proof",
+            Self::StateValueWithProof(_) => "state_value_with_proof",
         }
     }
 }
@@ -193,6 +200,36 @@ impl TryFrom<StorageServiceResponse> for StateValueChunkWithProof {
     }
 }
 
+impl TryFrom<StorageServiceResponse> for StateValuesByPrefixWithProof {
+    type Error = crate::responses::Error;
+
+    fn try_from(response: StorageServiceResponse) -> crate::Result<Self, Self::Error> {
+        let data_response = response.get_data_response()?;
+        match data_response {
+            DataResponse::StateValuesByPrefixWithProof(inner) => Ok(inner),
+            _ => Err(Error::UnexpectedResponseError(format!(
+                "expected state_values_by_prefix_with_proof, found {}",
+                data_response.get_label()
+            ))),
+        }
+    }
+}
+
+impl TryFrom<StorageServiceResponse> for StateValueWithProof {
+    type Error = crate::responses::Error;
+
+    fn try_from(response: StorageServiceResponse) -> crate::Result<Self, Self::Error> {
+        let data_response = response.get_data_response()?;
+        match data_response {
+            DataResponse::StateValueWithProof(inner) => Ok(inner),
+            _ => Err(Error::UnexpectedResponseError(format!(
+                "expected state_value_with_proof, found {}",
+                data_response.get_label()
+            ))),
+        }
+    }
+}
+
 impl TryFrom<StorageServiceResponse> for EpochChangeProof {
     type Error = crate::responses::Error;
 
@@ -488,6 +525,13 @@ impl DataSummary {
 
                 can_serve_states && can_create_proof
             },
+            // Note: state proofs can only be created at state checkpoint versions
+            // (which are not advertised), so requests should only be made at
+            // ledger info versions (which are always state checkpoints).
+            GetStateValuesByPrefixWithProof(request) => {
+                self.can_service_state_proof(request.version)
+            },
+            GetStateValueWithProof(request) => self.can_service_state_proof(request.version),
             GetTransactionOutputsWithProof(request) => {
                 let desired_range =
                     match CompleteDataRange::new(request.start_version, request.end_version) {
@@ -571,6 +615,29 @@ impl DataSummary {
         }
     }
 
+    /// Returns true iff a state proof (i.e., state values and the transaction
+    /// info that commits to the state root) can be created at the given version
+    fn can_service_state_proof(&self, proof_version: Version) -> bool {
+        let can_serve_states = self
+            .states
+            .map(|range| range.contains(proof_version))
+            .unwrap_or(false);
+
+        // The transaction info at the version is required to prove the state root
+        let can_serve_transaction_info = self
+            .transactions
+            .map(|range| range.contains(proof_version))
+            .unwrap_or(false);
+
+        let can_create_proof = self
+            .synced_ledger_info
+            .as_ref()
+            .map(|li| li.ledger_info().version() >= proof_version)
+            .unwrap_or(false);
+
+        can_serve_states && can_serve_transaction_info && can_create_proof
+    }
+
     /// Returns the version of the synced ledger info (if one exists)
     pub fn get_synced_ledger_info_version(&self) -> Option<u64> {
         self.synced_ledger_info
@@ -5,6 +5,7 @@ use crate::{
     requests::{
         DataRequest, EpochEndingLedgerInfoRequest, NewTransactionOutputsWithProofRequest,
         NewTransactionsOrOutputsWithProofRequest, NewTransactionsWithProofRequest,
+        StateValueWithProofRequest, StateValuesByPrefixWithProofRequest,
         StateValuesWithProofRequest, SubscribeTransactionOutputsWithProofRequest,
         SubscribeTransactionsOrOutputsWithProofRequest, SubscribeTransactionsWithProofRequest,
         SubscriptionStreamMetadata, TransactionOutputsWithProofRequest,
@@ -17,9 +18,11 @@ use aptos_config::config::AptosDataClientConfig;
 use aptos_crypto::hash::HashValue;
 use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_types::{
+    account_address::AccountAddress,
     aggregate_signature::AggregateSignature,
     block_info::BlockInfo,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    state_store::state_key::StateKey,
     transaction::Version,
 };
 use claims::{assert_err, assert_ok};
@@ -466,6 +469,76 @@ fn test_data_summary_can_service_state_chunk_request() {
     }
 }
 
+#[test]
+fn test_data_summary_can_service_state_prefix_request() {
+    // Create a data client config and data summary
+    let data_client_config = AptosDataClientConfig::default();
+    let data_summary = DataSummary {
+        synced_ledger_info: Some(create_ledger_info_at_version(250)),
+        states: Some(create_data_range(100, 300)),
+        transactions: Some(create_data_range(150, 300)),
+        ..Default::default()
+    };
+
+    // Verify the different requests that can be serviced
+    for compression in [true, false] {
+        // Test the valid request versions
+        let valid_request_versions = vec![150, 200, 250];
+        verify_can_service_state_prefix_requests(
+            &data_client_config,
+            &data_summary,
+            compression,
+            valid_request_versions,
+            true,
+        );
+
+        // Test invalid request versions
+        let invalid_request_versions = vec![50, 99, 100, 149, 251, 300];
+        verify_can_service_state_prefix_requests(
+            &data_client_config,
+            &data_summary,
+            compression,
+            invalid_request_versions,
+            false,
+        );
+    }
+}
+
+#[test]
+fn test_data_summary_can_service_state_value_request() {
+    // Create a data client config and data summary
+    let data_client_config = AptosDataClientConfig::default();
+    let data_summary = DataSummary {
+        synced_ledger_info: Some(create_ledger_info_at_version(250)),
+        states: Some(create_data_range(100, 300)),
+        transactions: Some(create_data_range(150, 300)),
+        ..Default::default()
+    };
+
+    // Verify the different requests that can be serviced
+    for compression in [true, false] {
+        // Test the valid request versions
+        let valid_request_versions = vec![150, 200, 250];
+        verify_can_service_state_value_requests(
+            &data_client_config,
+            &data_summary,
+            compression,
+            valid_request_versions,
+            true,
+        );
+
+        // Test invalid request versions
+        let invalid_request_versions = vec![50, 100, 149, 251, 300];
+        verify_can_service_state_value_requests(
+            &data_client_config,
+            &data_summary,
+            compression,
+            invalid_request_versions,
+            false,
+        );
+    }
+}
+
 #[test]
 fn test_protocol_metadata_service() {
     // Create the protocol metadata
@@ -691,6 +764,29 @@ fn create_state_values_request_at_version(
     create_state_values_request(version, 0, 1000, use_compression)
 }
 
+/// Creates a request for the state values under an account
+fn create_state_values_by_prefix_request(
+    version: Version,
+    use_compression: bool,
+) -> StorageServiceRequest {
+    let data_request =
+        DataRequest::GetStateValuesByPrefixWithProof(StateValuesByPrefixWithProofRequest {
+            version,
+            account_address: AccountAddress::random(),
+            start_key: None,
+        });
+    StorageServiceRequest::new(data_request, use_compression)
+}
+
+/// Creates a request for a single state value
+fn create_state_value_request(version: Version, use_compression: bool) -> StorageServiceRequest {
+    let data_request = DataRequest::GetStateValueWithProof(StateValueWithProofRequest {
+        version,
+        state_key: StateKey::raw(&[0]),
+    });
+    StorageServiceRequest::new(data_request, use_compression)
+}
+
 /// Generates a random u64
 fn get_random_u64() -> u64 {
     thread_rng().gen()
@@ -772,6 +868,56 @@ fn verify_can_service_state_chunk_requests(
     }
 }
 
+/// Verifies the serviceability of the state prefix request versions
+/// against the specified data summary. If `expect_service` is true,
+/// then the request should be serviceable.
+fn verify_can_service_state_prefix_requests(
+    data_client_config: &AptosDataClientConfig,
+    data_summary: &DataSummary,
+    use_compression: bool,
+    versions: Vec<u64>,
+    expect_service: bool,
+) {
+    for version in versions {
+        // Create the state prefix request
+        let request = create_state_values_by_prefix_request(version, use_compression);
+
+        // Verify the serviceability of the request
+        verify_serviceability(
+            data_client_config,
+            data_summary,
+            None,
+            request,
+            expect_service,
+        );
+    }
+}
+
+/// Verifies the serviceability of the state value request versions
+/// against the specified data summary. If `expect_service` is true,
+/// then the request should be serviceable.
+fn verify_can_service_state_value_requests(
+    data_client_config: &AptosDataClientConfig,
+    data_summary: &DataSummary,
+    use_compression: bool,
+    versions: Vec<u64>,
+    expect_service: bool,
+) {
+    for version in versions {
+        // Create the state value request
+        let request = create_state_value_request(version, use_compression);
+
+        // Verify the serviceability of the request
+        verify_serviceability(
+            data_client_config,
+            data_summary,
+            None,
+            request,
+            expect_service,
+        );
+    }
+}
+
 /// Verifies the serviceability of the subscription versions against
 /// the specified data summary. If `expect_service` is true, then the
 /// request should be serviceable.
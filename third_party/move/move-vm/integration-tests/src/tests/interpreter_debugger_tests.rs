@@ -0,0 +1,166 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::compiler::{as_module, compile_units};
+use move_binary_format::file_format::Bytecode;
+use move_core_types::{
+    account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+    value::MoveValue,
+};
+use move_vm_runtime::{
+    module_traversal::*, move_vm::MoveVM, tracing::set_interpreter_debugger, DebugLocation,
+    DebugState, InterpreterDebugger,
+};
+use move_vm_test_utils::InMemoryStorage;
+use move_vm_types::gas::UnmeteredGasMeter;
+use std::sync::{Arc, Mutex};
+
+// Not shared with the other tests: the debugger is global, and ignores the other modules
+const TEST_ADDR: AccountAddress = AccountAddress::new([43; AccountAddress::LENGTH]);
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    Instruction {
+        function: String,
+        pc: u16,
+        is_entry: bool,
+    },
+    Stop {
+        function: String,
+        pc: u16,
+        depth: usize,
+        operand_stack_size: usize,
+    },
+}
+
+// Records the instructions of the test module, and stops before additions
+struct RecordingDebugger {
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+impl InterpreterDebugger for RecordingDebugger {
+    fn should_stop(&mut self, location: &DebugLocation) -> bool {
+        if location.module_id.map(|id| *id.address()) != Some(TEST_ADDR) {
+            return false;
+        }
+        self.events.lock().unwrap().push(Event::Instruction {
+            function: location.function_name.to_string(),
+            pc: location.pc,
+            is_entry: location.is_entry,
+        });
+        matches!(location.instruction, Bytecode::Add)
+    }
+
+    fn on_stop(&mut self, state: DebugState) {
+        let frame = state.current_frame();
+        self.events.lock().unwrap().push(Event::Stop {
+            function: frame.function_name.clone(),
+            pc: frame.pc,
+            depth: state.frames.len(),
+            operand_stack_size: state.operand_stack.len(),
+        });
+    }
+}
+
+#[test]
+fn test_debugger_stops_before_instructions() {
+    let code = r#"
+        module {{ADDR}}::M {
+            fun add(a: u64, b: u64): u64 {
+                a + b
+            }
+
+            fun count_down(n: u64): u64 {
+                while (n > 0) {
+                    n = add(n, 0) - 1;
+                };
+                n
+            }
+        }
+    "#;
+    let code = code.replace("{{ADDR}}", &format!("0x{}", TEST_ADDR.to_hex()));
+    let mut units = compile_units(&code).unwrap();
+    let m = as_module(units.pop().unwrap());
+    let mut blob = vec![];
+    m.serialize(&mut blob).unwrap();
+
+    let mut storage = InMemoryStorage::new();
+    let module_id = ModuleId::new(TEST_ADDR, Identifier::new("M").unwrap());
+    storage.publish_or_overwrite_module(module_id.clone(), blob);
+
+    let vm = MoveVM::new(vec![]);
+    let mut sess = vm.new_session(&storage);
+    let traversal_storage = TraversalStorage::new();
+
+    let events = Arc::new(Mutex::new(vec![]));
+    set_interpreter_debugger(Some(Box::new(RecordingDebugger {
+        events: events.clone(),
+    })));
+    let result = sess.execute_function_bypass_visibility(
+        &module_id,
+        &Identifier::new("count_down").unwrap(),
+        vec![],
+        vec![MoveValue::U64(3).simple_serialize().unwrap()],
+        &mut UnmeteredGasMeter,
+        &mut TraversalContext::new(&traversal_storage),
+    );
+    set_interpreter_debugger(None);
+    result.unwrap();
+
+    let events = std::mem::take(&mut *events.lock().unwrap());
+    let instructions = |function: &str| {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Instruction {
+                    function: f,
+                    pc,
+                    is_entry,
+                } if f == function => Some((*pc, *is_entry)),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // The function is entered once, whatever the loop branches back to, and resumes after
+    // every call
+    let count_down = instructions("count_down");
+    assert_eq!(count_down[0], (0, true));
+    assert!(count_down[1..].iter().all(|(_, is_entry)| !is_entry));
+
+    // Every call enters the function once
+    let add = instructions("add");
+    assert_eq!(add.iter().filter(|(_, is_entry)| *is_entry).count(), 3);
+    assert!(add.iter().all(|(pc, is_entry)| (*pc == 0) == *is_entry));
+
+    // The interpreter stops before every addition, with both operands on the stack, and only
+    // executes the next instruction once the debugger resumes
+    let stops = events
+        .iter()
+        .enumerate()
+        .filter(|(_, event)| matches!(event, Event::Stop { .. }))
+        .map(|(idx, _)| idx)
+        .collect::<Vec<_>>();
+    assert_eq!(stops.len(), 3);
+    for idx in stops {
+        let Event::Instruction {
+            function,
+            pc: stop_pc,
+            ..
+        } = &events[idx - 1]
+        else {
+            panic!("The debugger must be asked whether to stop before stopping");
+        };
+        assert_eq!(events[idx], Event::Stop {
+            function: function.clone(),
+            pc: *stop_pc,
+            depth: 2,
+            operand_stack_size: 2,
+        });
+        assert_eq!(events[idx + 1], Event::Instruction {
+            function: "add".to_string(),
+            pc: stop_pc + 1,
+            is_entry: false,
+        });
+    }
+}
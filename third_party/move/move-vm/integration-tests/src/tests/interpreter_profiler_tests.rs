@@ -0,0 +1,116 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::compiler::{as_module, compile_units};
+use move_core_types::{
+    account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+    value::MoveValue,
+};
+use move_vm_runtime::{
+    module_traversal::*, move_vm::MoveVM, session::Session, tracing::set_interpreter_profiler,
+    DebugLocation, InterpreterProfiler,
+};
+use move_vm_test_utils::InMemoryStorage;
+use move_vm_types::gas::UnmeteredGasMeter;
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    thread::{self, ThreadId},
+};
+
+// Not shared with the other tests: the profiler is global, and ignores the other modules
+const TEST_ADDR: AccountAddress = AccountAddress::new([44; AccountAddress::LENGTH]);
+
+// Counts the instructions of the test module by function, and the exits of the interpreter on
+// the test thread
+struct CountingProfiler {
+    thread: ThreadId,
+    instructions: Mutex<BTreeMap<String, usize>>,
+    exits: Mutex<usize>,
+}
+
+impl InterpreterProfiler for CountingProfiler {
+    fn on_instruction(&self, location: &DebugLocation) {
+        if location.module_id.map(|id| *id.address()) != Some(TEST_ADDR) {
+            return;
+        }
+        *self
+            .instructions
+            .lock()
+            .unwrap()
+            .entry(location.function_name.to_string())
+            .or_default() += 1;
+    }
+
+    fn on_exit(&self) {
+        if thread::current().id() == self.thread {
+            *self.exits.lock().unwrap() += 1;
+        }
+    }
+}
+
+fn count_down(sess: &mut Session, module_id: &ModuleId) {
+    let traversal_storage = TraversalStorage::new();
+    sess.execute_function_bypass_visibility(
+        module_id,
+        &Identifier::new("count_down").unwrap(),
+        vec![],
+        vec![MoveValue::U64(3).simple_serialize().unwrap()],
+        &mut UnmeteredGasMeter,
+        &mut TraversalContext::new(&traversal_storage),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_profiler_is_called_for_every_instruction() {
+    let code = r#"
+        module {{ADDR}}::M {
+            fun add(a: u64, b: u64): u64 {
+                a + b
+            }
+
+            fun count_down(n: u64): u64 {
+                while (n > 0) {
+                    n = add(n, 0) - 1;
+                };
+                n
+            }
+        }
+    "#;
+    let code = code.replace("{{ADDR}}", &format!("0x{}", TEST_ADDR.to_hex()));
+    let mut units = compile_units(&code).unwrap();
+    let m = as_module(units.pop().unwrap());
+    let mut blob = vec![];
+    m.serialize(&mut blob).unwrap();
+
+    let mut storage = InMemoryStorage::new();
+    let module_id = ModuleId::new(TEST_ADDR, Identifier::new("M").unwrap());
+    storage.publish_or_overwrite_module(module_id.clone(), blob);
+
+    let vm = MoveVM::new(vec![]);
+    let mut sess = vm.new_session(&storage);
+
+    let profiler = Arc::new(CountingProfiler {
+        thread: thread::current().id(),
+        instructions: Mutex::new(BTreeMap::new()),
+        exits: Mutex::new(0),
+    });
+    set_interpreter_profiler(Some(profiler.clone()));
+    count_down(&mut sess, &module_id);
+    set_interpreter_profiler(None);
+
+    // Every call of `add` executes its four instructions, and the interpreter is exited once
+    let instructions = profiler.instructions.lock().unwrap().clone();
+    assert_eq!(instructions.keys().collect::<Vec<_>>(), vec![
+        "add",
+        "count_down"
+    ]);
+    assert_eq!(instructions["add"], 12);
+    assert_eq!(*profiler.exits.lock().unwrap(), 1);
+
+    // Nothing is recorded once the profiler is uninstalled
+    count_down(&mut sess, &module_id);
+    assert_eq!(*profiler.instructions.lock().unwrap(), instructions);
+    assert_eq!(*profiler.exits.lock().unwrap(), 1);
+}
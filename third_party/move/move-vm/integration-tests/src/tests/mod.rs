@@ -8,6 +8,10 @@ mod binary_format_version;
 mod exec_func_effects_tests;
 mod function_arg_tests;
 mod instantiation_tests;
+#[cfg(any(debug_assertions, feature = "debugging"))]
+mod interpreter_debugger_tests;
+#[cfg(any(debug_assertions, feature = "debugging"))]
+mod interpreter_profiler_tests;
 mod invariant_violation_tests;
 mod leak_tests;
 mod loader_tests;
@@ -3,7 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{interpreter::Interpreter, loader::Loader, LoadedFunction};
-use move_binary_format::file_format::Bytecode;
+use move_binary_format::file_format::{Bytecode, FunctionDefinitionIndex};
+use move_core_types::language_storage::{ModuleId, TypeTag};
 use move_vm_types::values::{self, Locals};
 use std::{
     collections::BTreeSet,
@@ -11,6 +12,124 @@ use std::{
     str::FromStr,
 };
 
+/// The location of the instruction the interpreter is about to execute
+pub struct DebugLocation<'a> {
+    /// `None` for scripts
+    pub module_id: Option<&'a ModuleId>,
+    pub function_name: &'a str,
+    pub function_index: FunctionDefinitionIndex,
+    pub pc: u16,
+    pub instruction: &'a Bytecode,
+    /// The number of frames on the call stack, including the current one
+    pub depth: usize,
+    /// Whether the instruction is the first one executed by the frame, i.e., on entry of the
+    /// function (branching back to the first instruction is not an entry)
+    pub is_entry: bool,
+}
+
+/// A frame of the call stack, as exposed to an [`InterpreterDebugger`]
+#[derive(Clone, Debug)]
+pub struct DebugFrame {
+    /// `None` for scripts
+    pub module_id: Option<ModuleId>,
+    pub function_name: String,
+    pub function_index: FunctionDefinitionIndex,
+    pub ty_args: Vec<TypeTag>,
+    /// The instruction being executed. For the callers, this is the call instruction.
+    pub pc: u16,
+    /// The parameters and locals of the function, by index. Values that are not set are
+    /// printed as `-`.
+    pub locals: Vec<String>,
+}
+
+impl DebugFrame {
+    pub(crate) fn new(
+        function: &LoadedFunction,
+        locals: &Locals,
+        pc: u16,
+        loader: &Loader,
+    ) -> Self {
+        Self {
+            module_id: function.module_id().cloned(),
+            function_name: function.name().to_string(),
+            function_index: function.index(),
+            ty_args: function
+                .ty_args()
+                .iter()
+                .filter_map(|ty| loader.type_to_type_tag(ty).ok())
+                .collect(),
+            pc,
+            locals: (0..function.local_tys().len())
+                .map(|idx| {
+                    let mut value = String::new();
+                    let _ = values::debug::print_local(&mut value, locals, idx);
+                    value
+                })
+                .collect(),
+        }
+    }
+
+    /// The fully qualified name of the function, e.g., `0x1::coin::transfer`
+    pub fn qualified_name(&self) -> String {
+        match &self.module_id {
+            Some(module_id) => format!(
+                "0x{}::{}::{}",
+                module_id.address().short_str_lossless(),
+                module_id.name(),
+                self.function_name
+            ),
+            None => format!("script::{}", self.function_name),
+        }
+    }
+}
+
+/// The state of the interpreter before the execution of an instruction
+#[derive(Clone, Debug)]
+pub struct DebugState {
+    /// The call stack, from the outermost frame to the current one
+    pub frames: Vec<DebugFrame>,
+    /// The instruction the current frame is about to execute
+    pub instruction: Bytecode,
+    /// The operand stack, from the bottom to the top
+    pub operand_stack: Vec<String>,
+}
+
+impl DebugState {
+    pub fn current_frame(&self) -> &DebugFrame {
+        self.frames
+            .last()
+            .expect("The call stack always contains the current frame")
+    }
+}
+
+/// A debugger driven by the interpreter, e.g., to step through a transaction. Once installed
+/// with [`crate::tracing::set_interpreter_debugger`], it is called before the execution of
+/// every instruction.
+pub trait InterpreterDebugger: Send {
+    /// Returns whether to stop before the execution of the instruction. Called for every
+    /// instruction, so it must be cheap.
+    fn should_stop(&mut self, location: &DebugLocation) -> bool;
+
+    /// Called when stopped, with the state of the interpreter. Execution resumes once it
+    /// returns.
+    fn on_stop(&mut self, state: DebugState);
+}
+
+/// A profiler driven by the interpreter. Once installed with
+/// [`crate::tracing::set_interpreter_profiler`], it is called before the execution of every
+/// instruction, and when the interpreter returns to its caller, on the thread running the
+/// interpreter. As the interpreter may run on several threads at once, e.g., for unit tests, it
+/// is called concurrently, and should keep the state of every thread apart, e.g., thread-locally,
+/// rather than lock shared state for every instruction.
+pub trait InterpreterProfiler: Send + Sync {
+    /// Called before the execution of every instruction, so it must be cheap.
+    fn on_instruction(&self, location: &DebugLocation);
+
+    /// Called when the interpreter returns to its caller, successfully or not, after the last
+    /// instruction it executed.
+    fn on_exit(&self);
+}
+
 #[derive(Debug)]
 enum DebugCommand {
     PrintStack,
@@ -94,7 +94,7 @@ impl Interpreter {
         extensions: &mut NativeContextExtensions,
         loader: &Loader,
     ) -> VMResult<Vec<Value>> {
-        Interpreter {
+        let result = Interpreter {
             operand_stack: Stack::new(),
             call_stack: CallStack::new(),
             paranoid_type_checks: loader.vm_config().paranoid_type_checks,
@@ -110,7 +110,11 @@ impl Interpreter {
             extensions,
             function,
             args,
-        )
+        );
+        // Only include this code in debug releases
+        #[cfg(any(debug_assertions, feature = "debugging"))]
+        crate::tracing::exit_interpreter();
+        result
     }
 
     /// Main loop for the execution of a function.
@@ -1045,6 +1049,47 @@ impl Interpreter {
         Ok(())
     }
 
+    /// The number of frames on the call stack, including the current one
+    #[cfg(any(debug_assertions, feature = "debugging"))]
+    pub(crate) fn debug_call_stack_depth(&self) -> usize {
+        self.call_stack.0.len() + 1
+    }
+
+    /// Captures the state of the interpreter for an [`crate::InterpreterDebugger`], before the
+    /// current frame executes the given instruction.
+    #[cfg(any(debug_assertions, feature = "debugging"))]
+    pub(crate) fn debug_state(
+        &self,
+        function: &LoadedFunction,
+        locals: &Locals,
+        pc: u16,
+        instr: &Bytecode,
+        loader: &Loader,
+    ) -> crate::DebugState {
+        let mut frames: Vec<_> = self
+            .call_stack
+            .0
+            .iter()
+            .map(|frame| crate::DebugFrame::new(&frame.function, &frame.locals, frame.pc, loader))
+            .collect();
+        frames.push(crate::DebugFrame::new(function, locals, pc, loader));
+        let operand_stack = self
+            .operand_stack
+            .value
+            .iter()
+            .map(|val| {
+                let mut value = String::new();
+                let _ = values::debug::print_value(&mut value, val);
+                value
+            })
+            .collect();
+        crate::DebugState {
+            frames,
+            instruction: instr.clone(),
+            operand_stack,
+        }
+    }
+
     /// Generate a string which is the status of the interpreter: call stack, current bytecode
     /// stream, locals and operand stack.
     ///
@@ -2274,6 +2319,9 @@ impl Frame {
         }
 
         let code = self.function.code();
+        // Callers resume after the call instruction, so the frame is only entered at offset 0
+        #[cfg(any(debug_assertions, feature = "debugging"))]
+        let mut is_entry = self.pc == 0;
         loop {
             for instruction in &code[self.pc as usize..] {
                 trace!(
@@ -2282,7 +2330,8 @@ impl Frame {
                     self.pc,
                     instruction,
                     resolver,
-                    interpreter
+                    interpreter,
+                    is_entry
                 );
 
                 fail_point!("move_vm::interpreter_loop", |_| {
@@ -2320,6 +2369,13 @@ impl Frame {
                     },
                     Bytecode::Ret => {
                         gas_meter.charge_simple_instr(S::Ret)?;
+                        if gas_meter.observes_ret_vals() {
+                            gas_meter.observe_ret_vals(
+                                interpreter
+                                    .operand_stack
+                                    .last_n(self.function.return_tys().len())?,
+                            )?;
+                        }
                         return Ok(ExitCode::Return);
                     },
                     Bytecode::BrTrue(offset) => {
@@ -28,6 +28,8 @@ pub mod module_traversal;
 // Only include debugging functionality in debug builds
 #[cfg(any(debug_assertions, feature = "debugging"))]
 mod debug;
+#[cfg(any(debug_assertions, feature = "debugging"))]
+pub use debug::{DebugFrame, DebugLocation, DebugState, InterpreterDebugger, InterpreterProfiler};
 
 mod access_control;
 
@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 #[cfg(any(debug_assertions, feature = "debugging"))]
-use crate::debug::DebugContext;
+use crate::debug::{DebugContext, DebugLocation, InterpreterDebugger, InterpreterProfiler};
 #[cfg(any(debug_assertions, feature = "debugging"))]
 use crate::{
     interpreter::Interpreter,
@@ -15,10 +15,14 @@ use ::{
     move_vm_types::values::Locals,
     once_cell::sync::Lazy,
     std::{
+        cell::RefCell,
         env,
         fs::{File, OpenOptions},
         io::Write,
-        sync::Mutex,
+        sync::{
+            atomic::{AtomicBool, AtomicU64, Ordering},
+            Arc, Mutex,
+        },
     },
 };
 
@@ -64,6 +68,81 @@ pub static SINGLE_STEP_FLUSHING: Lazy<bool> =
 #[cfg(any(debug_assertions, feature = "debugging"))]
 static DEBUG_CONTEXT: Lazy<Mutex<DebugContext>> = Lazy::new(|| Mutex::new(DebugContext::new()));
 
+#[cfg(any(debug_assertions, feature = "debugging"))]
+static INTERPRETER_DEBUGGER: Lazy<Mutex<Option<Box<dyn InterpreterDebugger>>>> =
+    Lazy::new(|| Mutex::new(None));
+
+// Avoids locking the debugger for every instruction when none is installed
+#[cfg(any(debug_assertions, feature = "debugging"))]
+static INTERPRETER_DEBUGGER_INSTALLED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(any(debug_assertions, feature = "debugging"))]
+static INTERPRETER_PROFILER: Lazy<Mutex<Option<Arc<dyn InterpreterProfiler>>>> =
+    Lazy::new(|| Mutex::new(None));
+
+// Avoids accessing the profiler for every instruction when none is installed
+#[cfg(any(debug_assertions, feature = "debugging"))]
+static INTERPRETER_PROFILER_INSTALLED: AtomicBool = AtomicBool::new(false);
+
+// Incremented whenever a profiler is installed or uninstalled, for the threads to refresh their
+// copy of it
+#[cfg(any(debug_assertions, feature = "debugging"))]
+static INTERPRETER_PROFILER_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    // The profiler of the thread and the generation it was copied at, so that the threads running
+    // the interpreter concurrently don't lock the global profiler for every instruction
+    #[cfg(any(debug_assertions, feature = "debugging"))]
+    static THREAD_INTERPRETER_PROFILER: RefCell<(u64, Option<Arc<dyn InterpreterProfiler>>)> =
+        const { RefCell::new((0, None)) };
+}
+
+/// Installs a debugger, which is called before the execution of every instruction, and returns
+/// the previous one. `None` uninstalls the current debugger.
+#[cfg(any(debug_assertions, feature = "debugging"))]
+pub fn set_interpreter_debugger(
+    debugger: Option<Box<dyn InterpreterDebugger>>,
+) -> Option<Box<dyn InterpreterDebugger>> {
+    let mut current = INTERPRETER_DEBUGGER.lock().unwrap();
+    INTERPRETER_DEBUGGER_INSTALLED.store(debugger.is_some(), Ordering::Release);
+    std::mem::replace(&mut *current, debugger)
+}
+
+/// Installs a profiler, which is called before the execution of every instruction, and returns
+/// the previous one. `None` uninstalls the current profiler.
+#[cfg(any(debug_assertions, feature = "debugging"))]
+pub fn set_interpreter_profiler(
+    profiler: Option<Arc<dyn InterpreterProfiler>>,
+) -> Option<Arc<dyn InterpreterProfiler>> {
+    let mut current = INTERPRETER_PROFILER.lock().unwrap();
+    INTERPRETER_PROFILER_INSTALLED.store(profiler.is_some(), Ordering::Release);
+    INTERPRETER_PROFILER_GENERATION.fetch_add(1, Ordering::AcqRel);
+    std::mem::replace(&mut *current, profiler)
+}
+
+// Calls the installed profiler, through the copy of the thread
+#[cfg(any(debug_assertions, feature = "debugging"))]
+fn with_interpreter_profiler(f: impl FnOnce(&dyn InterpreterProfiler)) {
+    let generation = INTERPRETER_PROFILER_GENERATION.load(Ordering::Acquire);
+    THREAD_INTERPRETER_PROFILER.with(|thread_profiler| {
+        let mut thread_profiler = thread_profiler.borrow_mut();
+        if thread_profiler.0 != generation {
+            *thread_profiler = (generation, INTERPRETER_PROFILER.lock().unwrap().clone());
+        }
+        if let Some(profiler) = &thread_profiler.1 {
+            f(profiler.as_ref());
+        }
+    });
+}
+
+// Notifies the profiler that the interpreter returns to its caller
+#[cfg(any(debug_assertions, feature = "debugging"))]
+pub(crate) fn exit_interpreter() {
+    if INTERPRETER_PROFILER_INSTALLED.load(Ordering::Acquire) {
+        with_interpreter_profiler(|profiler| profiler.on_exit());
+    }
+}
+
 // Only include in debug builds
 #[cfg(any(debug_assertions, feature = "debugging"))]
 pub(crate) fn trace(
@@ -73,6 +152,7 @@ pub(crate) fn trace(
     instr: &Bytecode,
     loader: &Loader,
     interp: &Interpreter,
+    is_entry: bool,
 ) {
     if *TRACING_ENABLED {
         let buf_writer = &mut *LOGGING_FILE_WRITER.lock().unwrap();
@@ -87,6 +167,26 @@ pub(crate) fn trace(
             buf_writer.flush().unwrap();
         }
     }
+    let location = || DebugLocation {
+        module_id: function.module_id(),
+        function_name: function.name(),
+        function_index: function.index(),
+        pc,
+        instruction: instr,
+        depth: interp.debug_call_stack_depth(),
+        is_entry,
+    };
+    if INTERPRETER_PROFILER_INSTALLED.load(Ordering::Acquire) {
+        with_interpreter_profiler(|profiler| profiler.on_instruction(&location()));
+    }
+    if INTERPRETER_DEBUGGER_INSTALLED.load(Ordering::Acquire) {
+        if let Some(debugger) = INTERPRETER_DEBUGGER.lock().unwrap().as_mut() {
+            let location = location();
+            if debugger.should_stop(&location) {
+                debugger.on_stop(interp.debug_state(function, locals, pc, instr, loader));
+            }
+        }
+    }
     if *DEBUGGING_ENABLED {
         DEBUG_CONTEXT
             .lock()
@@ -97,7 +197,15 @@ pub(crate) fn trace(
 
 #[macro_export]
 macro_rules! trace {
-    ($function_desc:expr, $locals:expr, $pc:expr, $instr:tt, $resolver:expr, $interp:expr) => {
+    (
+        $function_desc:expr,
+        $locals:expr,
+        $pc:expr,
+        $instr:tt,
+        $resolver:expr,
+        $interp:expr,
+        $is_entry:ident
+    ) => {
         // Only include this code in debug releases
         #[cfg(any(debug_assertions, feature = "debugging"))]
         $crate::tracing::trace(
@@ -107,6 +215,7 @@ macro_rules! trace {
             &$instr,
             $resolver.loader(),
             $interp,
+            std::mem::take(&mut $is_entry),
         )
     };
 }
@@ -329,6 +329,22 @@ pub trait GasMeter {
         locals: impl Iterator<Item = impl ValueView> + Clone,
     ) -> PartialVMResult<()>;
 
+    /// Whether the meter observes the values returned by (non-native) functions. Meters that
+    /// charge gas do not, so the interpreter skips collecting the returned values for them.
+    fn observes_ret_vals(&self) -> bool {
+        false
+    }
+
+    /// Called with the values a (non-native) function returns, right after its `Ret` instruction
+    /// has been charged, if the meter observes them. This is not charged for, and is only used
+    /// by meters that observe execution, e.g., for tracing.
+    fn observe_ret_vals(
+        &mut self,
+        _ret_vals: impl ExactSizeIterator<Item = impl ValueView> + Clone,
+    ) -> PartialVMResult<()> {
+        Ok(())
+    }
+
     fn charge_create_ty(&mut self, num_nodes: NumTypeNodes) -> PartialVMResult<()>;
 
     fn charge_dependency(
@@ -3077,6 +3077,13 @@ pub mod debug {
         Ok(())
     }
 
+    pub fn print_local<B: Write>(buf: &mut B, locals: &Locals, idx: usize) -> PartialVMResult<()> {
+        match locals.0.borrow().get(idx) {
+            Some(val) => print_value_impl(buf, val),
+            None => print_invalid(buf),
+        }
+    }
+
     pub fn print_value<B: Write>(buf: &mut B, val: &Value) -> PartialVMResult<()> {
         print_value_impl(buf, &val.0)
     }
@@ -14,6 +14,12 @@ pub struct BlockExecutorLocalConfig {
     // If true, we will discard the failed blocks and continue with the next block.
     // (allow_fallback needs to be set)
     pub discard_failed_blocks: bool,
+    // If true, parallel execution records the conflicts between transactions and publishes
+    // a report for every block (see aptos_block_executor::conflict_report).
+    pub conflict_report: bool,
+    // If true, the state that the transactions are predicted to read is prefetched
+    // concurrently while the block is executed (see aptos_block_executor::prefetch).
+    pub prefetch_read_sets: bool,
 }
 
 /// Configuration from on-chain configuration, that is
@@ -73,6 +79,8 @@ impl BlockExecutorConfig {
                 concurrency_level,
                 allow_fallback: true,
                 discard_failed_blocks: false,
+                conflict_report: false,
+                prefetch_read_sets: false,
             },
             onchain: BlockExecutorConfigFromOnchain::new_no_block_limit(),
         }
@@ -87,6 +95,8 @@ impl BlockExecutorConfig {
                 concurrency_level,
                 allow_fallback: true,
                 discard_failed_blocks: false,
+                conflict_report: false,
+                prefetch_read_sets: false,
             },
             onchain: BlockExecutorConfigFromOnchain::new_maybe_block_limit(maybe_block_gas_limit),
         }
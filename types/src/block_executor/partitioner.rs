@@ -1,10 +1,15 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::transaction::{
-    analyzed_transaction::{AnalyzedTransaction, StorageLocation},
-    signature_verified_transaction::{into_signature_verified_block, SignatureVerifiedTransaction},
-    Transaction,
+use crate::{
+    state_store::state_key::StateKey,
+    transaction::{
+        analyzed_transaction::{AnalyzedTransaction, StorageLocation},
+        signature_verified_transaction::{
+            into_signature_verified_block, SignatureVerifiedTransaction,
+        },
+        Transaction,
+    },
 };
 use aptos_crypto::HashValue;
 use serde::{Deserialize, Serialize};
@@ -304,6 +309,9 @@ impl<T: Clone> IntoIterator for SubBlock<T> {
 pub struct SubBlocksForShard<T> {
     pub shard_id: ShardId,
     pub sub_blocks: Vec<SubBlock<T>>,
+    // Set if the block contains transactions with advisory hints, for the shard to check that its
+    // sub blocks don't read locations written by preceding sub blocks without a dependency.
+    pub hinted_writes: Option<HintedWrites>,
 }
 
 impl<T: Clone> SubBlocksForShard<T> {
@@ -311,6 +319,7 @@ impl<T: Clone> SubBlocksForShard<T> {
         Self {
             shard_id,
             sub_blocks,
+            hinted_writes: None,
         }
     }
 
@@ -318,6 +327,7 @@ impl<T: Clone> SubBlocksForShard<T> {
         Self {
             shard_id,
             sub_blocks: Vec::new(),
+            hinted_writes: None,
         }
     }
 
@@ -394,6 +404,43 @@ impl<T: Clone> SubBlocksForShard<T> {
     }
 }
 
+/// The first sub block (by round, then by shard) whose transactions are hinted to write each state
+/// key. A sub block which reads a key from the base state, i.e. without a cross shard dependency,
+/// that a preceding sub block is hinted to write, may have read a stale value.
+#[derive(Default, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct HintedWrites {
+    first_writers: HashMap<StateKey, (RoundId, ShardId)>,
+}
+
+impl HintedWrites {
+    pub fn new(sharded_txns: &[SubBlocksForShard<AnalyzedTransaction>]) -> Self {
+        let mut first_writers = HashMap::new();
+        for (shard_id, sub_blocks) in sharded_txns.iter().enumerate() {
+            for (round, sub_block) in sub_blocks.sub_block_iter().enumerate() {
+                for txn in sub_block.iter() {
+                    for location in txn.txn().write_hints() {
+                        if let StorageLocation::Specific(state_key) = location {
+                            let writer = first_writers
+                                .entry(state_key.clone())
+                                .or_insert((round, shard_id));
+                            *writer = (*writer).min((round, shard_id));
+                        }
+                    }
+                }
+            }
+        }
+        Self { first_writers }
+    }
+
+    /// Whether a sub block preceding the one of the given round and shard is hinted to write the
+    /// state key. The global shard is preceded by all sub blocks.
+    pub fn written_before(&self, state_key: &StateKey, round: RoundId, shard_id: ShardId) -> bool {
+        self.first_writers
+            .get(state_key)
+            .map_or(false, |writer| *writer < (round, shard_id))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub struct TransactionWithDependencies<T> {
     pub txn: T,
@@ -511,6 +558,22 @@ impl PartitionedTransactions {
         self.num_sharded_txns() + self.global_txns.len()
     }
 
+    pub fn has_advisory_hints(&self) -> bool {
+        self.sharded_txns
+            .iter()
+            .flat_map(|sub_blocks| sub_blocks.iter())
+            .chain(self.global_txns.iter())
+            .any(|txn| txn.txn().has_advisory_hints())
+    }
+
+    /// Hands the hinted writes of the block to every shard, see [HintedWrites].
+    pub fn add_hinted_writes(&mut self) {
+        let hinted_writes = HintedWrites::new(&self.sharded_txns);
+        for sub_blocks in self.sharded_txns.iter_mut() {
+            sub_blocks.hinted_writes = Some(hinted_writes.clone());
+        }
+    }
+
     pub fn add_checkpoint_txn(&mut self, last_txn: SignatureVerifiedTransaction) {
         assert!(matches!(
             last_txn.expect_valid(),
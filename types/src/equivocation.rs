@@ -0,0 +1,79 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_crypto::HashValue;
+use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
+use move_core_types::account_address::AccountAddress;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+
+/// The kind of consensus message that a validator equivocated on.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum EquivocationKind {
+    /// Two different votes for the same round.
+    Vote,
+    /// Two different proposals for the same round.
+    Proposal,
+}
+
+impl EquivocationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EquivocationKind::Vote => "vote",
+            EquivocationKind::Proposal => "proposal",
+        }
+    }
+
+    /// The kind as stored on-chain (see `equivocation_evidence.move`).
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            EquivocationKind::Vote => 0,
+            EquivocationKind::Proposal => 1,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, CryptoHasher, BCSCryptoHash)]
+pub struct EquivocationReportMetadata {
+    pub epoch: u64,
+    pub round: u64,
+    pub kind: EquivocationKind,
+    /// The validator that equivocated.
+    pub offender: AccountAddress,
+    /// The validator that submitted the report.
+    pub reporter: AccountAddress,
+}
+
+/// A report of a validator equivocating, submitted on-chain as a validator transaction.
+/// The evidence is the BCS encoded consensus evidence (i.e., both conflicting signed
+/// messages), which consensus verifies before voting for a block that contains the report.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EquivocationReport {
+    pub metadata: EquivocationReportMetadata,
+    #[serde(with = "serde_bytes")]
+    pub evidence_bytes: Vec<u8>,
+}
+
+impl Debug for EquivocationReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EquivocationReport")
+            .field("metadata", &self.metadata)
+            .field("evidence_bytes_len", &self.evidence_bytes.len())
+            .finish()
+    }
+}
+
+impl EquivocationReport {
+    pub fn new(metadata: EquivocationReportMetadata, evidence_bytes: Vec<u8>) -> Self {
+        Self {
+            metadata,
+            evidence_bytes,
+        }
+    }
+
+    /// The hash of the evidence, which identifies the report (e.g., in the validator
+    /// transaction pool, and on-chain).
+    pub fn evidence_hash(&self) -> HashValue {
+        HashValue::sha3_256_of(&self.evidence_bytes)
+    }
+}
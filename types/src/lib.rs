@@ -15,6 +15,7 @@ pub mod contract_event;
 pub mod dkg;
 pub mod epoch_change;
 pub mod epoch_state;
+pub mod equivocation;
 pub mod error;
 pub mod event;
 pub mod executable;
@@ -92,6 +92,12 @@ pub enum FeatureFlag {
     ENABLE_ENUM_TYPES = 74,
     ENABLE_RESOURCE_ACCESS_CONTROL = 75,
     REJECT_UNSTABLE_BYTECODE_FOR_SCRIPT = 76,
+    /// Allows modules to declare the resources accessed by their entry functions in their
+    /// metadata, which is used to derive read/write hints for transactions.
+    RESOURCE_ACCESS_METADATA = 77,
+    /// Allows validators to submit the evidence of equivocating validators on-chain (as
+    /// validator transactions). Consensus rejects equivocation reports while disabled.
+    EQUIVOCATION_REPORTS = 78,
 }
 
 impl FeatureFlag {
@@ -167,6 +173,7 @@ impl FeatureFlag {
             FeatureFlag::ENABLE_ENUM_TYPES,
             FeatureFlag::ENABLE_RESOURCE_ACCESS_CONTROL,
             FeatureFlag::REJECT_UNSTABLE_BYTECODE_FOR_SCRIPT,
+            FeatureFlag::RESOURCE_ACCESS_METADATA,
         ]
     }
 }
@@ -304,6 +311,10 @@ impl Features {
         self.is_enabled(FeatureFlag::ABORT_IF_MULTISIG_PAYLOAD_MISMATCH)
     }
 
+    pub fn is_equivocation_reports_enabled(&self) -> bool {
+        self.is_enabled(FeatureFlag::EQUIVOCATION_REPORTS)
+    }
+
     pub fn get_max_identifier_size(&self) -> u64 {
         if self.is_enabled(FeatureFlag::LIMIT_MAX_IDENTIFIER_LENGTH) {
             IDENTIFIER_SIZE_MAX
@@ -158,6 +158,14 @@ pub enum TransactionShufflerType {
         platform_use_case_spread_factor: usize,
         user_use_case_spread_factor: usize,
     },
+    /// Orders by gas unit price within conflict-free groups filled fairly across fee payers,
+    /// deferring transactions beyond a single contract's share of the block to its tail.
+    FeePriority {
+        conflict_window_size: usize,
+        /// Percentage (1-100) of the block a single contract may take before its transactions
+        /// are deferred. 100 disables the cap.
+        max_contract_share_percentage: usize,
+    },
 }
 
 impl TransactionShufflerType {
@@ -2,12 +2,25 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{block_info::Round, on_chain_config::OnChainConfig};
+use crate::{
+    block_info::Round,
+    dkg::{DKGSessionState, DKGTrait, DefaultDKG},
+    on_chain_config::OnChainConfig,
+};
+use anyhow::{anyhow, ensure};
 use aptos_crypto::HashValue;
 use aptos_crypto_derive::SilentDebug;
-use aptos_dkg::{weighted_vuf, weighted_vuf::traits::WeightedVUF};
-use once_cell::sync::OnceCell;
+use aptos_dkg::{
+    pvss::{
+        traits::{SecretSharingConfig, Transcript},
+        Player,
+    },
+    weighted_vuf,
+    weighted_vuf::traits::WeightedVUF,
+};
+use once_cell::sync::{Lazy, OnceCell};
 use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
 
 pub type WVUF = weighted_vuf::pinkas::PinkasWUF;
 pub type WvufPP = <WVUF as WeightedVUF>::PublicParameters;
@@ -21,6 +34,14 @@ pub type Delta = <WVUF as WeightedVUF>::Delta;
 pub type Evaluation = <WVUF as WeightedVUF>::Evaluation;
 pub type Proof = <WVUF as WeightedVUF>::Proof;
 
+/// The thread pool used to derive the evaluations when verifying randomness proofs
+static VERIFICATION_POOL: Lazy<rayon::ThreadPool> = Lazy::new(|| {
+    rayon::ThreadPoolBuilder::new()
+        .thread_name(|index| format!("rand_proof_verify_{}", index))
+        .build()
+        .unwrap()
+});
+
 #[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq, Eq, Hash)]
 pub struct RandMetadata {
     pub epoch: u64,
@@ -135,3 +156,126 @@ impl RandKeys {
         Ok(())
     }
 }
+
+/// The WVUF proof share of a validator, with the delta that augments the validator's public
+/// key share (as dealt by the DKG transcript) into the key that verifies the share.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RandomnessProofShare {
+    pub player: Player,
+    pub delta: Delta,
+    pub share: ProofShare,
+}
+
+/// The proof of the randomness of a block: the shares aggregated by a validator to derive the
+/// randomness. It can be verified offline against the DKG transcript of the epoch.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RandomnessProof {
+    pub metadata: RandMetadata,
+    /// Whether the shares were created with the keys of the fast path transcript
+    pub fast_path: bool,
+    pub shares: Vec<RandomnessProofShare>,
+}
+
+impl RandomnessProof {
+    pub fn new(metadata: RandMetadata, fast_path: bool, shares: Vec<RandomnessProofShare>) -> Self {
+        Self {
+            metadata,
+            fast_path,
+            shares,
+        }
+    }
+
+    /// Verifies that `randomness` was derived from shares worth at least the threshold weight,
+    /// created with the secret key shares dealt by `dkg_session`. The DKG session of an epoch
+    /// is the `last_completed` session of `0x1::dkg::DKGState` at any version of the epoch.
+    pub fn verify(
+        &self,
+        dkg_session: &DKGSessionState,
+        randomness: &Randomness,
+    ) -> anyhow::Result<()> {
+        ensure!(
+            randomness.metadata() == &self.metadata,
+            "Randomness of {:?} does not match the proof of {:?}",
+            randomness.metadata(),
+            self.metadata
+        );
+        ensure!(
+            dkg_session.target_epoch() == self.metadata.epoch,
+            "DKG session of epoch {} does not match the proof of epoch {}",
+            dkg_session.target_epoch(),
+            self.metadata.epoch
+        );
+
+        let pub_params = DefaultDKG::new_public_params(&dkg_session.metadata);
+        let transcripts = bcs::from_bytes::<<DefaultDKG as DKGTrait>::Transcript>(
+            dkg_session.transcript.as_slice(),
+        )?;
+        let (wconfig, transcript) = if self.fast_path {
+            pub_params
+                .pvss_config
+                .fast_wconfig
+                .as_ref()
+                .zip(transcripts.fast.as_ref())
+                .ok_or_else(|| anyhow!("DKG session has no fast path transcript"))?
+        } else {
+            (&pub_params.pvss_config.wconfig, &transcripts.main)
+        };
+        let vuf_pp = WvufPP::from(&pub_params.pvss_config.pp);
+        let msg = bcs::to_bytes(&self.metadata)?;
+
+        let mut apks: Vec<Option<APK>> = vec![None; wconfig.get_total_num_players()];
+        let mut weight = 0;
+        for RandomnessProofShare {
+            player,
+            delta,
+            share,
+        } in &self.shares
+        {
+            ensure!(player.id < apks.len(), "Unknown player {}", player.id);
+            ensure!(
+                apks[player.id].is_none(),
+                "Duplicate share of player {}",
+                player.id
+            );
+            let pk_share = transcript.get_public_key_share(wconfig, player);
+            let apk = WVUF::augment_pubkey(&vuf_pp, pk_share, delta.clone())?;
+            WVUF::verify_share(&vuf_pp, &apk, msg.as_slice(), share)?;
+            apks[player.id] = Some(apk);
+            weight += wconfig.get_player_weight(player);
+        }
+        ensure!(
+            weight >= wconfig.get_threshold_weight(),
+            "Shares have weight {}, below the threshold {}",
+            weight,
+            wconfig.get_threshold_weight()
+        );
+
+        let proof: Proof = self
+            .shares
+            .iter()
+            .map(|share| (share.player, share.share))
+            .collect();
+        let eval = WVUF::derive_eval(
+            wconfig,
+            &vuf_pp,
+            msg.as_slice(),
+            &apks,
+            &proof,
+            &VERIFICATION_POOL,
+        )?;
+        let expected = Sha3_256::digest(bcs::to_bytes(&eval)?.as_slice());
+        ensure!(
+            expected.as_slice() == randomness.randomness(),
+            "Randomness does not match the evaluation derived from the proof"
+        );
+        Ok(())
+    }
+}
+
+/// Provides the randomness proofs kept by a validator (e.g., for the REST API).
+pub trait RandomnessProofReader: Send + Sync {
+    fn get_randomness_proof(
+        &self,
+        metadata: &RandMetadata,
+    ) -> anyhow::Result<Option<RandomnessProof>>;
+}
@@ -2,9 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    on_chain_config::CurrentTimeMicroseconds, proof::SparseMerkleRangeProof,
-    state_store::state_key::StateKey, transaction::Version,
+    ledger_info::LedgerInfo,
+    on_chain_config::CurrentTimeMicroseconds,
+    proof::{SparseMerkleProof, SparseMerkleRangeProof, TransactionInfoListWithProof},
+    state_store::state_key::{prefix::StateKeyPrefix, StateKey},
+    transaction::Version,
 };
+use anyhow::ensure;
 use aptos_crypto::{
     hash::{CryptoHash, SPARSE_MERKLE_PLACEHOLDER_HASH},
     HashValue,
@@ -343,6 +347,151 @@ impl StateValueChunkWithProof {
     }
 }
 
+/// A page of state values that share a common state key prefix (e.g., all
+/// state values under a single account) at a specific version. Pages are
+/// ordered by the encoded state keys, and chained using the next key.
+///
+/// Note: state keys are hashed before they are inserted into the sparse merkle
+/// tree, so the values under a prefix are not contiguous in the tree and cannot
+/// be covered by a single range proof. Instead, each value is accompanied by an
+/// individual inclusion proof against the state root hash at the version, and
+/// the state root hash is authenticated by the transaction info (and proof) at
+/// the version. As a result, a verified page proves that every returned value is
+/// correct, but it cannot prove that no values under the prefix were omitted.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateValuesByPrefixWithProof {
+    pub version: Version, // The version at which the state values were read
+    pub raw_values: Vec<(StateKey, StateValue, SparseMerkleProof)>, // The state values and their inclusion proofs
+    pub next_key: Option<StateKey>, // The first key of the next page (if more values exist)
+    pub transaction_info_with_proof: TransactionInfoListWithProof, // The transaction info at the version
+}
+
+impl StateValuesByPrefixWithProof {
+    /// Returns true iff this is the last page of state values for the prefix
+    pub fn is_last_page(&self) -> bool {
+        self.next_key.is_none()
+    }
+
+    /// Verifies the page of state values against the given ledger info. This
+    /// checks that: (i) the transaction info at the version is committed by the
+    /// ledger info; (ii) all keys (including the next key) have the given prefix,
+    /// start at the given start key and are strictly ordered; and (iii) every
+    /// state value is included in the state tree identified by the state
+    /// checkpoint hash of the transaction info.
+    pub fn verify(
+        &self,
+        ledger_info: &LedgerInfo,
+        key_prefix: &StateKeyPrefix,
+        start_key: Option<&StateKey>,
+    ) -> anyhow::Result<()> {
+        // Verify the state root hash against the ledger info
+        ensure!(
+            ledger_info.version() == self.version,
+            "Version mismatch! Ledger info version: {}, state values version: {}",
+            ledger_info.version(),
+            self.version
+        );
+        ensure!(
+            self.transaction_info_with_proof.transaction_infos.len() == 1,
+            "Expected a single transaction info, found: {}",
+            self.transaction_info_with_proof.transaction_infos.len()
+        );
+        self.transaction_info_with_proof
+            .verify(ledger_info, Some(self.version))?;
+        let state_root_hash =
+            self.transaction_info_with_proof.transaction_infos[0].ensure_state_checkpoint_hash()?;
+
+        // Verify the keys are under the prefix and strictly ordered
+        let state_keys = self
+            .raw_values
+            .iter()
+            .map(|(state_key, _, _)| state_key)
+            .chain(self.next_key.iter());
+        let mut previous_key = None;
+        for state_key in state_keys {
+            ensure!(
+                key_prefix.is_prefix(state_key)?,
+                "The state key does not have the expected prefix! Key: {:?}, prefix: {:?}",
+                state_key,
+                key_prefix
+            );
+            match previous_key {
+                Some(previous_key) => ensure!(
+                    previous_key.encoded() < state_key.encoded(),
+                    "The state keys are not strictly ordered! Previous key: {:?}, key: {:?}",
+                    previous_key,
+                    state_key
+                ),
+                None => {
+                    if let Some(start_key) = start_key {
+                        ensure!(
+                            state_key == start_key,
+                            "The first state key does not match the start key! \
+                            Start key: {:?}, key: {:?}",
+                            start_key,
+                            state_key
+                        );
+                    }
+                },
+            }
+            previous_key = Some(state_key);
+        }
+        ensure!(
+            self.next_key.is_none() || !self.raw_values.is_empty(),
+            "The page of state values is empty, but a next key was specified!"
+        );
+
+        // Verify the state values against the state root hash
+        for (state_key, state_value, proof) in &self.raw_values {
+            proof.verify(state_root_hash, state_key.hash(), Some(state_value))?;
+        }
+        Ok(())
+    }
+}
+
+/// A single state value (or its absence) at a specific version, along with
+/// the proofs required to authenticate it against a ledger info at that
+/// version. This allows clients that only hold verified ledger infos (e.g.,
+/// light clients) to query individual state values without storing any state.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateValueWithProof {
+    pub version: Version,    // The version at which the state value was read
+    pub state_key: StateKey, // The key of the state value
+    pub state_value: Option<StateValue>, // The state value (if it exists)
+    pub sparse_merkle_proof: SparseMerkleProof, // The proof of (non-)inclusion for the state value
+    pub transaction_info_with_proof: TransactionInfoListWithProof, // The transaction info at the version
+}
+
+impl StateValueWithProof {
+    /// Verifies the state value against the given ledger info. This checks
+    /// that the transaction info at the version is committed by the ledger
+    /// info, and that the state value is (or is not) included in the state
+    /// tree identified by the state checkpoint hash of the transaction info.
+    pub fn verify(&self, ledger_info: &LedgerInfo) -> anyhow::Result<()> {
+        ensure!(
+            ledger_info.version() == self.version,
+            "Version mismatch! Ledger info version: {}, state value version: {}",
+            ledger_info.version(),
+            self.version
+        );
+        ensure!(
+            self.transaction_info_with_proof.transaction_infos.len() == 1,
+            "Expected a single transaction info, found: {}",
+            self.transaction_info_with_proof.transaction_infos.len()
+        );
+        self.transaction_info_with_proof
+            .verify(ledger_info, Some(self.version))?;
+
+        let state_root_hash =
+            self.transaction_info_with_proof.transaction_infos[0].ensure_state_checkpoint_hash()?;
+        self.sparse_merkle_proof.verify(
+            state_root_hash,
+            self.state_key.hash(),
+            self.state_value.as_ref(),
+        )
+    }
+}
+
 /// Indicates a state value becomes stale since `stale_since_version`.
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
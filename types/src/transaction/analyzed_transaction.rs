@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    account_config::{AccountResource, CoinInfoResource, CoinStoreResource},
+    account_config::{
+        fungible_store::primary_store, AccountResource, CoinInfoResource, CoinStoreResource,
+        ObjectGroupResource,
+    },
     chain_id::ChainId,
     on_chain_config::{CurrentTimeMicroseconds, Features, TransactionFeeBurnCap},
     state_store::{state_key::StateKey, table::TableHandle},
@@ -15,7 +18,9 @@ use aptos_crypto::HashValue;
 pub use move_core_types::abi::{
     ArgumentABI, ScriptFunctionABI as EntryFunctionABI, TransactionScriptABI, TypeArgumentABI,
 };
-use move_core_types::{account_address::AccountAddress, language_storage::StructTag};
+use move_core_types::{
+    account_address::AccountAddress, language_storage::StructTag, move_resource::MoveStructType,
+};
 use serde::{Deserialize, Serialize};
 use std::hash::{Hash, Hasher};
 
@@ -31,6 +36,9 @@ pub struct AnalyzedTransaction {
     pub write_hints: Vec<StorageLocation>,
     /// A transaction is predictable if neither the read_hint or the write_hint have wildcards.
     predictable_transaction: bool,
+    /// Whether the hints are unverified, e.g. taken from the module metadata, so that the
+    /// transaction may access locations beyond them and the execution has to check that it doesn't.
+    advisory_hints: bool,
     /// The hash of the transaction - this is cached for performance reasons.
     hash: HashValue,
 }
@@ -66,6 +74,34 @@ impl StorageLocation {
 impl AnalyzedTransaction {
     pub fn new(transaction: SignatureVerifiedTransaction) -> Self {
         let (read_hints, write_hints) = transaction.get_read_write_hints();
+        Self::new_with_hints(transaction, read_hints, write_hints)
+    }
+
+    /// Creates an analyzed transaction with the given read/write hints.
+    pub fn new_with_hints(
+        transaction: SignatureVerifiedTransaction,
+        read_hints: Vec<StorageLocation>,
+        write_hints: Vec<StorageLocation>,
+    ) -> Self {
+        Self::new_impl(transaction, read_hints, write_hints, false)
+    }
+
+    /// Creates an analyzed transaction with read/write hints which the transaction isn't known to
+    /// respect, e.g. ones derived from the resource access metadata of the called entry function.
+    pub fn new_with_advisory_hints(
+        transaction: SignatureVerifiedTransaction,
+        read_hints: Vec<StorageLocation>,
+        write_hints: Vec<StorageLocation>,
+    ) -> Self {
+        Self::new_impl(transaction, read_hints, write_hints, true)
+    }
+
+    fn new_impl(
+        transaction: SignatureVerifiedTransaction,
+        read_hints: Vec<StorageLocation>,
+        write_hints: Vec<StorageLocation>,
+        advisory_hints: bool,
+    ) -> Self {
         let hints_contain_wildcard = read_hints
             .iter()
             .chain(write_hints.iter())
@@ -76,6 +112,7 @@ impl AnalyzedTransaction {
             read_hints,
             write_hints,
             predictable_transaction: !hints_contain_wildcard,
+            advisory_hints,
             hash,
         }
     }
@@ -100,6 +137,10 @@ impl AnalyzedTransaction {
         self.predictable_transaction
     }
 
+    pub fn has_advisory_hints(&self) -> bool {
+        self.advisory_hints
+    }
+
     pub fn sender(&self) -> Option<AccountAddress> {
         self.transaction.sender()
     }
@@ -164,6 +205,13 @@ pub fn coin_store_location(address: AccountAddress) -> StorageLocation {
     StorageLocation::Specific(StateKey::resource_typed::<CoinStoreResource>(&address).unwrap())
 }
 
+pub fn primary_fungible_store_location(address: AccountAddress) -> StorageLocation {
+    StorageLocation::Specific(StateKey::resource_group(
+        &primary_store(&address),
+        &ObjectGroupResource::struct_tag(),
+    ))
+}
+
 pub fn current_ts_location() -> StorageLocation {
     StorageLocation::Specific(StateKey::on_chain_config::<CurrentTimeMicroseconds>().unwrap())
 }
@@ -2097,6 +2097,17 @@ pub trait BlockExecutableTransaction: Sync + Send + Clone + 'static {
 
     /// Size of the user transaction in bytes, 0 otherwise
     fn user_txn_bytes_len(&self) -> usize;
+
+    /// Keys the transaction is likely to read, which may be prefetched during block execution.
+    fn read_hints(&self) -> Vec<Self::Key> {
+        vec![]
+    }
+
+    /// Identifies transactions that tend to read the same keys, e.g. calls of the same entry
+    /// function, so that keys they read in previous blocks can be prefetched as well.
+    fn access_pattern(&self) -> Option<String> {
+        None
+    }
 }
 
 pub struct ViewFunctionOutput {
@@ -2,9 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    account_config::{AccountResource, CoinStoreResource},
     contract_event::ContractEvent,
     state_store::state_key::StateKey,
-    transaction::{BlockExecutableTransaction, Transaction},
+    transaction::{BlockExecutableTransaction, Transaction, TransactionPayload},
     write_set::WriteOp,
 };
 use aptos_crypto::{hash::CryptoHash, HashValue};
@@ -74,6 +75,46 @@ impl BlockExecutableTransaction for SignatureVerifiedTransaction {
             _ => 0,
         }
     }
+
+    /// The accounts and coin stores of the sender, secondary signers and fee payer, and the
+    /// module of the entry function called.
+    fn read_hints(&self) -> Vec<StateKey> {
+        let txn = match self {
+            SignatureVerifiedTransaction::Valid(Transaction::UserTransaction(txn)) => txn,
+            _ => return vec![],
+        };
+
+        let authenticator = txn.authenticator_ref();
+        let mut accounts = vec![txn.sender()];
+        accounts.extend(authenticator.secondary_signer_addresses());
+        accounts.extend(authenticator.fee_payer_address());
+
+        let mut hints = vec![];
+        for account in accounts {
+            hints.extend(StateKey::resource_typed::<AccountResource>(&account).ok());
+            hints.extend(StateKey::resource_typed::<CoinStoreResource>(&account).ok());
+        }
+        if let TransactionPayload::EntryFunction(entry_function) = txn.payload() {
+            hints.push(StateKey::module_id(entry_function.module()));
+        }
+        hints
+    }
+
+    fn access_pattern(&self) -> Option<String> {
+        match self {
+            SignatureVerifiedTransaction::Valid(Transaction::UserTransaction(txn)) => {
+                match txn.payload() {
+                    TransactionPayload::EntryFunction(entry_function) => Some(format!(
+                        "{}::{}",
+                        entry_function.module(),
+                        entry_function.function()
+                    )),
+                    _ => None,
+                }
+            },
+            _ => None,
+        }
+    }
 }
 
 impl From<Transaction> for SignatureVerifiedTransaction {
@@ -7,6 +7,8 @@ mod block_metadata_test;
 mod code_debug_fmt_test;
 mod contract_event_test;
 mod keyless_serialization_test;
+mod randomness_test;
+mod state_value_test;
 mod transaction_test;
 mod trusted_state_test;
 mod validator_set_test;
@@ -0,0 +1,138 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    dkg::{
+        real_dkg::maybe_dk_from_bls_sk, DKGSessionMetadata, DKGSessionState, DKGTrait, DefaultDKG,
+    },
+    on_chain_config::OnChainRandomnessConfig,
+    randomness::{RandMetadata, Randomness, RandomnessProof, RandomnessProofShare, WvufPP, WVUF},
+    validator_verifier::{ValidatorConsensusInfo, ValidatorConsensusInfoMoveStruct},
+};
+use aptos_crypto::{bls12381, Uniform};
+use aptos_dkg::{pvss::Player, weighted_vuf::traits::WeightedVUF};
+use move_core_types::account_address::AccountAddress;
+use rand::thread_rng;
+use sha3::{Digest, Sha3_256};
+
+const TARGET_EPOCH: u64 = 5;
+
+/// Deals a secret to validators with the given weights and lets every validator create a
+/// share for `metadata`. Returns the DKG session, all the shares and the expected randomness.
+fn setup(
+    weights: Vec<u64>,
+    metadata: &RandMetadata,
+) -> (DKGSessionState, Vec<RandomnessProofShare>, Randomness) {
+    let mut rng = thread_rng();
+    let private_keys: Vec<bls12381::PrivateKey> = weights
+        .iter()
+        .map(|_| bls12381::PrivateKey::generate_for_testing())
+        .collect();
+    let validator_set: Vec<ValidatorConsensusInfoMoveStruct> = private_keys
+        .iter()
+        .zip(weights)
+        .map(|(private_key, weight)| {
+            ValidatorConsensusInfo::new(
+                AccountAddress::random(),
+                bls12381::PublicKey::from(private_key),
+                weight,
+            )
+            .into()
+        })
+        .collect();
+    let session_metadata = DKGSessionMetadata {
+        dealer_epoch: TARGET_EPOCH - 1,
+        randomness_config: OnChainRandomnessConfig::default_enabled().into(),
+        dealer_validator_set: validator_set.clone(),
+        target_validator_set: validator_set,
+    };
+    let pub_params = DefaultDKG::new_public_params(&session_metadata);
+    let input_secret = <DefaultDKG as DKGTrait>::InputSecret::generate_for_testing();
+    let transcript =
+        DefaultDKG::generate_transcript(&mut rng, &pub_params, &input_secret, 0, &private_keys[0]);
+
+    let msg = bcs::to_bytes(metadata).unwrap();
+    let vuf_pp = WvufPP::from(&pub_params.pvss_config.pp);
+    let shares = private_keys
+        .iter()
+        .enumerate()
+        .map(|(id, private_key)| {
+            let (sk, pk) = DefaultDKG::decrypt_secret_share_from_transcript(
+                &pub_params,
+                &transcript,
+                id as u64,
+                &maybe_dk_from_bls_sk(private_key).unwrap(),
+            )
+            .unwrap();
+            let (ask, apk) = WVUF::augment_key_pair(&vuf_pp, sk.main, pk.main, &mut rng);
+            RandomnessProofShare {
+                player: Player { id },
+                delta: WVUF::get_public_delta(&apk).clone(),
+                share: WVUF::create_share(&ask, msg.as_slice()),
+            }
+        })
+        .collect();
+
+    let dealt_secret = DefaultDKG::dealt_secret_from_input(&pub_params, &input_secret);
+    let eval = WVUF::eval(&dealt_secret, msg.as_slice());
+    let randomness = Randomness::new(
+        metadata.clone(),
+        Sha3_256::digest(bcs::to_bytes(&eval).unwrap().as_slice()).to_vec(),
+    );
+
+    let session = DKGSessionState {
+        metadata: session_metadata,
+        start_time_us: 0,
+        transcript: bcs::to_bytes(&transcript).unwrap(),
+    };
+    (session, shares, randomness)
+}
+
+#[test]
+fn test_verify_randomness_proof() {
+    let metadata = RandMetadata {
+        epoch: TARGET_EPOCH,
+        round: 7,
+    };
+    let (session, shares, randomness) = setup(vec![1, 2, 3, 4], &metadata);
+
+    // All the shares
+    let proof = RandomnessProof::new(metadata.clone(), false, shares.clone());
+    proof.verify(&session, &randomness).unwrap();
+
+    // A subset of the shares above the threshold
+    let proof = RandomnessProof::new(metadata.clone(), false, shares[1..].to_vec());
+    proof.verify(&session, &randomness).unwrap();
+
+    // Not enough shares
+    let proof = RandomnessProof::new(metadata.clone(), false, shares[..1].to_vec());
+    assert!(proof.verify(&session, &randomness).is_err());
+
+    // Duplicate shares
+    let duplicate_shares = [shares.clone(), shares[..1].to_vec()].concat();
+    let proof = RandomnessProof::new(metadata.clone(), false, duplicate_shares);
+    assert!(proof.verify(&session, &randomness).is_err());
+
+    // A share for another player
+    let mut forged_shares = shares.clone();
+    forged_shares[0].player = Player { id: 1 };
+    forged_shares[1].player = Player { id: 0 };
+    let proof = RandomnessProof::new(metadata.clone(), false, forged_shares);
+    assert!(proof.verify(&session, &randomness).is_err());
+
+    // Randomness that does not match the proof
+    let proof = RandomnessProof::new(metadata.clone(), false, shares.clone());
+    let wrong_randomness = Randomness::new(metadata.clone(), vec![0; 32]);
+    assert!(proof.verify(&session, &wrong_randomness).is_err());
+    let other_metadata = RandMetadata {
+        epoch: TARGET_EPOCH,
+        round: 8,
+    };
+    let other_randomness = Randomness::new(other_metadata, randomness.randomness_cloned());
+    assert!(proof.verify(&session, &other_randomness).is_err());
+
+    // The session of another epoch
+    let mut other_session = session.clone();
+    other_session.metadata.dealer_epoch += 1;
+    assert!(proof.verify(&other_session, &randomness).is_err());
+}
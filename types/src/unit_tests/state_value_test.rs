@@ -0,0 +1,156 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account_address::AccountAddress,
+    block_info::BlockInfo,
+    ledger_info::LedgerInfo,
+    proof::{
+        SparseMerkleLeafNode, SparseMerkleProof, TransactionAccumulatorRangeProof,
+        TransactionInfoListWithProof,
+    },
+    state_store::{
+        state_key::{prefix::StateKeyPrefix, StateKey},
+        state_value::{StateValue, StateValuesByPrefixWithProof},
+    },
+    transaction::{ExecutionStatus, TransactionInfo},
+};
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use move_core_types::identifier::Identifier;
+
+#[test]
+fn test_verify_state_values_by_prefix() {
+    // Create a page with a single state value under the account
+    let account = AccountAddress::random();
+    let state_key = create_module_key(&account, "a");
+    let (state_values_with_proof, ledger_info) =
+        create_state_values_by_prefix(vec![state_key.clone()], None);
+
+    // Verify the page against the ledger info
+    let key_prefix = StateKeyPrefix::from(account);
+    state_values_with_proof
+        .verify(&ledger_info, &key_prefix, None)
+        .unwrap();
+    state_values_with_proof
+        .verify(&ledger_info, &key_prefix, Some(&state_key))
+        .unwrap();
+
+    // Verify that the page fails verification against a different ledger info
+    let (_, other_ledger_info) = create_state_values_by_prefix(vec![state_key], None);
+    state_values_with_proof
+        .verify(&other_ledger_info, &key_prefix, None)
+        .unwrap_err();
+}
+
+#[test]
+fn test_verify_state_values_by_prefix_keys() {
+    // Create several state keys under the account (ordered by encoding)
+    let account = AccountAddress::random();
+    let mut state_keys: Vec<_> = ["a", "b", "c"]
+        .iter()
+        .map(|name| create_module_key(&account, name))
+        .collect();
+    state_keys.sort_by(|key_1, key_2| key_1.encoded().cmp(key_2.encoded()));
+    let key_prefix = StateKeyPrefix::from(account);
+
+    // Verify that a page with keys under a different account fails verification
+    let other_key = create_module_key(&AccountAddress::random(), "a");
+    let (state_values_with_proof, ledger_info) =
+        create_state_values_by_prefix(vec![other_key], None);
+    state_values_with_proof
+        .verify(&ledger_info, &key_prefix, None)
+        .unwrap_err();
+
+    // Verify that a page that doesn't start at the start key fails verification
+    let (state_values_with_proof, ledger_info) =
+        create_state_values_by_prefix(vec![state_keys[1].clone()], None);
+    state_values_with_proof
+        .verify(&ledger_info, &key_prefix, Some(&state_keys[0]))
+        .unwrap_err();
+
+    // Verify that a page with unordered keys fails verification
+    let (state_values_with_proof, ledger_info) =
+        create_state_values_by_prefix(vec![state_keys[1].clone()], Some(state_keys[0].clone()));
+    state_values_with_proof
+        .verify(&ledger_info, &key_prefix, None)
+        .unwrap_err();
+
+    // Verify that a page with a next key under a different account fails verification
+    let other_key = create_module_key(&AccountAddress::random(), "d");
+    let (state_values_with_proof, ledger_info) =
+        create_state_values_by_prefix(vec![state_keys[0].clone()], Some(other_key));
+    state_values_with_proof
+        .verify(&ledger_info, &key_prefix, None)
+        .unwrap_err();
+
+    // Verify that a page with an ordered next key passes verification
+    let (state_values_with_proof, ledger_info) =
+        create_state_values_by_prefix(vec![state_keys[0].clone()], Some(state_keys[2].clone()));
+    state_values_with_proof
+        .verify(&ledger_info, &key_prefix, None)
+        .unwrap();
+}
+
+/// Creates a module state key under the given account
+fn create_module_key(account: &AccountAddress, name: &str) -> StateKey {
+    StateKey::module(account, &Identifier::new(name).unwrap())
+}
+
+/// Creates a page of state values (at version 0) for the given keys, along
+/// with a ledger info that commits to the page. Note: only the first state
+/// value has a valid inclusion proof (the state tree contains a single leaf).
+fn create_state_values_by_prefix(
+    state_keys: Vec<StateKey>,
+    next_key: Option<StateKey>,
+) -> (StateValuesByPrefixWithProof, LedgerInfo) {
+    // Create the state values and the state root hash
+    let raw_values: Vec<_> = state_keys
+        .into_iter()
+        .map(|state_key| {
+            let state_value = StateValue::from(HashValue::random().to_vec());
+            let leaf = SparseMerkleLeafNode::new(state_key.hash(), state_value.hash());
+            (
+                state_key,
+                state_value,
+                SparseMerkleProof::new(Some(leaf), vec![]),
+            )
+        })
+        .collect();
+    let state_root_hash = raw_values
+        .first()
+        .map(|(_, _, proof)| proof.leaf().unwrap().hash())
+        .unwrap_or_else(HashValue::random);
+
+    // Create the transaction info and a ledger info that commits to it
+    let transaction_info = TransactionInfo::new(
+        HashValue::random(),
+        HashValue::random(),
+        HashValue::random(),
+        Some(state_root_hash),
+        0,
+        ExecutionStatus::Success,
+    );
+    let block_info = BlockInfo::new(
+        0,
+        0,
+        HashValue::random(),
+        transaction_info.hash(),
+        0,
+        0,
+        None,
+    );
+    let ledger_info = LedgerInfo::new(block_info, HashValue::zero());
+
+    // Create the page of state values
+    let state_values_with_proof = StateValuesByPrefixWithProof {
+        version: 0,
+        raw_values,
+        next_key,
+        transaction_info_with_proof: TransactionInfoListWithProof::new(
+            TransactionAccumulatorRangeProof::new_empty(),
+            vec![transaction_info],
+        ),
+    };
+
+    (state_values_with_proof, ledger_info)
+}
@@ -3,7 +3,8 @@
 
 #[cfg(any(test, feature = "fuzzing"))]
 use crate::dkg::DKGTranscriptMetadata;
-use crate::{dkg::DKGTranscript, jwks};
+use crate::{dkg::DKGTranscript, equivocation::EquivocationReport, jwks};
+use aptos_crypto::HashValue;
 use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
 #[cfg(any(test, feature = "fuzzing"))]
 use move_core_types::account_address::AccountAddress;
@@ -14,6 +15,7 @@ use std::fmt::Debug;
 pub enum ValidatorTransaction {
     DKGResult(DKGTranscript),
     ObservedJWKUpdate(jwks::QuorumCertifiedUpdate),
+    EquivocationReport(EquivocationReport),
 }
 
 impl ValidatorTransaction {
@@ -38,6 +40,9 @@ impl ValidatorTransaction {
             ValidatorTransaction::ObservedJWKUpdate(update) => {
                 Topic::JWK_CONSENSUS(update.update.issuer.clone())
             },
+            ValidatorTransaction::EquivocationReport(report) => {
+                Topic::EQUIVOCATION(report.evidence_hash())
+            },
         }
     }
 
@@ -47,6 +52,9 @@ impl ValidatorTransaction {
             ValidatorTransaction::ObservedJWKUpdate(_) => {
                 "validator_transaction__observed_jwk_update"
             },
+            ValidatorTransaction::EquivocationReport(_) => {
+                "validator_transaction__equivocation_report"
+            },
         }
     }
 }
@@ -56,4 +64,5 @@ impl ValidatorTransaction {
 pub enum Topic {
     DKG,
     JWK_CONSENSUS(jwks::Issuer),
+    EQUIVOCATION(HashValue),
 }